@@ -0,0 +1,267 @@
+//! a.out definitions.
+//!
+//! These definitions are independent of read/write support, although we do implement
+//! some traits useful for those.
+//!
+//! This module is based on the `<a.out.h>` headers of 4.3BSD, SunOS and Linux.
+//!
+//! An a.out file starts with an [`Exec`] header, which is followed by the text
+//! segment, the data segment, the text relocations, the data relocations, the
+//! symbol table, and the string table. The header, the relocations and the
+//! symbol table are in the byte order of the machine that the file is for,
+//! which is determined by the byte order of the magic number.
+
+#![allow(missing_docs)]
+
+use crate::endian::{Endian, U16, U32};
+use crate::pod::Pod;
+
+/// The header at the start of every a.out file.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Exec<E: Endian> {
+    /// The magic number in the low 16 bits, the machine type in the next 8 bits,
+    /// and flags in the high 8 bits.
+    ///
+    /// Use [`Exec::n_magic`], [`Exec::n_machtype`] and [`Exec::n_flags`]
+    /// to get these.
+    pub a_midmag: U32<E>,
+    /// The size of the text segment.
+    pub a_text: U32<E>,
+    /// The size of the initialized data segment.
+    pub a_data: U32<E>,
+    /// The size of the uninitialized data segment.
+    pub a_bss: U32<E>,
+    /// The size of the symbol table.
+    pub a_syms: U32<E>,
+    /// The entry point address.
+    pub a_entry: U32<E>,
+    /// The size of the text relocations.
+    pub a_trsize: U32<E>,
+    /// The size of the data relocations.
+    pub a_drsize: U32<E>,
+}
+
+impl<E: Endian> Exec<E> {
+    /// Return the magic number. One of the `*MAGIC` constants.
+    #[inline]
+    pub fn n_magic(&self, endian: E) -> u16 {
+        self.a_midmag.get(endian) as u16
+    }
+
+    /// Return the machine type. One of the `M_*` or `MID_*` constants.
+    #[inline]
+    pub fn n_machtype(&self, endian: E) -> u8 {
+        (self.a_midmag.get(endian) >> 16) as u8
+    }
+
+    /// Return the flags.
+    #[inline]
+    pub fn n_flags(&self, endian: E) -> u8 {
+        (self.a_midmag.get(endian) >> 24) as u8
+    }
+}
+
+/// An impure executable or a relocatable object file.
+///
+/// The text segment is writable, and the data segment immediately follows it.
+pub const OMAGIC: u16 = 0o407;
+/// A pure executable.
+///
+/// The text segment is read-only, and the data segment starts at the next
+/// segment boundary after it.
+pub const NMAGIC: u16 = 0o410;
+/// A demand paged executable.
+///
+/// The text segment starts at file offset [`ZMAGIC_TEXT_OFFSET`].
+pub const ZMAGIC: u16 = 0o413;
+/// A demand paged executable with the header in the text segment.
+///
+/// The text segment starts at file offset 0 and is loaded at address
+/// [`QMAGIC_TEXT_ADDRESS`].
+pub const QMAGIC: u16 = 0o314;
+
+/// The file offset of the text segment in a `ZMAGIC` file.
+pub const ZMAGIC_TEXT_OFFSET: u32 = 1024;
+/// The address of the text segment in a `QMAGIC` file.
+pub const QMAGIC_TEXT_ADDRESS: u32 = 0x1000;
+/// The alignment of the data segment in a `NMAGIC`, `ZMAGIC` or `QMAGIC` file.
+pub const SEGMENT_SIZE: u32 = 1024;
+
+// Values for `Exec::n_machtype`.
+/// Old Sun-2 or unknown machine.
+pub const M_OLDSUN2: u8 = 0;
+/// Motorola 68010.
+pub const M_68010: u8 = 1;
+/// Motorola 68020.
+pub const M_68020: u8 = 2;
+/// SPARC.
+pub const M_SPARC: u8 = 3;
+/// Intel 386 (Linux).
+pub const M_386: u8 = 100;
+/// MIPS R3000 (Linux).
+pub const M_MIPS1: u8 = 151;
+/// MIPS R6000 (Linux).
+pub const M_MIPS2: u8 = 152;
+/// Intel 386 (NetBSD).
+pub const MID_I386: u8 = 134;
+/// Motorola 68k with 8K pages (NetBSD).
+pub const MID_M68K: u8 = 135;
+/// Motorola 68k with 4K pages (NetBSD).
+pub const MID_M68K4K: u8 = 136;
+/// SPARC (NetBSD).
+pub const MID_SPARC: u8 = 138;
+/// Little endian MIPS (NetBSD).
+pub const MID_PMAX: u8 = 139;
+/// Big endian MIPS (NetBSD).
+pub const MID_MIPS: u8 = 142;
+/// ARM (NetBSD).
+pub const MID_ARM6: u8 = 143;
+
+/// A symbol table entry.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Nlist<E: Endian> {
+    /// The offset of the symbol name in the string table, or 0 if there is no name.
+    pub n_strx: U32<E>,
+    /// The symbol type. A combination of the `N_*` constants.
+    pub n_type: u8,
+    /// Other information. Unused for most symbols.
+    pub n_other: u8,
+    /// Additional information for debugging symbols.
+    pub n_desc: U16<E>,
+    /// The symbol value.
+    ///
+    /// This is an address for symbols defined in a segment, and the size
+    /// for common symbols.
+    pub n_value: U32<E>,
+}
+
+impl<E: Endian> Nlist<E> {
+    /// Return true if this is a debugging symbol.
+    #[inline]
+    pub fn is_stab(&self) -> bool {
+        self.n_type & N_STAB != 0
+    }
+
+    /// Return true if this is an external symbol.
+    #[inline]
+    pub fn is_external(&self) -> bool {
+        self.n_type & N_EXT != 0
+    }
+
+    /// Return the `N_TYPE` bits of the symbol type.
+    #[inline]
+    pub fn n_type(&self) -> u8 {
+        self.n_type & N_TYPE
+    }
+}
+
+// Values for `Nlist::n_type`.
+/// Undefined symbol, or common symbol if the value is non-zero.
+pub const N_UNDF: u8 = 0x0;
+/// Absolute symbol.
+pub const N_ABS: u8 = 0x2;
+/// Text segment symbol.
+pub const N_TEXT: u8 = 0x4;
+/// Data segment symbol.
+pub const N_DATA: u8 = 0x6;
+/// Uninitialized data segment symbol.
+pub const N_BSS: u8 = 0x8;
+/// Indirect symbol. The next symbol is the target.
+pub const N_INDR: u8 = 0xa;
+/// Common symbol (internal to the linker).
+pub const N_COMM: u8 = 0x12;
+/// Absolute set element symbol.
+pub const N_SETA: u8 = 0x14;
+/// Text set element symbol.
+pub const N_SETT: u8 = 0x16;
+/// Data set element symbol.
+pub const N_SETD: u8 = 0x18;
+/// Uninitialized data set element symbol.
+pub const N_SETB: u8 = 0x1a;
+/// Pointer to a set vector.
+pub const N_SETV: u8 = 0x1c;
+/// Warning message for the next symbol.
+pub const N_WARNING: u8 = 0x1e;
+/// File name symbol.
+pub const N_FN: u8 = 0x1f;
+
+/// External symbol flag.
+pub const N_EXT: u8 = 0x1;
+/// Mask for the symbol type.
+pub const N_TYPE: u8 = 0x1e;
+/// Mask for debugging symbol types.
+pub const N_STAB: u8 = 0xe0;
+
+/// A relocation entry.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Relocation<E: Endian> {
+    /// The offset in the segment of the location to relocate.
+    pub r_address: U32<E>,
+    /// The bit fields of the relocation.
+    ///
+    /// Use [`Relocation::info`] to get these.
+    pub r_info: U32<E>,
+}
+
+impl<E: Endian> Relocation<E> {
+    /// Return the fields of the relocation.
+    pub fn info(self, endian: E) -> RelocationInfo {
+        let r_address = self.r_address.get(endian);
+        let r_info = self.r_info.get(endian);
+        if endian.is_little_endian() {
+            RelocationInfo {
+                r_address,
+                r_symbolnum: r_info & 0x00ff_ffff,
+                r_pcrel: ((r_info >> 24) & 0x1) != 0,
+                r_length: ((r_info >> 25) & 0x3) as u8,
+                r_extern: ((r_info >> 27) & 0x1) != 0,
+                r_baserel: ((r_info >> 28) & 0x1) != 0,
+                r_jmptable: ((r_info >> 29) & 0x1) != 0,
+                r_relative: ((r_info >> 30) & 0x1) != 0,
+                r_copy: ((r_info >> 31) & 0x1) != 0,
+            }
+        } else {
+            RelocationInfo {
+                r_address,
+                r_symbolnum: r_info >> 8,
+                r_pcrel: ((r_info >> 7) & 0x1) != 0,
+                r_length: ((r_info >> 5) & 0x3) as u8,
+                r_extern: ((r_info >> 4) & 0x1) != 0,
+                r_baserel: ((r_info >> 3) & 0x1) != 0,
+                r_jmptable: ((r_info >> 2) & 0x1) != 0,
+                r_relative: ((r_info >> 1) & 0x1) != 0,
+                r_copy: (r_info & 0x1) != 0,
+            }
+        }
+    }
+}
+
+/// The fields of a [`Relocation`].
+#[derive(Debug, Clone, Copy)]
+pub struct RelocationInfo {
+    /// The offset in the segment of the location to relocate.
+    pub r_address: u32,
+    /// The symbol index if `r_extern` is true, otherwise the segment type.
+    ///
+    /// The segment type is one of `N_TEXT`, `N_DATA`, `N_BSS` or `N_ABS`.
+    pub r_symbolnum: u32,
+    /// Whether the location is PC relative.
+    pub r_pcrel: bool,
+    /// The size of the location: 0=byte, 1=word, 2=long, 3=quad.
+    pub r_length: u8,
+    /// Whether `r_symbolnum` is a symbol index.
+    pub r_extern: bool,
+    /// Whether the location is relative to the global offset table.
+    pub r_baserel: bool,
+    /// Whether the location is a jump table entry.
+    pub r_jmptable: bool,
+    /// Whether the location is relative to the load address.
+    pub r_relative: bool,
+    /// Whether the relocation is a copy relocation.
+    pub r_copy: bool,
+}
+
+unsafe_impl_endian_pod!(Exec, Nlist, Relocation);
@@ -0,0 +1,194 @@
+//! Rebuilding of COFF object files.
+//!
+//! [`rebuild_coff`] rebuilds a parsed COFF object file as a [`write::Object`]
+//! that can be edited and written again.
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+use hashbrown::HashMap;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::build::{Conversion, Result, Untranslated};
+use crate::pe;
+use crate::read::coff::{CoffFile, CoffHeader};
+use crate::read::{Object, ObjectComdat, ObjectSection, ObjectSymbol, ReadRef};
+use crate::write::{self, SectionId};
+use crate::{
+    BinaryFormat, ComdatKind, Endianness, RelocationTarget, SectionFlags, SectionKind, SymbolFlags,
+    SymbolKind, SymbolSection,
+};
+
+/// Rebuild a COFF object file as a [`write::Object`], so that it can be
+/// edited and written again.
+///
+/// Sections keep their names, contents and characteristics, and symbols,
+/// COMDATs and relocations are kept. The writer chooses the alignment and
+/// COMDAT characteristics of each section, and the symbol table is rebuilt,
+/// so auxiliary symbol records other than file names and section definitions
+/// are not kept. Weak symbols are written as ordinary symbols.
+pub fn rebuild_coff<'data, R: ReadRef<'data>, Coff: CoffHeader>(
+    file: &CoffFile<'data, R, Coff>,
+) -> Result<Conversion<'data>> {
+    rebuild_coff_sections(file, &mut |name| Some(name.to_vec())).map(|(conversion, _)| conversion)
+}
+
+/// Rebuild a COFF object file, calling `section_name` with the name of each
+/// section to choose its new name, or to remove it if it returns `None`.
+///
+/// Returns the conversion and the ids of the sections that were kept, in order.
+/// Symbols and relocations in removed sections are dropped.
+pub(super) fn rebuild_coff_sections<'data, R: ReadRef<'data>, Coff: CoffHeader>(
+    file: &CoffFile<'data, R, Coff>,
+    section_name: &mut dyn FnMut(&[u8]) -> Option<Vec<u8>>,
+) -> Result<(Conversion<'data>, Vec<SectionId>)> {
+    let mut object =
+        write::Object::new(BinaryFormat::Coff, file.architecture(), Endianness::Little);
+    object.set_sub_architecture(file.sub_architecture());
+    // COFF names already include any prefix added by the compiler.
+    object.set_mangling(write::Mangling::None);
+    object.flags = file.flags();
+    let mut untranslated = Vec::new();
+
+    let mut section_ids = HashMap::new();
+    let mut kept = Vec::new();
+    for section in file.sections() {
+        let name = match section_name(section.name_bytes()?) {
+            Some(name) => name,
+            None => continue,
+        };
+        let kind = section.kind();
+        let id = object.add_section(Vec::new(), name, kind);
+        if kind == SectionKind::UninitializedData {
+            object.append_section_bss(id, section.size(), section.align());
+        } else {
+            object.set_section_data(id, section.data()?, section.align());
+        }
+        if let SectionFlags::Coff { characteristics } = section.flags() {
+            // The writer sets these from the section alignment and COMDATs.
+            let characteristics = characteristics
+                & !(pe::IMAGE_SCN_ALIGN_MASK
+                    | pe::IMAGE_SCN_LNK_COMDAT
+                    | pe::IMAGE_SCN_LNK_NRELOC_OVFL);
+            object.section_mut(id).flags = SectionFlags::Coff { characteristics };
+        }
+        section_ids.insert(section.index(), id);
+        kept.push(id);
+    }
+
+    let mut symbol_ids = HashMap::new();
+    for symbol in file.symbols() {
+        let index = symbol.index();
+        let section = match symbol.section() {
+            SymbolSection::Section(section) => match section_ids.get(&section) {
+                Some(&id) => write::SymbolSection::Section(id),
+                // The section was removed.
+                None => continue,
+            },
+            SymbolSection::Absolute => write::SymbolSection::Absolute,
+            SymbolSection::Undefined => write::SymbolSection::Undefined,
+            SymbolSection::Common => write::SymbolSection::Common,
+            _ => {
+                if symbol.kind() == SymbolKind::File {
+                    object.add_file_symbol(symbol.name_bytes()?.to_vec());
+                } else {
+                    untranslated.push(Untranslated::Symbol {
+                        index,
+                        reason: "unsupported symbol section",
+                    });
+                }
+                continue;
+            }
+        };
+        let id = match (symbol.kind(), section) {
+            (SymbolKind::Section, write::SymbolSection::Section(id)) => object.section_symbol(id),
+            (kind, _) => {
+                if symbol.is_weak() {
+                    untranslated.push(Untranslated::Symbol {
+                        index,
+                        reason: "weak symbols are not supported",
+                    });
+                }
+                object.add_symbol(write::Symbol {
+                    name: symbol.name_bytes()?.to_vec(),
+                    value: symbol.address(),
+                    size: symbol.size(),
+                    kind,
+                    scope: symbol.scope(),
+                    weak: false,
+                    section,
+                    flags: SymbolFlags::None,
+                })
+            }
+        };
+        symbol_ids.insert(index, id);
+    }
+
+    for comdat in file.comdats() {
+        let symbol = match symbol_ids.get(&comdat.symbol()) {
+            Some(&symbol) => symbol,
+            // The COMDAT leader was removed.
+            None => continue,
+        };
+        let kind = comdat.kind();
+        if kind == ComdatKind::Unknown {
+            untranslated.push(Untranslated::Symbol {
+                index: comdat.symbol(),
+                reason: "unsupported COMDAT selection",
+            });
+            continue;
+        }
+        let sections = comdat
+            .sections()
+            .filter_map(|section| section_ids.get(&section).copied())
+            .collect();
+        object.add_comdat(write::Comdat {
+            kind,
+            symbol,
+            sections,
+        });
+    }
+
+    for section in file.sections() {
+        let index = section.index();
+        let id = match section_ids.get(&index) {
+            Some(&id) => id,
+            None => continue,
+        };
+        for (offset, relocation) in section.relocations() {
+            let symbol = match relocation.target() {
+                RelocationTarget::Symbol(symbol) => symbol_ids.get(&symbol).copied(),
+                _ => None,
+            };
+            let symbol = match symbol {
+                Some(symbol) => symbol,
+                None => {
+                    untranslated.push(Untranslated::Relocation {
+                        section: index,
+                        offset,
+                        reason: "unsupported relocation target",
+                    });
+                    continue;
+                }
+            };
+            // The addend cancels the writer's implicit addend adjustment, so the
+            // section data is unchanged.
+            object.add_relocation(
+                id,
+                write::Relocation {
+                    offset,
+                    symbol,
+                    addend: relocation.addend(),
+                    flags: relocation.flags(),
+                },
+            )?;
+        }
+    }
+
+    Ok((
+        Conversion {
+            object,
+            untranslated,
+        },
+        kept,
+    ))
+}
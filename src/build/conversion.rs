@@ -0,0 +1,67 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::read::{SectionIndex, SymbolIndex};
+use crate::write;
+
+/// The result of converting an object file to another format.
+#[derive(Debug)]
+pub struct Conversion<'data> {
+    /// The converted object file.
+    pub object: write::Object<'data>,
+    /// The parts of the input that could not be translated, in input order.
+    ///
+    /// The converted object file omits these, or uses the closest
+    /// representation that the output format supports.
+    pub untranslated: Vec<Untranslated>,
+}
+
+/// A part of an object file that could not be translated to the output format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Untranslated {
+    /// A section, by index in the input file.
+    Section {
+        /// The index of the section.
+        index: SectionIndex,
+        /// The reason that the section could not be translated.
+        reason: &'static str,
+    },
+    /// A symbol, by index in the input file.
+    Symbol {
+        /// The index of the symbol.
+        index: SymbolIndex,
+        /// The reason that the symbol could not be translated.
+        reason: &'static str,
+    },
+    /// A relocation, by section index and offset in the input file.
+    Relocation {
+        /// The index of the section containing the relocation.
+        section: SectionIndex,
+        /// The offset of the relocation within the section.
+        offset: u64,
+        /// The reason that the relocation could not be translated.
+        reason: &'static str,
+    },
+    /// The program entry point.
+    Entry,
+}
+
+impl fmt::Display for Untranslated {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Untranslated::Section { index, reason } => {
+                write!(f, "section {}: {}", index.0, reason)
+            }
+            Untranslated::Symbol { index, reason } => {
+                write!(f, "symbol {}: {}", index.0, reason)
+            }
+            Untranslated::Relocation {
+                section,
+                offset,
+                reason,
+            } => write!(f, "relocation at {}+{:#x}: {}", section.0, offset, reason),
+            Untranslated::Entry => f.write_str("the entry point is not supported"),
+        }
+    }
+}
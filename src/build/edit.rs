@@ -0,0 +1,252 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use crate::build::{Conversion, Error, Result};
+#[cfg(feature = "coff")]
+use crate::read::coff::{CoffBigFile, CoffFile};
+#[cfg(feature = "omf")]
+use crate::read::omf::OmfFile;
+use crate::read::{Object, ObjectSection, ObjectSymbol, SectionIndex};
+use crate::write::SectionId;
+use crate::{FileKind, RelocationTarget, SectionKind};
+
+/// Edits to the sections of an object file, such as those made by `objcopy`.
+///
+/// The edits are applied by [`SectionEdits::apply`], which parses the file,
+/// rebuilds it as a [`write::Object`](crate::write::Object), and edits the
+/// sections. Sections are selected by the name that
+/// [`ObjectSection::name`] returns, and all sections with that name are
+/// edited. The edits are made in this order:
+///
+/// 1. sections are removed,
+/// 2. sections are renamed,
+/// 3. sections are padded, using their new names,
+/// 4. sections are added.
+///
+/// Supports COFF object files, which are rebuilt by
+/// [`rebuild_coff`](crate::build::coff::rebuild_coff), and OMF object
+/// modules, which are rebuilt by
+/// [`rebuild_omf`](crate::build::omf::rebuild_omf).
+///
+/// Requires `feature = "coff"` or `feature = "omf"`.
+#[derive(Debug, Clone, Default)]
+pub struct SectionEdits {
+    removals: Vec<Vec<u8>>,
+    renames: Vec<(Vec<u8>, Vec<u8>)>,
+    padding: Vec<(Vec<u8>, u64)>,
+    additions: Vec<(Vec<u8>, SectionKind, Vec<u8>)>,
+}
+
+impl SectionEdits {
+    /// Create an empty set of edits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove the sections named `name`.
+    ///
+    /// The symbols defined in the sections, and the relocations for their
+    /// contents, are also removed. It is an error if a relocation in another
+    /// section refers to the sections.
+    pub fn remove_section(&mut self, name: Vec<u8>) -> &mut Self {
+        self.removals.push(name);
+        self
+    }
+
+    /// Rename the sections named `name` to `new_name`.
+    pub fn rename_section(&mut self, name: Vec<u8>, new_name: Vec<u8>) -> &mut Self {
+        self.renames.push((name, new_name));
+        self
+    }
+
+    /// Append zero bytes to the sections named `name` until they are at
+    /// least `size` bytes long.
+    pub fn pad_section(&mut self, name: Vec<u8>, size: u64) -> &mut Self {
+        self.padding.push((name, size));
+        self
+    }
+
+    /// Add a section named `name` with the given contents.
+    pub fn add_section(&mut self, name: Vec<u8>, kind: SectionKind, data: Vec<u8>) -> &mut Self {
+        self.additions.push((name, kind, data));
+        self
+    }
+
+    /// Apply the edits to the object file in `data`.
+    ///
+    /// Returns the edited object file, which can be written with
+    /// [`write::Object::write`](crate::write::Object::write), and the parts
+    /// of the input that could not be kept by the rebuild.
+    ///
+    /// Returns an error if the file format is not supported, if an edit names
+    /// a section that does not exist, or if a removed section is referenced
+    /// by a relocation in another section.
+    pub fn apply<'data>(&self, data: &'data [u8]) -> Result<Conversion<'data>> {
+        match FileKind::parse(data)? {
+            #[cfg(feature = "coff")]
+            FileKind::Coff => {
+                let file = CoffFile::<&[u8]>::parse(data)?;
+                self.edit(&file, |name| {
+                    super::coff::rebuild_coff_sections(&file, name)
+                })
+            }
+            #[cfg(feature = "coff")]
+            FileKind::CoffBig => {
+                let file = CoffBigFile::<&[u8]>::parse(data)?;
+                self.edit(&file, |name| {
+                    super::coff::rebuild_coff_sections(&file, name)
+                })
+            }
+            #[cfg(feature = "omf")]
+            FileKind::Omf => {
+                let file = OmfFile::<&[u8]>::parse(data)?;
+                self.edit(&file, |name| super::omf::rebuild_omf_sections(&file, name))
+            }
+            kind => Err(Error(format!("unsupported file kind {:?}", kind))),
+        }
+    }
+
+    /// Rebuild a parsed file with `rebuild`, removing and renaming its sections,
+    /// and then make the remaining edits.
+    fn edit<'data, O, F>(&self, file: &O, rebuild: F) -> Result<Conversion<'data>>
+    where
+        O: Object<'data>,
+        F: FnOnce(
+            &mut dyn FnMut(&[u8]) -> Option<Vec<u8>>,
+        ) -> Result<(Conversion<'data>, Vec<SectionId>)>,
+    {
+        let plan = self.plan(file)?;
+        // The rebuild calls this once for each section, in order.
+        let mut names = plan
+            .iter()
+            .map(|edit| edit.as_ref().map(|(name, _)| name.clone()));
+        let (mut conversion, sections) = rebuild(&mut |_| names.next().flatten())?;
+
+        let object = &mut conversion.object;
+        for ((_, padding), id) in plan.into_iter().flatten().zip(sections) {
+            if padding == 0 {
+                continue;
+            }
+            if object.section(id).is_bss() {
+                object.append_section_bss(id, padding, 1);
+            } else {
+                let padding = usize::try_from(padding)
+                    .map_err(|_| Error(format!("invalid section padding {}", padding)))?;
+                object.append_section_data(id, &vec![0; padding], 1);
+            }
+        }
+        for (name, kind, data) in &self.additions {
+            let id = object.add_section(Vec::new(), name.clone(), *kind);
+            object.set_section_data(id, data.clone(), 1);
+        }
+        Ok(conversion)
+    }
+
+    /// Return the new name and padding of each section, in the order of
+    /// [`Object::sections`], or `None` for removed sections.
+    fn plan<'data, O: Object<'data>>(&self, file: &O) -> Result<Vec<Option<(Vec<u8>, u64)>>> {
+        let mut plan = Vec::new();
+        let mut removed = Vec::new();
+        for section in file.sections() {
+            let name = section.name_bytes()?;
+            if self.removals.iter().any(|removal| removal == name) {
+                removed.push(section.index());
+                plan.push(None);
+                continue;
+            }
+            let name = match self.renames.iter().find(|(from, _)| from == name) {
+                Some((_, to)) => to.clone(),
+                None => name.to_vec(),
+            };
+            let size = self
+                .padding
+                .iter()
+                .filter(|(pad, _)| *pad == name)
+                .map(|&(_, size)| size)
+                .max()
+                .unwrap_or(0);
+            let padding = size.saturating_sub(section.size());
+            plan.push(Some((name, padding)));
+        }
+
+        let exists = |name: &[u8]| file.sections().any(|s| s.name_bytes().ok() == Some(name));
+        for name in self
+            .removals
+            .iter()
+            .chain(self.renames.iter().map(|(from, _)| from))
+        {
+            if !exists(name) {
+                return Err(missing_section(name));
+            }
+        }
+        for (name, _) in &self.padding {
+            if !plan.iter().flatten().any(|(new_name, _)| new_name == name) {
+                return Err(missing_section(name));
+            }
+        }
+
+        if !removed.is_empty() {
+            check_references(file, &removed)?;
+        }
+        Ok(plan)
+    }
+}
+
+/// Return an error if a relocation in a kept section refers to a removed section.
+///
+/// Undefined symbols refer to a removed section if a global symbol of the
+/// same name is defined in it, since some formats resolve these within the file.
+fn check_references<'data, O: Object<'data>>(file: &O, removed: &[SectionIndex]) -> Result<()> {
+    let mut removed_symbols = Vec::new();
+    for symbol in file.symbols() {
+        match symbol.section_index() {
+            Some(section) if symbol.is_global() && removed.contains(&section) => {
+                removed_symbols.push((symbol.name_bytes()?, section));
+            }
+            _ => {}
+        }
+    }
+    for section in file.sections() {
+        if removed.contains(&section.index()) {
+            continue;
+        }
+        for (offset, relocation) in section.relocations() {
+            let target = match relocation.target() {
+                RelocationTarget::Symbol(symbol) => {
+                    let symbol = file.symbol_by_index(symbol)?;
+                    match symbol.section_index() {
+                        Some(section) => Some(section),
+                        None if symbol.is_undefined() => {
+                            let name = symbol.name_bytes()?;
+                            removed_symbols
+                                .iter()
+                                .find(|(removed, _)| *removed == name)
+                                .map(|&(_, section)| section)
+                        }
+                        None => None,
+                    }
+                }
+                RelocationTarget::Section(section) => Some(section),
+                _ => None,
+            };
+            if let Some(target) = target.filter(|target| removed.contains(target)) {
+                let target = file.section_by_index(target)?;
+                return Err(Error(format!(
+                    "cannot remove section `{}`: it is referenced by a relocation at {}+{:#x}",
+                    String::from_utf8_lossy(target.name_bytes()?),
+                    String::from_utf8_lossy(section.name_bytes()?),
+                    offset
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn missing_section(name: &[u8]) -> Error {
+    Error(format!(
+        "section `{}` not found",
+        String::from_utf8_lossy(name)
+    ))
+}
@@ -10,6 +10,7 @@ use crate::{read, write};
 pub struct Error(pub(super) String);
 
 impl Error {
+    #[cfg_attr(not(feature = "elf"), allow(dead_code))]
     pub(super) fn new(message: impl Into<String>) -> Self {
         Error(message.into())
     }
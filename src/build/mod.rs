@@ -11,8 +11,23 @@ mod bytes;
 pub use bytes::{ByteString, Bytes};
 
 mod table;
+#[cfg(feature = "elf")]
 use table::IdPrivate;
 pub use table::{Id, Item, Table};
 
+mod conversion;
+pub use conversion::{Conversion, Untranslated};
+
+#[cfg(any(feature = "coff", feature = "omf"))]
+mod edit;
+#[cfg(any(feature = "coff", feature = "omf"))]
+pub use edit::SectionEdits;
+
+#[cfg(feature = "coff")]
+pub mod coff;
+
 #[cfg(feature = "elf")]
 pub mod elf;
+
+#[cfg(feature = "omf")]
+pub mod omf;
@@ -0,0 +1,847 @@
+//! Conversion of OMF object modules.
+//!
+//! [`rebuild_omf`] rebuilds a parsed OMF object module as a [`write::Object`]
+//! that can be edited and written again. [`omf_to_coff`] converts an OMF
+//! object module to a COFF object file, and [`coff_to_omf`] does the reverse.
+//! The formats do not support the same features, so each conversion also
+//! returns a list of the parts of the input that could not be translated.
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+use hashbrown::HashMap;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(feature = "coff")]
+use crate::build::Error;
+use crate::build::Result;
+pub use crate::build::{Conversion, Untranslated};
+#[cfg(feature = "coff")]
+use crate::read::coff::{CoffFile, CoffHeader};
+use crate::read::omf::{OmfFile, OmfFixupTarget, OmfStartAddress};
+#[cfg(feature = "coff")]
+use crate::read::omf::{OmfFixupFrame, OmfRelocation};
+use crate::read::{Object, ObjectSection, ReadRef, SectionIndex, SymbolIndex};
+#[cfg(feature = "coff")]
+use crate::read::{ObjectComdat, ObjectSymbol};
+use crate::write::omf::TargetProfile;
+use crate::write::{self, SectionId, SymbolId};
+use crate::{
+    Architecture, BinaryFormat, ComdatKind, Endianness, RelocationFlags, RelocationKind,
+    SectionKind, SymbolFlags, SymbolKind, SymbolScope,
+};
+#[cfg(feature = "coff")]
+use crate::{RelocationTarget, SymbolSection};
+
+/// Rebuild an OMF object module as a [`write::Object`], so that it can be
+/// edited and written again.
+///
+/// Segments and COMDATs become sections, keeping their segment names and
+/// classes. Public, external and communal names become symbols, and fixups
+/// become relocations. Groups, line numbers, and a start address that is a
+/// symbol or the start of a segment are also kept.
+///
+/// The target profile is [`TargetProfile::Flat386`] if the module has a `FLAT`
+/// group or 32-bit offsets, and [`TargetProfile::Real8086`] otherwise. The
+/// writer chooses the frame of each fixup, so fixups that use a different
+/// frame may change. Comments, type definitions, imports and exports are not
+/// kept.
+pub fn rebuild_omf<'data, R: ReadRef<'data>>(
+    file: &OmfFile<'data, R>,
+) -> Result<Conversion<'data>> {
+    rebuild_omf_sections(file, &mut |name| Some(name.to_vec())).map(|(conversion, _)| conversion)
+}
+
+/// Rebuild an OMF object module, calling `section_name` with the name of each
+/// section to choose its new name, or to remove it if it returns `None`.
+///
+/// Returns the conversion and the ids of the sections that were kept, in order.
+/// Symbols, COMDATs, fixups and line numbers in removed sections are dropped.
+pub(super) fn rebuild_omf_sections<'data, R: ReadRef<'data>>(
+    file: &OmfFile<'data, R>,
+    section_name: &mut dyn FnMut(&[u8]) -> Option<Vec<u8>>,
+) -> Result<(Conversion<'data>, Vec<SectionId>)> {
+    let mut object = write::Object::new(BinaryFormat::Omf, Architecture::I386, Endianness::Little);
+    // OMF names already include any prefix added by the compiler.
+    object.set_mangling(write::Mangling::None);
+    let mut untranslated = Vec::new();
+    if let Some(module_name) = file.module_name {
+        object.add_file_symbol(module_name.as_bytes().to_vec());
+    }
+
+    let fixups = || {
+        file.segments
+            .iter()
+            .map(|segment| &segment.fixups)
+            .chain(file.comdats.iter().map(|comdat| &comdat.fixups))
+    };
+    let is_32bit = file
+        .groups
+        .iter()
+        .any(|group| file.name(group.name) == b"FLAT")
+        || file
+            .segments
+            .iter()
+            .any(|segment| segment.length > 0x1_0000)
+        || fixups()
+            .flatten()
+            .any(|fixup| fixup.size == 32 && fixup.kind != RelocationKind::Unknown);
+    object.set_omf_target_profile(if is_32bit {
+        TargetProfile::Flat386
+    } else {
+        TargetProfile::Real8086
+    });
+
+    let mut section_ids = Vec::new();
+    for (position, section) in file.sections().enumerate() {
+        let segment = match file.segments.get(position) {
+            Some(segment) => Some(segment),
+            // COMDATs that are allocated in a segment use the same segment name and class.
+            None => usize::from(file.comdats[position - file.segments.len()].segment_index)
+                .checked_sub(1)
+                .and_then(|index| file.segments.get(index)),
+        };
+        let (class, name) = match segment {
+            Some(segment) => (file.name(segment.class), file.name(segment.name)),
+            None => (&[][..], section.name_bytes()?),
+        };
+        // The hook is given the name that `Object::sections` uses, and the
+        // segment name is kept unless the hook changes it.
+        let read_name = section.name_bytes()?;
+        let name = match section_name(read_name) {
+            Some(new_name) if new_name != read_name => new_name,
+            Some(_) => name.to_vec(),
+            None => {
+                section_ids.push(None);
+                continue;
+            }
+        };
+        if file
+            .segments
+            .get(position)
+            .map_or(false, |segment| segment.absolute.is_some())
+        {
+            untranslated.push(Untranslated::Section {
+                index: section.index(),
+                reason: "absolute segment addresses are not supported",
+            });
+        }
+        let kind = match section.kind() {
+            SectionKind::Unknown => SectionKind::Data,
+            kind => kind,
+        };
+        let id = object.add_section(class.to_vec(), name, kind);
+        if kind == SectionKind::UninitializedData {
+            object.append_section_bss(id, section.size(), section.align());
+        } else {
+            let data = section.uncompressed_data()?.into_owned();
+            object.set_section_data(id, data, section.align());
+        }
+        section_ids.push(Some(id));
+    }
+
+    let symbol_ids = add_omf_symbols(
+        file,
+        &mut object,
+        &section_ids,
+        |kind| kind,
+        &mut untranslated,
+    );
+    add_omf_comdats(
+        file,
+        &mut object,
+        &section_ids,
+        &symbol_ids,
+        &mut untranslated,
+    );
+
+    for group in &file.groups {
+        let name = file.name(group.name);
+        if is_32bit && name == b"FLAT" {
+            // The writer adds the FLAT group.
+            continue;
+        }
+        let sections = group
+            .segment_indices
+            .iter()
+            .filter_map(|&segment| omf_section_id(&section_ids, SectionIndex(segment.into())))
+            .collect();
+        object.add_omf_group(name.to_vec(), sections);
+    }
+
+    for (position, fixups) in fixups().enumerate() {
+        let index = SectionIndex(position + 1);
+        let id = match section_ids[position] {
+            Some(id) => id,
+            None => continue,
+        };
+        for fixup in fixups {
+            let offset = u64::from(fixup.offset);
+            let translated = match (fixup.kind, fixup.size) {
+                _ if object.section(id).is_bss() => Err("fixup in uninitialized data"),
+                (RelocationKind::Relative | RelocationKind::SegmentRelative, 8 | 16 | 32)
+                | (RelocationKind::SectionIndex, 16) => {
+                    omf_fixup_symbol(file, &mut object, &section_ids, &symbol_ids, fixup.target)
+                        .ok_or("unsupported fixup target")
+                }
+                _ => Err("unsupported fixup location"),
+            };
+            let symbol = match translated {
+                Ok(symbol) => symbol,
+                Err(reason) => {
+                    untranslated.push(Untranslated::Relocation {
+                        section: index,
+                        offset,
+                        reason,
+                    });
+                    continue;
+                }
+            };
+            // Generic self-relative addends are relative to the start of the location.
+            let addend = if fixup.kind == RelocationKind::Relative {
+                fixup.addend - i64::from(fixup.size / 8)
+            } else {
+                fixup.addend
+            };
+            object.add_relocation(
+                id,
+                write::Relocation {
+                    offset,
+                    symbol,
+                    addend,
+                    flags: RelocationFlags::Generic {
+                        kind: fixup.kind,
+                        encoding: fixup.encoding,
+                        size: fixup.size,
+                    },
+                },
+            )?;
+        }
+    }
+
+    for line in &file.line_numbers {
+        if let Some(id) = omf_section_id(&section_ids, line.section) {
+            object.add_omf_line_number(id, line.offset, line.line);
+        }
+    }
+
+    if let Some(start) = file.start_address {
+        let entry = match start {
+            // The writer uses this form for symbols that are defined in a segment.
+            OmfStartAddress::Logical {
+                target: OmfFixupTarget::Segment(segment),
+                displacement,
+                ..
+            } if displacement != 0 => file
+                .symbols
+                .iter()
+                .find(|symbol| {
+                    !symbol.is_common
+                        && symbol.segment == Some(SectionIndex(segment.into()))
+                        && symbol.offset == u64::from(displacement)
+                })
+                .and_then(|symbol| symbol_ids[symbol.index]),
+            OmfStartAddress::Logical {
+                target,
+                displacement: 0,
+                ..
+            } => omf_fixup_symbol(file, &mut object, &section_ids, &symbol_ids, target),
+            _ => None,
+        };
+        match entry {
+            Some(symbol) => object.set_omf_entry(symbol),
+            None => untranslated.push(Untranslated::Entry),
+        }
+    }
+    let kept = section_ids.into_iter().flatten().collect();
+    Ok((
+        Conversion {
+            object,
+            untranslated,
+        },
+        kept,
+    ))
+}
+
+/// Convert an OMF object module to a COFF object file.
+///
+/// Segments and COMDATs become sections, public and external names become
+/// symbols, and communal names become common symbols. Fixups become COFF
+/// relocations if COFF has an equivalent: 32-bit self-relative fixups, 32-bit
+/// offsets relative to the `FLAT` group, and 32-bit offsets relative to the
+/// target segment. The fixup displacement is added to the section data, since
+/// COFF relocations use implicit addends.
+///
+/// Groups other than `FLAT` are discarded, and relocations that depend on them
+/// are reported as untranslated.
+///
+/// Requires `feature = "coff"`.
+#[cfg(feature = "coff")]
+pub fn omf_to_coff<'data, R: ReadRef<'data>>(
+    file: &OmfFile<'data, R>,
+) -> Result<Conversion<'data>> {
+    let mut object = write::Object::new(BinaryFormat::Coff, Architecture::I386, Endianness::Little);
+    // OMF names already include any prefix added by the compiler.
+    object.set_mangling(write::Mangling::None);
+    let mut untranslated = Vec::new();
+
+    // Sections are indexed in the order of `Object::sections`: segments
+    // followed by COMDATs.
+    let mut section_ids = Vec::new();
+    let mut section_data = Vec::new();
+    for section in file.sections() {
+        let kind = match section.kind() {
+            SectionKind::Unknown => SectionKind::Data,
+            kind => kind,
+        };
+        let id = object.add_section(Vec::new(), section.name_bytes()?.to_vec(), kind);
+        if kind == SectionKind::UninitializedData {
+            object.append_section_bss(id, section.size(), section.align());
+            section_data.push(None);
+        } else {
+            let data = section.uncompressed_data()?.into_owned();
+            section_data.push(Some((data, section.align())));
+        }
+        section_ids.push(Some(id));
+    }
+    // COFF COMDAT sections need a section symbol, which must precede the
+    // COMDAT symbol.
+    for comdat in &file.comdats {
+        if let Some(section) = omf_section_id(&section_ids, comdat.section) {
+            object.section_symbol(section);
+        }
+    }
+
+    let symbol_ids = add_omf_symbols(
+        file,
+        &mut object,
+        &section_ids,
+        coff_symbol_kind,
+        &mut untranslated,
+    );
+    add_omf_comdats(
+        file,
+        &mut object,
+        &section_ids,
+        &symbol_ids,
+        &mut untranslated,
+    );
+
+    let fixups = file
+        .segments
+        .iter()
+        .map(|segment| &segment.fixups)
+        .chain(file.comdats.iter().map(|comdat| &comdat.fixups));
+    for (position, (fixups, data)) in fixups.zip(section_data).enumerate() {
+        let index = SectionIndex(position + 1);
+        let id = match section_ids[position] {
+            Some(id) => id,
+            None => continue,
+        };
+        let (mut data, align) = match data {
+            Some(data) => data,
+            None => {
+                for fixup in fixups {
+                    untranslated.push(Untranslated::Relocation {
+                        section: index,
+                        offset: fixup.offset.into(),
+                        reason: "fixup in uninitialized data",
+                    });
+                }
+                continue;
+            }
+        };
+        let mut relocations = Vec::new();
+        for fixup in fixups {
+            let offset = u64::from(fixup.offset);
+            let translated = coff_relocation_kind(file, &fixup).and_then(|kind| {
+                omf_fixup_symbol(file, &mut object, &section_ids, &symbol_ids, fixup.target)
+                    .map(|symbol| (kind, symbol))
+                    .ok_or("unsupported fixup target")
+            });
+            let (kind, symbol) = match translated {
+                Ok(translated) => translated,
+                Err(reason) => {
+                    untranslated.push(Untranslated::Relocation {
+                        section: index,
+                        offset,
+                        reason,
+                    });
+                    continue;
+                }
+            };
+            // Add the displacement to the implicit addend.
+            let location = match data
+                .get_mut(fixup.offset as usize..)
+                .and_then(|d| d.get_mut(..4))
+            {
+                Some(location) => location,
+                None => {
+                    untranslated.push(Untranslated::Relocation {
+                        section: index,
+                        offset,
+                        reason: "fixup location is outside the section data",
+                    });
+                    continue;
+                }
+            };
+            let value = u32::from_le_bytes([location[0], location[1], location[2], location[3]]);
+            let value = value.wrapping_add(fixup.addend as u32);
+            location.copy_from_slice(&value.to_le_bytes());
+            relocations.push(write::Relocation {
+                offset,
+                symbol,
+                // COFF self-relative relocations are relative to the end of the location,
+                // which is where OMF self-relative fixups are relative to.
+                addend: if kind == RelocationKind::Relative {
+                    -4
+                } else {
+                    0
+                },
+                flags: RelocationFlags::Generic {
+                    kind,
+                    encoding: fixup.encoding,
+                    size: 32,
+                },
+            });
+        }
+        object.set_section_data(id, data, align);
+        for relocation in relocations {
+            object.add_relocation(id, relocation)?;
+        }
+    }
+
+    if file.start_address.is_some() {
+        untranslated.push(Untranslated::Entry);
+    }
+    Ok(Conversion {
+        object,
+        untranslated,
+    })
+}
+
+/// Convert a COFF object file to an OMF object module.
+///
+/// Code, data and uninitialized data sections become segments, with the
+/// segment class chosen from the section flags, and COMDAT sections become
+/// COMDATs. Symbols become public, external and communal names. Relocations
+/// become fixups if OMF has an equivalent: 16-bit and 32-bit absolute and
+/// self-relative relocations. COFF relocations use implicit addends, which
+/// are left in the segment data.
+///
+/// The module is written for [`TargetProfile::Flat386`](write::omf::TargetProfile::Flat386).
+/// Debug and linker directive sections are not translated.
+///
+/// Requires `feature = "coff"`.
+#[cfg(feature = "coff")]
+pub fn coff_to_omf<'data, R: ReadRef<'data>, Coff: CoffHeader>(
+    file: &CoffFile<'data, R, Coff>,
+) -> Result<Conversion<'data>> {
+    if file.architecture() != Architecture::I386 {
+        return Err(Error(format!(
+            "unsupported architecture {:?}",
+            file.architecture()
+        )));
+    }
+    let mut object = write::Object::new(BinaryFormat::Omf, Architecture::I386, Endianness::Little);
+    // COFF names already include any prefix added by the compiler.
+    object.set_mangling(write::Mangling::None);
+    let mut untranslated = Vec::new();
+
+    let mut section_ids = HashMap::new();
+    for section in file.sections() {
+        let index = section.index();
+        let kind = section.kind();
+        if !matches!(
+            kind,
+            SectionKind::Text
+                | SectionKind::Data
+                | SectionKind::ReadOnlyData
+                | SectionKind::UninitializedData
+        ) {
+            untranslated.push(Untranslated::Section {
+                index,
+                reason: "unsupported section kind",
+            });
+            continue;
+        }
+        let mut align = section.align();
+        if align > 4096 {
+            untranslated.push(Untranslated::Section {
+                index,
+                reason: "alignment is reduced to 4096",
+            });
+            align = 4096;
+        }
+        let id = object.add_section(Vec::new(), section.name_bytes()?.to_vec(), kind);
+        if kind == SectionKind::UninitializedData {
+            object.append_section_bss(id, section.size(), align);
+        } else {
+            object.set_section_data(id, section.data()?, align);
+        }
+        section_ids.insert(index, id);
+    }
+
+    let mut symbol_ids = HashMap::new();
+    for symbol in file.symbols() {
+        let index = symbol.index();
+        let section = match symbol.section() {
+            SymbolSection::Section(section) => match section_ids.get(&section) {
+                Some(&id) => write::SymbolSection::Section(id),
+                None => {
+                    if symbol.kind() != SymbolKind::Section {
+                        untranslated.push(Untranslated::Symbol {
+                            index,
+                            reason: "symbol is in an untranslated section",
+                        });
+                    }
+                    continue;
+                }
+            },
+            SymbolSection::Absolute => write::SymbolSection::Absolute,
+            SymbolSection::Undefined => write::SymbolSection::Undefined,
+            SymbolSection::Common => write::SymbolSection::Common,
+            _ => {
+                if symbol.kind() == SymbolKind::File {
+                    object.add_file_symbol(symbol.name_bytes()?.to_vec());
+                }
+                continue;
+            }
+        };
+        let id = match (symbol.kind(), section) {
+            (SymbolKind::Section, write::SymbolSection::Section(id)) => object.section_symbol(id),
+            (kind, _) => {
+                if symbol.is_weak() {
+                    untranslated.push(Untranslated::Symbol {
+                        index,
+                        reason: "weak symbols are not supported",
+                    });
+                }
+                object.add_symbol(write::Symbol {
+                    name: symbol.name_bytes()?.to_vec(),
+                    value: symbol.address(),
+                    size: symbol.size(),
+                    kind: match kind {
+                        SymbolKind::Text => SymbolKind::Text,
+                        _ => SymbolKind::Data,
+                    },
+                    scope: symbol.scope(),
+                    weak: false,
+                    section,
+                    flags: SymbolFlags::None,
+                })
+            }
+        };
+        symbol_ids.insert(index, id);
+    }
+
+    for comdat in file.comdats() {
+        let symbol_index = comdat.symbol();
+        let symbol = file.symbol_by_index(symbol_index)?;
+        let leader = match symbol.section_index() {
+            Some(section) => section,
+            None => continue,
+        };
+        for section in comdat.sections() {
+            if section != leader && section_ids.contains_key(&section) {
+                untranslated.push(Untranslated::Section {
+                    index: section,
+                    reason: "associative COMDAT sections are not supported",
+                });
+            }
+        }
+        let (section, symbol) = match (section_ids.get(&leader), symbol_ids.get(&symbol_index)) {
+            (Some(&section), Some(&symbol)) if object.symbol(symbol).value == 0 => {
+                (section, symbol)
+            }
+            _ => {
+                untranslated.push(Untranslated::Section {
+                    index: leader,
+                    reason: "unsupported COMDAT symbol",
+                });
+                continue;
+            }
+        };
+        let kind = match comdat.kind() {
+            kind @ (ComdatKind::NoDuplicates
+            | ComdatKind::Any
+            | ComdatKind::SameSize
+            | ComdatKind::ExactMatch) => kind,
+            _ => {
+                untranslated.push(Untranslated::Section {
+                    index: leader,
+                    reason: "unsupported COMDAT selection",
+                });
+                ComdatKind::Any
+            }
+        };
+        object.add_comdat(write::Comdat {
+            kind,
+            symbol,
+            sections: vec![section],
+        });
+    }
+
+    for section in file.sections() {
+        let index = section.index();
+        let id = match section_ids.get(&index) {
+            Some(&id) => id,
+            None => continue,
+        };
+        for (offset, relocation) in section.relocations() {
+            let symbol = match relocation.target() {
+                RelocationTarget::Symbol(symbol) => symbol_ids.get(&symbol).copied(),
+                _ => None,
+            };
+            let symbol = match (relocation.kind(), relocation.size(), symbol) {
+                (RelocationKind::Absolute | RelocationKind::Relative, 16 | 32, Some(symbol)) => {
+                    symbol
+                }
+                (_, _, symbol) => {
+                    untranslated.push(Untranslated::Relocation {
+                        section: index,
+                        offset,
+                        reason: if symbol.is_some() {
+                            "unsupported relocation kind"
+                        } else {
+                            "unsupported relocation target"
+                        },
+                    });
+                    continue;
+                }
+            };
+            object.add_relocation(
+                id,
+                write::Relocation {
+                    offset,
+                    symbol,
+                    addend: relocation.addend(),
+                    flags: RelocationFlags::Generic {
+                        kind: relocation.kind(),
+                        encoding: relocation.encoding(),
+                        size: relocation.size(),
+                    },
+                },
+            )?;
+        }
+    }
+
+    Ok(Conversion {
+        object,
+        untranslated,
+    })
+}
+
+/// Return the output section for a 1-based OMF section index.
+///
+/// Returns `None` if the index is invalid or the section was removed.
+fn omf_section_id(section_ids: &[Option<SectionId>], index: SectionIndex) -> Option<SectionId> {
+    index
+        .0
+        .checked_sub(1)
+        .and_then(|index| section_ids.get(index))
+        .copied()
+        .flatten()
+}
+
+/// Return true if the 1-based OMF section index refers to a removed section.
+fn is_removed_section(section_ids: &[Option<SectionId>], index: SectionIndex) -> bool {
+    index
+        .0
+        .checked_sub(1)
+        .and_then(|index| section_ids.get(index))
+        .map_or(false, Option::is_none)
+}
+
+/// Add the symbols of an OMF module to `object`.
+///
+/// Returns the output symbol for each input symbol. Externals are resolved to
+/// a definition of the same name in the module if there is one.
+fn add_omf_symbols<'data, R: ReadRef<'data>>(
+    file: &OmfFile<'data, R>,
+    object: &mut write::Object<'_>,
+    section_ids: &[Option<SectionId>],
+    symbol_kind: fn(SymbolKind) -> SymbolKind,
+    untranslated: &mut Vec<Untranslated>,
+) -> Vec<Option<SymbolId>> {
+    // Definitions are added first, so that externals can be resolved to
+    // definitions in the same module.
+    let mut symbol_ids = vec![None; file.symbols.len()];
+    let mut definitions = HashMap::new();
+    for symbol in &file.symbols {
+        let section = match symbol.segment {
+            _ if symbol.is_common => continue,
+            None => continue,
+            Some(SectionIndex(0)) => write::SymbolSection::Absolute,
+            Some(index) => match omf_section_id(section_ids, index) {
+                Some(id) => write::SymbolSection::Section(id),
+                None if is_removed_section(section_ids, index) => continue,
+                None => {
+                    untranslated.push(Untranslated::Symbol {
+                        index: SymbolIndex(symbol.index),
+                        reason: "invalid section index",
+                    });
+                    continue;
+                }
+            },
+        };
+        let id = object.add_symbol(write::Symbol {
+            name: symbol.name.to_vec(),
+            value: symbol.offset,
+            size: symbol.size,
+            kind: symbol_kind(symbol.kind),
+            scope: if symbol.global {
+                SymbolScope::Linkage
+            } else {
+                SymbolScope::Compilation
+            },
+            weak: false,
+            section,
+            flags: SymbolFlags::None,
+        });
+        symbol_ids[symbol.index] = Some(id);
+        if symbol.global {
+            definitions.insert(symbol.name, id);
+        } else {
+            definitions.entry(symbol.name).or_insert(id);
+        }
+    }
+    for symbol in &file.symbols {
+        if symbol.segment.is_some() && !symbol.is_common {
+            continue;
+        }
+        if symbol.is_common && !symbol.global {
+            untranslated.push(Untranslated::Symbol {
+                index: SymbolIndex(symbol.index),
+                reason: "local common symbols are not supported",
+            });
+        }
+        if symbol.default_resolution.is_some() {
+            untranslated.push(Untranslated::Symbol {
+                index: SymbolIndex(symbol.index),
+                reason: "default resolutions are not supported",
+            });
+        }
+        if let Some(&id) = definitions.get(symbol.name) {
+            symbol_ids[symbol.index] = Some(id);
+            continue;
+        }
+        let id = object.add_symbol(write::Symbol {
+            name: symbol.name.to_vec(),
+            value: 0,
+            size: if symbol.is_common { symbol.size } else { 0 },
+            kind: symbol_kind(symbol.kind),
+            scope: if symbol.is_common {
+                SymbolScope::Linkage
+            } else {
+                SymbolScope::Unknown
+            },
+            weak: false,
+            section: if symbol.is_common {
+                write::SymbolSection::Common
+            } else {
+                write::SymbolSection::Undefined
+            },
+            flags: SymbolFlags::None,
+        });
+        symbol_ids[symbol.index] = Some(id);
+        definitions.insert(symbol.name, id);
+    }
+    symbol_ids
+}
+
+/// Add the COMDATs of an OMF module to `object`.
+fn add_omf_comdats<'data, R: ReadRef<'data>>(
+    file: &OmfFile<'data, R>,
+    object: &mut write::Object<'_>,
+    section_ids: &[Option<SectionId>],
+    symbol_ids: &[Option<SymbolId>],
+    untranslated: &mut Vec<Untranslated>,
+) {
+    for comdat in &file.comdats {
+        if is_removed_section(section_ids, comdat.section) {
+            continue;
+        }
+        let kind = comdat.kind();
+        let symbol = symbol_ids.get(comdat.symbol).copied().flatten();
+        match (omf_section_id(section_ids, comdat.section), symbol) {
+            (Some(section), Some(symbol)) if kind != ComdatKind::Unknown => {
+                object.add_comdat(write::Comdat {
+                    kind,
+                    symbol,
+                    sections: vec![section],
+                });
+            }
+            _ => untranslated.push(Untranslated::Section {
+                index: comdat.section,
+                reason: "unsupported COMDAT selection",
+            }),
+        }
+    }
+}
+
+/// Return the output symbol for the target of an OMF fixup.
+///
+/// Segment targets use the section symbol. Group targets are not supported.
+fn omf_fixup_symbol<'data, R: ReadRef<'data>>(
+    file: &OmfFile<'data, R>,
+    object: &mut write::Object<'_>,
+    section_ids: &[Option<SectionId>],
+    symbol_ids: &[Option<SymbolId>],
+    target: OmfFixupTarget,
+) -> Option<SymbolId> {
+    match target {
+        OmfFixupTarget::Segment(segment) => {
+            omf_section_id(section_ids, SectionIndex(segment.into()))
+                .map(|section| object.section_symbol(section))
+        }
+        OmfFixupTarget::Symbol(external) => file
+            .external_symbol(external)
+            .and_then(|symbol| symbol_ids.get(symbol.0).copied().flatten()),
+        OmfFixupTarget::Group(_) => None,
+    }
+}
+
+/// Return the kind of the COFF symbol for an OMF symbol.
+#[cfg(feature = "coff")]
+fn coff_symbol_kind(kind: SymbolKind) -> SymbolKind {
+    match kind {
+        SymbolKind::Text => SymbolKind::Text,
+        _ => SymbolKind::Data,
+    }
+}
+
+/// Return the kind of the COFF relocation for an OMF fixup.
+#[cfg(feature = "coff")]
+fn coff_relocation_kind<'data, R: ReadRef<'data>>(
+    file: &OmfFile<'data, R>,
+    fixup: &OmfRelocation,
+) -> core::result::Result<RelocationKind, &'static str> {
+    if fixup.size != 32 {
+        return Err("unsupported fixup location");
+    }
+    match fixup.kind {
+        RelocationKind::Relative => Ok(RelocationKind::Relative),
+        RelocationKind::SegmentRelative => match fixup.frame {
+            Some(OmfFixupFrame::Group(group)) if is_flat_group(file, group) => {
+                Ok(RelocationKind::Absolute)
+            }
+            Some(OmfFixupFrame::Target) => Ok(RelocationKind::SectionOffset),
+            Some(OmfFixupFrame::Segment(segment))
+                if fixup.target == OmfFixupTarget::Segment(segment) =>
+            {
+                Ok(RelocationKind::SectionOffset)
+            }
+            _ => Err("unsupported fixup frame"),
+        },
+        _ => Err("unsupported fixup location"),
+    }
+}
+
+/// Return true if the 1-based group index refers to the `FLAT` group.
+#[cfg(feature = "coff")]
+fn is_flat_group<'data, R: ReadRef<'data>>(file: &OmfFile<'data, R>, group: u16) -> bool {
+    usize::from(group)
+        .checked_sub(1)
+        .and_then(|index| file.groups.get(index))
+        .map_or(false, |group| file.name(group.name) == b"FLAT")
+}
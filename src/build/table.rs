@@ -30,22 +30,26 @@ pub(super) use id_private::IdPrivate;
 pub struct Table<T>(Vec<T>);
 
 impl<T> Table<T> {
+    #[cfg_attr(not(feature = "elf"), allow(dead_code))]
     pub(super) fn new() -> Self {
         Table(Vec::new())
     }
 }
 
 impl<T: Item> Table<T> {
+    #[cfg_attr(not(feature = "elf"), allow(dead_code))]
     pub(super) fn next_id(&self) -> T::Id {
         T::Id::new(self.0.len())
     }
 
+    #[cfg_attr(not(feature = "elf"), allow(dead_code))]
     pub(super) fn push(&mut self, item: T) -> &mut T {
         self.0.push(item);
         self.0.last_mut().unwrap()
     }
 
     /// Number of items, including deleted items.
+    #[cfg_attr(not(feature = "elf"), allow(dead_code))]
     pub(super) fn len(&self) -> usize {
         self.0.len()
     }
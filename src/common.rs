@@ -37,6 +37,8 @@ pub enum Architecture {
     Sparc32Plus,
     Sparc64,
     SuperH,
+    TiC2000,
+    TiC6000,
     Wasm32,
     Wasm64,
     Xtensa,
@@ -91,6 +93,8 @@ impl Architecture {
             Architecture::Wasm64 => Some(AddressSize::U64),
             Architecture::Xtensa => Some(AddressSize::U32),
             Architecture::SuperH => Some(AddressSize::U32),
+            Architecture::TiC2000 => Some(AddressSize::U32),
+            Architecture::TiC6000 => Some(AddressSize::U32),
         }
     }
 }
@@ -128,6 +132,19 @@ pub enum BinaryFormat {
     Pe,
     Wasm,
     Xcoff,
+    Omf,
+    Ne,
+    Lx,
+    PharLap,
+    Aout,
+    Hunk,
+    Gemdos,
+    Pef,
+    Goff,
+    Ihex,
+    Srec,
+    TiCoff,
+    Xbe,
 }
 
 impl BinaryFormat {
@@ -359,7 +376,7 @@ pub enum RelocationKind {
     SectionOffset,
     /// The index of the section containing the symbol.
     SectionIndex,
-    
+
     // --- OMF-specific kinds (not yet used) ---
     /// Offset is relative to a segment base (not linear memory).
     #[allow(dead_code)]
@@ -445,11 +462,11 @@ pub enum RelocationEncoding {
 
     /// E2K 28-bit value stored in CS0
     E2KDisp,
-    
+
     // --- OMF-specific encodings (not yet used) ---
     /// Only the lower 16 bits of a 32-bit target are used.
     #[allow(dead_code)]
-    OmfLow16,       // Used with ADD2/LOW fixup modifiers
+    OmfLow16, // Used with ADD2/LOW fixup modifiers
 }
 
 /// File flags that are specific to each file format.
@@ -0,0 +1,105 @@
+//! Atari TOS/GEMDOS executable definitions.
+//!
+//! These definitions are independent of read/write support, although we do implement
+//! some traits useful for those.
+//!
+//! A GEMDOS executable (`.PRG`, `.TOS`, `.TTP` or `.APP` file) starts with
+//! a [`PrgHeader`], which is followed by the text segment, the data segment,
+//! the symbol table, and the fixup list. All values are big endian.
+//!
+//! The text, data and bss segments are loaded contiguously, and the fixup list
+//! gives the offsets in the program of 32-bit values that the load address is
+//! added to.
+
+#![allow(missing_docs)]
+
+use crate::endian::{BigEndian as BE, U16Bytes, U32Bytes};
+use crate::pod::Pod;
+
+/// The value of [`PrgHeader::ph_branch`]. This is a 68000 `bra.s` instruction.
+pub const PRG_MAGIC: u16 = 0x601a;
+
+/// The header at the start of a GEMDOS executable.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct PrgHeader {
+    /// The magic number, [`PRG_MAGIC`].
+    pub ph_branch: U16Bytes<BE>,
+    /// The size of the text segment.
+    pub ph_tlen: U32Bytes<BE>,
+    /// The size of the data segment.
+    pub ph_dlen: U32Bytes<BE>,
+    /// The size of the bss segment.
+    pub ph_blen: U32Bytes<BE>,
+    /// The size of the symbol table.
+    pub ph_slen: U32Bytes<BE>,
+    /// Reserved.
+    pub ph_res1: U32Bytes<BE>,
+    /// Program flags. A combination of the `PF_*` constants.
+    pub ph_prgflags: U32Bytes<BE>,
+    /// Zero if the fixup list is present.
+    pub ph_absflag: U16Bytes<BE>,
+}
+
+// Values for `PrgHeader::ph_prgflags`.
+/// Only clear the bss segment when loading.
+pub const PF_FASTLOAD: u32 = 0x1;
+/// The program may be loaded into alternate RAM.
+pub const PF_TTRAMLOAD: u32 = 0x2;
+/// Memory allocations may come from alternate RAM.
+pub const PF_TTRAMMEM: u32 = 0x4;
+/// Mask for the memory protection mode.
+pub const PF_MEMPROT: u32 = 0x30;
+/// Memory protection mode: private.
+pub const PF_PRIVATE: u32 = 0x00;
+/// Memory protection mode: global.
+pub const PF_GLOBAL: u32 = 0x10;
+/// Memory protection mode: supervisor.
+pub const PF_SUPERVISOR: u32 = 0x20;
+/// Memory protection mode: readable.
+pub const PF_READABLE: u32 = 0x30;
+/// The text segment may be shared between processes.
+pub const PF_SHARED_TEXT: u32 = 0x800;
+/// Shift for the TPA size in units of 128KB.
+pub const PF_TPASIZE_SHIFT: u32 = 28;
+
+/// An entry in the DRI symbol table.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DriSymbol {
+    /// The symbol name, padded with zeros.
+    pub name: [u8; 8],
+    /// The symbol type. A combination of the `DRI_*` constants.
+    pub sym_type: U16Bytes<BE>,
+    /// The symbol value.
+    ///
+    /// For symbols in the text, data and bss segments, this is the offset from
+    /// the start of the text segment.
+    pub value: U32Bytes<BE>,
+}
+
+// Values for `DriSymbol::sym_type`.
+/// The symbol is defined.
+pub const DRI_DEFINED: u16 = 0x8000;
+/// The symbol is an equate.
+pub const DRI_EQUATED: u16 = 0x4000;
+/// The symbol is global.
+pub const DRI_GLOBAL: u16 = 0x2000;
+/// The symbol is an equated register.
+pub const DRI_REGISTER: u16 = 0x1000;
+/// The symbol is an external reference.
+pub const DRI_EXTERNAL: u16 = 0x0800;
+/// The symbol is in the data segment.
+pub const DRI_DATA: u16 = 0x0400;
+/// The symbol is in the text segment.
+pub const DRI_TEXT: u16 = 0x0200;
+/// The symbol is in the bss segment.
+pub const DRI_BSS: u16 = 0x0100;
+/// Mask for the GST extended name flag.
+pub const DRI_LONG_NAME_MASK: u16 = 0x00ff;
+/// The GST extended name flag.
+///
+/// The next entry contains the next 14 bytes of the name.
+pub const DRI_LONG_NAME: u16 = 0x0048;
+
+unsafe_impl_pod!(PrgHeader, DriSymbol);
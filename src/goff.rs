@@ -0,0 +1,409 @@
+//! IBM GOFF definitions.
+//!
+//! These definitions are independent of read/write support, although we do implement
+//! some traits useful for those.
+//!
+//! The Generalized Object File Format (GOFF) is used for z/OS object files.
+//! A GOFF file is a sequence of fixed-length 80-byte records. Each record
+//! starts with a 3-byte prefix that gives the record type, and whether the
+//! record is continued in the next record. A continuation record has the same
+//! prefix, followed by the next 77 bytes of the logical record.
+//!
+//! The structures defined here are the fixed fields at the start of each
+//! logical record. All values are big endian, and names are usually encoded
+//! in EBCDIC.
+//!
+//! Bit numbers in the comments use the IBM convention, where bit 0 is the
+//! most significant bit.
+
+#![allow(missing_docs)]
+
+use crate::endian::{BigEndian as BE, U16Bytes, U32Bytes};
+use crate::pod::Pod;
+
+/// The length of a physical record.
+pub const GOFF_RECORD_LENGTH: usize = 80;
+/// The length of the prefix of each physical record.
+pub const GOFF_PREFIX_LENGTH: usize = 3;
+
+/// The value of [`RecordPrefix::ptv_prefix`].
+pub const GOFF_PTV_PREFIX: u8 = 0x03;
+
+// Values for the record type in `RecordPrefix::ptv_flags`.
+/// External symbol definition record.
+pub const GOFF_RT_ESD: u8 = 0x0;
+/// Text record.
+pub const GOFF_RT_TXT: u8 = 0x1;
+/// Relocation directory record.
+pub const GOFF_RT_RLD: u8 = 0x2;
+/// Deferred element length record.
+pub const GOFF_RT_LEN: u8 = 0x3;
+/// Module end record.
+pub const GOFF_RT_END: u8 = 0x4;
+/// Module header record.
+pub const GOFF_RT_HDR: u8 = 0xf;
+/// Shift for the record type in `RecordPrefix::ptv_flags`.
+pub const GOFF_RT_SHIFT: u8 = 4;
+
+// Flags in `RecordPrefix::ptv_flags`.
+/// The logical record is continued in the next physical record.
+pub const GOFF_CONTINUED: u8 = 0x02;
+/// This physical record is a continuation of the previous record.
+pub const GOFF_CONTINUATION: u8 = 0x01;
+
+/// The prefix of each physical record.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct RecordPrefix {
+    /// The value [`GOFF_PTV_PREFIX`].
+    pub ptv_prefix: u8,
+    /// The record type in bits 0-3, and the continuation flags in bits 6-7.
+    pub ptv_flags: u8,
+    /// The record version. This is 0.
+    pub ptv_version: u8,
+}
+
+impl RecordPrefix {
+    /// Return the record type. One of the `GOFF_RT_*` constants.
+    pub fn record_type(&self) -> u8 {
+        self.ptv_flags >> GOFF_RT_SHIFT
+    }
+}
+
+/// The fixed fields of a module header (HDR) record.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct HdrRecord {
+    pub prefix: RecordPrefix,
+    /// Reserved.
+    pub reserved1: [u8; 45],
+    /// The architecture level of the module.
+    pub architecture_level: U32Bytes<BE>,
+    /// The length of the module properties.
+    pub module_properties_length: U16Bytes<BE>,
+    /// Reserved.
+    pub reserved2: [u8; 6],
+}
+
+/// The fixed fields of an external symbol definition (ESD) record.
+///
+/// The name follows the fixed fields.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct EsdRecord {
+    pub prefix: RecordPrefix,
+    /// The symbol type. One of the `ESD_ST_*` constants.
+    pub symbol_type: u8,
+    /// The identifier of this symbol. Identifiers start at 1.
+    pub esdid: U32Bytes<BE>,
+    /// The identifier of the owning symbol, or 0 if there is none.
+    pub parent_esdid: U32Bytes<BE>,
+    /// Reserved.
+    pub reserved1: U32Bytes<BE>,
+    /// The offset of a label or part within its element.
+    pub offset: U32Bytes<BE>,
+    /// Reserved.
+    pub reserved2: U32Bytes<BE>,
+    /// The length of an element or part.
+    pub length: U32Bytes<BE>,
+    /// The identifier of the element containing the extended attributes.
+    pub extended_attribute_esdid: U32Bytes<BE>,
+    /// The offset of the extended attributes.
+    pub extended_attribute_offset: U32Bytes<BE>,
+    /// Reserved.
+    pub reserved3: U32Bytes<BE>,
+    /// The name space. One of the `ESD_NS_*` constants.
+    pub name_space: u8,
+    /// A combination of the `ESD_FLAG_*` constants.
+    pub flags: u8,
+    /// The fill byte, if [`ESD_FLAG_FILL_BYTE`] is set.
+    pub fill_byte: u8,
+    /// Reserved.
+    pub reserved4: u8,
+    /// The identifier of the associated data area.
+    pub ada_esdid: U32Bytes<BE>,
+    /// The sort priority of a part.
+    pub sort_priority: U32Bytes<BE>,
+    /// The signature of the symbol.
+    pub signature: [u8; 8],
+    /// The addressing mode. One of the `ESD_AMODE_*` constants.
+    pub amode: u8,
+    /// The residence mode. One of the `ESD_RMODE_*` constants.
+    pub rmode: u8,
+    /// The text style in bits 0-3, and the binding algorithm in bits 4-7.
+    pub text_attributes: u8,
+    /// The tasking behavior in bits 0-2, the read-only flag in bit 4, and the
+    /// executable attribute in bits 5-7.
+    pub tasking_attributes: u8,
+    /// The duplicate symbol severity in bits 2-3, and the binding strength in
+    /// bits 4-7.
+    pub binding_attributes: u8,
+    /// The loading behavior in bits 0-1, the common flag in bit 2, the
+    /// indirect reference flag in bit 3, and the binding scope in bits 4-7.
+    pub loading_attributes: u8,
+    /// The linkage type in bit 2, and the alignment in bits 3-7.
+    pub alignment_attributes: u8,
+    /// Reserved.
+    pub reserved5: [u8; 3],
+    /// The length of the name.
+    pub name_length: U16Bytes<BE>,
+}
+
+impl EsdRecord {
+    /// Return the executable attribute. One of the `ESD_EXE_*` constants.
+    pub fn executable(&self) -> u8 {
+        self.tasking_attributes & 0x07
+    }
+
+    /// Return true if the element or part is read-only.
+    pub fn is_read_only(&self) -> bool {
+        self.tasking_attributes & 0x08 != 0
+    }
+
+    /// Return the binding strength. One of the `ESD_BST_*` constants.
+    pub fn binding_strength(&self) -> u8 {
+        self.binding_attributes & 0x0f
+    }
+
+    /// Return the loading behavior. One of the `ESD_LB_*` constants.
+    pub fn loading_behavior(&self) -> u8 {
+        self.loading_attributes >> 6
+    }
+
+    /// Return the binding scope. One of the `ESD_BSC_*` constants.
+    pub fn binding_scope(&self) -> u8 {
+        self.loading_attributes & 0x0f
+    }
+
+    /// Return the alignment, as a power of 2.
+    pub fn alignment(&self) -> u8 {
+        self.alignment_attributes & 0x1f
+    }
+}
+
+// Values for `EsdRecord::symbol_type`.
+/// Section definition.
+pub const ESD_ST_SD: u8 = 0;
+/// Element definition. The parent is a section definition.
+pub const ESD_ST_ED: u8 = 1;
+/// Label definition. The parent is an element definition.
+pub const ESD_ST_LD: u8 = 2;
+/// Part reference. The parent is an element definition.
+pub const ESD_ST_PR: u8 = 3;
+/// External reference.
+pub const ESD_ST_ER: u8 = 4;
+
+// Values for `EsdRecord::name_space`.
+/// Program management binder names.
+pub const ESD_NS_PROGRAM_MANAGEMENT_BINDER: u8 = 0;
+/// Normal names.
+pub const ESD_NS_NORMAL_NAME: u8 = 1;
+/// Pseudo register names.
+pub const ESD_NS_PSEUDO_REGISTER: u8 = 2;
+/// Part names.
+pub const ESD_NS_PARTS: u8 = 3;
+
+// Values for `EsdRecord::flags`.
+/// The fill byte is present.
+pub const ESD_FLAG_FILL_BYTE: u8 = 0x80;
+/// The name is mangled.
+pub const ESD_FLAG_MANGLED: u8 = 0x40;
+/// The symbol may be renamed.
+pub const ESD_FLAG_RENAMEABLE: u8 = 0x20;
+/// The class may be removed.
+pub const ESD_FLAG_REMOVABLE: u8 = 0x10;
+
+// Values for `EsdRecord::amode`.
+pub const ESD_AMODE_NONE: u8 = 0;
+pub const ESD_AMODE_24: u8 = 1;
+pub const ESD_AMODE_31: u8 = 2;
+pub const ESD_AMODE_ANY: u8 = 3;
+pub const ESD_AMODE_64: u8 = 4;
+pub const ESD_AMODE_MIN: u8 = 16;
+
+// Values for `EsdRecord::rmode`.
+pub const ESD_RMODE_NONE: u8 = 0;
+pub const ESD_RMODE_24: u8 = 1;
+pub const ESD_RMODE_31: u8 = 3;
+pub const ESD_RMODE_64: u8 = 4;
+
+// Values for `EsdRecord::executable`.
+pub const ESD_EXE_UNSPECIFIED: u8 = 0;
+pub const ESD_EXE_DATA: u8 = 1;
+pub const ESD_EXE_CODE: u8 = 2;
+
+// Values for `EsdRecord::binding_strength`.
+pub const ESD_BST_STRONG: u8 = 0;
+pub const ESD_BST_WEAK: u8 = 1;
+
+// Values for `EsdRecord::loading_behavior`.
+pub const ESD_LB_INITIAL: u8 = 0;
+pub const ESD_LB_DEFERRED: u8 = 1;
+pub const ESD_LB_NO_LOAD: u8 = 2;
+
+// Values for `EsdRecord::binding_scope`.
+pub const ESD_BSC_UNSPECIFIED: u8 = 0;
+pub const ESD_BSC_SECTION: u8 = 1;
+pub const ESD_BSC_MODULE: u8 = 2;
+pub const ESD_BSC_LIBRARY: u8 = 3;
+pub const ESD_BSC_IMPORT_EXPORT: u8 = 4;
+
+/// The fixed fields of a text (TXT) record.
+///
+/// The data follows the fixed fields.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct TxtRecord {
+    pub prefix: RecordPrefix,
+    /// The text record style in bits 4-7. One of the `TXT_RS_*` constants.
+    pub flags: u8,
+    /// The identifier of the element or part that the data is for.
+    pub element_esdid: U32Bytes<BE>,
+    /// Reserved.
+    pub reserved: U32Bytes<BE>,
+    /// The offset of the data within the element or part.
+    pub offset: U32Bytes<BE>,
+    /// The length of the data before encoding, or 0 if it is not encoded.
+    pub true_length: U32Bytes<BE>,
+    /// The data encoding, or 0 if it is not encoded.
+    pub text_encoding: U16Bytes<BE>,
+    /// The length of the data in this record.
+    pub data_length: U16Bytes<BE>,
+}
+
+// Values for the text record style in `TxtRecord::flags`.
+pub const TXT_RS_BYTE: u8 = 0;
+pub const TXT_RS_STRUCTURED: u8 = 1;
+pub const TXT_RS_UNSTRUCTURED: u8 = 2;
+
+/// The fixed fields of a relocation directory (RLD) record.
+///
+/// A sequence of relocation items follows the fixed fields. Each item starts
+/// with an [`RldItem`], which is followed by the R pointer, the P pointer,
+/// the offset, and the extended attributes, if present.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct RldRecord {
+    pub prefix: RecordPrefix,
+    /// Reserved.
+    pub reserved: u8,
+    /// The length of the relocation items.
+    pub length: U16Bytes<BE>,
+}
+
+/// The fixed fields of a relocation item.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct RldItem {
+    /// A combination of the `RLD_FLAG_*` constants.
+    pub flags: u8,
+    /// The reference type in bits 0-3, and the referent type in bits 4-7.
+    pub reference_flags: u8,
+    /// The action in bits 0-6, and the fetch/store flag in bit 7.
+    pub action_flags: u8,
+    /// Reserved.
+    pub reserved1: u8,
+    /// The length in bytes of the relocated field.
+    pub target_length: u8,
+    /// The bit length and bit offset of the relocated field.
+    pub bit_attributes: u8,
+    /// Reserved.
+    pub reserved2: U16Bytes<BE>,
+}
+
+impl RldItem {
+    /// Return the reference type. One of the `RLD_RT_*` constants.
+    pub fn reference_type(&self) -> u8 {
+        self.reference_flags >> 4
+    }
+
+    /// Return the referent type. One of the `RLD_RO_*` constants.
+    pub fn referent_type(&self) -> u8 {
+        self.reference_flags & 0x0f
+    }
+
+    /// Return the action. One of the `RLD_ACT_*` constants.
+    pub fn action(&self) -> u8 {
+        self.action_flags >> 1
+    }
+}
+
+// Values for `RldItem::flags`.
+/// The R pointer is omitted, and is the same as the previous item.
+pub const RLD_FLAG_SAME_RID: u8 = 0x80;
+/// The P pointer is omitted, and is the same as the previous item.
+pub const RLD_FLAG_SAME_PID: u8 = 0x40;
+/// The offset is omitted, and is the same as the previous item.
+pub const RLD_FLAG_SAME_OFFSET: u8 = 0x20;
+/// The extended attribute identifier and offset are present.
+pub const RLD_FLAG_EA_PRESENT: u8 = 0x04;
+/// The offset is 8 bytes instead of 4 bytes.
+pub const RLD_FLAG_OFFSET_8: u8 = 0x02;
+/// The relocated value depends on the addressing mode.
+pub const RLD_FLAG_AMODE_SENSITIVE: u8 = 0x01;
+
+// Values for `RldItem::reference_type`.
+/// The address of the referent.
+pub const RLD_RT_ADDRESS: u8 = 0;
+/// The offset of the referent within its class.
+pub const RLD_RT_OFFSET: u8 = 1;
+/// The length of the referent.
+pub const RLD_RT_LENGTH: u8 = 2;
+/// The relative immediate offset to the referent, in halfwords.
+pub const RLD_RT_RELATIVE_IMMEDIATE: u8 = 6;
+/// The type of the referent.
+pub const RLD_RT_TYPE_CONSTANT: u8 = 7;
+/// The long displacement of the referent.
+pub const RLD_RT_LONG_DISPLACEMENT: u8 = 9;
+
+// Values for `RldItem::referent_type`.
+pub const RLD_RO_LABEL: u8 = 0;
+pub const RLD_RO_ELEMENT: u8 = 1;
+pub const RLD_RO_CLASS: u8 = 2;
+pub const RLD_RO_PART: u8 = 3;
+
+// Values for `RldItem::action`.
+/// Add the value to the relocated field.
+pub const RLD_ACT_ADD: u8 = 0;
+/// Subtract the value from the relocated field.
+pub const RLD_ACT_SUBTRACT: u8 = 1;
+
+/// The fixed fields of a module end (END) record.
+///
+/// The entry point name follows the fixed fields.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct EndRecord {
+    pub prefix: RecordPrefix,
+    /// The entry point request type in bits 6-7. One of the `END_EPR_*` constants.
+    pub flags: u8,
+    /// The addressing mode of the entry point.
+    pub amode: u8,
+    /// Reserved.
+    pub reserved1: [u8; 3],
+    /// The number of logical records in the module, or 0.
+    pub record_count: U32Bytes<BE>,
+    /// The identifier of the entry point symbol.
+    pub esdid: U32Bytes<BE>,
+    /// Reserved.
+    pub reserved2: U32Bytes<BE>,
+    /// The offset of the entry point from the symbol.
+    pub offset: U32Bytes<BE>,
+    /// The length of the entry point name.
+    pub name_length: U16Bytes<BE>,
+}
+
+// Values for the entry point request type in `EndRecord::flags`.
+pub const END_EPR_NONE: u8 = 0;
+pub const END_EPR_ESDID: u8 = 1;
+pub const END_EPR_NAME: u8 = 2;
+
+unsafe_impl_pod!(
+    RecordPrefix,
+    HdrRecord,
+    EsdRecord,
+    TxtRecord,
+    RldRecord,
+    RldItem,
+    EndRecord,
+);
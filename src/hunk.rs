@@ -0,0 +1,121 @@
+//! AmigaOS Hunk definitions.
+//!
+//! These definitions are independent of read/write support, although we do implement
+//! some traits useful for those.
+//!
+//! This module is based on `<dos/doshunks.h>`.
+//!
+//! A Hunk file is a sequence of big endian 32-bit words. It is made up of
+//! blocks that start with a block type, which is one of the `HUNK_*` constants.
+//! Executables (load files) start with [`HUNK_HEADER`], and object files start
+//! with [`HUNK_UNIT`]. Each hunk is a [`HUNK_CODE`], [`HUNK_DATA`] or [`HUNK_BSS`]
+//! block, followed by relocation, symbol and debug blocks, and ends with
+//! [`HUNK_END`].
+//!
+//! Names are stored as a count of 32-bit words followed by the name, padded
+//! with zeros to a multiple of 4 bytes.
+
+#![allow(missing_docs)]
+
+/// The start of a program unit in an object file.
+pub const HUNK_UNIT: u32 = 999;
+/// The name of the next hunk.
+pub const HUNK_NAME: u32 = 1000;
+/// A hunk containing code.
+pub const HUNK_CODE: u32 = 1001;
+/// A hunk containing initialized data.
+pub const HUNK_DATA: u32 = 1002;
+/// A hunk containing uninitialized data.
+pub const HUNK_BSS: u32 = 1003;
+/// 32-bit absolute relocations.
+pub const HUNK_RELOC32: u32 = 1004;
+pub const HUNK_ABSRELOC32: u32 = HUNK_RELOC32;
+/// 16-bit PC-relative relocations.
+pub const HUNK_RELOC16: u32 = 1005;
+pub const HUNK_RELRELOC16: u32 = HUNK_RELOC16;
+/// 8-bit PC-relative relocations.
+pub const HUNK_RELOC8: u32 = 1006;
+pub const HUNK_RELRELOC8: u32 = HUNK_RELOC8;
+/// External symbol definitions and references.
+pub const HUNK_EXT: u32 = 1007;
+/// Symbols for debuggers.
+pub const HUNK_SYMBOL: u32 = 1008;
+/// Debug information.
+pub const HUNK_DEBUG: u32 = 1009;
+/// The end of a hunk.
+pub const HUNK_END: u32 = 1010;
+/// The start of an executable.
+pub const HUNK_HEADER: u32 = 1011;
+/// An overlay table.
+pub const HUNK_OVERLAY: u32 = 1013;
+/// The end of an overlay node.
+pub const HUNK_BREAK: u32 = 1014;
+/// 32-bit data relative relocations.
+///
+/// In executables, this is used for [`HUNK_RELOC32SHORT`] instead.
+pub const HUNK_DREL32: u32 = 1015;
+/// 16-bit data relative relocations.
+pub const HUNK_DREL16: u32 = 1016;
+/// 8-bit data relative relocations.
+pub const HUNK_DREL8: u32 = 1017;
+/// A library of units.
+pub const HUNK_LIB: u32 = 1018;
+/// The index of a library of units.
+pub const HUNK_INDEX: u32 = 1019;
+/// 32-bit absolute relocations using 16-bit offsets.
+pub const HUNK_RELOC32SHORT: u32 = 1020;
+/// 32-bit PC-relative relocations.
+pub const HUNK_RELRELOC32: u32 = 1021;
+/// 16-bit absolute relocations.
+pub const HUNK_ABSRELOC16: u32 = 1022;
+
+/// Flag in a block type indicating that an unknown block can be skipped.
+pub const HUNKF_ADVISORY: u32 = 1 << 29;
+/// Flag in a hunk type or size indicating the hunk must be loaded in chip memory.
+pub const HUNKF_CHIP: u32 = 1 << 30;
+/// Flag in a hunk type or size indicating the hunk must be loaded in fast memory.
+pub const HUNKF_FAST: u32 = 1 << 31;
+/// Mask for the memory flags.
+///
+/// If both flags are set in a hunk size in [`HUNK_HEADER`], then the
+/// memory attributes follow in the next word.
+pub const HUNKF_MEMORY: u32 = HUNKF_CHIP | HUNKF_FAST;
+/// Mask for the block type or hunk size without the flags.
+pub const HUNK_TYPE_MASK: u32 = 0x3fff_ffff;
+
+// Symbol types in `HUNK_EXT` and `HUNK_SYMBOL`, stored in the high byte of the
+// name length.
+/// Symbol table entry.
+pub const EXT_SYMB: u8 = 0;
+/// Relocatable definition.
+pub const EXT_DEF: u8 = 1;
+/// Absolute definition.
+pub const EXT_ABS: u8 = 2;
+/// Resident library definition. No longer supported.
+pub const EXT_RES: u8 = 3;
+/// 32-bit absolute reference to a symbol.
+pub const EXT_REF32: u8 = 129;
+pub const EXT_ABSREF32: u8 = EXT_REF32;
+/// 32-bit absolute reference to a common block.
+pub const EXT_COMMON: u8 = 130;
+pub const EXT_ABSCOMMON: u8 = EXT_COMMON;
+/// 16-bit PC-relative reference to a symbol.
+pub const EXT_REF16: u8 = 131;
+pub const EXT_RELREF16: u8 = EXT_REF16;
+/// 8-bit PC-relative reference to a symbol.
+pub const EXT_REF8: u8 = 132;
+pub const EXT_RELREF8: u8 = EXT_REF8;
+/// 32-bit data relative reference to a symbol.
+pub const EXT_DEXT32: u8 = 133;
+/// 16-bit data relative reference to a symbol.
+pub const EXT_DEXT16: u8 = 134;
+/// 8-bit data relative reference to a symbol.
+pub const EXT_DEXT8: u8 = 135;
+/// 32-bit PC-relative reference to a symbol.
+pub const EXT_RELREF32: u8 = 136;
+/// 32-bit PC-relative reference to a common block.
+pub const EXT_RELCOMMON: u8 = 137;
+/// 16-bit absolute reference to a symbol.
+pub const EXT_ABSREF16: u8 = 138;
+/// 8-bit absolute reference to a symbol.
+pub const EXT_ABSREF8: u8 = 139;
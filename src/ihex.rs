@@ -0,0 +1,48 @@
+//! Intel HEX definitions.
+//!
+//! These definitions are independent of read/write support, although we do implement
+//! some traits useful for those.
+//!
+//! An Intel HEX file is a text file containing a sequence of records, one
+//! per line. Each record starts with [`IHEX_RECORD_MARK`], which is followed
+//! by pairs of hexadecimal digits giving the bytes of the record:
+//!
+//! - the number of data bytes,
+//! - the 16-bit address, big endian,
+//! - the record type, one of the `IHEX_*` record type constants,
+//! - the data bytes,
+//! - a checksum, which is the two's complement of the sum of the other bytes.
+//!
+//! The address of a data record is relative to the base address given by the
+//! most recent extended segment address or extended linear address record.
+//! The offset within the base address wraps at 64 KiB.
+
+/// The character at the start of each record.
+pub const IHEX_RECORD_MARK: u8 = b':';
+
+/// The size of the fields in a record, excluding the data.
+///
+/// This is the count, address, record type, and checksum.
+pub const IHEX_RECORD_OVERHEAD: usize = 5;
+
+// Values for the record type.
+/// Data record.
+pub const IHEX_DATA: u8 = 0x00;
+/// End of file record.
+pub const IHEX_END_OF_FILE: u8 = 0x01;
+/// Extended segment address record.
+///
+/// The data is a 16-bit segment, which is multiplied by 16 to give the base address.
+pub const IHEX_EXTENDED_SEGMENT_ADDRESS: u8 = 0x02;
+/// Start segment address record.
+///
+/// The data is the 16-bit CS and IP of the entry point.
+pub const IHEX_START_SEGMENT_ADDRESS: u8 = 0x03;
+/// Extended linear address record.
+///
+/// The data is the upper 16 bits of the base address.
+pub const IHEX_EXTENDED_LINEAR_ADDRESS: u8 = 0x04;
+/// Start linear address record.
+///
+/// The data is the 32-bit EIP of the entry point.
+pub const IHEX_START_LINEAR_ADDRESS: u8 = 0x05;
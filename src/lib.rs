@@ -7,7 +7,10 @@
 //! ## Raw struct definitions
 //!
 //! Raw structs are defined for: [ELF](elf), [Mach-O](macho), [PE/COFF](pe),
-//! [XCOFF](xcoff), [archive].
+//! [XCOFF](xcoff), [OMF](omf), [NE](ne), [LE/LX](lx),
+//! [PharLap](pharlap), [a.out](aout),
+//! [Amiga Hunk](hunk), [GEMDOS](gemdos), [PEF](pef), [GOFF](goff),
+//! [Intel HEX](ihex), [S-record](srec), [TI COFF](ticoff), [XBE](xbe), [archive].
 //! Types and traits for zerocopy support are defined in the [`pod`] and [`endian`] modules.
 //!
 //! ## Unified read API
@@ -97,15 +100,45 @@ pub mod write;
 #[cfg(feature = "build_core")]
 pub mod build;
 
+#[cfg(feature = "aout")]
+pub mod aout;
 #[cfg(feature = "archive")]
 pub mod archive;
 #[cfg(feature = "elf")]
 pub mod elf;
+#[cfg(feature = "gemdos")]
+pub mod gemdos;
+#[cfg(feature = "goff")]
+pub mod goff;
+#[cfg(feature = "hunk")]
+pub mod hunk;
+#[cfg(feature = "ihex")]
+pub mod ihex;
+#[cfg(feature = "lx")]
+pub mod lx;
 #[cfg(feature = "macho")]
 pub mod macho;
-#[cfg(any(feature = "coff", feature = "pe"))]
+#[cfg(feature = "ne")]
+pub mod ne;
+#[cfg(feature = "omf")]
+pub mod omf;
+#[cfg(any(
+    feature = "coff",
+    feature = "pe",
+    feature = "ne",
+    feature = "lx",
+    feature = "pharlap"
+))]
 pub mod pe;
+#[cfg(feature = "pef")]
+pub mod pef;
+#[cfg(feature = "pharlap")]
+pub mod pharlap;
+#[cfg(feature = "srec")]
+pub mod srec;
+#[cfg(feature = "ticoff")]
+pub mod ticoff;
+#[cfg(feature = "xbe")]
+pub mod xbe;
 #[cfg(feature = "xcoff")]
 pub mod xcoff;
-#[cfg(feature = "omf")]
-pub mod omf;
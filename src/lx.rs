@@ -0,0 +1,220 @@
+//! LE/LX definitions.
+//!
+//! These definitions are independent of read/write support, although we do implement
+//! some traits useful for those.
+//!
+//! The LX format is used by OS/2 2.x and later executables and libraries, and
+//! the closely related LE format is used by Windows VxDs and by DOS extenders
+//! such as DOS/4GW. The file usually starts with an MS-DOS stub using
+//! [`pe::ImageDosHeader`](crate::pe::ImageDosHeader), which gives the offset
+//! of the LE/LX header, [`pe::ImageVxdHeader`](crate::pe::ImageVxdHeader).
+//! The other tables use the structures and constants defined here.
+//!
+//! The two formats differ mainly in the object page table. In the LX format,
+//! the `e32_lastpagesize` header field is instead the shift count for the
+//! offsets in the object page table.
+
+#![allow(missing_docs)]
+
+use crate::endian::{LittleEndian as LE, U16Bytes, U32Bytes};
+use crate::pod::Pod;
+
+/// The signature of an LE header, "LE".
+pub const IMAGE_LE_SIGNATURE: u16 = 0x454C;
+/// The signature of an LX header, "LX".
+pub const IMAGE_LX_SIGNATURE: u16 = 0x584C;
+/// The signature of a DOS/4G extender stub header, "BW".
+///
+/// Like the MS-DOS header, this is followed by the number of bytes in the
+/// last page and the number of 512-byte pages in the stub.
+pub const IMAGE_BW_SIGNATURE: u16 = 0x5742;
+
+// Values for `ImageVxdHeader::e32_cpu`.
+
+pub const E32_CPU286: u16 = 0x01;
+pub const E32_CPU386: u16 = 0x02;
+pub const E32_CPU486: u16 = 0x03;
+
+// Values for `ImageVxdHeader::e32_os`.
+
+pub const E32_OS_UNKNOWN: u16 = 0;
+pub const E32_OS_OS2: u16 = 1;
+pub const E32_OS_WINDOWS: u16 = 2;
+pub const E32_OS_DOS4: u16 = 3;
+pub const E32_OS_WIN386: u16 = 4;
+
+// Values for `ImageVxdHeader::e32_mflags`.
+
+/// Per-process library initialization.
+pub const E32_LIBINIT: u32 = 0x0000_0004;
+/// Internal fixups have been applied.
+pub const E32_NOINTFIX: u32 = 0x0000_0010;
+/// External fixups have been applied.
+pub const E32_NOEXTFIX: u32 = 0x0000_0020;
+/// Mask for the application type.
+pub const E32_APPMASK: u32 = 0x0000_0700;
+/// The linker reported errors when creating the module.
+pub const E32_NOLOAD: u32 = 0x0000_2000;
+/// Mask for the module type.
+pub const E32_MODMASK: u32 = 0x0003_8000;
+/// The module is a program.
+pub const E32_MODEXE: u32 = 0x0000_0000;
+/// The module is a library.
+pub const E32_MODDLL: u32 = 0x0000_8000;
+/// The module is a protected memory library.
+pub const E32_MODPROTDLL: u32 = 0x0001_8000;
+/// The module is a physical device driver.
+pub const E32_MODPDEV: u32 = 0x0002_0000;
+/// The module is a virtual device driver.
+pub const E32_MODVDEV: u32 = 0x0002_8000;
+/// Per-process library termination.
+pub const E32_LIBTERM: u32 = 0x4000_0000;
+
+/// An entry in the object table.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ObjectTableEntry {
+    /// The size of the object in memory.
+    pub virtual_size: U32Bytes<LE>,
+    /// The linear address that the object is relocated to.
+    pub base_address: U32Bytes<LE>,
+    /// The object flags. See the `OBJ_*` constants.
+    pub flags: U32Bytes<LE>,
+    /// The 1-based index of the first page of the object in the object page table.
+    pub page_table_index: U32Bytes<LE>,
+    /// The number of entries in the object page table for this object.
+    pub page_count: U32Bytes<LE>,
+    /// Reserved.
+    pub reserved: U32Bytes<LE>,
+}
+
+// Values for `ObjectTableEntry::flags`.
+
+pub const OBJ_READ: u32 = 0x0001;
+pub const OBJ_WRITE: u32 = 0x0002;
+pub const OBJ_EXEC: u32 = 0x0004;
+pub const OBJ_RSRC: u32 = 0x0008;
+pub const OBJ_DISCARD: u32 = 0x0010;
+pub const OBJ_SHARED: u32 = 0x0020;
+pub const OBJ_PRELOAD: u32 = 0x0040;
+pub const OBJ_INVALID: u32 = 0x0080;
+pub const OBJ_ZEROFILL: u32 = 0x0100;
+pub const OBJ_RESIDENT: u32 = 0x0200;
+pub const OBJ_CONTIG: u32 = 0x0300;
+pub const OBJ_DYNAMIC: u32 = 0x0400;
+pub const OBJ_ALIAS16: u32 = 0x1000;
+/// The object uses 32-bit offsets.
+pub const OBJ_BIGDEF: u32 = 0x2000;
+pub const OBJ_CONFORM: u32 = 0x4000;
+pub const OBJ_IOPL: u32 = 0x8000;
+
+/// An entry in the object page table of an LX file.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct LxObjectPageEntry {
+    /// The offset of the page data from `e32_datapage`, shifted right by the
+    /// page offset shift.
+    pub offset: U32Bytes<LE>,
+    /// The size of the page data in the file.
+    pub size: U16Bytes<LE>,
+    /// The page flags. See the `PAGE_*` constants.
+    pub flags: U16Bytes<LE>,
+}
+
+/// An entry in the object page table of an LE file.
+///
+/// The pages are stored in order starting at `e32_datapage`, and each page is
+/// `e32_pagesize` bytes except for the last page in the file.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct LeObjectPageEntry {
+    /// The 1-based page number, with the most significant byte first.
+    pub page_number: [u8; 3],
+    /// The page flags. See the `PAGE_*` constants.
+    pub flags: u8,
+}
+
+// Values for the page flags.
+
+/// The page data is stored in the file.
+pub const PAGE_VALID: u16 = 0x0000;
+/// The page data is compressed with EXEPACK1 iterated records.
+pub const PAGE_ITERDATA: u16 = 0x0001;
+/// The page is not present.
+pub const PAGE_INVALID: u16 = 0x0002;
+/// The page is filled with zeros.
+pub const PAGE_ZEROED: u16 = 0x0003;
+/// The page is part of a range of pages.
+pub const PAGE_RANGE: u16 = 0x0004;
+/// The page data is compressed with EXEPACK2.
+pub const PAGE_ITERDATA2: u16 = 0x0005;
+
+// Values for the source type of a fixup record.
+
+/// Mask for the kind of location to fix up.
+pub const NRSTYP: u8 = 0x0f;
+/// An 8-bit offset.
+pub const NRSBYT: u8 = 0x00;
+/// A 16-bit selector.
+pub const NRSSEG: u8 = 0x02;
+/// A 16-bit offset and 16-bit selector.
+pub const NRSPTR: u8 = 0x03;
+/// A 16-bit offset.
+pub const NRSOFF: u8 = 0x05;
+/// A 32-bit offset and 16-bit selector.
+pub const NRPTR48: u8 = 0x06;
+/// A 32-bit offset.
+pub const NROFF32: u8 = 0x07;
+/// A 32-bit self-relative offset.
+pub const NRSOFF32: u8 = 0x08;
+/// The fixup refers to the 16:16 alias of an object.
+pub const NRALIAS: u8 = 0x10;
+/// The fixup record has a list of source offsets.
+pub const NRCHAIN: u8 = 0x20;
+
+// Values for the target flags of a fixup record.
+
+/// Mask for the kind of target.
+pub const NRRTYP: u8 = 0x03;
+/// A reference to an object in the module.
+pub const NRRINT: u8 = 0x00;
+/// An import by ordinal.
+pub const NRRORD: u8 = 0x01;
+/// An import by name.
+pub const NRRNAM: u8 = 0x02;
+/// A reference to an entry point in the module, using its ordinal.
+pub const NRRENT: u8 = 0x03;
+/// The fixup record has an additive value.
+pub const NRADD: u8 = 0x04;
+/// The target offset or ordinal is 32 bits.
+pub const NR32BITOFF: u8 = 0x10;
+/// The additive value is 32 bits.
+pub const NR32BITADD: u8 = 0x20;
+/// The object number or module ordinal is 16 bits.
+pub const NR16OBJMOD: u8 = 0x40;
+/// The import ordinal is 8 bits.
+pub const NR8BITORD: u8 = 0x80;
+
+// Values for the type of an entry table bundle.
+
+/// The bundle contains unused entries.
+pub const ENTRY_UNUSED: u8 = 0x00;
+/// The bundle contains 16-bit entries.
+pub const ENTRY16: u8 = 0x01;
+/// The bundle contains 286 call gate entries.
+pub const ENTRY_GATE16: u8 = 0x02;
+/// The bundle contains 32-bit entries.
+pub const ENTRY32: u8 = 0x03;
+/// The bundle contains forwarder entries.
+pub const ENTRY_FORWARDER: u8 = 0x04;
+/// The bundle has parameter typing information.
+pub const ENTRY_TYPEINFO: u8 = 0x80;
+
+// Values for the flags of an entry.
+
+/// The entry is exported.
+pub const ENTRY_EXPORTED: u8 = 0x01;
+/// The forwarder is imported by ordinal.
+pub const ENTRY_FWD_ORDINAL: u8 = 0x01;
+
+unsafe_impl_pod!(ObjectTableEntry, LxObjectPageEntry, LeObjectPageEntry);
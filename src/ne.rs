@@ -0,0 +1,260 @@
+//! NE definitions.
+//!
+//! These definitions are independent of read/write support, although we do implement
+//! some traits useful for those.
+//!
+//! The NE format is used by 16-bit Windows and OS/2 1.x executables and
+//! libraries. The file starts with an MS-DOS stub using
+//! [`pe::ImageDosHeader`](crate::pe::ImageDosHeader), which gives the offset
+//! of the NE header, [`pe::ImageOs2Header`](crate::pe::ImageOs2Header).
+//! The other tables use the structures and constants defined here.
+//!
+//! The names of the constants are based on those used by Wine.
+
+#![allow(missing_docs)]
+
+use crate::endian::{LittleEndian as LE, U16Bytes, U32Bytes};
+use crate::pod::Pod;
+
+// Values for `ImageOs2Header::ne_flags`.
+
+/// The module has a single shared data segment.
+pub const NE_FFLAGS_SINGLEDATA: u16 = 0x0001;
+/// The module has a data segment for each instance.
+pub const NE_FFLAGS_MULTIPLEDATA: u16 = 0x0002;
+/// The module runs in protected mode only.
+pub const NE_FFLAGS_PROTMODE: u16 = 0x0008;
+/// The module uses 8086 instructions.
+pub const NE_FFLAGS_8086: u16 = 0x0010;
+/// The module uses 80286 instructions.
+pub const NE_FFLAGS_80286: u16 = 0x0020;
+/// The module uses 80386 instructions.
+pub const NE_FFLAGS_80386: u16 = 0x0040;
+/// The module uses 80x87 instructions.
+pub const NE_FFLAGS_80X87: u16 = 0x0080;
+/// Mask for the application type.
+pub const NE_FFLAGS_APPTYPE: u16 = 0x0700;
+/// The application is not compatible with the Presentation Manager or Windows API.
+pub const NE_FFLAGS_NOTWINCOMPAT: u16 = 0x0100;
+/// The application is compatible with the Presentation Manager or Windows API.
+pub const NE_FFLAGS_WINCOMPAT: u16 = 0x0200;
+/// The application uses the Presentation Manager or Windows API.
+pub const NE_FFLAGS_WINAPI: u16 = 0x0300;
+/// The module has a self-loading first segment.
+pub const NE_FFLAGS_SELFLOAD: u16 = 0x0800;
+/// The linker reported errors when creating the module.
+pub const NE_FFLAGS_LINKERROR: u16 = 0x2000;
+/// The library module calls its WEP routine when it is unloaded.
+pub const NE_FFLAGS_CALLWEP: u16 = 0x4000;
+/// The module is a library.
+pub const NE_FFLAGS_LIBMODULE: u16 = 0x8000;
+
+// Values for `ImageOs2Header::ne_exetyp`.
+
+pub const NE_OSFLAGS_UNKNOWN: u8 = 0;
+pub const NE_OSFLAGS_OS2: u8 = 1;
+pub const NE_OSFLAGS_WINDOWS: u8 = 2;
+pub const NE_OSFLAGS_DOS4: u8 = 3;
+pub const NE_OSFLAGS_WIN386: u8 = 4;
+pub const NE_OSFLAGS_BOSS: u8 = 5;
+
+/// An entry in the segment table.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct SegmentTableEntry {
+    /// The offset of the segment data in the file, in units of
+    /// `1 << ne_align` bytes, or 0 if the segment has no data in the file.
+    pub sector: U16Bytes<LE>,
+    /// The length of the segment data in the file.
+    ///
+    /// 0 means 65536 if `sector` is not 0.
+    pub length: U16Bytes<LE>,
+    /// The segment flags. See the `NE_SEGFLAGS_*` constants.
+    pub flags: U16Bytes<LE>,
+    /// The minimum allocation size of the segment.
+    ///
+    /// 0 means 65536.
+    pub min_alloc: U16Bytes<LE>,
+}
+
+// Values for `SegmentTableEntry::flags`.
+
+/// Mask for the segment type.
+pub const NE_SEGFLAGS_TYPE_MASK: u16 = 0x0007;
+/// The segment contains data. Otherwise it contains code.
+pub const NE_SEGFLAGS_DATA: u16 = 0x0001;
+/// The segment data is iterated.
+pub const NE_SEGFLAGS_ITERATED: u16 = 0x0008;
+/// The segment is moveable.
+pub const NE_SEGFLAGS_MOVEABLE: u16 = 0x0010;
+/// The segment is shareable.
+pub const NE_SEGFLAGS_SHAREABLE: u16 = 0x0020;
+/// The segment is preloaded.
+pub const NE_SEGFLAGS_PRELOAD: u16 = 0x0040;
+/// The code segment is execute-only, or the data segment is read-only.
+pub const NE_SEGFLAGS_READONLY: u16 = 0x0080;
+/// The segment data is followed by relocation records.
+pub const NE_SEGFLAGS_RELOC_DATA: u16 = 0x0100;
+/// The code segment is conforming.
+pub const NE_SEGFLAGS_CONFORMING: u16 = 0x0200;
+/// Mask for the I/O privilege level.
+pub const NE_SEGFLAGS_PRIVILEGE_MASK: u16 = 0x0C00;
+/// The segment is discardable.
+pub const NE_SEGFLAGS_DISCARDABLE: u16 = 0x1000;
+/// The segment uses 32-bit offsets.
+pub const NE_SEGFLAGS_32BIT: u16 = 0x2000;
+/// The segment is part of a huge segment.
+pub const NE_SEGFLAGS_HUGE: u16 = 0x4000;
+
+/// A relocation record for a segment.
+///
+/// The segment data is followed by a 16-bit count of these records.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct RelocationEntry {
+    /// The kind of location to fix up. See the `NE_RADDR_*` constants.
+    pub address_type: u8,
+    /// The kind of target. See the `NE_RELTYPE_*` and `NE_RELFLAG_*` constants.
+    pub relocation_type: u8,
+    /// The offset of the location within the segment.
+    ///
+    /// If the relocation is not additive, then the location contains the
+    /// offset of the next location for the same target, with 0xffff ending
+    /// the chain.
+    pub offset: U16Bytes<LE>,
+    /// The first target field.
+    ///
+    /// This is the segment number for internal references, the module
+    /// reference index for imports, and the fixup type for OS fixups.
+    pub target1: U16Bytes<LE>,
+    /// The second target field.
+    ///
+    /// This is the offset or entry ordinal for internal references, the
+    /// ordinal for imports by ordinal, and the offset in the imported names
+    /// table for imports by name.
+    pub target2: U16Bytes<LE>,
+}
+
+// Values for `RelocationEntry::address_type`.
+
+/// The low byte of an offset.
+pub const NE_RADDR_LOWBYTE: u8 = 0;
+/// A 16-bit segment selector.
+pub const NE_RADDR_SELECTOR: u8 = 2;
+/// A 16-bit offset and 16-bit segment selector.
+pub const NE_RADDR_POINTER32: u8 = 3;
+/// A 16-bit offset.
+pub const NE_RADDR_OFFSET16: u8 = 5;
+/// A 32-bit offset and 16-bit segment selector.
+pub const NE_RADDR_POINTER48: u8 = 11;
+/// A 32-bit offset.
+pub const NE_RADDR_OFFSET32: u8 = 13;
+
+// Values for `RelocationEntry::relocation_type`.
+
+/// Mask for the target type.
+pub const NE_RELTYPE_MASK: u8 = 0x03;
+/// A reference to a segment in the module.
+pub const NE_RELTYPE_INTERNAL: u8 = 0;
+/// An import by ordinal.
+pub const NE_RELTYPE_ORDINAL: u8 = 1;
+/// An import by name.
+pub const NE_RELTYPE_NAME: u8 = 2;
+/// An operating system fixup, such as for floating point instructions.
+pub const NE_RELTYPE_OSFIXUP: u8 = 3;
+/// The target is added to the value at the location.
+pub const NE_RELFLAG_ADDITIVE: u8 = 0x04;
+
+/// The segment number used in internal references to moveable segments.
+///
+/// The second target field is then an entry ordinal.
+pub const NE_SEGNUM_MOVEABLE: u8 = 0xff;
+
+// Values for the type of an entry table bundle.
+
+/// The bundle contains unused entries.
+pub const NE_ENTRY_UNUSED: u8 = 0x00;
+/// The bundle contains entries for constants.
+pub const NE_ENTRY_CONSTANT: u8 = 0xfe;
+/// The bundle contains entries for moveable segments.
+pub const NE_ENTRY_MOVEABLE: u8 = 0xff;
+
+// Values for the flags of an entry.
+
+/// The entry is exported.
+pub const NE_ENTFLAGS_EXPORTED: u8 = 0x01;
+/// The entry uses a shared data segment.
+pub const NE_ENTFLAGS_SHAREDDATA: u8 = 0x02;
+
+/// The header of a group of resources with the same type in a Windows resource table.
+///
+/// The resource table starts with a 16-bit alignment shift count, followed by
+/// these headers. A type ID of 0 ends the table.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ResourceTypeInfo {
+    /// The resource type.
+    ///
+    /// If the high bit is set then the low bits are an integer ID, otherwise
+    /// this is the offset of the name from the start of the resource table.
+    pub type_id: U16Bytes<LE>,
+    /// The number of resources of this type.
+    pub count: U16Bytes<LE>,
+    /// Reserved.
+    pub reserved: U32Bytes<LE>,
+}
+
+/// A resource in a Windows resource table.
+///
+/// These follow each [`ResourceTypeInfo`].
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ResourceNameInfo {
+    /// The offset of the resource data in the file, in units of the alignment shift count.
+    pub offset: U16Bytes<LE>,
+    /// The length of the resource data, in units of the alignment shift count.
+    pub length: U16Bytes<LE>,
+    /// The resource flags. See the `NE_RSCFLAGS_*` constants.
+    pub flags: U16Bytes<LE>,
+    /// The resource ID.
+    ///
+    /// If the high bit is set then the low bits are an integer ID, otherwise
+    /// this is the offset of the name from the start of the resource table.
+    pub id: U16Bytes<LE>,
+    /// Reserved.
+    pub handle: U16Bytes<LE>,
+    /// Reserved.
+    pub usage: U16Bytes<LE>,
+}
+
+/// A resource in an OS/2 resource table.
+///
+/// The resource data is in one of the last `ne_cres` segments.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Os2ResourceEntry {
+    /// The resource type ID.
+    pub type_id: U16Bytes<LE>,
+    /// The resource ID.
+    pub id: U16Bytes<LE>,
+}
+
+/// The high bit of a resource type or name ID, which is set for integer IDs.
+pub const NE_RSCTYPE_INTEGER: u16 = 0x8000;
+
+// Values for `ResourceNameInfo::flags`.
+
+/// The resource is moveable.
+pub const NE_RSCFLAGS_MOVEABLE: u16 = 0x0010;
+/// The resource is shareable.
+pub const NE_RSCFLAGS_PURE: u16 = 0x0020;
+/// The resource is preloaded.
+pub const NE_RSCFLAGS_PRELOAD: u16 = 0x0040;
+
+unsafe_impl_pod!(
+    SegmentTableEntry,
+    RelocationEntry,
+    ResourceTypeInfo,
+    ResourceNameInfo,
+    Os2ResourceEntry,
+);
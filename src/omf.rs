@@ -0,0 +1,244 @@
+//! OMF definitions.
+//!
+//! These definitions are independent of read/write support.
+//!
+//! This module contains the record types and other constants that are shared
+//! by the OMF reader and writer, and the OMF library dictionary definitions.
+//!
+//! OMF libraries end with a dictionary that maps public names to the page
+//! numbers of the members that define them. The dictionary is a number of
+//! blocks, each containing [`DICTIONARY_BUCKETS`] buckets. A name is found by
+//! starting at the block and bucket given by [`dictionary_hash`], and
+//! stepping by the deltas until the name or an empty bucket is found.
+
+// OMF record types.
+//
+// This is the first byte of each record. Where a record has a 32-bit form,
+// it uses the next (odd) record type.
+
+/// Translator header (THEADR). Gives the name of the module.
+pub const THEADR: u8 = 0x80;
+/// Library module header (LHEADR). Like THEADR, used by some library members.
+pub const LHEADR: u8 = 0x82;
+/// Comment (COMENT). Translator, library and extension information.
+pub const COMENT: u8 = 0x88;
+/// Module end (MODEND).
+pub const MODEND: u8 = 0x8A;
+/// Module end (MODEND) with a 32-bit start address.
+pub const MODEND32: u8 = 0x8B;
+/// External names definition (EXTDEF).
+pub const EXTDEF: u8 = 0x8C;
+/// Type definition (TYPDEF).
+pub const TYPDEF: u8 = 0x8E;
+/// Public names definition (PUBDEF).
+pub const PUBDEF: u8 = 0x90;
+/// Public names definition (PUBDEF) with 32-bit offsets.
+pub const PUBDEF32: u8 = 0x91;
+/// Line numbers (LINNUM).
+pub const LINNUM: u8 = 0x94;
+/// Line numbers (LINNUM) with 32-bit offsets.
+pub const LINNUM32: u8 = 0x95;
+/// List of names (LNAMES). Segment, class, group and overlay names.
+pub const LNAMES: u8 = 0x96;
+/// Segment definition (SEGDEF).
+pub const SEGDEF: u8 = 0x98;
+/// Segment definition (SEGDEF) with a 32-bit segment length.
+pub const SEGDEF32: u8 = 0x99;
+/// Group definition (GRPDEF).
+pub const GRPDEF: u8 = 0x9A;
+/// Fixups (FIXUPP).
+pub const FIXUPP: u8 = 0x9C;
+/// Fixups (FIXUPP) with 32-bit displacements.
+pub const FIXUPP32: u8 = 0x9D;
+/// Logical enumerated data (LEDATA).
+pub const LEDATA: u8 = 0xA0;
+/// Logical enumerated data (LEDATA) with a 32-bit offset.
+pub const LEDATA32: u8 = 0xA1;
+/// Logical iterated data (LIDATA).
+pub const LIDATA: u8 = 0xA2;
+/// Logical iterated data (LIDATA) with a 32-bit offset.
+pub const LIDATA32: u8 = 0xA3;
+/// Communal names definition (COMDEF).
+pub const COMDEF: u8 = 0xB0;
+/// Backpatch (BAKPAT).
+pub const BAKPAT: u8 = 0xB2;
+/// Backpatch (BAKPAT) with 32-bit offsets and values.
+pub const BAKPAT32: u8 = 0xB3;
+/// Local external names definition (LEXTDEF).
+pub const LEXTDEF: u8 = 0xB4;
+/// Local external names definition (LEXTDEF). Identical to 0xB4.
+pub const LEXTDEF32: u8 = 0xB5;
+/// Local public names definition (LPUBDEF).
+pub const LPUBDEF: u8 = 0xB6;
+/// Local public names definition (LPUBDEF) with 32-bit offsets.
+pub const LPUBDEF32: u8 = 0xB7;
+/// Local communal names definition (LCOMDEF).
+pub const LCOMDEF: u8 = 0xB8;
+/// COMDAT external names definition (CEXTDEF).
+pub const CEXTDEF: u8 = 0xBC;
+/// Initialized communal data (COMDAT).
+pub const COMDAT: u8 = 0xC2;
+/// Initialized communal data (COMDAT) with a 32-bit offset.
+pub const COMDAT32: u8 = 0xC3;
+/// Symbol line numbers (LINSYM), for code in a COMDAT.
+pub const LINSYM: u8 = 0xC4;
+/// Symbol line numbers (LINSYM) with 32-bit offsets.
+pub const LINSYM32: u8 = 0xC5;
+/// Alias definition (ALIAS).
+pub const ALIAS: u8 = 0xC6;
+/// Named backpatch (NBKPAT).
+pub const NBKPAT: u8 = 0xC8;
+/// Named backpatch (NBKPAT) with 32-bit offsets and values.
+pub const NBKPAT32: u8 = 0xC9;
+/// Local logical names definition (LLNAMES).
+pub const LLNAMES: u8 = 0xCA;
+/// OMF version number (VERNUM).
+pub const VERNUM: u8 = 0xCC;
+/// Vendor-specific OMF extension (VENDEXT).
+pub const VENDEXT: u8 = 0xCE;
+/// Library header (LIBHDR).
+pub const LIBHDR: u8 = 0xF0;
+/// Library end (LIBEND). Followed by the library dictionary.
+pub const LIBEND: u8 = 0xF1;
+
+/// COMENT attribute bit: utilities that strip comments must keep the comment.
+pub const COMENT_NO_PURGE: u8 = 0x80;
+/// COMENT attribute bit: utilities that list records should not show the comment.
+pub const COMENT_NO_LIST: u8 = 0x40;
+
+/// COMDAT flag 0x01: continuation — the record continues the previous COMDAT with the same name.
+pub const COMDAT_CONTINUATION: u8 = 0x01;
+
+/// COMDAT flag 0x02: iterated — the data uses the LIDATA format.
+pub const COMDAT_ITERATED: u8 = 0x02;
+
+/// COMDAT flag 0x04: local — the COMDAT name is only visible within the module.
+pub const COMDAT_LOCAL: u8 = 0x04;
+
+/// The mask of the COMDAT allocation type in the attributes byte.
+pub const COMDAT_ALLOCATION_MASK: u8 = 0x0F;
+
+/// COMDAT allocation type 0x00: explicit — allocated in the segment given by the public base.
+pub const COMDAT_EXPLICIT: u8 = 0x00;
+
+/// COMDAT allocation type 0x01: far code — allocated in an implicit 16-bit code segment.
+pub const COMDAT_CODE16: u8 = 0x01;
+
+/// COMDAT allocation type 0x02: far data — allocated in an implicit 16-bit data segment.
+pub const COMDAT_DATA16: u8 = 0x02;
+
+/// COMDAT allocation type 0x03: code32 — allocated in an implicit 32-bit code segment.
+pub const COMDAT_CODE32: u8 = 0x03;
+
+/// COMDAT allocation type 0x04: data32 — allocated in an implicit 32-bit data segment.
+pub const COMDAT_DATA32: u8 = 0x04;
+
+/// COMENT class 0x00: translator — the name of the compiler or assembler.
+pub const COMENT_TRANSLATOR: u8 = 0x00;
+
+/// COMENT class 0x01: copyright — a copyright notice.
+pub const COMENT_COPYRIGHT: u8 = 0x01;
+
+/// COMENT class 0x9D: memory model — ASCII codes for the model, CPU and FPU settings.
+pub const COMENT_MEMORY_MODEL: u8 = 0x9D;
+
+/// COMENT class 0x9E: DOSSEG — use the standard DOS segment ordering.
+pub const COMENT_DOSSEG: u8 = 0x9E;
+
+/// COMENT class 0x9F: default library — a library for the linker to search.
+pub const COMENT_DEFAULT_LIBRARY: u8 = 0x9F;
+
+/// COMENT class 0xA0: OMF extensions, identified by a subtype byte.
+pub const COMENT_OMFEXT: u8 = 0xA0;
+
+/// OMF extension subtype 0x01: IMPDEF — import definition.
+pub const OMFEXT_IMPDEF: u8 = 0x01;
+
+/// OMF extension subtype 0x02: EXPDEF — export definition.
+pub const OMFEXT_EXPDEF: u8 = 0x02;
+
+/// OMF extension subtype 0x03: INCDEF — incremental compilation index deltas.
+pub const OMFEXT_INCDEF: u8 = 0x03;
+
+/// OMF extension subtype 0x05: LNKDIR — C++ linker directives.
+pub const OMFEXT_LNKDIR: u8 = 0x05;
+
+/// COMENT class 0xA2: link pass separator — records after this are only needed in pass 2.
+pub const COMENT_LINK_PASS: u8 = 0xA2;
+
+/// COMENT class 0xA3: LIBMOD — the module name of an object in a library.
+pub const COMENT_LIBMOD: u8 = 0xA3;
+
+/// COMENT class 0xA4: EXESTR — a string to copy into the output executable.
+pub const COMENT_EXESTR: u8 = 0xA4;
+
+/// COMENT class 0xA8: WKEXT — weak external symbols with a default resolution.
+pub const COMENT_WKEXT: u8 = 0xA8;
+
+/// COMENT class 0xA9: LZEXT — lazy external symbols with a default resolution.
+pub const COMENT_LZEXT: u8 = 0xA9;
+
+/// COMENT class 0xAA: Easy OMF-386 — PharLap 32-bit object signature (`80386`).
+pub const COMENT_EASY_OMF: u8 = 0xAA;
+
+/// COMENT class 0xE0: the first class of Borland Turbo Debugger type, scope and source file information.
+pub const COMENT_BORLAND_DEBUG_FIRST: u8 = 0xE0;
+
+/// COMENT class 0xE8: the last class of Borland Turbo Debugger type, scope and source file information.
+pub const COMENT_BORLAND_DEBUG_LAST: u8 = 0xE8;
+
+/// The size of a library dictionary block.
+pub const DICTIONARY_BLOCK_SIZE: usize = 512;
+
+/// The number of buckets in a library dictionary block.
+pub const DICTIONARY_BUCKETS: u16 = 37;
+
+/// The value of the free space byte of a library dictionary block that is full.
+pub const DICTIONARY_BLOCK_FULL: u8 = 0xFF;
+
+/// The starting positions and deltas for a library dictionary lookup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DictionaryHash {
+    /// The index of the first block to search.
+    pub block: u16,
+    /// The amount to add to the block index when a block is full.
+    pub block_delta: u16,
+    /// The index of the first bucket to search within a block.
+    pub bucket: u16,
+    /// The amount to add to the bucket index when a bucket is used.
+    pub bucket_delta: u16,
+}
+
+/// Compute the library dictionary hash of a public name.
+///
+/// `blocks` is the number of blocks in the dictionary, and must not be 0.
+///
+/// The hash is case-insensitive. The block index and bucket delta are computed
+/// from the name read forwards, and the bucket index and block delta from the
+/// name read backwards.
+pub fn dictionary_hash(name: &[u8], blocks: u16) -> DictionaryHash {
+    let len = name.len() as u16;
+    let mut block_x = len | 0x20;
+    let mut bucket_d = len | 0x20;
+    let mut block_d = 0u16;
+    let mut bucket_x = 0u16;
+    let mut front = name.iter();
+    let mut back = name.iter().rev();
+    for i in 0..name.len() {
+        let c = u16::from(*back.next().unwrap() | 0x20);
+        bucket_x = bucket_x.rotate_right(2) ^ c;
+        block_d = block_d.rotate_left(2) ^ c;
+        if i + 1 == name.len() {
+            break;
+        }
+        let c = u16::from(*front.next().unwrap() | 0x20);
+        block_x = block_x.rotate_left(2) ^ c;
+        bucket_d = bucket_d.rotate_right(2) ^ c;
+    }
+    DictionaryHash {
+        block: block_x % blocks,
+        block_delta: (block_d % blocks).max(1),
+        bucket: bucket_x % DICTIONARY_BUCKETS,
+        bucket_delta: (bucket_d % DICTIONARY_BUCKETS).max(1),
+    }
+}
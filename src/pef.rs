@@ -0,0 +1,332 @@
+//! Preferred Executable Format (PEF) definitions.
+//!
+//! These definitions are independent of read/write support, although we do implement
+//! some traits useful for those.
+//!
+//! This module is based on `<PEFBinaryFormat.h>`.
+//!
+//! PEF containers are used by the Code Fragment Manager (CFM) of the classic
+//! Mac OS for PowerPC and CFM-68K applications and shared libraries. The file
+//! starts with a [`ContainerHeader`], which is followed by a [`SectionHeader`]
+//! for each section and the section name table. All values are big endian.
+//!
+//! The loader section starts with a [`LoaderInfoHeader`], which is followed by
+//! the imported library table, the imported symbol table, the relocation
+//! headers, the relocation instructions, the loader string table, and the
+//! export hash table, key table and symbol table.
+
+#![allow(missing_docs)]
+
+use crate::endian::{BigEndian as BE, I16Bytes, I32Bytes, U16Bytes, U32Bytes};
+use crate::pod::Pod;
+
+/// The value of [`ContainerHeader::tag1`]: `Joy!`.
+pub const PEF_TAG1: u32 = 0x4a6f_7921;
+/// The value of [`ContainerHeader::tag2`]: `peff`.
+pub const PEF_TAG2: u32 = 0x7065_6666;
+/// The value of [`ContainerHeader::format_version`].
+pub const PEF_VERSION: u32 = 1;
+
+// Values for `ContainerHeader::architecture`.
+/// PowerPC: `pwpc`.
+pub const PEF_ARCH_POWERPC: u32 = 0x7077_7063;
+/// CFM-68K: `m68k`.
+pub const PEF_ARCH_M68K: u32 = 0x6d36_386b;
+
+/// The header at the start of a PEF container.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ContainerHeader {
+    /// The first magic number, [`PEF_TAG1`].
+    pub tag1: U32Bytes<BE>,
+    /// The second magic number, [`PEF_TAG2`].
+    pub tag2: U32Bytes<BE>,
+    /// The architecture. One of the `PEF_ARCH_*` constants.
+    pub architecture: U32Bytes<BE>,
+    /// The container format version, [`PEF_VERSION`].
+    pub format_version: U32Bytes<BE>,
+    /// The creation time, in seconds since 1904.
+    pub date_time_stamp: U32Bytes<BE>,
+    /// The oldest compatible version of the fragment for definitions.
+    pub old_def_version: U32Bytes<BE>,
+    /// The oldest compatible version of the fragment for implementations.
+    pub old_imp_version: U32Bytes<BE>,
+    /// The current version of the fragment.
+    pub current_version: U32Bytes<BE>,
+    /// The total number of sections.
+    pub section_count: U16Bytes<BE>,
+    /// The number of instantiated sections.
+    ///
+    /// The instantiated sections come before the other sections.
+    pub inst_section_count: U16Bytes<BE>,
+    /// Reserved.
+    pub reserved_a: U32Bytes<BE>,
+}
+
+/// A section header.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct SectionHeader {
+    /// The offset of the name in the section name table, or -1 if the
+    /// section has no name.
+    pub name_offset: I32Bytes<BE>,
+    /// The preferred address of the section.
+    pub default_address: U32Bytes<BE>,
+    /// The size of the section in memory, including zero-initialized data.
+    pub total_length: U32Bytes<BE>,
+    /// The size of the initialized data of the section after expansion.
+    pub unpacked_length: U32Bytes<BE>,
+    /// The size of the section data in the container.
+    pub container_length: U32Bytes<BE>,
+    /// The offset of the section data from the start of the container.
+    pub container_offset: U32Bytes<BE>,
+    /// The section kind. One of the `PEF_*_SECTION` constants.
+    pub section_kind: u8,
+    /// How the section is shared. One of the `PEF_*_SHARE` constants.
+    pub share_kind: u8,
+    /// The alignment of the section, as a power of 2.
+    pub alignment: u8,
+    /// Reserved.
+    pub reserved_a: u8,
+}
+
+// Values for `SectionHeader::section_kind`.
+/// Code. Instantiated, read-only.
+pub const PEF_CODE_SECTION: u8 = 0;
+/// Data that is stored unpacked. Instantiated, read-write.
+pub const PEF_UNPACKED_DATA_SECTION: u8 = 1;
+/// Data that is stored as pattern-initialization instructions.
+/// Instantiated, read-write.
+pub const PEF_PATTERN_DATA_SECTION: u8 = 2;
+/// Read-only data. Instantiated.
+pub const PEF_CONSTANT_SECTION: u8 = 3;
+/// The loader section. Not instantiated.
+pub const PEF_LOADER_SECTION: u8 = 4;
+/// Debugging information. Not instantiated.
+pub const PEF_DEBUG_SECTION: u8 = 5;
+/// Executable data. Instantiated, read-write.
+pub const PEF_EXEC_DATA_SECTION: u8 = 6;
+/// Exception handling information. Not instantiated.
+pub const PEF_EXCEPTION_SECTION: u8 = 7;
+/// Traceback tables. Not instantiated.
+pub const PEF_TRACEBACK_SECTION: u8 = 8;
+
+// Values for `SectionHeader::share_kind`.
+/// The section is shared within a process.
+pub const PEF_PROCESS_SHARE: u8 = 1;
+/// The section is shared between all processes.
+pub const PEF_GLOBAL_SHARE: u8 = 4;
+/// The section is shared between all processes, and is read-only for
+/// user mode code.
+pub const PEF_PROTECTED_SHARE: u8 = 5;
+
+// Pattern-initialization opcodes.
+//
+// Each instruction starts with a byte containing the opcode in the high 3 bits
+// and a count in the low 5 bits. If the count is 0, then the count is stored in
+// an argument after the byte. Arguments are stored in big endian order, using
+// 7 bits per byte, with the high bit set in every byte except the last.
+/// Zero fill `count` bytes.
+pub const PEF_PK_DATA_ZERO: u8 = 0;
+/// Copy the next `count` bytes.
+pub const PEF_PK_DATA_BLOCK: u8 = 1;
+/// Copy the next `count` bytes. They are repeated `repeatCount + 1` times,
+/// where `repeatCount` is the next argument.
+pub const PEF_PK_DATA_REPEAT: u8 = 2;
+/// Interleave a common block of `count` bytes with custom blocks.
+///
+/// This is followed by the `customSize` and `repeatCount` arguments, the
+/// common block, and `repeatCount` custom blocks of `customSize` bytes.
+/// The common block is written before each custom block and at the end.
+pub const PEF_PK_DATA_REPEAT_BLOCK: u8 = 3;
+/// Like [`PEF_PK_DATA_REPEAT_BLOCK`], except that the common block is
+/// `count` zero bytes, and is not stored.
+pub const PEF_PK_DATA_REPEAT_ZERO: u8 = 4;
+/// Shift for the opcode in the first byte of an instruction.
+pub const PEF_PK_OPCODE_SHIFT: u8 = 5;
+/// Mask for the count in the first byte of an instruction.
+pub const PEF_PK_COUNT_MASK: u8 = 0x1f;
+/// Set in each byte of an argument except the last.
+pub const PEF_PK_ARG_MORE: u8 = 0x80;
+
+/// The header at the start of the loader section.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct LoaderInfoHeader {
+    /// The section containing the main symbol, or -1 if there is none.
+    pub main_section: I32Bytes<BE>,
+    /// The offset of the main symbol in its section.
+    pub main_offset: U32Bytes<BE>,
+    /// The section containing the initialization routine, or -1 if there is none.
+    pub init_section: I32Bytes<BE>,
+    /// The offset of the initialization routine in its section.
+    pub init_offset: U32Bytes<BE>,
+    /// The section containing the termination routine, or -1 if there is none.
+    pub term_section: I32Bytes<BE>,
+    /// The offset of the termination routine in its section.
+    pub term_offset: U32Bytes<BE>,
+    /// The number of entries in the imported library table.
+    pub imported_library_count: U32Bytes<BE>,
+    /// The number of entries in the imported symbol table.
+    pub total_imported_symbol_count: U32Bytes<BE>,
+    /// The number of relocation headers.
+    pub reloc_section_count: U32Bytes<BE>,
+    /// The offset of the relocation instructions from the start of the
+    /// loader section.
+    pub reloc_instr_offset: U32Bytes<BE>,
+    /// The offset of the loader string table from the start of the
+    /// loader section.
+    pub loader_strings_offset: U32Bytes<BE>,
+    /// The offset of the export hash table from the start of the
+    /// loader section.
+    pub export_hash_offset: U32Bytes<BE>,
+    /// The number of entries in the export hash table, as a power of 2.
+    pub export_hash_table_power: U32Bytes<BE>,
+    /// The number of exported symbols.
+    pub exported_symbol_count: U32Bytes<BE>,
+}
+
+/// An entry in the imported library table.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ImportedLibrary {
+    /// The offset of the library name in the loader string table.
+    pub name_offset: U32Bytes<BE>,
+    /// The oldest compatible implementation version.
+    pub old_imp_version: U32Bytes<BE>,
+    /// The version that the fragment was linked against.
+    pub current_version: U32Bytes<BE>,
+    /// The number of symbols imported from this library.
+    pub imported_symbol_count: U32Bytes<BE>,
+    /// The index of the first symbol in the imported symbol table.
+    pub first_imported_symbol: U32Bytes<BE>,
+    /// A combination of the `PEF_*_LIB` flags.
+    pub options: u8,
+    /// Reserved.
+    pub reserved_a: u8,
+    /// Reserved.
+    pub reserved_b: U16Bytes<BE>,
+}
+
+// Values for `ImportedLibrary::options`.
+/// The library must be initialized before the client.
+pub const PEF_INIT_LIB_BEFORE: u8 = 0x80;
+/// The library is weakly imported.
+pub const PEF_WEAK_IMPORT_LIB: u8 = 0x40;
+
+/// An entry in the imported symbol table.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ImportedSymbol {
+    /// The symbol class in the high 8 bits, and the offset of the name in the
+    /// loader string table in the low 24 bits.
+    pub class_and_name: U32Bytes<BE>,
+}
+
+impl ImportedSymbol {
+    /// Return the symbol class, including the flags.
+    pub fn symbol_class(&self) -> u8 {
+        (self.class_and_name.get(BE) >> 24) as u8
+    }
+
+    /// Return the offset of the name in the loader string table.
+    pub fn name_offset(&self) -> u32 {
+        self.class_and_name.get(BE) & 0x00ff_ffff
+    }
+}
+
+// Values for the symbol class of `ImportedSymbol` and `ExportedSymbol`.
+/// A code address.
+pub const PEF_CODE_SYMBOL: u8 = 0x00;
+/// A data address.
+pub const PEF_DATA_SYMBOL: u8 = 0x01;
+/// A transition vector, which is the standard function pointer.
+pub const PEF_TVECT_SYMBOL: u8 = 0x02;
+/// A TOC symbol.
+pub const PEF_TOC_SYMBOL: u8 = 0x03;
+/// A linker-inserted glue symbol.
+pub const PEF_GLUE_SYMBOL: u8 = 0x04;
+/// An undefined symbol.
+pub const PEF_UNDEFINED_SYMBOL: u8 = 0x0f;
+/// Mask for the class of a symbol class.
+pub const PEF_SYMBOL_CLASS_MASK: u8 = 0x0f;
+/// Flag for a weakly imported symbol.
+pub const PEF_WEAK_IMPORT_SYMBOL: u8 = 0x80;
+
+/// A relocation header.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct LoaderRelocationHeader {
+    /// The index of the section that the relocations apply to.
+    pub section_index: U16Bytes<BE>,
+    /// Reserved.
+    pub reserved_a: U16Bytes<BE>,
+    /// The number of 16-bit relocation instruction words.
+    pub reloc_count: U32Bytes<BE>,
+    /// The offset of the first relocation instruction from the start of the
+    /// relocation instructions.
+    pub first_reloc_offset: U32Bytes<BE>,
+}
+
+/// An entry in the export key table.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ExportedSymbolKey {
+    /// The length of the name in the high 16 bits, and the hash of the name
+    /// in the low 16 bits.
+    pub hash_word: U32Bytes<BE>,
+}
+
+impl ExportedSymbolKey {
+    /// Return the length of the symbol name.
+    pub fn name_length(&self) -> u16 {
+        (self.hash_word.get(BE) >> 16) as u16
+    }
+}
+
+/// An entry in the exported symbol table.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ExportedSymbol {
+    /// The symbol class in the high 8 bits, and the offset of the name in the
+    /// loader string table in the low 24 bits.
+    ///
+    /// The name is not null terminated. Its length is stored in the
+    /// corresponding [`ExportedSymbolKey`].
+    pub class_and_name: U32Bytes<BE>,
+    /// The offset of the symbol in its section, the absolute value of the
+    /// symbol, or the index of a re-exported imported symbol.
+    pub symbol_value: U32Bytes<BE>,
+    /// The section containing the symbol, or one of the `PEF_*_EXPORT`
+    /// constants.
+    pub section_index: I16Bytes<BE>,
+}
+
+impl ExportedSymbol {
+    /// Return the symbol class, including the flags.
+    pub fn symbol_class(&self) -> u8 {
+        (self.class_and_name.get(BE) >> 24) as u8
+    }
+
+    /// Return the offset of the name in the loader string table.
+    pub fn name_offset(&self) -> u32 {
+        self.class_and_name.get(BE) & 0x00ff_ffff
+    }
+}
+
+// Values for `ExportedSymbol::section_index`.
+/// The symbol value is absolute.
+pub const PEF_ABSOLUTE_EXPORT: i16 = -2;
+/// The symbol value is the index of an imported symbol that is re-exported.
+pub const PEF_REEXPORTED_IMPORT: i16 = -3;
+
+unsafe_impl_pod!(
+    ContainerHeader,
+    SectionHeader,
+    LoaderInfoHeader,
+    ImportedLibrary,
+    ImportedSymbol,
+    LoaderRelocationHeader,
+    ExportedSymbolKey,
+    ExportedSymbol,
+);
@@ -0,0 +1,166 @@
+//! PharLap 386|DOS-Extender executable definitions.
+//!
+//! These definitions are independent of read/write support, although we do implement
+//! some traits useful for those.
+//!
+//! PharLap executables (`.EXP` files) come in two forms. The old-style format
+//! uses [`MpHeader`], which is similar to the MS-DOS executable header. The
+//! new-style format uses [`P3Header`] for flat model programs. Either header
+//! may be at the start of the file, or may follow an MS-DOS stub program when
+//! the executable has been bound with the extender.
+//!
+//! All offsets in the headers are relative to the start of the PharLap header.
+//! In both formats, each entry in the relocation table is a 32-bit offset in
+//! the load image of a 16-bit segment selector.
+
+#![allow(missing_docs)]
+
+use crate::endian::{LittleEndian as LE, U16Bytes, U32Bytes};
+use crate::pod::Pod;
+
+/// The signature of an old-style header, "MP".
+pub const EXP_MP_SIGNATURE: u16 = 0x504D;
+/// The signature of a new-style header for a multisegmented program, "P2".
+pub const EXP_P2_SIGNATURE: u16 = 0x3250;
+/// The signature of a new-style header for a flat model program, "P3".
+pub const EXP_P3_SIGNATURE: u16 = 0x3350;
+
+/// The header of an old-style `.EXP` file.
+///
+/// The load image follows the header, and is loaded at offset 0.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct MpHeader {
+    /// The signature, [`EXP_MP_SIGNATURE`].
+    pub signature: U16Bytes<LE>,
+    /// The number of bytes in the last 512-byte page of the file, or 0 if
+    /// the last page is full.
+    pub last_page_size: U16Bytes<LE>,
+    /// The number of 512-byte pages in the file, including the header.
+    pub page_count: U16Bytes<LE>,
+    /// The number of entries in the relocation table.
+    pub relocation_count: U16Bytes<LE>,
+    /// The size of the header in paragraphs, including the relocation table.
+    pub header_size: U16Bytes<LE>,
+    /// The minimum number of 4K pages to allocate after the load image.
+    pub min_extra: U16Bytes<LE>,
+    /// The maximum number of 4K pages to allocate after the load image.
+    pub max_extra: U16Bytes<LE>,
+    /// The initial ESP.
+    pub initial_esp: U32Bytes<LE>,
+    /// The word checksum of the file.
+    pub checksum: U16Bytes<LE>,
+    /// The initial EIP.
+    pub initial_eip: U32Bytes<LE>,
+    /// The offset of the relocation table.
+    pub relocation_offset: U16Bytes<LE>,
+    /// The overlay number, which is 0 for the main program.
+    pub overlay: U16Bytes<LE>,
+    /// Reserved, always 1.
+    pub reserved: U16Bytes<LE>,
+}
+
+/// The header of a new-style `.EXP` file.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct P3Header {
+    /// The signature, [`EXP_P3_SIGNATURE`] or [`EXP_P2_SIGNATURE`].
+    pub signature: U16Bytes<LE>,
+    /// The header level. See the `P3_LEVEL_*` constants.
+    pub level: U16Bytes<LE>,
+    /// The size of the header.
+    pub header_size: U16Bytes<LE>,
+    /// The size of the file.
+    pub file_size: U32Bytes<LE>,
+    /// The word checksum of the file.
+    pub checksum: U16Bytes<LE>,
+    /// The offset of the run-time parameters.
+    pub runtime_params_offset: U32Bytes<LE>,
+    /// The size of the run-time parameters.
+    pub runtime_params_size: U32Bytes<LE>,
+    /// The offset of the relocation table.
+    pub relocation_offset: U32Bytes<LE>,
+    /// The size in bytes of the relocation table.
+    pub relocation_size: U32Bytes<LE>,
+    /// The offset of the segment information table.
+    pub segment_info_offset: U32Bytes<LE>,
+    /// The size in bytes of the segment information table.
+    pub segment_info_size: U32Bytes<LE>,
+    /// The size of an entry in the segment information table.
+    pub segment_info_entry_size: U16Bytes<LE>,
+    /// The offset of the load image.
+    pub image_offset: U32Bytes<LE>,
+    /// The size of the load image in the file.
+    pub image_size: U32Bytes<LE>,
+    /// The offset of the symbol table.
+    pub symbol_table_offset: U32Bytes<LE>,
+    /// The size of the symbol table.
+    pub symbol_table_size: U32Bytes<LE>,
+    /// The offset of the global descriptor table.
+    pub gdt_offset: U32Bytes<LE>,
+    /// The size of the global descriptor table.
+    pub gdt_size: U32Bytes<LE>,
+    /// The offset of the local descriptor table.
+    pub ldt_offset: U32Bytes<LE>,
+    /// The size of the local descriptor table.
+    pub ldt_size: U32Bytes<LE>,
+    /// The offset of the interrupt descriptor table.
+    pub idt_offset: U32Bytes<LE>,
+    /// The size of the interrupt descriptor table.
+    pub idt_size: U32Bytes<LE>,
+    /// The offset of the task state segment.
+    pub tss_offset: U32Bytes<LE>,
+    /// The size of the task state segment.
+    pub tss_size: U32Bytes<LE>,
+    /// The minimum number of extra bytes to allocate after the load image.
+    pub min_extra: U32Bytes<LE>,
+    /// The maximum number of extra bytes to allocate after the load image.
+    pub max_extra: U32Bytes<LE>,
+    /// The offset in the program segment that the load image is loaded at.
+    pub base_load_offset: U32Bytes<LE>,
+    /// The initial ESP.
+    pub initial_esp: U32Bytes<LE>,
+    /// The initial SS.
+    pub initial_ss: U16Bytes<LE>,
+    /// The initial EIP.
+    pub initial_eip: U32Bytes<LE>,
+    /// The initial CS.
+    pub initial_cs: U16Bytes<LE>,
+    /// The initial LDT selector.
+    pub initial_ldt: U16Bytes<LE>,
+    /// The initial TSS selector.
+    pub initial_tss: U16Bytes<LE>,
+    /// The load flags. See the `P3_FLAG_*` constants.
+    pub flags: U16Bytes<LE>,
+    /// The size of the load image in memory.
+    pub memory_size: U32Bytes<LE>,
+    /// The 32-bit checksum of the file.
+    pub checksum32: U32Bytes<LE>,
+    /// The size of the stack.
+    pub stack_size: U32Bytes<LE>,
+    /// Reserved.
+    pub reserved: [u8; 0x100],
+}
+
+// Values for `P3Header::level`.
+
+/// A flat model program.
+pub const P3_LEVEL_FLAT: u16 = 1;
+/// A multisegmented program.
+pub const P3_LEVEL_MULTISEGMENT: u16 = 2;
+
+// Values for `P3Header::flags`.
+
+/// The load image is packed.
+///
+/// The packed image is a sequence of runs. Each run starts with a 16-bit
+/// count. If [`P3_PACK_REPEAT`] is clear, the count is the number of bytes of
+/// data that follow. Otherwise, the count (with [`P3_PACK_REPEAT`] masked off)
+/// is the number of times to repeat a string, and is followed by an 8-bit
+/// string length and the string.
+pub const P3_FLAG_PACKED: u16 = 0x0001;
+
+/// The flag in a packed run count for a repeated string.
+pub const P3_PACK_REPEAT: u16 = 0x8000;
+
+unsafe_impl_pod!(MpHeader, P3Header);
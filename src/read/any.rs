@@ -1,23 +1,64 @@
+use alloc::borrow::Cow;
+#[cfg(any(
+    feature = "omf",
+    feature = "ne",
+    feature = "lx",
+    feature = "pharlap",
+    feature = "aout",
+    feature = "hunk",
+    feature = "gemdos",
+    feature = "pef",
+    feature = "goff",
+    feature = "ihex",
+    feature = "srec",
+    feature = "ticoff",
+    feature = "xbe"
+))]
+use alloc::boxed::Box;
 use alloc::fmt;
 use alloc::vec::Vec;
 use core::marker::PhantomData;
 
 #[allow(unused_imports)] // Unused for Wasm
 use crate::endian::Endianness;
+#[cfg(feature = "aout")]
+use crate::read::aout;
 #[cfg(feature = "coff")]
 use crate::read::coff;
 #[cfg(feature = "elf")]
 use crate::read::elf;
+#[cfg(feature = "gemdos")]
+use crate::read::gemdos;
+#[cfg(feature = "goff")]
+use crate::read::goff;
+#[cfg(feature = "hunk")]
+use crate::read::hunk;
+#[cfg(feature = "ihex")]
+use crate::read::ihex;
+#[cfg(feature = "lx")]
+use crate::read::lx;
 #[cfg(feature = "macho")]
 use crate::read::macho;
+#[cfg(feature = "ne")]
+use crate::read::ne;
+#[cfg(feature = "omf")]
+use crate::read::omf;
 #[cfg(feature = "pe")]
 use crate::read::pe;
+#[cfg(feature = "pef")]
+use crate::read::pef;
+#[cfg(feature = "pharlap")]
+use crate::read::pharlap;
+#[cfg(feature = "srec")]
+use crate::read::srec;
+#[cfg(feature = "ticoff")]
+use crate::read::ticoff;
 #[cfg(feature = "wasm")]
 use crate::read::wasm;
+#[cfg(feature = "xbe")]
+use crate::read::xbe;
 #[cfg(feature = "xcoff")]
 use crate::read::xcoff;
-#[cfg(feature = "omf")]
-use crate::read::omf;
 use crate::read::{
     self, Architecture, BinaryFormat, CodeView, ComdatKind, CompressedData, CompressedFileRange,
     Error, Export, FileFlags, FileKind, Import, Object, ObjectComdat, ObjectKind, ObjectMap,
@@ -54,6 +95,32 @@ macro_rules! with_inner {
             $enum::Xcoff32(ref $var) => $body,
             #[cfg(feature = "xcoff")]
             $enum::Xcoff64(ref $var) => $body,
+            #[cfg(feature = "omf")]
+            $enum::Omf(ref $var) => $body,
+            #[cfg(feature = "ne")]
+            $enum::Ne(ref $var) => $body,
+            #[cfg(feature = "lx")]
+            $enum::Lx(ref $var) => $body,
+            #[cfg(feature = "pharlap")]
+            $enum::PharLap(ref $var) => $body,
+            #[cfg(feature = "aout")]
+            $enum::Aout(ref $var) => $body,
+            #[cfg(feature = "hunk")]
+            $enum::Hunk(ref $var) => $body,
+            #[cfg(feature = "gemdos")]
+            $enum::Gemdos(ref $var) => $body,
+            #[cfg(feature = "pef")]
+            $enum::Pef(ref $var) => $body,
+            #[cfg(feature = "goff")]
+            $enum::Goff(ref $var) => $body,
+            #[cfg(feature = "ihex")]
+            $enum::Ihex(ref $var) => $body,
+            #[cfg(feature = "srec")]
+            $enum::Srec(ref $var) => $body,
+            #[cfg(feature = "ticoff")]
+            $enum::TiCoff(ref $var) => $body,
+            #[cfg(feature = "xbe")]
+            $enum::Xbe(ref $var) => $body,
         }
     };
 }
@@ -83,6 +150,32 @@ macro_rules! with_inner_mut {
             $enum::Xcoff32(ref mut $var) => $body,
             #[cfg(feature = "xcoff")]
             $enum::Xcoff64(ref mut $var) => $body,
+            #[cfg(feature = "omf")]
+            $enum::Omf(ref mut $var) => $body,
+            #[cfg(feature = "ne")]
+            $enum::Ne(ref mut $var) => $body,
+            #[cfg(feature = "lx")]
+            $enum::Lx(ref mut $var) => $body,
+            #[cfg(feature = "pharlap")]
+            $enum::PharLap(ref mut $var) => $body,
+            #[cfg(feature = "aout")]
+            $enum::Aout(ref mut $var) => $body,
+            #[cfg(feature = "hunk")]
+            $enum::Hunk(ref mut $var) => $body,
+            #[cfg(feature = "gemdos")]
+            $enum::Gemdos(ref mut $var) => $body,
+            #[cfg(feature = "pef")]
+            $enum::Pef(ref mut $var) => $body,
+            #[cfg(feature = "goff")]
+            $enum::Goff(ref mut $var) => $body,
+            #[cfg(feature = "ihex")]
+            $enum::Ihex(ref mut $var) => $body,
+            #[cfg(feature = "srec")]
+            $enum::Srec(ref mut $var) => $body,
+            #[cfg(feature = "ticoff")]
+            $enum::TiCoff(ref mut $var) => $body,
+            #[cfg(feature = "xbe")]
+            $enum::Xbe(ref mut $var) => $body,
         }
     };
 }
@@ -113,6 +206,32 @@ macro_rules! map_inner {
             $from::Xcoff32(ref $var) => $to::Xcoff32($body),
             #[cfg(feature = "xcoff")]
             $from::Xcoff64(ref $var) => $to::Xcoff64($body),
+            #[cfg(feature = "omf")]
+            $from::Omf(ref $var) => $to::Omf($body),
+            #[cfg(feature = "ne")]
+            $from::Ne(ref $var) => $to::Ne($body),
+            #[cfg(feature = "lx")]
+            $from::Lx(ref $var) => $to::Lx($body),
+            #[cfg(feature = "pharlap")]
+            $from::PharLap(ref $var) => $to::PharLap($body),
+            #[cfg(feature = "aout")]
+            $from::Aout(ref $var) => $to::Aout($body),
+            #[cfg(feature = "hunk")]
+            $from::Hunk(ref $var) => $to::Hunk($body),
+            #[cfg(feature = "gemdos")]
+            $from::Gemdos(ref $var) => $to::Gemdos($body),
+            #[cfg(feature = "pef")]
+            $from::Pef(ref $var) => $to::Pef($body),
+            #[cfg(feature = "goff")]
+            $from::Goff(ref $var) => $to::Goff($body),
+            #[cfg(feature = "ihex")]
+            $from::Ihex(ref $var) => $to::Ihex($body),
+            #[cfg(feature = "srec")]
+            $from::Srec(ref $var) => $to::Srec($body),
+            #[cfg(feature = "ticoff")]
+            $from::TiCoff(ref $var) => $to::TiCoff($body),
+            #[cfg(feature = "xbe")]
+            $from::Xbe(ref $var) => $to::Xbe($body),
         }
     };
 }
@@ -143,6 +262,32 @@ macro_rules! map_inner_option {
             $from::Xcoff32(ref $var) => $body.map($to::Xcoff32),
             #[cfg(feature = "xcoff")]
             $from::Xcoff64(ref $var) => $body.map($to::Xcoff64),
+            #[cfg(feature = "omf")]
+            $from::Omf(ref $var) => $body.map($to::Omf),
+            #[cfg(feature = "ne")]
+            $from::Ne(ref $var) => $body.map($to::Ne),
+            #[cfg(feature = "lx")]
+            $from::Lx(ref $var) => $body.map($to::Lx),
+            #[cfg(feature = "pharlap")]
+            $from::PharLap(ref $var) => $body.map($to::PharLap),
+            #[cfg(feature = "aout")]
+            $from::Aout(ref $var) => $body.map($to::Aout),
+            #[cfg(feature = "hunk")]
+            $from::Hunk(ref $var) => $body.map($to::Hunk),
+            #[cfg(feature = "gemdos")]
+            $from::Gemdos(ref $var) => $body.map($to::Gemdos),
+            #[cfg(feature = "pef")]
+            $from::Pef(ref $var) => $body.map($to::Pef),
+            #[cfg(feature = "goff")]
+            $from::Goff(ref $var) => $body.map($to::Goff),
+            #[cfg(feature = "ihex")]
+            $from::Ihex(ref $var) => $body.map($to::Ihex),
+            #[cfg(feature = "srec")]
+            $from::Srec(ref $var) => $body.map($to::Srec),
+            #[cfg(feature = "ticoff")]
+            $from::TiCoff(ref $var) => $body.map($to::TiCoff),
+            #[cfg(feature = "xbe")]
+            $from::Xbe(ref $var) => $body.map($to::Xbe),
         }
     };
 }
@@ -172,6 +317,32 @@ macro_rules! map_inner_option_mut {
             $from::Xcoff32(ref mut $var) => $body.map($to::Xcoff32),
             #[cfg(feature = "xcoff")]
             $from::Xcoff64(ref mut $var) => $body.map($to::Xcoff64),
+            #[cfg(feature = "omf")]
+            $from::Omf(ref mut $var) => $body.map($to::Omf),
+            #[cfg(feature = "ne")]
+            $from::Ne(ref mut $var) => $body.map($to::Ne),
+            #[cfg(feature = "lx")]
+            $from::Lx(ref mut $var) => $body.map($to::Lx),
+            #[cfg(feature = "pharlap")]
+            $from::PharLap(ref mut $var) => $body.map($to::PharLap),
+            #[cfg(feature = "aout")]
+            $from::Aout(ref mut $var) => $body.map($to::Aout),
+            #[cfg(feature = "hunk")]
+            $from::Hunk(ref mut $var) => $body.map($to::Hunk),
+            #[cfg(feature = "gemdos")]
+            $from::Gemdos(ref mut $var) => $body.map($to::Gemdos),
+            #[cfg(feature = "pef")]
+            $from::Pef(ref mut $var) => $body.map($to::Pef),
+            #[cfg(feature = "goff")]
+            $from::Goff(ref mut $var) => $body.map($to::Goff),
+            #[cfg(feature = "ihex")]
+            $from::Ihex(ref mut $var) => $body.map($to::Ihex),
+            #[cfg(feature = "srec")]
+            $from::Srec(ref mut $var) => $body.map($to::Srec),
+            #[cfg(feature = "ticoff")]
+            $from::TiCoff(ref mut $var) => $body.map($to::TiCoff),
+            #[cfg(feature = "xbe")]
+            $from::Xbe(ref mut $var) => $body.map($to::Xbe),
         }
     };
 }
@@ -202,6 +373,32 @@ macro_rules! next_inner {
             $from::Xcoff32(ref mut iter) => iter.next().map($to::Xcoff32),
             #[cfg(feature = "xcoff")]
             $from::Xcoff64(ref mut iter) => iter.next().map($to::Xcoff64),
+            #[cfg(feature = "omf")]
+            $from::Omf(ref mut iter) => iter.next().map($to::Omf),
+            #[cfg(feature = "ne")]
+            $from::Ne(ref mut iter) => iter.next().map($to::Ne),
+            #[cfg(feature = "lx")]
+            $from::Lx(ref mut iter) => iter.next().map($to::Lx),
+            #[cfg(feature = "pharlap")]
+            $from::PharLap(ref mut iter) => iter.next().map($to::PharLap),
+            #[cfg(feature = "aout")]
+            $from::Aout(ref mut iter) => iter.next().map($to::Aout),
+            #[cfg(feature = "hunk")]
+            $from::Hunk(ref mut iter) => iter.next().map($to::Hunk),
+            #[cfg(feature = "gemdos")]
+            $from::Gemdos(ref mut iter) => iter.next().map($to::Gemdos),
+            #[cfg(feature = "pef")]
+            $from::Pef(ref mut iter) => iter.next().map($to::Pef),
+            #[cfg(feature = "goff")]
+            $from::Goff(ref mut iter) => iter.next().map($to::Goff),
+            #[cfg(feature = "ihex")]
+            $from::Ihex(ref mut iter) => iter.next().map($to::Ihex),
+            #[cfg(feature = "srec")]
+            $from::Srec(ref mut iter) => iter.next().map($to::Srec),
+            #[cfg(feature = "ticoff")]
+            $from::TiCoff(ref mut iter) => iter.next().map($to::TiCoff),
+            #[cfg(feature = "xbe")]
+            $from::Xbe(ref mut iter) => iter.next().map($to::Xbe),
         }
     };
 }
@@ -235,9 +432,33 @@ pub enum File<'data, R: ReadRef<'data> = &'data [u8]> {
     Xcoff32(xcoff::XcoffFile32<'data, R>),
     #[cfg(feature = "xcoff")]
     Xcoff64(xcoff::XcoffFile64<'data, R>),
+    // Boxed because OMF files hold many more parsed tables than the other formats.
     #[cfg(feature = "omf")]
-    Omf(omf::OmfFile<'data, R>),
-
+    Omf(Box<omf::OmfFile<'data, R>>),
+    #[cfg(feature = "ne")]
+    Ne(Box<ne::NeFile<'data, R>>),
+    #[cfg(feature = "lx")]
+    Lx(Box<lx::LxFile<'data, R>>),
+    #[cfg(feature = "pharlap")]
+    PharLap(Box<pharlap::PharLapFile<'data, R>>),
+    #[cfg(feature = "aout")]
+    Aout(Box<aout::AoutFile<'data, R>>),
+    #[cfg(feature = "hunk")]
+    Hunk(Box<hunk::HunkFile<'data, R>>),
+    #[cfg(feature = "gemdos")]
+    Gemdos(Box<gemdos::GemdosFile<'data, R>>),
+    #[cfg(feature = "pef")]
+    Pef(Box<pef::PefFile<'data, R>>),
+    #[cfg(feature = "goff")]
+    Goff(Box<goff::GoffFile<'data, R>>),
+    #[cfg(feature = "ihex")]
+    Ihex(Box<ihex::IhexFile<'data, R>>),
+    #[cfg(feature = "srec")]
+    Srec(Box<srec::SrecFile<'data, R>>),
+    #[cfg(feature = "ticoff")]
+    TiCoff(Box<ticoff::TiCoffFile<'data, R>>),
+    #[cfg(feature = "xbe")]
+    Xbe(Box<xbe::XbeFile<'data, R>>),
 }
 
 impl<'data, R: ReadRef<'data>> File<'data, R> {
@@ -267,7 +488,33 @@ impl<'data, R: ReadRef<'data>> File<'data, R> {
             #[cfg(feature = "xcoff")]
             FileKind::Xcoff64 => File::Xcoff64(xcoff::XcoffFile64::parse(data)?),
             #[cfg(feature = "omf")]
-            FileKind::Omf => File::Omf(omf::OmfFile::parse(data)?),
+            FileKind::Omf => File::Omf(Box::new(omf::OmfFile::parse(data)?)),
+            #[cfg(feature = "omf")]
+            FileKind::OmfLibrary => return Err(Error("OMF library is not an object file")),
+            #[cfg(feature = "ne")]
+            FileKind::Ne => File::Ne(Box::new(ne::NeFile::parse(data)?)),
+            #[cfg(feature = "lx")]
+            FileKind::Lx => File::Lx(Box::new(lx::LxFile::parse(data)?)),
+            #[cfg(feature = "pharlap")]
+            FileKind::PharLap => File::PharLap(Box::new(pharlap::PharLapFile::parse(data)?)),
+            #[cfg(feature = "aout")]
+            FileKind::Aout => File::Aout(Box::new(aout::AoutFile::parse(data)?)),
+            #[cfg(feature = "hunk")]
+            FileKind::Hunk => File::Hunk(Box::new(hunk::HunkFile::parse(data)?)),
+            #[cfg(feature = "gemdos")]
+            FileKind::Gemdos => File::Gemdos(Box::new(gemdos::GemdosFile::parse(data)?)),
+            #[cfg(feature = "pef")]
+            FileKind::Pef => File::Pef(Box::new(pef::PefFile::parse(data)?)),
+            #[cfg(feature = "goff")]
+            FileKind::Goff => File::Goff(Box::new(goff::GoffFile::parse(data)?)),
+            #[cfg(feature = "ihex")]
+            FileKind::Ihex => File::Ihex(Box::new(ihex::IhexFile::parse(data)?)),
+            #[cfg(feature = "srec")]
+            FileKind::Srec => File::Srec(Box::new(srec::SrecFile::parse(data)?)),
+            #[cfg(feature = "ticoff")]
+            FileKind::TiCoff => File::TiCoff(Box::new(ticoff::TiCoffFile::parse(data)?)),
+            #[cfg(feature = "xbe")]
+            FileKind::Xbe => File::Xbe(Box::new(xbe::XbeFile::parse(data)?)),
             #[allow(unreachable_patterns)]
             _ => return Err(Error("Unsupported file format")),
         })
@@ -304,6 +551,32 @@ impl<'data, R: ReadRef<'data>> File<'data, R> {
             File::Wasm(_) => BinaryFormat::Wasm,
             #[cfg(feature = "xcoff")]
             File::Xcoff32(_) | File::Xcoff64(_) => BinaryFormat::Xcoff,
+            #[cfg(feature = "omf")]
+            File::Omf(_) => BinaryFormat::Omf,
+            #[cfg(feature = "ne")]
+            File::Ne(_) => BinaryFormat::Ne,
+            #[cfg(feature = "lx")]
+            File::Lx(_) => BinaryFormat::Lx,
+            #[cfg(feature = "pharlap")]
+            File::PharLap(_) => BinaryFormat::PharLap,
+            #[cfg(feature = "aout")]
+            File::Aout(_) => BinaryFormat::Aout,
+            #[cfg(feature = "hunk")]
+            File::Hunk(_) => BinaryFormat::Hunk,
+            #[cfg(feature = "gemdos")]
+            File::Gemdos(_) => BinaryFormat::Gemdos,
+            #[cfg(feature = "pef")]
+            File::Pef(_) => BinaryFormat::Pef,
+            #[cfg(feature = "goff")]
+            File::Goff(_) => BinaryFormat::Goff,
+            #[cfg(feature = "ihex")]
+            File::Ihex(_) => BinaryFormat::Ihex,
+            #[cfg(feature = "srec")]
+            File::Srec(_) => BinaryFormat::Srec,
+            #[cfg(feature = "ticoff")]
+            File::TiCoff(_) => BinaryFormat::TiCoff,
+            #[cfg(feature = "xbe")]
+            File::Xbe(_) => BinaryFormat::Xbe,
         }
     }
 }
@@ -564,6 +837,32 @@ enum SegmentIteratorInternal<'data, 'file, R: ReadRef<'data>> {
     Xcoff32(xcoff::XcoffSegmentIterator32<'data, 'file, R>),
     #[cfg(feature = "xcoff")]
     Xcoff64(xcoff::XcoffSegmentIterator64<'data, 'file, R>),
+    #[cfg(feature = "omf")]
+    Omf(omf::OmfSegmentIterator<'data, 'file, R>),
+    #[cfg(feature = "ne")]
+    Ne(ne::NeSegmentIterator<'data, 'file, R>),
+    #[cfg(feature = "lx")]
+    Lx(lx::LxSegmentIterator<'data, 'file, R>),
+    #[cfg(feature = "pharlap")]
+    PharLap(pharlap::PharLapSegmentIterator<'data, 'file, R>),
+    #[cfg(feature = "aout")]
+    Aout(aout::AoutSegmentIterator<'data, 'file, R>),
+    #[cfg(feature = "hunk")]
+    Hunk(hunk::HunkSegmentIterator<'data, 'file, R>),
+    #[cfg(feature = "gemdos")]
+    Gemdos(gemdos::GemdosSegmentIterator<'data, 'file, R>),
+    #[cfg(feature = "pef")]
+    Pef(pef::PefSegmentIterator<'data, 'file, R>),
+    #[cfg(feature = "goff")]
+    Goff(goff::GoffSegmentIterator<'data, 'file, R>),
+    #[cfg(feature = "ihex")]
+    Ihex(ihex::IhexSegmentIterator<'data, 'file, R>),
+    #[cfg(feature = "srec")]
+    Srec(srec::SrecSegmentIterator<'data, 'file, R>),
+    #[cfg(feature = "ticoff")]
+    TiCoff(ticoff::TiCoffSegmentIterator<'data, 'file, R>),
+    #[cfg(feature = "xbe")]
+    Xbe(xbe::XbeSegmentIterator<'data, 'file, R>),
 }
 
 impl<'data, 'file, R: ReadRef<'data>> Iterator for SegmentIterator<'data, 'file, R> {
@@ -606,6 +905,32 @@ enum SegmentInternal<'data, 'file, R: ReadRef<'data>> {
     Xcoff32(xcoff::XcoffSegment32<'data, 'file, R>),
     #[cfg(feature = "xcoff")]
     Xcoff64(xcoff::XcoffSegment64<'data, 'file, R>),
+    #[cfg(feature = "omf")]
+    Omf(omf::OmfSegment<'data, 'file, R>),
+    #[cfg(feature = "ne")]
+    Ne(ne::NeSegment<'data, 'file, R>),
+    #[cfg(feature = "lx")]
+    Lx(lx::LxSegment<'data, 'file, R>),
+    #[cfg(feature = "pharlap")]
+    PharLap(pharlap::PharLapSegment<'data, 'file, R>),
+    #[cfg(feature = "aout")]
+    Aout(aout::AoutSegment<'data, 'file, R>),
+    #[cfg(feature = "hunk")]
+    Hunk(hunk::HunkSegment<'data, 'file, R>),
+    #[cfg(feature = "gemdos")]
+    Gemdos(gemdos::GemdosSegment<'data, 'file, R>),
+    #[cfg(feature = "pef")]
+    Pef(pef::PefSegment<'data, 'file, R>),
+    #[cfg(feature = "goff")]
+    Goff(goff::GoffSegment<'data, 'file, R>),
+    #[cfg(feature = "ihex")]
+    Ihex(ihex::IhexSegment<'data, 'file, R>),
+    #[cfg(feature = "srec")]
+    Srec(srec::SrecSegment<'data, 'file, R>),
+    #[cfg(feature = "ticoff")]
+    TiCoff(ticoff::TiCoffSegment<'data, 'file, R>),
+    #[cfg(feature = "xbe")]
+    Xbe(xbe::XbeSegment<'data, 'file, R>),
 }
 
 impl<'data, 'file, R: ReadRef<'data>> fmt::Debug for Segment<'data, 'file, R> {
@@ -698,6 +1023,32 @@ enum SectionIteratorInternal<'data, 'file, R: ReadRef<'data>> {
     Xcoff32(xcoff::XcoffSectionIterator32<'data, 'file, R>),
     #[cfg(feature = "xcoff")]
     Xcoff64(xcoff::XcoffSectionIterator64<'data, 'file, R>),
+    #[cfg(feature = "omf")]
+    Omf(omf::OmfSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "ne")]
+    Ne(ne::NeSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "lx")]
+    Lx(lx::LxSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "pharlap")]
+    PharLap(pharlap::PharLapSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "aout")]
+    Aout(aout::AoutSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "hunk")]
+    Hunk(hunk::HunkSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "gemdos")]
+    Gemdos(gemdos::GemdosSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "pef")]
+    Pef(pef::PefSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "goff")]
+    Goff(goff::GoffSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "ihex")]
+    Ihex(ihex::IhexSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "srec")]
+    Srec(srec::SrecSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "ticoff")]
+    TiCoff(ticoff::TiCoffSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "xbe")]
+    Xbe(xbe::XbeSectionIterator<'data, 'file, R>),
 }
 
 impl<'data, 'file, R: ReadRef<'data>> Iterator for SectionIterator<'data, 'file, R> {
@@ -739,6 +1090,32 @@ enum SectionInternal<'data, 'file, R: ReadRef<'data>> {
     Xcoff32(xcoff::XcoffSection32<'data, 'file, R>),
     #[cfg(feature = "xcoff")]
     Xcoff64(xcoff::XcoffSection64<'data, 'file, R>),
+    #[cfg(feature = "omf")]
+    Omf(omf::OmfSection<'data, 'file, R>),
+    #[cfg(feature = "ne")]
+    Ne(ne::NeSection<'data, 'file, R>),
+    #[cfg(feature = "lx")]
+    Lx(lx::LxSection<'data, 'file, R>),
+    #[cfg(feature = "pharlap")]
+    PharLap(pharlap::PharLapSection<'data, 'file, R>),
+    #[cfg(feature = "aout")]
+    Aout(aout::AoutSection<'data, 'file, R>),
+    #[cfg(feature = "hunk")]
+    Hunk(hunk::HunkSection<'data, 'file, R>),
+    #[cfg(feature = "gemdos")]
+    Gemdos(gemdos::GemdosSection<'data, 'file, R>),
+    #[cfg(feature = "pef")]
+    Pef(pef::PefSection<'data, 'file, R>),
+    #[cfg(feature = "goff")]
+    Goff(goff::GoffSection<'data, 'file, R>),
+    #[cfg(feature = "ihex")]
+    Ihex(ihex::IhexSection<'data, 'file, R>),
+    #[cfg(feature = "srec")]
+    Srec(srec::SrecSection<'data, 'file, R>),
+    #[cfg(feature = "ticoff")]
+    TiCoff(ticoff::TiCoffSection<'data, 'file, R>),
+    #[cfg(feature = "xbe")]
+    Xbe(xbe::XbeSection<'data, 'file, R>),
 }
 
 impl<'data, 'file, R: ReadRef<'data>> fmt::Debug for Section<'data, 'file, R> {
@@ -805,6 +1182,10 @@ impl<'data, 'file, R: ReadRef<'data>> ObjectSection<'data> for Section<'data, 'f
         with_inner!(self.inner, SectionInternal, |x| x.compressed_data())
     }
 
+    fn uncompressed_data(&self) -> Result<Cow<'data, [u8]>> {
+        with_inner!(self.inner, SectionInternal, |x| x.uncompressed_data())
+    }
+
     fn name_bytes(&self) -> Result<&'data [u8]> {
         with_inner!(self.inner, SectionInternal, |x| x.name_bytes())
     }
@@ -875,6 +1256,32 @@ enum ComdatIteratorInternal<'data, 'file, R: ReadRef<'data>> {
     Xcoff32(xcoff::XcoffComdatIterator32<'data, 'file, R>),
     #[cfg(feature = "xcoff")]
     Xcoff64(xcoff::XcoffComdatIterator64<'data, 'file, R>),
+    #[cfg(feature = "omf")]
+    Omf(omf::OmfComdatIterator<'data, 'file, R>),
+    #[cfg(feature = "ne")]
+    Ne(ne::NeComdatIterator<'data, 'file, R>),
+    #[cfg(feature = "lx")]
+    Lx(lx::LxComdatIterator<'data, 'file, R>),
+    #[cfg(feature = "pharlap")]
+    PharLap(pharlap::PharLapComdatIterator<'data, 'file, R>),
+    #[cfg(feature = "aout")]
+    Aout(aout::AoutComdatIterator<'data, 'file, R>),
+    #[cfg(feature = "hunk")]
+    Hunk(hunk::HunkComdatIterator<'data, 'file, R>),
+    #[cfg(feature = "gemdos")]
+    Gemdos(gemdos::GemdosComdatIterator<'data, 'file, R>),
+    #[cfg(feature = "pef")]
+    Pef(pef::PefComdatIterator<'data, 'file, R>),
+    #[cfg(feature = "goff")]
+    Goff(goff::GoffComdatIterator<'data, 'file, R>),
+    #[cfg(feature = "ihex")]
+    Ihex(ihex::IhexComdatIterator<'data, 'file, R>),
+    #[cfg(feature = "srec")]
+    Srec(srec::SrecComdatIterator<'data, 'file, R>),
+    #[cfg(feature = "ticoff")]
+    TiCoff(ticoff::TiCoffComdatIterator<'data, 'file, R>),
+    #[cfg(feature = "xbe")]
+    Xbe(xbe::XbeComdatIterator<'data, 'file, R>),
 }
 
 impl<'data, 'file, R: ReadRef<'data>> Iterator for ComdatIterator<'data, 'file, R> {
@@ -916,6 +1323,32 @@ enum ComdatInternal<'data, 'file, R: ReadRef<'data>> {
     Xcoff32(xcoff::XcoffComdat32<'data, 'file, R>),
     #[cfg(feature = "xcoff")]
     Xcoff64(xcoff::XcoffComdat64<'data, 'file, R>),
+    #[cfg(feature = "omf")]
+    Omf(omf::OmfComdat<'data, 'file, R>),
+    #[cfg(feature = "ne")]
+    Ne(ne::NeComdat<'data, 'file, R>),
+    #[cfg(feature = "lx")]
+    Lx(lx::LxComdat<'data, 'file, R>),
+    #[cfg(feature = "pharlap")]
+    PharLap(pharlap::PharLapComdat<'data, 'file, R>),
+    #[cfg(feature = "aout")]
+    Aout(aout::AoutComdat<'data, 'file, R>),
+    #[cfg(feature = "hunk")]
+    Hunk(hunk::HunkComdat<'data, 'file, R>),
+    #[cfg(feature = "gemdos")]
+    Gemdos(gemdos::GemdosComdat<'data, 'file, R>),
+    #[cfg(feature = "pef")]
+    Pef(pef::PefComdat<'data, 'file, R>),
+    #[cfg(feature = "goff")]
+    Goff(goff::GoffComdat<'data, 'file, R>),
+    #[cfg(feature = "ihex")]
+    Ihex(ihex::IhexComdat<'data, 'file, R>),
+    #[cfg(feature = "srec")]
+    Srec(srec::SrecComdat<'data, 'file, R>),
+    #[cfg(feature = "ticoff")]
+    TiCoff(ticoff::TiCoffComdat<'data, 'file, R>),
+    #[cfg(feature = "xbe")]
+    Xbe(xbe::XbeComdat<'data, 'file, R>),
 }
 
 impl<'data, 'file, R: ReadRef<'data>> fmt::Debug for Comdat<'data, 'file, R> {
@@ -991,6 +1424,32 @@ enum ComdatSectionIteratorInternal<'data, 'file, R: ReadRef<'data>> {
     Xcoff32(xcoff::XcoffComdatSectionIterator32<'data, 'file, R>),
     #[cfg(feature = "xcoff")]
     Xcoff64(xcoff::XcoffComdatSectionIterator64<'data, 'file, R>),
+    #[cfg(feature = "omf")]
+    Omf(omf::OmfComdatSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "ne")]
+    Ne(ne::NeComdatSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "lx")]
+    Lx(lx::LxComdatSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "pharlap")]
+    PharLap(pharlap::PharLapComdatSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "aout")]
+    Aout(aout::AoutComdatSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "hunk")]
+    Hunk(hunk::HunkComdatSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "gemdos")]
+    Gemdos(gemdos::GemdosComdatSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "pef")]
+    Pef(pef::PefComdatSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "goff")]
+    Goff(goff::GoffComdatSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "ihex")]
+    Ihex(ihex::IhexComdatSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "srec")]
+    Srec(srec::SrecComdatSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "ticoff")]
+    TiCoff(ticoff::TiCoffComdatSectionIterator<'data, 'file, R>),
+    #[cfg(feature = "xbe")]
+    Xbe(xbe::XbeComdatSectionIterator<'data, 'file, R>),
 }
 
 impl<'data, 'file, R: ReadRef<'data>> Iterator for ComdatSectionIterator<'data, 'file, R> {
@@ -1059,6 +1518,32 @@ where
     Xcoff32((xcoff::XcoffSymbolTable32<'data, 'file, R>, PhantomData<R>)),
     #[cfg(feature = "xcoff")]
     Xcoff64((xcoff::XcoffSymbolTable64<'data, 'file, R>, PhantomData<R>)),
+    #[cfg(feature = "omf")]
+    Omf((omf::OmfSymbolTable<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "ne")]
+    Ne((ne::NeSymbolTable<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "lx")]
+    Lx((lx::LxSymbolTable<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "pharlap")]
+    PharLap((pharlap::PharLapSymbolTable<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "aout")]
+    Aout((aout::AoutSymbolTable<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "hunk")]
+    Hunk((hunk::HunkSymbolTable<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "gemdos")]
+    Gemdos((gemdos::GemdosSymbolTable<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "pef")]
+    Pef((pef::PefSymbolTable<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "goff")]
+    Goff((goff::GoffSymbolTable<'data, 'file, R>, PhantomData<R>)),
+    #[cfg(feature = "ihex")]
+    Ihex((ihex::IhexSymbolTable<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "srec")]
+    Srec((srec::SrecSymbolTable<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "ticoff")]
+    TiCoff((ticoff::TiCoffSymbolTable<'data, 'file, R>, PhantomData<R>)),
+    #[cfg(feature = "xbe")]
+    Xbe((xbe::XbeSymbolTable<'data, 'file>, PhantomData<R>)),
 }
 
 impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for SymbolTable<'data, 'file, R> {}
@@ -1153,6 +1638,37 @@ where
             PhantomData<R>,
         ),
     ),
+    #[cfg(feature = "omf")]
+    Omf((omf::OmfSymbolIterator<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "ne")]
+    Ne((ne::NeSymbolIterator<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "lx")]
+    Lx((lx::LxSymbolIterator<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "pharlap")]
+    PharLap((pharlap::PharLapSymbolIterator<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "aout")]
+    Aout((aout::AoutSymbolIterator<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "hunk")]
+    Hunk((hunk::HunkSymbolIterator<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "gemdos")]
+    Gemdos((gemdos::GemdosSymbolIterator<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "pef")]
+    Pef((pef::PefSymbolIterator<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "goff")]
+    Goff((goff::GoffSymbolIterator<'data, 'file, R>, PhantomData<R>)),
+    #[cfg(feature = "ihex")]
+    Ihex((ihex::IhexSymbolIterator<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "srec")]
+    Srec((srec::SrecSymbolIterator<'data, 'file>, PhantomData<R>)),
+    #[cfg(feature = "ticoff")]
+    TiCoff(
+        (
+            ticoff::TiCoffSymbolIterator<'data, 'file, R>,
+            PhantomData<R>,
+        ),
+    ),
+    #[cfg(feature = "xbe")]
+    Xbe((xbe::XbeSymbolIterator<'data, 'file>, PhantomData<R>)),
 }
 
 impl<'data, 'file, R: ReadRef<'data>> Iterator for SymbolIterator<'data, 'file, R> {
@@ -1222,6 +1738,32 @@ where
     Xcoff32((xcoff::XcoffSymbol32<'data, 'file, R>, PhantomData<R>)),
     #[cfg(feature = "xcoff")]
     Xcoff64((xcoff::XcoffSymbol64<'data, 'file, R>, PhantomData<R>)),
+    #[cfg(feature = "omf")]
+    Omf((omf::OmfSymbol<'data>, PhantomData<(&'file (), R)>)),
+    #[cfg(feature = "ne")]
+    Ne((ne::NeSymbol<'data>, PhantomData<(&'file (), R)>)),
+    #[cfg(feature = "lx")]
+    Lx((lx::LxSymbol<'data>, PhantomData<(&'file (), R)>)),
+    #[cfg(feature = "pharlap")]
+    PharLap((pharlap::PharLapSymbol<'data>, PhantomData<(&'file (), R)>)),
+    #[cfg(feature = "aout")]
+    Aout((aout::AoutSymbol<'data>, PhantomData<(&'file (), R)>)),
+    #[cfg(feature = "hunk")]
+    Hunk((hunk::HunkSymbol<'data>, PhantomData<(&'file (), R)>)),
+    #[cfg(feature = "gemdos")]
+    Gemdos((gemdos::GemdosSymbol<'data>, PhantomData<(&'file (), R)>)),
+    #[cfg(feature = "pef")]
+    Pef((pef::PefSymbol<'data>, PhantomData<(&'file (), R)>)),
+    #[cfg(feature = "goff")]
+    Goff((goff::GoffSymbol<'data, 'file, R>, PhantomData<R>)),
+    #[cfg(feature = "ihex")]
+    Ihex((ihex::IhexSymbol<'data>, PhantomData<(&'file (), R)>)),
+    #[cfg(feature = "srec")]
+    Srec((srec::SrecSymbol<'data>, PhantomData<(&'file (), R)>)),
+    #[cfg(feature = "ticoff")]
+    TiCoff((ticoff::TiCoffSymbol<'data, 'file, R>, PhantomData<R>)),
+    #[cfg(feature = "xbe")]
+    Xbe((xbe::XbeSymbol<'data>, PhantomData<(&'file (), R)>)),
 }
 
 impl<'data, 'file, R: ReadRef<'data>> fmt::Debug for Symbol<'data, 'file, R> {
@@ -1370,6 +1912,32 @@ enum SectionRelocationIteratorInternal<'data, 'file, R: ReadRef<'data>> {
     Xcoff32(xcoff::XcoffRelocationIterator32<'data, 'file, R>),
     #[cfg(feature = "xcoff")]
     Xcoff64(xcoff::XcoffRelocationIterator64<'data, 'file, R>),
+    #[cfg(feature = "omf")]
+    Omf(omf::OmfRelocationIterator<'data, 'file, R>),
+    #[cfg(feature = "ne")]
+    Ne(ne::NeRelocationIterator<'data, 'file, R>),
+    #[cfg(feature = "lx")]
+    Lx(lx::LxRelocationIterator<'data, 'file, R>),
+    #[cfg(feature = "pharlap")]
+    PharLap(pharlap::PharLapRelocationIterator<'data, 'file, R>),
+    #[cfg(feature = "aout")]
+    Aout(aout::AoutRelocationIterator<'data, 'file, R>),
+    #[cfg(feature = "hunk")]
+    Hunk(hunk::HunkRelocationIterator<'data, 'file, R>),
+    #[cfg(feature = "gemdos")]
+    Gemdos(gemdos::GemdosRelocationIterator<'data, 'file, R>),
+    #[cfg(feature = "pef")]
+    Pef(pef::PefRelocationIterator<'data, 'file, R>),
+    #[cfg(feature = "goff")]
+    Goff(goff::GoffRelocationIterator<'data, 'file, R>),
+    #[cfg(feature = "ihex")]
+    Ihex(ihex::IhexRelocationIterator<'data, 'file, R>),
+    #[cfg(feature = "srec")]
+    Srec(srec::SrecRelocationIterator<'data, 'file, R>),
+    #[cfg(feature = "ticoff")]
+    TiCoff(ticoff::TiCoffRelocationIterator<'data, 'file, R>),
+    #[cfg(feature = "xbe")]
+    Xbe(xbe::XbeRelocationIterator<'data, 'file, R>),
 }
 
 impl<'data, 'file, R: ReadRef<'data>> Iterator for SectionRelocationIterator<'data, 'file, R> {
@@ -0,0 +1,515 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::aout;
+use crate::endian::{Endian, Endianness, U32Bytes};
+use crate::read::{
+    self, Architecture, ComdatKind, Error, Export, FileFlags, Import, NoDynamicRelocationIterator,
+    Object, ObjectComdat, ObjectKind, ObjectSection, ReadError, ReadRef, Result, SectionIndex,
+    StringTable, SymbolIndex,
+};
+
+use super::{
+    AoutSection, AoutSectionIterator, AoutSegment, AoutSegmentIterator, AoutSymbol,
+    AoutSymbolIterator, AoutSymbolTable,
+};
+
+/// The section index of the text segment.
+pub(super) const TEXT_SECTION: SectionIndex = SectionIndex(1);
+/// The section index of the initialized data segment.
+pub(super) const DATA_SECTION: SectionIndex = SectionIndex(2);
+/// The section index of the uninitialized data segment.
+pub(super) const BSS_SECTION: SectionIndex = SectionIndex(3);
+
+/// An a.out object file or executable.
+///
+/// Most functions in the [`Object`] trait are implemented for this type.
+#[derive(Debug)]
+pub struct AoutFile<'data, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) data: R,
+    pub(super) endian: Endianness,
+    header: &'data aout::Exec<Endianness>,
+    text_offset: u64,
+    text_address: u64,
+    data_address: u64,
+    text_relocations: &'data [aout::Relocation<Endianness>],
+    data_relocations: &'data [aout::Relocation<Endianness>],
+    symbols: &'data [aout::Nlist<Endianness>],
+    strings: StringTable<'data>,
+}
+
+impl<'data, R: ReadRef<'data>> AoutFile<'data, R> {
+    /// Parse the raw a.out file data.
+    pub fn parse(data: R) -> Result<Self> {
+        let (header, endian) = parse_header(data, 0)?;
+        let magic = header.n_magic(endian);
+        let text_size = u64::from(header.a_text.get(endian));
+        let data_size = u64::from(header.a_data.get(endian));
+        let text_offset = text_offset(magic);
+        let text_address = match magic {
+            aout::QMAGIC => aout::QMAGIC_TEXT_ADDRESS.into(),
+            _ => 0,
+        };
+        let data_address = match magic {
+            aout::OMAGIC => text_address + text_size,
+            _ => {
+                let align = u64::from(aout::SEGMENT_SIZE);
+                (text_address + text_size + align - 1) & !(align - 1)
+            }
+        };
+
+        let mut offset = text_offset + text_size + data_size;
+        let trsize = header.a_trsize.get(endian);
+        let drsize = header.a_drsize.get(endian);
+        let mut text_relocations = &[][..];
+        let mut data_relocations = &[][..];
+        // SPARC uses a different relocation format, which isn't supported.
+        if !matches!(header.n_machtype(endian), aout::M_SPARC | aout::MID_SPARC) {
+            let size = core::mem::size_of::<aout::Relocation<Endianness>>();
+            text_relocations = data
+                .read_slice_at(offset, trsize as usize / size)
+                .read_error("Invalid a.out text relocation offset or size")?;
+            data_relocations = data
+                .read_slice_at(offset + u64::from(trsize), drsize as usize / size)
+                .read_error("Invalid a.out data relocation offset or size")?;
+        }
+        offset += u64::from(trsize) + u64::from(drsize);
+
+        let syms = header.a_syms.get(endian);
+        let symbols = data
+            .read_slice_at(
+                offset,
+                syms as usize / core::mem::size_of::<aout::Nlist<Endianness>>(),
+            )
+            .read_error("Invalid a.out symbol table offset or size")?;
+        offset += u64::from(syms);
+
+        // The string table is optional if there are no symbols.
+        let mut strings = StringTable::default();
+        if let Ok(size) = data.read_at::<U32Bytes<Endianness>>(offset) {
+            let size = size.get(endian);
+            let bytes = data
+                .read_bytes_at(offset, size.into())
+                .read_error("Invalid a.out string table size")?;
+            strings = StringTable::new(bytes, 0, size.into());
+        } else if !symbols.is_empty() {
+            return Err(Error("Missing a.out string table"));
+        }
+
+        Ok(AoutFile {
+            data,
+            endian,
+            header,
+            text_offset,
+            text_address,
+            data_address,
+            text_relocations,
+            data_relocations,
+            symbols,
+            strings,
+        })
+    }
+
+    /// Returns the endianness.
+    #[inline]
+    pub fn endian(&self) -> Endianness {
+        self.endian
+    }
+
+    /// Returns the raw a.out file data.
+    #[inline]
+    pub fn data(&self) -> R {
+        self.data
+    }
+
+    /// Returns the a.out header.
+    #[inline]
+    pub fn header(&self) -> &'data aout::Exec<Endianness> {
+        self.header
+    }
+
+    /// Returns the magic number. One of the `aout::*MAGIC` constants.
+    #[inline]
+    pub fn magic(&self) -> u16 {
+        self.header.n_magic(self.endian)
+    }
+
+    /// Returns the relocations for the text segment.
+    #[inline]
+    pub fn text_relocations(&self) -> &'data [aout::Relocation<Endianness>] {
+        self.text_relocations
+    }
+
+    /// Returns the relocations for the initialized data segment.
+    #[inline]
+    pub fn data_relocations(&self) -> &'data [aout::Relocation<Endianness>] {
+        self.data_relocations
+    }
+
+    /// Returns the raw symbol table entries, including debugging symbols.
+    #[inline]
+    pub fn nlists(&self) -> &'data [aout::Nlist<Endianness>] {
+        self.symbols
+    }
+
+    /// Returns the string table.
+    #[inline]
+    pub fn strings(&self) -> StringTable<'data> {
+        self.strings
+    }
+
+    /// Return the address and size of a segment in memory.
+    pub(super) fn section_range(&self, index: SectionIndex) -> (u64, u64) {
+        let endian = self.endian;
+        let text_size = u64::from(self.header.a_text.get(endian));
+        let data_size = u64::from(self.header.a_data.get(endian));
+        let bss_size = u64::from(self.header.a_bss.get(endian));
+        match index {
+            TEXT_SECTION => (self.text_address, text_size),
+            DATA_SECTION => (self.data_address, data_size),
+            _ => (self.data_address + data_size, bss_size),
+        }
+    }
+
+    /// Return the range of a segment in the file.
+    pub(super) fn section_file_range(&self, index: SectionIndex) -> Option<(u64, u64)> {
+        let endian = self.endian;
+        let text_size = u64::from(self.header.a_text.get(endian));
+        let data_size = u64::from(self.header.a_data.get(endian));
+        match index {
+            TEXT_SECTION => Some((self.text_offset, text_size)),
+            DATA_SECTION => Some((self.text_offset + text_size, data_size)),
+            _ => None,
+        }
+    }
+
+    pub(super) fn section_relocations(
+        &self,
+        index: SectionIndex,
+    ) -> &'data [aout::Relocation<Endianness>] {
+        match index {
+            TEXT_SECTION => self.text_relocations,
+            DATA_SECTION => self.data_relocations,
+            _ => &[],
+        }
+    }
+
+    fn symbol_table_internal(&self) -> AoutSymbolTable<'data, '_> {
+        AoutSymbolTable {
+            symbols: self.symbols,
+            strings: self.strings,
+            endian: self.endian,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'data, R: ReadRef<'data>> read::private::Sealed for AoutFile<'data, R> {}
+
+impl<'data, R: ReadRef<'data>> Object<'data> for AoutFile<'data, R> {
+    type Segment<'file>
+        = AoutSegment<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SegmentIterator<'file>
+        = AoutSegmentIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Section<'file>
+        = AoutSection<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SectionIterator<'file>
+        = AoutSectionIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Comdat<'file>
+        = AoutComdat<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type ComdatIterator<'file>
+        = AoutComdatIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Symbol<'file>
+        = AoutSymbol<'data>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolIterator<'file>
+        = AoutSymbolIterator<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolTable<'file>
+        = AoutSymbolTable<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type DynamicRelocationIterator<'file>
+        = NoDynamicRelocationIterator
+    where
+        Self: 'file,
+        'data: 'file;
+
+    fn architecture(&self) -> Architecture {
+        match self.header.n_machtype(self.endian) {
+            aout::M_68010 | aout::M_68020 | aout::MID_M68K | aout::MID_M68K4K => Architecture::M68k,
+            aout::M_SPARC | aout::MID_SPARC => Architecture::Sparc,
+            aout::M_386 | aout::MID_I386 => Architecture::I386,
+            aout::M_MIPS1 | aout::M_MIPS2 | aout::MID_PMAX | aout::MID_MIPS => Architecture::Mips,
+            aout::MID_ARM6 => Architecture::Arm,
+            _ => Architecture::Unknown,
+        }
+    }
+
+    #[inline]
+    fn is_little_endian(&self) -> bool {
+        self.endian.is_little_endian()
+    }
+
+    #[inline]
+    fn is_64(&self) -> bool {
+        false
+    }
+
+    /// `OMAGIC` files are assumed to be relocatable object files.
+    fn kind(&self) -> ObjectKind {
+        match self.magic() {
+            aout::OMAGIC => ObjectKind::Relocatable,
+            _ => ObjectKind::Executable,
+        }
+    }
+
+    fn segments(&self) -> AoutSegmentIterator<'data, '_, R> {
+        AoutSegmentIterator {
+            file: self,
+            index: TEXT_SECTION.0,
+        }
+    }
+
+    fn section_by_name_bytes<'file>(
+        &'file self,
+        section_name: &[u8],
+    ) -> Option<AoutSection<'data, 'file, R>> {
+        self.sections()
+            .find(|section| section.name_bytes() == Ok(section_name))
+    }
+
+    fn section_by_index(&self, index: SectionIndex) -> Result<AoutSection<'data, '_, R>> {
+        match index {
+            TEXT_SECTION | DATA_SECTION | BSS_SECTION => Ok(AoutSection { file: self, index }),
+            _ => Err(Error("Invalid a.out section index")),
+        }
+    }
+
+    fn sections(&self) -> AoutSectionIterator<'data, '_, R> {
+        AoutSectionIterator {
+            file: self,
+            index: TEXT_SECTION.0,
+        }
+    }
+
+    #[inline]
+    fn comdats(&self) -> AoutComdatIterator<'data, '_, R> {
+        AoutComdatIterator { file: self }
+    }
+
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<AoutSymbol<'data>> {
+        use crate::read::ObjectSymbolTable;
+        self.symbol_table_internal().symbol_by_index(index)
+    }
+
+    fn symbols(&self) -> AoutSymbolIterator<'data, '_> {
+        AoutSymbolIterator {
+            table: self.symbol_table_internal(),
+            index: 0,
+        }
+    }
+
+    #[inline]
+    fn symbol_table(&self) -> Option<AoutSymbolTable<'data, '_>> {
+        Some(self.symbol_table_internal())
+    }
+
+    fn dynamic_symbols(&self) -> AoutSymbolIterator<'data, '_> {
+        AoutSymbolIterator {
+            table: AoutSymbolTable::default(),
+            index: 0,
+        }
+    }
+
+    #[inline]
+    fn dynamic_symbol_table(&self) -> Option<AoutSymbolTable<'data, '_>> {
+        None
+    }
+
+    #[inline]
+    fn dynamic_relocations(&self) -> Option<NoDynamicRelocationIterator> {
+        None
+    }
+
+    fn imports(&self) -> Result<Vec<Import<'data>>> {
+        // a.out files don't support dynamic linking.
+        Ok(Vec::new())
+    }
+
+    fn exports(&self) -> Result<Vec<Export<'data>>> {
+        // a.out files don't support dynamic linking.
+        Ok(Vec::new())
+    }
+
+    fn has_debug_symbols(&self) -> bool {
+        self.symbols.iter().any(|nlist| nlist.is_stab())
+    }
+
+    #[inline]
+    fn relative_address_base(&self) -> u64 {
+        0
+    }
+
+    #[inline]
+    fn entry(&self) -> u64 {
+        self.header.a_entry.get(self.endian).into()
+    }
+
+    #[inline]
+    fn flags(&self) -> FileFlags {
+        FileFlags::None
+    }
+}
+
+/// Read the a.out header at the given offset, and determine its endianness.
+fn parse_header<'data, R: ReadRef<'data>>(
+    data: R,
+    offset: u64,
+) -> Result<(&'data aout::Exec<Endianness>, Endianness)> {
+    let header = data
+        .read_at::<aout::Exec<Endianness>>(offset)
+        .read_error("Invalid a.out header size or alignment")?;
+    for endian in [Endianness::Little, Endianness::Big] {
+        if matches!(
+            header.n_magic(endian),
+            aout::OMAGIC | aout::NMAGIC | aout::ZMAGIC | aout::QMAGIC
+        ) {
+            return Ok((header, endian));
+        }
+    }
+    Err(Error("Unsupported a.out magic"))
+}
+
+/// Return the file offset of the text segment for the given magic number.
+fn text_offset(magic: u16) -> u64 {
+    match magic {
+        aout::ZMAGIC => aout::ZMAGIC_TEXT_OFFSET.into(),
+        aout::QMAGIC => 0,
+        _ => core::mem::size_of::<aout::Exec<Endianness>>() as u64,
+    }
+}
+
+/// Check whether the data at the given offset looks like an a.out file.
+///
+/// The magic numbers are short, so this also checks that the segments,
+/// relocations and symbol table in the header fit in the file.
+pub(crate) fn is_aout_file<'data, R: ReadRef<'data>>(data: R, offset: u64) -> bool {
+    let (header, endian) = match parse_header(data, offset) {
+        Ok(header) => header,
+        Err(_) => return false,
+    };
+    let text_offset = text_offset(header.n_magic(endian));
+    let size = [
+        header.a_text,
+        header.a_data,
+        header.a_trsize,
+        header.a_drsize,
+        header.a_syms,
+    ]
+    .iter()
+    .map(|size| u64::from(size.get(endian)))
+    .sum::<u64>();
+    match data.len() {
+        Ok(len) => offset + text_offset + size <= len,
+        Err(_) => false,
+    }
+}
+
+/// An iterator for the COMDAT section groups in a [`AoutFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct AoutComdatIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file AoutFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for AoutComdatIterator<'data, 'file, R> {
+    type Item = AoutComdat<'data, 'file, R>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+/// A COMDAT section group in a [`AoutFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct AoutComdat<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file AoutFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for AoutComdat<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectComdat<'data> for AoutComdat<'data, 'file, R> {
+    type SectionIterator = AoutComdatSectionIterator<'data, 'file, R>;
+
+    #[inline]
+    fn kind(&self) -> ComdatKind {
+        unreachable!();
+    }
+
+    #[inline]
+    fn symbol(&self) -> SymbolIndex {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn sections(&self) -> Self::SectionIterator {
+        unreachable!();
+    }
+}
+
+/// An iterator for the sections in a COMDAT section group in a [`AoutFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct AoutComdatSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file AoutFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for AoutComdatSectionIterator<'data, 'file, R> {
+    type Item = SectionIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
@@ -0,0 +1,39 @@
+//! Support for reading a.out files.
+//!
+//! The a.out format was used for object files and executables by early Unix
+//! systems, BSD, SunOS and Linux. The `OMAGIC`, `NMAGIC`, `ZMAGIC` and `QMAGIC`
+//! variants are supported, in either byte order. The segment layout of 4.3BSD
+//! and Linux is assumed.
+//!
+//! ## High level API
+//!
+//! [`AoutFile`] implements the [`Object`](crate::read::Object) trait for a.out
+//! files. The text, data and bss segments are sections with the names `.text`,
+//! `.data` and `.bss`, and with the section indices 1, 2 and 3. `OMAGIC` files
+//! are treated as relocatable object files, and the other variants as
+//! executables.
+//!
+//! The symbol table and relocations use the raw a.out indices, so symbol
+//! indices in relocations can be passed to
+//! [`Object::symbol_by_index`](crate::read::Object::symbol_by_index).
+//! Debugging symbols are skipped when iterating the symbol table.
+//! The extended relocation format used by SPARC is not supported.
+//!
+//! ## Low level API
+//!
+//! [`AoutFile`] also provides access to the raw [`Exec`](crate::aout::Exec)
+//! header, the relocation entries for each segment
+//! ([`AoutFile::text_relocations`] and [`AoutFile::data_relocations`]),
+//! and the symbol table entries including debugging symbols
+//! ([`AoutFile::nlists`]).
+mod file;
+pub use file::*;
+
+mod section;
+pub use section::*;
+
+mod symbol;
+pub use symbol::*;
+
+mod relocation;
+pub use relocation::*;
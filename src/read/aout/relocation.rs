@@ -0,0 +1,76 @@
+use core::slice;
+
+use crate::aout;
+use crate::endian::Endianness;
+use crate::read::{
+    ReadRef, Relocation, RelocationEncoding, RelocationFlags, RelocationKind, RelocationTarget,
+    SymbolIndex,
+};
+
+use super::{AoutFile, BSS_SECTION, DATA_SECTION, TEXT_SECTION};
+
+/// An iterator for the relocations in an [`AoutSection`](super::AoutSection).
+///
+/// The addend is stored in the relocated location. For relocations against
+/// a segment the stored value is an address, so the addend is the negated
+/// address of the segment.
+#[derive(Debug)]
+pub struct AoutRelocationIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file AoutFile<'data, R>,
+    pub(super) relocations: slice::Iter<'data, aout::Relocation<Endianness>>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for AoutRelocationIterator<'data, 'file, R> {
+    type Item = (u64, Relocation);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let info = self.relocations.next()?.info(self.file.endian);
+        let kind = if info.r_copy || info.r_relative {
+            RelocationKind::Unknown
+        } else if info.r_jmptable {
+            RelocationKind::PltRelative
+        } else if info.r_baserel {
+            RelocationKind::Got
+        } else if info.r_pcrel {
+            RelocationKind::Relative
+        } else {
+            RelocationKind::Absolute
+        };
+        let encoding = RelocationEncoding::Generic;
+        let size = 8 << info.r_length;
+        let mut addend = 0;
+        let target = if info.r_extern {
+            RelocationTarget::Symbol(SymbolIndex(info.r_symbolnum as usize))
+        } else {
+            let index = match info.r_symbolnum as u8 & aout::N_TYPE {
+                aout::N_TEXT => Some(TEXT_SECTION),
+                aout::N_DATA => Some(DATA_SECTION),
+                aout::N_BSS => Some(BSS_SECTION),
+                _ => None,
+            };
+            match index {
+                Some(index) => {
+                    addend = (self.file.section_range(index).0 as i64).wrapping_neg();
+                    RelocationTarget::Section(index)
+                }
+                None => RelocationTarget::Absolute,
+            }
+        };
+        Some((
+            info.r_address.into(),
+            Relocation {
+                kind,
+                encoding,
+                size,
+                target,
+                addend,
+                implicit_addend: true,
+                flags: RelocationFlags::Generic {
+                    kind,
+                    encoding,
+                    size,
+                },
+            },
+        ))
+    }
+}
@@ -0,0 +1,246 @@
+use core::str;
+
+use crate::aout;
+use crate::read::{
+    self, CompressedData, CompressedFileRange, ObjectSection, ObjectSegment, ReadError, ReadRef,
+    RelocationMap, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags,
+};
+
+use super::{AoutFile, AoutRelocationIterator, BSS_SECTION, DATA_SECTION, TEXT_SECTION};
+
+/// An iterator for the sections in an [`AoutFile`].
+#[derive(Debug)]
+pub struct AoutSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file AoutFile<'data, R>,
+    pub(super) index: usize,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for AoutSectionIterator<'data, 'file, R> {
+    type Item = AoutSection<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = SectionIndex(self.index);
+        if index.0 > BSS_SECTION.0 {
+            return None;
+        }
+        self.index += 1;
+        Some(AoutSection {
+            file: self.file,
+            index,
+        })
+    }
+}
+
+/// A section in an [`AoutFile`].
+///
+/// The text, data and bss segments are sections with the indices 1, 2 and 3.
+#[derive(Debug)]
+pub struct AoutSection<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file AoutFile<'data, R>,
+    pub(super) index: SectionIndex,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for AoutSection<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSection<'data> for AoutSection<'data, 'file, R> {
+    type RelocationIterator = AoutRelocationIterator<'data, 'file, R>;
+
+    #[inline]
+    fn index(&self) -> SectionIndex {
+        self.index
+    }
+
+    #[inline]
+    fn address(&self) -> u64 {
+        self.file.section_range(self.index).0
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.file.section_range(self.index).1
+    }
+
+    fn align(&self) -> u64 {
+        if self.index == DATA_SECTION && self.file.magic() != aout::OMAGIC {
+            aout::SEGMENT_SIZE.into()
+        } else {
+            4
+        }
+    }
+
+    #[inline]
+    fn file_range(&self) -> Option<(u64, u64)> {
+        self.file.section_file_range(self.index)
+    }
+
+    fn data(&self) -> Result<&'data [u8]> {
+        match self.file_range() {
+            Some((offset, size)) => self
+                .file
+                .data
+                .read_bytes_at(offset, size)
+                .read_error("Invalid a.out segment size"),
+            None => Ok(&[]),
+        }
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        Ok(read::util::data_range(
+            self.data()?,
+            self.address(),
+            address,
+            size,
+        ))
+    }
+
+    #[inline]
+    fn compressed_file_range(&self) -> Result<CompressedFileRange> {
+        Ok(CompressedFileRange::none(self.file_range()))
+    }
+
+    #[inline]
+    fn compressed_data(&self) -> Result<CompressedData<'data>> {
+        self.data().map(CompressedData::none)
+    }
+
+    /// Return `.text`, `.data` or `.bss`.
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        Ok(match self.index {
+            TEXT_SECTION => b".text",
+            DATA_SECTION => b".data",
+            _ => b".bss",
+        })
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name_bytes()?)
+            .ok()
+            .read_error("Non UTF-8 a.out section name")
+    }
+
+    #[inline]
+    fn segment_name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn segment_name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    fn kind(&self) -> SectionKind {
+        match self.index {
+            TEXT_SECTION => SectionKind::Text,
+            DATA_SECTION => SectionKind::Data,
+            _ => SectionKind::UninitializedData,
+        }
+    }
+
+    fn relocations(&self) -> AoutRelocationIterator<'data, 'file, R> {
+        AoutRelocationIterator {
+            file: self.file,
+            relocations: self.file.section_relocations(self.index).iter(),
+        }
+    }
+
+    fn relocation_map(&self) -> Result<RelocationMap> {
+        RelocationMap::new(self.file, self)
+    }
+
+    #[inline]
+    fn flags(&self) -> SectionFlags {
+        SectionFlags::None
+    }
+}
+
+/// An iterator for the segments in an [`AoutFile`].
+#[derive(Debug)]
+pub struct AoutSegmentIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file AoutFile<'data, R>,
+    pub(super) index: usize,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for AoutSegmentIterator<'data, 'file, R> {
+    type Item = AoutSegment<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = SectionIndex(self.index);
+        if index.0 > DATA_SECTION.0 {
+            return None;
+        }
+        self.index += 1;
+        Some(AoutSegment {
+            file: self.file,
+            index,
+        })
+    }
+}
+
+/// A segment in an [`AoutFile`].
+///
+/// This is either the text segment, or the data segment followed by the bss segment.
+#[derive(Debug)]
+pub struct AoutSegment<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    file: &'file AoutFile<'data, R>,
+    index: SectionIndex,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> AoutSegment<'data, 'file, R> {
+    fn section(&self) -> AoutSection<'data, 'file, R> {
+        AoutSection {
+            file: self.file,
+            index: self.index,
+        }
+    }
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for AoutSegment<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSegment<'data> for AoutSegment<'data, 'file, R> {
+    #[inline]
+    fn address(&self) -> u64 {
+        self.section().address()
+    }
+
+    fn size(&self) -> u64 {
+        let mut size = self.section().size();
+        if self.index == DATA_SECTION {
+            size += self.file.section_range(BSS_SECTION).1;
+        }
+        size
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        self.section().align()
+    }
+
+    #[inline]
+    fn file_range(&self) -> (u64, u64) {
+        self.section().file_range().unwrap_or((0, 0))
+    }
+
+    fn data(&self) -> Result<&'data [u8]> {
+        self.section().data()
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        self.section().data_range(address, size)
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn flags(&self) -> SegmentFlags {
+        SegmentFlags::None
+    }
+}
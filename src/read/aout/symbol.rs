@@ -0,0 +1,221 @@
+use core::marker::PhantomData;
+use core::str;
+
+use crate::aout;
+use crate::endian::Endianness;
+use crate::read::{
+    self, ObjectSymbol, ObjectSymbolTable, ReadError, Result, SectionIndex, StringTable,
+    SymbolFlags, SymbolIndex, SymbolKind, SymbolScope, SymbolSection,
+};
+
+use super::{BSS_SECTION, DATA_SECTION, TEXT_SECTION};
+
+/// A symbol table in an [`AoutFile`](super::AoutFile).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AoutSymbolTable<'data, 'file> {
+    pub(super) symbols: &'data [aout::Nlist<Endianness>],
+    pub(super) strings: StringTable<'data>,
+    pub(super) endian: Endianness,
+    pub(super) marker: PhantomData<&'file ()>,
+}
+
+impl<'data, 'file> read::private::Sealed for AoutSymbolTable<'data, 'file> {}
+
+impl<'data, 'file> ObjectSymbolTable<'data> for AoutSymbolTable<'data, 'file> {
+    type Symbol = AoutSymbol<'data>;
+    type SymbolIterator = AoutSymbolIterator<'data, 'file>;
+
+    fn symbols(&self) -> Self::SymbolIterator {
+        AoutSymbolIterator {
+            table: *self,
+            index: 0,
+        }
+    }
+
+    /// Get the symbol at the given index.
+    ///
+    /// Returns an error for debugging symbols.
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<Self::Symbol> {
+        let nlist = self
+            .symbols
+            .get(index.0)
+            .filter(|nlist| !nlist.is_stab())
+            .read_error("Invalid a.out symbol index")?;
+        Ok(AoutSymbol {
+            index,
+            nlist,
+            strings: self.strings,
+            endian: self.endian,
+        })
+    }
+}
+
+/// An iterator for the symbols in an [`AoutFile`](super::AoutFile).
+///
+/// Debugging symbols are skipped.
+#[derive(Debug)]
+pub struct AoutSymbolIterator<'data, 'file> {
+    pub(super) table: AoutSymbolTable<'data, 'file>,
+    pub(super) index: usize,
+}
+
+impl<'data, 'file> Iterator for AoutSymbolIterator<'data, 'file> {
+    type Item = AoutSymbol<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let index = self.index;
+            let nlist = self.table.symbols.get(index)?;
+            self.index += 1;
+            if !nlist.is_stab() {
+                return Some(AoutSymbol {
+                    index: SymbolIndex(index),
+                    nlist,
+                    strings: self.table.strings,
+                    endian: self.table.endian,
+                });
+            }
+        }
+    }
+}
+
+/// A symbol in an [`AoutFile`](super::AoutFile).
+#[derive(Debug, Clone, Copy)]
+pub struct AoutSymbol<'data> {
+    index: SymbolIndex,
+    nlist: &'data aout::Nlist<Endianness>,
+    strings: StringTable<'data>,
+    endian: Endianness,
+}
+
+impl<'data> AoutSymbol<'data> {
+    /// Return the raw symbol table entry.
+    #[inline]
+    pub fn nlist(&self) -> &'data aout::Nlist<Endianness> {
+        self.nlist
+    }
+
+    fn value(&self) -> u64 {
+        self.nlist.n_value.get(self.endian).into()
+    }
+}
+
+impl<'data> read::private::Sealed for AoutSymbol<'data> {}
+
+impl<'data> ObjectSymbol<'data> for AoutSymbol<'data> {
+    #[inline]
+    fn index(&self) -> SymbolIndex {
+        self.index
+    }
+
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        match self.nlist.n_strx.get(self.endian) {
+            0 => Ok(&[]),
+            offset => self
+                .strings
+                .get(offset)
+                .read_error("Invalid a.out symbol name offset"),
+        }
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        let name = self.name_bytes()?;
+        str::from_utf8(name)
+            .ok()
+            .read_error("Non UTF-8 a.out symbol name")
+    }
+
+    fn address(&self) -> u64 {
+        match self.section() {
+            SymbolSection::Undefined | SymbolSection::Common => 0,
+            _ => self.value(),
+        }
+    }
+
+    /// Return the size of a common symbol.
+    ///
+    /// a.out does not record the size of other symbols.
+    fn size(&self) -> u64 {
+        if self.is_common() {
+            self.value()
+        } else {
+            0
+        }
+    }
+
+    fn kind(&self) -> SymbolKind {
+        match self.nlist.n_type {
+            aout::N_FN => SymbolKind::File,
+            _ => match self.nlist.n_type() {
+                aout::N_TEXT => SymbolKind::Text,
+                aout::N_DATA | aout::N_BSS | aout::N_COMM => SymbolKind::Data,
+                aout::N_UNDF if self.is_common() => SymbolKind::Data,
+                _ => SymbolKind::Unknown,
+            },
+        }
+    }
+
+    fn section(&self) -> SymbolSection {
+        if self.nlist.n_type == aout::N_FN {
+            return SymbolSection::None;
+        }
+        match self.nlist.n_type() {
+            aout::N_UNDF if self.nlist.is_external() && self.value() != 0 => SymbolSection::Common,
+            aout::N_UNDF => SymbolSection::Undefined,
+            aout::N_ABS => SymbolSection::Absolute,
+            aout::N_TEXT => SymbolSection::Section(TEXT_SECTION),
+            aout::N_DATA => SymbolSection::Section(DATA_SECTION),
+            aout::N_BSS => SymbolSection::Section(BSS_SECTION),
+            aout::N_COMM => SymbolSection::Common,
+            _ => SymbolSection::Unknown,
+        }
+    }
+
+    #[inline]
+    fn is_undefined(&self) -> bool {
+        self.section() == SymbolSection::Undefined
+    }
+
+    fn is_definition(&self) -> bool {
+        matches!(
+            self.nlist.n_type(),
+            aout::N_ABS | aout::N_TEXT | aout::N_DATA | aout::N_BSS
+        )
+    }
+
+    #[inline]
+    fn is_common(&self) -> bool {
+        self.section() == SymbolSection::Common
+    }
+
+    #[inline]
+    fn is_weak(&self) -> bool {
+        false
+    }
+
+    fn scope(&self) -> SymbolScope {
+        if self.is_undefined() {
+            SymbolScope::Unknown
+        } else if self.nlist.is_external() {
+            SymbolScope::Linkage
+        } else {
+            SymbolScope::Compilation
+        }
+    }
+
+    #[inline]
+    fn is_global(&self) -> bool {
+        self.nlist.is_external()
+    }
+
+    #[inline]
+    fn is_local(&self) -> bool {
+        !self.is_global()
+    }
+
+    #[inline]
+    fn flags(&self) -> SymbolFlags<SectionIndex, SymbolIndex> {
+        SymbolFlags::None
+    }
+}
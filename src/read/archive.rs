@@ -25,6 +25,8 @@ use core::slice;
 
 use crate::archive;
 use crate::endian::{BigEndian as BE, LittleEndian as LE, U16Bytes, U32Bytes, U64Bytes};
+#[cfg(feature = "omf")]
+use crate::read::omf;
 use crate::read::{self, Bytes, Error, ReadError, ReadRef};
 
 /// The kind of archive format.
@@ -47,6 +49,11 @@ pub enum ArchiveKind {
     Coff,
     /// The AIX big archive format.
     AixBig,
+    /// The Intel OMF library format.
+    ///
+    /// See [`omf::OmfLibrary`] for access to the OMF specific details.
+    #[cfg(feature = "omf")]
+    OmfLib,
 }
 
 /// The list of members in the archive.
@@ -59,6 +66,13 @@ enum Members<'data> {
     AixBig {
         index: &'data [archive::AixMemberOffset],
     },
+    /// Members of an OMF library start on a page boundary.
+    #[cfg(feature = "omf")]
+    Omf {
+        offset: u64,
+        end_offset: u64,
+        page_size: u32,
+    },
 }
 
 /// A partially parsed archive file.
@@ -81,6 +95,12 @@ impl<'data, R: ReadRef<'data>> ArchiveFile<'data, R> {
             .read_bytes(&mut tail, archive::MAGIC.len() as u64)
             .read_error("Invalid archive size")?;
 
+        // OMF libraries start with a LIBHDR record.
+        #[cfg(feature = "omf")]
+        if magic[0] == 0xF0 {
+            return Self::parse_omf(data);
+        }
+
         let thin = if magic == archive::AIX_BIG_MAGIC {
             return Self::parse_aixbig(data);
         } else if magic == archive::THIN_MAGIC {
@@ -265,6 +285,34 @@ impl<'data, R: ReadRef<'data>> ArchiveFile<'data, R> {
         Ok(file)
     }
 
+    #[cfg(feature = "omf")]
+    fn parse_omf(data: R) -> read::Result<Self> {
+        let library = omf::OmfLibrary::parse(data)?;
+        let len = data.len().read_error("Unknown archive length")?;
+        // The dictionary follows the LIBEND record.
+        let dictionary_offset = u64::from(library.dictionary_offset());
+        let end_offset = if dictionary_offset != 0 {
+            dictionary_offset.min(len)
+        } else {
+            len
+        };
+        Ok(ArchiveFile {
+            data,
+            kind: ArchiveKind::OmfLib,
+            members: Members::Omf {
+                offset: library.page_size().into(),
+                end_offset,
+                page_size: library.page_size(),
+            },
+            symbols: (
+                dictionary_offset,
+                u64::from(library.dictionary_blocks()) * 512,
+            ),
+            names: &[],
+            thin: false,
+        })
+    }
+
     /// Return the archive format.
     #[inline]
     pub fn kind(&self) -> ArchiveKind {
@@ -303,11 +351,36 @@ impl<'data, R: ReadRef<'data>> ArchiveFile<'data, R> {
                 let offset = member.0;
                 ArchiveMember::parse_aixbig(self.data, offset)
             }
+            #[cfg(feature = "omf")]
+            Members::Omf {
+                offset,
+                end_offset,
+                page_size,
+            } => {
+                if member.0 < offset
+                    || member.0 >= end_offset
+                    || member.0 % u64::from(page_size) != 0
+                {
+                    return Err(Error("Invalid archive member offset"));
+                }
+                ArchiveMember::parse_omf(self.data, end_offset, member.0)?
+                    .read_error("Invalid archive member offset")
+            }
         }
     }
 
     /// Iterate over the symbols in the archive.
     pub fn symbols(&self) -> read::Result<Option<ArchiveSymbolIterator<'data>>> {
+        #[cfg(feature = "omf")]
+        if self.kind == ArchiveKind::OmfLib {
+            if self.symbols.1 == 0 {
+                return Ok(None);
+            }
+            let library = omf::OmfLibrary::parse(self.data)?;
+            return Ok(Some(ArchiveSymbolIterator(SymbolIteratorInternal::Omf(
+                library.symbols()?,
+            ))));
+        }
         if self.symbols == (0, 0) {
             return Ok(None);
         }
@@ -356,6 +429,33 @@ impl<'data, R: ReadRef<'data>> Iterator for ArchiveMemberIterator<'data, R> {
                     Some(member)
                 }
             },
+            #[cfg(feature = "omf")]
+            Members::Omf {
+                ref mut offset,
+                end_offset,
+                page_size,
+            } => {
+                if *offset >= *end_offset {
+                    return None;
+                }
+                match ArchiveMember::parse_omf(self.data, *end_offset, *offset) {
+                    Ok(Some(member)) => {
+                        // The next member starts at the next page boundary.
+                        let page_size = u64::from(*page_size);
+                        let end = member.offset + member.size;
+                        *offset = (end + page_size - 1) / page_size * page_size;
+                        Some(Ok(member))
+                    }
+                    Ok(None) => {
+                        *offset = *end_offset;
+                        None
+                    }
+                    Err(error) => {
+                        *offset = *end_offset;
+                        Some(Err(error))
+                    }
+                }
+            }
         }
     }
 }
@@ -367,6 +467,9 @@ enum MemberHeader<'data> {
     Common(&'data archive::Header),
     /// AIX big archive header
     AixBig(&'data archive::AixHeader),
+    /// OMF library members have no header.
+    #[cfg(feature = "omf")]
+    Omf,
 }
 
 /// A partially parsed archive member.
@@ -497,6 +600,27 @@ impl<'data> ArchiveMember<'data> {
         })
     }
 
+    /// Parse the OMF library member at `offset`.
+    ///
+    /// Returns `Ok(None)` at the end of the members.
+    #[cfg(feature = "omf")]
+    fn parse_omf<R: ReadRef<'data>>(
+        data: R,
+        end_offset: u64,
+        offset: u64,
+    ) -> read::Result<Option<Self>> {
+        let member = match omf::parse_library_member(data, end_offset, offset)? {
+            Some(member) => member,
+            None => return Ok(None),
+        };
+        Ok(Some(ArchiveMember {
+            header: MemberHeader::Omf,
+            name: member.name_bytes(),
+            offset,
+            size: member.size(),
+        }))
+    }
+
     /// Return the raw header that is common to many archive formats.
     ///
     /// Returns `None` if this archive does not use the common header format.
@@ -521,7 +645,8 @@ impl<'data> ArchiveMember<'data> {
 
     /// Return the parsed file name.
     ///
-    /// This may be an extended file name.
+    /// This may be an extended file name. For OMF libraries, this is the
+    /// module name from the THEADR record.
     #[inline]
     pub fn name(&self) -> &'data [u8] {
         self.name
@@ -533,6 +658,8 @@ impl<'data> ArchiveMember<'data> {
         match &self.header {
             MemberHeader::Common(header) => parse_u64_digits(&header.date, 10),
             MemberHeader::AixBig(header) => parse_u64_digits(&header.date, 10),
+            #[cfg(feature = "omf")]
+            MemberHeader::Omf => None,
         }
     }
 
@@ -542,6 +669,8 @@ impl<'data> ArchiveMember<'data> {
         match &self.header {
             MemberHeader::Common(header) => parse_u64_digits(&header.uid, 10),
             MemberHeader::AixBig(header) => parse_u64_digits(&header.uid, 10),
+            #[cfg(feature = "omf")]
+            MemberHeader::Omf => None,
         }
     }
 
@@ -551,6 +680,8 @@ impl<'data> ArchiveMember<'data> {
         match &self.header {
             MemberHeader::Common(header) => parse_u64_digits(&header.gid, 10),
             MemberHeader::AixBig(header) => parse_u64_digits(&header.gid, 10),
+            #[cfg(feature = "omf")]
+            MemberHeader::Omf => None,
         }
     }
 
@@ -560,6 +691,8 @@ impl<'data> ArchiveMember<'data> {
         match &self.header {
             MemberHeader::Common(header) => parse_u64_digits(&header.mode, 8),
             MemberHeader::AixBig(header) => parse_u64_digits(&header.mode, 8),
+            #[cfg(feature = "omf")]
+            MemberHeader::Omf => None,
         }
     }
 
@@ -662,6 +795,11 @@ enum SymbolIteratorInternal<'data> {
         indices: slice::Iter<'data, U16Bytes<LE>>,
         names: Bytes<'data>,
     },
+    /// An OMF library dictionary.
+    ///
+    /// The dictionary gives the page number of the member defining each symbol.
+    #[cfg(feature = "omf")]
+    Omf(omf::OmfLibrarySymbolIterator<'data>),
 }
 
 impl<'data> ArchiveSymbolIterator<'data> {
@@ -723,6 +861,9 @@ impl<'data> ArchiveSymbolIterator<'data> {
             }
             // TODO: Implement AIX big archive symbol table.
             ArchiveKind::AixBig => Ok(ArchiveSymbolIterator(SymbolIteratorInternal::None)),
+            // The OMF dictionary is handled by `ArchiveFile::symbols`.
+            #[cfg(feature = "omf")]
+            ArchiveKind::OmfLib => Ok(ArchiveSymbolIterator(SymbolIteratorInternal::None)),
         }
     }
 }
@@ -800,6 +941,13 @@ impl<'data> Iterator for ArchiveSymbolIterator<'data> {
                     })
                 }))
             }
+            #[cfg(feature = "omf")]
+            SymbolIteratorInternal::Omf(symbols) => {
+                Some(symbols.next()?.map(|symbol| ArchiveSymbol {
+                    name: symbol.name(),
+                    offset: ArchiveOffset(symbol.offset()),
+                }))
+            }
         }
     }
 
@@ -814,6 +962,8 @@ impl<'data> Iterator for ArchiveSymbolIterator<'data> {
                 // The `slice::Iter` is in the indices field for this variant
                 indices.size_hint()
             }
+            #[cfg(feature = "omf")]
+            SymbolIteratorInternal::Omf(_) => (0, None),
         }
     }
 }
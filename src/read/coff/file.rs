@@ -43,15 +43,20 @@ pub struct CoffFile<'data, R: ReadRef<'data> = &'data [u8], Coff: CoffHeader = p
     pub(super) header: &'data Coff,
     pub(super) common: CoffCommon<'data, R, Coff>,
     pub(super) data: R,
+    pub(super) image_offset: u64,
 }
 
 impl<'data, R: ReadRef<'data>, Coff: CoffHeader> CoffFile<'data, R, Coff> {
     /// Parse the raw COFF file data.
+    ///
+    /// The COFF image may follow an MS-DOS stub program, as it does in
+    /// DJGPP executables.
     pub fn parse(data: R) -> Result<Self> {
-        let mut offset = 0;
+        let image_offset = dos_stub_size(data).unwrap_or(0);
+        let mut offset = image_offset;
         let header = Coff::parse(data, &mut offset)?;
         let sections = header.sections(data, offset)?;
-        let symbols = header.symbols(data)?;
+        let symbols = SymbolTable::parse_at(header, data, image_offset)?;
 
         Ok(CoffFile {
             header,
@@ -61,9 +66,29 @@ impl<'data, R: ReadRef<'data>, Coff: CoffHeader> CoffFile<'data, R, Coff> {
                 image_base: 0,
             },
             data,
+            image_offset,
         })
     }
 
+    /// Get the MS-DOS stub program that precedes the COFF image.
+    ///
+    /// Returns `None` if there is no stub. DJGPP executables have a stub,
+    /// such as `go32-v2`, which loads the COFF image.
+    pub fn dos_stub(&self) -> Option<&'data [u8]> {
+        if self.image_offset == 0 {
+            return None;
+        }
+        self.data.read_bytes_at(0, self.image_offset).ok()
+    }
+
+    /// Get the file offset of the COFF image.
+    ///
+    /// This is non-zero if the image follows an MS-DOS stub program. The file
+    /// offsets in the COFF headers are relative to this.
+    pub fn image_offset(&self) -> u64 {
+        self.image_offset
+    }
+
     /// Get the raw COFF file header.
     pub fn coff_header(&self) -> &'data Coff {
         self.header
@@ -176,7 +201,11 @@ where
     }
 
     fn kind(&self) -> ObjectKind {
-        ObjectKind::Relocatable
+        if self.header.characteristics() & pe::IMAGE_FILE_EXECUTABLE_IMAGE != 0 {
+            ObjectKind::Executable
+        } else {
+            ObjectKind::Relocatable
+        }
     }
 
     fn segments(&self) -> CoffSegmentIterator<'data, '_, R, Coff> {
@@ -288,6 +317,37 @@ pub fn anon_object_class_id<'data, R: ReadRef<'data>>(data: R) -> Result<pe::Cls
     Ok(header.class_id)
 }
 
+/// Return the size of the MS-DOS stub program at the start of the data.
+///
+/// Returns `None` if the data doesn't start with an MS-DOS header.
+fn dos_stub_size<'data, R: ReadRef<'data>>(data: R) -> Option<u64> {
+    let dos_header = data.read_at::<pe::ImageDosHeader>(0).ok()?;
+    if dos_header.e_magic.get(LE) != pe::IMAGE_DOS_SIGNATURE {
+        return None;
+    }
+    let mut size = u64::from(dos_header.e_cp.get(LE)) * 512;
+    let last_page_size = dos_header.e_cblp.get(LE);
+    if last_page_size != 0 {
+        size = size.saturating_sub(512u64.checked_sub(last_page_size.into())?);
+    }
+    Some(size)
+}
+
+/// Return true if the data is a DJGPP executable.
+///
+/// This is an i386 COFF executable that follows an MS-DOS stub program.
+pub fn is_djgpp_file<'data, R: ReadRef<'data>>(data: R) -> bool {
+    let header =
+        dos_stub_size(data).and_then(|offset| data.read_at::<pe::ImageFileHeader>(offset).ok());
+    match header {
+        Some(header) => {
+            header.machine.get(LE) == pe::IMAGE_FILE_MACHINE_I386
+                && header.characteristics.get(LE) & pe::IMAGE_FILE_EXECUTABLE_IMAGE != 0
+        }
+        None => false,
+    }
+}
+
 /// A trait for generic access to [`pe::ImageFileHeader`] and [`pe::AnonObjectHeaderBigobj`].
 #[allow(missing_docs)]
 pub trait CoffHeader: Debug + Pod {
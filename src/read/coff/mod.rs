@@ -10,6 +10,9 @@
 //! The default parameter allows reading regular COFF object files,
 //! while the type alias [`CoffBigFile`] allows reading COFF bigobj files.
 //!
+//! DJGPP executables, which are COFF images that follow an MS-DOS stub
+//! program, are also supported. [`CoffFile::dos_stub`] returns the stub.
+//!
 //! [`ImportFile`] allows reading COFF short imports that are used in import
 //! libraries. Currently these are not integrated with the unified read API.
 //!
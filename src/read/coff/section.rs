@@ -174,7 +174,7 @@ impl<'data, 'file, R: ReadRef<'data>, Coff: CoffHeader> CoffSegment<'data, 'file
 
     fn bytes(&self) -> Result<&'data [u8]> {
         self.section
-            .coff_data(self.file.data)
+            .coff_data_at(self.file.data, self.file.image_offset)
             .read_error("Invalid COFF section offset or size")
     }
 }
@@ -204,8 +204,10 @@ impl<'data, 'file, R: ReadRef<'data>, Coff: CoffHeader> ObjectSegment<'data>
 
     #[inline]
     fn file_range(&self) -> (u64, u64) {
-        let (offset, size) = self.section.coff_file_range().unwrap_or((0, 0));
-        (u64::from(offset), u64::from(size))
+        match self.section.coff_file_range() {
+            Some((offset, size)) => (self.file.image_offset + u64::from(offset), u64::from(size)),
+            None => (0, 0),
+        }
     }
 
     fn data(&self) -> Result<&'data [u8]> {
@@ -308,12 +310,13 @@ impl<'data, 'file, R: ReadRef<'data>, Coff: CoffHeader> CoffSection<'data, 'file
 
     /// Get the raw COFF relocations for this section.
     pub fn coff_relocations(&self) -> Result<&'data [pe::ImageRelocation]> {
-        self.section.coff_relocations(self.file.data)
+        self.section
+            .coff_relocations_at(self.file.data, self.file.image_offset)
     }
 
     fn bytes(&self) -> Result<&'data [u8]> {
         self.section
-            .coff_data(self.file.data)
+            .coff_data_at(self.file.data, self.file.image_offset)
             .read_error("Invalid COFF section offset or size")
     }
 }
@@ -352,7 +355,7 @@ impl<'data, 'file, R: ReadRef<'data>, Coff: CoffHeader> ObjectSection<'data>
     #[inline]
     fn file_range(&self) -> Option<(u64, u64)> {
         let (offset, size) = self.section.coff_file_range()?;
-        Some((u64::from(offset), u64::from(size)))
+        Some((self.file.image_offset + u64::from(offset), u64::from(size)))
     }
 
     fn data(&self) -> Result<&'data [u8]> {
@@ -534,8 +537,17 @@ impl pe::ImageSectionHeader {
     /// Returns `Ok(&[])` if the section has no data.
     /// Returns `Err` for invalid values.
     pub fn coff_data<'data, R: ReadRef<'data>>(&self, data: R) -> result::Result<&'data [u8], ()> {
+        self.coff_data_at(data, 0)
+    }
+
+    /// Return the section data in a COFF image at the given file offset.
+    pub(super) fn coff_data_at<'data, R: ReadRef<'data>>(
+        &self,
+        data: R,
+        image_offset: u64,
+    ) -> result::Result<&'data [u8], ()> {
         if let Some((offset, size)) = self.coff_file_range() {
-            data.read_bytes_at(offset.into(), size.into())
+            data.read_bytes_at(image_offset + u64::from(offset), size.into())
         } else {
             Ok(&[])
         }
@@ -571,7 +583,16 @@ impl pe::ImageSectionHeader {
         &self,
         data: R,
     ) -> read::Result<&'data [pe::ImageRelocation]> {
-        let mut pointer = self.pointer_to_relocations.get(LE).into();
+        self.coff_relocations_at(data, 0)
+    }
+
+    /// Read the relocations in a COFF image at the given file offset.
+    pub(super) fn coff_relocations_at<'data, R: ReadRef<'data>>(
+        &self,
+        data: R,
+        image_offset: u64,
+    ) -> read::Result<&'data [pe::ImageRelocation]> {
+        let mut pointer = image_offset + u64::from(self.pointer_to_relocations.get(LE));
         let mut number: usize = self.number_of_relocations.get(LE).into();
         if number == u16::MAX.into()
             && self.characteristics.get(LE) & pe::IMAGE_SCN_LNK_NRELOC_OVFL != 0
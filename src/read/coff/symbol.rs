@@ -42,9 +42,15 @@ impl<'data, R: ReadRef<'data>, Coff: CoffHeader> Default for SymbolTable<'data,
 impl<'data, R: ReadRef<'data>, Coff: CoffHeader> SymbolTable<'data, R, Coff> {
     /// Read the symbol table.
     pub fn parse(header: &Coff, data: R) -> Result<Self> {
+        Self::parse_at(header, data, 0)
+    }
+
+    /// Read the symbol table of a COFF image at the given file offset.
+    pub(super) fn parse_at(header: &Coff, data: R, image_offset: u64) -> Result<Self> {
         // The symbol table may not be present.
-        let mut offset = header.pointer_to_symbol_table().into();
-        let (symbols, strings) = if offset != 0 {
+        let pointer = header.pointer_to_symbol_table();
+        let (symbols, strings) = if pointer != 0 {
+            let mut offset = image_offset + u64::from(pointer);
             let symbols = data
                 .read_slice(&mut offset, header.number_of_symbols() as usize)
                 .read_error("Invalid COFF symbol table offset or size")?;
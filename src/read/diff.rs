@@ -0,0 +1,525 @@
+use alloc::collections::btree_map::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::read::{
+    Object, ObjectSection, ObjectSymbol, RelocationEncoding, RelocationKind, RelocationTarget,
+    SectionFlags, SectionKind, SymbolKind, SymbolScope, SymbolSection,
+};
+
+/// The differences between two object files.
+///
+/// Sections and symbols are matched by name, and relocations are matched by
+/// their offset within matching sections. If several entities have the same
+/// name or offset, they are matched in the order they appear in each file.
+///
+/// This works with any pair of file formats, but some properties are
+/// represented differently by each format, so it is most useful for files of
+/// the same format, such as the output of two versions of a compiler.
+///
+/// The [`Display`](fmt::Display) implementation writes one difference per line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObjectDiff {
+    /// The differences, in the order of the entities in the old file, followed
+    /// by entities that were added in the new file.
+    pub differences: Vec<ObjectDifference>,
+}
+
+/// A difference between two object files, as found by [`ObjectDiff::new`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ObjectDifference {
+    /// A property of the file changed.
+    FileChanged {
+        /// The property that changed.
+        field: FileDiffField,
+    },
+    /// A section is only in the new file.
+    SectionAdded {
+        /// The section name.
+        name: Vec<u8>,
+    },
+    /// A section is only in the old file.
+    SectionRemoved {
+        /// The section name.
+        name: Vec<u8>,
+    },
+    /// A property of a section changed.
+    SectionChanged {
+        /// The section name.
+        name: Vec<u8>,
+        /// The property that changed.
+        field: SectionDiffField,
+    },
+    /// A symbol is only in the new file.
+    SymbolAdded {
+        /// The symbol name.
+        name: Vec<u8>,
+    },
+    /// A symbol is only in the old file.
+    SymbolRemoved {
+        /// The symbol name.
+        name: Vec<u8>,
+    },
+    /// A property of a symbol changed.
+    SymbolChanged {
+        /// The symbol name.
+        name: Vec<u8>,
+        /// The property that changed.
+        field: SymbolDiffField,
+    },
+    /// A relocation is only in the new file.
+    RelocationAdded {
+        /// The name of the section containing the relocation.
+        section: Vec<u8>,
+        /// The offset of the relocation within the section.
+        offset: u64,
+    },
+    /// A relocation is only in the old file.
+    RelocationRemoved {
+        /// The name of the section containing the relocation.
+        section: Vec<u8>,
+        /// The offset of the relocation within the section.
+        offset: u64,
+    },
+    /// A property of a relocation changed.
+    RelocationChanged {
+        /// The name of the section containing the relocation.
+        section: Vec<u8>,
+        /// The offset of the relocation within the section.
+        offset: u64,
+        /// The property that changed.
+        field: RelocationDiffField,
+    },
+}
+
+/// A property of a file that is compared by [`ObjectDiff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FileDiffField {
+    /// See [`Object::architecture`].
+    Architecture,
+    /// See [`Object::endianness`].
+    Endianness,
+    /// See [`Object::kind`].
+    Kind,
+    /// See [`Object::entry`].
+    Entry,
+}
+
+/// A property of a section that is compared by [`ObjectDiff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SectionDiffField {
+    /// See [`ObjectSection::kind`].
+    Kind,
+    /// See [`ObjectSection::address`].
+    Address,
+    /// See [`ObjectSection::size`].
+    Size,
+    /// See [`ObjectSection::align`].
+    Align,
+    /// See [`ObjectSection::flags`].
+    Flags,
+    /// See [`ObjectSection::data`].
+    Data,
+}
+
+/// A property of a symbol that is compared by [`ObjectDiff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SymbolDiffField {
+    /// See [`ObjectSymbol::kind`].
+    Kind,
+    /// See [`ObjectSymbol::scope`].
+    Scope,
+    /// The section containing the symbol, compared by name.
+    ///
+    /// This also changes if a symbol becomes undefined, absolute or common.
+    Section,
+    /// See [`ObjectSymbol::address`].
+    Address,
+    /// See [`ObjectSymbol::size`].
+    Size,
+    /// See [`ObjectSymbol::is_weak`].
+    Weak,
+}
+
+/// A property of a relocation that is compared by [`ObjectDiff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RelocationDiffField {
+    /// See [`Relocation::kind`](crate::read::Relocation::kind).
+    Kind,
+    /// See [`Relocation::encoding`](crate::read::Relocation::encoding).
+    Encoding,
+    /// See [`Relocation::size`](crate::read::Relocation::size).
+    Size,
+    /// The target of the relocation, compared by symbol or section name.
+    Target,
+    /// The explicit addend, and whether the addend is implicit.
+    Addend,
+}
+
+impl ObjectDiff {
+    /// Compare two object files.
+    pub fn new<'old, 'new, Old, New>(old: &Old, new: &New) -> Self
+    where
+        Old: Object<'old>,
+        New: Object<'new>,
+    {
+        let mut differences = Vec::new();
+        let mut file_field = |changed: bool, field| {
+            if changed {
+                differences.push(ObjectDifference::FileChanged { field });
+            }
+        };
+        file_field(
+            old.architecture() != new.architecture(),
+            FileDiffField::Architecture,
+        );
+        file_field(
+            old.endianness() != new.endianness(),
+            FileDiffField::Endianness,
+        );
+        file_field(old.kind() != new.kind(), FileDiffField::Kind);
+        file_field(old.entry() != new.entry(), FileDiffField::Entry);
+
+        let old_sections = sections(old);
+        let new_sections = sections(new);
+        let (matches, added) = match_lists(
+            old_sections.iter().map(|section| section.name),
+            new_sections.iter().map(|section| section.name),
+        );
+        for (old, new) in old_sections.iter().zip(matches) {
+            let new = match new {
+                Some(new) => &new_sections[new],
+                None => {
+                    differences.push(ObjectDifference::SectionRemoved {
+                        name: old.name.to_vec(),
+                    });
+                    continue;
+                }
+            };
+            let mut field = |changed: bool, field| {
+                if changed {
+                    differences.push(ObjectDifference::SectionChanged {
+                        name: old.name.to_vec(),
+                        field,
+                    });
+                }
+            };
+            field(old.kind != new.kind, SectionDiffField::Kind);
+            field(old.address != new.address, SectionDiffField::Address);
+            field(old.size != new.size, SectionDiffField::Size);
+            field(old.align != new.align, SectionDiffField::Align);
+            field(old.flags != new.flags, SectionDiffField::Flags);
+            field(old.data != new.data, SectionDiffField::Data);
+            diff_relocations(&mut differences, old, new);
+        }
+        for new in added {
+            differences.push(ObjectDifference::SectionAdded {
+                name: new_sections[new].name.to_vec(),
+            });
+        }
+
+        let old_symbols = symbols(old);
+        let new_symbols = symbols(new);
+        let (matches, added) = match_lists(
+            old_symbols.iter().map(|symbol| symbol.name),
+            new_symbols.iter().map(|symbol| symbol.name),
+        );
+        for (old, new) in old_symbols.iter().zip(matches) {
+            let new = match new {
+                Some(new) => &new_symbols[new],
+                None => {
+                    differences.push(ObjectDifference::SymbolRemoved {
+                        name: old.name.to_vec(),
+                    });
+                    continue;
+                }
+            };
+            let mut field = |changed: bool, field| {
+                if changed {
+                    differences.push(ObjectDifference::SymbolChanged {
+                        name: old.name.to_vec(),
+                        field,
+                    });
+                }
+            };
+            field(old.kind != new.kind, SymbolDiffField::Kind);
+            field(old.scope != new.scope, SymbolDiffField::Scope);
+            field(old.section != new.section, SymbolDiffField::Section);
+            field(old.address != new.address, SymbolDiffField::Address);
+            field(old.size != new.size, SymbolDiffField::Size);
+            field(old.weak != new.weak, SymbolDiffField::Weak);
+        }
+        for new in added {
+            differences.push(ObjectDifference::SymbolAdded {
+                name: new_symbols[new].name.to_vec(),
+            });
+        }
+
+        ObjectDiff { differences }
+    }
+
+    /// Return true if no differences were found.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+impl fmt::Display for ObjectDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for difference in &self.differences {
+            writeln!(f, "{}", difference)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ObjectDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |name: &[u8]| String::from_utf8_lossy(name).into_owned();
+        match self {
+            ObjectDifference::FileChanged { field } => write!(f, "file: {:?} changed", field),
+            ObjectDifference::SectionAdded { name: n } => {
+                write!(f, "section `{}` added", name(n))
+            }
+            ObjectDifference::SectionRemoved { name: n } => {
+                write!(f, "section `{}` removed", name(n))
+            }
+            ObjectDifference::SectionChanged { name: n, field } => {
+                write!(f, "section `{}`: {:?} changed", name(n), field)
+            }
+            ObjectDifference::SymbolAdded { name: n } => write!(f, "symbol `{}` added", name(n)),
+            ObjectDifference::SymbolRemoved { name: n } => {
+                write!(f, "symbol `{}` removed", name(n))
+            }
+            ObjectDifference::SymbolChanged { name: n, field } => {
+                write!(f, "symbol `{}`: {:?} changed", name(n), field)
+            }
+            ObjectDifference::RelocationAdded { section, offset } => {
+                write!(f, "relocation `{}`+0x{:x} added", name(section), offset)
+            }
+            ObjectDifference::RelocationRemoved { section, offset } => {
+                write!(f, "relocation `{}`+0x{:x} removed", name(section), offset)
+            }
+            ObjectDifference::RelocationChanged {
+                section,
+                offset,
+                field,
+            } => write!(
+                f,
+                "relocation `{}`+0x{:x}: {:?} changed",
+                name(section),
+                offset,
+                field
+            ),
+        }
+    }
+}
+
+/// The compared properties of a section.
+struct SectionInfo<'data> {
+    name: &'data [u8],
+    kind: SectionKind,
+    address: u64,
+    size: u64,
+    align: u64,
+    flags: SectionFlags,
+    data: Option<&'data [u8]>,
+    relocations: Vec<RelocationInfo<'data>>,
+}
+
+/// The compared properties of a relocation.
+struct RelocationInfo<'data> {
+    offset: u64,
+    kind: RelocationKind,
+    encoding: RelocationEncoding,
+    size: u8,
+    target: TargetInfo<'data>,
+    addend: (i64, bool),
+}
+
+/// A relocation target, identified by name.
+#[derive(PartialEq, Eq)]
+enum TargetInfo<'data> {
+    Symbol(&'data [u8]),
+    Section(&'data [u8]),
+    Absolute,
+    Unknown,
+}
+
+/// The compared properties of a symbol.
+struct SymbolInfo<'data> {
+    name: &'data [u8],
+    kind: SymbolKind,
+    scope: SymbolScope,
+    section: SectionInfoKey<'data>,
+    address: u64,
+    size: u64,
+    weak: bool,
+}
+
+/// A symbol section, with sections identified by name.
+#[derive(PartialEq, Eq)]
+enum SectionInfoKey<'data> {
+    Section(&'data [u8]),
+    Other(SymbolSection),
+}
+
+fn section_name<'data, O: Object<'data>>(file: &O, index: crate::SectionIndex) -> &'data [u8] {
+    file.section_by_index(index)
+        .and_then(|section| section.name_bytes())
+        .unwrap_or(&[])
+}
+
+fn sections<'data, O: Object<'data>>(file: &O) -> Vec<SectionInfo<'data>> {
+    file.sections()
+        .map(|section| SectionInfo {
+            name: section.name_bytes().unwrap_or(&[]),
+            kind: section.kind(),
+            address: section.address(),
+            size: section.size(),
+            align: section.align(),
+            flags: section.flags(),
+            data: section.data().ok(),
+            relocations: section
+                .relocations()
+                .map(|(offset, relocation)| RelocationInfo {
+                    offset,
+                    kind: relocation.kind(),
+                    encoding: relocation.encoding(),
+                    size: relocation.size(),
+                    target: match relocation.target() {
+                        RelocationTarget::Symbol(index) => match file.symbol_by_index(index) {
+                            // Section symbols are often unnamed, so use the section name.
+                            Ok(symbol) if symbol.kind() == SymbolKind::Section => {
+                                match symbol.section_index() {
+                                    Some(index) => TargetInfo::Section(section_name(file, index)),
+                                    None => TargetInfo::Unknown,
+                                }
+                            }
+                            Ok(symbol) => TargetInfo::Symbol(symbol.name_bytes().unwrap_or(&[])),
+                            Err(_) => TargetInfo::Unknown,
+                        },
+                        RelocationTarget::Section(index) => {
+                            TargetInfo::Section(section_name(file, index))
+                        }
+                        RelocationTarget::Absolute => TargetInfo::Absolute,
+                    },
+                    addend: (relocation.addend(), relocation.has_implicit_addend()),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn symbols<'data, O: Object<'data>>(file: &O) -> Vec<SymbolInfo<'data>> {
+    file.symbols()
+        .filter(|symbol| symbol.kind() != SymbolKind::Section)
+        .map(|symbol| SymbolInfo {
+            name: symbol.name_bytes().unwrap_or(&[]),
+            kind: symbol.kind(),
+            scope: symbol.scope(),
+            section: match symbol.section() {
+                SymbolSection::Section(index) => SectionInfoKey::Section(section_name(file, index)),
+                section => SectionInfoKey::Other(section),
+            },
+            address: symbol.address(),
+            size: symbol.size(),
+            weak: symbol.is_weak(),
+        })
+        .collect()
+}
+
+fn diff_relocations(
+    differences: &mut Vec<ObjectDifference>,
+    old: &SectionInfo<'_>,
+    new: &SectionInfo<'_>,
+) {
+    let (matches, added) = match_lists(
+        old.relocations.iter().map(|relocation| relocation.offset),
+        new.relocations.iter().map(|relocation| relocation.offset),
+    );
+    for (relocation, new_relocation) in old.relocations.iter().zip(matches) {
+        let new_relocation = match new_relocation {
+            Some(new_relocation) => &new.relocations[new_relocation],
+            None => {
+                differences.push(ObjectDifference::RelocationRemoved {
+                    section: old.name.to_vec(),
+                    offset: relocation.offset,
+                });
+                continue;
+            }
+        };
+        let mut field = |changed: bool, field| {
+            if changed {
+                differences.push(ObjectDifference::RelocationChanged {
+                    section: old.name.to_vec(),
+                    offset: relocation.offset,
+                    field,
+                });
+            }
+        };
+        field(
+            relocation.kind != new_relocation.kind,
+            RelocationDiffField::Kind,
+        );
+        field(
+            relocation.encoding != new_relocation.encoding,
+            RelocationDiffField::Encoding,
+        );
+        field(
+            relocation.size != new_relocation.size,
+            RelocationDiffField::Size,
+        );
+        field(
+            relocation.target != new_relocation.target,
+            RelocationDiffField::Target,
+        );
+        field(
+            relocation.addend != new_relocation.addend,
+            RelocationDiffField::Addend,
+        );
+    }
+    for index in added {
+        differences.push(ObjectDifference::RelocationAdded {
+            section: old.name.to_vec(),
+            offset: new.relocations[index].offset,
+        });
+    }
+}
+
+/// Match the entries of two lists by key, in order for duplicate keys.
+///
+/// Returns the index of the matching new entry for each old entry, and the
+/// indices of the new entries without a match.
+fn match_lists<K: Ord>(
+    old: impl Iterator<Item = K>,
+    new: impl Iterator<Item = K>,
+) -> (Vec<Option<usize>>, Vec<usize>) {
+    let mut new_indices = BTreeMap::<K, Vec<usize>>::new();
+    let mut count = 0;
+    for (index, key) in new.enumerate() {
+        new_indices.entry(key).or_default().push(index);
+        count += 1;
+    }
+    // Reverse the indices so that matches can be popped in order.
+    for indices in new_indices.values_mut() {
+        indices.reverse();
+    }
+    let mut matched = vec![false; count];
+    let matches = old
+        .map(|key| {
+            let index = new_indices.get_mut(&key)?.pop()?;
+            matched[index] = true;
+            Some(index)
+        })
+        .collect();
+    let added = (0..count).filter(|&index| !matched[index]).collect();
+    (matches, added)
+}
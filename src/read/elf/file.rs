@@ -281,6 +281,8 @@ where
             (elf::EM_SPARCV9, true) => Architecture::Sparc64,
             (elf::EM_XTENSA, false) => Architecture::Xtensa,
             (elf::EM_SH, false) => Architecture::SuperH,
+            (elf::EM_TI_C2000, false) => Architecture::TiC2000,
+            (elf::EM_TI_C6000, false) => Architecture::TiC6000,
             _ => Architecture::Unknown,
         }
     }
@@ -892,6 +892,7 @@ impl<'data> CrelIterator<'data> {
     }
 
     /// Return the number of encoded relocations.
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
         self.header.count - self.state.index
     }
@@ -0,0 +1,450 @@
+use alloc::vec::Vec;
+
+use crate::endian::{BigEndian as BE, U32Bytes};
+use crate::gemdos;
+use crate::read::{
+    self, Architecture, Bytes, ComdatKind, Error, Export, FileFlags, Import,
+    NoDynamicRelocationIterator, Object, ObjectComdat, ObjectKind, ReadError, ReadRef, Result,
+    SectionIndex, SymbolIndex, SymbolKind, SymbolSection,
+};
+
+use super::{
+    GemdosSection, GemdosSectionIterator, GemdosSegment, GemdosSegmentIterator, GemdosSymbol,
+    GemdosSymbolIterator, GemdosSymbolTable,
+};
+
+/// The section index of the text segment.
+pub(super) const TEXT_SECTION: SectionIndex = SectionIndex(1);
+/// The section index of the data segment.
+pub(super) const DATA_SECTION: SectionIndex = SectionIndex(2);
+/// The section index of the bss segment.
+pub(super) const BSS_SECTION: SectionIndex = SectionIndex(3);
+
+/// An Atari TOS/GEMDOS executable.
+///
+/// Most functions in the [`Object`] trait are implemented for this type.
+#[derive(Debug)]
+pub struct GemdosFile<'data, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) data: R,
+    header: &'data gemdos::PrgHeader,
+    pub(super) symbols: Vec<GemdosSymbol<'data>>,
+    pub(super) relocations: Vec<u32>,
+}
+
+impl<'data, R: ReadRef<'data>> GemdosFile<'data, R> {
+    /// Parse the raw GEMDOS executable data.
+    pub fn parse(data: R) -> Result<Self> {
+        let header = data
+            .read_at::<gemdos::PrgHeader>(0)
+            .read_error("Invalid GEMDOS header size or alignment")?;
+        if header.ph_branch.get(BE) != gemdos::PRG_MAGIC {
+            return Err(Error("Invalid GEMDOS magic"));
+        }
+        let mut offset = core::mem::size_of::<gemdos::PrgHeader>() as u64
+            + u64::from(header.ph_tlen.get(BE))
+            + u64::from(header.ph_dlen.get(BE));
+
+        let symbol_size = header.ph_slen.get(BE);
+        let symbol_data = data
+            .read_bytes_at(offset, symbol_size.into())
+            .read_error("Invalid GEMDOS symbol table size")?;
+        let symbols = parse_symbols(symbol_data)?;
+        offset += u64::from(symbol_size);
+
+        let mut relocations = Vec::new();
+        if header.ph_absflag.get(BE) == 0 {
+            // The fixup list may be omitted if there are no fixups.
+            if let Ok(first) = data.read_at::<U32Bytes<BE>>(offset) {
+                offset += 4;
+                let mut address = first.get(BE);
+                if address != 0 {
+                    relocations.push(address);
+                    loop {
+                        let step = *data
+                            .read_at::<u8>(offset)
+                            .read_error("Missing GEMDOS fixup list terminator")?;
+                        offset += 1;
+                        match step {
+                            0 => break,
+                            1 => address = address.wrapping_add(254),
+                            _ => {
+                                address = address.wrapping_add(step.into());
+                                relocations.push(address);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(GemdosFile {
+            data,
+            header,
+            symbols,
+            relocations,
+        })
+    }
+
+    /// Returns the GEMDOS executable header.
+    #[inline]
+    pub fn header(&self) -> &'data gemdos::PrgHeader {
+        self.header
+    }
+
+    /// Returns the program flags. A combination of the `gemdos::PF_*` constants.
+    #[inline]
+    pub fn program_flags(&self) -> u32 {
+        self.header.ph_prgflags.get(BE)
+    }
+
+    /// Returns the offsets in the program of the 32-bit values that are relocated
+    /// when it is loaded.
+    #[inline]
+    pub fn fixups(&self) -> &[u32] {
+        &self.relocations
+    }
+
+    /// Return the address and size of a segment.
+    pub(super) fn section_range(&self, index: SectionIndex) -> (u64, u64) {
+        let text_size = u64::from(self.header.ph_tlen.get(BE));
+        let data_size = u64::from(self.header.ph_dlen.get(BE));
+        match index {
+            TEXT_SECTION => (0, text_size),
+            DATA_SECTION => (text_size, data_size),
+            _ => (
+                text_size + data_size,
+                u64::from(self.header.ph_blen.get(BE)),
+            ),
+        }
+    }
+
+    /// Return the range of a segment in the file.
+    pub(super) fn section_file_range(&self, index: SectionIndex) -> Option<(u64, u64)> {
+        if index == BSS_SECTION {
+            return None;
+        }
+        let (address, size) = self.section_range(index);
+        Some((
+            core::mem::size_of::<gemdos::PrgHeader>() as u64 + address,
+            size,
+        ))
+    }
+}
+
+impl<'data, R: ReadRef<'data>> read::private::Sealed for GemdosFile<'data, R> {}
+
+impl<'data, R: ReadRef<'data>> Object<'data> for GemdosFile<'data, R> {
+    type Segment<'file>
+        = GemdosSegment<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SegmentIterator<'file>
+        = GemdosSegmentIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Section<'file>
+        = GemdosSection<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SectionIterator<'file>
+        = GemdosSectionIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Comdat<'file>
+        = GemdosComdat<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type ComdatIterator<'file>
+        = GemdosComdatIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Symbol<'file>
+        = GemdosSymbol<'data>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolIterator<'file>
+        = GemdosSymbolIterator<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolTable<'file>
+        = GemdosSymbolTable<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type DynamicRelocationIterator<'file>
+        = NoDynamicRelocationIterator
+    where
+        Self: 'file,
+        'data: 'file;
+
+    fn architecture(&self) -> Architecture {
+        Architecture::M68k
+    }
+
+    #[inline]
+    fn is_little_endian(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn is_64(&self) -> bool {
+        false
+    }
+
+    fn kind(&self) -> ObjectKind {
+        ObjectKind::Executable
+    }
+
+    fn segments(&self) -> GemdosSegmentIterator<'data, '_, R> {
+        GemdosSegmentIterator {
+            file: self,
+            done: false,
+        }
+    }
+
+    fn section_by_name_bytes<'file>(
+        &'file self,
+        section_name: &[u8],
+    ) -> Option<GemdosSection<'data, 'file, R>> {
+        use crate::read::ObjectSection;
+        self.sections()
+            .find(|section| section.name_bytes() == Ok(section_name))
+    }
+
+    fn section_by_index(&self, index: SectionIndex) -> Result<GemdosSection<'data, '_, R>> {
+        match index {
+            TEXT_SECTION | DATA_SECTION | BSS_SECTION => Ok(GemdosSection { file: self, index }),
+            _ => Err(Error("Invalid GEMDOS section index")),
+        }
+    }
+
+    fn sections(&self) -> GemdosSectionIterator<'data, '_, R> {
+        GemdosSectionIterator {
+            file: self,
+            index: TEXT_SECTION.0,
+        }
+    }
+
+    #[inline]
+    fn comdats(&self) -> GemdosComdatIterator<'data, '_, R> {
+        GemdosComdatIterator { file: self }
+    }
+
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<GemdosSymbol<'data>> {
+        self.symbols
+            .get(index.0)
+            .copied()
+            .read_error("Invalid GEMDOS symbol index")
+    }
+
+    fn symbols(&self) -> GemdosSymbolIterator<'data, '_> {
+        GemdosSymbolIterator {
+            iter: self.symbols.iter(),
+        }
+    }
+
+    fn symbol_table(&self) -> Option<GemdosSymbolTable<'data, '_>> {
+        Some(GemdosSymbolTable {
+            symbols: &self.symbols,
+        })
+    }
+
+    fn dynamic_symbols(&self) -> GemdosSymbolIterator<'data, '_> {
+        GemdosSymbolIterator { iter: [].iter() }
+    }
+
+    #[inline]
+    fn dynamic_symbol_table(&self) -> Option<GemdosSymbolTable<'data, '_>> {
+        None
+    }
+
+    #[inline]
+    fn dynamic_relocations(&self) -> Option<NoDynamicRelocationIterator> {
+        None
+    }
+
+    fn imports(&self) -> Result<Vec<Import<'data>>> {
+        // GEMDOS executables don't support dynamic linking.
+        Ok(Vec::new())
+    }
+
+    fn exports(&self) -> Result<Vec<Export<'data>>> {
+        // GEMDOS executables don't support dynamic linking.
+        Ok(Vec::new())
+    }
+
+    #[inline]
+    fn has_debug_symbols(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn relative_address_base(&self) -> u64 {
+        0
+    }
+
+    /// Execution starts at the beginning of the text segment, so this is always 0.
+    #[inline]
+    fn entry(&self) -> u64 {
+        0
+    }
+
+    #[inline]
+    fn flags(&self) -> FileFlags {
+        FileFlags::None
+    }
+}
+
+/// Check whether the data at the given offset looks like a GEMDOS executable.
+///
+/// The magic number is a common instruction, so this also checks that the
+/// segments and symbol table in the header fit in the file.
+pub(crate) fn is_gemdos_file<'data, R: ReadRef<'data>>(data: R, offset: u64) -> bool {
+    let header = match data.read_at::<gemdos::PrgHeader>(offset) {
+        Ok(header) => header,
+        Err(_) => return false,
+    };
+    let size = core::mem::size_of::<gemdos::PrgHeader>() as u64
+        + u64::from(header.ph_tlen.get(BE))
+        + u64::from(header.ph_dlen.get(BE))
+        + u64::from(header.ph_slen.get(BE));
+    match data.len() {
+        Ok(len) => header.ph_branch.get(BE) == gemdos::PRG_MAGIC && offset + size <= len,
+        Err(_) => false,
+    }
+}
+
+/// Parse a DRI symbol table.
+fn parse_symbols(data: &[u8]) -> Result<Vec<GemdosSymbol<'_>>> {
+    let mut data = Bytes(data);
+    let mut symbols = Vec::new();
+    while !data.is_empty() {
+        let symbol = data
+            .read::<gemdos::DriSymbol>()
+            .read_error("Invalid GEMDOS symbol table size")?;
+        let sym_type = symbol.sym_type.get(BE);
+        let mut extended_name: &[u8] = &[];
+        if sym_type & gemdos::DRI_LONG_NAME_MASK == gemdos::DRI_LONG_NAME {
+            extended_name = data
+                .read_bytes(core::mem::size_of::<gemdos::DriSymbol>())
+                .read_error("Missing GEMDOS extended symbol name")?
+                .0;
+        }
+        let section = if sym_type & gemdos::DRI_EXTERNAL != 0 {
+            SymbolSection::Undefined
+        } else if sym_type & gemdos::DRI_TEXT != 0 {
+            SymbolSection::Section(TEXT_SECTION)
+        } else if sym_type & gemdos::DRI_DATA != 0 {
+            SymbolSection::Section(DATA_SECTION)
+        } else if sym_type & gemdos::DRI_BSS != 0 {
+            SymbolSection::Section(BSS_SECTION)
+        } else if sym_type & gemdos::DRI_DEFINED != 0 {
+            SymbolSection::Absolute
+        } else {
+            SymbolSection::Unknown
+        };
+        let kind = match section {
+            SymbolSection::Section(TEXT_SECTION) => SymbolKind::Text,
+            SymbolSection::Section(_) => SymbolKind::Data,
+            _ => SymbolKind::Unknown,
+        };
+        symbols.push(GemdosSymbol {
+            index: SymbolIndex(symbols.len()),
+            name: trim_name(&symbol.name),
+            extended_name: trim_name(extended_name),
+            sym_type,
+            section,
+            kind,
+            value: symbol.value.get(BE),
+        });
+    }
+    Ok(symbols)
+}
+
+fn trim_name(name: &[u8]) -> &[u8] {
+    let end = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+    &name[..end]
+}
+
+/// An iterator for the COMDAT section groups in a [`GemdosFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct GemdosComdatIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file GemdosFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for GemdosComdatIterator<'data, 'file, R> {
+    type Item = GemdosComdat<'data, 'file, R>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+/// A COMDAT section group in a [`GemdosFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct GemdosComdat<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file GemdosFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for GemdosComdat<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectComdat<'data> for GemdosComdat<'data, 'file, R> {
+    type SectionIterator = GemdosComdatSectionIterator<'data, 'file, R>;
+
+    #[inline]
+    fn kind(&self) -> ComdatKind {
+        unreachable!();
+    }
+
+    #[inline]
+    fn symbol(&self) -> SymbolIndex {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn sections(&self) -> Self::SectionIterator {
+        unreachable!();
+    }
+}
+
+/// An iterator for the sections in a COMDAT section group in a [`GemdosFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct GemdosComdatSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file GemdosFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for GemdosComdatSectionIterator<'data, 'file, R> {
+    type Item = SectionIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
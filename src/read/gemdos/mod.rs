@@ -0,0 +1,31 @@
+//! Support for reading Atari TOS/GEMDOS executables.
+//!
+//! GEMDOS executables are used by the Atari ST and its successors, and are
+//! usually named with a `.PRG`, `.TOS`, `.TTP` or `.APP` extension.
+//!
+//! ## High level API
+//!
+//! [`GemdosFile`] implements the [`Object`](crate::read::Object) trait for
+//! GEMDOS executables. The text, data and bss segments are sections, and
+//! they are loaded contiguously as a single segment. The program is relocated
+//! when it is loaded, so section addresses and symbol addresses are offsets
+//! from the start of the text segment.
+//!
+//! The entries in the fixup list are relocations with the text section as
+//! the target. Symbols are read from the DRI symbol table, if present.
+//!
+//! ## Low level API
+//!
+//! [`GemdosFile`] also provides access to the header ([`GemdosFile::header`])
+//! and the decoded fixup list ([`GemdosFile::fixups`]).
+mod file;
+pub use file::*;
+
+mod section;
+pub use section::*;
+
+mod symbol;
+pub use symbol::*;
+
+mod relocation;
+pub use relocation::*;
@@ -0,0 +1,55 @@
+use core::slice;
+
+use crate::read::{
+    ReadRef, Relocation, RelocationEncoding, RelocationFlags, RelocationKind, RelocationTarget,
+};
+
+use super::{GemdosFile, TEXT_SECTION};
+
+/// An iterator for the relocations in a [`GemdosSection`](super::GemdosSection).
+///
+/// These are the entries in the fixup list that are within the section.
+/// Each relocation is a 32-bit absolute relocation to the start of the text
+/// section, and the addend is stored in the relocated location.
+#[derive(Debug)]
+pub struct GemdosRelocationIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    pub(super) file: &'file GemdosFile<'data, R>,
+    pub(super) relocations: slice::Iter<'file, u32>,
+    /// The address and size of the section.
+    pub(super) range: (u64, u64),
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for GemdosRelocationIterator<'data, 'file, R> {
+    type Item = (u64, Relocation);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (address, section_size) = self.range;
+        for &offset in self.relocations.by_ref() {
+            let offset = u64::from(offset);
+            if offset < address || offset - address >= section_size {
+                continue;
+            }
+            let kind = RelocationKind::Absolute;
+            let encoding = RelocationEncoding::Generic;
+            let size = 32;
+            return Some((
+                offset - address,
+                Relocation {
+                    kind,
+                    encoding,
+                    size,
+                    target: RelocationTarget::Section(TEXT_SECTION),
+                    addend: 0,
+                    implicit_addend: true,
+                    flags: RelocationFlags::Generic {
+                        kind,
+                        encoding,
+                        size,
+                    },
+                },
+            ));
+        }
+        None
+    }
+}
@@ -0,0 +1,236 @@
+use core::str;
+
+use crate::read::{
+    self, CompressedData, CompressedFileRange, ObjectSection, ObjectSegment, ReadError, ReadRef,
+    RelocationMap, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags,
+};
+
+use super::{GemdosFile, GemdosRelocationIterator, BSS_SECTION, DATA_SECTION, TEXT_SECTION};
+
+/// An iterator for the sections in a [`GemdosFile`].
+#[derive(Debug)]
+pub struct GemdosSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file GemdosFile<'data, R>,
+    pub(super) index: usize,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for GemdosSectionIterator<'data, 'file, R> {
+    type Item = GemdosSection<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = SectionIndex(self.index);
+        if index.0 > BSS_SECTION.0 {
+            return None;
+        }
+        self.index += 1;
+        Some(GemdosSection {
+            file: self.file,
+            index,
+        })
+    }
+}
+
+/// A section in a [`GemdosFile`].
+///
+/// The text, data and bss segments are sections with the indices 1, 2 and 3.
+#[derive(Debug)]
+pub struct GemdosSection<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file GemdosFile<'data, R>,
+    pub(super) index: SectionIndex,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for GemdosSection<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSection<'data> for GemdosSection<'data, 'file, R> {
+    type RelocationIterator = GemdosRelocationIterator<'data, 'file, R>;
+
+    #[inline]
+    fn index(&self) -> SectionIndex {
+        self.index
+    }
+
+    #[inline]
+    fn address(&self) -> u64 {
+        self.file.section_range(self.index).0
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.file.section_range(self.index).1
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        2
+    }
+
+    #[inline]
+    fn file_range(&self) -> Option<(u64, u64)> {
+        self.file.section_file_range(self.index)
+    }
+
+    fn data(&self) -> Result<&'data [u8]> {
+        match self.file_range() {
+            Some((offset, size)) => self
+                .file
+                .data
+                .read_bytes_at(offset, size)
+                .read_error("Invalid GEMDOS section size"),
+            None => Ok(&[]),
+        }
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        Ok(read::util::data_range(
+            self.data()?,
+            self.address(),
+            address,
+            size,
+        ))
+    }
+
+    #[inline]
+    fn compressed_file_range(&self) -> Result<CompressedFileRange> {
+        Ok(CompressedFileRange::none(self.file_range()))
+    }
+
+    #[inline]
+    fn compressed_data(&self) -> Result<CompressedData<'data>> {
+        self.data().map(CompressedData::none)
+    }
+
+    /// Return `.text`, `.data` or `.bss`.
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        Ok(match self.index {
+            TEXT_SECTION => b".text",
+            DATA_SECTION => b".data",
+            _ => b".bss",
+        })
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name_bytes()?)
+            .ok()
+            .read_error("Non UTF-8 GEMDOS section name")
+    }
+
+    #[inline]
+    fn segment_name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn segment_name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    fn kind(&self) -> SectionKind {
+        match self.index {
+            TEXT_SECTION => SectionKind::Text,
+            DATA_SECTION => SectionKind::Data,
+            _ => SectionKind::UninitializedData,
+        }
+    }
+
+    fn relocations(&self) -> GemdosRelocationIterator<'data, 'file, R> {
+        GemdosRelocationIterator {
+            file: self.file,
+            relocations: self.file.relocations.iter(),
+            range: self.file.section_range(self.index),
+        }
+    }
+
+    fn relocation_map(&self) -> Result<RelocationMap> {
+        RelocationMap::new(self.file, self)
+    }
+
+    #[inline]
+    fn flags(&self) -> SectionFlags {
+        SectionFlags::None
+    }
+}
+
+/// An iterator for the segments in a [`GemdosFile`].
+#[derive(Debug)]
+pub struct GemdosSegmentIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file GemdosFile<'data, R>,
+    pub(super) done: bool,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for GemdosSegmentIterator<'data, 'file, R> {
+    type Item = GemdosSegment<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        self.done = true;
+        Some(GemdosSegment { file: self.file })
+    }
+}
+
+/// A segment in a [`GemdosFile`].
+///
+/// The text, data and bss segments are loaded contiguously, so they are
+/// a single segment.
+#[derive(Debug)]
+pub struct GemdosSegment<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    file: &'file GemdosFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for GemdosSegment<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSegment<'data> for GemdosSegment<'data, 'file, R> {
+    #[inline]
+    fn address(&self) -> u64 {
+        0
+    }
+
+    fn size(&self) -> u64 {
+        let (address, size) = self.file.section_range(BSS_SECTION);
+        address + size
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        2
+    }
+
+    fn file_range(&self) -> (u64, u64) {
+        let (offset, _) = self.file.section_file_range(TEXT_SECTION).unwrap_or((0, 0));
+        (offset, self.file.section_range(BSS_SECTION).0)
+    }
+
+    fn data(&self) -> Result<&'data [u8]> {
+        let (offset, size) = self.file_range();
+        self.file
+            .data
+            .read_bytes_at(offset, size)
+            .read_error("Invalid GEMDOS segment size")
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        Ok(read::util::data_range(
+            self.data()?,
+            self.address(),
+            address,
+            size,
+        ))
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn flags(&self) -> SegmentFlags {
+        SegmentFlags::None
+    }
+}
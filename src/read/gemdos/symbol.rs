@@ -0,0 +1,179 @@
+use core::str;
+
+use crate::gemdos;
+use crate::read::{
+    self, ObjectSymbol, ObjectSymbolTable, ReadError, Result, SectionIndex, SymbolFlags,
+    SymbolIndex, SymbolKind, SymbolScope, SymbolSection,
+};
+
+/// A symbol in a [`GemdosFile`](super::GemdosFile).
+///
+/// This is an entry in the DRI symbol table. GST extended names use two
+/// entries, and only the first entry is a symbol.
+#[derive(Debug, Clone, Copy)]
+pub struct GemdosSymbol<'data> {
+    pub(super) index: SymbolIndex,
+    pub(super) name: &'data [u8],
+    pub(super) extended_name: &'data [u8],
+    pub(super) sym_type: u16,
+    pub(super) section: SymbolSection,
+    pub(super) kind: SymbolKind,
+    pub(super) value: u32,
+}
+
+impl<'data> GemdosSymbol<'data> {
+    /// Return the continuation of a GST extended name.
+    ///
+    /// The full name is the concatenation of [`ObjectSymbol::name_bytes`] and
+    /// this. This is empty if the symbol doesn't have an extended name.
+    #[inline]
+    pub fn extended_name(&self) -> &'data [u8] {
+        self.extended_name
+    }
+
+    /// Return the symbol type. A combination of the `gemdos::DRI_*` constants.
+    #[inline]
+    pub fn sym_type(&self) -> u16 {
+        self.sym_type
+    }
+}
+
+impl<'data> read::private::Sealed for GemdosSymbol<'data> {}
+
+impl<'data> ObjectSymbol<'data> for GemdosSymbol<'data> {
+    #[inline]
+    fn index(&self) -> SymbolIndex {
+        self.index
+    }
+
+    /// Return the first 8 bytes of the name.
+    ///
+    /// See [`GemdosSymbol::extended_name`] for the rest of a GST extended name.
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        Ok(self.name)
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name)
+            .ok()
+            .read_error("Non UTF-8 GEMDOS symbol name")
+    }
+
+    /// Return the offset of the symbol from the start of the text segment,
+    /// or its absolute value.
+    fn address(&self) -> u64 {
+        match self.section {
+            SymbolSection::Undefined => 0,
+            _ => self.value.into(),
+        }
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        0
+    }
+
+    #[inline]
+    fn kind(&self) -> SymbolKind {
+        self.kind
+    }
+
+    #[inline]
+    fn section(&self) -> SymbolSection {
+        self.section
+    }
+
+    #[inline]
+    fn is_undefined(&self) -> bool {
+        self.section == SymbolSection::Undefined
+    }
+
+    #[inline]
+    fn is_definition(&self) -> bool {
+        matches!(
+            self.section,
+            SymbolSection::Section(_) | SymbolSection::Absolute
+        )
+    }
+
+    #[inline]
+    fn is_common(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn is_weak(&self) -> bool {
+        false
+    }
+
+    fn scope(&self) -> SymbolScope {
+        if self.is_undefined() {
+            SymbolScope::Unknown
+        } else if self.is_global() {
+            SymbolScope::Linkage
+        } else {
+            SymbolScope::Compilation
+        }
+    }
+
+    #[inline]
+    fn is_global(&self) -> bool {
+        self.sym_type & gemdos::DRI_GLOBAL != 0
+    }
+
+    #[inline]
+    fn is_local(&self) -> bool {
+        !self.is_global()
+    }
+
+    #[inline]
+    fn flags(&self) -> SymbolFlags<SectionIndex, SymbolIndex> {
+        SymbolFlags::None
+    }
+}
+
+/// A symbol table in a [`GemdosFile`](super::GemdosFile).
+#[derive(Debug, Clone, Copy)]
+pub struct GemdosSymbolTable<'data, 'file> {
+    pub(super) symbols: &'file [GemdosSymbol<'data>],
+}
+
+impl<'data, 'file> read::private::Sealed for GemdosSymbolTable<'data, 'file> {}
+
+impl<'data, 'file> ObjectSymbolTable<'data> for GemdosSymbolTable<'data, 'file> {
+    type Symbol = GemdosSymbol<'data>;
+    type SymbolIterator = GemdosSymbolIterator<'data, 'file>;
+
+    fn symbols(&self) -> Self::SymbolIterator {
+        GemdosSymbolIterator {
+            iter: self.symbols.iter(),
+        }
+    }
+
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<Self::Symbol> {
+        self.symbols
+            .get(index.0)
+            .copied()
+            .read_error("Invalid GEMDOS symbol index")
+    }
+}
+
+/// An iterator for the symbols in a [`GemdosFile`](super::GemdosFile).
+#[derive(Debug)]
+pub struct GemdosSymbolIterator<'data, 'file> {
+    pub(super) iter: core::slice::Iter<'file, GemdosSymbol<'data>>,
+}
+
+impl<'data, 'file> Iterator for GemdosSymbolIterator<'data, 'file> {
+    type Item = GemdosSymbol<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().copied()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
@@ -0,0 +1,722 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use crate::endian::BigEndian as BE;
+use crate::goff;
+use crate::pod::{self, Pod};
+use crate::read::{
+    self, Architecture, ComdatKind, Error, Export, FileFlags, Import, Map,
+    NoDynamicRelocationIterator, Object, ObjectComdat, ObjectKind, ReadError, ReadRef,
+    RelocationEncoding, RelocationKind, RelocationTarget, Result, SectionIndex, SymbolIndex,
+};
+
+use super::{
+    GoffRelocation, GoffSection, GoffSectionIterator, GoffSegment, GoffSegmentIterator, GoffSymbol,
+    GoffSymbolIterator, GoffSymbolTable,
+};
+
+/// A logical record in a GOFF file.
+///
+/// This is a physical record and its continuation records. The data of the
+/// continuation records follows the first record, so the offsets of the
+/// fields are the same as for a record that isn't continued.
+#[derive(Debug, Clone)]
+pub struct GoffRecord<'data> {
+    offset: u64,
+    data: Cow<'data, [u8]>,
+}
+
+impl<'data> GoffRecord<'data> {
+    /// Returns the offset of the first physical record in the file.
+    #[inline]
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the record type. One of the `goff::GOFF_RT_*` constants.
+    #[inline]
+    pub fn record_type(&self) -> u8 {
+        self.data[1] >> goff::GOFF_RT_SHIFT
+    }
+
+    /// Returns the data of the logical record, including the prefix of the
+    /// first physical record.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Parse the fixed fields at the start of the record.
+    pub fn fields<T: Pod>(&self) -> Result<&T> {
+        pod::from_bytes(&self.data)
+            .map(|(fields, _)| fields)
+            .read_error("Invalid GOFF record size")
+    }
+
+    /// Return the bytes at the given offset in the record.
+    ///
+    /// The bytes are borrowed from the file data if the record isn't continued.
+    pub fn bytes(&self, offset: usize, size: usize) -> Result<Cow<'data, [u8]>> {
+        let range = offset
+            ..offset
+                .checked_add(size)
+                .read_error("Invalid GOFF field size")?;
+        match self.data {
+            Cow::Borrowed(data) => data.get(range).map(Cow::Borrowed),
+            Cow::Owned(ref data) => data.get(range).map(|data| Cow::Owned(data.to_vec())),
+        }
+        .read_error("Invalid GOFF field size")
+    }
+}
+
+/// An iterator for the logical records in a GOFF file.
+#[derive(Debug)]
+pub struct GoffRecordIterator<'data, R: ReadRef<'data> = &'data [u8]> {
+    data: R,
+    offset: u64,
+    size: u64,
+    marker: core::marker::PhantomData<&'data ()>,
+}
+
+impl<'data, R: ReadRef<'data>> GoffRecordIterator<'data, R> {
+    /// Create an iterator for the logical records in the given data.
+    pub fn new(data: R) -> Self {
+        GoffRecordIterator {
+            data,
+            offset: 0,
+            size: data.len().unwrap_or(0),
+            marker: core::marker::PhantomData,
+        }
+    }
+
+    fn read_record(&mut self) -> Result<GoffRecord<'data>> {
+        let offset = self.offset;
+        let first = self.read_physical()?;
+        if first[1] & goff::GOFF_CONTINUATION != 0 {
+            return Err(Error("Unexpected GOFF continuation record"));
+        }
+        let mut flags = first[1];
+        if flags & goff::GOFF_CONTINUED == 0 {
+            return Ok(GoffRecord {
+                offset,
+                data: Cow::Borrowed(first),
+            });
+        }
+        let mut data = first.to_vec();
+        while flags & goff::GOFF_CONTINUED != 0 {
+            let next = self.read_physical()?;
+            if next[1] & goff::GOFF_CONTINUATION == 0
+                || next[1] >> goff::GOFF_RT_SHIFT != first[1] >> goff::GOFF_RT_SHIFT
+            {
+                return Err(Error("Missing GOFF continuation record"));
+            }
+            data.extend_from_slice(&next[goff::GOFF_PREFIX_LENGTH..]);
+            flags = next[1];
+        }
+        Ok(GoffRecord {
+            offset,
+            data: Cow::Owned(data),
+        })
+    }
+
+    fn read_physical(&mut self) -> Result<&'data [u8]> {
+        let record = self
+            .data
+            .read_bytes_at(self.offset, goff::GOFF_RECORD_LENGTH as u64)
+            .read_error("Truncated GOFF record")?;
+        if record[0] != goff::GOFF_PTV_PREFIX {
+            return Err(Error("Invalid GOFF record prefix"));
+        }
+        self.offset += goff::GOFF_RECORD_LENGTH as u64;
+        Ok(record)
+    }
+}
+
+impl<'data, R: ReadRef<'data>> Iterator for GoffRecordIterator<'data, R> {
+    type Item = Result<GoffRecord<'data>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.size {
+            return None;
+        }
+        let result = self.read_record();
+        if result.is_err() {
+            self.offset = self.size;
+        }
+        Some(result)
+    }
+}
+
+/// An entry from an ESD record in a [`GoffFile`].
+#[derive(Debug, Clone)]
+pub struct GoffEsd<'data> {
+    pub(super) record: goff::EsdRecord,
+    pub(super) name: Cow<'data, [u8]>,
+}
+
+impl<'data> GoffEsd<'data> {
+    /// Returns the fixed fields of the ESD record.
+    #[inline]
+    pub fn record(&self) -> &goff::EsdRecord {
+        &self.record
+    }
+
+    /// Returns the symbol type. One of the `goff::ESD_ST_*` constants.
+    #[inline]
+    pub fn symbol_type(&self) -> u8 {
+        self.record.symbol_type
+    }
+
+    /// Returns the identifier of this entry.
+    #[inline]
+    pub fn esdid(&self) -> u32 {
+        self.record.esdid.get(BE)
+    }
+
+    /// Returns the identifier of the parent entry, or 0 if there is none.
+    #[inline]
+    pub fn parent_esdid(&self) -> u32 {
+        self.record.parent_esdid.get(BE)
+    }
+
+    /// Returns the name. This is usually encoded in EBCDIC.
+    #[inline]
+    pub fn name(&self) -> &[u8] {
+        &self.name
+    }
+}
+
+/// The data for an element or part in a [`GoffFile`].
+#[derive(Debug)]
+pub(super) struct GoffSectionData<'data> {
+    /// The index of the entry in [`GoffFile::esds`].
+    pub(super) esd: usize,
+    /// The offset and data of each TXT record.
+    pub(super) text: Vec<(u32, Cow<'data, [u8]>)>,
+    pub(super) relocations: Vec<GoffRelocation>,
+}
+
+/// A relocation item from an RLD record, before the pointers are resolved.
+#[derive(Debug, Clone, Copy)]
+struct RldEntry {
+    item: goff::RldItem,
+    rid: u32,
+    pid: u32,
+    offset: u64,
+}
+
+/// A GOFF object file.
+///
+/// Most functions in the [`Object`] trait are implemented for this type.
+#[derive(Debug)]
+pub struct GoffFile<'data, R: ReadRef<'data> = &'data [u8]> {
+    data: R,
+    esds: Vec<GoffEsd<'data>>,
+    esd_indices: Map<u32, usize>,
+    pub(super) sections: Vec<GoffSectionData<'data>>,
+    section_indices: Map<u32, SectionIndex>,
+    /// The index in `esds` of each symbol.
+    pub(super) symbols: Vec<usize>,
+    symbol_indices: Map<u32, SymbolIndex>,
+}
+
+impl<'data, R: ReadRef<'data>> GoffFile<'data, R> {
+    /// Parse the raw GOFF file data.
+    pub fn parse(data: R) -> Result<Self> {
+        let mut file = GoffFile {
+            data,
+            esds: Vec::new(),
+            esd_indices: Map::new(),
+            sections: Vec::new(),
+            section_indices: Map::new(),
+            symbols: Vec::new(),
+            symbol_indices: Map::new(),
+        };
+        let mut rld_entries = Vec::new();
+        let mut records = GoffRecordIterator::new(data);
+        match records.next() {
+            Some(Ok(record)) if record.record_type() == goff::GOFF_RT_HDR => {}
+            Some(Err(e)) => return Err(e),
+            _ => return Err(Error("Missing GOFF HDR record")),
+        }
+        for record in records {
+            let record = record?;
+            match record.record_type() {
+                goff::GOFF_RT_ESD => file.parse_esd(&record)?,
+                goff::GOFF_RT_TXT => file.parse_txt(&record)?,
+                goff::GOFF_RT_RLD => parse_rld(&record, &mut rld_entries)?,
+                goff::GOFF_RT_END => break,
+                // LEN records give the lengths of elements with deferred lengths,
+                // which are computed from the TXT records instead.
+                goff::GOFF_RT_LEN => {}
+                _ => return Err(Error("Unknown GOFF record type")),
+            }
+        }
+
+        for (index, esd) in file.esds.iter().enumerate() {
+            if matches!(
+                esd.symbol_type(),
+                goff::ESD_ST_LD | goff::ESD_ST_PR | goff::ESD_ST_ER
+            ) {
+                file.symbol_indices
+                    .insert(esd.esdid(), SymbolIndex(file.symbols.len()));
+                file.symbols.push(index);
+            }
+        }
+        for entry in rld_entries {
+            file.add_relocation(entry)?;
+        }
+        Ok(file)
+    }
+
+    fn parse_esd(&mut self, record: &GoffRecord<'data>) -> Result<()> {
+        let fields = *record.fields::<goff::EsdRecord>()?;
+        let name = record.bytes(
+            core::mem::size_of::<goff::EsdRecord>(),
+            fields.name_length.get(BE).into(),
+        )?;
+        let esdid = fields.esdid.get(BE);
+        let index = self.esds.len();
+        if self.esd_indices.insert(esdid, index).is_some() {
+            return Err(Error("Duplicate GOFF ESDID"));
+        }
+        if matches!(fields.symbol_type, goff::ESD_ST_ED | goff::ESD_ST_PR) {
+            self.section_indices
+                .insert(esdid, SectionIndex(self.sections.len() + 1));
+            self.sections.push(GoffSectionData {
+                esd: index,
+                text: Vec::new(),
+                relocations: Vec::new(),
+            });
+        }
+        self.esds.push(GoffEsd {
+            record: fields,
+            name,
+        });
+        Ok(())
+    }
+
+    fn parse_txt(&mut self, record: &GoffRecord<'data>) -> Result<()> {
+        let fields = record.fields::<goff::TxtRecord>()?;
+        if fields.text_encoding.get(BE) != 0 {
+            return Err(Error("Unsupported GOFF text encoding"));
+        }
+        let index = self
+            .section_indices
+            .get(&fields.element_esdid.get(BE))
+            .read_error("Invalid GOFF TXT element ESDID")?;
+        let text = record.bytes(
+            core::mem::size_of::<goff::TxtRecord>(),
+            fields.data_length.get(BE).into(),
+        )?;
+        self.sections[index.0 - 1]
+            .text
+            .push((fields.offset.get(BE), text));
+        Ok(())
+    }
+
+    fn add_relocation(&mut self, entry: RldEntry) -> Result<()> {
+        let section = *self
+            .section_indices
+            .get(&entry.pid)
+            .read_error("Invalid GOFF RLD P pointer")?;
+        let target = if let Some(symbol) = self.symbol_indices.get(&entry.rid) {
+            RelocationTarget::Symbol(*symbol)
+        } else if let Some(section) = self.section_indices.get(&entry.rid) {
+            RelocationTarget::Section(*section)
+        } else {
+            return Err(Error("Invalid GOFF RLD R pointer"));
+        };
+        let item = entry.item;
+        let (kind, encoding) = match (item.reference_type(), item.action()) {
+            (goff::RLD_RT_ADDRESS, goff::RLD_ACT_ADD) => {
+                (RelocationKind::Absolute, RelocationEncoding::Generic)
+            }
+            (goff::RLD_RT_RELATIVE_IMMEDIATE, goff::RLD_ACT_ADD) => {
+                (RelocationKind::Relative, RelocationEncoding::S390xDbl)
+            }
+            _ => (RelocationKind::Unknown, RelocationEncoding::Generic),
+        };
+        self.sections[section.0 - 1]
+            .relocations
+            .push(GoffRelocation {
+                offset: entry.offset,
+                kind,
+                encoding,
+                size: item.target_length.wrapping_mul(8),
+                target,
+                item,
+            });
+        Ok(())
+    }
+
+    /// Returns the entries from the ESD records.
+    #[inline]
+    pub fn esds(&self) -> &[GoffEsd<'data>] {
+        &self.esds
+    }
+
+    /// Returns the ESD entry with the given identifier.
+    pub fn esd(&self, esdid: u32) -> Option<&GoffEsd<'data>> {
+        self.esd_indices.get(&esdid).map(|index| &self.esds[*index])
+    }
+
+    /// Returns an iterator for the logical records in the file.
+    pub fn records(&self) -> GoffRecordIterator<'data, R> {
+        GoffRecordIterator::new(self.data)
+    }
+
+    /// Returns the contents of an element or part, combined from its TXT
+    /// records.
+    ///
+    /// Returns an error if a TXT record extends past the length of the element
+    /// or part in its ESD record.
+    pub fn section_contents(&self, index: SectionIndex) -> Result<Vec<u8>> {
+        let section = index
+            .0
+            .checked_sub(1)
+            .and_then(|index| self.sections.get(index))
+            .read_error("Invalid GOFF section index")?;
+        let length = self.esds[section.esd].record.length.get(BE) as usize;
+        let mut contents = Vec::new();
+        contents
+            .try_reserve_exact(length)
+            .ok()
+            .read_error("GOFF section data allocation failed")?;
+        for (offset, text) in &section.text {
+            let start = *offset as usize;
+            let end = start
+                .checked_add(text.len())
+                .filter(|end| *end <= length)
+                .read_error("Invalid GOFF TXT offset")?;
+            if contents.len() < end {
+                contents.resize(end, 0);
+            }
+            contents[start..end].copy_from_slice(text);
+        }
+        Ok(contents)
+    }
+
+    pub(super) fn esd_at(&self, index: usize) -> &GoffEsd<'data> {
+        &self.esds[index]
+    }
+
+    /// Return the section index of the element or part with the given identifier.
+    pub(super) fn section_index(&self, esdid: u32) -> Option<SectionIndex> {
+        self.section_indices.get(&esdid).copied()
+    }
+}
+
+/// Parse the relocation items in an RLD record.
+fn parse_rld(record: &GoffRecord<'_>, entries: &mut Vec<RldEntry>) -> Result<()> {
+    let fields = record.fields::<goff::RldRecord>()?;
+    let items = record.bytes(
+        core::mem::size_of::<goff::RldRecord>(),
+        fields.length.get(BE).into(),
+    )?;
+    let mut items = read::Bytes(&items);
+    // The pointers and offset may be omitted if they are the same as the previous item.
+    let mut previous = entries.last().copied();
+    while !items.is_empty() {
+        let item = *items
+            .read::<goff::RldItem>()
+            .read_error("Invalid GOFF RLD item")?;
+        let mut read_u32 = |same: u8, value: Option<u32>| -> Result<u32> {
+            if item.flags & same != 0 {
+                value.read_error("Missing GOFF RLD item field")
+            } else {
+                items
+                    .read::<crate::endian::U32Bytes<BE>>()
+                    .map(|value| value.get(BE))
+                    .read_error("Invalid GOFF RLD item")
+            }
+        };
+        let rid = read_u32(goff::RLD_FLAG_SAME_RID, previous.map(|entry| entry.rid))?;
+        let pid = read_u32(goff::RLD_FLAG_SAME_PID, previous.map(|entry| entry.pid))?;
+        let offset = if item.flags & goff::RLD_FLAG_SAME_OFFSET != 0 {
+            previous
+                .map(|entry| entry.offset)
+                .read_error("Missing GOFF RLD item field")?
+        } else if item.flags & goff::RLD_FLAG_OFFSET_8 != 0 {
+            items
+                .read::<crate::endian::U64Bytes<BE>>()
+                .read_error("Invalid GOFF RLD item")?
+                .get(BE)
+        } else {
+            items
+                .read::<crate::endian::U32Bytes<BE>>()
+                .read_error("Invalid GOFF RLD item")?
+                .get(BE)
+                .into()
+        };
+        if item.flags & goff::RLD_FLAG_EA_PRESENT != 0 {
+            // The extended attribute ESDID and offset.
+            items.skip(8).read_error("Invalid GOFF RLD item")?;
+        }
+        let entry = RldEntry {
+            item,
+            rid,
+            pid,
+            offset,
+        };
+        entries.push(entry);
+        previous = Some(entry);
+    }
+    Ok(())
+}
+
+impl<'data, R: ReadRef<'data>> read::private::Sealed for GoffFile<'data, R> {}
+
+impl<'data, R: ReadRef<'data>> Object<'data> for GoffFile<'data, R> {
+    type Segment<'file>
+        = GoffSegment<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SegmentIterator<'file>
+        = GoffSegmentIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Section<'file>
+        = GoffSection<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SectionIterator<'file>
+        = GoffSectionIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Comdat<'file>
+        = GoffComdat<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type ComdatIterator<'file>
+        = GoffComdatIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Symbol<'file>
+        = GoffSymbol<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolIterator<'file>
+        = GoffSymbolIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolTable<'file>
+        = GoffSymbolTable<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type DynamicRelocationIterator<'file>
+        = NoDynamicRelocationIterator
+    where
+        Self: 'file,
+        'data: 'file;
+
+    fn architecture(&self) -> Architecture {
+        Architecture::S390x
+    }
+
+    #[inline]
+    fn is_little_endian(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn is_64(&self) -> bool {
+        true
+    }
+
+    fn kind(&self) -> ObjectKind {
+        ObjectKind::Relocatable
+    }
+
+    fn segments(&self) -> GoffSegmentIterator<'data, '_, R> {
+        GoffSegmentIterator { file: self }
+    }
+
+    fn section_by_name_bytes<'file>(
+        &'file self,
+        section_name: &[u8],
+    ) -> Option<GoffSection<'data, 'file, R>> {
+        use crate::read::ObjectSection;
+        self.sections()
+            .find(|section| section.name_bytes() == Ok(section_name))
+    }
+
+    fn section_by_index(&self, index: SectionIndex) -> Result<GoffSection<'data, '_, R>> {
+        let section = index
+            .0
+            .checked_sub(1)
+            .and_then(|index| self.sections.get(index))
+            .read_error("Invalid GOFF section index")?;
+        Ok(GoffSection {
+            file: self,
+            index,
+            section,
+        })
+    }
+
+    fn sections(&self) -> GoffSectionIterator<'data, '_, R> {
+        GoffSectionIterator {
+            file: self,
+            iter: self.sections.iter().enumerate(),
+        }
+    }
+
+    #[inline]
+    fn comdats(&self) -> GoffComdatIterator<'data, '_, R> {
+        GoffComdatIterator { file: self }
+    }
+
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<GoffSymbol<'data, '_, R>> {
+        let esd = *self
+            .symbols
+            .get(index.0)
+            .read_error("Invalid GOFF symbol index")?;
+        Ok(GoffSymbol {
+            file: self,
+            index,
+            esd: &self.esds[esd],
+        })
+    }
+
+    fn symbols(&self) -> GoffSymbolIterator<'data, '_, R> {
+        GoffSymbolIterator {
+            file: self,
+            index: 0,
+        }
+    }
+
+    fn symbol_table(&self) -> Option<GoffSymbolTable<'data, '_, R>> {
+        Some(GoffSymbolTable { file: self })
+    }
+
+    fn dynamic_symbols(&self) -> GoffSymbolIterator<'data, '_, R> {
+        GoffSymbolIterator {
+            file: self,
+            index: self.symbols.len(),
+        }
+    }
+
+    #[inline]
+    fn dynamic_symbol_table(&self) -> Option<GoffSymbolTable<'data, '_, R>> {
+        None
+    }
+
+    #[inline]
+    fn dynamic_relocations(&self) -> Option<NoDynamicRelocationIterator> {
+        None
+    }
+
+    fn imports(&self) -> Result<Vec<Import<'data>>> {
+        // GOFF object files don't have a separate import table.
+        Ok(Vec::new())
+    }
+
+    fn exports(&self) -> Result<Vec<Export<'data>>> {
+        // GOFF object files don't have a separate export table.
+        Ok(Vec::new())
+    }
+
+    #[inline]
+    fn has_debug_symbols(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn relative_address_base(&self) -> u64 {
+        0
+    }
+
+    #[inline]
+    fn entry(&self) -> u64 {
+        0
+    }
+
+    #[inline]
+    fn flags(&self) -> FileFlags {
+        FileFlags::None
+    }
+}
+
+/// An iterator for the COMDAT section groups in a [`GoffFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct GoffComdatIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file GoffFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for GoffComdatIterator<'data, 'file, R> {
+    type Item = GoffComdat<'data, 'file, R>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+/// A COMDAT section group in a [`GoffFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct GoffComdat<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file GoffFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for GoffComdat<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectComdat<'data> for GoffComdat<'data, 'file, R> {
+    type SectionIterator = GoffComdatSectionIterator<'data, 'file, R>;
+
+    #[inline]
+    fn kind(&self) -> ComdatKind {
+        unreachable!();
+    }
+
+    #[inline]
+    fn symbol(&self) -> SymbolIndex {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn sections(&self) -> Self::SectionIterator {
+        unreachable!();
+    }
+}
+
+/// An iterator for the sections in a COMDAT section group in a [`GoffFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct GoffComdatSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file GoffFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for GoffComdatSectionIterator<'data, 'file, R> {
+    type Item = SectionIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
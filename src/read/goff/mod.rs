@@ -0,0 +1,35 @@
+//! Support for reading GOFF object files.
+//!
+//! The Generalized Object File Format (GOFF) is used by z/OS compilers and
+//! assemblers. A GOFF file is a sequence of fixed length 80-byte records.
+//! Records that don't fit in a single physical record are continued in the
+//! following records.
+//!
+//! ## High level API
+//!
+//! [`GoffFile`] implements the [`Object`](crate::read::Object) trait for GOFF
+//! object files. Each element (ED) and part (PR) is a section, and the data of
+//! the section is combined from its TXT records by
+//! [`ObjectSection::uncompressed_data`](crate::read::ObjectSection::uncompressed_data).
+//!
+//! Label definitions (LD), part references (PR), and external references (ER)
+//! are symbols. The items in RLD records are relocations.
+//!
+//! Names in GOFF files are usually encoded in EBCDIC, so the `name` functions
+//! will often fail. Use the `name_bytes` functions instead.
+//!
+//! ## Low level API
+//!
+//! [`GoffFile`] also provides access to the ESD entries ([`GoffFile::esds`])
+//! and the logical records ([`GoffFile::records`]).
+mod file;
+pub use file::*;
+
+mod section;
+pub use section::*;
+
+mod symbol;
+pub use symbol::*;
+
+mod relocation;
+pub use relocation::*;
@@ -0,0 +1,75 @@
+use crate::goff;
+use crate::read::{
+    ReadRef, Relocation, RelocationEncoding, RelocationFlags, RelocationKind, RelocationTarget,
+};
+
+use super::GoffFile;
+
+/// A relocation item from an RLD record in a [`GoffFile`].
+///
+/// The pointers in the item have been resolved to a symbol or section.
+#[derive(Debug, Clone, Copy)]
+pub struct GoffRelocation {
+    pub(super) offset: u64,
+    pub(super) kind: RelocationKind,
+    pub(super) encoding: RelocationEncoding,
+    pub(super) size: u8,
+    pub(super) target: RelocationTarget,
+    pub(super) item: goff::RldItem,
+}
+
+impl GoffRelocation {
+    /// Return the offset of the relocation within its element or part.
+    #[inline]
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Return the fixed fields of the relocation item.
+    #[inline]
+    pub fn item(&self) -> &goff::RldItem {
+        &self.item
+    }
+}
+
+/// An iterator for the relocations in a [`GoffSection`](super::GoffSection).
+#[derive(Debug)]
+pub struct GoffRelocationIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    pub(super) file: &'file GoffFile<'data, R>,
+    pub(super) iter: core::slice::Iter<'file, GoffRelocation>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for GoffRelocationIterator<'data, 'file, R> {
+    type Item = (u64, Relocation);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let relocation = self.iter.next()?;
+        let GoffRelocation {
+            kind,
+            encoding,
+            size,
+            ..
+        } = *relocation;
+        Some((
+            relocation.offset,
+            Relocation {
+                kind,
+                encoding,
+                size,
+                target: relocation.target,
+                addend: 0,
+                implicit_addend: true,
+                flags: RelocationFlags::Generic {
+                    kind,
+                    encoding,
+                    size,
+                },
+            },
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
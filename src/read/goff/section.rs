@@ -0,0 +1,285 @@
+use alloc::borrow::Cow;
+use core::str;
+
+use crate::endian::BigEndian as BE;
+use crate::goff;
+use crate::read::{
+    self, CompressedData, CompressedFileRange, Error, ObjectSection, ObjectSegment, ReadError,
+    ReadRef, RelocationMap, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags,
+};
+
+use super::{GoffEsd, GoffFile, GoffRelocationIterator, GoffSectionData};
+
+/// An iterator for the segments in a [`GoffFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct GoffSegmentIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    pub(super) file: &'file GoffFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for GoffSegmentIterator<'data, 'file, R> {
+    type Item = GoffSegment<'data, 'file, R>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+/// A segment in a [`GoffFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct GoffSegment<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file GoffFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for GoffSegment<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSegment<'data> for GoffSegment<'data, 'file, R> {
+    #[inline]
+    fn address(&self) -> u64 {
+        unreachable!()
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        unreachable!()
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        unreachable!()
+    }
+
+    #[inline]
+    fn file_range(&self) -> (u64, u64) {
+        unreachable!()
+    }
+
+    fn data(&self) -> Result<&'data [u8]> {
+        unreachable!()
+    }
+
+    fn data_range(&self, _address: u64, _size: u64) -> Result<Option<&'data [u8]>> {
+        unreachable!()
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<Option<&[u8]>> {
+        unreachable!()
+    }
+
+    #[inline]
+    fn name(&self) -> Result<Option<&str>> {
+        unreachable!()
+    }
+
+    #[inline]
+    fn flags(&self) -> SegmentFlags {
+        unreachable!()
+    }
+}
+
+/// An iterator for the sections in a [`GoffFile`].
+#[derive(Debug)]
+pub struct GoffSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file GoffFile<'data, R>,
+    pub(super) iter: core::iter::Enumerate<core::slice::Iter<'file, GoffSectionData<'data>>>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for GoffSectionIterator<'data, 'file, R> {
+    type Item = GoffSection<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(index, section)| GoffSection {
+            file: self.file,
+            index: SectionIndex(index + 1),
+            section,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// A section in a [`GoffFile`].
+///
+/// Each element (ED) or part (PR) is a section. The section index is the
+/// 1-based index of the element or part in the order of the ESD records.
+#[derive(Debug)]
+pub struct GoffSection<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file GoffFile<'data, R>,
+    pub(super) index: SectionIndex,
+    pub(super) section: &'file GoffSectionData<'data>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> GoffSection<'data, 'file, R> {
+    /// Return the ESD entry for the element or part.
+    #[inline]
+    pub fn esd(&self) -> &'file GoffEsd<'data> {
+        self.file.esd_at(self.section.esd)
+    }
+
+    /// Return the ESD entry of the section definition (SD) that owns the
+    /// element or part.
+    pub fn owner(&self) -> Option<&'file GoffEsd<'data>> {
+        // A part is owned by an element, which is owned by a section definition.
+        let mut esd = self.esd();
+        for _ in 0..2 {
+            esd = self.file.esd(esd.parent_esdid())?;
+            if esd.symbol_type() == goff::ESD_ST_SD {
+                return Some(esd);
+            }
+        }
+        None
+    }
+
+    fn text_size(&self) -> u64 {
+        self.section
+            .text
+            .iter()
+            .map(|(offset, text)| u64::from(*offset) + text.len() as u64)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for GoffSection<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSection<'data> for GoffSection<'data, 'file, R> {
+    type RelocationIterator = GoffRelocationIterator<'data, 'file, R>;
+
+    #[inline]
+    fn index(&self) -> SectionIndex {
+        self.index
+    }
+
+    #[inline]
+    fn address(&self) -> u64 {
+        0
+    }
+
+    /// Return the length from the ESD record, or the size of the text if
+    /// that is larger.
+    fn size(&self) -> u64 {
+        u64::from(self.esd().record.length.get(BE)).max(self.text_size())
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        1 << self.esd().record.alignment().min(63)
+    }
+
+    #[inline]
+    fn file_range(&self) -> Option<(u64, u64)> {
+        None
+    }
+
+    /// Return the section data if it is stored in a single TXT record at
+    /// offset 0 that isn't continued.
+    ///
+    /// Use [`ObjectSection::uncompressed_data`] for the data of other sections.
+    fn data(&self) -> Result<&'data [u8]> {
+        match self.section.text[..] {
+            [] => Ok(&[]),
+            [(0, Cow::Borrowed(text))] => Ok(text),
+            _ => Err(Error("GOFF section data is split across TXT records")),
+        }
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        Ok(read::util::data_range(
+            self.data()?,
+            self.address(),
+            address,
+            size,
+        ))
+    }
+
+    #[inline]
+    fn compressed_file_range(&self) -> Result<CompressedFileRange> {
+        Ok(CompressedFileRange::none(self.file_range()))
+    }
+
+    #[inline]
+    fn compressed_data(&self) -> Result<CompressedData<'data>> {
+        self.data().map(CompressedData::none)
+    }
+
+    /// Return the section data combined from all of its TXT records.
+    ///
+    /// Gaps between the TXT records are filled with zeros.
+    fn uncompressed_data(&self) -> Result<Cow<'data, [u8]>> {
+        if let Ok(data) = self.data() {
+            return Ok(Cow::Borrowed(data));
+        }
+        self.file.section_contents(self.index).map(Cow::Owned)
+    }
+
+    /// Return the class name of an element, or the name of a part.
+    ///
+    /// Returns an error if the name is continued across ESD records.
+    /// The name is usually encoded in EBCDIC.
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        match self.esd().name {
+            Cow::Borrowed(name) => Ok(name),
+            Cow::Owned(_) => Err(Error("GOFF section name is continued across records")),
+        }
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name_bytes()?)
+            .ok()
+            .read_error("Non UTF-8 GOFF section name")
+    }
+
+    /// Return the name of the section definition that owns the element or part.
+    fn segment_name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(self.owner().map(|esd| esd.name()))
+    }
+
+    fn segment_name(&self) -> Result<Option<&str>> {
+        match self.segment_name_bytes()? {
+            Some(name) => str::from_utf8(name)
+                .map(Some)
+                .ok()
+                .read_error("Non UTF-8 GOFF segment name"),
+            None => Ok(None),
+        }
+    }
+
+    fn kind(&self) -> SectionKind {
+        let record = &self.esd().record;
+        if record.loading_behavior() == goff::ESD_LB_NO_LOAD {
+            return SectionKind::Other;
+        }
+        match record.executable() {
+            goff::ESD_EXE_CODE => SectionKind::Text,
+            goff::ESD_EXE_DATA if record.is_read_only() => SectionKind::ReadOnlyData,
+            goff::ESD_EXE_DATA => SectionKind::Data,
+            _ => SectionKind::Unknown,
+        }
+    }
+
+    fn relocations(&self) -> GoffRelocationIterator<'data, 'file, R> {
+        GoffRelocationIterator {
+            file: self.file,
+            iter: self.section.relocations.iter(),
+        }
+    }
+
+    fn relocation_map(&self) -> Result<RelocationMap> {
+        RelocationMap::new(self.file, self)
+    }
+
+    #[inline]
+    fn flags(&self) -> SectionFlags {
+        SectionFlags::None
+    }
+}
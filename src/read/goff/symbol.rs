@@ -0,0 +1,182 @@
+use alloc::borrow::Cow;
+use core::str;
+
+use crate::endian::BigEndian as BE;
+use crate::goff;
+use crate::read::{
+    self, Error, Object, ObjectSymbol, ObjectSymbolTable, ReadError, ReadRef, Result, SectionIndex,
+    SymbolFlags, SymbolIndex, SymbolKind, SymbolScope, SymbolSection,
+};
+
+use super::{GoffEsd, GoffFile};
+
+/// A symbol table in a [`GoffFile`].
+#[derive(Debug, Clone, Copy)]
+pub struct GoffSymbolTable<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file GoffFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for GoffSymbolTable<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSymbolTable<'data>
+    for GoffSymbolTable<'data, 'file, R>
+{
+    type Symbol = GoffSymbol<'data, 'file, R>;
+    type SymbolIterator = GoffSymbolIterator<'data, 'file, R>;
+
+    fn symbols(&self) -> Self::SymbolIterator {
+        self.file.symbols()
+    }
+
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<Self::Symbol> {
+        self.file.symbol_by_index(index)
+    }
+}
+
+/// An iterator for the symbols in a [`GoffFile`].
+#[derive(Debug)]
+pub struct GoffSymbolIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file GoffFile<'data, R>,
+    pub(super) index: usize,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for GoffSymbolIterator<'data, 'file, R> {
+    type Item = GoffSymbol<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let esd = *self.file.symbols.get(self.index)?;
+        let index = SymbolIndex(self.index);
+        self.index += 1;
+        Some(GoffSymbol {
+            file: self.file,
+            index,
+            esd: self.file.esd_at(esd),
+        })
+    }
+}
+
+/// A symbol in a [`GoffFile`].
+///
+/// Each label definition (LD), part reference (PR), and external reference
+/// (ER) is a symbol.
+#[derive(Debug, Clone, Copy)]
+pub struct GoffSymbol<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file GoffFile<'data, R>,
+    pub(super) index: SymbolIndex,
+    pub(super) esd: &'file GoffEsd<'data>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> GoffSymbol<'data, 'file, R> {
+    /// Return the ESD entry for the symbol.
+    #[inline]
+    pub fn esd(&self) -> &'file GoffEsd<'data> {
+        self.esd
+    }
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for GoffSymbol<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSymbol<'data> for GoffSymbol<'data, 'file, R> {
+    #[inline]
+    fn index(&self) -> SymbolIndex {
+        self.index
+    }
+
+    /// Return the symbol name.
+    ///
+    /// Returns an error if the name is continued across ESD records.
+    /// The name is usually encoded in EBCDIC.
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        match self.esd.name {
+            Cow::Borrowed(name) => Ok(name),
+            Cow::Owned(_) => Err(Error("GOFF symbol name is continued across records")),
+        }
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name_bytes()?)
+            .ok()
+            .read_error("Non UTF-8 GOFF symbol name")
+    }
+
+    fn address(&self) -> u64 {
+        match self.esd.symbol_type() {
+            goff::ESD_ST_LD => self.esd.record.offset.get(BE).into(),
+            _ => 0,
+        }
+    }
+
+    fn size(&self) -> u64 {
+        match self.esd.symbol_type() {
+            goff::ESD_ST_PR => self.esd.record.length.get(BE).into(),
+            _ => 0,
+        }
+    }
+
+    fn kind(&self) -> SymbolKind {
+        match self.esd.record.executable() {
+            goff::ESD_EXE_CODE => SymbolKind::Text,
+            goff::ESD_EXE_DATA => SymbolKind::Data,
+            _ => SymbolKind::Unknown,
+        }
+    }
+
+    /// Return the element for a label definition, or the part for a part
+    /// reference.
+    fn section(&self) -> SymbolSection {
+        let esdid = match self.esd.symbol_type() {
+            goff::ESD_ST_LD => self.esd.parent_esdid(),
+            goff::ESD_ST_PR => self.esd.esdid(),
+            _ => return SymbolSection::Undefined,
+        };
+        match self.file.section_index(esdid) {
+            Some(index) => SymbolSection::Section(index),
+            None => SymbolSection::Unknown,
+        }
+    }
+
+    #[inline]
+    fn is_undefined(&self) -> bool {
+        self.esd.symbol_type() == goff::ESD_ST_ER
+    }
+
+    #[inline]
+    fn is_definition(&self) -> bool {
+        !self.is_undefined()
+    }
+
+    #[inline]
+    fn is_common(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn is_weak(&self) -> bool {
+        self.esd.record.binding_strength() == goff::ESD_BST_WEAK
+    }
+
+    fn scope(&self) -> SymbolScope {
+        match self.esd.record.binding_scope() {
+            goff::ESD_BSC_SECTION => SymbolScope::Compilation,
+            goff::ESD_BSC_MODULE | goff::ESD_BSC_LIBRARY => SymbolScope::Linkage,
+            goff::ESD_BSC_IMPORT_EXPORT => SymbolScope::Dynamic,
+            _ => SymbolScope::Unknown,
+        }
+    }
+
+    #[inline]
+    fn is_global(&self) -> bool {
+        self.esd.record.binding_scope() >= goff::ESD_BSC_MODULE
+    }
+
+    #[inline]
+    fn is_local(&self) -> bool {
+        self.esd.record.binding_scope() == goff::ESD_BSC_SECTION
+    }
+
+    #[inline]
+    fn flags(&self) -> SymbolFlags<SectionIndex, SymbolIndex> {
+        SymbolFlags::None
+    }
+}
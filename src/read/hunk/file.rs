@@ -0,0 +1,818 @@
+use alloc::collections::btree_map::BTreeMap;
+use alloc::vec::Vec;
+
+use crate::endian::{BigEndian as BE, U16Bytes, U32Bytes};
+use crate::hunk;
+use crate::read::{
+    self, Architecture, Bytes, ComdatKind, Error, Export, FileFlags, Import,
+    NoDynamicRelocationIterator, Object, ObjectComdat, ObjectKind, ReadError, ReadRef, Relocation,
+    RelocationEncoding, RelocationFlags, RelocationKind, RelocationTarget, Result, SectionIndex,
+    SymbolIndex, SymbolKind, SymbolSection,
+};
+
+use super::{
+    HunkSection, HunkSectionIterator, HunkSegment, HunkSegmentIterator, HunkSymbol,
+    HunkSymbolIterator, HunkSymbolTable,
+};
+
+/// An AmigaOS Hunk executable or object file.
+///
+/// Most functions in the [`Object`] trait are implemented for this type.
+#[derive(Debug)]
+pub struct HunkFile<'data, R: ReadRef<'data> = &'data [u8]> {
+    data: R,
+    executable: bool,
+    units: Vec<HunkUnit<'data>>,
+    pub(super) hunks: Vec<Hunk<'data>>,
+    pub(super) symbols: Vec<HunkSymbol<'data>>,
+    has_debug: bool,
+}
+
+impl<'data, R: ReadRef<'data>> HunkFile<'data, R> {
+    /// Parse the raw Hunk file data.
+    pub fn parse(data: R) -> Result<Self> {
+        let len = data.len().read_error("Unknown Hunk file length")?;
+        let bytes = data
+            .read_bytes_at(0, len)
+            .read_error("Invalid Hunk file data")?;
+        let mut builder = Builder {
+            data: Bytes(bytes),
+            len: bytes.len(),
+            executable: false,
+            header_sizes: Vec::new(),
+            first_hunk: 0,
+            unit_base: 0,
+            units: Vec::new(),
+            hunks: Vec::new(),
+            current: None,
+            name: &[],
+            symbols: Vec::new(),
+            references: BTreeMap::new(),
+            has_debug: false,
+        };
+        match builder.read_u32()? {
+            hunk::HUNK_HEADER => builder.parse_header()?,
+            hunk::HUNK_UNIT => builder.parse_unit()?,
+            _ => return Err(Error("Invalid Hunk file magic")),
+        }
+        builder.parse_blocks()?;
+        Ok(HunkFile {
+            data,
+            executable: builder.executable,
+            units: builder.units,
+            hunks: builder.hunks,
+            symbols: builder.symbols,
+            has_debug: builder.has_debug,
+        })
+    }
+
+    /// Returns the raw Hunk file data.
+    #[inline]
+    pub fn data(&self) -> R {
+        self.data
+    }
+
+    /// Return true if this is an executable, or false if this is an object file.
+    #[inline]
+    pub fn is_executable(&self) -> bool {
+        self.executable
+    }
+
+    /// Returns the program units in an object file.
+    ///
+    /// This is empty for executables.
+    #[inline]
+    pub fn units(&self) -> &[HunkUnit<'data>] {
+        &self.units
+    }
+
+    /// Returns the hunks in the file.
+    ///
+    /// The hunk at index `i` is the section with index `i + 1`.
+    #[inline]
+    pub fn hunks(&self) -> &[Hunk<'data>] {
+        &self.hunks
+    }
+
+    pub(super) fn hunk(&self, index: SectionIndex) -> Result<&Hunk<'data>> {
+        index
+            .0
+            .checked_sub(1)
+            .and_then(|index| self.hunks.get(index))
+            .read_error("Invalid Hunk section index")
+    }
+}
+
+impl<'data, R: ReadRef<'data>> read::private::Sealed for HunkFile<'data, R> {}
+
+impl<'data, R: ReadRef<'data>> Object<'data> for HunkFile<'data, R> {
+    type Segment<'file>
+        = HunkSegment<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SegmentIterator<'file>
+        = HunkSegmentIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Section<'file>
+        = HunkSection<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SectionIterator<'file>
+        = HunkSectionIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Comdat<'file>
+        = HunkComdat<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type ComdatIterator<'file>
+        = HunkComdatIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Symbol<'file>
+        = HunkSymbol<'data>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolIterator<'file>
+        = HunkSymbolIterator<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolTable<'file>
+        = HunkSymbolTable<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type DynamicRelocationIterator<'file>
+        = NoDynamicRelocationIterator
+    where
+        Self: 'file,
+        'data: 'file;
+
+    fn architecture(&self) -> Architecture {
+        Architecture::M68k
+    }
+
+    #[inline]
+    fn is_little_endian(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn is_64(&self) -> bool {
+        false
+    }
+
+    fn kind(&self) -> ObjectKind {
+        if self.executable {
+            ObjectKind::Executable
+        } else {
+            ObjectKind::Relocatable
+        }
+    }
+
+    /// Each hunk in an executable is also a segment. Object files have no segments.
+    fn segments(&self) -> HunkSegmentIterator<'data, '_, R> {
+        let mut sections = self.sections();
+        if !self.executable {
+            sections.iter = self.hunks[..0].iter().enumerate();
+        }
+        HunkSegmentIterator { sections }
+    }
+
+    fn section_by_name_bytes<'file>(
+        &'file self,
+        section_name: &[u8],
+    ) -> Option<HunkSection<'data, 'file, R>> {
+        use crate::read::ObjectSection;
+        self.sections()
+            .find(|section| section.name_bytes() == Ok(section_name))
+    }
+
+    fn section_by_index(&self, index: SectionIndex) -> Result<HunkSection<'data, '_, R>> {
+        let hunk = self.hunk(index)?;
+        Ok(HunkSection {
+            file: self,
+            index,
+            hunk,
+        })
+    }
+
+    fn sections(&self) -> HunkSectionIterator<'data, '_, R> {
+        HunkSectionIterator {
+            file: self,
+            iter: self.hunks.iter().enumerate(),
+        }
+    }
+
+    #[inline]
+    fn comdats(&self) -> HunkComdatIterator<'data, '_, R> {
+        HunkComdatIterator { file: self }
+    }
+
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<HunkSymbol<'data>> {
+        self.symbols
+            .get(index.0)
+            .copied()
+            .read_error("Invalid Hunk symbol index")
+    }
+
+    fn symbols(&self) -> HunkSymbolIterator<'data, '_> {
+        HunkSymbolIterator {
+            iter: self.symbols.iter(),
+        }
+    }
+
+    fn symbol_table(&self) -> Option<HunkSymbolTable<'data, '_>> {
+        Some(HunkSymbolTable {
+            symbols: &self.symbols,
+        })
+    }
+
+    fn dynamic_symbols(&self) -> HunkSymbolIterator<'data, '_> {
+        HunkSymbolIterator { iter: [].iter() }
+    }
+
+    #[inline]
+    fn dynamic_symbol_table(&self) -> Option<HunkSymbolTable<'data, '_>> {
+        None
+    }
+
+    #[inline]
+    fn dynamic_relocations(&self) -> Option<NoDynamicRelocationIterator> {
+        None
+    }
+
+    fn imports(&self) -> Result<Vec<Import<'data>>> {
+        // Hunk files don't support dynamic linking.
+        Ok(Vec::new())
+    }
+
+    fn exports(&self) -> Result<Vec<Export<'data>>> {
+        // Hunk files don't support dynamic linking.
+        Ok(Vec::new())
+    }
+
+    #[inline]
+    fn has_debug_symbols(&self) -> bool {
+        self.has_debug
+    }
+
+    #[inline]
+    fn relative_address_base(&self) -> u64 {
+        0
+    }
+
+    /// Executables start at the beginning of the first hunk, so this is always 0.
+    #[inline]
+    fn entry(&self) -> u64 {
+        0
+    }
+
+    #[inline]
+    fn flags(&self) -> FileFlags {
+        FileFlags::None
+    }
+}
+
+/// A program unit in a Hunk object file.
+#[derive(Debug, Clone, Copy)]
+pub struct HunkUnit<'data> {
+    name: &'data [u8],
+    first_hunk: usize,
+}
+
+impl<'data> HunkUnit<'data> {
+    /// Return the name of the unit.
+    #[inline]
+    pub fn name(&self) -> &'data [u8] {
+        self.name
+    }
+
+    /// Return the index in [`HunkFile::hunks`] of the first hunk in the unit.
+    #[inline]
+    pub fn first_hunk(&self) -> usize {
+        self.first_hunk
+    }
+}
+
+/// A code, data or bss hunk in a Hunk file.
+#[derive(Debug)]
+pub struct Hunk<'data> {
+    hunk_type: u32,
+    memory_flags: u32,
+    memory_attributes: u32,
+    name: &'data [u8],
+    size: u64,
+    data: &'data [u8],
+    file_offset: u64,
+    pub(super) relocations: Vec<HunkResolvedRelocation>,
+}
+
+impl<'data> Hunk<'data> {
+    /// Return the hunk type: `HUNK_CODE`, `HUNK_DATA` or `HUNK_BSS`.
+    #[inline]
+    pub fn hunk_type(&self) -> u32 {
+        self.hunk_type
+    }
+
+    /// Return the memory flags: a combination of `HUNKF_CHIP` and `HUNKF_FAST`.
+    #[inline]
+    pub fn memory_flags(&self) -> u32 {
+        self.memory_flags
+    }
+
+    /// Return the memory attributes.
+    ///
+    /// This is only set if both `HUNKF_CHIP` and `HUNKF_FAST` are set
+    /// in the executable header.
+    #[inline]
+    pub fn memory_attributes(&self) -> u32 {
+        self.memory_attributes
+    }
+
+    /// Return the name given by `HUNK_NAME`, or an empty slice if there is no name.
+    #[inline]
+    pub fn name(&self) -> &'data [u8] {
+        self.name
+    }
+
+    /// Return the size of the hunk in memory.
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Return the initialized data of the hunk.
+    ///
+    /// This may be shorter than the size of the hunk, and is empty for bss hunks.
+    #[inline]
+    pub fn data(&self) -> &'data [u8] {
+        self.data
+    }
+
+    /// Return the file offset of the initialized data of the hunk.
+    #[inline]
+    pub fn file_offset(&self) -> u64 {
+        self.file_offset
+    }
+}
+
+/// A relocation that has been resolved to a section or symbol target.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct HunkResolvedRelocation {
+    pub(super) offset: u32,
+    kind: RelocationKind,
+    size: u8,
+    target: RelocationTarget,
+}
+
+impl HunkResolvedRelocation {
+    /// The addend is always stored in the relocated location.
+    pub(super) fn relocation(&self) -> Relocation {
+        Relocation {
+            kind: self.kind,
+            encoding: RelocationEncoding::Generic,
+            size: self.size,
+            target: self.target,
+            addend: 0,
+            implicit_addend: true,
+            flags: RelocationFlags::Generic {
+                kind: self.kind,
+                encoding: RelocationEncoding::Generic,
+                size: self.size,
+            },
+        }
+    }
+}
+
+/// The state for parsing the blocks of a Hunk file.
+struct Builder<'data> {
+    data: Bytes<'data>,
+    len: usize,
+    executable: bool,
+    /// The memory size, flags and attributes of each hunk in an executable.
+    header_sizes: Vec<(u64, u32, u32)>,
+    /// The number of the first hunk in an executable.
+    first_hunk: u32,
+    /// The index of the first hunk of the current unit.
+    unit_base: usize,
+    units: Vec<HunkUnit<'data>>,
+    hunks: Vec<Hunk<'data>>,
+    current: Option<Hunk<'data>>,
+    /// The name given by `HUNK_NAME` for the next hunk.
+    name: &'data [u8],
+    symbols: Vec<HunkSymbol<'data>>,
+    /// The undefined and common symbols, by name.
+    references: BTreeMap<&'data [u8], SymbolIndex>,
+    has_debug: bool,
+}
+
+impl<'data> Builder<'data> {
+    fn offset(&self) -> u64 {
+        (self.len - self.data.len()) as u64
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        self.data
+            .read::<U16Bytes<BE>>()
+            .map(|x| x.get(BE))
+            .read_error("Hunk file is truncated")
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        self.data
+            .read::<U32Bytes<BE>>()
+            .map(|x| x.get(BE))
+            .read_error("Hunk file is truncated")
+    }
+
+    fn read_words(&mut self, count: u32) -> Result<&'data [u8]> {
+        let size = (count as usize)
+            .checked_mul(4)
+            .read_error("Invalid Hunk block size")?;
+        self.data
+            .read_bytes(size)
+            .map(|bytes| bytes.0)
+            .read_error("Hunk file is truncated")
+    }
+
+    /// Read a name that is padded with zeros.
+    fn read_name(&mut self, count: u32) -> Result<&'data [u8]> {
+        let name = self.read_words(count)?;
+        let end = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+        Ok(&name[..end])
+    }
+
+    fn parse_header(&mut self) -> Result<()> {
+        self.executable = true;
+        // Skip the resident library names.
+        loop {
+            let count = self.read_u32()?;
+            if count == 0 {
+                break;
+            }
+            self.read_words(count)?;
+        }
+        let _table_size = self.read_u32()?;
+        self.first_hunk = self.read_u32()?;
+        let last_hunk = self.read_u32()?;
+        let count = last_hunk
+            .checked_sub(self.first_hunk)
+            .read_error("Invalid Hunk header hunk range")?;
+        for _ in 0..=count {
+            let size = self.read_u32()?;
+            let flags = size & hunk::HUNKF_MEMORY;
+            let attributes = if flags == hunk::HUNKF_MEMORY {
+                self.read_u32()?
+            } else {
+                0
+            };
+            let size = u64::from(size & hunk::HUNK_TYPE_MASK) * 4;
+            self.header_sizes.push((size, flags, attributes));
+        }
+        Ok(())
+    }
+
+    fn parse_unit(&mut self) -> Result<()> {
+        self.end_hunk();
+        let count = self.read_u32()?;
+        let name = self.read_name(count)?;
+        self.unit_base = self.hunks.len();
+        self.units.push(HunkUnit {
+            name,
+            first_hunk: self.unit_base,
+        });
+        Ok(())
+    }
+
+    fn parse_blocks(&mut self) -> Result<()> {
+        while !self.data.is_empty() {
+            let block = self.read_u32()?;
+            match block & hunk::HUNK_TYPE_MASK {
+                hunk::HUNK_UNIT if !self.executable => self.parse_unit()?,
+                hunk::HUNK_NAME => {
+                    let count = self.read_u32()?;
+                    self.name = self.read_name(count)?;
+                }
+                hunk::HUNK_CODE | hunk::HUNK_DATA | hunk::HUNK_BSS => self.begin_hunk(block)?,
+                hunk::HUNK_RELOC32SHORT => self.parse_relocations(block, true)?,
+                // Executables use this for short relocations.
+                hunk::HUNK_DREL32 => self.parse_relocations(block, self.executable)?,
+                hunk::HUNK_RELOC32
+                | hunk::HUNK_RELOC16
+                | hunk::HUNK_RELOC8
+                | hunk::HUNK_DREL16
+                | hunk::HUNK_DREL8
+                | hunk::HUNK_RELRELOC32
+                | hunk::HUNK_ABSRELOC16 => self.parse_relocations(block, false)?,
+                hunk::HUNK_EXT => self.parse_symbols(false)?,
+                hunk::HUNK_SYMBOL => self.parse_symbols(true)?,
+                hunk::HUNK_DEBUG => {
+                    let count = self.read_u32()?;
+                    self.read_words(count)?;
+                    self.has_debug = true;
+                }
+                hunk::HUNK_END => self.end_hunk(),
+                hunk::HUNK_OVERLAY | hunk::HUNK_BREAK => {
+                    return Err(Error("Unsupported Hunk overlay"));
+                }
+                _ if block & hunk::HUNKF_ADVISORY != 0 => {
+                    let count = self.read_u32()?;
+                    self.read_words(count)?;
+                }
+                _ => return Err(Error("Unsupported Hunk block type")),
+            }
+        }
+        self.end_hunk();
+        Ok(())
+    }
+
+    fn begin_hunk(&mut self, block: u32) -> Result<()> {
+        self.end_hunk();
+        let hunk_type = block & hunk::HUNK_TYPE_MASK;
+        let mut memory_flags = block & hunk::HUNKF_MEMORY;
+        let mut memory_attributes = 0;
+        let count = self.read_u32()?;
+        memory_flags |= count & hunk::HUNKF_MEMORY;
+        let count = count & hunk::HUNK_TYPE_MASK;
+        let file_offset = self.offset();
+        let data = if hunk_type == hunk::HUNK_BSS {
+            &[][..]
+        } else {
+            self.read_words(count)?
+        };
+        let mut size = u64::from(count) * 4;
+        if self.executable {
+            let (header_size, flags, attributes) = self
+                .header_sizes
+                .get(self.hunks.len())
+                .copied()
+                .read_error("Too many hunks for Hunk header")?;
+            size = size.max(header_size);
+            memory_flags |= flags;
+            memory_attributes = attributes;
+        }
+        self.current = Some(Hunk {
+            hunk_type,
+            memory_flags,
+            memory_attributes,
+            name: core::mem::take(&mut self.name),
+            size,
+            data,
+            file_offset,
+            relocations: Vec::new(),
+        });
+        Ok(())
+    }
+
+    fn end_hunk(&mut self) {
+        if let Some(hunk) = self.current.take() {
+            self.hunks.push(hunk);
+        }
+    }
+
+    /// Return the section index of the current hunk.
+    fn current_index(&self) -> Result<SectionIndex> {
+        if self.current.is_none() {
+            return Err(Error("Hunk block is outside of a hunk"));
+        }
+        Ok(SectionIndex(self.hunks.len() + 1))
+    }
+
+    fn add_relocation(&mut self, relocation: HunkResolvedRelocation) -> Result<()> {
+        self.current
+            .as_mut()
+            .read_error("Hunk block is outside of a hunk")?
+            .relocations
+            .push(relocation);
+        Ok(())
+    }
+
+    fn parse_relocations(&mut self, block: u32, short: bool) -> Result<()> {
+        let (kind, size) = match block & hunk::HUNK_TYPE_MASK {
+            hunk::HUNK_RELOC32 | hunk::HUNK_RELOC32SHORT => (RelocationKind::Absolute, 32),
+            hunk::HUNK_DREL32 if short => (RelocationKind::Absolute, 32),
+            hunk::HUNK_RELOC16 => (RelocationKind::Relative, 16),
+            hunk::HUNK_RELOC8 => (RelocationKind::Relative, 8),
+            hunk::HUNK_DREL32 => (RelocationKind::Unknown, 32),
+            hunk::HUNK_DREL16 => (RelocationKind::Unknown, 16),
+            hunk::HUNK_DREL8 => (RelocationKind::Unknown, 8),
+            hunk::HUNK_RELRELOC32 => (RelocationKind::Relative, 32),
+            hunk::HUNK_ABSRELOC16 => (RelocationKind::Absolute, 16),
+            _ => return Err(Error("Unsupported Hunk relocation block type")),
+        };
+        let read = |builder: &mut Self| {
+            if short {
+                builder.read_u16().map(u32::from)
+            } else {
+                builder.read_u32()
+            }
+        };
+        loop {
+            let count = read(self)?;
+            if count == 0 {
+                break;
+            }
+            let target = read(self)?
+                .checked_sub(self.first_hunk)
+                .read_error("Invalid Hunk relocation target")?;
+            let target = SectionIndex(self.unit_base + target as usize + 1);
+            for _ in 0..count {
+                let offset = read(self)?;
+                self.add_relocation(HunkResolvedRelocation {
+                    offset,
+                    kind,
+                    size,
+                    target: RelocationTarget::Section(target),
+                })?;
+            }
+        }
+        if short && self.offset() % 4 != 0 {
+            self.read_u16()?;
+        }
+        Ok(())
+    }
+
+    /// Parse a `HUNK_EXT` or `HUNK_SYMBOL` block.
+    fn parse_symbols(&mut self, debug: bool) -> Result<()> {
+        loop {
+            let word = self.read_u32()?;
+            if word == 0 {
+                break;
+            }
+            let ext_type = (word >> 24) as u8;
+            let name = self.read_name(word & 0x00ff_ffff)?;
+            if debug || ext_type < hunk::EXT_REF32 {
+                let value = self.read_u32()?;
+                let section = match ext_type {
+                    hunk::EXT_ABS => SymbolSection::Absolute,
+                    hunk::EXT_RES => SymbolSection::Unknown,
+                    _ => SymbolSection::Section(self.current_index()?),
+                };
+                let kind = match self.current.as_ref().map(|hunk| hunk.hunk_type) {
+                    _ if section == SymbolSection::Absolute => SymbolKind::Unknown,
+                    Some(hunk::HUNK_CODE) => SymbolKind::Text,
+                    Some(_) => SymbolKind::Data,
+                    None => SymbolKind::Unknown,
+                };
+                self.symbols.push(HunkSymbol {
+                    index: SymbolIndex(self.symbols.len()),
+                    name,
+                    ext_type,
+                    section,
+                    kind,
+                    value,
+                    global: !debug,
+                });
+                continue;
+            }
+
+            let (kind, size) = match ext_type {
+                hunk::EXT_REF32 | hunk::EXT_COMMON => (RelocationKind::Absolute, 32),
+                hunk::EXT_REF16 => (RelocationKind::Relative, 16),
+                hunk::EXT_REF8 => (RelocationKind::Relative, 8),
+                hunk::EXT_DEXT32 => (RelocationKind::Unknown, 32),
+                hunk::EXT_DEXT16 => (RelocationKind::Unknown, 16),
+                hunk::EXT_DEXT8 => (RelocationKind::Unknown, 8),
+                hunk::EXT_RELREF32 | hunk::EXT_RELCOMMON => (RelocationKind::Relative, 32),
+                hunk::EXT_ABSREF16 => (RelocationKind::Absolute, 16),
+                hunk::EXT_ABSREF8 => (RelocationKind::Absolute, 8),
+                _ => return Err(Error("Unsupported Hunk external reference type")),
+            };
+            let common_size = match ext_type {
+                hunk::EXT_COMMON | hunk::EXT_RELCOMMON => Some(self.read_u32()?),
+                _ => None,
+            };
+            let symbol = self.reference(name, ext_type, common_size);
+            let count = self.read_u32()?;
+            for _ in 0..count {
+                let offset = self.read_u32()?;
+                self.add_relocation(HunkResolvedRelocation {
+                    offset,
+                    kind,
+                    size,
+                    target: RelocationTarget::Symbol(symbol),
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the undefined or common symbol for a reference, adding it if needed.
+    fn reference(
+        &mut self,
+        name: &'data [u8],
+        ext_type: u8,
+        common_size: Option<u32>,
+    ) -> SymbolIndex {
+        let index = match self.references.get(name) {
+            Some(index) => *index,
+            None => {
+                let index = SymbolIndex(self.symbols.len());
+                self.symbols.push(HunkSymbol {
+                    index,
+                    name,
+                    ext_type,
+                    section: SymbolSection::Undefined,
+                    kind: SymbolKind::Unknown,
+                    value: 0,
+                    global: true,
+                });
+                self.references.insert(name, index);
+                index
+            }
+        };
+        if let Some(size) = common_size {
+            let symbol = &mut self.symbols[index.0];
+            symbol.ext_type = ext_type;
+            symbol.section = SymbolSection::Common;
+            symbol.kind = SymbolKind::Data;
+            symbol.value = symbol.value.max(size);
+        }
+        index
+    }
+}
+
+/// An iterator for the COMDAT section groups in a [`HunkFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct HunkComdatIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file HunkFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for HunkComdatIterator<'data, 'file, R> {
+    type Item = HunkComdat<'data, 'file, R>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+/// A COMDAT section group in a [`HunkFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct HunkComdat<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file HunkFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for HunkComdat<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectComdat<'data> for HunkComdat<'data, 'file, R> {
+    type SectionIterator = HunkComdatSectionIterator<'data, 'file, R>;
+
+    #[inline]
+    fn kind(&self) -> ComdatKind {
+        unreachable!();
+    }
+
+    #[inline]
+    fn symbol(&self) -> SymbolIndex {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn sections(&self) -> Self::SectionIterator {
+        unreachable!();
+    }
+}
+
+/// An iterator for the sections in a COMDAT section group in a [`HunkFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct HunkComdatSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file HunkFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for HunkComdatSectionIterator<'data, 'file, R> {
+    type Item = SectionIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
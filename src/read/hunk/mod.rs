@@ -0,0 +1,37 @@
+//! Support for reading AmigaOS Hunk files.
+//!
+//! Hunk files are used for AmigaOS executables (load files) and object files.
+//! Overlaid executables and libraries of units (`HUNK_LIB`) are not supported.
+//!
+//! ## High level API
+//!
+//! [`HunkFile`] implements the [`Object`](crate::read::Object) trait for Hunk
+//! files. Each code, data or bss hunk is a section, using the 1-based hunk
+//! number as the section index. In executables, each hunk is also a segment.
+//! Hunks are relocated when they are loaded, so sections have an address of 0
+//! and symbol addresses are offsets within their hunk.
+//!
+//! Definitions in `HUNK_EXT` blocks are global symbols, and entries in
+//! `HUNK_SYMBOL` blocks are local symbols. References in `HUNK_EXT` blocks are
+//! relocations with an undefined or common symbol as the target, and the
+//! entries in `HUNK_RELOC*` blocks are relocations with a section as the target.
+//!
+//! Object files may contain multiple units, and hunk numbers in relocations
+//! are relative to the start of the unit.
+//!
+//! ## Low level API
+//!
+//! [`HunkFile`] also provides access to the memory flags and initialized data
+//! of each hunk ([`HunkFile::hunks`]), and the units in an object file
+//! ([`HunkFile::units`]).
+mod file;
+pub use file::*;
+
+mod section;
+pub use section::*;
+
+mod symbol;
+pub use symbol::*;
+
+mod relocation;
+pub use relocation::*;
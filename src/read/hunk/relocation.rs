@@ -0,0 +1,27 @@
+use core::slice;
+
+use crate::read::{ReadRef, Relocation};
+
+use super::{HunkFile, HunkResolvedRelocation};
+
+/// An iterator for the relocations in a [`HunkSection`](super::HunkSection).
+///
+/// This includes the relocations from `HUNK_RELOC*` blocks, which target
+/// sections, and the external references from `HUNK_EXT` blocks, which target
+/// undefined or common symbols. The addend is always stored in the relocated
+/// location. Data relative relocations have an unknown kind.
+#[derive(Debug)]
+pub struct HunkRelocationIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    pub(super) file: &'file HunkFile<'data, R>,
+    pub(super) relocations: slice::Iter<'file, HunkResolvedRelocation>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for HunkRelocationIterator<'data, 'file, R> {
+    type Item = (u64, Relocation);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let relocation = self.relocations.next()?;
+        Some((relocation.offset.into(), relocation.relocation()))
+    }
+}
@@ -0,0 +1,238 @@
+use core::str;
+
+use crate::hunk;
+use crate::read::{
+    self, CompressedData, CompressedFileRange, ObjectSection, ObjectSegment, ReadError, ReadRef,
+    RelocationMap, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags,
+};
+
+use super::{Hunk, HunkFile, HunkRelocationIterator};
+
+/// An iterator for the sections in a [`HunkFile`].
+#[derive(Debug)]
+pub struct HunkSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file HunkFile<'data, R>,
+    pub(super) iter: core::iter::Enumerate<core::slice::Iter<'file, Hunk<'data>>>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for HunkSectionIterator<'data, 'file, R> {
+    type Item = HunkSection<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(index, hunk)| HunkSection {
+            file: self.file,
+            index: SectionIndex(index + 1),
+            hunk,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// A section in a [`HunkFile`].
+///
+/// Each code, data or bss hunk is a section. Hunks are relocated when they
+/// are loaded, so all sections have an address of 0.
+#[derive(Debug)]
+pub struct HunkSection<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file HunkFile<'data, R>,
+    pub(super) index: SectionIndex,
+    pub(super) hunk: &'file Hunk<'data>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> HunkSection<'data, 'file, R> {
+    /// Return the hunk for this section.
+    #[inline]
+    pub fn hunk(&self) -> &'file Hunk<'data> {
+        self.hunk
+    }
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for HunkSection<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSection<'data> for HunkSection<'data, 'file, R> {
+    type RelocationIterator = HunkRelocationIterator<'data, 'file, R>;
+
+    #[inline]
+    fn index(&self) -> SectionIndex {
+        self.index
+    }
+
+    #[inline]
+    fn address(&self) -> u64 {
+        0
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.hunk.size()
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        4
+    }
+
+    fn file_range(&self) -> Option<(u64, u64)> {
+        if self.hunk.hunk_type() == hunk::HUNK_BSS {
+            None
+        } else {
+            Some((self.hunk.file_offset(), self.hunk.data().len() as u64))
+        }
+    }
+
+    /// Return the initialized data of the hunk.
+    ///
+    /// This may be shorter than the size of the section.
+    #[inline]
+    fn data(&self) -> Result<&'data [u8]> {
+        Ok(self.hunk.data())
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        Ok(read::util::data_range(
+            self.data()?,
+            self.address(),
+            address,
+            size,
+        ))
+    }
+
+    #[inline]
+    fn compressed_file_range(&self) -> Result<CompressedFileRange> {
+        Ok(CompressedFileRange::none(self.file_range()))
+    }
+
+    #[inline]
+    fn compressed_data(&self) -> Result<CompressedData<'data>> {
+        self.data().map(CompressedData::none)
+    }
+
+    /// Return the name given by `HUNK_NAME`.
+    ///
+    /// If the hunk has no name, then return `CODE`, `DATA` or `BSS` depending
+    /// on the hunk type.
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        let name = self.hunk.name();
+        if !name.is_empty() {
+            return Ok(name);
+        }
+        Ok(match self.hunk.hunk_type() {
+            hunk::HUNK_CODE => b"CODE",
+            hunk::HUNK_DATA => b"DATA",
+            _ => b"BSS",
+        })
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name_bytes()?)
+            .ok()
+            .read_error("Non UTF-8 Hunk section name")
+    }
+
+    #[inline]
+    fn segment_name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn segment_name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    fn kind(&self) -> SectionKind {
+        match self.hunk.hunk_type() {
+            hunk::HUNK_CODE => SectionKind::Text,
+            hunk::HUNK_DATA => SectionKind::Data,
+            _ => SectionKind::UninitializedData,
+        }
+    }
+
+    fn relocations(&self) -> HunkRelocationIterator<'data, 'file, R> {
+        HunkRelocationIterator {
+            file: self.file,
+            relocations: self.hunk.relocations.iter(),
+        }
+    }
+
+    fn relocation_map(&self) -> Result<RelocationMap> {
+        RelocationMap::new(self.file, self)
+    }
+
+    #[inline]
+    fn flags(&self) -> SectionFlags {
+        SectionFlags::None
+    }
+}
+
+/// An iterator for the segments in a [`HunkFile`].
+#[derive(Debug)]
+pub struct HunkSegmentIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) sections: HunkSectionIterator<'data, 'file, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for HunkSegmentIterator<'data, 'file, R> {
+    type Item = HunkSegment<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.sections.next().map(|section| HunkSegment { section })
+    }
+}
+
+/// A segment in a [`HunkFile`].
+///
+/// Each hunk in an executable is both a section and a segment.
+#[derive(Debug)]
+pub struct HunkSegment<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    section: HunkSection<'data, 'file, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for HunkSegment<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSegment<'data> for HunkSegment<'data, 'file, R> {
+    #[inline]
+    fn address(&self) -> u64 {
+        self.section.address()
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.section.size()
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        self.section.align()
+    }
+
+    #[inline]
+    fn file_range(&self) -> (u64, u64) {
+        self.section.file_range().unwrap_or((0, 0))
+    }
+
+    fn data(&self) -> Result<&'data [u8]> {
+        self.section.data()
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        self.section.data_range(address, size)
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn flags(&self) -> SegmentFlags {
+        SegmentFlags::None
+    }
+}
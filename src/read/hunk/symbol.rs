@@ -0,0 +1,171 @@
+use core::str;
+
+use crate::read::{
+    self, ObjectSymbol, ObjectSymbolTable, ReadError, Result, SectionIndex, SymbolFlags,
+    SymbolIndex, SymbolKind, SymbolScope, SymbolSection,
+};
+
+/// A symbol in a [`HunkFile`](super::HunkFile).
+///
+/// This is either a definition or reference in a `HUNK_EXT` block, or
+/// a debugging symbol in a `HUNK_SYMBOL` block. There is a single undefined
+/// or common symbol for all of the references to a name.
+#[derive(Debug, Clone, Copy)]
+pub struct HunkSymbol<'data> {
+    pub(super) index: SymbolIndex,
+    pub(super) name: &'data [u8],
+    pub(super) ext_type: u8,
+    pub(super) section: SymbolSection,
+    pub(super) kind: SymbolKind,
+    pub(super) value: u32,
+    pub(super) global: bool,
+}
+
+impl<'data> HunkSymbol<'data> {
+    /// Return the symbol type. One of the `hunk::EXT_*` constants.
+    ///
+    /// For references, this is the type of the first reference.
+    #[inline]
+    pub fn ext_type(&self) -> u8 {
+        self.ext_type
+    }
+}
+
+impl<'data> read::private::Sealed for HunkSymbol<'data> {}
+
+impl<'data> ObjectSymbol<'data> for HunkSymbol<'data> {
+    #[inline]
+    fn index(&self) -> SymbolIndex {
+        self.index
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        Ok(self.name)
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name)
+            .ok()
+            .read_error("Non UTF-8 Hunk symbol name")
+    }
+
+    /// Return the offset of the symbol in its hunk, or its absolute value.
+    fn address(&self) -> u64 {
+        match self.section {
+            SymbolSection::Undefined | SymbolSection::Common => 0,
+            _ => self.value.into(),
+        }
+    }
+
+    /// Return the size of a common symbol.
+    fn size(&self) -> u64 {
+        match self.section {
+            SymbolSection::Common => self.value.into(),
+            _ => 0,
+        }
+    }
+
+    #[inline]
+    fn kind(&self) -> SymbolKind {
+        self.kind
+    }
+
+    #[inline]
+    fn section(&self) -> SymbolSection {
+        self.section
+    }
+
+    #[inline]
+    fn is_undefined(&self) -> bool {
+        self.section == SymbolSection::Undefined
+    }
+
+    #[inline]
+    fn is_definition(&self) -> bool {
+        matches!(
+            self.section,
+            SymbolSection::Section(_) | SymbolSection::Absolute
+        )
+    }
+
+    #[inline]
+    fn is_common(&self) -> bool {
+        self.section == SymbolSection::Common
+    }
+
+    #[inline]
+    fn is_weak(&self) -> bool {
+        false
+    }
+
+    fn scope(&self) -> SymbolScope {
+        if self.is_undefined() {
+            SymbolScope::Unknown
+        } else if self.global {
+            SymbolScope::Linkage
+        } else {
+            SymbolScope::Compilation
+        }
+    }
+
+    #[inline]
+    fn is_global(&self) -> bool {
+        self.global
+    }
+
+    #[inline]
+    fn is_local(&self) -> bool {
+        !self.global
+    }
+
+    #[inline]
+    fn flags(&self) -> SymbolFlags<SectionIndex, SymbolIndex> {
+        SymbolFlags::None
+    }
+}
+
+/// A symbol table in a [`HunkFile`](super::HunkFile).
+#[derive(Debug, Clone, Copy)]
+pub struct HunkSymbolTable<'data, 'file> {
+    pub(super) symbols: &'file [HunkSymbol<'data>],
+}
+
+impl<'data, 'file> read::private::Sealed for HunkSymbolTable<'data, 'file> {}
+
+impl<'data, 'file> ObjectSymbolTable<'data> for HunkSymbolTable<'data, 'file> {
+    type Symbol = HunkSymbol<'data>;
+    type SymbolIterator = HunkSymbolIterator<'data, 'file>;
+
+    fn symbols(&self) -> Self::SymbolIterator {
+        HunkSymbolIterator {
+            iter: self.symbols.iter(),
+        }
+    }
+
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<Self::Symbol> {
+        self.symbols
+            .get(index.0)
+            .copied()
+            .read_error("Invalid Hunk symbol index")
+    }
+}
+
+/// An iterator for the symbols in a [`HunkFile`](super::HunkFile).
+#[derive(Debug)]
+pub struct HunkSymbolIterator<'data, 'file> {
+    pub(super) iter: core::slice::Iter<'file, HunkSymbol<'data>>,
+}
+
+impl<'data, 'file> Iterator for HunkSymbolIterator<'data, 'file> {
+    type Item = HunkSymbol<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().copied()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
@@ -0,0 +1,446 @@
+use alloc::vec::Vec;
+
+use crate::ihex;
+use crate::read::{
+    self, Architecture, ComdatKind, Error, Export, FileFlags, Import, NoDynamicRelocationIterator,
+    Object, ObjectComdat, ObjectKind, ReadError, ReadRef, Result, SectionIndex, SymbolIndex,
+};
+
+use super::{
+    IhexSection, IhexSectionIterator, IhexSegment, IhexSegmentIterator, IhexSymbol,
+    IhexSymbolIterator, IhexSymbolTable,
+};
+
+/// A contiguous range of data in an [`IhexFile`].
+#[derive(Debug)]
+pub(super) struct IhexSectionData {
+    pub(super) address: u64,
+    pub(super) data: Vec<u8>,
+}
+
+/// An Intel HEX file.
+///
+/// Most functions in the [`Object`] trait are implemented for this type.
+#[derive(Debug)]
+pub struct IhexFile<'data, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    data: R,
+    pub(super) sections: Vec<IhexSectionData>,
+    start_segment_address: Option<(u16, u16)>,
+    start_linear_address: Option<u32>,
+    marker: core::marker::PhantomData<&'data ()>,
+}
+
+impl<'data, R: ReadRef<'data>> IhexFile<'data, R> {
+    /// Parse the raw Intel HEX file data.
+    pub fn parse(data: R) -> Result<Self> {
+        let len = data.len().read_error("Unknown Intel HEX file length")?;
+        let text = data
+            .read_bytes_at(0, len)
+            .read_error("Invalid Intel HEX file data")?;
+        let mut file = IhexFile {
+            data,
+            sections: Vec::new(),
+            start_segment_address: None,
+            start_linear_address: None,
+            marker: core::marker::PhantomData,
+        };
+        let mut base = 0;
+        for line in text.split(|c| *c == b'\n') {
+            let line = trim(line);
+            if line.is_empty() {
+                continue;
+            }
+            let record = parse_record(line)?;
+            let (record_type, address, data) = (record[3], &record[1..3], &record[4..]);
+            let address = u16::from_be_bytes([address[0], address[1]]);
+            match record_type {
+                ihex::IHEX_DATA => file.add_data(base, address, data),
+                ihex::IHEX_END_OF_FILE => break,
+                ihex::IHEX_EXTENDED_SEGMENT_ADDRESS => {
+                    let segment = read_u16(data)?;
+                    base = u64::from(segment) << 4;
+                }
+                ihex::IHEX_START_SEGMENT_ADDRESS => match *data {
+                    [a, b, c, d] => {
+                        let cs = u16::from_be_bytes([a, b]);
+                        let ip = u16::from_be_bytes([c, d]);
+                        file.start_segment_address = Some((cs, ip));
+                    }
+                    _ => return Err(Error("Invalid Intel HEX start segment address")),
+                },
+                ihex::IHEX_EXTENDED_LINEAR_ADDRESS => {
+                    let upper = read_u16(data)?;
+                    base = u64::from(upper) << 16;
+                }
+                ihex::IHEX_START_LINEAR_ADDRESS => match *data {
+                    [a, b, c, d] => {
+                        file.start_linear_address = Some(u32::from_be_bytes([a, b, c, d]));
+                    }
+                    _ => return Err(Error("Invalid Intel HEX start linear address")),
+                },
+                _ => return Err(Error("Unknown Intel HEX record type")),
+            }
+        }
+        Ok(file)
+    }
+
+    /// Add the data of a data record, starting a new section if it doesn't
+    /// follow the previous data.
+    fn add_data(&mut self, base: u64, offset: u16, data: &[u8]) {
+        // The offset wraps within the 64 KiB at the base address.
+        let split = data.len().min(0x1_0000 - usize::from(offset));
+        let (data, wrapped) = data.split_at(split);
+        self.add_data_at(base + u64::from(offset), data);
+        if !wrapped.is_empty() {
+            self.add_data_at(base, wrapped);
+        }
+    }
+
+    fn add_data_at(&mut self, address: u64, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+        if let Some(section) = self.sections.last_mut() {
+            if section.address + section.data.len() as u64 == address {
+                section.data.extend_from_slice(data);
+                return;
+            }
+        }
+        self.sections.push(IhexSectionData {
+            address,
+            data: data.to_vec(),
+        });
+    }
+
+    /// Return the CS and IP from the start segment address record, if any.
+    #[inline]
+    pub fn start_segment_address(&self) -> Option<(u16, u16)> {
+        self.start_segment_address
+    }
+
+    /// Return the EIP from the start linear address record, if any.
+    #[inline]
+    pub fn start_linear_address(&self) -> Option<u32> {
+        self.start_linear_address
+    }
+}
+
+/// Remove the line ending from a line.
+fn trim(mut line: &[u8]) -> &[u8] {
+    while let [rest @ .., b'\r' | b' ' | b'\t'] = line {
+        line = rest;
+    }
+    line
+}
+
+/// Decode a record, and check its length and checksum.
+///
+/// The returned bytes exclude the checksum.
+fn parse_record(line: &[u8]) -> Result<Vec<u8>> {
+    let digits = match line.split_first() {
+        Some((&ihex::IHEX_RECORD_MARK, digits)) if digits.len() % 2 == 0 => digits,
+        _ => return Err(Error("Invalid Intel HEX record")),
+    };
+    let mut record = Vec::with_capacity(digits.len() / 2);
+    for pair in digits.chunks_exact(2) {
+        let byte = hex_digit(pair[0])
+            .zip(hex_digit(pair[1]))
+            .map(|(high, low)| (high << 4) | low)
+            .read_error("Invalid Intel HEX digit")?;
+        record.push(byte);
+    }
+    if record.len() < ihex::IHEX_RECORD_OVERHEAD
+        || record.len() != usize::from(record[0]) + ihex::IHEX_RECORD_OVERHEAD
+    {
+        return Err(Error("Invalid Intel HEX record length"));
+    }
+    if record.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0 {
+        return Err(Error("Invalid Intel HEX record checksum"));
+    }
+    record.pop();
+    Ok(record)
+}
+
+fn hex_digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+fn read_u16(data: &[u8]) -> Result<u16> {
+    match *data {
+        [high, low] => Ok(u16::from_be_bytes([high, low])),
+        _ => Err(Error("Invalid Intel HEX address record")),
+    }
+}
+
+/// Check if the data starts with an Intel HEX record.
+///
+/// This checks the record mark, and that the count, address, and record type
+/// fields are hexadecimal digits giving a known record type.
+pub(crate) fn is_ihex_file<'data, R: ReadRef<'data>>(data: R) -> bool {
+    let header = match data.read_bytes_at(0, 9) {
+        Ok(header) => header,
+        Err(_) => return false,
+    };
+    header[0] == ihex::IHEX_RECORD_MARK
+        && header[1..].iter().all(|c| hex_digit(*c).is_some())
+        && header[7] == b'0'
+        && header[8] <= b'5'
+}
+
+impl<'data, R: ReadRef<'data>> read::private::Sealed for IhexFile<'data, R> {}
+
+impl<'data, R: ReadRef<'data>> Object<'data> for IhexFile<'data, R> {
+    type Segment<'file>
+        = IhexSegment<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SegmentIterator<'file>
+        = IhexSegmentIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Section<'file>
+        = IhexSection<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SectionIterator<'file>
+        = IhexSectionIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Comdat<'file>
+        = IhexComdat<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type ComdatIterator<'file>
+        = IhexComdatIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Symbol<'file>
+        = IhexSymbol<'data>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolIterator<'file>
+        = IhexSymbolIterator<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolTable<'file>
+        = IhexSymbolTable<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type DynamicRelocationIterator<'file>
+        = NoDynamicRelocationIterator
+    where
+        Self: 'file,
+        'data: 'file;
+
+    /// Intel HEX files don't record the architecture.
+    #[inline]
+    fn architecture(&self) -> Architecture {
+        Architecture::Unknown
+    }
+
+    /// Intel HEX files don't record the byte order, so this returns true.
+    #[inline]
+    fn is_little_endian(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn is_64(&self) -> bool {
+        false
+    }
+
+    fn kind(&self) -> ObjectKind {
+        ObjectKind::Executable
+    }
+
+    fn segments(&self) -> IhexSegmentIterator<'data, '_, R> {
+        IhexSegmentIterator {
+            sections: self.sections(),
+        }
+    }
+
+    fn section_by_name_bytes<'file>(
+        &'file self,
+        _section_name: &[u8],
+    ) -> Option<IhexSection<'data, 'file, R>> {
+        // Sections in Intel HEX files don't have names.
+        None
+    }
+
+    fn section_by_index(&self, index: SectionIndex) -> Result<IhexSection<'data, '_, R>> {
+        let section = index
+            .0
+            .checked_sub(1)
+            .and_then(|index| self.sections.get(index))
+            .read_error("Invalid Intel HEX section index")?;
+        Ok(IhexSection {
+            file: self,
+            index,
+            section,
+        })
+    }
+
+    fn sections(&self) -> IhexSectionIterator<'data, '_, R> {
+        IhexSectionIterator {
+            file: self,
+            iter: self.sections.iter().enumerate(),
+        }
+    }
+
+    #[inline]
+    fn comdats(&self) -> IhexComdatIterator<'data, '_, R> {
+        IhexComdatIterator { file: self }
+    }
+
+    fn symbol_by_index(&self, _index: SymbolIndex) -> Result<IhexSymbol<'data>> {
+        Err(Error("Intel HEX files have no symbols"))
+    }
+
+    fn symbols(&self) -> IhexSymbolIterator<'data, '_> {
+        IhexSymbolIterator::default()
+    }
+
+    #[inline]
+    fn symbol_table(&self) -> Option<IhexSymbolTable<'data, '_>> {
+        None
+    }
+
+    fn dynamic_symbols(&self) -> IhexSymbolIterator<'data, '_> {
+        IhexSymbolIterator::default()
+    }
+
+    #[inline]
+    fn dynamic_symbol_table(&self) -> Option<IhexSymbolTable<'data, '_>> {
+        None
+    }
+
+    #[inline]
+    fn dynamic_relocations(&self) -> Option<NoDynamicRelocationIterator> {
+        None
+    }
+
+    fn imports(&self) -> Result<Vec<Import<'data>>> {
+        Ok(Vec::new())
+    }
+
+    fn exports(&self) -> Result<Vec<Export<'data>>> {
+        Ok(Vec::new())
+    }
+
+    #[inline]
+    fn has_debug_symbols(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn relative_address_base(&self) -> u64 {
+        0
+    }
+
+    /// Return the address from the start linear address record, or the
+    /// linear address of the start segment address record.
+    fn entry(&self) -> u64 {
+        if let Some(eip) = self.start_linear_address {
+            eip.into()
+        } else if let Some((cs, ip)) = self.start_segment_address {
+            (u64::from(cs) << 4) + u64::from(ip)
+        } else {
+            0
+        }
+    }
+
+    #[inline]
+    fn flags(&self) -> FileFlags {
+        FileFlags::None
+    }
+}
+
+/// An iterator for the COMDAT section groups in an [`IhexFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct IhexComdatIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file IhexFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for IhexComdatIterator<'data, 'file, R> {
+    type Item = IhexComdat<'data, 'file, R>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+/// A COMDAT section group in an [`IhexFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct IhexComdat<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file IhexFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for IhexComdat<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectComdat<'data> for IhexComdat<'data, 'file, R> {
+    type SectionIterator = IhexComdatSectionIterator<'data, 'file, R>;
+
+    #[inline]
+    fn kind(&self) -> ComdatKind {
+        unreachable!();
+    }
+
+    #[inline]
+    fn symbol(&self) -> SymbolIndex {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn sections(&self) -> Self::SectionIterator {
+        unreachable!();
+    }
+}
+
+/// An iterator for the sections in a COMDAT section group in an [`IhexFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct IhexComdatSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file IhexFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for IhexComdatSectionIterator<'data, 'file, R> {
+    type Item = SectionIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
@@ -0,0 +1,31 @@
+//! Support for reading Intel HEX files.
+//!
+//! Intel HEX is a text format for firmware and ROM images. Both the extended
+//! segment address records used for 20-bit addresses and the extended linear
+//! address records used for 32-bit addresses are supported.
+//!
+//! ## High level API
+//!
+//! [`IhexFile`] implements the [`Object`](crate::read::Object) trait for
+//! Intel HEX files. Each contiguous range of data is a section and a segment,
+//! and the section data is decoded by
+//! [`ObjectSection::uncompressed_data`](crate::read::ObjectSection::uncompressed_data).
+//! The entry point is given by the start address records. Intel HEX files
+//! have no symbols or relocations.
+//!
+//! ## Low level API
+//!
+//! [`IhexFile`] also provides access to the start address records
+//! ([`IhexFile::start_segment_address`] and [`IhexFile::start_linear_address`]),
+//! and [`IhexSection::contents`] borrows the decoded data of a section.
+mod file;
+pub use file::*;
+
+mod section;
+pub use section::*;
+
+mod symbol;
+pub use symbol::*;
+
+mod relocation;
+pub use relocation::*;
@@ -0,0 +1,22 @@
+use crate::read::{ReadRef, Relocation};
+
+use super::IhexFile;
+
+/// An iterator for the relocations in an [`IhexSection`](super::IhexSection).
+///
+/// This is a stub that doesn't implement any functionality. Intel HEX files
+/// have no relocations.
+#[derive(Debug)]
+pub struct IhexRelocationIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    pub(super) file: &'file IhexFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for IhexRelocationIterator<'data, 'file, R> {
+    type Item = (u64, Relocation);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
@@ -0,0 +1,222 @@
+use alloc::borrow::Cow;
+
+use crate::read::{
+    self, CompressedData, CompressedFileRange, Error, ObjectSection, ObjectSegment, ReadRef,
+    RelocationMap, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags,
+};
+
+use super::{IhexFile, IhexRelocationIterator, IhexSectionData};
+
+/// An iterator for the sections in an [`IhexFile`].
+#[derive(Debug)]
+pub struct IhexSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file IhexFile<'data, R>,
+    pub(super) iter: core::iter::Enumerate<core::slice::Iter<'file, IhexSectionData>>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for IhexSectionIterator<'data, 'file, R> {
+    type Item = IhexSection<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(index, section)| IhexSection {
+            file: self.file,
+            index: SectionIndex(index + 1),
+            section,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// A section in an [`IhexFile`].
+///
+/// Each contiguous range of data records is a section, and is also a segment.
+/// The section index is the 1-based index of the range in the file.
+#[derive(Debug)]
+pub struct IhexSection<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file IhexFile<'data, R>,
+    pub(super) index: SectionIndex,
+    pub(super) section: &'file IhexSectionData,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> IhexSection<'data, 'file, R> {
+    /// Return the data decoded from the data records.
+    #[inline]
+    pub fn contents(&self) -> &'file [u8] {
+        &self.section.data
+    }
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for IhexSection<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSection<'data> for IhexSection<'data, 'file, R> {
+    type RelocationIterator = IhexRelocationIterator<'data, 'file, R>;
+
+    #[inline]
+    fn index(&self) -> SectionIndex {
+        self.index
+    }
+
+    #[inline]
+    fn address(&self) -> u64 {
+        self.section.address
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.section.data.len() as u64
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        1
+    }
+
+    #[inline]
+    fn file_range(&self) -> Option<(u64, u64)> {
+        None
+    }
+
+    /// Returns an error because the data is encoded as text in the file.
+    ///
+    /// Use [`ObjectSection::uncompressed_data`] or [`IhexSection::contents`] instead.
+    fn data(&self) -> Result<&'data [u8]> {
+        Err(Error("Intel HEX section data must be decoded"))
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        Ok(read::util::data_range(
+            self.data()?,
+            self.address(),
+            address,
+            size,
+        ))
+    }
+
+    #[inline]
+    fn compressed_file_range(&self) -> Result<CompressedFileRange> {
+        Ok(CompressedFileRange::none(self.file_range()))
+    }
+
+    #[inline]
+    fn compressed_data(&self) -> Result<CompressedData<'data>> {
+        self.data().map(CompressedData::none)
+    }
+
+    /// Return the data decoded from the data records.
+    fn uncompressed_data(&self) -> Result<Cow<'data, [u8]>> {
+        Ok(Cow::Owned(self.section.data.clone()))
+    }
+
+    /// Sections in Intel HEX files don't have names, so return an empty name.
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        Ok(&[])
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        Ok("")
+    }
+
+    #[inline]
+    fn segment_name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn segment_name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    /// The data may contain both code and data.
+    #[inline]
+    fn kind(&self) -> SectionKind {
+        SectionKind::Text
+    }
+
+    fn relocations(&self) -> IhexRelocationIterator<'data, 'file, R> {
+        IhexRelocationIterator { file: self.file }
+    }
+
+    fn relocation_map(&self) -> Result<RelocationMap> {
+        RelocationMap::new(self.file, self)
+    }
+
+    #[inline]
+    fn flags(&self) -> SectionFlags {
+        SectionFlags::None
+    }
+}
+
+/// An iterator for the segments in an [`IhexFile`].
+#[derive(Debug)]
+pub struct IhexSegmentIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) sections: IhexSectionIterator<'data, 'file, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for IhexSegmentIterator<'data, 'file, R> {
+    type Item = IhexSegment<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.sections.next().map(|section| IhexSegment { section })
+    }
+}
+
+/// A segment in an [`IhexFile`].
+///
+/// Each segment is also a section.
+#[derive(Debug)]
+pub struct IhexSegment<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) section: IhexSection<'data, 'file, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for IhexSegment<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSegment<'data> for IhexSegment<'data, 'file, R> {
+    #[inline]
+    fn address(&self) -> u64 {
+        self.section.address()
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.section.size()
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        self.section.align()
+    }
+
+    #[inline]
+    fn file_range(&self) -> (u64, u64) {
+        (0, 0)
+    }
+
+    /// Returns an error because the data is encoded as text in the file.
+    fn data(&self) -> Result<&'data [u8]> {
+        self.section.data()
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        self.section.data_range(address, size)
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn flags(&self) -> SegmentFlags {
+        SegmentFlags::None
+    }
+}
@@ -0,0 +1,1024 @@
+use alloc::vec::Vec;
+
+use crate::endian::{LittleEndian as LE, U16Bytes, U32Bytes};
+use crate::lx;
+use crate::pe;
+use crate::read::{
+    self, Architecture, ByteString, ComdatKind, Error, Export, FileFlags, Import,
+    NoDynamicRelocationIterator, Object, ObjectComdat, ObjectKind, ReadError, ReadRef, Result,
+    SectionIndex, SymbolIndex, SymbolKind, SymbolSection,
+};
+
+use super::{
+    LxResolvedRelocation, LxSection, LxSectionIterator, LxSegment, LxSegmentIterator, LxSymbol,
+    LxSymbolIterator, LxSymbolTable,
+};
+
+/// An entry in the object page table of an [`LxFile`].
+///
+/// The LE and LX page table formats are both converted to this.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LxPage {
+    /// The file offset of the page data.
+    pub offset: u64,
+    /// The size of the page data in the file.
+    pub size: u32,
+    /// The page flags. See the `PAGE_*` constants.
+    pub flags: u16,
+}
+
+/// An entry point in the entry table of an [`LxFile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LxEntry {
+    /// The 1-based ordinal of the entry point.
+    pub ordinal: u16,
+    /// The type of the bundle containing the entry point.
+    ///
+    /// One of [`lx::ENTRY16`], [`lx::ENTRY_GATE16`], [`lx::ENTRY32`] or
+    /// [`lx::ENTRY_FORWARDER`].
+    pub kind: u8,
+    /// The entry flags. See the `ENTRY_*` flag constants.
+    pub flags: u8,
+    /// The 1-based object number, or the 1-based import module ordinal for
+    /// a forwarder.
+    pub object: u16,
+    /// The offset within the object, or the import ordinal or procedure name
+    /// offset for a forwarder.
+    pub offset: u32,
+    /// The call gate selector for a 286 call gate entry, or 0.
+    pub selector: u16,
+}
+
+/// A name in the resident or non-resident name table of an [`LxFile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LxName<'data> {
+    /// The name.
+    pub name: &'data [u8],
+    /// The ordinal of the entry point with this name.
+    ///
+    /// This is 0 for the module name and description.
+    pub ordinal: u16,
+}
+
+/// A function imported by an [`LxFile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LxImport<'data> {
+    /// The name of the module the function is imported from.
+    pub module: &'data [u8],
+    /// The name or ordinal of the function.
+    pub name: LxImportName<'data>,
+}
+
+/// The name or ordinal of a function imported by an [`LxFile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LxImportName<'data> {
+    /// Imported by name.
+    Name(&'data [u8]),
+    /// Imported by ordinal.
+    Ordinal(u32),
+}
+
+/// An OS/2 LX file, or a VxD or DOS extender LE file.
+///
+/// Most functions in the [`Object`] trait are implemented for this type.
+#[derive(Debug)]
+pub struct LxFile<'data, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) data: R,
+    pub(super) dos_header: &'data pe::ImageDosHeader,
+    pub(super) header: &'data pe::ImageVxdHeader,
+    base_offset: u64,
+    pub(super) header_offset: u64,
+    pub(super) objects: &'data [lx::ObjectTableEntry],
+    pages: Vec<LxPage>,
+    entries: Vec<LxEntry>,
+    resident_names: Vec<LxName<'data>>,
+    nonresident_names: Vec<LxName<'data>>,
+    import_modules: Vec<&'data [u8]>,
+    imports: Vec<LxImport<'data>>,
+    pub(super) symbols: Vec<LxSymbol<'data>>,
+    /// The relocations of each object, with source lists expanded.
+    pub(super) relocations: Vec<Vec<(u64, LxResolvedRelocation)>>,
+}
+
+impl<'data, R: ReadRef<'data>> LxFile<'data, R> {
+    /// Parse the raw LE or LX file data.
+    pub fn parse(data: R) -> Result<Self> {
+        let Headers {
+            dos_header,
+            base_offset,
+            header_offset,
+            header,
+        } = parse_headers(data)?;
+        if header.e32_border != 0 || header.e32_worder != 0 {
+            return Err(Error("Unsupported big-endian LX file"));
+        }
+        let table = |offset: u32| header_offset + u64::from(offset);
+        let objects = data
+            .read_slice_at::<lx::ObjectTableEntry>(
+                table(header.e32_objtab.get(LE)),
+                header.e32_objcnt.get(LE) as usize,
+            )
+            .read_error("Invalid LX object table offset or size")?;
+        let pages = parse_pages(data, base_offset, header_offset, header)?;
+        let entries = parse_entries(data, table(header.e32_enttab.get(LE)))?;
+        let resident_names = parse_names(data, table(header.e32_restab.get(LE)), None)?;
+        let nonresident_names = match header.e32_cbnrestab.get(LE) {
+            0 => Vec::new(),
+            size => parse_names(
+                data,
+                base_offset + u64::from(header.e32_nrestab.get(LE)),
+                Some(size.into()),
+            )?,
+        };
+        let mut offset = table(header.e32_impmod.get(LE));
+        let import_modules = (0..header.e32_impmodcnt.get(LE))
+            .map(|_| {
+                let name = read_name(data, offset)?;
+                offset += 1 + name.len() as u64;
+                Ok(name)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut file = LxFile {
+            data,
+            dos_header,
+            header,
+            base_offset,
+            header_offset,
+            objects,
+            pages,
+            entries,
+            resident_names,
+            nonresident_names,
+            import_modules,
+            imports: Vec::new(),
+            symbols: Vec::new(),
+            relocations: Vec::new(),
+        };
+        file.symbols = file.export_symbols();
+
+        let mut object_fixups = Vec::with_capacity(objects.len());
+        for object in objects {
+            let mut fixups = Vec::new();
+            let first = object.page_table_index.get(LE).saturating_sub(1) as usize;
+            for page in 0..object.page_count.get(LE) as usize {
+                let base = page as u64 * u64::from(file.page_size());
+                for fixup in file.page_fixups(first + page)? {
+                    fixups.push((base, fixup));
+                }
+            }
+            object_fixups.push(fixups);
+        }
+        for (_, fixup) in object_fixups.iter().flatten() {
+            if let Some(import) = file.fixup_import(fixup.target)? {
+                if !file.imports.contains(&import) {
+                    file.imports.push(import);
+                }
+            }
+        }
+        let first_import = file.symbols.len();
+        for (i, import) in file.imports.iter().enumerate() {
+            file.symbols.push(LxSymbol {
+                index: SymbolIndex(first_import + i),
+                // Imports by ordinal have no name.
+                name: match import.name {
+                    LxImportName::Name(name) => name,
+                    LxImportName::Ordinal(_) => &[],
+                },
+                section: SymbolSection::Undefined,
+                address: 0,
+                kind: SymbolKind::Unknown,
+                entry: None,
+                import: Some(*import),
+            });
+        }
+
+        let mut relocations = Vec::with_capacity(objects.len());
+        for fixups in &object_fixups {
+            let mut generic = Vec::new();
+            for (base, fixup) in fixups {
+                let symbol = match file.fixup_import(fixup.target)? {
+                    Some(import) => file
+                        .imports
+                        .iter()
+                        .position(|i| *i == import)
+                        .map(|index| SymbolIndex(first_import + index)),
+                    None => None,
+                };
+                let resolved = fixup.resolve(&file, symbol)?;
+                // Fixups that cross a page boundary are repeated with a
+                // negative offset in the following page.
+                for &offset in fixup.offsets.iter().filter(|&&offset| offset >= 0) {
+                    generic.push((base + offset as u64, resolved));
+                }
+            }
+            relocations.push(generic);
+        }
+        file.relocations = relocations;
+        Ok(file)
+    }
+
+    /// Return the DOS header at the start of this file.
+    ///
+    /// For bound executables, this is the header of the DOS extender stub.
+    pub fn dos_header(&self) -> &'data pe::ImageDosHeader {
+        self.dos_header
+    }
+
+    /// Return the LE or LX header of this file.
+    pub fn header(&self) -> &'data pe::ImageVxdHeader {
+        self.header
+    }
+
+    /// Return the file offset of the bound LE or LX image.
+    ///
+    /// This is non-zero for executables that are bound to a DOS extender,
+    /// such as DOS/4GW. The offsets of the data pages and the non-resident
+    /// name table are relative to this.
+    pub fn base_offset(&self) -> u64 {
+        self.base_offset
+    }
+
+    /// Return the file offset of the LE or LX header.
+    ///
+    /// Most table offsets in the header are relative to this.
+    pub fn header_offset(&self) -> u64 {
+        self.header_offset
+    }
+
+    /// Return true if this is an LE file, or false if it is an LX file.
+    pub fn is_le(&self) -> bool {
+        self.header.e32_magic.get(LE) == lx::IMAGE_LE_SIGNATURE
+    }
+
+    /// Return the size of a page in memory.
+    pub fn page_size(&self) -> u32 {
+        self.header.e32_pagesize.get(LE)
+    }
+
+    /// Return the object table.
+    pub fn object_table(&self) -> &'data [lx::ObjectTableEntry] {
+        self.objects
+    }
+
+    /// Return the object page table.
+    pub fn pages(&self) -> &[LxPage] {
+        &self.pages
+    }
+
+    /// Return the entry table, omitting unused ordinals.
+    pub fn entries(&self) -> &[LxEntry] {
+        &self.entries
+    }
+
+    /// Return the entry with the given ordinal.
+    pub fn entry_by_ordinal(&self, ordinal: u16) -> Option<&LxEntry> {
+        self.entries.iter().find(|entry| entry.ordinal == ordinal)
+    }
+
+    /// Return the resident name table.
+    ///
+    /// The first name is the module name.
+    pub fn resident_names(&self) -> &[LxName<'data>] {
+        &self.resident_names
+    }
+
+    /// Return the non-resident name table.
+    ///
+    /// The first name is the module description.
+    pub fn nonresident_names(&self) -> &[LxName<'data>] {
+        &self.nonresident_names
+    }
+
+    /// Return the module name, which is the first name in the resident name table.
+    pub fn module_name(&self) -> Option<&'data [u8]> {
+        self.resident_names.first().map(|name| name.name)
+    }
+
+    /// Return the module description, which is the first name in the
+    /// non-resident name table.
+    pub fn description(&self) -> Option<&'data [u8]> {
+        self.nonresident_names.first().map(|name| name.name)
+    }
+
+    /// Return the names in the import module name table.
+    ///
+    /// Fixup records refer to these using a 1-based ordinal.
+    pub fn import_modules(&self) -> &[&'data [u8]] {
+        &self.import_modules
+    }
+
+    /// Return the functions imported by fixup records, in order of first use.
+    ///
+    /// Unlike [`Object::imports`], this includes imports by ordinal.
+    pub fn import_list(&self) -> &[LxImport<'data>] {
+        &self.imports
+    }
+
+    /// Return the object with the given 1-based index.
+    pub(super) fn object(&self, index: SectionIndex) -> Result<&'data lx::ObjectTableEntry> {
+        index
+            .0
+            .checked_sub(1)
+            .and_then(|index| self.objects.get(index))
+            .read_error("Invalid LX object index")
+    }
+
+    /// Return the entries in the object page table for an object.
+    pub fn object_pages(&self, object: &lx::ObjectTableEntry) -> Result<&[LxPage]> {
+        let first = object.page_table_index.get(LE).saturating_sub(1) as usize;
+        let count = object.page_count.get(LE) as usize;
+        first
+            .checked_add(count)
+            .and_then(|end| self.pages.get(first..end))
+            .read_error("Invalid LX object page table index or count")
+    }
+
+    /// Return the range of the data of an object in the file, if it is stored
+    /// contiguously.
+    ///
+    /// Returns `Ok(None)` if the object has no data in the file, and an error
+    /// if the data is not contiguous or is compressed.
+    pub(super) fn object_file_range(
+        &self,
+        object: &lx::ObjectTableEntry,
+    ) -> Result<Option<(u64, u64)>> {
+        let unsupported = Error("Unsupported LX object page layout");
+        let mut range: Option<(u64, u64)> = None;
+        let mut ended = false;
+        for page in self.object_pages(object)? {
+            match page.flags {
+                lx::PAGE_VALID | lx::PAGE_RANGE if !ended => {
+                    range = match range {
+                        None => Some((page.offset, page.size.into())),
+                        // Only the last page may be shorter than the page size.
+                        Some((offset, size))
+                            if offset + size == page.offset
+                                && size % u64::from(self.page_size()) == 0 =>
+                        {
+                            Some((offset, size + u64::from(page.size)))
+                        }
+                        Some(_) => return Err(unsupported),
+                    };
+                    if page.size != self.page_size() {
+                        ended = true;
+                    }
+                }
+                lx::PAGE_INVALID | lx::PAGE_ZEROED => ended = true,
+                _ => return Err(unsupported),
+            }
+        }
+        Ok(range)
+    }
+
+    /// Return the data of an object, expanding its pages.
+    ///
+    /// Zero-filled and invalid pages are filled with zeros, and iterated pages
+    /// are expanded. The data is truncated to the virtual size of the object.
+    pub fn object_data(&self, index: SectionIndex) -> Result<Vec<u8>> {
+        let object = self.object(index)?;
+        let page_size = self.page_size();
+        if !page_size.is_power_of_two() || page_size > MAX_PAGE_SIZE {
+            return Err(Error("Invalid LX page size"));
+        }
+        let page_size = page_size as usize;
+        let size = object.virtual_size.get(LE) as usize;
+        let mut data = Vec::new();
+        data.try_reserve_exact(size)
+            .ok()
+            .read_error("LX object data allocation failed")?;
+        for page in self.object_pages(object)? {
+            // Pages past the virtual size are never used.
+            if data.len() >= size {
+                break;
+            }
+            let start = data.len();
+            match page.flags {
+                lx::PAGE_VALID | lx::PAGE_RANGE => {
+                    data.extend_from_slice(
+                        self.data
+                            .read_bytes_at(page.offset, page.size.into())
+                            .read_error("Invalid LX page offset or size")?,
+                    );
+                }
+                lx::PAGE_ITERDATA => {
+                    let page_data = self
+                        .data
+                        .read_bytes_at(page.offset, page.size.into())
+                        .read_error("Invalid LX page offset or size")?;
+                    expand_iterated_page(page_data, &mut data, page_size)?;
+                }
+                lx::PAGE_INVALID | lx::PAGE_ZEROED => {}
+                _ => return Err(Error("Unsupported LX page compression")),
+            }
+            data.resize(start + page_size, 0);
+        }
+        data.truncate(size);
+        Ok(data)
+    }
+
+    /// Return the symbol kind for addresses in an object.
+    fn object_symbol_kind(&self, index: u16) -> SymbolKind {
+        match self.object(SectionIndex(index.into())) {
+            Ok(object) if object.flags.get(LE) & lx::OBJ_EXEC != 0 => SymbolKind::Text,
+            Ok(_) => SymbolKind::Data,
+            Err(_) => SymbolKind::Unknown,
+        }
+    }
+
+    /// Return the linear address of an offset in an object.
+    pub(super) fn object_address(&self, index: u16, offset: u32) -> u64 {
+        let base = self
+            .object(SectionIndex(index.into()))
+            .map_or(0, |object| object.base_address.get(LE));
+        u64::from(base).wrapping_add(offset.into())
+    }
+
+    /// Create a symbol for each named entry point.
+    ///
+    /// Forwarders are omitted, since they are defined in another module.
+    fn export_symbols(&self) -> Vec<LxSymbol<'data>> {
+        // The first name in each table is the module name or description.
+        let names = self
+            .resident_names
+            .iter()
+            .skip(1)
+            .chain(self.nonresident_names.iter().skip(1));
+        let mut symbols = Vec::new();
+        for name in names {
+            let entry = match self.entry_by_ordinal(name.ordinal) {
+                Some(entry) if entry.kind != lx::ENTRY_FORWARDER => *entry,
+                _ => continue,
+            };
+            symbols.push(LxSymbol {
+                index: SymbolIndex(symbols.len()),
+                name: name.name,
+                section: SymbolSection::Section(SectionIndex(entry.object.into())),
+                address: self.object_address(entry.object, entry.offset),
+                kind: self.object_symbol_kind(entry.object),
+                entry: Some(entry),
+                import: None,
+            });
+        }
+        symbols
+    }
+}
+
+/// Return true if the data is an LE or LX file.
+pub fn is_lx_file<'data, R: ReadRef<'data>>(data: R) -> bool {
+    parse_headers(data).is_ok()
+}
+
+/// The LE or LX headers, and the offsets needed to find the other tables.
+struct Headers<'data> {
+    dos_header: &'data pe::ImageDosHeader,
+    base_offset: u64,
+    header_offset: u64,
+    header: &'data pe::ImageVxdHeader,
+}
+
+/// The maximum number of stub headers to skip when looking for a bound image.
+const MAX_STUB_HEADERS: usize = 16;
+
+/// The largest supported page size.
+const MAX_PAGE_SIZE: u32 = 0x10000;
+
+/// Find the LE or LX header.
+///
+/// The header is usually given by `e_lfanew` in the DOS header. Executables
+/// that are bound to a DOS extender instead have a chain of stub programs,
+/// each of which starts with an MS-DOS or DOS/4G (`BW`) header. The chain
+/// is followed using the image size of each stub until either a stub whose
+/// `e_lfanew` gives the header, or the header itself, is found.
+fn parse_headers<'data, R: ReadRef<'data>>(data: R) -> Result<Headers<'data>> {
+    let dos_header = data
+        .read_at::<pe::ImageDosHeader>(0)
+        .read_error("Invalid DOS header size or alignment")?;
+    if dos_header.e_magic.get(LE) != pe::IMAGE_DOS_SIGNATURE {
+        return Err(Error("Invalid DOS magic"));
+    }
+    let read_header = |offset: u64| {
+        data.read_at::<pe::ImageVxdHeader>(offset)
+            .ok()
+            .filter(|header| {
+                matches!(
+                    header.e32_magic.get(LE),
+                    lx::IMAGE_LE_SIGNATURE | lx::IMAGE_LX_SIGNATURE
+                )
+            })
+    };
+    let mut offset = 0;
+    for _ in 0..MAX_STUB_HEADERS {
+        // The signature, bytes in the last page, and number of pages.
+        let stub = data
+            .read_at::<[U16Bytes<LE>; 3]>(offset)
+            .read_error("Invalid LX stub header offset")?;
+        match stub[0].get(LE) {
+            pe::IMAGE_DOS_SIGNATURE => {
+                let lfanew = data
+                    .read_at::<U32Bytes<LE>>(offset + 0x3c)
+                    .read_error("Invalid LX stub header size")?
+                    .get(LE);
+                let header_offset = offset + u64::from(lfanew);
+                if let Some(header) = read_header(header_offset) {
+                    // File offsets in the header are relative to the
+                    // stub of the bound image.
+                    return Ok(Headers {
+                        dos_header,
+                        base_offset: offset,
+                        header_offset,
+                        header,
+                    });
+                }
+            }
+            lx::IMAGE_BW_SIGNATURE => {}
+            lx::IMAGE_LE_SIGNATURE | lx::IMAGE_LX_SIGNATURE => {
+                let header = read_header(offset).read_error("Invalid LX header size")?;
+                return Ok(Headers {
+                    dos_header,
+                    base_offset: 0,
+                    header_offset: offset,
+                    header,
+                });
+            }
+            _ => break,
+        }
+        let mut size = u64::from(stub[2].get(LE)) * 512;
+        let last_page_size = stub[1].get(LE);
+        if last_page_size != 0 {
+            let unused = 512u64
+                .checked_sub(last_page_size.into())
+                .read_error("Invalid LX stub last page size")?;
+            size = size.saturating_sub(unused);
+        }
+        if size == 0 {
+            break;
+        }
+        offset += size;
+    }
+    Err(Error("Invalid LX header offset"))
+}
+
+/// Parse the object page table, converting LE entries to the LX form.
+fn parse_pages<'data, R: ReadRef<'data>>(
+    data: R,
+    base_offset: u64,
+    header_offset: u64,
+    header: &pe::ImageVxdHeader,
+) -> Result<Vec<LxPage>> {
+    let offset = header_offset + u64::from(header.e32_objmap.get(LE));
+    let count = header.e32_mpages.get(LE) as usize;
+    let data_pages = base_offset + u64::from(header.e32_datapage.get(LE));
+    let page_size = header.e32_pagesize.get(LE);
+    if header.e32_magic.get(LE) == lx::IMAGE_LE_SIGNATURE {
+        let entries = data
+            .read_slice_at::<lx::LeObjectPageEntry>(offset, count)
+            .read_error("Invalid LE object page table offset or size")?;
+        entries
+            .iter()
+            .map(|entry| {
+                let [high, middle, low] = entry.page_number;
+                let number = u32::from_be_bytes([0, high, middle, low]);
+                let index = number
+                    .checked_sub(1)
+                    .read_error("Invalid LE object page number")?;
+                // Only the last page in the file may be shorter than the page size.
+                let size = if number as usize == count {
+                    header.e32_lastpagesize.get(LE)
+                } else {
+                    page_size
+                };
+                Ok(LxPage {
+                    offset: data_pages + u64::from(index) * u64::from(page_size),
+                    size,
+                    flags: entry.flags.into(),
+                })
+            })
+            .collect()
+    } else {
+        let shift = header.e32_lastpagesize.get(LE);
+        let entries = data
+            .read_slice_at::<lx::LxObjectPageEntry>(offset, count)
+            .read_error("Invalid LX object page table offset or size")?;
+        entries
+            .iter()
+            .map(|entry| {
+                let offset = u64::from(entry.offset.get(LE))
+                    .checked_shl(shift)
+                    .read_error("Invalid LX page offset shift")?;
+                Ok(LxPage {
+                    offset: data_pages + offset,
+                    size: entry.size.get(LE).into(),
+                    flags: entry.flags.get(LE),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parse the bundles in the entry table.
+fn parse_entries<'data, R: ReadRef<'data>>(data: R, mut offset: u64) -> Result<Vec<LxEntry>> {
+    let invalid = "Invalid LX entry table";
+    let read_u8 = |offset: &mut u64| data.read::<u8>(offset).copied().read_error(invalid);
+    let read_u16 = |offset: &mut u64| {
+        data.read::<U16Bytes<LE>>(offset)
+            .map(|x| x.get(LE))
+            .read_error(invalid)
+    };
+    let read_u32 = |offset: &mut u64| {
+        data.read::<U32Bytes<LE>>(offset)
+            .map(|x| x.get(LE))
+            .read_error(invalid)
+    };
+
+    let mut entries = Vec::new();
+    let mut ordinal = 1u16;
+    loop {
+        let count = read_u8(&mut offset)?;
+        if count == 0 {
+            break;
+        }
+        let kind = read_u8(&mut offset)? & !lx::ENTRY_TYPEINFO;
+        if kind == lx::ENTRY_UNUSED {
+            ordinal = ordinal.wrapping_add(count.into());
+            continue;
+        }
+        // The object number, which is reserved for forwarders.
+        let object = read_u16(&mut offset)?;
+        for _ in 0..count {
+            let flags = read_u8(&mut offset)?;
+            let entry = match kind {
+                lx::ENTRY16 => LxEntry {
+                    ordinal,
+                    kind,
+                    flags,
+                    object,
+                    offset: read_u16(&mut offset)?.into(),
+                    selector: 0,
+                },
+                lx::ENTRY_GATE16 => LxEntry {
+                    ordinal,
+                    kind,
+                    flags,
+                    object,
+                    offset: read_u16(&mut offset)?.into(),
+                    selector: read_u16(&mut offset)?,
+                },
+                lx::ENTRY32 => LxEntry {
+                    ordinal,
+                    kind,
+                    flags,
+                    object,
+                    offset: read_u32(&mut offset)?,
+                    selector: 0,
+                },
+                lx::ENTRY_FORWARDER => LxEntry {
+                    ordinal,
+                    kind,
+                    flags,
+                    object: read_u16(&mut offset)?,
+                    offset: read_u32(&mut offset)?,
+                    selector: 0,
+                },
+                _ => return Err(Error("Unsupported LX entry table bundle type")),
+            };
+            entries.push(entry);
+            ordinal = ordinal.wrapping_add(1);
+        }
+    }
+    Ok(entries)
+}
+
+/// Expand a page that uses EXEPACK1 iterated records.
+///
+/// Each record is a 16-bit repeat count, a 16-bit length, and the data to
+/// repeat. A repeat count of 0 ends the page.
+fn expand_iterated_page(mut page: &[u8], out: &mut Vec<u8>, page_size: usize) -> Result<()> {
+    let limit = out.len() + page_size;
+    while page.len() >= 4 {
+        let count = usize::from(u16::from_le_bytes([page[0], page[1]]));
+        let length = usize::from(u16::from_le_bytes([page[2], page[3]]));
+        if count == 0 {
+            break;
+        }
+        let data = page
+            .get(4..4 + length)
+            .read_error("Invalid LX iterated page record")?;
+        if out.len() + count * length > limit {
+            return Err(Error("Invalid LX iterated page size"));
+        }
+        for _ in 0..count {
+            out.extend_from_slice(data);
+        }
+        page = &page[4 + length..];
+    }
+    Ok(())
+}
+
+/// Read a name that is prefixed by its length.
+fn read_name<'data, R: ReadRef<'data>>(data: R, offset: u64) -> Result<&'data [u8]> {
+    let length = *data
+        .read_at::<u8>(offset)
+        .read_error("Invalid LX name offset")?;
+    data.read_bytes_at(offset + 1, length.into())
+        .read_error("Invalid LX name length")
+}
+
+/// Parse a resident or non-resident name table.
+///
+/// The table ends with an empty name, or after `size` bytes if given.
+fn parse_names<'data, R: ReadRef<'data>>(
+    data: R,
+    mut offset: u64,
+    size: Option<u64>,
+) -> Result<Vec<LxName<'data>>> {
+    let end = size.map(|size| offset + size);
+    let mut names = Vec::new();
+    while end.map_or(true, |end| offset < end) {
+        let name = read_name(data, offset)?;
+        if name.is_empty() {
+            break;
+        }
+        offset += 1 + name.len() as u64;
+        let ordinal = data
+            .read_at::<U16Bytes<LE>>(offset)
+            .read_error("Invalid LX name table ordinal")?
+            .get(LE);
+        offset += 2;
+        names.push(LxName { name, ordinal });
+    }
+    Ok(names)
+}
+
+impl<'data, R: ReadRef<'data>> read::private::Sealed for LxFile<'data, R> {}
+
+impl<'data, R: ReadRef<'data>> Object<'data> for LxFile<'data, R> {
+    type Segment<'file>
+        = LxSegment<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SegmentIterator<'file>
+        = LxSegmentIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Section<'file>
+        = LxSection<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SectionIterator<'file>
+        = LxSectionIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Comdat<'file>
+        = LxComdat<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type ComdatIterator<'file>
+        = LxComdatIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Symbol<'file>
+        = LxSymbol<'data>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolIterator<'file>
+        = LxSymbolIterator<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolTable<'file>
+        = LxSymbolTable<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type DynamicRelocationIterator<'file>
+        = NoDynamicRelocationIterator
+    where
+        Self: 'file,
+        'data: 'file;
+
+    fn architecture(&self) -> Architecture {
+        Architecture::I386
+    }
+
+    #[inline]
+    fn is_little_endian(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn is_64(&self) -> bool {
+        false
+    }
+
+    fn kind(&self) -> ObjectKind {
+        match self.header.e32_mflags.get(LE) & lx::E32_MODMASK {
+            lx::E32_MODEXE => ObjectKind::Executable,
+            _ => ObjectKind::Dynamic,
+        }
+    }
+
+    fn segments(&self) -> LxSegmentIterator<'data, '_, R> {
+        LxSegmentIterator {
+            sections: self.sections(),
+        }
+    }
+
+    fn section_by_name_bytes<'file>(
+        &'file self,
+        section_name: &[u8],
+    ) -> Option<LxSection<'data, 'file, R>> {
+        use crate::read::ObjectSection;
+        self.sections()
+            .find(|section| section.name_bytes() == Ok(section_name))
+    }
+
+    fn section_by_index(&self, index: SectionIndex) -> Result<LxSection<'data, '_, R>> {
+        let object = self.object(index)?;
+        Ok(LxSection {
+            file: self,
+            index,
+            object,
+        })
+    }
+
+    fn sections(&self) -> LxSectionIterator<'data, '_, R> {
+        LxSectionIterator {
+            file: self,
+            iter: self.objects.iter().enumerate(),
+        }
+    }
+
+    #[inline]
+    fn comdats(&self) -> LxComdatIterator<'data, '_, R> {
+        LxComdatIterator { file: self }
+    }
+
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<LxSymbol<'data>> {
+        self.symbols
+            .get(index.0)
+            .cloned()
+            .read_error("Invalid LX symbol index")
+    }
+
+    fn symbols(&self) -> LxSymbolIterator<'data, '_> {
+        LxSymbolIterator {
+            iter: self.symbols.iter(),
+        }
+    }
+
+    fn symbol_table(&self) -> Option<LxSymbolTable<'data, '_>> {
+        Some(LxSymbolTable {
+            symbols: &self.symbols,
+        })
+    }
+
+    fn dynamic_symbols(&self) -> LxSymbolIterator<'data, '_> {
+        LxSymbolIterator { iter: [].iter() }
+    }
+
+    #[inline]
+    fn dynamic_symbol_table(&self) -> Option<LxSymbolTable<'data, '_>> {
+        None
+    }
+
+    #[inline]
+    fn dynamic_relocations(&self) -> Option<NoDynamicRelocationIterator> {
+        None
+    }
+
+    fn imports(&self) -> Result<Vec<Import<'data>>> {
+        // Like PE, imports by ordinal are omitted because they have no name.
+        let imports = self
+            .imports
+            .iter()
+            .filter_map(|import| match import.name {
+                LxImportName::Name(name) => Some(Import {
+                    library: ByteString(import.module),
+                    name: ByteString(name),
+                }),
+                LxImportName::Ordinal(_) => None,
+            })
+            .collect();
+        Ok(imports)
+    }
+
+    /// Return the named entry points, other than forwarders.
+    fn exports(&self) -> Result<Vec<Export<'data>>> {
+        let exports = self
+            .symbols
+            .iter()
+            .filter(|symbol| symbol.entry.is_some())
+            .map(|symbol| Export {
+                name: ByteString(symbol.name),
+                address: symbol.address,
+            })
+            .collect();
+        Ok(exports)
+    }
+
+    #[inline]
+    fn has_debug_symbols(&self) -> bool {
+        self.header.e32_debuglen.get(LE) != 0
+    }
+
+    #[inline]
+    fn relative_address_base(&self) -> u64 {
+        0
+    }
+
+    /// Return the linear address of the initial EIP in the initial object.
+    fn entry(&self) -> u64 {
+        match self.header.e32_startobj.get(LE) {
+            0 => 0,
+            object => self.object_address(object as u16, self.header.e32_eip.get(LE)),
+        }
+    }
+
+    #[inline]
+    fn flags(&self) -> FileFlags {
+        FileFlags::None
+    }
+}
+
+/// An iterator for the COMDAT section groups in a [`LxFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct LxComdatIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file LxFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for LxComdatIterator<'data, 'file, R> {
+    type Item = LxComdat<'data, 'file, R>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+/// A COMDAT section group in a [`LxFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct LxComdat<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file LxFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for LxComdat<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectComdat<'data> for LxComdat<'data, 'file, R> {
+    type SectionIterator = LxComdatSectionIterator<'data, 'file, R>;
+
+    #[inline]
+    fn kind(&self) -> ComdatKind {
+        unreachable!();
+    }
+
+    #[inline]
+    fn symbol(&self) -> SymbolIndex {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn sections(&self) -> Self::SectionIterator {
+        unreachable!();
+    }
+}
+
+/// An iterator for the sections in a COMDAT section group in a [`LxFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct LxComdatSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file LxFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for LxComdatSectionIterator<'data, 'file, R> {
+    type Item = SectionIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
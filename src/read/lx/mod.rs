@@ -0,0 +1,40 @@
+//! Support for reading LE and LX files.
+//!
+//! The LX (Linear Executable) format is used by OS/2 2.x and later
+//! executables and libraries. The LE format is an earlier variant that is
+//! used by Windows VxDs and by DOS extenders such as DOS/4GW.
+//!
+//! Executables that are bound to a DOS extender are also supported. These
+//! start with the extender's stub program, and the LE image follows it.
+//!
+//! ## High level API
+//!
+//! [`LxFile`] implements the [`Object`](crate::read::Object) trait for LE and
+//! LX files. Each entry in the object table is both a segment and a section,
+//! using the 1-based object number as the section index. Sections have the
+//! address that the object is relocated to, so symbol addresses and the entry
+//! point are linear addresses.
+//!
+//! Exported entry points that have a name in the resident or non-resident
+//! name table are defined symbols, and imported functions are undefined
+//! symbols. Fixup records are relocations, with imported functions as symbol
+//! targets and references to other objects as section targets.
+//!
+//! ## Low level API
+//!
+//! [`LxFile`] also provides access to the tables that do not have an
+//! equivalent in the unified API, such as the object page table
+//! ([`LxFile::pages`]), the fixup records of each page
+//! ([`LxFile::page_fixups`]), the entry table ([`LxFile::entries`]), and the
+//! import module name table ([`LxFile::import_modules`]).
+mod file;
+pub use file::*;
+
+mod section;
+pub use section::*;
+
+mod symbol;
+pub use symbol::*;
+
+mod relocation;
+pub use relocation::*;
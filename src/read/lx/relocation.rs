@@ -0,0 +1,297 @@
+use alloc::vec::Vec;
+
+use crate::endian::{LittleEndian as LE, U16Bytes, U32Bytes};
+use crate::lx;
+use crate::read::{
+    Bytes, Error, ReadError, ReadRef, Relocation, RelocationEncoding, RelocationFlags,
+    RelocationKind, RelocationTarget, Result, SectionIndex, SymbolIndex,
+};
+
+use super::{LxFile, LxImport, LxImportName};
+
+/// A fixup record for a page in an [`LxFile`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LxFixup {
+    /// The kind of location to fix up. See the `NRS*` constants.
+    pub source_type: u8,
+    /// The target flags. See the `NRR*` and `NR*` flag constants.
+    pub target_flags: u8,
+    /// The offsets of the locations within the page.
+    ///
+    /// An offset is negative if the location starts in the previous page.
+    pub offsets: Vec<i32>,
+    /// The target of the fixup.
+    pub target: LxFixupTarget,
+    /// The additive value, or 0 if the record has no additive value.
+    pub additive: u32,
+}
+
+/// The target of an [`LxFixup`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LxFixupTarget {
+    /// An offset in an object in this module.
+    Internal {
+        /// The 1-based object number.
+        object: u16,
+        /// The offset within the object.
+        ///
+        /// This is 0 for selector fixups.
+        offset: u32,
+    },
+    /// A function imported by ordinal.
+    ImportOrdinal {
+        /// The 1-based index in the import module name table.
+        module: u16,
+        /// The ordinal of the function.
+        ordinal: u32,
+    },
+    /// A function imported by name.
+    ImportName {
+        /// The 1-based index in the import module name table.
+        module: u16,
+        /// The offset of the name in the import procedure name table.
+        name: u32,
+    },
+    /// An entry point in this module, using its ordinal in the entry table.
+    Entry(u16),
+}
+
+impl LxFixup {
+    /// Convert to a generic relocation.
+    ///
+    /// `symbol` is the undefined symbol for an imported function.
+    pub(super) fn resolve<'data, R: ReadRef<'data>>(
+        &self,
+        file: &LxFile<'data, R>,
+        symbol: Option<SymbolIndex>,
+    ) -> Result<LxResolvedRelocation> {
+        let mut addend = 0;
+        let (kind, size) = match self.source_type & lx::NRSTYP {
+            lx::NRSBYT => (RelocationKind::SegmentRelative, 8),
+            lx::NRSSEG => (RelocationKind::SectionIndex, 16),
+            // Far pointers: an offset followed by a segment selector.
+            lx::NRSPTR => (RelocationKind::Unknown, 32),
+            lx::NRPTR48 => (RelocationKind::Unknown, 48),
+            lx::NRSOFF => (RelocationKind::SegmentRelative, 16),
+            lx::NROFF32 => (RelocationKind::Absolute, 32),
+            lx::NRSOFF32 => {
+                // The offset is relative to the end of the location.
+                addend = -4;
+                (RelocationKind::Relative, 32)
+            }
+            _ => return Err(Error("Unsupported LX fixup source type")),
+        };
+        let target = match self.target {
+            LxFixupTarget::Internal { object, offset } => {
+                addend += i64::from(offset);
+                RelocationTarget::Section(SectionIndex(object.into()))
+            }
+            LxFixupTarget::Entry(ordinal) => {
+                let entry = file
+                    .entry_by_ordinal(ordinal)
+                    .read_error("Invalid LX fixup entry ordinal")?;
+                if entry.kind == lx::ENTRY_FORWARDER {
+                    return Err(Error("Unsupported LX fixup to forwarder entry"));
+                }
+                addend += i64::from(entry.offset);
+                RelocationTarget::Section(SectionIndex(entry.object.into()))
+            }
+            LxFixupTarget::ImportOrdinal { .. } | LxFixupTarget::ImportName { .. } => {
+                let symbol = symbol.read_error("Invalid LX fixup import")?;
+                RelocationTarget::Symbol(symbol)
+            }
+        };
+        Ok(LxResolvedRelocation {
+            kind,
+            size,
+            target,
+            addend: addend + i64::from(self.additive),
+        })
+    }
+}
+
+impl<'data, R: ReadRef<'data>> LxFile<'data, R> {
+    /// Return the fixup records for a page, using its 0-based index in the
+    /// object page table.
+    pub fn page_fixups(&self, page: usize) -> Result<Vec<LxFixup>> {
+        let offset = self.header_offset + u64::from(self.header.e32_fpagetab.get(LE));
+        let table = self
+            .data
+            .read_slice_at::<U32Bytes<LE>>(offset, self.pages().len() + 1)
+            .read_error("Invalid LX fixup page table offset or size")?;
+        let (start, end) = match (table.get(page), table.get(page + 1)) {
+            (Some(start), Some(end)) => (start.get(LE), end.get(LE)),
+            _ => return Err(Error("Invalid LX page index")),
+        };
+        let size = end
+            .checked_sub(start)
+            .read_error("Invalid LX fixup page table entry")?;
+        let offset =
+            self.header_offset + u64::from(self.header.e32_frectab.get(LE)) + u64::from(start);
+        let mut data = self
+            .data
+            .read_bytes_at(offset, size.into())
+            .map(Bytes)
+            .read_error("Invalid LX fixup record offset or size")?;
+        let mut fixups = Vec::new();
+        while !data.is_empty() {
+            fixups.push(parse_fixup(&mut data).read_error("Invalid LX fixup record")?);
+        }
+        Ok(fixups)
+    }
+
+    /// Return the imported function that is the target of a fixup, if any.
+    pub fn fixup_import(&self, target: LxFixupTarget) -> Result<Option<LxImport<'data>>> {
+        let (module, name) = match target {
+            LxFixupTarget::ImportOrdinal { module, ordinal } => {
+                (module, LxImportName::Ordinal(ordinal))
+            }
+            LxFixupTarget::ImportName { module, name } => {
+                let offset = self.header_offset
+                    + u64::from(self.header.e32_impproc.get(LE))
+                    + u64::from(name);
+                let length = *self
+                    .data
+                    .read_at::<u8>(offset)
+                    .read_error("Invalid LX import procedure name offset")?;
+                let name = self
+                    .data
+                    .read_bytes_at(offset + 1, length.into())
+                    .read_error("Invalid LX import procedure name length")?;
+                (module, LxImportName::Name(name))
+            }
+            _ => return Ok(None),
+        };
+        let module = usize::from(module)
+            .checked_sub(1)
+            .and_then(|index| self.import_modules().get(index))
+            .read_error("Invalid LX import module index")?;
+        Ok(Some(LxImport { module, name }))
+    }
+}
+
+fn parse_fixup(data: &mut Bytes<'_>) -> core::result::Result<LxFixup, ()> {
+    let source_type = *data.read::<u8>()?;
+    let target_flags = *data.read::<u8>()?;
+    let read_u8 = |data: &mut Bytes<'_>| data.read::<u8>().map(|x| u32::from(*x));
+    let read_u16 = |data: &mut Bytes<'_>| data.read::<U16Bytes<LE>>().map(|x| x.get(LE));
+    let read_u32 = |data: &mut Bytes<'_>| data.read::<U32Bytes<LE>>().map(|x| x.get(LE));
+    let read_offset = |data: &mut Bytes<'_>| {
+        if target_flags & lx::NR32BITOFF != 0 {
+            read_u32(data)
+        } else {
+            read_u16(data).map(u32::from)
+        }
+    };
+    let read_object = |data: &mut Bytes<'_>| {
+        if target_flags & lx::NR16OBJMOD != 0 {
+            read_u16(data)
+        } else {
+            read_u8(data).map(|x| x as u16)
+        }
+    };
+
+    let chain = source_type & lx::NRCHAIN != 0;
+    let (count, first) = if chain {
+        (read_u8(data)?, None)
+    } else {
+        (0, Some(read_u16(data)? as i16))
+    };
+    let target = match target_flags & lx::NRRTYP {
+        lx::NRRINT => {
+            let object = read_object(data)?;
+            let offset = if source_type & lx::NRSTYP == lx::NRSSEG {
+                0
+            } else {
+                read_offset(data)?
+            };
+            LxFixupTarget::Internal { object, offset }
+        }
+        lx::NRRORD => {
+            let module = read_object(data)?;
+            let ordinal = if target_flags & lx::NR8BITORD != 0 {
+                read_u8(data)?
+            } else {
+                read_offset(data)?
+            };
+            LxFixupTarget::ImportOrdinal { module, ordinal }
+        }
+        lx::NRRNAM => {
+            let module = read_object(data)?;
+            let name = read_offset(data)?;
+            LxFixupTarget::ImportName { module, name }
+        }
+        _ => LxFixupTarget::Entry(read_object(data)?),
+    };
+    let additive = if target_flags & lx::NRADD == 0 {
+        0
+    } else if target_flags & lx::NR32BITADD != 0 {
+        read_u32(data)?
+    } else {
+        read_u16(data)?.into()
+    };
+    let offsets = match first {
+        Some(offset) => vec![offset.into()],
+        None => data
+            .read_slice::<U16Bytes<LE>>(count as usize)?
+            .iter()
+            .map(|offset| i32::from(offset.get(LE) as i16))
+            .collect(),
+    };
+    Ok(LxFixup {
+        source_type,
+        target_flags,
+        offsets,
+        target,
+        additive,
+    })
+}
+
+/// A relocation that has been resolved to a generic target.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct LxResolvedRelocation {
+    kind: RelocationKind,
+    size: u8,
+    target: RelocationTarget,
+    addend: i64,
+}
+
+impl LxResolvedRelocation {
+    fn relocation(&self) -> Relocation {
+        Relocation {
+            kind: self.kind,
+            encoding: RelocationEncoding::Generic,
+            size: self.size,
+            target: self.target,
+            addend: self.addend,
+            implicit_addend: false,
+            flags: RelocationFlags::Generic {
+                kind: self.kind,
+                encoding: RelocationEncoding::Generic,
+                size: self.size,
+            },
+        }
+    }
+}
+
+/// An iterator for the relocations in an [`LxSection`](super::LxSection).
+#[derive(Debug)]
+pub struct LxRelocationIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file LxFile<'data, R>,
+    pub(super) section: SectionIndex,
+    pub(super) index: usize,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for LxRelocationIterator<'data, 'file, R> {
+    type Item = (u64, Relocation);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (offset, relocation) = self
+            .file
+            .relocations
+            .get(self.section.0.checked_sub(1)?)?
+            .get(self.index)?;
+        self.index += 1;
+        Some((*offset, relocation.relocation()))
+    }
+}
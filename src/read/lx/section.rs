@@ -0,0 +1,264 @@
+use alloc::borrow::Cow;
+use core::str;
+
+use crate::endian::LittleEndian as LE;
+use crate::lx;
+use crate::read::{
+    self, CompressedData, CompressedFileRange, ObjectSection, ObjectSegment, ReadError, ReadRef,
+    RelocationMap, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags,
+};
+
+use super::{LxFile, LxRelocationIterator};
+
+/// An iterator for the sections in an [`LxFile`].
+#[derive(Debug)]
+pub struct LxSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file LxFile<'data, R>,
+    pub(super) iter: core::iter::Enumerate<core::slice::Iter<'data, lx::ObjectTableEntry>>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for LxSectionIterator<'data, 'file, R> {
+    type Item = LxSection<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(index, object)| LxSection {
+            file: self.file,
+            index: SectionIndex(index + 1),
+            object,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// A section in an [`LxFile`].
+///
+/// Each entry in the object table is both a section and a segment.
+#[derive(Debug)]
+pub struct LxSection<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file LxFile<'data, R>,
+    pub(super) index: SectionIndex,
+    pub(super) object: &'data lx::ObjectTableEntry,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> LxSection<'data, 'file, R> {
+    /// Return the object table entry for this section.
+    pub fn object_table_entry(&self) -> &'data lx::ObjectTableEntry {
+        self.object
+    }
+
+    /// Return the data of this section, expanding compressed and zero-filled pages.
+    ///
+    /// See [`LxFile::object_data`].
+    pub fn uncompressed_data(&self) -> Result<Cow<'data, [u8]>> {
+        self.file.object_data(self.index).map(Cow::Owned)
+    }
+
+    fn is_code(&self) -> bool {
+        self.object.flags.get(LE) & lx::OBJ_EXEC != 0
+    }
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for LxSection<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSection<'data> for LxSection<'data, 'file, R> {
+    type RelocationIterator = LxRelocationIterator<'data, 'file, R>;
+
+    #[inline]
+    fn index(&self) -> SectionIndex {
+        self.index
+    }
+
+    /// Return the linear address that the object is relocated to.
+    #[inline]
+    fn address(&self) -> u64 {
+        self.object.base_address.get(LE).into()
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.object.virtual_size.get(LE).into()
+    }
+
+    /// Objects are allocated in units of pages.
+    #[inline]
+    fn align(&self) -> u64 {
+        self.file.page_size().into()
+    }
+
+    /// Return the range of the object's pages in the file.
+    ///
+    /// Returns `None` if the pages are not stored contiguously and uncompressed.
+    fn file_range(&self) -> Option<(u64, u64)> {
+        self.file.object_file_range(self.object).ok().flatten()
+    }
+
+    /// Return the data of the object's pages in the file.
+    ///
+    /// Returns an error if the pages are not stored contiguously and
+    /// uncompressed; use [`LxSection::uncompressed_data`] instead.
+    fn data(&self) -> Result<&'data [u8]> {
+        match self.file.object_file_range(self.object)? {
+            Some((offset, size)) => self
+                .file
+                .data
+                .read_bytes_at(offset, size)
+                .read_error("Invalid LX page offset or size"),
+            None => Ok(&[]),
+        }
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        Ok(read::util::data_range(
+            self.data()?,
+            self.address(),
+            address,
+            size,
+        ))
+    }
+
+    #[inline]
+    fn compressed_file_range(&self) -> Result<CompressedFileRange> {
+        Ok(CompressedFileRange::none(self.file_range()))
+    }
+
+    #[inline]
+    fn compressed_data(&self) -> Result<CompressedData<'data>> {
+        self.data().map(CompressedData::none)
+    }
+
+    /// LX objects have no names, so return `CODE` or `DATA` depending on the object flags.
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        Ok(if self.is_code() { b"CODE" } else { b"DATA" })
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name_bytes()?)
+            .ok()
+            .read_error("Non UTF-8 LX section name")
+    }
+
+    #[inline]
+    fn segment_name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn segment_name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    fn kind(&self) -> SectionKind {
+        let flags = self.object.flags.get(LE);
+        if self.is_code() {
+            SectionKind::Text
+        } else if flags & lx::OBJ_WRITE == 0 {
+            SectionKind::ReadOnlyData
+        } else if self.file.object_pages(self.object).map_or(false, |pages| {
+            pages
+                .iter()
+                .all(|page| matches!(page.flags, lx::PAGE_INVALID | lx::PAGE_ZEROED))
+        }) {
+            SectionKind::UninitializedData
+        } else {
+            SectionKind::Data
+        }
+    }
+
+    /// Return an iterator over the relocations in this section.
+    ///
+    /// Fixup records with a source list are returned once for each location.
+    fn relocations(&self) -> LxRelocationIterator<'data, 'file, R> {
+        LxRelocationIterator {
+            file: self.file,
+            section: self.index,
+            index: 0,
+        }
+    }
+
+    fn relocation_map(&self) -> Result<RelocationMap> {
+        RelocationMap::new(self.file, self)
+    }
+
+    #[inline]
+    fn flags(&self) -> SectionFlags {
+        SectionFlags::None
+    }
+}
+
+/// An iterator for the segments in an [`LxFile`].
+#[derive(Debug)]
+pub struct LxSegmentIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) sections: LxSectionIterator<'data, 'file, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for LxSegmentIterator<'data, 'file, R> {
+    type Item = LxSegment<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.sections.next().map(|section| LxSegment { section })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sections.size_hint()
+    }
+}
+
+/// A segment in an [`LxFile`].
+///
+/// Each entry in the object table is both a section and a segment.
+#[derive(Debug)]
+pub struct LxSegment<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) section: LxSection<'data, 'file, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for LxSegment<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSegment<'data> for LxSegment<'data, 'file, R> {
+    #[inline]
+    fn address(&self) -> u64 {
+        self.section.address()
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.section.size()
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        self.section.align()
+    }
+
+    #[inline]
+    fn file_range(&self) -> (u64, u64) {
+        self.section.file_range().unwrap_or((0, 0))
+    }
+
+    fn data(&self) -> Result<&'data [u8]> {
+        self.section.data()
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        self.section.data_range(address, size)
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn flags(&self) -> SegmentFlags {
+        SegmentFlags::None
+    }
+}
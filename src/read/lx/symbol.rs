@@ -0,0 +1,168 @@
+use core::str;
+
+use crate::read::{
+    self, ObjectSymbol, ObjectSymbolTable, ReadError, Result, SectionIndex, SymbolFlags,
+    SymbolIndex, SymbolKind, SymbolScope, SymbolSection,
+};
+
+use super::{LxEntry, LxImport};
+
+/// A symbol in an [`LxFile`](super::LxFile).
+///
+/// This is either a named entry point or an imported function.
+#[derive(Debug, Clone)]
+pub struct LxSymbol<'data> {
+    pub(super) index: SymbolIndex,
+    pub(super) name: &'data [u8],
+    pub(super) section: SymbolSection,
+    pub(super) address: u64,
+    pub(super) kind: SymbolKind,
+    pub(super) entry: Option<LxEntry>,
+    pub(super) import: Option<LxImport<'data>>,
+}
+
+impl<'data> LxSymbol<'data> {
+    /// Return the entry table entry for a named entry point.
+    #[inline]
+    pub fn entry(&self) -> Option<LxEntry> {
+        self.entry
+    }
+
+    /// Return the module and name or ordinal of an imported function.
+    #[inline]
+    pub fn import(&self) -> Option<LxImport<'data>> {
+        self.import
+    }
+}
+
+impl<'data> read::private::Sealed for LxSymbol<'data> {}
+
+impl<'data> ObjectSymbol<'data> for LxSymbol<'data> {
+    #[inline]
+    fn index(&self) -> SymbolIndex {
+        self.index
+    }
+
+    /// Return the symbol name.
+    ///
+    /// This is empty for functions that are imported by ordinal.
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        Ok(self.name)
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name)
+            .ok()
+            .read_error("Non UTF-8 LX symbol name")
+    }
+
+    #[inline]
+    fn address(&self) -> u64 {
+        self.address
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        0
+    }
+
+    #[inline]
+    fn kind(&self) -> SymbolKind {
+        self.kind
+    }
+
+    #[inline]
+    fn section(&self) -> SymbolSection {
+        self.section
+    }
+
+    #[inline]
+    fn is_undefined(&self) -> bool {
+        self.section == SymbolSection::Undefined
+    }
+
+    #[inline]
+    fn is_definition(&self) -> bool {
+        self.entry.is_some()
+    }
+
+    #[inline]
+    fn is_common(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn is_weak(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn scope(&self) -> SymbolScope {
+        if self.is_undefined() {
+            SymbolScope::Unknown
+        } else {
+            SymbolScope::Dynamic
+        }
+    }
+
+    #[inline]
+    fn is_global(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn is_local(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn flags(&self) -> SymbolFlags<SectionIndex, SymbolIndex> {
+        SymbolFlags::None
+    }
+}
+
+/// A symbol table in an [`LxFile`](super::LxFile).
+#[derive(Debug, Clone, Copy)]
+pub struct LxSymbolTable<'data, 'file> {
+    pub(super) symbols: &'file [LxSymbol<'data>],
+}
+
+impl<'data, 'file> read::private::Sealed for LxSymbolTable<'data, 'file> {}
+
+impl<'data, 'file> ObjectSymbolTable<'data> for LxSymbolTable<'data, 'file> {
+    type Symbol = LxSymbol<'data>;
+    type SymbolIterator = LxSymbolIterator<'data, 'file>;
+
+    fn symbols(&self) -> Self::SymbolIterator {
+        LxSymbolIterator {
+            iter: self.symbols.iter(),
+        }
+    }
+
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<Self::Symbol> {
+        self.symbols
+            .get(index.0)
+            .cloned()
+            .read_error("Invalid LX symbol index")
+    }
+}
+
+/// An iterator for the symbols in an [`LxFile`](super::LxFile).
+#[derive(Debug)]
+pub struct LxSymbolIterator<'data, 'file> {
+    pub(super) iter: core::slice::Iter<'file, LxSymbol<'data>>,
+}
+
+impl<'data, 'file> Iterator for LxSymbolIterator<'data, 'file> {
+    type Item = LxSymbol<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().cloned()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
@@ -61,6 +61,9 @@ pub use read_cache::*;
 mod util;
 pub use util::*;
 
+mod diff;
+pub use diff::*;
+
 #[cfg(any(feature = "elf", feature = "macho"))]
 mod gnu_compression;
 
@@ -70,7 +73,20 @@ mod gnu_compression;
     feature = "macho",
     feature = "pe",
     feature = "wasm",
-    feature = "xcoff"
+    feature = "xcoff",
+    feature = "omf",
+    feature = "ne",
+    feature = "lx",
+    feature = "pharlap",
+    feature = "aout",
+    feature = "hunk",
+    feature = "gemdos",
+    feature = "pef",
+    feature = "goff",
+    feature = "ihex",
+    feature = "srec",
+    feature = "ticoff",
+    feature = "xbe"
 ))]
 mod any;
 #[cfg(any(
@@ -79,7 +95,20 @@ mod any;
     feature = "macho",
     feature = "pe",
     feature = "wasm",
-    feature = "xcoff"
+    feature = "xcoff",
+    feature = "omf",
+    feature = "ne",
+    feature = "lx",
+    feature = "pharlap",
+    feature = "aout",
+    feature = "hunk",
+    feature = "gemdos",
+    feature = "pef",
+    feature = "goff",
+    feature = "ihex",
+    feature = "srec",
+    feature = "ticoff",
+    feature = "xbe"
 ))]
 pub use any::*;
 
@@ -108,6 +137,42 @@ pub mod xcoff;
 #[cfg(feature = "omf")]
 pub mod omf;
 
+#[cfg(feature = "ne")]
+pub mod ne;
+
+#[cfg(feature = "lx")]
+pub mod lx;
+
+#[cfg(feature = "pharlap")]
+pub mod pharlap;
+
+#[cfg(feature = "aout")]
+pub mod aout;
+
+#[cfg(feature = "hunk")]
+pub mod hunk;
+
+#[cfg(feature = "gemdos")]
+pub mod gemdos;
+
+#[cfg(feature = "pef")]
+pub mod pef;
+
+#[cfg(feature = "goff")]
+pub mod goff;
+
+#[cfg(feature = "ihex")]
+pub mod ihex;
+
+#[cfg(feature = "srec")]
+pub mod srec;
+
+#[cfg(feature = "ticoff")]
+pub mod ticoff;
+
+#[cfg(feature = "xbe")]
+pub mod xbe;
+
 mod traits;
 pub use traits::*;
 
@@ -205,7 +270,7 @@ pub enum FileKind {
     /// See [`archive::ArchiveFile`].
     #[cfg(feature = "archive")]
     Archive,
-    /// A COFF object file.
+    /// A COFF object file, or a DJGPP COFF executable.
     ///
     /// See [`coff::CoffFile`].
     #[cfg(feature = "coff")]
@@ -282,10 +347,78 @@ pub enum FileKind {
     /// See [`xcoff::XcoffFile64`].
     #[cfg(feature = "xcoff")]
     Xcoff64,
-    
+
     /// An Intel OMF object file (16-bit or 32-bit).
+    ///
+    /// See [`omf::OmfFile`].
     #[cfg(feature = "omf")]
     Omf,
+    /// An Intel OMF library.
+    ///
+    /// This can be parsed with [`archive::ArchiveFile`] if the `archive`
+    /// feature is enabled, or with [`omf::OmfLibrary`].
+    #[cfg(feature = "omf")]
+    OmfLibrary,
+    /// A 16-bit Windows or OS/2 NE file.
+    ///
+    /// See [`ne::NeFile`].
+    #[cfg(feature = "ne")]
+    Ne,
+    /// An OS/2 LX file, or a VxD or DOS extender LE file.
+    ///
+    /// See [`lx::LxFile`].
+    #[cfg(feature = "lx")]
+    Lx,
+    /// A PharLap 386|DOS-Extender executable.
+    ///
+    /// See [`pharlap::PharLapFile`].
+    #[cfg(feature = "pharlap")]
+    PharLap,
+    /// An a.out object file or executable.
+    ///
+    /// See [`aout::AoutFile`].
+    #[cfg(feature = "aout")]
+    Aout,
+    /// An AmigaOS Hunk executable or object file.
+    ///
+    /// See [`hunk::HunkFile`].
+    #[cfg(feature = "hunk")]
+    Hunk,
+    /// An Atari TOS/GEMDOS executable.
+    ///
+    /// See [`gemdos::GemdosFile`].
+    #[cfg(feature = "gemdos")]
+    Gemdos,
+    /// A PEF container.
+    ///
+    /// See [`pef::PefFile`].
+    #[cfg(feature = "pef")]
+    Pef,
+    /// An IBM GOFF object file.
+    ///
+    /// See [`goff::GoffFile`].
+    #[cfg(feature = "goff")]
+    Goff,
+    /// An Intel HEX file.
+    ///
+    /// See [`ihex::IhexFile`].
+    #[cfg(feature = "ihex")]
+    Ihex,
+    /// A Motorola S-record file.
+    ///
+    /// See [`srec::SrecFile`].
+    #[cfg(feature = "srec")]
+    Srec,
+    /// A TI COFF object file or executable.
+    ///
+    /// See [`ticoff::TiCoffFile`].
+    #[cfg(feature = "ticoff")]
+    TiCoff,
+    /// An original Xbox executable.
+    ///
+    /// See [`xbe::XbeFile`].
+    #[cfg(feature = "xbe")]
+    Xbe,
 }
 
 impl FileKind {
@@ -325,19 +458,37 @@ impl FileKind {
             [0xca, 0xfe, 0xba, 0xbf, ..] => FileKind::MachOFat64,
             #[cfg(feature = "wasm")]
             [0x00, b'a', b's', b'm', _, _, 0x00, 0x00] => FileKind::Wasm,
-            #[cfg(feature = "pe")]
+            #[cfg(any(
+                feature = "pe",
+                feature = "ne",
+                feature = "lx",
+                feature = "pharlap",
+                feature = "coff"
+            ))]
             [b'M', b'Z', ..] if offset == 0 => {
                 // offset == 0 restriction is because optional_header_magic only looks at offset 0
-                match pe::optional_header_magic(data) {
-                    Ok(crate::pe::IMAGE_NT_OPTIONAL_HDR32_MAGIC) => {
-                        FileKind::Pe32
-                    }
-                    Ok(crate::pe::IMAGE_NT_OPTIONAL_HDR64_MAGIC) => {
-                        FileKind::Pe64
-                    }
-                    _ => return Err(Error("Unknown MS-DOS file")),
-                }
+                Self::parse_dos(data)?
+            }
+            #[cfg(feature = "pharlap")]
+            [b'M', b'P', ..] | [b'P', b'3', ..]
+                if offset == 0 && pharlap::is_pharlap_file(data) =>
+            {
+                FileKind::PharLap
             }
+            #[cfg(feature = "hunk")]
+            [0x00, 0x00, 0x03, 0xf3, ..] | [0x00, 0x00, 0x03, 0xe7, ..] => FileKind::Hunk,
+            #[cfg(feature = "gemdos")]
+            [0x60, 0x1a, ..] if gemdos::is_gemdos_file(data, offset) => FileKind::Gemdos,
+            #[cfg(feature = "pef")]
+            [b'J', b'o', b'y', b'!', b'p', b'e', b'f', b'f', ..] => FileKind::Pef,
+            #[cfg(feature = "xbe")]
+            [b'X', b'B', b'E', b'H', ..] => FileKind::Xbe,
+            #[cfg(feature = "goff")]
+            [0x03, 0xf0, 0x00, ..] => FileKind::Goff,
+            #[cfg(feature = "ihex")]
+            [b':', ..] if offset == 0 && ihex::is_ihex_file(data) => FileKind::Ihex,
+            #[cfg(feature = "srec")]
+            [b'S', ..] if offset == 0 && srec::is_srec_file(data) => FileKind::Srec,
             // TODO: more COFF machines
             #[cfg(feature = "coff")]
             // COFF arm
@@ -368,11 +519,61 @@ impl FileKind {
             [0x01, 0xdf, ..] => FileKind::Xcoff32,
             #[cfg(feature = "xcoff")]
             [0x01, 0xf7, ..] => FileKind::Xcoff64,
-            [0x80..=0x9F, ..] => FileKind::Omf,
+            #[cfg(feature = "omf")]
+            [0x80 | 0x82, ..] if omf::is_module_header(data, offset) => {
+                FileKind::Omf
+            }
+            #[cfg(feature = "omf")]
+            [0xF0, ..] if omf::is_library_header(data, offset) => FileKind::OmfLibrary,
+            #[cfg(feature = "ticoff")]
+            [0x00, 0xc1 | 0xc2, ..]
+            | [0xc1 | 0xc2, 0x00, ..]
+            | [0x00, 0x97..=0xa1, ..]
+            | [0x97..=0xa1, 0x00, ..]
+                if offset == 0 && ticoff::is_ticoff_file(data) =>
+            {
+                FileKind::TiCoff
+            }
+            #[cfg(feature = "aout")]
+            _ if aout::is_aout_file(data, offset) => FileKind::Aout,
             _ => return Err(Error("Unknown file magic")),
         };
         Ok(kind)
     }
+
+    /// Determine the kind of a file that starts with an MS-DOS header.
+    #[cfg(any(
+        feature = "pe",
+        feature = "ne",
+        feature = "lx",
+        feature = "pharlap",
+        feature = "coff"
+    ))]
+    fn parse_dos<'data, R: ReadRef<'data>>(data: R) -> Result<FileKind> {
+        #[cfg(feature = "pe")]
+        match pe::optional_header_magic(data) {
+            Ok(crate::pe::IMAGE_NT_OPTIONAL_HDR32_MAGIC) => return Ok(FileKind::Pe32),
+            Ok(crate::pe::IMAGE_NT_OPTIONAL_HDR64_MAGIC) => return Ok(FileKind::Pe64),
+            _ => {}
+        }
+        #[cfg(feature = "ne")]
+        if ne::is_ne_file(data) {
+            return Ok(FileKind::Ne);
+        }
+        #[cfg(feature = "lx")]
+        if lx::is_lx_file(data) {
+            return Ok(FileKind::Lx);
+        }
+        #[cfg(feature = "pharlap")]
+        if pharlap::is_pharlap_file(data) {
+            return Ok(FileKind::PharLap);
+        }
+        #[cfg(feature = "coff")]
+        if coff::is_djgpp_file(data) {
+            return Ok(FileKind::Coff);
+        }
+        Err(Error("Unknown MS-DOS file"))
+    }
 }
 
 /// An object kind.
@@ -524,7 +725,8 @@ impl<'data> SymbolMapEntry for SymbolMapName<'data> {
 
 /// A map from addresses to symbol names and object files.
 ///
-/// This is derived from STAB entries in Mach-O files.
+/// This is derived from STAB entries in Mach-O files, and from public symbols
+/// and line numbers in OMF files.
 ///
 /// Returned by [`Object::object_map`].
 #[derive(Debug, Default, Clone)]
@@ -612,7 +814,7 @@ pub struct ObjectMapFile<'data> {
 }
 
 impl<'data> ObjectMapFile<'data> {
-    #[cfg(feature = "macho")]
+    #[cfg(any(feature = "macho", feature = "omf"))]
     fn new(path: &'data [u8], member: Option<&'data [u8]>) -> Self {
         ObjectMapFile { path, member }
     }
@@ -0,0 +1,726 @@
+use alloc::vec::Vec;
+
+use crate::endian::{LittleEndian as LE, U16Bytes};
+use crate::ne;
+use crate::pe;
+use crate::read::{
+    self, Architecture, ByteString, Bytes, ComdatKind, Error, Export, FileFlags, Import,
+    NoDynamicRelocationIterator, Object, ObjectComdat, ObjectKind, ReadError, ReadRef, Result,
+    SectionIndex, SymbolIndex, SymbolKind, SymbolSection,
+};
+
+use super::{
+    NeResolvedRelocation, NeSection, NeSectionIterator, NeSegment, NeSegmentIterator, NeSymbol,
+    NeSymbolIterator, NeSymbolTable,
+};
+
+/// An entry point in the entry table of an [`NeFile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NeEntry {
+    /// The 1-based ordinal of the entry point.
+    pub ordinal: u16,
+    /// The 1-based number of the segment containing the entry point, or
+    /// [`ne::NE_ENTRY_CONSTANT`] if the entry point is a constant.
+    pub segment: u8,
+    /// The offset of the entry point within its segment, or the value of a constant.
+    pub offset: u16,
+    /// The entry flags. See the `NE_ENTFLAGS_*` constants.
+    pub flags: u8,
+    /// Whether the entry point is in a moveable segment.
+    pub moveable: bool,
+}
+
+/// A name in the resident or non-resident name table of an [`NeFile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NeName<'data> {
+    /// The name.
+    pub name: &'data [u8],
+    /// The ordinal of the entry point with this name.
+    ///
+    /// This is 0 for the module name and description.
+    pub ordinal: u16,
+}
+
+/// A function imported by an [`NeFile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NeImport<'data> {
+    /// The name of the module the function is imported from.
+    pub module: &'data [u8],
+    /// The name or ordinal of the function.
+    pub name: NeImportName<'data>,
+}
+
+/// The name or ordinal of a function imported by an [`NeFile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NeImportName<'data> {
+    /// Imported by name.
+    Name(&'data [u8]),
+    /// Imported by ordinal.
+    Ordinal(u16),
+}
+
+/// A 16-bit Windows or OS/2 1.x NE file.
+///
+/// Most functions in the [`Object`] trait are implemented for this type.
+#[derive(Debug)]
+pub struct NeFile<'data, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) data: R,
+    pub(super) dos_header: &'data pe::ImageDosHeader,
+    pub(super) header: &'data pe::ImageOs2Header,
+    pub(super) header_offset: u64,
+    pub(super) segments: &'data [ne::SegmentTableEntry],
+    entries: Vec<NeEntry>,
+    resident_names: Vec<NeName<'data>>,
+    nonresident_names: Vec<NeName<'data>>,
+    module_references: Vec<&'data [u8]>,
+    imports: Vec<NeImport<'data>>,
+    pub(super) symbols: Vec<NeSymbol<'data>>,
+    /// The relocations of each segment, with non-additive chains expanded.
+    pub(super) relocations: Vec<Vec<(u64, NeResolvedRelocation)>>,
+}
+
+impl<'data, R: ReadRef<'data>> NeFile<'data, R> {
+    /// Parse the raw NE file data.
+    pub fn parse(data: R) -> Result<Self> {
+        let (dos_header, header_offset, header) = parse_headers(data)?;
+        let segments = data
+            .read_slice_at::<ne::SegmentTableEntry>(
+                header_offset + u64::from(header.ne_segtab.get(LE)),
+                header.ne_cseg.get(LE).into(),
+            )
+            .read_error("Invalid NE segment table offset or size")?;
+        let entries = parse_entries(
+            data.read_bytes_at(
+                header_offset + u64::from(header.ne_enttab.get(LE)),
+                header.ne_cbenttab.get(LE).into(),
+            )
+            .read_error("Invalid NE entry table offset or size")?,
+        )?;
+        let resident_names = parse_names(
+            data,
+            header_offset + u64::from(header.ne_restab.get(LE)),
+            None,
+        )?;
+        let nonresident_names = match header.ne_cbnrestab.get(LE) {
+            0 => Vec::new(),
+            size => parse_names(
+                data,
+                u64::from(header.ne_nrestab.get(LE) as u32),
+                Some(size.into()),
+            )?,
+        };
+        let imported_names = header_offset + u64::from(header.ne_imptab.get(LE));
+        let module_references = data
+            .read_slice_at::<U16Bytes<LE>>(
+                header_offset + u64::from(header.ne_modtab.get(LE)),
+                header.ne_cmod.get(LE).into(),
+            )
+            .read_error("Invalid NE module reference table offset or size")?
+            .iter()
+            .map(|offset| read_name(data, imported_names + u64::from(offset.get(LE))))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut file = NeFile {
+            data,
+            dos_header,
+            header,
+            header_offset,
+            segments,
+            entries,
+            resident_names,
+            nonresident_names,
+            module_references,
+            imports: Vec::new(),
+            symbols: Vec::new(),
+            relocations: Vec::new(),
+        };
+        file.symbols = file.export_symbols();
+
+        let segment_relocations = (1..=segments.len())
+            .map(|index| file.segment_relocations(SectionIndex(index)))
+            .collect::<Result<Vec<_>>>()?;
+        for relocation in segment_relocations.iter().flatten() {
+            if let Some(import) = file.relocation_import(relocation.target)? {
+                if !file.imports.contains(&import) {
+                    file.imports.push(import);
+                }
+            }
+        }
+        let first_import = file.symbols.len();
+        for (i, import) in file.imports.iter().enumerate() {
+            file.symbols.push(NeSymbol {
+                index: SymbolIndex(first_import + i),
+                // Imports by ordinal have no name.
+                name: match import.name {
+                    NeImportName::Name(name) => name,
+                    NeImportName::Ordinal(_) => &[],
+                },
+                section: SymbolSection::Undefined,
+                address: 0,
+                kind: SymbolKind::Unknown,
+                entry: None,
+                import: Some(*import),
+            });
+        }
+
+        let mut relocations = Vec::with_capacity(segments.len());
+        for (i, segment_relocations) in segment_relocations.iter().enumerate() {
+            let data = file.segment_data(SectionIndex(i + 1))?;
+            let mut generic = Vec::new();
+            for relocation in segment_relocations {
+                let symbol = match file.relocation_import(relocation.target)? {
+                    Some(import) => file
+                        .imports
+                        .iter()
+                        .position(|i| *i == import)
+                        .map(|index| SymbolIndex(first_import + index)),
+                    None => None,
+                };
+                let resolved = relocation.resolve(&file, symbol)?;
+                for offset in relocation.offsets(data)? {
+                    generic.push((offset, resolved));
+                }
+            }
+            relocations.push(generic);
+        }
+        file.relocations = relocations;
+        Ok(file)
+    }
+
+    /// Return the DOS header of this file.
+    pub fn dos_header(&self) -> &'data pe::ImageDosHeader {
+        self.dos_header
+    }
+
+    /// Return the NE header of this file.
+    pub fn header(&self) -> &'data pe::ImageOs2Header {
+        self.header
+    }
+
+    /// Return the file offset of the NE header.
+    ///
+    /// Most table offsets in the NE header are relative to this.
+    pub fn header_offset(&self) -> u64 {
+        self.header_offset
+    }
+
+    /// Return the segment table.
+    pub fn segment_table(&self) -> &'data [ne::SegmentTableEntry] {
+        self.segments
+    }
+
+    /// Return the entry table, omitting unused ordinals.
+    pub fn entries(&self) -> &[NeEntry] {
+        &self.entries
+    }
+
+    /// Return the entry with the given ordinal.
+    pub fn entry_by_ordinal(&self, ordinal: u16) -> Option<&NeEntry> {
+        self.entries.iter().find(|entry| entry.ordinal == ordinal)
+    }
+
+    /// Return the resident name table.
+    ///
+    /// The first name is the module name.
+    pub fn resident_names(&self) -> &[NeName<'data>] {
+        &self.resident_names
+    }
+
+    /// Return the non-resident name table.
+    ///
+    /// The first name is the module description.
+    pub fn nonresident_names(&self) -> &[NeName<'data>] {
+        &self.nonresident_names
+    }
+
+    /// Return the module name, which is the first name in the resident name table.
+    pub fn module_name(&self) -> Option<&'data [u8]> {
+        self.resident_names.first().map(|name| name.name)
+    }
+
+    /// Return the module description, which is the first name in the
+    /// non-resident name table.
+    pub fn description(&self) -> Option<&'data [u8]> {
+        self.nonresident_names.first().map(|name| name.name)
+    }
+
+    /// Return the names of the modules in the module reference table.
+    ///
+    /// Relocations refer to these using a 1-based index.
+    pub fn module_references(&self) -> &[&'data [u8]] {
+        &self.module_references
+    }
+
+    /// Return the functions imported by relocations, in order of first use.
+    ///
+    /// Unlike [`Object::imports`], this includes imports by ordinal.
+    pub fn import_list(&self) -> &[NeImport<'data>] {
+        &self.imports
+    }
+
+    /// Return the segment with the given 1-based index.
+    pub(super) fn segment(&self, index: SectionIndex) -> Result<&'data ne::SegmentTableEntry> {
+        index
+            .0
+            .checked_sub(1)
+            .and_then(|index| self.segments.get(index))
+            .read_error("Invalid NE segment index")
+    }
+
+    /// Return the range of the data of a segment in the file, if it has any.
+    pub(super) fn segment_file_range(&self, segment: &ne::SegmentTableEntry) -> Option<(u64, u64)> {
+        match segment.sector.get(LE) {
+            0 => None,
+            sector => {
+                let offset = u64::from(sector).checked_shl(self.header.ne_align.get(LE).into())?;
+                let size = match segment.length.get(LE) {
+                    0 => 0x10000,
+                    length => length.into(),
+                };
+                Some((offset, size))
+            }
+        }
+    }
+
+    /// Return the symbol kind for addresses in a segment.
+    fn segment_symbol_kind(&self, index: u8) -> SymbolKind {
+        match self.segment(SectionIndex(index.into())) {
+            Ok(segment) if segment.flags.get(LE) & ne::NE_SEGFLAGS_DATA != 0 => SymbolKind::Data,
+            Ok(_) => SymbolKind::Text,
+            Err(_) => SymbolKind::Unknown,
+        }
+    }
+
+    /// Create a symbol for each named entry point.
+    fn export_symbols(&self) -> Vec<NeSymbol<'data>> {
+        // The first name in each table is the module name or description.
+        let names = self
+            .resident_names
+            .iter()
+            .skip(1)
+            .chain(self.nonresident_names.iter().skip(1));
+        let mut symbols = Vec::new();
+        for name in names {
+            let entry = match self.entry_by_ordinal(name.ordinal) {
+                Some(entry) => *entry,
+                None => continue,
+            };
+            let (section, kind) = match entry.segment {
+                ne::NE_ENTRY_CONSTANT => (SymbolSection::Absolute, SymbolKind::Data),
+                segment => (
+                    SymbolSection::Section(SectionIndex(segment.into())),
+                    self.segment_symbol_kind(segment),
+                ),
+            };
+            symbols.push(NeSymbol {
+                index: SymbolIndex(symbols.len()),
+                name: name.name,
+                section,
+                address: entry.offset.into(),
+                kind,
+                entry: Some(entry),
+                import: None,
+            });
+        }
+        symbols
+    }
+
+    /// Return the data of a segment in the file.
+    ///
+    /// This is empty if the segment has no data in the file.
+    pub fn segment_data(&self, index: SectionIndex) -> Result<&'data [u8]> {
+        let segment = self.segment(index)?;
+        match self.segment_file_range(segment) {
+            Some((offset, size)) => self
+                .data
+                .read_bytes_at(offset, size)
+                .read_error("Invalid NE segment data offset or size"),
+            None => Ok(&[]),
+        }
+    }
+}
+
+/// Return true if the data is an NE file.
+pub fn is_ne_file<'data, R: ReadRef<'data>>(data: R) -> bool {
+    parse_headers(data).is_ok()
+}
+
+fn parse_headers<'data, R: ReadRef<'data>>(
+    data: R,
+) -> Result<(&'data pe::ImageDosHeader, u64, &'data pe::ImageOs2Header)> {
+    let dos_header = data
+        .read_at::<pe::ImageDosHeader>(0)
+        .read_error("Invalid DOS header size or alignment")?;
+    if dos_header.e_magic.get(LE) != pe::IMAGE_DOS_SIGNATURE {
+        return Err(Error("Invalid DOS magic"));
+    }
+    let header_offset = u64::from(dos_header.e_lfanew.get(LE));
+    let header = data
+        .read_at::<pe::ImageOs2Header>(header_offset)
+        .read_error("Invalid NE header offset, size, or alignment")?;
+    if header.ne_magic.get(LE) != pe::IMAGE_OS2_SIGNATURE {
+        return Err(Error("Invalid NE magic"));
+    }
+    Ok((dos_header, header_offset, header))
+}
+
+/// Parse the bundles in the entry table.
+fn parse_entries(data: &[u8]) -> Result<Vec<NeEntry>> {
+    let mut data = Bytes(data);
+    let mut entries = Vec::new();
+    let mut ordinal = 1u16;
+    while let Ok(&count) = data.read::<u8>() {
+        if count == 0 {
+            break;
+        }
+        let kind = *data
+            .read::<u8>()
+            .read_error("Invalid NE entry table bundle")?;
+        if kind == ne::NE_ENTRY_UNUSED {
+            ordinal = ordinal.wrapping_add(count.into());
+            continue;
+        }
+        for _ in 0..count {
+            let entry = if kind == ne::NE_ENTRY_MOVEABLE {
+                // Flags, INT 3Fh instruction, segment number, offset.
+                let entry = data
+                    .read_bytes(6)
+                    .read_error("Invalid NE entry table entry")?
+                    .0;
+                NeEntry {
+                    ordinal,
+                    segment: entry[3],
+                    offset: u16::from_le_bytes([entry[4], entry[5]]),
+                    flags: entry[0],
+                    moveable: true,
+                }
+            } else {
+                // Flags, offset.
+                let entry = data
+                    .read_bytes(3)
+                    .read_error("Invalid NE entry table entry")?
+                    .0;
+                NeEntry {
+                    ordinal,
+                    segment: kind,
+                    offset: u16::from_le_bytes([entry[1], entry[2]]),
+                    flags: entry[0],
+                    moveable: false,
+                }
+            };
+            entries.push(entry);
+            ordinal = ordinal.wrapping_add(1);
+        }
+    }
+    Ok(entries)
+}
+
+/// Read a name that is prefixed by its length.
+pub(super) fn read_name<'data, R: ReadRef<'data>>(data: R, offset: u64) -> Result<&'data [u8]> {
+    let length = *data
+        .read_at::<u8>(offset)
+        .read_error("Invalid NE name offset")?;
+    data.read_bytes_at(offset + 1, length.into())
+        .read_error("Invalid NE name length")
+}
+
+/// Parse a resident or non-resident name table.
+///
+/// The table ends with an empty name, or after `size` bytes if given.
+fn parse_names<'data, R: ReadRef<'data>>(
+    data: R,
+    mut offset: u64,
+    size: Option<u64>,
+) -> Result<Vec<NeName<'data>>> {
+    let end = size.map(|size| offset + size);
+    let mut names = Vec::new();
+    while end.map_or(true, |end| offset < end) {
+        let name = read_name(data, offset)?;
+        if name.is_empty() {
+            break;
+        }
+        offset += 1 + name.len() as u64;
+        let ordinal = data
+            .read_at::<U16Bytes<LE>>(offset)
+            .read_error("Invalid NE name table ordinal")?
+            .get(LE);
+        offset += 2;
+        names.push(NeName { name, ordinal });
+    }
+    Ok(names)
+}
+
+impl<'data, R: ReadRef<'data>> read::private::Sealed for NeFile<'data, R> {}
+
+impl<'data, R: ReadRef<'data>> Object<'data> for NeFile<'data, R> {
+    type Segment<'file>
+        = NeSegment<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SegmentIterator<'file>
+        = NeSegmentIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Section<'file>
+        = NeSection<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SectionIterator<'file>
+        = NeSectionIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Comdat<'file>
+        = NeComdat<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type ComdatIterator<'file>
+        = NeComdatIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Symbol<'file>
+        = NeSymbol<'data>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolIterator<'file>
+        = NeSymbolIterator<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolTable<'file>
+        = NeSymbolTable<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type DynamicRelocationIterator<'file>
+        = NoDynamicRelocationIterator
+    where
+        Self: 'file,
+        'data: 'file;
+
+    fn architecture(&self) -> Architecture {
+        Architecture::I386
+    }
+
+    #[inline]
+    fn is_little_endian(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn is_64(&self) -> bool {
+        false
+    }
+
+    fn kind(&self) -> ObjectKind {
+        if self.header.ne_flags.get(LE) & ne::NE_FFLAGS_LIBMODULE != 0 {
+            ObjectKind::Dynamic
+        } else {
+            ObjectKind::Executable
+        }
+    }
+
+    fn segments(&self) -> NeSegmentIterator<'data, '_, R> {
+        NeSegmentIterator {
+            sections: self.sections(),
+        }
+    }
+
+    fn section_by_name_bytes<'file>(
+        &'file self,
+        section_name: &[u8],
+    ) -> Option<NeSection<'data, 'file, R>> {
+        use crate::read::ObjectSection;
+        self.sections()
+            .find(|section| section.name_bytes() == Ok(section_name))
+    }
+
+    fn section_by_index(&self, index: SectionIndex) -> Result<NeSection<'data, '_, R>> {
+        let segment = self.segment(index)?;
+        Ok(NeSection {
+            file: self,
+            index,
+            segment,
+        })
+    }
+
+    fn sections(&self) -> NeSectionIterator<'data, '_, R> {
+        NeSectionIterator {
+            file: self,
+            iter: self.segments.iter().enumerate(),
+        }
+    }
+
+    #[inline]
+    fn comdats(&self) -> NeComdatIterator<'data, '_, R> {
+        NeComdatIterator { file: self }
+    }
+
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<NeSymbol<'data>> {
+        self.symbols
+            .get(index.0)
+            .cloned()
+            .read_error("Invalid NE symbol index")
+    }
+
+    fn symbols(&self) -> NeSymbolIterator<'data, '_> {
+        NeSymbolIterator {
+            iter: self.symbols.iter(),
+        }
+    }
+
+    fn symbol_table(&self) -> Option<NeSymbolTable<'data, '_>> {
+        Some(NeSymbolTable {
+            symbols: &self.symbols,
+        })
+    }
+
+    fn dynamic_symbols(&self) -> NeSymbolIterator<'data, '_> {
+        NeSymbolIterator { iter: [].iter() }
+    }
+
+    #[inline]
+    fn dynamic_symbol_table(&self) -> Option<NeSymbolTable<'data, '_>> {
+        None
+    }
+
+    #[inline]
+    fn dynamic_relocations(&self) -> Option<NoDynamicRelocationIterator> {
+        None
+    }
+
+    fn imports(&self) -> Result<Vec<Import<'data>>> {
+        // Like PE, imports by ordinal are omitted because they have no name.
+        let imports = self
+            .imports
+            .iter()
+            .filter_map(|import| match import.name {
+                NeImportName::Name(name) => Some(Import {
+                    library: ByteString(import.module),
+                    name: ByteString(name),
+                }),
+                NeImportName::Ordinal(_) => None,
+            })
+            .collect();
+        Ok(imports)
+    }
+
+    /// Return the named entry points.
+    ///
+    /// The address is the offset within the segment containing the entry
+    /// point, or the value of a constant.
+    fn exports(&self) -> Result<Vec<Export<'data>>> {
+        let exports = self
+            .symbols
+            .iter()
+            .filter(|symbol| symbol.entry.is_some())
+            .map(|symbol| Export {
+                name: ByteString(symbol.name),
+                address: symbol.address,
+            })
+            .collect();
+        Ok(exports)
+    }
+
+    #[inline]
+    fn has_debug_symbols(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn relative_address_base(&self) -> u64 {
+        0
+    }
+
+    /// Return the initial IP, which is the offset of the entry point within
+    /// the segment given by the initial CS.
+    fn entry(&self) -> u64 {
+        u64::from(self.header.ne_csip.get(LE) as u32 & 0xffff)
+    }
+
+    #[inline]
+    fn flags(&self) -> FileFlags {
+        FileFlags::None
+    }
+}
+
+/// An iterator for the COMDAT section groups in a [`NeFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct NeComdatIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file NeFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for NeComdatIterator<'data, 'file, R> {
+    type Item = NeComdat<'data, 'file, R>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+/// A COMDAT section group in a [`NeFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct NeComdat<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file NeFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for NeComdat<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectComdat<'data> for NeComdat<'data, 'file, R> {
+    type SectionIterator = NeComdatSectionIterator<'data, 'file, R>;
+
+    #[inline]
+    fn kind(&self) -> ComdatKind {
+        unreachable!();
+    }
+
+    #[inline]
+    fn symbol(&self) -> SymbolIndex {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn sections(&self) -> Self::SectionIterator {
+        unreachable!();
+    }
+}
+
+/// An iterator for the sections in a COMDAT section group in a [`NeFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct NeComdatSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file NeFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for NeComdatSectionIterator<'data, 'file, R> {
+    type Item = SectionIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
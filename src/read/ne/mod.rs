@@ -0,0 +1,40 @@
+//! Support for reading NE files.
+//!
+//! The NE (New Executable) format is used by 16-bit Windows and OS/2 1.x
+//! executables and libraries.
+//!
+//! ## High level API
+//!
+//! [`NeFile`] implements the [`Object`](crate::read::Object) trait for NE
+//! files. Each entry in the segment table is both a segment and a section,
+//! using the 1-based segment number as the section index. Sections have an
+//! address of 0, since NE segments are loaded at addresses chosen at run time,
+//! so symbol addresses and the entry point are offsets within their segment.
+//!
+//! Exported entry points that have a name in the resident or non-resident
+//! name table are defined symbols, and imported functions are undefined
+//! symbols. Segment relocation records are relocations, with imported
+//! functions as symbol targets and references to other segments as section
+//! targets.
+//!
+//! ## Low level API
+//!
+//! [`NeFile`] also provides access to the tables that do not have an
+//! equivalent in the unified API, such as the entry table
+//! ([`NeFile::entries`]), the module reference table
+//! ([`NeFile::module_references`]), and the resource table
+//! ([`NeFile::resources`]).
+mod file;
+pub use file::*;
+
+mod section;
+pub use section::*;
+
+mod symbol;
+pub use symbol::*;
+
+mod relocation;
+pub use relocation::*;
+
+mod resource;
+pub use resource::*;
@@ -0,0 +1,264 @@
+use alloc::vec::Vec;
+
+use crate::endian::{LittleEndian as LE, U16Bytes};
+use crate::ne;
+use crate::read::{
+    Bytes, Error, ReadError, ReadRef, Relocation, RelocationEncoding, RelocationFlags,
+    RelocationKind, RelocationTarget, Result, SectionIndex, SymbolIndex,
+};
+
+use super::{NeFile, NeImport, NeImportName};
+
+/// A relocation record for a segment in an [`NeFile`].
+///
+/// A record that is not additive applies to a chain of locations, which is
+/// returned by [`NeRelocation::offsets`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NeRelocation {
+    /// The kind of location to fix up. See the `NE_RADDR_*` constants.
+    pub address_type: u8,
+    /// Whether the target is added to the value at the location.
+    pub additive: bool,
+    /// The offset of the first location within the segment.
+    pub offset: u16,
+    /// The target of the relocation.
+    pub target: NeRelocationTarget,
+}
+
+/// The target of an [`NeRelocation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NeRelocationTarget {
+    /// An offset in a fixed segment.
+    Segment {
+        /// The 1-based segment number.
+        segment: u8,
+        /// The offset within the segment.
+        offset: u16,
+    },
+    /// An entry point in a moveable segment, using its ordinal in the entry table.
+    Entry(u16),
+    /// A function imported by ordinal.
+    ImportOrdinal {
+        /// The 1-based index in the module reference table.
+        module: u16,
+        /// The ordinal of the function.
+        ordinal: u16,
+    },
+    /// A function imported by name.
+    ImportName {
+        /// The 1-based index in the module reference table.
+        module: u16,
+        /// The offset of the name in the imported names table.
+        name: u16,
+    },
+    /// An operating system fixup, using its fixup type.
+    OsFixup(u16),
+}
+
+impl NeRelocation {
+    /// Return the offsets of the locations that this relocation applies to.
+    ///
+    /// `data` is the segment data. For relocations that are not additive,
+    /// each location contains the offset of the next location in the chain.
+    pub fn offsets(&self, data: &[u8]) -> Result<Vec<u64>> {
+        let mut offsets = Vec::new();
+        let mut offset = self.offset;
+        loop {
+            offsets.push(offset.into());
+            if self.additive || matches!(self.target, NeRelocationTarget::OsFixup(_)) {
+                break;
+            }
+            offset = Bytes(data)
+                .read_at::<U16Bytes<LE>>(offset.into())
+                .read_error("Invalid NE relocation chain offset")?
+                .get(LE);
+            if offset == 0xffff {
+                break;
+            }
+            // Each location holds 2 bytes, so a longer chain must have a loop.
+            if offsets.len() > data.len() / 2 {
+                return Err(Error("Invalid NE relocation chain"));
+            }
+        }
+        Ok(offsets)
+    }
+
+    /// Convert to a generic relocation.
+    ///
+    /// `symbol` is the undefined symbol for an imported function.
+    pub(super) fn resolve<'data, R: ReadRef<'data>>(
+        &self,
+        file: &NeFile<'data, R>,
+        symbol: Option<SymbolIndex>,
+    ) -> Result<NeResolvedRelocation> {
+        let (kind, size) = match self.address_type {
+            ne::NE_RADDR_LOWBYTE => (RelocationKind::SegmentRelative, 8),
+            ne::NE_RADDR_SELECTOR => (RelocationKind::SectionIndex, 16),
+            // Far pointers: an offset followed by a segment selector.
+            ne::NE_RADDR_POINTER32 => (RelocationKind::Unknown, 32),
+            ne::NE_RADDR_POINTER48 => (RelocationKind::Unknown, 48),
+            ne::NE_RADDR_OFFSET16 => (RelocationKind::SegmentRelative, 16),
+            ne::NE_RADDR_OFFSET32 => (RelocationKind::SegmentRelative, 32),
+            _ => return Err(Error("Unsupported NE relocation address type")),
+        };
+        let (target, addend) = match self.target {
+            NeRelocationTarget::Segment { segment, offset } => (
+                RelocationTarget::Section(SectionIndex(segment.into())),
+                offset.into(),
+            ),
+            NeRelocationTarget::Entry(ordinal) => {
+                let entry = file
+                    .entry_by_ordinal(ordinal)
+                    .read_error("Invalid NE relocation entry ordinal")?;
+                match entry.segment {
+                    ne::NE_ENTRY_CONSTANT => (RelocationTarget::Absolute, entry.offset.into()),
+                    segment => (
+                        RelocationTarget::Section(SectionIndex(segment.into())),
+                        entry.offset.into(),
+                    ),
+                }
+            }
+            NeRelocationTarget::ImportOrdinal { .. } | NeRelocationTarget::ImportName { .. } => {
+                let symbol = symbol.read_error("Invalid NE relocation import")?;
+                (RelocationTarget::Symbol(symbol), 0)
+            }
+            NeRelocationTarget::OsFixup(_) => (RelocationTarget::Absolute, 0),
+        };
+        Ok(NeResolvedRelocation {
+            kind,
+            size,
+            target,
+            addend,
+            implicit_addend: self.additive,
+        })
+    }
+}
+
+impl<'data, R: ReadRef<'data>> NeFile<'data, R> {
+    /// Return the relocation records for a segment.
+    ///
+    /// These follow the segment data if the segment has the
+    /// [`ne::NE_SEGFLAGS_RELOC_DATA`] flag.
+    pub fn segment_relocations(&self, index: SectionIndex) -> Result<Vec<NeRelocation>> {
+        let segment = self.segment(index)?;
+        let (offset, size) = match self.segment_file_range(segment) {
+            Some(range) if segment.flags.get(LE) & ne::NE_SEGFLAGS_RELOC_DATA != 0 => range,
+            _ => return Ok(Vec::new()),
+        };
+        let mut offset = offset + size;
+        let count = self
+            .data
+            .read::<U16Bytes<LE>>(&mut offset)
+            .read_error("Invalid NE relocation count offset")?
+            .get(LE);
+        let entries = self
+            .data
+            .read_slice_at::<ne::RelocationEntry>(offset, count.into())
+            .read_error("Invalid NE relocation records offset or size")?;
+        let relocations = entries
+            .iter()
+            .map(|entry| {
+                let target1 = entry.target1.get(LE);
+                let target2 = entry.target2.get(LE);
+                let target = match entry.relocation_type & ne::NE_RELTYPE_MASK {
+                    ne::NE_RELTYPE_INTERNAL => match target1 as u8 {
+                        ne::NE_SEGNUM_MOVEABLE => NeRelocationTarget::Entry(target2),
+                        segment => NeRelocationTarget::Segment {
+                            segment,
+                            offset: target2,
+                        },
+                    },
+                    ne::NE_RELTYPE_ORDINAL => NeRelocationTarget::ImportOrdinal {
+                        module: target1,
+                        ordinal: target2,
+                    },
+                    ne::NE_RELTYPE_NAME => NeRelocationTarget::ImportName {
+                        module: target1,
+                        name: target2,
+                    },
+                    _ => NeRelocationTarget::OsFixup(target1),
+                };
+                NeRelocation {
+                    address_type: entry.address_type,
+                    additive: entry.relocation_type & ne::NE_RELFLAG_ADDITIVE != 0,
+                    offset: entry.offset.get(LE),
+                    target,
+                }
+            })
+            .collect();
+        Ok(relocations)
+    }
+
+    /// Return the imported function that is the target of a relocation, if any.
+    pub fn relocation_import(&self, target: NeRelocationTarget) -> Result<Option<NeImport<'data>>> {
+        let (module, name) = match target {
+            NeRelocationTarget::ImportOrdinal { module, ordinal } => {
+                (module, NeImportName::Ordinal(ordinal))
+            }
+            NeRelocationTarget::ImportName { module, name } => {
+                let offset =
+                    self.header_offset + u64::from(self.header.ne_imptab.get(LE)) + u64::from(name);
+                (
+                    module,
+                    NeImportName::Name(super::file::read_name(self.data, offset)?),
+                )
+            }
+            _ => return Ok(None),
+        };
+        let module = usize::from(module)
+            .checked_sub(1)
+            .and_then(|index| self.module_references().get(index))
+            .read_error("Invalid NE module reference index")?;
+        Ok(Some(NeImport { module, name }))
+    }
+}
+
+/// A relocation that has been resolved to a generic target.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct NeResolvedRelocation {
+    kind: RelocationKind,
+    size: u8,
+    target: RelocationTarget,
+    addend: i64,
+    implicit_addend: bool,
+}
+
+impl NeResolvedRelocation {
+    fn relocation(&self) -> Relocation {
+        Relocation {
+            kind: self.kind,
+            encoding: RelocationEncoding::Generic,
+            size: self.size,
+            target: self.target,
+            addend: self.addend,
+            implicit_addend: self.implicit_addend,
+            flags: RelocationFlags::Generic {
+                kind: self.kind,
+                encoding: RelocationEncoding::Generic,
+                size: self.size,
+            },
+        }
+    }
+}
+
+/// An iterator for the relocations in an [`NeSection`](super::NeSection).
+#[derive(Debug)]
+pub struct NeRelocationIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file NeFile<'data, R>,
+    pub(super) section: SectionIndex,
+    pub(super) index: usize,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for NeRelocationIterator<'data, 'file, R> {
+    type Item = (u64, Relocation);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (offset, relocation) = self
+            .file
+            .relocations
+            .get(self.section.0.checked_sub(1)?)?
+            .get(self.index)?;
+        self.index += 1;
+        Some((*offset, relocation.relocation()))
+    }
+}
@@ -0,0 +1,129 @@
+use alloc::vec::Vec;
+
+use crate::endian::{LittleEndian as LE, U16Bytes};
+use crate::ne;
+use crate::read::{ReadError, ReadRef, Result, SectionIndex};
+
+use super::NeFile;
+
+/// A resource in an [`NeFile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NeResource<'data> {
+    /// The resource type.
+    pub type_id: NeResourceName<'data>,
+    /// The resource name.
+    pub name: NeResourceName<'data>,
+    /// The resource flags. See the `NE_RSCFLAGS_*` constants.
+    ///
+    /// This is 0 for OS/2 resources.
+    pub flags: u16,
+    /// The resource data.
+    pub data: &'data [u8],
+}
+
+/// The type or name of a resource in an [`NeFile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NeResourceName<'data> {
+    /// An integer ID.
+    Id(u16),
+    /// A string name.
+    Name(&'data [u8]),
+}
+
+impl<'data, R: ReadRef<'data>> NeFile<'data, R> {
+    /// Return the resources in the resource table.
+    ///
+    /// For OS/2 files, the resource data is in the last `ne_cres` segments.
+    pub fn resources(&self) -> Result<Vec<NeResource<'data>>> {
+        if self.header.ne_exetyp == ne::NE_OSFLAGS_OS2 {
+            self.os2_resources()
+        } else {
+            self.windows_resources()
+        }
+    }
+
+    fn windows_resources(&self) -> Result<Vec<NeResource<'data>>> {
+        let mut resources = Vec::new();
+        // The resource table is empty if it has the same offset as the
+        // resident name table, which follows it.
+        if self.header.ne_rsrctab.get(LE) == self.header.ne_restab.get(LE) {
+            return Ok(resources);
+        }
+        let table = self.header_offset + u64::from(self.header.ne_rsrctab.get(LE));
+        let mut offset = table;
+        let shift = self
+            .data
+            .read::<U16Bytes<LE>>(&mut offset)
+            .read_error("Invalid NE resource table offset")?
+            .get(LE);
+        let scale = |value: u16| u64::from(value).checked_shl(shift.into());
+        let name = |id: u16| -> Result<NeResourceName<'data>> {
+            if id & ne::NE_RSCTYPE_INTEGER != 0 {
+                Ok(NeResourceName::Id(id & !ne::NE_RSCTYPE_INTEGER))
+            } else {
+                super::file::read_name(self.data, table + u64::from(id)).map(NeResourceName::Name)
+            }
+        };
+        loop {
+            // The table ends with a type ID of 0, without the rest of the header.
+            let type_id = self
+                .data
+                .read_at::<U16Bytes<LE>>(offset)
+                .read_error("Invalid NE resource type offset")?
+                .get(LE);
+            if type_id == 0 {
+                break;
+            }
+            let type_info = self
+                .data
+                .read::<ne::ResourceTypeInfo>(&mut offset)
+                .read_error("Invalid NE resource type information")?;
+            let type_id = name(type_id)?;
+            let names = self
+                .data
+                .read_slice::<ne::ResourceNameInfo>(&mut offset, type_info.count.get(LE).into())
+                .read_error("Invalid NE resource name information")?;
+            for info in names {
+                let data = scale(info.offset.get(LE))
+                    .zip(scale(info.length.get(LE)))
+                    .and_then(|(offset, size)| self.data.read_bytes_at(offset, size).ok())
+                    .read_error("Invalid NE resource data offset or size")?;
+                resources.push(NeResource {
+                    type_id,
+                    name: name(info.id.get(LE))?,
+                    flags: info.flags.get(LE),
+                    data,
+                });
+            }
+        }
+        Ok(resources)
+    }
+
+    fn os2_resources(&self) -> Result<Vec<NeResource<'data>>> {
+        let count = self.header.ne_cres.get(LE);
+        let entries = self
+            .data
+            .read_slice_at::<ne::Os2ResourceEntry>(
+                self.header_offset + u64::from(self.header.ne_rsrctab.get(LE)),
+                count.into(),
+            )
+            .read_error("Invalid NE resource table offset or size")?;
+        let first_segment = self
+            .segments
+            .len()
+            .checked_sub(count.into())
+            .read_error("Invalid NE resource count")?;
+        entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                Ok(NeResource {
+                    type_id: NeResourceName::Id(entry.type_id.get(LE)),
+                    name: NeResourceName::Id(entry.id.get(LE)),
+                    flags: 0,
+                    data: self.segment_data(SectionIndex(first_segment + i + 1))?,
+                })
+            })
+            .collect()
+    }
+}
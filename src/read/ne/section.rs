@@ -0,0 +1,249 @@
+use core::str;
+
+use crate::endian::LittleEndian as LE;
+use crate::ne;
+use crate::read::{
+    self, CompressedData, CompressedFileRange, ObjectSection, ObjectSegment, ReadError, ReadRef,
+    RelocationMap, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags,
+};
+
+use super::{NeFile, NeRelocationIterator};
+
+/// An iterator for the sections in an [`NeFile`].
+#[derive(Debug)]
+pub struct NeSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file NeFile<'data, R>,
+    pub(super) iter: core::iter::Enumerate<core::slice::Iter<'data, ne::SegmentTableEntry>>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for NeSectionIterator<'data, 'file, R> {
+    type Item = NeSection<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(index, segment)| NeSection {
+            file: self.file,
+            index: SectionIndex(index + 1),
+            segment,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// A section in an [`NeFile`].
+///
+/// Each entry in the segment table is both a section and a segment.
+#[derive(Debug)]
+pub struct NeSection<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file NeFile<'data, R>,
+    pub(super) index: SectionIndex,
+    pub(super) segment: &'data ne::SegmentTableEntry,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> NeSection<'data, 'file, R> {
+    /// Return the segment table entry for this section.
+    pub fn segment_table_entry(&self) -> &'data ne::SegmentTableEntry {
+        self.segment
+    }
+
+    fn is_data(&self) -> bool {
+        self.segment.flags.get(LE) & ne::NE_SEGFLAGS_DATA != 0
+    }
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for NeSection<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSection<'data> for NeSection<'data, 'file, R> {
+    type RelocationIterator = NeRelocationIterator<'data, 'file, R>;
+
+    #[inline]
+    fn index(&self) -> SectionIndex {
+        self.index
+    }
+
+    /// NE segments are loaded at addresses chosen at run time, so return 0.
+    #[inline]
+    fn address(&self) -> u64 {
+        0
+    }
+
+    /// Return the larger of the minimum allocation size and the size of the
+    /// data in the file.
+    fn size(&self) -> u64 {
+        let min_alloc = match self.segment.min_alloc.get(LE) {
+            0 => 0x10000,
+            min_alloc => min_alloc.into(),
+        };
+        let file_size = self
+            .file
+            .segment_file_range(self.segment)
+            .map_or(0, |r| r.1);
+        min_alloc.max(file_size)
+    }
+
+    /// Segments are allocated in units of paragraphs.
+    #[inline]
+    fn align(&self) -> u64 {
+        16
+    }
+
+    #[inline]
+    fn file_range(&self) -> Option<(u64, u64)> {
+        self.file.segment_file_range(self.segment)
+    }
+
+    fn data(&self) -> Result<&'data [u8]> {
+        self.file.segment_data(self.index)
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        Ok(read::util::data_range(
+            self.data()?,
+            self.address(),
+            address,
+            size,
+        ))
+    }
+
+    #[inline]
+    fn compressed_file_range(&self) -> Result<CompressedFileRange> {
+        Ok(CompressedFileRange::none(self.file_range()))
+    }
+
+    #[inline]
+    fn compressed_data(&self) -> Result<CompressedData<'data>> {
+        self.data().map(CompressedData::none)
+    }
+
+    /// NE segments have no names, so return `CODE` or `DATA` depending on the segment type.
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        Ok(if self.is_data() { b"DATA" } else { b"CODE" })
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name_bytes()?)
+            .ok()
+            .read_error("Non UTF-8 NE section name")
+    }
+
+    #[inline]
+    fn segment_name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn segment_name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    fn kind(&self) -> SectionKind {
+        let flags = self.segment.flags.get(LE);
+        if !self.is_data() {
+            SectionKind::Text
+        } else if self.file_range().is_none() {
+            SectionKind::UninitializedData
+        } else if flags & ne::NE_SEGFLAGS_READONLY != 0 {
+            SectionKind::ReadOnlyData
+        } else {
+            SectionKind::Data
+        }
+    }
+
+    /// Return an iterator over the relocations in this section.
+    ///
+    /// Relocation records that are not additive are returned once for each
+    /// location in their chain.
+    fn relocations(&self) -> NeRelocationIterator<'data, 'file, R> {
+        NeRelocationIterator {
+            file: self.file,
+            section: self.index,
+            index: 0,
+        }
+    }
+
+    fn relocation_map(&self) -> Result<RelocationMap> {
+        RelocationMap::new(self.file, self)
+    }
+
+    #[inline]
+    fn flags(&self) -> SectionFlags {
+        SectionFlags::None
+    }
+}
+
+/// An iterator for the segments in an [`NeFile`].
+#[derive(Debug)]
+pub struct NeSegmentIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) sections: NeSectionIterator<'data, 'file, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for NeSegmentIterator<'data, 'file, R> {
+    type Item = NeSegment<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.sections.next().map(|section| NeSegment { section })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sections.size_hint()
+    }
+}
+
+/// A segment in an [`NeFile`].
+///
+/// Each entry in the segment table is both a section and a segment.
+#[derive(Debug)]
+pub struct NeSegment<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) section: NeSection<'data, 'file, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for NeSegment<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSegment<'data> for NeSegment<'data, 'file, R> {
+    #[inline]
+    fn address(&self) -> u64 {
+        0
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.section.size()
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        self.section.align()
+    }
+
+    #[inline]
+    fn file_range(&self) -> (u64, u64) {
+        self.section.file_range().unwrap_or((0, 0))
+    }
+
+    fn data(&self) -> Result<&'data [u8]> {
+        self.section.data()
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        self.section.data_range(address, size)
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn flags(&self) -> SegmentFlags {
+        SegmentFlags::None
+    }
+}
@@ -0,0 +1,168 @@
+use core::str;
+
+use crate::read::{
+    self, ObjectSymbol, ObjectSymbolTable, ReadError, Result, SectionIndex, SymbolFlags,
+    SymbolIndex, SymbolKind, SymbolScope, SymbolSection,
+};
+
+use super::{NeEntry, NeImport};
+
+/// A symbol in an [`NeFile`](super::NeFile).
+///
+/// This is either a named entry point or an imported function.
+#[derive(Debug, Clone)]
+pub struct NeSymbol<'data> {
+    pub(super) index: SymbolIndex,
+    pub(super) name: &'data [u8],
+    pub(super) section: SymbolSection,
+    pub(super) address: u64,
+    pub(super) kind: SymbolKind,
+    pub(super) entry: Option<NeEntry>,
+    pub(super) import: Option<NeImport<'data>>,
+}
+
+impl<'data> NeSymbol<'data> {
+    /// Return the entry table entry for a named entry point.
+    #[inline]
+    pub fn entry(&self) -> Option<NeEntry> {
+        self.entry
+    }
+
+    /// Return the module and name or ordinal of an imported function.
+    #[inline]
+    pub fn import(&self) -> Option<NeImport<'data>> {
+        self.import
+    }
+}
+
+impl<'data> read::private::Sealed for NeSymbol<'data> {}
+
+impl<'data> ObjectSymbol<'data> for NeSymbol<'data> {
+    #[inline]
+    fn index(&self) -> SymbolIndex {
+        self.index
+    }
+
+    /// Return the symbol name.
+    ///
+    /// This is empty for functions that are imported by ordinal.
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        Ok(self.name)
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name)
+            .ok()
+            .read_error("Non UTF-8 NE symbol name")
+    }
+
+    #[inline]
+    fn address(&self) -> u64 {
+        self.address
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        0
+    }
+
+    #[inline]
+    fn kind(&self) -> SymbolKind {
+        self.kind
+    }
+
+    #[inline]
+    fn section(&self) -> SymbolSection {
+        self.section
+    }
+
+    #[inline]
+    fn is_undefined(&self) -> bool {
+        self.section == SymbolSection::Undefined
+    }
+
+    #[inline]
+    fn is_definition(&self) -> bool {
+        self.entry.is_some()
+    }
+
+    #[inline]
+    fn is_common(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn is_weak(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn scope(&self) -> SymbolScope {
+        if self.is_undefined() {
+            SymbolScope::Unknown
+        } else {
+            SymbolScope::Dynamic
+        }
+    }
+
+    #[inline]
+    fn is_global(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn is_local(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn flags(&self) -> SymbolFlags<SectionIndex, SymbolIndex> {
+        SymbolFlags::None
+    }
+}
+
+/// A symbol table in an [`NeFile`](super::NeFile).
+#[derive(Debug, Clone, Copy)]
+pub struct NeSymbolTable<'data, 'file> {
+    pub(super) symbols: &'file [NeSymbol<'data>],
+}
+
+impl<'data, 'file> read::private::Sealed for NeSymbolTable<'data, 'file> {}
+
+impl<'data, 'file> ObjectSymbolTable<'data> for NeSymbolTable<'data, 'file> {
+    type Symbol = NeSymbol<'data>;
+    type SymbolIterator = NeSymbolIterator<'data, 'file>;
+
+    fn symbols(&self) -> Self::SymbolIterator {
+        NeSymbolIterator {
+            iter: self.symbols.iter(),
+        }
+    }
+
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<Self::Symbol> {
+        self.symbols
+            .get(index.0)
+            .cloned()
+            .read_error("Invalid NE symbol index")
+    }
+}
+
+/// An iterator for the symbols in an [`NeFile`](super::NeFile).
+#[derive(Debug)]
+pub struct NeSymbolIterator<'data, 'file> {
+    pub(super) iter: core::slice::Iter<'file, NeSymbol<'data>>,
+}
+
+impl<'data, 'file> Iterator for NeSymbolIterator<'data, 'file> {
+    type Item = NeSymbol<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().cloned()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
@@ -0,0 +1,86 @@
+//! Caching of views derived from a parsed OMF module.
+//!
+//! Assembling the contents of a segment from its LEDATA and LIDATA records,
+//! and building symbol maps, can be expensive for large modules.
+//! [`OmfFile::precompute`] computes these once and stores them in the
+//! [`OmfFile`], so that later calls can borrow the results.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::read::{ReadRef, SectionIndex, SymbolMap, SymbolMapName};
+
+use super::OmfFile;
+
+/// The derived views of an [`OmfFile`] computed by [`OmfFile::precompute`].
+#[derive(Default)]
+pub(super) struct OmfCache<'data> {
+    /// Assembled segment and COMDAT contents, by section position.
+    ///
+    /// This is `None` for sections whose contents can't be assembled.
+    sections: Vec<Option<Box<[u8]>>>,
+    /// The symbol map for all sections.
+    symbol_map: Option<SymbolMap<SymbolMapName<'data>>>,
+    /// The symbol maps for single sections, by section position.
+    section_symbol_maps: Vec<SymbolMap<SymbolMapName<'data>>>,
+}
+
+impl<'data> fmt::Debug for OmfCache<'data> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OmfCache").finish_non_exhaustive()
+    }
+}
+
+impl<'data> OmfCache<'data> {
+    /// Return the precomputed contents of a section, if any.
+    pub(super) fn section(&self, index: SectionIndex) -> Option<&[u8]> {
+        let position = index.0.checked_sub(1)?;
+        self.sections.get(position)?.as_deref()
+    }
+
+    /// Return the precomputed symbol map for all sections, if any.
+    pub(super) fn symbol_map(&self) -> Option<&SymbolMap<SymbolMapName<'data>>> {
+        self.symbol_map.as_ref()
+    }
+
+    /// Return the precomputed symbol map of a section, if any.
+    pub(super) fn section_symbol_map(
+        &self,
+        index: SectionIndex,
+    ) -> Option<&SymbolMap<SymbolMapName<'data>>> {
+        let position = index.0.checked_sub(1)?;
+        self.section_symbol_maps.get(position)
+    }
+}
+
+impl<'data, R: ReadRef<'data>> OmfFile<'data, R> {
+    /// Compute the derived views that are otherwise computed on every call.
+    ///
+    /// This assembles the contents of every segment and COMDAT, and builds the
+    /// symbol maps. Later calls to [`Self::segment_data`] and
+    /// [`Self::section_symbol_map`] borrow the stored results, and
+    /// [`Object::symbol_map`](crate::read::Object::symbol_map) copies the stored map instead of building it.
+    ///
+    /// Sections whose contents can't be assembled are left out, and still
+    /// return their error from [`Self::segment_data`].
+    pub fn precompute(&mut self) {
+        let count = self.segments.len() + self.comdats.len();
+        let mut cache = OmfCache {
+            sections: Vec::with_capacity(count),
+            symbol_map: Some(self.build_symbol_map(|_| true)),
+            section_symbol_maps: Vec::with_capacity(count),
+        };
+        for index in (1..=count).map(SectionIndex) {
+            cache.sections.push(
+                self.segment_data(index)
+                    .ok()
+                    .map(|contents| contents.into_owned().into_boxed_slice()),
+            );
+            cache
+                .section_symbol_maps
+                .push(self.build_symbol_map(|section| section == index));
+        }
+        self.cache = cache;
+    }
+}
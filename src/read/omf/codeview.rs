@@ -0,0 +1,742 @@
+//! CodeView debug information in OMF object files.
+//!
+//! Microsoft compilers store CodeView symbols in a segment named `$$SYMBOLS`
+//! (class `DEBSYM`) and CodeView types in a segment named `$$TYPES` (class
+//! `DEBTYP`). Only the CodeView 4 format, which starts with a signature of 1,
+//! is supported.
+//!
+//! The segment fields of symbols are usually 0 in object files, and are set
+//! by fixups that apply to the `$$SYMBOLS` segment.
+
+use alloc::vec::Vec;
+use core::str;
+
+use crate::read::{Error, ReadRef, Result, SectionIndex};
+
+use super::OmfFile;
+
+/// The name of the segment containing CodeView symbols.
+pub const CODEVIEW_SYMBOLS_SEGMENT: &str = "$$SYMBOLS";
+
+/// The name of the segment containing CodeView types.
+pub const CODEVIEW_TYPES_SEGMENT: &str = "$$TYPES";
+
+/// The signature at the start of CodeView 4 debug segments.
+const CV_SIGNATURE_C7: u32 = 1;
+
+/// Compile flags.
+pub const S_COMPILE: u16 = 0x0001;
+/// A register variable.
+pub const S_REGISTER: u16 = 0x0002;
+/// A constant.
+pub const S_CONSTANT: u16 = 0x0003;
+/// A user defined type.
+pub const S_UDT: u16 = 0x0004;
+/// The end of a block, procedure or thunk.
+pub const S_END: u16 = 0x0006;
+/// The object file name.
+pub const S_OBJNAME: u16 = 0x0009;
+/// A BP-relative variable (16:16).
+pub const S_BPREL16: u16 = 0x0100;
+/// A local data symbol (16:16).
+pub const S_LDATA16: u16 = 0x0101;
+/// A global data symbol (16:16).
+pub const S_GDATA16: u16 = 0x0102;
+/// A public symbol (16:16).
+pub const S_PUB16: u16 = 0x0103;
+/// A local procedure (16:16).
+pub const S_LPROC16: u16 = 0x0104;
+/// A global procedure (16:16).
+pub const S_GPROC16: u16 = 0x0105;
+/// A BP-relative variable (16:32).
+pub const S_BPREL32: u16 = 0x0200;
+/// A local data symbol (16:32).
+pub const S_LDATA32: u16 = 0x0201;
+/// A global data symbol (16:32).
+pub const S_GDATA32: u16 = 0x0202;
+/// A public symbol (16:32).
+pub const S_PUB32: u16 = 0x0203;
+/// A local procedure (16:32).
+pub const S_LPROC32: u16 = 0x0204;
+/// A global procedure (16:32).
+pub const S_GPROC32: u16 = 0x0205;
+/// A register-relative variable (16:32).
+pub const S_REGREL32: u16 = 0x020C;
+
+/// A type modifier, such as `const` or `volatile`.
+pub const LF_MODIFIER: u16 = 0x0001;
+/// A pointer type.
+pub const LF_POINTER: u16 = 0x0002;
+/// An array type.
+pub const LF_ARRAY: u16 = 0x0003;
+/// A class type.
+pub const LF_CLASS: u16 = 0x0004;
+/// A structure type.
+pub const LF_STRUCTURE: u16 = 0x0005;
+/// A union type.
+pub const LF_UNION: u16 = 0x0006;
+/// An enumeration type.
+pub const LF_ENUM: u16 = 0x0007;
+/// A procedure type.
+pub const LF_PROCEDURE: u16 = 0x0008;
+/// A procedure argument list.
+pub const LF_ARGLIST: u16 = 0x0201;
+/// A structure, union or enumeration field list.
+pub const LF_FIELDLIST: u16 = 0x0204;
+
+/// The first type index that refers to a type record. Lower indices are primitive types.
+pub const CV_FIRST_NONPRIMITIVE: u16 = 0x1000;
+
+/// The CodeView symbols from the `$$SYMBOLS` segment of an [`OmfFile`].
+#[derive(Debug, Clone)]
+pub struct OmfCodeViewSymbols {
+    data: Vec<u8>,
+}
+
+impl OmfCodeViewSymbols {
+    /// Return an iterator over the symbol records.
+    pub fn iter(&self) -> OmfCodeViewSymbolIterator<'_> {
+        OmfCodeViewSymbolIterator {
+            data: &self.data,
+            offset: 4,
+        }
+    }
+}
+
+/// An iterator over CodeView symbol records.
+#[derive(Debug)]
+pub struct OmfCodeViewSymbolIterator<'data> {
+    data: &'data [u8],
+    offset: usize,
+}
+
+impl<'data> Iterator for OmfCodeViewSymbolIterator<'data> {
+    type Item = Result<OmfCodeViewSymbol<'data>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.data.len() {
+            return None;
+        }
+        let result = parse_symbol(self.data, self.offset);
+        match result {
+            Ok((symbol, next)) => {
+                self.offset = next;
+                Some(Ok(symbol))
+            }
+            Err(error) => {
+                self.offset = self.data.len();
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+/// A CodeView symbol record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OmfCodeViewSymbol<'data> {
+    /// The offset of the record within the `$$SYMBOLS` segment.
+    pub offset: usize,
+    /// The record type, such as [`S_GPROC16`].
+    pub record_type: u16,
+    /// The record data, following the length and type.
+    pub data: &'data [u8],
+    /// The decoded record.
+    pub kind: OmfCodeViewSymbolKind<'data>,
+}
+
+/// The decoded contents of a CodeView symbol record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OmfCodeViewSymbolKind<'data> {
+    /// A procedure ([`S_LPROC16`], [`S_GPROC16`], [`S_LPROC32`] or [`S_GPROC32`]).
+    Procedure {
+        /// The procedure name.
+        name: &'data [u8],
+        /// Whether the procedure is visible to other modules.
+        global: bool,
+        /// The offset of the procedure within its segment.
+        offset: u32,
+        /// The segment containing the procedure.
+        segment: u16,
+        /// The length of the procedure in bytes.
+        length: u32,
+        /// The type index of the procedure type.
+        type_index: u16,
+        /// The offset of the matching [`S_END`] record.
+        end: u32,
+    },
+    /// A data symbol ([`S_LDATA16`], [`S_GDATA16`], [`S_LDATA32`] or [`S_GDATA32`]).
+    Data {
+        /// The symbol name.
+        name: &'data [u8],
+        /// Whether the symbol is visible to other modules.
+        global: bool,
+        /// The offset of the symbol within its segment.
+        offset: u32,
+        /// The segment containing the symbol.
+        segment: u16,
+        /// The type index of the symbol type.
+        type_index: u16,
+    },
+    /// A public symbol ([`S_PUB16`] or [`S_PUB32`]).
+    Public {
+        /// The symbol name.
+        name: &'data [u8],
+        /// The offset of the symbol within its segment.
+        offset: u32,
+        /// The segment containing the symbol.
+        segment: u16,
+        /// The type index of the symbol type.
+        type_index: u16,
+    },
+    /// A local variable relative to the frame pointer ([`S_BPREL16`] or [`S_BPREL32`]).
+    FrameRelative {
+        /// The variable name.
+        name: &'data [u8],
+        /// The offset from the frame pointer.
+        offset: i32,
+        /// The type index of the variable type.
+        type_index: u16,
+    },
+    /// A local variable relative to a register ([`S_REGREL32`]).
+    RegisterRelative {
+        /// The variable name.
+        name: &'data [u8],
+        /// The offset from the register.
+        offset: i32,
+        /// The register number.
+        register: u16,
+        /// The type index of the variable type.
+        type_index: u16,
+    },
+    /// A variable held in a register ([`S_REGISTER`]).
+    Register {
+        /// The variable name.
+        name: &'data [u8],
+        /// The register number.
+        register: u16,
+        /// The type index of the variable type.
+        type_index: u16,
+    },
+    /// A user defined type ([`S_UDT`]).
+    UserDefinedType {
+        /// The type name.
+        name: &'data [u8],
+        /// The type index.
+        type_index: u16,
+    },
+    /// The object file name ([`S_OBJNAME`]).
+    ObjectName {
+        /// The object file name.
+        name: &'data [u8],
+        /// The signature of the precompiled types, if any.
+        signature: u32,
+    },
+    /// The end of a procedure or block ([`S_END`]).
+    End,
+    /// Any other record type.
+    Other,
+}
+
+/// The CodeView types from the `$$TYPES` segment of an [`OmfFile`].
+#[derive(Debug, Clone)]
+pub struct OmfCodeViewTypes {
+    data: Vec<u8>,
+    /// The offset of each type record, in type index order.
+    offsets: Vec<usize>,
+}
+
+impl OmfCodeViewTypes {
+    fn parse(data: Vec<u8>) -> Result<Self> {
+        let mut offsets = Vec::new();
+        let mut fields = Fields {
+            data: &data,
+            offset: 4,
+        };
+        while fields.offset < data.len() {
+            offsets.push(fields.offset);
+            let length = fields.u16()?;
+            fields.bytes(length.into())?;
+        }
+        Ok(OmfCodeViewTypes { data, offsets })
+    }
+
+    /// Return the number of type records.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Return true if there are no type records.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Return the type record for a type index.
+    ///
+    /// Returns `Ok(None)` for primitive type indices, and for indices past
+    /// the last type record.
+    pub fn get(&self, index: u16) -> Result<Option<OmfCodeViewType<'_>>> {
+        let offset = index
+            .checked_sub(CV_FIRST_NONPRIMITIVE)
+            .and_then(|index| self.offsets.get(usize::from(index)));
+        match offset {
+            Some(&offset) => parse_type(&self.data, offset, index).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Return an iterator over the type records, in type index order.
+    pub fn iter(&self) -> impl Iterator<Item = Result<OmfCodeViewType<'_>>> + '_ {
+        self.offsets
+            .iter()
+            .zip(CV_FIRST_NONPRIMITIVE..)
+            .map(move |(&offset, index)| parse_type(&self.data, offset, index))
+    }
+}
+
+/// A CodeView type record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OmfCodeViewType<'data> {
+    /// The type index of the record.
+    pub index: u16,
+    /// The leaf type, such as [`LF_STRUCTURE`].
+    pub leaf: u16,
+    /// The record data, following the length and leaf type.
+    pub data: &'data [u8],
+    /// The decoded record.
+    pub kind: OmfCodeViewTypeKind<'data>,
+}
+
+/// The decoded contents of a CodeView type record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OmfCodeViewTypeKind<'data> {
+    /// A modified type ([`LF_MODIFIER`]).
+    Modifier {
+        /// The modifier attributes (bit 0 const, bit 1 volatile).
+        attributes: u16,
+        /// The modified type.
+        type_index: u16,
+    },
+    /// A pointer type ([`LF_POINTER`]).
+    Pointer {
+        /// The pointer attributes, including the pointer kind and mode.
+        attributes: u16,
+        /// The type pointed to.
+        type_index: u16,
+    },
+    /// An array type ([`LF_ARRAY`]).
+    Array {
+        /// The element type.
+        element_type: u16,
+        /// The type of the array index.
+        index_type: u16,
+        /// The size of the array in bytes.
+        size: u64,
+        /// The array name.
+        name: &'data [u8],
+    },
+    /// A class or structure type ([`LF_CLASS`] or [`LF_STRUCTURE`]).
+    Structure {
+        /// Whether this is a class rather than a structure.
+        is_class: bool,
+        /// The number of fields.
+        count: u16,
+        /// The type index of the field list.
+        fields: u16,
+        /// The property flags.
+        property: u16,
+        /// The size of the structure in bytes.
+        size: u64,
+        /// The structure name.
+        name: &'data [u8],
+    },
+    /// A union type ([`LF_UNION`]).
+    Union {
+        /// The number of fields.
+        count: u16,
+        /// The type index of the field list.
+        fields: u16,
+        /// The property flags.
+        property: u16,
+        /// The size of the union in bytes.
+        size: u64,
+        /// The union name.
+        name: &'data [u8],
+    },
+    /// An enumeration type ([`LF_ENUM`]).
+    Enum {
+        /// The number of enumerators.
+        count: u16,
+        /// The underlying integer type.
+        underlying_type: u16,
+        /// The type index of the field list.
+        fields: u16,
+        /// The property flags.
+        property: u16,
+        /// The enumeration name.
+        name: &'data [u8],
+    },
+    /// A procedure type ([`LF_PROCEDURE`]).
+    Procedure {
+        /// The return type.
+        return_type: u16,
+        /// The calling convention.
+        calling_convention: u8,
+        /// The number of parameters.
+        parameter_count: u16,
+        /// The type index of the argument list.
+        arguments: u16,
+    },
+    /// A procedure argument list ([`LF_ARGLIST`]).
+    ArgumentList {
+        /// The argument types, as little-endian 16-bit type indices.
+        types: &'data [u8],
+    },
+    /// Any other leaf type, including field lists.
+    Other,
+}
+
+/// A cursor for reading the fields of a CodeView record.
+struct Fields<'data> {
+    data: &'data [u8],
+    offset: usize,
+}
+
+impl<'data> Fields<'data> {
+    fn bytes(&mut self, len: usize) -> Result<&'data [u8]> {
+        let bytes = self
+            .data
+            .get(self.offset..)
+            .and_then(|data| data.get(..len))
+            .ok_or(Error("truncated OMF CodeView record"))?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Read a 16-bit or 32-bit offset.
+    fn offset(&mut self, is_32bit: bool) -> Result<u32> {
+        if is_32bit {
+            self.u32()
+        } else {
+            self.u16().map(u32::from)
+        }
+    }
+
+    /// Read a numeric leaf.
+    fn numeric(&mut self) -> Result<u64> {
+        let value = self.u16()?;
+        Ok(match value {
+            0..=0x7FFF => u64::from(value),
+            // LF_CHAR, LF_SHORT, LF_USHORT, LF_LONG, LF_ULONG.
+            0x8000 => self.u8()? as i8 as u64,
+            0x8001 => self.u16()? as i16 as u64,
+            0x8002 => u64::from(self.u16()?),
+            0x8003 => self.u32()? as i32 as u64,
+            0x8004 => u64::from(self.u32()?),
+            _ => return Err(Error("unsupported OMF CodeView numeric leaf")),
+        })
+    }
+
+    /// Read a length-prefixed name.
+    fn name(&mut self) -> Result<&'data [u8]> {
+        let len = self.u8()?;
+        self.bytes(len.into())
+    }
+}
+
+/// Parse the symbol record at `offset`, and return it with the offset of the next record.
+fn parse_symbol(data: &[u8], offset: usize) -> Result<(OmfCodeViewSymbol<'_>, usize)> {
+    let mut header = Fields { data, offset };
+    // The length includes the type, but not the length itself.
+    let length = usize::from(header.u16()?);
+    let record = header.bytes(length)?;
+    let next = header.offset;
+
+    let mut fields = Fields {
+        data: record,
+        offset: 0,
+    };
+    let record_type = fields.u16()?;
+    let record_data = &record[2..];
+    let is_32bit = record_type & 0x0200 != 0;
+    let kind = match record_type {
+        S_LPROC16 | S_GPROC16 | S_LPROC32 | S_GPROC32 => {
+            // Skip the parent and next pointers.
+            fields.u32()?;
+            let end = fields.u32()?;
+            fields.u32()?;
+            let length = fields.offset(is_32bit)?;
+            // Skip the debug start and end offsets.
+            fields.offset(is_32bit)?;
+            fields.offset(is_32bit)?;
+            let offset = fields.offset(is_32bit)?;
+            let segment = fields.u16()?;
+            let type_index = fields.u16()?;
+            // Skip the flags.
+            fields.u8()?;
+            OmfCodeViewSymbolKind::Procedure {
+                name: fields.name()?,
+                global: record_type == S_GPROC16 || record_type == S_GPROC32,
+                offset,
+                segment,
+                length,
+                type_index,
+                end,
+            }
+        }
+        S_LDATA16 | S_GDATA16 | S_LDATA32 | S_GDATA32 => {
+            let offset = fields.offset(is_32bit)?;
+            let segment = fields.u16()?;
+            let type_index = fields.u16()?;
+            OmfCodeViewSymbolKind::Data {
+                name: fields.name()?,
+                global: record_type == S_GDATA16 || record_type == S_GDATA32,
+                offset,
+                segment,
+                type_index,
+            }
+        }
+        S_PUB16 | S_PUB32 => {
+            let offset = fields.offset(is_32bit)?;
+            let segment = fields.u16()?;
+            let type_index = fields.u16()?;
+            OmfCodeViewSymbolKind::Public {
+                name: fields.name()?,
+                offset,
+                segment,
+                type_index,
+            }
+        }
+        S_BPREL16 | S_BPREL32 => {
+            let offset = if is_32bit {
+                fields.u32()? as i32
+            } else {
+                i32::from(fields.u16()? as i16)
+            };
+            let type_index = fields.u16()?;
+            OmfCodeViewSymbolKind::FrameRelative {
+                name: fields.name()?,
+                offset,
+                type_index,
+            }
+        }
+        S_REGREL32 => {
+            let offset = fields.u32()? as i32;
+            let register = fields.u16()?;
+            let type_index = fields.u16()?;
+            OmfCodeViewSymbolKind::RegisterRelative {
+                name: fields.name()?,
+                offset,
+                register,
+                type_index,
+            }
+        }
+        S_REGISTER => {
+            let type_index = fields.u16()?;
+            let register = fields.u16()?;
+            OmfCodeViewSymbolKind::Register {
+                name: fields.name()?,
+                register,
+                type_index,
+            }
+        }
+        S_UDT => {
+            let type_index = fields.u16()?;
+            OmfCodeViewSymbolKind::UserDefinedType {
+                name: fields.name()?,
+                type_index,
+            }
+        }
+        S_OBJNAME => {
+            let signature = fields.u32()?;
+            OmfCodeViewSymbolKind::ObjectName {
+                name: fields.name()?,
+                signature,
+            }
+        }
+        S_END => OmfCodeViewSymbolKind::End,
+        _ => OmfCodeViewSymbolKind::Other,
+    };
+    let symbol = OmfCodeViewSymbol {
+        offset,
+        record_type,
+        data: record_data,
+        kind,
+    };
+    Ok((symbol, next))
+}
+
+/// Parse the type record at `offset`.
+fn parse_type(data: &[u8], offset: usize, index: u16) -> Result<OmfCodeViewType<'_>> {
+    let mut header = Fields { data, offset };
+    let length = usize::from(header.u16()?);
+    let record = header.bytes(length)?;
+
+    let mut fields = Fields {
+        data: record,
+        offset: 0,
+    };
+    let leaf = fields.u16()?;
+    let record_data = &record[2..];
+    let kind = match leaf {
+        LF_MODIFIER => OmfCodeViewTypeKind::Modifier {
+            attributes: fields.u16()?,
+            type_index: fields.u16()?,
+        },
+        LF_POINTER => OmfCodeViewTypeKind::Pointer {
+            attributes: fields.u16()?,
+            type_index: fields.u16()?,
+        },
+        LF_ARRAY => OmfCodeViewTypeKind::Array {
+            element_type: fields.u16()?,
+            index_type: fields.u16()?,
+            size: fields.numeric()?,
+            name: fields.name()?,
+        },
+        LF_CLASS | LF_STRUCTURE => {
+            let count = fields.u16()?;
+            let field_list = fields.u16()?;
+            let property = fields.u16()?;
+            // Skip the derivation list and the virtual function table shape.
+            fields.u16()?;
+            fields.u16()?;
+            OmfCodeViewTypeKind::Structure {
+                is_class: leaf == LF_CLASS,
+                count,
+                fields: field_list,
+                property,
+                size: fields.numeric()?,
+                name: fields.name()?,
+            }
+        }
+        LF_UNION => OmfCodeViewTypeKind::Union {
+            count: fields.u16()?,
+            fields: fields.u16()?,
+            property: fields.u16()?,
+            size: fields.numeric()?,
+            name: fields.name()?,
+        },
+        LF_ENUM => OmfCodeViewTypeKind::Enum {
+            count: fields.u16()?,
+            underlying_type: fields.u16()?,
+            fields: fields.u16()?,
+            property: fields.u16()?,
+            name: fields.name()?,
+        },
+        LF_PROCEDURE => {
+            let return_type = fields.u16()?;
+            let calling_convention = fields.u8()?;
+            // Skip the reserved byte.
+            fields.u8()?;
+            OmfCodeViewTypeKind::Procedure {
+                return_type,
+                calling_convention,
+                parameter_count: fields.u16()?,
+                arguments: fields.u16()?,
+            }
+        }
+        LF_ARGLIST => {
+            let count = fields.u16()?;
+            OmfCodeViewTypeKind::ArgumentList {
+                types: fields.bytes(usize::from(count) * 2)?,
+            }
+        }
+        _ => OmfCodeViewTypeKind::Other,
+    };
+    Ok(OmfCodeViewType {
+        index,
+        leaf,
+        data: record_data,
+        kind,
+    })
+}
+
+impl<'data> OmfCodeViewSymbolKind<'data> {
+    /// Return the name of the symbol, if it has one.
+    pub fn name(&self) -> Option<&'data [u8]> {
+        match *self {
+            OmfCodeViewSymbolKind::Procedure { name, .. }
+            | OmfCodeViewSymbolKind::Data { name, .. }
+            | OmfCodeViewSymbolKind::Public { name, .. }
+            | OmfCodeViewSymbolKind::FrameRelative { name, .. }
+            | OmfCodeViewSymbolKind::RegisterRelative { name, .. }
+            | OmfCodeViewSymbolKind::Register { name, .. }
+            | OmfCodeViewSymbolKind::UserDefinedType { name, .. }
+            | OmfCodeViewSymbolKind::ObjectName { name, .. } => Some(name),
+            OmfCodeViewSymbolKind::End | OmfCodeViewSymbolKind::Other => None,
+        }
+    }
+}
+
+/// Find a segment by name, and return its section index.
+fn find_segment<'data, R: ReadRef<'data>>(
+    file: &OmfFile<'data, R>,
+    name: &str,
+) -> Option<SectionIndex> {
+    file.segments
+        .iter()
+        .position(|segment| file.name(segment.name) == name.as_bytes())
+        .map(|index| SectionIndex(index + 1))
+}
+
+/// Check the CodeView signature at the start of a debug segment.
+fn check_signature(data: &[u8]) -> Result<()> {
+    let signature = data
+        .get(..4)
+        .ok_or(Error("truncated OMF CodeView signature"))?;
+    let signature = u32::from_le_bytes([signature[0], signature[1], signature[2], signature[3]]);
+    if signature != CV_SIGNATURE_C7 {
+        return Err(Error("unsupported OMF CodeView version"));
+    }
+    Ok(())
+}
+
+impl<'data, R: ReadRef<'data>> OmfFile<'data, R> {
+    /// Return the CodeView symbols from the `$$SYMBOLS` segment.
+    ///
+    /// Returns `Ok(None)` if the module has no `$$SYMBOLS` segment.
+    pub fn codeview_symbols(&self) -> Result<Option<OmfCodeViewSymbols>> {
+        let index = match find_segment(self, CODEVIEW_SYMBOLS_SEGMENT) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let data = self.segment_contents(index)?;
+        check_signature(&data)?;
+        Ok(Some(OmfCodeViewSymbols { data }))
+    }
+}
+
+impl<'data, R: ReadRef<'data>> OmfFile<'data, R> {
+    /// Return the CodeView types from the `$$TYPES` segment.
+    ///
+    /// Returns `Ok(None)` if the module has no `$$TYPES` segment.
+    pub fn codeview_types(&self) -> Result<Option<OmfCodeViewTypes>> {
+        let index = match find_segment(self, CODEVIEW_TYPES_SEGMENT) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let data = self.segment_contents(index)?;
+        check_signature(&data)?;
+        OmfCodeViewTypes::parse(data).map(Some)
+    }
+}
+
+impl<'data> OmfCodeViewSymbol<'data> {
+    /// Return the name of the symbol as a string, if it has one and it is UTF-8.
+    pub fn name(&self) -> Option<&'data str> {
+        str::from_utf8(self.kind.name()?).ok()
+    }
+}
@@ -1,58 +1,317 @@
-//! COMMENT record parsing for OMF object files.
-//! Supports common Microsoft, Borland, and Watcom variants.
-//! COMMENT records contain metadata, compiler info, copyright, etc.
+//! COMENT record parsing for OMF object files.
+//!
+//! A COMENT record holds an attribute byte, a comment class, and a payload
+//! whose layout depends on the class. Supports common Microsoft, Borland,
+//! and Watcom classes.
 
-use crate::read::Result;
+use core::str;
 
-/// Known COMMENT kinds found in OMF files.
+use crate::read::{Error, ReadRef, Result};
+
+use super::{export, import, parse_string, OmfExport, OmfFile, OmfImport};
+use crate::omf::*;
+
+/// A comment from a COMENT record.
+#[derive(Debug, Clone, Copy)]
+pub struct OmfComment<'data> {
+    /// Whether the comment must be preserved by utilities that strip comments.
+    ///
+    /// This is the NP bit of the comment type byte.
+    pub no_purge: bool,
+    /// Whether the comment should be omitted from listings.
+    ///
+    /// This is the NL bit of the comment type byte.
+    pub no_list: bool,
+    /// The comment class.
+    pub class: u8,
+    /// The subtype, for the OMF extensions class (0xA0).
+    pub subtype: Option<u8>,
+    /// The raw payload, following the class and subtype.
+    pub data: &'data [u8],
+    /// The decoded payload.
+    pub kind: OmfCommentKind<'data>,
+}
+
+/// The decoded payload of a comment.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum OmfCommentKind {
-    CompilerInfo,
+pub enum OmfCommentKind<'data> {
+    /// The name of the translator that produced the module (class 0x00).
+    Translator,
+    /// A copyright notice (class 0x01).
     Copyright,
-    LinkerInfo,
-    Other(u8),
+    /// Memory model settings (class 0x9D).
+    MemoryModel(OmfMemoryModel),
+    /// A request for the standard DOS segment ordering (class 0x9E).
+    DosSeg,
+    /// A library for the linker to search (class 0x9F).
+    DefaultLibrary(&'data str),
+    /// A DLL import (class 0xA0, subtype 0x01).
+    Import(OmfImport<'data>),
+    /// A DLL export (class 0xA0, subtype 0x02).
+    Export(OmfExport<'data>),
+    /// Incremental compilation index adjustments (class 0xA0, subtype 0x03).
+    IncrementalDefinition(OmfIncrementalDefinition),
+    /// C++ linker directives (class 0xA0, subtype 0x05).
+    LinkerDirectives(OmfLinkerDirectives),
+    /// The end of the records needed by the first linker pass (class 0xA2).
+    LinkPassSeparator,
+    /// The module name of an object in a library (class 0xA3).
+    LibraryModule(&'data str),
+    /// A string to be copied into the output executable (class 0xA4).
+    ExeString,
+    /// Weak externals with default resolutions (class 0xA8).
+    WeakExternals,
+    /// Lazy externals with default resolutions (class 0xA9).
+    LazyExternals,
+    /// PharLap Easy OMF-386 signature (class 0xAA).
+    EasyOmf,
+    /// Borland Turbo Debugger information (classes 0xE0 to 0xE8).
+    ///
+    /// These describe types, scopes, local symbols and source files.
+    BorlandDebug,
+    /// Any other comment class or subtype.
+    Other,
 }
 
-/// Parsed COMMENT metadata (subset of known encodings).
-#[derive(Debug)]
-pub struct OmfComment<'data> {
-    pub kind: OmfCommentKind,
-    pub data: &'data [u8],
+/// Parse the body of a COMENT record.
+pub(super) fn parse_comment(body: &[u8]) -> Result<OmfComment<'_>> {
+    // The comment type byte holds the attribute bits, and is followed by the class.
+    let attributes = *body.first().ok_or(Error("truncated OMF COMENT"))?;
+    let class = *body.get(1).ok_or(Error("truncated OMF COMENT"))?;
+    let mut data = &body[2..];
+    let mut subtype = None;
+    let kind = match class {
+        COMENT_TRANSLATOR => OmfCommentKind::Translator,
+        COMENT_COPYRIGHT => OmfCommentKind::Copyright,
+        COMENT_MEMORY_MODEL => OmfCommentKind::MemoryModel(parse_memory_model(data)),
+        COMENT_DOSSEG => OmfCommentKind::DosSeg,
+        COMENT_DEFAULT_LIBRARY => {
+            // The payload is the library name, without a length prefix.
+            let name = str::from_utf8(data).map_err(|_| Error("non UTF-8 OMF string"))?;
+            OmfCommentKind::DefaultLibrary(name)
+        }
+        COMENT_OMFEXT => {
+            let (&kind, rest) = data
+                .split_first()
+                .ok_or(Error("truncated OMF extension comment"))?;
+            subtype = Some(kind);
+            data = rest;
+            match kind {
+                OMFEXT_IMPDEF => OmfCommentKind::Import(import::parse_impdef(data)?),
+                OMFEXT_EXPDEF => OmfCommentKind::Export(export::parse_expdef(data)?),
+                OMFEXT_INCDEF => OmfCommentKind::IncrementalDefinition(parse_incdef(data)?),
+                OMFEXT_LNKDIR => OmfCommentKind::LinkerDirectives(parse_lnkdir(data)?),
+                _ => OmfCommentKind::Other,
+            }
+        }
+        COMENT_LINK_PASS if data.first() == Some(&0x01) => OmfCommentKind::LinkPassSeparator,
+        COMENT_LIBMOD => OmfCommentKind::LibraryModule(parse_string(data)?),
+        COMENT_EXESTR => OmfCommentKind::ExeString,
+        COMENT_WKEXT => OmfCommentKind::WeakExternals,
+        COMENT_LZEXT => OmfCommentKind::LazyExternals,
+        COMENT_EASY_OMF if data.starts_with(b"80386") => OmfCommentKind::EasyOmf,
+        COMENT_BORLAND_DEBUG_FIRST..=COMENT_BORLAND_DEBUG_LAST => OmfCommentKind::BorlandDebug,
+        _ => OmfCommentKind::Other,
+    };
+    Ok(OmfComment {
+        no_purge: attributes & 0x80 != 0,
+        no_list: attributes & 0x40 != 0,
+        class,
+        subtype,
+        data,
+        kind,
+    })
 }
 
-/// Parses a COMMENT record body and classifies its kind.
-///
-/// This implementation supports subtyped classes used by MS/Borland/Watcom.
-/// If the class is unknown or malformed, returns `None`.
-pub fn parse_comment<'data>(body: &'data [u8]) -> Option<OmfComment<'data>> {
-    if body.len() < 2 {
-        return None;
+impl<'data, R: ReadRef<'data>> OmfFile<'data, R> {
+    /// Return the name of the translator that produced the module.
+    ///
+    /// This is the text of the first translator comment (COMENT class 0x00).
+    /// Returns `None` if there is no such comment or the text is not UTF-8.
+    pub fn producer(&self) -> Option<&'data str> {
+        self.comment_text(OmfCommentKind::Translator)
     }
 
-    let class = body[0];
-    let subtype = body[1];
-    let data = &body[2..];
+    /// Return the copyright notice of the module.
+    ///
+    /// This is the text of the first copyright comment (COMENT class 0x01).
+    /// Returns `None` if there is no such comment or the text is not UTF-8.
+    pub fn copyright(&self) -> Option<&'data str> {
+        self.comment_text(OmfCommentKind::Copyright)
+    }
 
-    let kind = match class {
-        0x00 => OmfCommentKind::CompilerInfo,
-        0x01 => OmfCommentKind::Copyright,
-        0x9A if is_known_subtyped_class(subtype) => OmfCommentKind::LinkerInfo,
-        other => OmfCommentKind::Other(other),
-    };
+    fn comment_text(&self, kind: OmfCommentKind<'data>) -> Option<&'data str> {
+        let comment = self.comments.iter().find(|comment| comment.kind == kind)?;
+        str::from_utf8(comment.data).ok()
+    }
+
+    /// Return the libraries that the module asks the linker to search.
+    ///
+    /// These are given by default library comments (COMENT class 0x9F), such as
+    /// `SLIBCE` for the small model C runtime.
+    pub fn default_libraries(&self) -> impl Iterator<Item = &'data str> + '_ {
+        self.comments
+            .iter()
+            .filter_map(|comment| match comment.kind {
+                OmfCommentKind::DefaultLibrary(name) => Some(name),
+                _ => None,
+            })
+    }
 
-    Some(OmfComment { kind, data })
+    /// Return the strings from EXESTR comments, in file order.
+    ///
+    /// The linker copies these strings into the output executable. They are
+    /// returned as raw bytes since their contents are arbitrary.
+    pub fn exe_strings(&self) -> impl Iterator<Item = &'data [u8]> + '_ {
+        self.comments
+            .iter()
+            .filter(|comment| comment.kind == OmfCommentKind::ExeString)
+            .map(|comment| comment.data)
+    }
+
+    /// Return the index adjustments from INCDEF comments, in file order.
+    ///
+    /// Incremental linkers use these to reserve space in the EXTDEF and LINNUM
+    /// index spaces across compilations.
+    pub fn incremental_definitions(&self) -> impl Iterator<Item = OmfIncrementalDefinition> + '_ {
+        self.comments
+            .iter()
+            .filter_map(|comment| match comment.kind {
+                OmfCommentKind::IncrementalDefinition(incdef) => Some(incdef),
+                _ => None,
+            })
+    }
 }
 
-/// Returns true if this subtype is known in class 0x9A (MS/Borland style).
-fn is_known_subtyped_class(subtype: u8) -> bool {
-    matches!(
-        subtype,
-        0x00 | // Linker Version
-        0x01 | // Memory model
-        0x02 | // DOSSEG
-        0x03 | // Filename
-        0x9A | // Borland .MODEL
-        0x9B | // Watcom signature
-        0x9C   // Pharlap or other signatures
-    )
+/// Index adjustments from an INCDEF comment (COMENT class 0xA0, subtype 0x03).
+///
+/// Incremental compilers emit these to tell the linker how many EXTDEF and
+/// LINNUM indices were added or removed since the previous compilation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OmfIncrementalDefinition {
+    /// The change in the number of EXTDEF indices.
+    pub extdef_delta: i16,
+    /// The change in the number of LINNUM indices.
+    pub linnum_delta: i16,
+}
+
+/// Parse the payload of an INCDEF comment, following the subtype byte.
+pub(super) fn parse_incdef(data: &[u8]) -> Result<OmfIncrementalDefinition> {
+    let data = data.get(..4).ok_or(Error("truncated OMF INCDEF"))?;
+    Ok(OmfIncrementalDefinition {
+        extdef_delta: i16::from_le_bytes([data[0], data[1]]),
+        linnum_delta: i16::from_le_bytes([data[2], data[3]]),
+    })
+}
+
+/// Linker directives from a LNKDIR comment (COMENT class 0xA0, subtype 0x05).
+///
+/// These are emitted by Microsoft C 7 and later for C++ modules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OmfLinkerDirectives {
+    /// Whether the output should be a new executable (segmented) file.
+    pub new_executable: bool,
+    /// Whether to omit CodeView `$PUBLICS` information.
+    pub omit_publics: bool,
+    /// Whether to run the MPCEX p-code compactor.
+    pub run_mpcex: bool,
+    /// The pseudocode version.
+    pub pcode_version: u8,
+    /// The CodeView version.
+    pub codeview_version: u8,
+}
+
+/// Parse the payload of a LNKDIR comment, following the subtype byte.
+pub(super) fn parse_lnkdir(data: &[u8]) -> Result<OmfLinkerDirectives> {
+    let data = data.get(..3).ok_or(Error("truncated OMF LNKDIR"))?;
+    let flags = data[0];
+    Ok(OmfLinkerDirectives {
+        new_executable: flags & 0x01 != 0,
+        omit_publics: flags & 0x02 != 0,
+        run_mpcex: flags & 0x04 != 0,
+        pcode_version: data[1],
+        codeview_version: data[2],
+    })
+}
+
+/// The memory model settings from a memory model comment (COMENT class 0x9D).
+///
+/// The comment payload is a string of ASCII codes, each of which sets one of
+/// these fields. Codes that are not recognized are ignored.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct OmfMemoryModel {
+    /// The memory model.
+    pub model: Option<OmfModelKind>,
+    /// The target instruction set.
+    pub instruction_set: Option<OmfInstructionSet>,
+    /// How floating point operations are performed.
+    pub float: Option<OmfFloatKind>,
+    /// Whether the module was compiled with optimizations.
+    pub optimized: bool,
+}
+
+/// A memory model given by a memory model comment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OmfModelKind {
+    /// Near code and near data (`s`).
+    Small,
+    /// Far code and near data (`m`).
+    Medium,
+    /// Near code and far data (`c`).
+    Compact,
+    /// Far code and far data (`l`).
+    Large,
+    /// Far code and huge data (`h`).
+    Huge,
+    /// 32-bit flat model (`f`).
+    Flat,
+}
+
+/// An instruction set given by a memory model comment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OmfInstructionSet {
+    /// 8086 (`0`).
+    I8086,
+    /// 80186 (`1`).
+    I80186,
+    /// 80286 (`2`).
+    I80286,
+    /// 80386 (`3`).
+    I80386,
+}
+
+/// A floating point setting given by a memory model comment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OmfFloatKind {
+    /// Coprocessor instructions with emulator fixups (`e`).
+    Emulated,
+    /// Inline coprocessor instructions (`p`).
+    Inline,
+    /// Calls to the alternate math library (`A`).
+    Alternate,
+}
+
+/// Parse the payload of a memory model comment.
+pub(super) fn parse_memory_model(data: &[u8]) -> OmfMemoryModel {
+    let mut model = OmfMemoryModel::default();
+    for code in data {
+        match code {
+            b's' => model.model = Some(OmfModelKind::Small),
+            b'm' => model.model = Some(OmfModelKind::Medium),
+            b'c' => model.model = Some(OmfModelKind::Compact),
+            b'l' => model.model = Some(OmfModelKind::Large),
+            b'h' => model.model = Some(OmfModelKind::Huge),
+            b'f' => model.model = Some(OmfModelKind::Flat),
+            b'0' => model.instruction_set = Some(OmfInstructionSet::I8086),
+            b'1' => model.instruction_set = Some(OmfInstructionSet::I80186),
+            b'2' => model.instruction_set = Some(OmfInstructionSet::I80286),
+            b'3' => model.instruction_set = Some(OmfInstructionSet::I80386),
+            b'e' => model.float = Some(OmfFloatKind::Emulated),
+            b'p' => model.float = Some(OmfFloatKind::Inline),
+            b'A' => model.float = Some(OmfFloatKind::Alternate),
+            b'O' => model.optimized = true,
+            _ => {}
+        }
+    }
+    model
 }
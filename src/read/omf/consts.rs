@@ -1,73 +1,181 @@
-// OMF (Object Module Format) Record Type Constants
-// -------------------------------------------------
-// These record types define various kinds of data and metadata in OMF object files,
-// used by Microsoft, Borland, Watcom, and other toolchains.
-// Each value is a unique 8-bit identifier used at the start of a record.
-
-/// 0x80: Comment record — tool/vendor info, debug info, or misc annotations.
-pub const COMENT: u8 = 0x88;
-
-/// 0x8A: Defines an external symbol (used but not defined here).
-pub const EXTDEF: u8 = 0x8C;
-/// 0xA0: 32-bit version of EXTDEF.
-pub const LEXTDEF: u8 = 0xA0;
-
-/// 0x8C: Defines a segment name.
-pub const LNAMES: u8 = 0x96;
-
-/// 0x98: Segment definition (SEGDEF).
-pub const SEGDEF: u8 = 0x98;
-/// 0x99: Segment definition (SEGDEF) with 32-bit addressing flag.
-pub const SEGDEF32: u8 = 0x99;
-
-/// 0x9A: Group definition (GRPDEF).
-pub const GRPDEF: u8 = 0x9A;
-/// 0xA2: Long group definition (rare, not always emitted).
-pub const LGRPDEF: u8 = 0xA2;
-
-/// 0x9C: Public symbol definition (PUBDEF).
-pub const PUBDEF: u8 = 0x90;
-/// 0xA6: Long public symbol definition (32-bit).
-pub const LPUBDEF: u8 = 0xA6;
-
-/// 0x9E: Segment data record (LEdata).
-pub const LEDATA: u8 = 0xA0;
-/// 0xA8: Large/long LEDATA record (32-bit data offset).
-pub const LLEDATA: u8 = 0xA8;
-
-/// 0xA2: Fixup (relocation) information.
-pub const FIXUPP: u8 = 0x9C;
-
-/// 0x9D: Common (BSS-style) uninitialized variable definition.
-pub const COMDEF: u8 = 0xB0;
-/// 0xB2: Long COMDEF.
-pub const LCOMDEF: u8 = 0xB2;
-
-/// 0xC2: COMDAT record — reusable code or data.
-pub const COMDAT: u8 = 0xC2;
-
-/// 0x8A / 0x8B: Module end records (16/32-bit variants).
-pub const MODEND: u8 = 0x8A;
-pub const MODEND32: u8 = 0x8B;
-
-/// 0x94: Threaded relocation (not handled yet).
-pub const THREAD: u8 = 0x94;
-
-/// 0x92: Repeated/iterated data — structured fill block.
-pub const LIDATA: u8 = 0xA2;
-pub const LLIDATA: u8 = 0xA9;
-
-/// 0xA4–0xAB: Misc Borland/Watcom linker/debug records.
-pub const BAKPAT: u8 = 0xA4;
-pub const NBKPAT: u8 = 0xA6;
-pub const LIBHDR: u8 = 0xF0;
-pub const LIBDIR: u8 = 0xF1;
-pub const RIDATA: u8 = 0xF2;
-
-/// 0xFC–0xFE: Borland incremental linker.
-pub const LIDRNAME: u8 = 0xFC;
-pub const LIDRTYP: u8 = 0xFD;
-pub const LIDRVAL: u8 = 0xFE;
-
-/// 0x86: Theadr — specifies the name of the source module.
-pub const THEADR: u8 = 0x80;
+// OMF (Object Module Format) record types.
+// ----------------------------------------
+// The record type and COMENT constants are defined in `crate::omf`, and are
+// shared with the writer.
+
+use crate::omf;
+
+/// The type of an OMF record.
+///
+/// This is the first byte of each record. Where a record has a 32-bit form,
+/// it uses the next (odd) record type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[repr(u8)]
+pub enum OmfRecordType {
+    /// 0x80: Translator header (THEADR). Gives the name of the module.
+    Theadr = omf::THEADR,
+    /// 0x82: Library module header (LHEADR). Like THEADR, used by some library members.
+    Lheadr = omf::LHEADR,
+    /// 0x88: Comment (COMENT). Translator, library and extension information.
+    Coment = omf::COMENT,
+    /// 0x8A: Module end (MODEND).
+    Modend = omf::MODEND,
+    /// 0x8B: Module end (MODEND) with a 32-bit start address.
+    Modend32 = omf::MODEND32,
+    /// 0x8C: External names definition (EXTDEF).
+    Extdef = omf::EXTDEF,
+    /// 0x8E: Type definition (TYPDEF).
+    Typdef = omf::TYPDEF,
+    /// 0x90: Public names definition (PUBDEF).
+    Pubdef = omf::PUBDEF,
+    /// 0x91: Public names definition (PUBDEF) with 32-bit offsets.
+    Pubdef32 = omf::PUBDEF32,
+    /// 0x94: Line numbers (LINNUM).
+    Linnum = omf::LINNUM,
+    /// 0x95: Line numbers (LINNUM) with 32-bit offsets.
+    Linnum32 = omf::LINNUM32,
+    /// 0x96: List of names (LNAMES). Segment, class, group and overlay names.
+    Lnames = omf::LNAMES,
+    /// 0x98: Segment definition (SEGDEF).
+    Segdef = omf::SEGDEF,
+    /// 0x99: Segment definition (SEGDEF) with a 32-bit segment length.
+    Segdef32 = omf::SEGDEF32,
+    /// 0x9A: Group definition (GRPDEF).
+    Grpdef = omf::GRPDEF,
+    /// 0x9C: Fixups (FIXUPP).
+    Fixupp = omf::FIXUPP,
+    /// 0x9D: Fixups (FIXUPP) with 32-bit displacements.
+    Fixupp32 = omf::FIXUPP32,
+    /// 0xA0: Logical enumerated data (LEDATA).
+    Ledata = omf::LEDATA,
+    /// 0xA1: Logical enumerated data (LEDATA) with a 32-bit offset.
+    Ledata32 = omf::LEDATA32,
+    /// 0xA2: Logical iterated data (LIDATA).
+    Lidata = omf::LIDATA,
+    /// 0xA3: Logical iterated data (LIDATA) with a 32-bit offset.
+    Lidata32 = omf::LIDATA32,
+    /// 0xB0: Communal names definition (COMDEF).
+    Comdef = omf::COMDEF,
+    /// 0xB2: Backpatch (BAKPAT).
+    Bakpat = omf::BAKPAT,
+    /// 0xB3: Backpatch (BAKPAT) with 32-bit offsets and values.
+    Bakpat32 = omf::BAKPAT32,
+    /// 0xB4: Local external names definition (LEXTDEF).
+    Lextdef = omf::LEXTDEF,
+    /// 0xB5: Local external names definition (LEXTDEF). Identical to 0xB4.
+    Lextdef32 = omf::LEXTDEF32,
+    /// 0xB6: Local public names definition (LPUBDEF).
+    Lpubdef = omf::LPUBDEF,
+    /// 0xB7: Local public names definition (LPUBDEF) with 32-bit offsets.
+    Lpubdef32 = omf::LPUBDEF32,
+    /// 0xB8: Local communal names definition (LCOMDEF).
+    Lcomdef = omf::LCOMDEF,
+    /// 0xBC: COMDAT external names definition (CEXTDEF).
+    Cextdef = omf::CEXTDEF,
+    /// 0xC2: Initialized communal data (COMDAT).
+    Comdat = omf::COMDAT,
+    /// 0xC3: Initialized communal data (COMDAT) with a 32-bit offset.
+    Comdat32 = omf::COMDAT32,
+    /// 0xC4: Symbol line numbers (LINSYM), for code in a COMDAT.
+    Linsym = omf::LINSYM,
+    /// 0xC5: Symbol line numbers (LINSYM) with 32-bit offsets.
+    Linsym32 = omf::LINSYM32,
+    /// 0xC6: Alias definition (ALIAS).
+    Alias = omf::ALIAS,
+    /// 0xC8: Named backpatch (NBKPAT).
+    Nbkpat = omf::NBKPAT,
+    /// 0xC9: Named backpatch (NBKPAT) with 32-bit offsets and values.
+    Nbkpat32 = omf::NBKPAT32,
+    /// 0xCA: Local logical names definition (LLNAMES).
+    Llnames = omf::LLNAMES,
+    /// 0xCC: OMF version number (VERNUM).
+    Vernum = omf::VERNUM,
+    /// 0xCE: Vendor-specific OMF extension (VENDEXT).
+    Vendext = omf::VENDEXT,
+    /// 0xF0: Library header (LIBHDR).
+    Libhdr = omf::LIBHDR,
+    /// 0xF1: Library end (LIBEND). Followed by the library dictionary.
+    Libend = omf::LIBEND,
+}
+
+impl OmfRecordType {
+    /// Return the record type for the first byte of a record.
+    ///
+    /// Returns `None` for obsolete or unknown record types.
+    pub fn from_u8(value: u8) -> Option<Self> {
+        use OmfRecordType::*;
+        Some(match value {
+            omf::THEADR => Theadr,
+            omf::LHEADR => Lheadr,
+            omf::COMENT => Coment,
+            omf::MODEND => Modend,
+            omf::MODEND32 => Modend32,
+            omf::EXTDEF => Extdef,
+            omf::TYPDEF => Typdef,
+            omf::PUBDEF => Pubdef,
+            omf::PUBDEF32 => Pubdef32,
+            omf::LINNUM => Linnum,
+            omf::LINNUM32 => Linnum32,
+            omf::LNAMES => Lnames,
+            omf::SEGDEF => Segdef,
+            omf::SEGDEF32 => Segdef32,
+            omf::GRPDEF => Grpdef,
+            omf::FIXUPP => Fixupp,
+            omf::FIXUPP32 => Fixupp32,
+            omf::LEDATA => Ledata,
+            omf::LEDATA32 => Ledata32,
+            omf::LIDATA => Lidata,
+            omf::LIDATA32 => Lidata32,
+            omf::COMDEF => Comdef,
+            omf::BAKPAT => Bakpat,
+            omf::BAKPAT32 => Bakpat32,
+            omf::LEXTDEF => Lextdef,
+            omf::LEXTDEF32 => Lextdef32,
+            omf::LPUBDEF => Lpubdef,
+            omf::LPUBDEF32 => Lpubdef32,
+            omf::LCOMDEF => Lcomdef,
+            omf::CEXTDEF => Cextdef,
+            omf::COMDAT => Comdat,
+            omf::COMDAT32 => Comdat32,
+            omf::LINSYM => Linsym,
+            omf::LINSYM32 => Linsym32,
+            omf::ALIAS => Alias,
+            omf::NBKPAT => Nbkpat,
+            omf::NBKPAT32 => Nbkpat32,
+            omf::LLNAMES => Llnames,
+            omf::VERNUM => Vernum,
+            omf::VENDEXT => Vendext,
+            omf::LIBHDR => Libhdr,
+            omf::LIBEND => Libend,
+            _ => return None,
+        })
+    }
+
+    /// Return the first byte of a record of this type.
+    #[inline]
+    pub fn to_u8(self) -> u8 {
+        self as u8
+    }
+
+    /// Return true if this is the 32-bit form of a record.
+    pub fn is_32bit(self) -> bool {
+        use OmfRecordType::*;
+        matches!(
+            self,
+            Modend32
+                | Pubdef32
+                | Linnum32
+                | Segdef32
+                | Fixupp32
+                | Ledata32
+                | Lidata32
+                | Bakpat32
+                | Lextdef32
+                | Lpubdef32
+                | Comdat32
+                | Linsym32
+                | Nbkpat32
+        )
+    }
+}
@@ -0,0 +1,346 @@
+//! OMF library dictionary parsing.
+//!
+//! The dictionary at the end of an OMF library maps public symbol names to the
+//! page numbers of the members that define them. It consists of a prime number
+//! of 512 byte blocks, and symbols are located with a two-level hash: one hash
+//! selects the block, and another selects one of the 37 buckets in the block.
+//! Collisions are resolved by adding a delta, also computed from the name.
+//!
+//! Some librarians add an extended dictionary after the dictionary, which
+//! lists the dependencies between members.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::omf::{
+    dictionary_hash, DICTIONARY_BLOCK_FULL, DICTIONARY_BLOCK_SIZE, DICTIONARY_BUCKETS,
+};
+use crate::read::{Error, ReadRef, Result};
+
+use super::{OmfFile, OmfLibrary, OmfLibraryMember};
+
+/// The record type of the extended dictionary.
+const EXTENDED_DICTIONARY: u8 = 0xF2;
+
+/// The symbol dictionary of an [`OmfLibrary`].
+#[derive(Debug, Clone, Copy)]
+pub struct OmfDictionary<'data> {
+    data: &'data [u8],
+    case_sensitive: bool,
+}
+
+impl<'data> OmfDictionary<'data> {
+    /// Return the number of dictionary blocks.
+    pub fn blocks(&self) -> usize {
+        self.data.len() / DICTIONARY_BLOCK_SIZE
+    }
+
+    /// Find the page number of the member that defines the given symbol.
+    ///
+    /// Names are compared case-insensitively unless the library is marked as
+    /// case sensitive.
+    pub fn find(&self, name: &[u8]) -> Option<u16> {
+        let blocks = self.blocks() as u16;
+        if blocks == 0 || name.is_empty() || name.len() > 255 {
+            return None;
+        }
+        let hash = dictionary_hash(name, blocks);
+        let mut block = hash.block;
+        for _ in 0..blocks {
+            let data =
+                &self.data[usize::from(block) * DICTIONARY_BLOCK_SIZE..][..DICTIONARY_BLOCK_SIZE];
+            let mut bucket = hash.bucket;
+            for _ in 0..DICTIONARY_BUCKETS {
+                let offset = usize::from(data[usize::from(bucket)]) * 2;
+                if offset == 0 {
+                    // A block that isn't full would have stored the symbol here.
+                    if data[usize::from(DICTIONARY_BUCKETS)] != DICTIONARY_BLOCK_FULL {
+                        return None;
+                    }
+                } else if let Some((entry, page)) = parse_entry(data, offset) {
+                    let matches = if self.case_sensitive {
+                        entry == name
+                    } else {
+                        entry.eq_ignore_ascii_case(name)
+                    };
+                    if matches {
+                        return Some(page);
+                    }
+                }
+                bucket = (bucket + hash.bucket_delta) % DICTIONARY_BUCKETS;
+            }
+            block = (block + hash.block_delta) % blocks;
+        }
+        None
+    }
+
+    /// Return an iterator over the symbol names and member page numbers in the dictionary.
+    ///
+    /// The symbols are returned in dictionary order, which is not sorted.
+    pub fn symbols(&self) -> OmfDictionaryIterator<'data> {
+        OmfDictionaryIterator {
+            data: self.data,
+            block: 0,
+            bucket: 0,
+        }
+    }
+}
+
+/// An iterator over the symbols in an [`OmfDictionary`].
+#[derive(Debug, Clone)]
+pub struct OmfDictionaryIterator<'data> {
+    data: &'data [u8],
+    block: usize,
+    bucket: usize,
+}
+
+impl<'data> Iterator for OmfDictionaryIterator<'data> {
+    type Item = Result<(&'data [u8], u16)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(data) = self
+            .data
+            .get(self.block * DICTIONARY_BLOCK_SIZE..)?
+            .get(..DICTIONARY_BLOCK_SIZE)
+        {
+            while self.bucket < usize::from(DICTIONARY_BUCKETS) {
+                let offset = usize::from(data[self.bucket]) * 2;
+                self.bucket += 1;
+                if offset != 0 {
+                    return Some(
+                        parse_entry(data, offset).ok_or(Error("invalid OMF dictionary entry")),
+                    );
+                }
+            }
+            self.block += 1;
+            self.bucket = 0;
+        }
+        None
+    }
+}
+
+/// Parse the dictionary entry at `offset` within a block.
+fn parse_entry(block: &[u8], offset: usize) -> Option<(&[u8], u16)> {
+    let len = usize::from(*block.get(offset)?);
+    let name = block.get(offset + 1..offset + 1 + len)?;
+    let page = block.get(offset + 1 + len..offset + 3 + len)?;
+    Some((name, u16::from_le_bytes([page[0], page[1]])))
+}
+
+/// The extended dictionary of an [`OmfLibrary`].
+///
+/// This lists the members of the library, and for each member, the other
+/// members that it depends on.
+#[derive(Debug, Clone, Copy)]
+pub struct OmfExtendedDictionary<'data> {
+    /// The extended dictionary, starting at the module count.
+    data: &'data [u8],
+    count: u16,
+}
+
+impl<'data> OmfExtendedDictionary<'data> {
+    /// Return the number of members in the module table.
+    pub fn len(&self) -> usize {
+        self.count.into()
+    }
+
+    /// Return true if the module table is empty.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Return the entry for a member, by its 0-based index in the module table.
+    pub fn module(&self, index: usize) -> Result<OmfLibraryModule<'data>> {
+        if index >= self.len() {
+            return Err(Error("invalid OMF extended dictionary index"));
+        }
+        let entry = self
+            .data
+            .get(2 + index * 4..6 + index * 4)
+            .ok_or(Error("truncated OMF extended dictionary"))?;
+        let page = u16::from_le_bytes([entry[0], entry[1]]);
+        // The offset is relative to the start of the module count.
+        let offset = usize::from(u16::from_le_bytes([entry[2], entry[3]]));
+        let dependencies = self
+            .data
+            .get(offset..)
+            .ok_or(Error("invalid OMF extended dictionary offset"))?;
+        Ok(OmfLibraryModule { page, dependencies })
+    }
+
+    /// Return an iterator over the members in the module table.
+    pub fn modules(&self) -> impl Iterator<Item = Result<OmfLibraryModule<'data>>> + '_ {
+        (0..self.len()).map(move |index| self.module(index))
+    }
+}
+
+/// A member entry in an [`OmfExtendedDictionary`].
+#[derive(Debug, Clone, Copy)]
+pub struct OmfLibraryModule<'data> {
+    page: u16,
+    dependencies: &'data [u8],
+}
+
+impl<'data> OmfLibraryModule<'data> {
+    /// Return the page number of the member.
+    #[inline]
+    pub fn page(&self) -> u16 {
+        self.page
+    }
+
+    /// Return the members that this member depends on.
+    ///
+    /// These are 1-based indices into the module table of the extended
+    /// dictionary, since each list is terminated by a 0 index.
+    pub fn dependencies(&self) -> impl Iterator<Item = u16> + 'data {
+        self.dependencies
+            .chunks_exact(2)
+            .map(|index| u16::from_le_bytes([index[0], index[1]]))
+            .take_while(|&index| index != 0)
+    }
+}
+
+impl<'data, R: ReadRef<'data>> OmfLibrary<'data, R> {
+    /// Return the symbol dictionary.
+    pub fn dictionary(&self) -> Result<OmfDictionary<'data>> {
+        let size = u64::from(self.dictionary_blocks()) * DICTIONARY_BLOCK_SIZE as u64;
+        let data = self
+            .data
+            .read_bytes_at(self.dictionary_offset().into(), size)
+            .map_err(|_| Error("truncated OMF library dictionary"))?;
+        Ok(OmfDictionary {
+            data,
+            case_sensitive: self.is_case_sensitive(),
+        })
+    }
+
+    /// Return the extended dictionary, if the library has one.
+    pub fn extended_dictionary(&self) -> Result<Option<OmfExtendedDictionary<'data>>> {
+        let offset = u64::from(self.dictionary_offset())
+            + u64::from(self.dictionary_blocks()) * DICTIONARY_BLOCK_SIZE as u64;
+        let header = match self.data.read_bytes_at(offset, 3) {
+            Ok(header) if header[0] == EXTENDED_DICTIONARY => header,
+            _ => return Ok(None),
+        };
+        let length = u16::from_le_bytes([header[1], header[2]]);
+        let data = self
+            .data
+            .read_bytes_at(offset + 3, length.into())
+            .map_err(|_| Error("truncated OMF extended dictionary"))?;
+        let count = data
+            .get(..2)
+            .map(|count| u16::from_le_bytes([count[0], count[1]]))
+            .ok_or(Error("truncated OMF extended dictionary"))?;
+        Ok(Some(OmfExtendedDictionary { data, count }))
+    }
+
+    /// Return an iterator over the symbols in the dictionary, and the offsets of
+    /// the members that define them.
+    ///
+    /// This is the equivalent of the symbol table of an ar archive.
+    pub fn symbols(&self) -> Result<OmfLibrarySymbolIterator<'data>> {
+        Ok(OmfLibrarySymbolIterator {
+            symbols: self.dictionary()?.symbols(),
+            page_size: self.page_size(),
+        })
+    }
+
+    /// Find the member that defines a public symbol, using the dictionary.
+    ///
+    /// Only the dictionary and the defining member are read, so a linker can
+    /// use this to extract just the members that it needs.
+    ///
+    /// Returns `Ok(None)` if the dictionary does not contain the symbol.
+    pub fn member_for_symbol(&self, name: &[u8]) -> Result<Option<OmfLibraryMember<'data>>> {
+        match self.dictionary()?.find(name) {
+            Some(page) => self.member_at_page(page).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Build an index of the public symbols defined by the members, and the
+    /// offsets of the members that define them.
+    ///
+    /// Unlike [`Self::symbols`], this does not use the dictionary, so it can be
+    /// used for libraries with a missing or stale dictionary. Each member is
+    /// parsed with [`OmfFile::parse_symbols`]. If the `rayon` feature is enabled,
+    /// the members are parsed in parallel.
+    ///
+    /// The symbols are returned in member order.
+    pub fn symbol_index(&self) -> Result<Vec<OmfLibrarySymbol<'data>>> {
+        // Locating the members only reads the record headers, so do this first.
+        let members = self
+            .members()
+            .map(|member| {
+                let member = member?;
+                Ok((member.offset(), member.data(self.data)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        #[cfg(feature = "rayon")]
+        let members = members.into_par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let members = members.into_iter();
+        let symbols = members
+            .map(|(offset, data)| member_symbols(offset, data))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(symbols.into_iter().flatten().collect())
+    }
+}
+
+/// Return the public symbols defined by the member at `offset`.
+fn member_symbols(offset: u64, data: &[u8]) -> Result<Vec<OmfLibrarySymbol<'_>>> {
+    let file = OmfFile::parse_symbols(data)?;
+    Ok(file
+        .symbols
+        .iter()
+        .filter(|symbol| symbol.global && !symbol.is_common && symbol.segment.is_some())
+        .map(|symbol| OmfLibrarySymbol {
+            name: symbol.name,
+            offset,
+        })
+        .collect())
+}
+
+/// An iterator over the symbols in the dictionary of an [`OmfLibrary`].
+#[derive(Debug, Clone)]
+pub struct OmfLibrarySymbolIterator<'data> {
+    symbols: OmfDictionaryIterator<'data>,
+    page_size: u32,
+}
+
+impl<'data> Iterator for OmfLibrarySymbolIterator<'data> {
+    type Item = Result<OmfLibrarySymbol<'data>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let page_size = u64::from(self.page_size);
+        Some(self.symbols.next()?.map(|(name, page)| OmfLibrarySymbol {
+            name,
+            offset: u64::from(page) * page_size,
+        }))
+    }
+}
+
+/// A symbol in the dictionary of an [`OmfLibrary`].
+#[derive(Debug, Clone, Copy)]
+pub struct OmfLibrarySymbol<'data> {
+    name: &'data [u8],
+    offset: u64,
+}
+
+impl<'data> OmfLibrarySymbol<'data> {
+    /// Return the symbol name.
+    #[inline]
+    pub fn name(&self) -> &'data [u8] {
+        self.name
+    }
+
+    /// Return the file offset of the member that defines the symbol.
+    ///
+    /// This can be passed to [`OmfLibrary::member`].
+    #[inline]
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+}
@@ -0,0 +1,731 @@
+//! A record-by-record description of OMF object modules.
+//!
+//! This is intended for debugging modules from unfamiliar tools. Unlike the
+//! other views of [`OmfFile`], it shows every record in file order, including
+//! records that the parser ignores.
+
+use core::fmt;
+
+use crate::read::{self, ReadRef, RelocationKind};
+
+use super::{
+    comment, fixupp, modend, parse_comdat, parse_group_component, parse_name, read_index,
+    read_length, OmfCommentKind, OmfFile, OmfFixupFrame, OmfFixupTarget, OmfRecordType,
+    OmfStartAddress,
+};
+use crate::omf::*;
+
+/// A description of every record in an OMF module.
+///
+/// The [`Display`](fmt::Display) implementation writes each record with its
+/// file offset, type and length, followed by its decoded fields. Indices are
+/// resolved to the names that they refer to, and fixups are decoded including
+/// any THREAD subrecords that they use.
+///
+/// A record that cannot be decoded is shown with the error, and the description
+/// continues with the next record.
+///
+/// Returned by [`OmfFile::dump`].
+#[derive(Debug, Clone, Copy)]
+pub struct OmfDump<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    file: &'file OmfFile<'data, R>,
+}
+
+impl<'data, R: ReadRef<'data>> OmfFile<'data, R> {
+    /// Return a description of every record in the module.
+    ///
+    /// See [`OmfDump`].
+    pub fn dump(&self) -> OmfDump<'data, '_, R> {
+        OmfDump { file: self }
+    }
+}
+
+/// An error while describing a record.
+enum DumpError {
+    Fmt(fmt::Error),
+    Read(read::Error),
+}
+
+impl From<fmt::Error> for DumpError {
+    fn from(error: fmt::Error) -> Self {
+        DumpError::Fmt(error)
+    }
+}
+
+impl From<read::Error> for DumpError {
+    fn from(error: read::Error) -> Self {
+        DumpError::Read(error)
+    }
+}
+
+type DumpResult = Result<(), DumpError>;
+
+/// The state carried between records.
+#[derive(Default)]
+struct DumpState {
+    pharlap: bool,
+    threads: fixupp::ThreadState,
+    /// The description and offset of the last data record, for FIXUPP records.
+    data: Option<(DataRecord, u32)>,
+    /// The number of names seen so far in each index space.
+    lnames: usize,
+    segments: usize,
+    groups: usize,
+    externals: usize,
+}
+
+#[derive(Clone, Copy)]
+enum DataRecord {
+    Segment(u16),
+    Comdat(u16),
+}
+
+impl<'data, 'file, R: ReadRef<'data>> fmt::Display for OmfDump<'data, 'file, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file = self.file;
+        let data = file.data;
+        let size = match data.len() {
+            Ok(size) => size,
+            Err(()) => return writeln!(f, "<unknown file size>"),
+        };
+        let mut state = DumpState::default();
+        let mut pos = 0;
+        while pos + 3 <= size {
+            let header = match data.read_bytes_at(pos, 3) {
+                Ok(header) => header,
+                Err(()) => break,
+            };
+            let kind = header[0];
+            let len = u16::from_le_bytes([header[1], header[2]]);
+            let record_type = OmfRecordType::from_u8(kind);
+            write!(f, "0x{:04X} ", pos)?;
+            match record_type {
+                Some(record_type) => write!(f, "{}", record_name(record_type))?,
+                None => write!(f, "<unknown>")?,
+            }
+            writeln!(f, " (0x{:02X}), length {}", kind, len)?;
+
+            let record = match data.read_bytes_at(pos, 3 + u64::from(len)) {
+                Ok(record) if len != 0 => record,
+                _ => {
+                    writeln!(f, "    <truncated record>")?;
+                    break;
+                }
+            };
+            let body = &record[3..record.len() - 1];
+            let checksum = record[record.len() - 1];
+            if checksum != 0 && record.iter().fold(0u8, |sum, &b| sum.wrapping_add(b)) != 0 {
+                writeln!(f, "    <checksum mismatch: 0x{:02X}>", checksum)?;
+            }
+            pos += 3 + u64::from(len);
+
+            if let Some(record_type) = record_type {
+                match dump_record(f, file, &mut state, record_type, body) {
+                    Ok(()) => {}
+                    Err(DumpError::Fmt(error)) => return Err(error),
+                    Err(DumpError::Read(error)) => writeln!(f, "    <error: {}>", error)?,
+                }
+                if matches!(record_type, OmfRecordType::Modend | OmfRecordType::Modend32) {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn dump_record<'data, R: ReadRef<'data>>(
+    f: &mut fmt::Formatter<'_>,
+    file: &OmfFile<'data, R>,
+    state: &mut DumpState,
+    record_type: OmfRecordType,
+    body: &[u8],
+) -> DumpResult {
+    use OmfRecordType::*;
+
+    let is_32bit = record_type.is_32bit() || state.pharlap;
+    let names = Names { file };
+    match record_type {
+        Theadr | Lheadr => {
+            writeln!(f, "    Name: {}", Quoted(parse_name(body)?))?;
+        }
+        Coment => dump_comment(f, names, state, body)?,
+        Modend | Modend32 => {
+            let (main, start) = modend::parse_modend(body, is_32bit)?;
+            writeln!(f, "    Main: {}", main)?;
+            match start {
+                Some(OmfStartAddress::Logical {
+                    target,
+                    frame,
+                    displacement,
+                }) => {
+                    writeln!(
+                        f,
+                        "    Start: {} + 0x{:X}, frame {}",
+                        names.target(target),
+                        displacement,
+                        names.frame(frame)
+                    )?;
+                }
+                Some(OmfStartAddress::Physical { frame, offset }) => {
+                    writeln!(f, "    Start: {:04X}:{:04X}", frame, offset)?;
+                }
+                None => {}
+            }
+        }
+        Lnames | Llnames => {
+            let mut p = 0;
+            while p < body.len() {
+                let name = parse_name(&body[p..])?;
+                p += 1 + name.len();
+                state.lnames += 1;
+                writeln!(f, "    Name {}: {}", state.lnames, Quoted(name))?;
+            }
+        }
+        Segdef | Segdef32 => {
+            let attributes = *body.first().ok_or(read::Error("truncated OMF SEGDEF"))?;
+            state.segments += 1;
+            writeln!(f, "    Segment: {}", state.segments)?;
+            let mut p = 1;
+            let alignment = attributes >> 5;
+            if alignment == 0 {
+                let bytes = body
+                    .get(p..p + 3)
+                    .ok_or(read::Error("truncated OMF SEGDEF"))?;
+                p += 3;
+                writeln!(
+                    f,
+                    "    Absolute: {:04X}:{:02X}",
+                    u16::from_le_bytes([bytes[0], bytes[1]]),
+                    bytes[2]
+                )?;
+            }
+            let length = read_offset(body, &mut p, is_32bit)?;
+            let name = read_index(body, &mut p)?;
+            let class = read_index(body, &mut p)?;
+            // Some tools omit the overlay name index.
+            let overlay = if p < body.len() {
+                read_index(body, &mut p)?
+            } else {
+                0
+            };
+            writeln!(f, "    Name: {}", names.lname(name))?;
+            writeln!(f, "    Class: {}", names.lname(class))?;
+            if overlay != 0 {
+                writeln!(f, "    Overlay: {}", names.lname(overlay))?;
+            }
+            writeln!(f, "    Alignment: {}", alignment_name(alignment))?;
+            writeln!(f, "    Combine: {}", combine_name((attributes >> 2) & 0x07))?;
+            if attributes & 0x02 != 0 {
+                writeln!(f, "    Length: 0x{:X} (big)", length)?;
+            } else {
+                writeln!(f, "    Length: 0x{:X}", length)?;
+            }
+            writeln!(f, "    Use32: {}", attributes & 0x01 != 0)?;
+        }
+        Grpdef => {
+            let mut p = 0;
+            let name = read_index(body, &mut p)?;
+            state.groups += 1;
+            writeln!(f, "    Group: {}", state.groups)?;
+            writeln!(f, "    Name: {}", names.lname(name))?;
+            while p < body.len() {
+                let kind = body[p];
+                match parse_group_component(body, &mut p)? {
+                    Some(index) => writeln!(f, "    Member: {}", names.segment(index))?,
+                    None => writeln!(f, "    Member: <descriptor 0x{:02X}>", kind)?,
+                }
+            }
+        }
+        Extdef | Lextdef | Lextdef32 => {
+            let mut p = 0;
+            while p < body.len() {
+                let name = parse_name(&body[p..])?;
+                p += 1 + name.len();
+                let type_index = read_index(body, &mut p)?;
+                state.externals += 1;
+                write!(f, "    External {}: {}", state.externals, Quoted(name))?;
+                if type_index != 0 {
+                    write!(f, ", type {}", type_index)?;
+                }
+                writeln!(f)?;
+            }
+        }
+        Cextdef => {
+            let mut p = 0;
+            while p < body.len() {
+                let name = read_index(body, &mut p)?;
+                read_index(body, &mut p)?;
+                state.externals += 1;
+                writeln!(f, "    External {}: {}", state.externals, names.lname(name))?;
+            }
+        }
+        Comdef | Lcomdef => {
+            let mut p = 0;
+            while p < body.len() {
+                let name = parse_name(&body[p..])?;
+                p += 1 + name.len();
+                read_index(body, &mut p)?;
+                let data_type = *body.get(p).ok_or(read::Error("truncated OMF COMDEF"))?;
+                p += 1;
+                state.externals += 1;
+                write!(f, "    Common {}: {}", state.externals, Quoted(name))?;
+                match data_type {
+                    0x61 => {
+                        let count = read_length(body, &mut p)?;
+                        let size = read_length(body, &mut p)?;
+                        writeln!(f, ", far, {} x 0x{:X}", count, size)?;
+                    }
+                    0x62 => {
+                        let size = read_length(body, &mut p)?;
+                        writeln!(f, ", near, 0x{:X}", size)?;
+                    }
+                    _ => {
+                        let size = read_length(body, &mut p)?;
+                        writeln!(
+                            f,
+                            ", segment {}, 0x{:X}",
+                            names.segment(data_type.into()),
+                            size
+                        )?;
+                    }
+                }
+            }
+        }
+        Pubdef | Pubdef32 | Lpubdef | Lpubdef32 => {
+            let mut p = 0;
+            let group = read_index(body, &mut p)?;
+            let segment = read_index(body, &mut p)?;
+            if group != 0 {
+                writeln!(f, "    Group: {}", names.group(group))?;
+            }
+            if segment != 0 {
+                writeln!(f, "    Segment: {}", names.segment(segment))?;
+            } else {
+                let frame = body
+                    .get(p..p + 2)
+                    .ok_or(read::Error("truncated OMF PUBDEF frame"))?;
+                p += 2;
+                writeln!(
+                    f,
+                    "    Frame: {:04X}",
+                    u16::from_le_bytes([frame[0], frame[1]])
+                )?;
+            }
+            while p < body.len() {
+                let name = parse_name(&body[p..])?;
+                p += 1 + name.len();
+                let offset = read_offset(body, &mut p, is_32bit)?;
+                let type_index = read_index(body, &mut p)?;
+                write!(f, "    Public: {} at 0x{:X}", Quoted(name), offset)?;
+                if type_index != 0 {
+                    write!(f, ", type {}", type_index)?;
+                }
+                writeln!(f)?;
+            }
+        }
+        Ledata | Ledata32 | Lidata | Lidata32 => {
+            let mut p = 0;
+            let segment = read_index(body, &mut p)?;
+            let offset = read_offset(body, &mut p, is_32bit)?;
+            state.data = Some((DataRecord::Segment(segment), offset));
+            writeln!(f, "    Segment: {}", names.segment(segment))?;
+            writeln!(f, "    Offset: 0x{:X}", offset)?;
+            writeln!(f, "    Size: 0x{:X}", body.len().saturating_sub(p))?;
+        }
+        Comdat | Comdat32 => {
+            let header = parse_comdat(body, record_type.is_32bit())?;
+            state.data = Some((DataRecord::Comdat(header.name_index), header.offset));
+            writeln!(f, "    Name: {}", names.lname(header.name_index))?;
+            write!(f, "    Flags: 0x{:02X}", header.flags)?;
+            for (flag, name) in [
+                (COMDAT_CONTINUATION, "continuation"),
+                (COMDAT_ITERATED, "iterated"),
+                (COMDAT_LOCAL, "local"),
+            ] {
+                if header.flags & flag != 0 {
+                    write!(f, " {}", name)?;
+                }
+            }
+            writeln!(f)?;
+            writeln!(
+                f,
+                "    Selection: {}",
+                selection_name(header.attributes >> 4)
+            )?;
+            match header.attributes & COMDAT_ALLOCATION_MASK {
+                COMDAT_EXPLICIT => {
+                    writeln!(f, "    Segment: {}", names.segment(header.segment_index))?
+                }
+                COMDAT_CODE16 => writeln!(f, "    Allocation: far code")?,
+                COMDAT_DATA16 => writeln!(f, "    Allocation: far data")?,
+                COMDAT_CODE32 => writeln!(f, "    Allocation: code32")?,
+                COMDAT_DATA32 => writeln!(f, "    Allocation: data32")?,
+                allocation => writeln!(f, "    Allocation: 0x{:X}", allocation)?,
+            }
+            if header.align == 0 {
+                writeln!(f, "    Alignment: segment")?;
+            } else {
+                writeln!(f, "    Alignment: {}", alignment_name(header.align))?;
+            }
+            writeln!(f, "    Offset: 0x{:X}", header.offset)?;
+            writeln!(f, "    Size: 0x{:X}", header.data.len())?;
+        }
+        Fixupp | Fixupp32 => {
+            let fixups = fixupp::parse_fixupp(
+                body,
+                record_type.is_32bit(),
+                state.pharlap,
+                &mut state.threads,
+            )?;
+            let (data, data_offset) = match state.data {
+                Some(data) => data,
+                None => {
+                    writeln!(f, "    <no preceding data record>")?;
+                    (DataRecord::Segment(0), 0)
+                }
+            };
+            match data {
+                DataRecord::Segment(segment) => {
+                    writeln!(f, "    Segment: {}", names.segment(segment))?
+                }
+                DataRecord::Comdat(name) => writeln!(f, "    Comdat: {}", names.lname(name))?,
+            }
+            for fixup in fixups {
+                let fixup = fixup.decode();
+                writeln!(
+                    f,
+                    "    Fixup 0x{:X}: {}, target {} + 0x{:X}, frame {}",
+                    u64::from(data_offset) + u64::from(fixup.offset),
+                    location_name(fixup.kind, fixup.size),
+                    names.target(fixup.target),
+                    fixup.addend,
+                    names.frame(fixup.frame),
+                )?;
+            }
+        }
+        Linnum | Linnum32 => {
+            let mut p = 0;
+            read_index(body, &mut p)?;
+            let segment = read_index(body, &mut p)?;
+            writeln!(f, "    Segment: {}", names.segment(segment))?;
+            dump_lines(f, body, p, is_32bit)?;
+        }
+        Linsym | Linsym32 => {
+            let mut p = 1;
+            let name = read_index(body, &mut p)?;
+            writeln!(f, "    Comdat: {}", names.lname(name))?;
+            dump_lines(f, body, p, record_type.is_32bit())?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn dump_comment<'data, R: ReadRef<'data>>(
+    f: &mut fmt::Formatter<'_>,
+    names: Names<'data, '_, R>,
+    state: &mut DumpState,
+    body: &[u8],
+) -> DumpResult {
+    let comment = comment::parse_comment(body)?;
+    write!(f, "    Class: 0x{:02X}", comment.class)?;
+    if let Some(subtype) = comment.subtype {
+        write!(f, ", subtype 0x{:02X}", subtype)?;
+    }
+    if comment.no_purge {
+        write!(f, ", no purge")?;
+    }
+    if comment.no_list {
+        write!(f, ", no list")?;
+    }
+    writeln!(f)?;
+    match comment.kind {
+        OmfCommentKind::Translator => writeln!(f, "    Translator: {}", Quoted(comment.data))?,
+        OmfCommentKind::Copyright => writeln!(f, "    Copyright: {}", Quoted(comment.data))?,
+        OmfCommentKind::ExeString => writeln!(f, "    String: {}", Quoted(comment.data))?,
+        OmfCommentKind::DefaultLibrary(name) => {
+            writeln!(f, "    Library: {}", Quoted(name.as_bytes()))?
+        }
+        OmfCommentKind::LibraryModule(name) => {
+            writeln!(f, "    Module: {}", Quoted(name.as_bytes()))?
+        }
+        OmfCommentKind::LinkPassSeparator => writeln!(f, "    Link pass separator")?,
+        OmfCommentKind::DosSeg => writeln!(f, "    DOSSEG")?,
+        OmfCommentKind::EasyOmf => {
+            state.pharlap = true;
+            writeln!(f, "    Easy OMF-386")?;
+        }
+        OmfCommentKind::WeakExternals | OmfCommentKind::LazyExternals => {
+            let mut p = 0;
+            while p < comment.data.len() {
+                let external = read_index(comment.data, &mut p)?;
+                let default = read_index(comment.data, &mut p)?;
+                writeln!(
+                    f,
+                    "    {}: {} defaults to {}",
+                    if comment.kind == OmfCommentKind::WeakExternals {
+                        "Weak"
+                    } else {
+                        "Lazy"
+                    },
+                    names.external(external),
+                    names.external(default)
+                )?;
+            }
+        }
+        OmfCommentKind::MemoryModel(model) => writeln!(f, "    {:?}", model)?,
+        OmfCommentKind::Import(import) => writeln!(f, "    {:?}", import)?,
+        OmfCommentKind::Export(export) => writeln!(f, "    {:?}", export)?,
+        OmfCommentKind::IncrementalDefinition(incdef) => writeln!(f, "    {:?}", incdef)?,
+        OmfCommentKind::LinkerDirectives(directives) => writeln!(f, "    {:?}", directives)?,
+        OmfCommentKind::BorlandDebug | OmfCommentKind::Other => {
+            writeln!(f, "    Size: 0x{:X}", comment.data.len())?
+        }
+    }
+    Ok(())
+}
+
+fn dump_lines(f: &mut fmt::Formatter<'_>, body: &[u8], mut p: usize, is_32bit: bool) -> DumpResult {
+    while p < body.len() {
+        let line = body
+            .get(p..p + 2)
+            .ok_or(read::Error("truncated OMF line number entry"))?;
+        let line = u16::from_le_bytes([line[0], line[1]]);
+        p += 2;
+        let offset = read_offset(body, &mut p, is_32bit)?;
+        writeln!(f, "    Line {}: 0x{:X}", line, offset)?;
+    }
+    Ok(())
+}
+
+/// Read a 16-bit or 32-bit offset or length field.
+fn read_offset(body: &[u8], p: &mut usize, is_32bit: bool) -> read::Result<u32> {
+    let truncated = read::Error("truncated OMF offset");
+    let value = if is_32bit {
+        let bytes = body.get(*p..*p + 4).ok_or(truncated)?;
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    } else {
+        let bytes = body.get(*p..*p + 2).ok_or(truncated)?;
+        u32::from(u16::from_le_bytes([bytes[0], bytes[1]]))
+    };
+    *p += if is_32bit { 4 } else { 2 };
+    Ok(value)
+}
+
+/// Resolves indices to the names they refer to, using the parsed module.
+struct Names<'data, 'file, R: ReadRef<'data>> {
+    file: &'file OmfFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Clone for Names<'data, 'file, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Copy for Names<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> Names<'data, 'file, R> {
+    fn lname(self, index: u16) -> Indexed<'data> {
+        let name = usize::from(index)
+            .checked_sub(1)
+            .and_then(|i| self.file.lnames.get(i))
+            .map(|&id| self.file.name(id));
+        Indexed { name, index }
+    }
+
+    fn segment(self, index: u16) -> Indexed<'data> {
+        let name = usize::from(index)
+            .checked_sub(1)
+            .and_then(|i| self.file.segments.get(i))
+            .map(|segment| self.file.name(segment.name));
+        Indexed { name, index }
+    }
+
+    fn group(self, index: u16) -> Indexed<'data> {
+        let name = usize::from(index)
+            .checked_sub(1)
+            .and_then(|i| self.file.groups.get(i))
+            .map(|group| self.file.name(group.name));
+        Indexed { name, index }
+    }
+
+    fn external(self, index: u16) -> Indexed<'data> {
+        let name = self
+            .file
+            .external_symbol(index)
+            .and_then(|symbol| self.file.symbols.get(symbol.0))
+            .map(|symbol| symbol.name);
+        Indexed { name, index }
+    }
+
+    fn target(self, target: OmfFixupTarget) -> Target<'data> {
+        match target {
+            OmfFixupTarget::Segment(index) => Target("segment", self.segment(index)),
+            OmfFixupTarget::Group(index) => Target("group", self.group(index)),
+            OmfFixupTarget::Symbol(index) => Target("external", self.external(index)),
+        }
+    }
+
+    fn frame(self, frame: Option<OmfFixupFrame>) -> Frame<'data> {
+        match frame {
+            Some(OmfFixupFrame::Segment(index)) => {
+                Frame::Index(Target("segment", self.segment(index)))
+            }
+            Some(OmfFixupFrame::Group(index)) => Frame::Index(Target("group", self.group(index))),
+            Some(OmfFixupFrame::Symbol(index)) => {
+                Frame::Index(Target("external", self.external(index)))
+            }
+            Some(OmfFixupFrame::Location) => Frame::Name("location"),
+            Some(OmfFixupFrame::Target) => Frame::Name("target"),
+            None => Frame::Name("<unknown>"),
+        }
+    }
+}
+
+/// A name and the index it was resolved from.
+struct Indexed<'data> {
+    name: Option<&'data [u8]>,
+    index: u16,
+}
+
+impl<'data> fmt::Display for Indexed<'data> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "{} ({})", Quoted(name), self.index),
+            None => write!(f, "<invalid> ({})", self.index),
+        }
+    }
+}
+
+/// A fixup target or frame that is given by an index.
+struct Target<'data>(&'static str, Indexed<'data>);
+
+impl<'data> fmt::Display for Target<'data> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.0, self.1)
+    }
+}
+
+enum Frame<'data> {
+    Index(Target<'data>),
+    Name(&'static str),
+}
+
+impl<'data> fmt::Display for Frame<'data> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Frame::Index(target) => target.fmt(f),
+            Frame::Name(name) => f.write_str(name),
+        }
+    }
+}
+
+/// A name that is displayed in quotes, lossily as UTF-8.
+struct Quoted<'data>(&'data [u8]);
+
+impl<'data> fmt::Display for Quoted<'data> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match core::str::from_utf8(self.0) {
+            Ok(name) => write!(f, "{:?}", name),
+            Err(_) => write!(f, "{:?}", alloc::string::String::from_utf8_lossy(self.0)),
+        }
+    }
+}
+
+fn record_name(record_type: OmfRecordType) -> &'static str {
+    use OmfRecordType::*;
+    match record_type {
+        Theadr => "THEADR",
+        Lheadr => "LHEADR",
+        Coment => "COMENT",
+        Modend => "MODEND",
+        Modend32 => "MODEND32",
+        Extdef => "EXTDEF",
+        Typdef => "TYPDEF",
+        Pubdef => "PUBDEF",
+        Pubdef32 => "PUBDEF32",
+        Linnum => "LINNUM",
+        Linnum32 => "LINNUM32",
+        Lnames => "LNAMES",
+        Segdef => "SEGDEF",
+        Segdef32 => "SEGDEF32",
+        Grpdef => "GRPDEF",
+        Fixupp => "FIXUPP",
+        Fixupp32 => "FIXUPP32",
+        Ledata => "LEDATA",
+        Ledata32 => "LEDATA32",
+        Lidata => "LIDATA",
+        Lidata32 => "LIDATA32",
+        Comdef => "COMDEF",
+        Bakpat => "BAKPAT",
+        Bakpat32 => "BAKPAT32",
+        Lextdef | Lextdef32 => "LEXTDEF",
+        Lpubdef => "LPUBDEF",
+        Lpubdef32 => "LPUBDEF32",
+        Lcomdef => "LCOMDEF",
+        Cextdef => "CEXTDEF",
+        Comdat => "COMDAT",
+        Comdat32 => "COMDAT32",
+        Linsym => "LINSYM",
+        Linsym32 => "LINSYM32",
+        Alias => "ALIAS",
+        Nbkpat => "NBKPAT",
+        Nbkpat32 => "NBKPAT32",
+        Llnames => "LLNAMES",
+        Vernum => "VERNUM",
+        Vendext => "VENDEXT",
+        Libhdr => "LIBHDR",
+        Libend => "LIBEND",
+    }
+}
+
+fn alignment_name(alignment: u8) -> &'static str {
+    match alignment {
+        0 => "absolute",
+        1 => "byte",
+        2 => "word",
+        3 => "paragraph",
+        4 => "page",
+        5 => "dword",
+        6 => "4K page",
+        _ => "<unknown>",
+    }
+}
+
+fn combine_name(combine: u8) -> &'static str {
+    match combine {
+        0 => "private",
+        2 | 4 | 7 => "public",
+        5 => "stack",
+        6 => "common",
+        _ => "<unknown>",
+    }
+}
+
+fn selection_name(selection: u8) -> &'static str {
+    match selection {
+        0 => "no match",
+        1 => "pick any",
+        2 => "same size",
+        3 => "exact match",
+        _ => "<unknown>",
+    }
+}
+
+fn location_name(kind: RelocationKind, size: u8) -> &'static str {
+    match (kind, size) {
+        (RelocationKind::Relative, 8) => "self-relative low byte",
+        (RelocationKind::Relative, 16) => "self-relative offset16",
+        (RelocationKind::Relative, 32) => "self-relative offset32",
+        (RelocationKind::SegmentRelative, 8) => "low byte",
+        (RelocationKind::SegmentRelative, 16) => "offset16",
+        (RelocationKind::SegmentRelative, 32) => "offset32",
+        (RelocationKind::SectionIndex, _) => "segment",
+        (_, 8) => "high byte",
+        (_, 32) => "pointer16:16",
+        (_, 48) => "pointer16:32",
+        _ => "<unknown>",
+    }
+}
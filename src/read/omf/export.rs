@@ -0,0 +1,69 @@
+//! EXPDEF comment parsing for OMF object files.
+//!
+//! EXPDEF comments (COMENT class 0xA0, subtype 0x02) are emitted for DLL
+//! source modules. Each one declares an entry point that the linker should
+//! add to the DLL's export table.
+
+use crate::read::{Error, ReadRef, Result};
+
+use super::{parse_string, OmfCommentKind, OmfFile};
+
+/// An export declared by an EXPDEF comment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OmfExport<'data> {
+    /// The name that the entry point is exported as.
+    pub exported_name: &'data str,
+    /// The name of the public symbol that is exported.
+    ///
+    /// This is the same as `exported_name` if the comment does not give an
+    /// internal name.
+    pub internal_name: &'data str,
+    /// The export ordinal, if one was given.
+    pub ordinal: Option<u16>,
+    /// Whether the exported name is kept in the resident names table.
+    pub resident_name: bool,
+    /// Whether the entry point does not use the data segment.
+    pub no_data: bool,
+    /// The number of parameter words for call gates.
+    pub parameter_count: u8,
+}
+
+/// Parse the payload of an EXPDEF comment, following the subtype byte.
+pub(super) fn parse_expdef(data: &[u8]) -> Result<OmfExport<'_>> {
+    let flags = *data.first().ok_or(Error("truncated OMF EXPDEF"))?;
+    let mut p = 1;
+    let exported_name = parse_string(&data[p..])?;
+    p += 1 + exported_name.len();
+    let internal_name = parse_string(data.get(p..).unwrap_or(&[]))?;
+    p += 1 + internal_name.len();
+    let ordinal = if flags & 0x80 != 0 {
+        let ordinal = data.get(p..p + 2).ok_or(Error("truncated OMF EXPDEF"))?;
+        Some(u16::from_le_bytes([ordinal[0], ordinal[1]]))
+    } else {
+        None
+    };
+    Ok(OmfExport {
+        exported_name,
+        internal_name: if internal_name.is_empty() {
+            exported_name
+        } else {
+            internal_name
+        },
+        ordinal,
+        resident_name: flags & 0x40 != 0,
+        no_data: flags & 0x20 != 0,
+        parameter_count: flags & 0x1F,
+    })
+}
+
+impl<'data, R: ReadRef<'data>> OmfFile<'data, R> {
+    /// Return the exports declared by EXPDEF comments, in file order.
+    pub fn export_definitions(&self) -> impl Iterator<Item = OmfExport<'data>> + '_ {
+        self.comments
+            .iter()
+            .filter_map(|comment| match comment.kind {
+                OmfCommentKind::Export(export) => Some(export),
+                _ => None,
+            })
+    }
+}
@@ -1,85 +1,225 @@
-//! Minimal parser for OMF FIXUPP records.
-//! Supports basic 16-bit/32-bit segment-relative fixups.
-
-#[derive(Debug)]
-pub enum OmfFixup {
-    /// A relocation to a segment + offset (absolute).
-    SegmentOffset {
-        location: u16,
-        is_32bit: bool,
-        target_segment: u8,
-        displacement: u16,
-    },
-}
+//! Parser for OMF FIXUPP records.
+//!
+//! A FIXUPP record contains a sequence of THREAD and FIXUP subrecords.
+//! FIXUP subrecords apply to the data of the preceding LEDATA, LIDATA or
+//! COMDAT record.
 
-impl OmfFixup {
-    pub fn parse_all(data: &[u8]) -> Vec<Self> {
-        let mut out = Vec::new();
-        let mut i = 0;
+use alloc::vec::Vec;
 
-        while i < data.len() {
-            let kind = data[i];
-            i += 1;
+use crate::read::{Error, RelocationEncoding, RelocationKind, Result};
 
-            if kind & 0x80 == 0 {
-                continue; // skip thread records
-            }
+use super::{read_index, OmfFixupFrame, OmfFixupTarget, OmfRelocation};
 
-            let loc_type = (kind >> 3) & 0b11;
-            let is_32bit = loc_type == 0b10;
+/// A fixup in the compact form stored by [`OmfFixups`](super::OmfFixups).
+///
+/// The target, frame and location are stored as the raw methods and location
+/// type from the FIXUP subrecord, and are decoded when the fixup is read.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct PackedFixup {
+    /// Offset of the fixup location within the section.
+    pub(super) offset: u32,
+    /// The target displacement.
+    displacement: u32,
+    target_index: u16,
+    frame_index: u16,
+    /// The target method in bits 0-1, and the frame method in bits 2-4.
+    methods: u8,
+    /// The location type in bits 0-3, the M bit in bit 4, and whether the
+    /// fixup is from a PharLap module in bit 5.
+    location: u8,
+}
 
-            if i >= data.len() {
-                break;
-            }
-            let loc_off = u16::from_le_bytes([data[i], data[i + 1]]);
-            i += 2;
+impl PackedFixup {
+    /// Decode the fixup.
+    pub(super) fn decode(&self) -> OmfRelocation {
+        let target = match self.methods & 0x03 {
+            0 => OmfFixupTarget::Segment(self.target_index),
+            1 => OmfFixupTarget::Group(self.target_index),
+            _ => OmfFixupTarget::Symbol(self.target_index),
+        };
+        let frame = match (self.methods >> 2) & 0x07 {
+            0 => Some(OmfFixupFrame::Segment(self.frame_index)),
+            1 => Some(OmfFixupFrame::Group(self.frame_index)),
+            2 => Some(OmfFixupFrame::Symbol(self.frame_index)),
+            4 => Some(OmfFixupFrame::Location),
+            5 => Some(OmfFixupFrame::Target),
+            _ => None,
+        };
+        // The location was validated when the fixup was parsed.
+        let (kind, size) = location_kind(self.location).unwrap_or((RelocationKind::Unknown, 0));
+        OmfRelocation {
+            offset: self.offset,
+            target,
+            frame,
+            kind,
+            encoding: RelocationEncoding::Generic,
+            size,
+            addend: self.displacement.into(),
+        }
+    }
+}
 
-            if i >= data.len() {
-                break;
-            }
-            let tgt_desc = data[i];
-            i += 1;
+/// Return the relocation kind and size for a packed location byte.
+fn location_kind(location: u8) -> Option<(RelocationKind, u8)> {
+    let pharlap = location & 0x20 != 0;
+    let segment_relative = location & 0x10 != 0;
+    let (kind, size) = match location & 0x0F {
+        // PharLap 32-bit offset.
+        5 if pharlap => (RelocationKind::Absolute, 32),
+        // PharLap 16:32 far pointer.
+        6 if pharlap => (RelocationKind::Unknown, 48),
+        // Low-order byte.
+        0 => (RelocationKind::Absolute, 8),
+        // 16-bit offset.
+        1 | 5 => (RelocationKind::Absolute, 16),
+        // 16-bit segment base.
+        2 => (RelocationKind::SectionIndex, 16),
+        // 16:16 far pointer.
+        3 => (RelocationKind::Unknown, 32),
+        // High-order byte.
+        4 => (RelocationKind::Unknown, 8),
+        // 32-bit offset.
+        9 | 13 => (RelocationKind::Absolute, 32),
+        // 16:32 far pointer.
+        11 => (RelocationKind::Unknown, 48),
+        _ => return None,
+    };
+    let kind = match kind {
+        RelocationKind::Absolute if !segment_relative => RelocationKind::Relative,
+        RelocationKind::Absolute => RelocationKind::SegmentRelative,
+        kind => kind,
+    };
+    Some((kind, size))
+}
 
-            if tgt_desc & 0x04 != 0 {
-                continue; // unsupported external fixup
-            }
+/// State for handling threaded fixups in OMF.
+///
+/// A THREAD subrecord allows reusing a frame or target for multiple fixups,
+/// reducing object file size. Each thread is identified by a 2-bit thread number.
+/// Threads persist across FIXUPP records until they are redefined.
+#[derive(Debug, Default)]
+pub(super) struct ThreadState {
+    /// Frame threads, with the frame method.
+    frames: [Option<(u8, u16)>; 4],
+    /// Target threads, with the target method.
+    targets: [Option<(u8, u16)>; 4],
+}
+
+/// Parse the subrecords of a FIXUPP record.
+///
+/// The offset of each returned relocation is relative to the start of the
+/// data in the preceding data record.
+///
+/// For PharLap Easy OMF-386 modules, displacements are always 32-bit and the
+/// location types differ.
+pub(super) fn parse_fixupp(
+    body: &[u8],
+    is_32bit: bool,
+    pharlap: bool,
+    threads: &mut ThreadState,
+) -> Result<Vec<PackedFixup>> {
+    let is_32bit = is_32bit || pharlap;
+    let mut relocs = Vec::new();
+    let mut p = 0;
+    while p < body.len() {
+        let typ = body[p];
+        p += 1;
 
-            if i + 2 > data.len() {
-                break;
+        if typ & 0x80 == 0 {
+            // THREAD subrecord.
+            let method = (typ >> 2) & 0x07;
+            let thread = usize::from(typ & 0x03);
+            if typ & 0x40 != 0 {
+                threads.frames[thread] = Some(parse_frame(body, &mut p, method)?);
+            } else {
+                // Only the low two bits of a target thread method are used.
+                let method = method & 0x03;
+                let index = if method < 3 {
+                    read_index(body, &mut p)?
+                } else {
+                    0
+                };
+                threads.targets[thread] = Some((method, index));
             }
+            continue;
+        }
 
-            let seg = data[i];
-            let disp = data[i + 1] as u16;
-            i += 2;
+        // FIXUP subrecord.
+        let low_offset = body.get(p).ok_or(Error("truncated OMF fixup"))?;
+        p += 1;
+        let offset = (u32::from(typ & 0x03) << 8) | u32::from(*low_offset);
+        let mut location = ((typ >> 2) & 0x0F) | ((typ & 0x40) >> 2);
+        if pharlap {
+            location |= 0x20;
+        }
+        if location_kind(location).is_none() {
+            return Err(Error("unsupported OMF fixup location type"));
+        }
 
-            out.push(OmfFixup::SegmentOffset {
-                location: loc_off,
-                is_32bit,
-                target_segment: seg,
-                displacement: disp,
-            });
+        let fix_data = *body.get(p).ok_or(Error("truncated OMF fixup"))?;
+        p += 1;
+
+        let frame_method = (fix_data >> 4) & 0x07;
+        let (frame_method, frame_index) = if fix_data & 0x80 != 0 {
+            threads.frames[usize::from(frame_method & 0x03)]
+                .ok_or(Error("undefined OMF fixup frame thread"))?
+        } else {
+            parse_frame(body, &mut p, frame_method)?
+        };
+
+        let (target_method, target_index) = if fix_data & 0x08 != 0 {
+            threads.targets[usize::from(fix_data & 0x03)]
+                .ok_or(Error("undefined OMF fixup target thread"))?
+        } else {
+            let method = fix_data & 0x03;
+            let index = if method < 3 {
+                read_index(body, &mut p)?
+            } else {
+                0
+            };
+            (method, index)
+        };
+        if target_method == 3 {
+            return Err(Error("unsupported OMF fixup target method"));
         }
 
-        out
+        // The displacement is omitted if the P bit is set.
+        let displacement = if fix_data & 0x04 != 0 {
+            0
+        } else if is_32bit {
+            let bytes = body
+                .get(p..p + 4)
+                .ok_or(Error("truncated OMF fixup displacement"))?;
+            p += 4;
+            u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+        } else {
+            let bytes = body
+                .get(p..p + 2)
+                .ok_or(Error("truncated OMF fixup displacement"))?;
+            p += 2;
+            u32::from(u16::from_le_bytes([bytes[0], bytes[1]]))
+        };
+
+        relocs.push(PackedFixup {
+            offset,
+            displacement,
+            target_index,
+            frame_index,
+            methods: target_method | (frame_method << 2),
+            location,
+        });
     }
+    Ok(relocs)
 }
 
-/// State for handling threaded fixups in OMF.
-/// In OMF, a "thread" record allows reusing a frame or target index for multiple fixups,
-/// reducing object file size.
-///
-/// Each thread is identified by a Thread Index (0–3 for frame, 0–7 for target),
-/// and stores either a segment/group/symbol index.
-///
-/// Thread fixups are optional. If missing, fixups specify explicit frame/target.
+/// Parse a frame datum for the given frame method.
 ///
-/// NOTE: This is currently unused — FIXUPP handling skips thread records.
-/// See the `FIXUPP` match arm in `mod.rs`.
-#[derive(Default, Debug)]
-pub struct ThreadState {
-    /// Frame threads (indexed 0..4) — reused in fixups when frame = 0b11
-    pub frame_threads: [Option<OmfFixupFrame>; 4],
-
-    /// Target threads (indexed 0..8) — reused when target = 0b11
-    pub target_threads: [Option<OmfFixupTarget>; 8],
+/// Returns the frame method and index.
+pub(super) fn parse_frame(body: &[u8], p: &mut usize, method: u8) -> Result<(u8, u16)> {
+    let index = match method {
+        0..=2 => read_index(body, p)?,
+        4 | 5 => 0,
+        _ => return Err(Error("unsupported OMF fixup frame method")),
+    };
+    Ok((method, index))
 }
@@ -0,0 +1,384 @@
+//! Linking OMF modules into a flat binary image.
+
+use alloc::collections::btree_map::BTreeMap;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use crate::read::{Error, ReadRef, RelocationKind, Result, SectionIndex};
+
+use super::{
+    OmfFile, OmfFixupFrame, OmfFixupTarget, OmfRelocation, OmfSectionData, OmfSegmentLayout,
+    OmfSymbol,
+};
+
+/// A flat binary image linked from OMF modules.
+///
+/// This is a minimal linker for images without a header or relocation table,
+/// such as DOS `.COM` files and boot sectors. The caller chooses the linear
+/// address of each segment with an [`OmfSegmentLayout`], and
+/// [`OmfImage::new`] copies the segment contents into the image and applies
+/// their fixups.
+///
+/// Linear addresses are treated as physical addresses, so segment base fixups
+/// store the paragraph number of the frame as if the image were loaded at
+/// address 0. This is correct for a boot sector, but a `.COM` file must not
+/// contain segment base fixups, since it is loaded at an unknown segment.
+///
+/// Fixups are resolved as follows:
+/// - external symbols are resolved to a definition with the same name in any
+///   module, preferring definitions in the module that refers to them, and
+///   using the default resolution of weak externals if there is no definition,
+/// - segments with the same name and class in different modules are combined,
+///   and have the frame of the lowest base address,
+/// - groups with the same name in different modules are combined, and have
+///   the frame of the lowest base address of their segments.
+///
+/// Communal variables and COMDATs are not allocated, so fixups that refer to
+/// them are errors. Fixups in LIDATA records are also not supported.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OmfImage {
+    /// The linear address of the first byte of the image.
+    pub origin: u64,
+    /// The image contents.
+    ///
+    /// This extends from `origin` to the end of the highest segment with a base
+    /// address, and bytes that are not in any segment are zero.
+    pub data: Vec<u8>,
+}
+
+/// The linked modules, with the combined segments and groups.
+struct Linker<'data, 'file, R: ReadRef<'data>> {
+    modules: Vec<(&'file OmfFile<'data, R>, &'file OmfSegmentLayout)>,
+    segment_bases: BTreeMap<(&'data [u8], &'data [u8]), u64>,
+    group_frames: BTreeMap<&'data [u8], u64>,
+    publics: BTreeMap<&'data [u8], (usize, usize)>,
+}
+
+/// The linear address of a fixup target, and the frame it is relative to.
+struct Resolved {
+    address: u64,
+    frame: Option<u64>,
+}
+
+impl OmfImage {
+    /// Link modules that have been laid out.
+    ///
+    /// Each module is paired with the layout of its segments. Segments without
+    /// a base address in the layout are not included in the image, and fixups
+    /// that refer to them are errors.
+    ///
+    /// Returns an error if segment data is placed below `origin`, if a fixup
+    /// cannot be resolved, or if a fixup value does not fit in its location.
+    pub fn new<'data, 'file, R, I>(modules: I, origin: u64) -> Result<Self>
+    where
+        'data: 'file,
+        R: ReadRef<'data> + 'file,
+        I: IntoIterator<Item = (&'file OmfFile<'data, R>, &'file OmfSegmentLayout)>,
+    {
+        let linker = Linker::new(modules.into_iter().collect());
+
+        let mut data = Vec::new();
+        for &(file, layout) in &linker.modules {
+            for (i, segment) in file.segments.iter().enumerate() {
+                let index = SectionIndex(i + 1);
+                let base = match layout.base(index) {
+                    Some(base) => base,
+                    None => continue,
+                };
+                // Bytes below the origin are omitted, so that a segment can
+                // start before the origin if its data doesn't (e.g. `ORG 100h`).
+                let first = segment.records.iter().map(OmfSectionData::offset).min();
+                if let Some(first) = first {
+                    if base + u64::from(first) < origin {
+                        return Err(Error("OMF segment data is below the image origin"));
+                    }
+                }
+                let skip = origin.saturating_sub(base);
+                let start = usize::try_from(base.saturating_sub(origin))
+                    .map_err(|_| Error("OMF image is too large"))?;
+                let length = usize::try_from(segment.length.saturating_sub(skip))
+                    .map_err(|_| Error("OMF image is too large"))?;
+                let end = start
+                    .checked_add(length)
+                    .ok_or(Error("OMF image is too large"))?;
+                if data.len() < end {
+                    data.resize(end, 0);
+                }
+                let contents = file.segment_data(index)?;
+                // `skip` is at most the offset of the first record.
+                let contents = &contents[(skip as usize).min(contents.len())..];
+                data.get_mut(start..start + contents.len())
+                    .ok_or(Error("OMF segment data exceeds the segment length"))?
+                    .copy_from_slice(contents);
+            }
+        }
+
+        for (module, &(file, layout)) in linker.modules.iter().enumerate() {
+            for (i, segment) in file.segments.iter().enumerate() {
+                let index = SectionIndex(i + 1);
+                let base = match layout.base(index) {
+                    Some(base) => base,
+                    None => continue,
+                };
+                if !segment.fixups.is_empty()
+                    && segment
+                        .records
+                        .iter()
+                        .any(|record| matches!(record, OmfSectionData::Lidata { .. }))
+                {
+                    return Err(Error("unsupported OMF fixups for LIDATA record"));
+                }
+                for fixup in &segment.fixups {
+                    let location = base + u64::from(fixup.offset);
+                    let value = linker.fixup_value(module, index, location, &fixup)?;
+                    if u64::from(fixup.offset) + value.len() as u64 > segment.length {
+                        return Err(Error("OMF fixup is outside its segment"));
+                    }
+                    // The fixup is in a data record, which is within the image.
+                    let start = (location - origin) as usize;
+                    value.apply(&mut data[start..start + value.len()]);
+                }
+            }
+        }
+
+        Ok(OmfImage { origin, data })
+    }
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Linker<'data, 'file, R> {
+    fn new(modules: Vec<(&'file OmfFile<'data, R>, &'file OmfSegmentLayout)>) -> Self {
+        let mut segment_bases = BTreeMap::new();
+        let mut group_frames = BTreeMap::new();
+        let mut publics = BTreeMap::new();
+        for (module, &(file, layout)) in modules.iter().enumerate() {
+            for (i, segment) in file.segments.iter().enumerate() {
+                if let Some(base) = layout.base(SectionIndex(i + 1)) {
+                    let key = (file.name(segment.name), file.name(segment.class));
+                    let entry = segment_bases.entry(key).or_insert(base);
+                    *entry = (*entry).min(base);
+                }
+            }
+            for (i, group) in file.groups.iter().enumerate() {
+                if let Some(frame) = file.group_frame(layout, i as u16 + 1) {
+                    let entry = group_frames.entry(file.name(group.name)).or_insert(frame);
+                    *entry = (*entry).min(frame);
+                }
+            }
+            for (i, symbol) in file.symbols.iter().enumerate() {
+                if symbol.global && symbol.segment.is_some() {
+                    publics.entry(symbol.name).or_insert((module, i));
+                }
+            }
+        }
+        Linker {
+            modules,
+            segment_bases,
+            group_frames,
+            publics,
+        }
+    }
+
+    /// Return the frame of a segment, combined with segments of the same name and class.
+    fn segment_frame(&self, module: usize, segment: SectionIndex) -> Option<u64> {
+        let file = self.modules[module].0;
+        let segment = file.segments.get(segment.0.checked_sub(1)?)?;
+        let key = (file.name(segment.name), file.name(segment.class));
+        self.segment_bases.get(&key).map(|base| base & !0xf)
+    }
+
+    /// Return the frame of a group, combined with groups of the same name.
+    fn group_frame(&self, module: usize, group: u16) -> Option<u64> {
+        let file = self.modules[module].0;
+        let group = file.groups.get(usize::from(group).checked_sub(1)?)?;
+        self.group_frames.get(file.name(group.name)).copied()
+    }
+
+    /// Find the definition of an external symbol.
+    fn definition(&self, module: usize, external: u16) -> Result<(usize, &'file OmfSymbol<'data>)> {
+        let file = self.modules[module].0;
+        let symbol = file
+            .external_symbol(external)
+            .and_then(|index| file.symbols.get(index.0))
+            .ok_or(Error("invalid OMF external index"))?;
+        if let Some(definition) = self.lookup(module, symbol.name) {
+            return Ok(definition);
+        }
+        symbol
+            .default_resolution
+            .and_then(|index| file.symbols.get(index.0))
+            .and_then(|default| self.lookup(module, default.name))
+            .ok_or(Error("unresolved OMF external symbol"))
+    }
+
+    /// Find a definition by name, preferring definitions in `module`.
+    fn lookup(&self, module: usize, name: &[u8]) -> Option<(usize, &'file OmfSymbol<'data>)> {
+        let file = self.modules[module].0;
+        let local = file
+            .symbols
+            .iter()
+            .find(|symbol| symbol.name == name && symbol.segment.is_some());
+        if let Some(symbol) = local {
+            return Some((module, symbol));
+        }
+        let &(module, index) = self.publics.get(name)?;
+        Some((module, &self.modules[module].0.symbols[index]))
+    }
+
+    /// Return the address and frame of a defined symbol.
+    fn symbol(&self, module: usize, symbol: &OmfSymbol<'data>) -> Result<Resolved> {
+        if symbol.is_common || symbol.is_comdat {
+            return Err(Error("unsupported OMF fixup target"));
+        }
+        let (file, layout) = self.modules[module];
+        let address = file
+            .symbol_address(layout, symbol)
+            .ok_or(Error("OMF fixup target segment has no base address"))?;
+        let frame = match (symbol.segment, symbol.group) {
+            (Some(SectionIndex(0)), _) => Some(address & !0xf),
+            (_, 0) => symbol
+                .segment
+                .and_then(|segment| self.segment_frame(module, segment)),
+            (_, group) => self.group_frame(module, group),
+        };
+        Ok(Resolved { address, frame })
+    }
+
+    /// Return the address and frame of a fixup target, without the displacement.
+    fn target(&self, module: usize, target: OmfFixupTarget) -> Result<Resolved> {
+        let (file, layout) = self.modules[module];
+        match target {
+            OmfFixupTarget::Segment(segment) => {
+                let segment = SectionIndex(segment.into());
+                Ok(Resolved {
+                    address: file
+                        .linear_address(layout, segment, 0)
+                        .ok_or(Error("OMF fixup target segment has no base address"))?,
+                    frame: self.segment_frame(module, segment),
+                })
+            }
+            OmfFixupTarget::Group(group) => {
+                let frame = self
+                    .group_frame(module, group)
+                    .ok_or(Error("OMF fixup target group has no base address"))?;
+                Ok(Resolved {
+                    address: frame,
+                    frame: Some(frame),
+                })
+            }
+            OmfFixupTarget::Symbol(external) => {
+                let (module, symbol) = self.definition(module, external)?;
+                self.symbol(module, symbol)
+            }
+        }
+    }
+
+    /// Calculate the value to add to a fixup location.
+    fn fixup_value(
+        &self,
+        module: usize,
+        segment: SectionIndex,
+        location: u64,
+        fixup: &OmfRelocation,
+    ) -> Result<FixupValue> {
+        let target = self.target(module, fixup.target)?;
+        let frame = match fixup.frame {
+            Some(OmfFixupFrame::Segment(segment)) => {
+                self.segment_frame(module, SectionIndex(segment.into()))
+            }
+            Some(OmfFixupFrame::Group(group)) => self.group_frame(module, group),
+            Some(OmfFixupFrame::Symbol(external)) => {
+                let (module, symbol) = self.definition(module, external)?;
+                self.symbol(module, symbol)?.frame
+            }
+            Some(OmfFixupFrame::Location) => self.segment_frame(module, segment),
+            Some(OmfFixupFrame::Target) => target.frame,
+            None => None,
+        };
+        let address = target.address.wrapping_add(fixup.addend as u64);
+        let offset = |frame: Option<u64>| -> Result<u64> {
+            let frame = frame.ok_or(Error("unsupported OMF fixup frame"))?;
+            address
+                .checked_sub(frame)
+                .ok_or(Error("OMF fixup target is below its frame"))
+        };
+        let base = |frame: Option<u64>| -> Result<u64> {
+            Ok(frame.ok_or(Error("unsupported OMF fixup frame"))? >> 4)
+        };
+        let overflow = Error("OMF fixup value does not fit in its location");
+        let fits = |value: u64, bits: u8| -> Result<u64> {
+            if bits < 64 && value >> bits != 0 {
+                Err(overflow)
+            } else {
+                Ok(value)
+            }
+        };
+        Ok(match (fixup.kind, fixup.size) {
+            (RelocationKind::Relative, size) => {
+                let value = address.wrapping_sub(location + u64::from(size / 8));
+                // Near jumps and calls may wrap around within a 64K segment, but a
+                // short jump must reach its target.
+                if size == 8 && !(-0x80..0x80).contains(&(value as i64)) {
+                    return Err(overflow);
+                }
+                FixupValue::new(value, size)
+            }
+            (RelocationKind::SegmentRelative, size) => {
+                FixupValue::new(fits(offset(frame)?, size)?, size)
+            }
+            (RelocationKind::SectionIndex, 16) => FixupValue::new(fits(base(frame)?, 16)?, 16),
+            // High-order byte of a 16-bit offset.
+            (RelocationKind::Unknown, 8) => FixupValue::new(fits(offset(frame)?, 16)? >> 8, 8),
+            // Far pointers: an offset followed by a segment base.
+            (RelocationKind::Unknown, size @ (32 | 48)) => {
+                let offset_size = size - 16;
+                let offset = fits(offset(frame)?, offset_size)?;
+                let base = fits(base(frame)?, 16)?;
+                FixupValue {
+                    base: Some(base),
+                    ..FixupValue::new(offset, offset_size)
+                }
+            }
+            _ => return Err(Error("unsupported OMF fixup location")),
+        })
+    }
+}
+
+/// A value to add to the bytes at a fixup location.
+struct FixupValue {
+    value: u64,
+    size: usize,
+    /// The segment base of a far pointer, which follows the offset.
+    base: Option<u64>,
+}
+
+impl FixupValue {
+    fn new(value: u64, bits: u8) -> Self {
+        FixupValue {
+            value,
+            size: usize::from(bits / 8),
+            base: None,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.size + if self.base.is_some() { 2 } else { 0 }
+    }
+
+    /// Add the value to the little-endian bytes at the location.
+    ///
+    /// The offset and segment base of a far pointer are added separately.
+    fn apply(&self, bytes: &mut [u8]) {
+        let (offset, base) = bytes.split_at_mut(self.size);
+        add_le(offset, self.value);
+        if let Some(value) = self.base {
+            add_le(base, value);
+        }
+    }
+}
+
+fn add_le(bytes: &mut [u8], value: u64) {
+    let mut existing = [0; 8];
+    existing[..bytes.len()].copy_from_slice(bytes);
+    let value = u64::from_le_bytes(existing).wrapping_add(value);
+    bytes.copy_from_slice(&value.to_le_bytes()[..bytes.len()]);
+}
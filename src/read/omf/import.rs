@@ -0,0 +1,141 @@
+//! IMPDEF comment parsing for OMF object files.
+//!
+//! IMPDEF comments (COMENT class 0xA0, subtype 0x01) are found in OMF import
+//! libraries. Each one describes a symbol that is imported from a DLL, either
+//! by name or by ordinal.
+//!
+//! Import libraries that contain only IMPDEF comments can be converted to
+//! COFF short import files for use with linkers that do not read OMF.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "coff")]
+use crate::endian::{LittleEndian as LE, U16, U32};
+#[cfg(feature = "coff")]
+use crate::pe;
+#[cfg(feature = "coff")]
+use crate::pod::bytes_of;
+use crate::read::{Error, ReadRef, Result};
+
+use super::{parse_string, OmfCommentKind, OmfFile, OmfLibrary};
+
+/// A DLL import described by an IMPDEF comment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OmfImport<'data> {
+    /// The name that the module uses to refer to the imported symbol.
+    pub internal_name: &'data str,
+    /// The name of the DLL to import the symbol from.
+    pub module: &'data str,
+    /// The entry to import from the DLL.
+    pub entry: OmfImportEntry<'data>,
+}
+
+/// The DLL entry imported by an IMPDEF comment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OmfImportEntry<'data> {
+    /// Import by name.
+    Name(&'data str),
+    /// Import by ordinal.
+    Ordinal(u16),
+}
+
+impl<'data> OmfImport<'data> {
+    /// Return the equivalent COFF short import file.
+    ///
+    /// `machine` is the `IMAGE_FILE_MACHINE_*` value for the header. The module
+    /// name is used as the DLL name without adding an extension. IMPDEF comments
+    /// do not distinguish between code and data, so the import type is always
+    /// code.
+    ///
+    /// The result can be parsed with `read::coff::ImportFile`, and added as a
+    /// member of a COFF import library.
+    #[cfg(feature = "coff")]
+    pub fn to_coff_import(&self, machine: u16) -> Vec<u8> {
+        let (ordinal_or_hint, name_type, export) = match self.entry {
+            OmfImportEntry::Ordinal(ordinal) => (ordinal, pe::IMPORT_OBJECT_ORDINAL, None),
+            OmfImportEntry::Name(name) if name == self.internal_name => {
+                (0, pe::IMPORT_OBJECT_NAME, None)
+            }
+            OmfImportEntry::Name(name) => (0, pe::IMPORT_OBJECT_NAME_EXPORTAS, Some(name)),
+        };
+        let mut data = Vec::new();
+        data.extend_from_slice(self.internal_name.as_bytes());
+        data.push(0);
+        data.extend_from_slice(self.module.as_bytes());
+        data.push(0);
+        if let Some(export) = export {
+            data.extend_from_slice(export.as_bytes());
+            data.push(0);
+        }
+        let header = pe::ImportObjectHeader {
+            sig1: U16::new(LE, pe::IMAGE_FILE_MACHINE_UNKNOWN),
+            sig2: U16::new(LE, pe::IMPORT_OBJECT_HDR_SIG2),
+            version: U16::new(LE, 0),
+            machine: U16::new(LE, machine),
+            time_date_stamp: U32::new(LE, 0),
+            size_of_data: U32::new(LE, data.len() as u32),
+            ordinal_or_hint: U16::new(LE, ordinal_or_hint),
+            name_type: U16::new(
+                LE,
+                (pe::IMPORT_OBJECT_CODE << pe::IMPORT_OBJECT_TYPE_SHIFT)
+                    | (name_type << pe::IMPORT_OBJECT_NAME_SHIFT),
+            ),
+        };
+        let mut out = Vec::with_capacity(core::mem::size_of_val(&header) + data.len());
+        out.extend_from_slice(bytes_of(&header));
+        out.extend_from_slice(&data);
+        out
+    }
+}
+
+/// Parse the payload of an IMPDEF comment, following the subtype byte.
+pub(super) fn parse_impdef(data: &[u8]) -> Result<OmfImport<'_>> {
+    let ordinal_flag = *data.first().ok_or(Error("truncated OMF IMPDEF"))?;
+    let mut p = 1;
+    let internal_name = parse_string(&data[p..])?;
+    p += 1 + internal_name.len();
+    let module = parse_string(data.get(p..).unwrap_or(&[]))?;
+    p += 1 + module.len();
+    let entry = if ordinal_flag != 0 {
+        let ordinal = data.get(p..p + 2).ok_or(Error("truncated OMF IMPDEF"))?;
+        OmfImportEntry::Ordinal(u16::from_le_bytes([ordinal[0], ordinal[1]]))
+    } else {
+        // An empty entry name means the entry name is the same as the internal name.
+        let name = match data.get(p..) {
+            Some(rest) if !rest.is_empty() => parse_string(rest)?,
+            _ => "",
+        };
+        OmfImportEntry::Name(if name.is_empty() { internal_name } else { name })
+    };
+    Ok(OmfImport {
+        internal_name,
+        module,
+        entry,
+    })
+}
+
+impl<'data, R: ReadRef<'data>> OmfFile<'data, R> {
+    /// Return the imports described by IMPDEF comments, in file order.
+    pub fn import_definitions(&self) -> impl Iterator<Item = OmfImport<'data>> + '_ {
+        self.comments
+            .iter()
+            .filter_map(|comment| match comment.kind {
+                OmfCommentKind::Import(import) => Some(import),
+                _ => None,
+            })
+    }
+}
+
+impl<'data, R: ReadRef<'data>> OmfLibrary<'data, R> {
+    /// Return the imports described by IMPDEF comments in all members, in file order.
+    ///
+    /// For an import library, this is the complete list of imports.
+    pub fn import_definitions(&self) -> Result<Vec<OmfImport<'data>>> {
+        let mut imports = Vec::new();
+        for member in self.members() {
+            let file = member?.parse(self.data)?;
+            imports.extend(file.import_definitions());
+        }
+        Ok(imports)
+    }
+}
@@ -0,0 +1,205 @@
+//! Resolution of OMF `segment:offset` pairs to linear addresses.
+
+use alloc::vec::Vec;
+
+use crate::read::{ReadRef, SectionIndex};
+
+use super::{OmfFile, OmfSymbol};
+
+/// Linear base addresses assigned to the segments of an [`OmfFile`].
+///
+/// OMF object modules do not assign addresses to segments; that is done by the
+/// linker. A layout records the base address chosen for each segment so that
+/// `segment:offset` pairs can be converted to linear addresses, and back.
+///
+/// Segments are identified by their 1-based SEGDEF index, which is also
+/// their [`SectionIndex`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OmfSegmentLayout {
+    bases: Vec<Option<u64>>,
+}
+
+impl OmfSegmentLayout {
+    /// Create an empty layout.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the linear base address of a segment.
+    pub fn set_base(&mut self, segment: SectionIndex, base: u64) {
+        if segment.0 == 0 {
+            return;
+        }
+        if self.bases.len() < segment.0 {
+            self.bases.resize(segment.0, None);
+        }
+        self.bases[segment.0 - 1] = Some(base);
+    }
+
+    /// Return the linear base address of a segment, if it has been assigned.
+    pub fn base(&self, segment: SectionIndex) -> Option<u64> {
+        self.bases.get(segment.0.checked_sub(1)?).copied().flatten()
+    }
+}
+
+impl<'data, R: ReadRef<'data>> OmfFile<'data, R> {
+    /// Lay out the segments consecutively in definition order, starting at `start`.
+    ///
+    /// Each segment is aligned according to its SEGDEF alignment attribute.
+    /// Absolute segments are placed at the address given by their frame number,
+    /// and do not affect the placement of other segments.
+    pub fn sequential_layout(&self, start: u64) -> OmfSegmentLayout {
+        let mut layout = OmfSegmentLayout::new();
+        let mut next = start;
+        for (i, segment) in self.segments.iter().enumerate() {
+            let index = SectionIndex(i + 1);
+            if let Some(address) = segment.absolute {
+                layout.set_base(index, address);
+                continue;
+            }
+            let align = segment.align.max(1);
+            let base = next.wrapping_add(align - 1) & !(align - 1);
+            layout.set_base(index, base);
+            next = base.wrapping_add(segment.length);
+        }
+        layout
+    }
+
+    /// Return the segment indices in DOSSEG order.
+    ///
+    /// This is the standard ordering used by DOS linkers when a module contains
+    /// a DOSSEG comment (see [`OmfFile::dosseg`]):
+    /// 1. segments with a class name ending in `CODE`,
+    /// 2. other segments that are not in `DGROUP`,
+    /// 3. `DGROUP` segments with class `BEGDATA`,
+    /// 4. other `DGROUP` segments, except those with class `BSS` or `STACK`,
+    /// 5. `DGROUP` segments with class `BSS`,
+    /// 6. `DGROUP` segments with class `STACK`.
+    ///
+    /// Segments within each category retain their definition order.
+    pub fn dosseg_order(&self) -> Vec<SectionIndex> {
+        let dgroup = self
+            .groups
+            .iter()
+            .find(|group| self.name(group.name).eq_ignore_ascii_case(b"DGROUP"));
+        let in_dgroup = |index: usize| {
+            dgroup.map_or(false, |group| {
+                group
+                    .segment_indices
+                    .iter()
+                    .any(|&segment| usize::from(segment) == index)
+            })
+        };
+        let category = |index: usize, class: &[u8]| {
+            let is_class = |name: &[u8]| class.eq_ignore_ascii_case(name);
+            let ends_with = |suffix: &[u8]| {
+                class.len() >= suffix.len()
+                    && class[class.len() - suffix.len()..].eq_ignore_ascii_case(suffix)
+            };
+            if ends_with(b"CODE") {
+                0
+            } else if !in_dgroup(index) {
+                1
+            } else if is_class(b"BEGDATA") {
+                2
+            } else if is_class(b"BSS") {
+                4
+            } else if is_class(b"STACK") {
+                5
+            } else {
+                3
+            }
+        };
+        let mut order = self
+            .segments
+            .iter()
+            .enumerate()
+            .map(|(i, segment)| {
+                let class = self.name(segment.class);
+                (category(i + 1, class), SectionIndex(i + 1))
+            })
+            .collect::<Vec<_>>();
+        // Stable sort, so definition order is kept within each category.
+        order.sort_by_key(|&(category, _)| category);
+        order.into_iter().map(|(_, index)| index).collect()
+    }
+
+    /// Resolve an offset within a segment to a linear address.
+    ///
+    /// Returns `None` if the segment index is invalid or the segment has no base
+    /// address in `layout`.
+    pub fn linear_address(
+        &self,
+        layout: &OmfSegmentLayout,
+        segment: SectionIndex,
+        offset: u64,
+    ) -> Option<u64> {
+        self.segments.get(segment.0.checked_sub(1)?)?;
+        layout.base(segment)?.checked_add(offset)
+    }
+
+    /// Resolve a linear address to the segment containing it and the offset within it.
+    ///
+    /// If segments overlap, the segment with the lowest index is returned.
+    pub fn segment_offset(
+        &self,
+        layout: &OmfSegmentLayout,
+        address: u64,
+    ) -> Option<(SectionIndex, u64)> {
+        self.segments.iter().enumerate().find_map(|(i, segment)| {
+            let index = SectionIndex(i + 1);
+            let offset = address.checked_sub(layout.base(index)?)?;
+            (offset < segment.length).then_some((index, offset))
+        })
+    }
+
+    /// Return the linear address of the frame of a group.
+    ///
+    /// The frame is the paragraph containing the lowest base address of the
+    /// segments in the group. `group` is the 1-based GRPDEF index.
+    ///
+    /// Returns `None` if the group index is invalid, or if none of the group's
+    /// segments have a base address in `layout`.
+    pub fn group_frame(&self, layout: &OmfSegmentLayout, group: u16) -> Option<u64> {
+        let group = self.groups.get(usize::from(group).checked_sub(1)?)?;
+        group
+            .segment_indices
+            .iter()
+            .filter_map(|&segment| layout.base(SectionIndex(segment.into())))
+            .min()
+            .map(|base| base & !0xf)
+    }
+
+    /// Resolve an offset within a segment to an offset relative to a group frame.
+    ///
+    /// This is the value that a group-relative fixup stores for the location.
+    pub fn group_offset(
+        &self,
+        layout: &OmfSegmentLayout,
+        group: u16,
+        segment: SectionIndex,
+        offset: u64,
+    ) -> Option<u64> {
+        let frame = self.group_frame(layout, group)?;
+        self.linear_address(layout, segment, offset)?
+            .checked_sub(frame)
+    }
+
+    /// Return the linear address of a symbol.
+    ///
+    /// Absolute symbols have a fixed address. For symbols with a base group,
+    /// [`OmfFile::group_offset`] gives the offset relative to the group frame.
+    ///
+    /// Returns `None` for undefined symbols, or if the symbol's segment has no
+    /// base address in `layout`.
+    pub fn symbol_address(
+        &self,
+        layout: &OmfSegmentLayout,
+        symbol: &OmfSymbol<'data>,
+    ) -> Option<u64> {
+        match symbol.segment? {
+            SectionIndex(0) => Some(symbol.offset),
+            segment => self.linear_address(layout, segment, symbol.offset),
+        }
+    }
+}
@@ -0,0 +1,290 @@
+//! OMF library (`.LIB`) reader.
+//!
+//! An OMF library starts with a LIBHDR record that gives the page size and the
+//! location of the symbol dictionary. Each member object module starts on a
+//! page boundary and ends with a MODEND record. The last member is followed by
+//! a LIBEND record, and then the dictionary.
+//!
+//! Libraries such as the Watcom runtime libraries can be several megabytes, so
+//! members are located using only their record headers, and the member data is
+//! not read until it is requested.
+
+use core::marker::PhantomData;
+use core::str;
+
+use crate::read::{Error, ReadRef, Result};
+
+use super::{is_module_header, parse_name, OmfFile, OmfRecordType};
+
+/// An OMF library file.
+#[derive(Debug, Clone, Copy)]
+pub struct OmfLibrary<'data, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) data: R,
+    len: u64,
+    page_size: u32,
+    dictionary_offset: u32,
+    dictionary_blocks: u16,
+    flags: u8,
+    marker: PhantomData<&'data ()>,
+}
+
+impl<'data, R: ReadRef<'data>> OmfLibrary<'data, R> {
+    /// Check whether the data looks like an OMF library.
+    ///
+    /// The LIBHDR record must have a valid page size, and the first page must
+    /// start with a member header or the LIBEND record.
+    pub fn peek(data: R) -> core::result::Result<(), ()> {
+        if is_library_header(data, 0) {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    /// Parse the LIBHDR record at the start of the library.
+    pub fn parse(data: R) -> Result<Self> {
+        let len = data.len().map_err(|_| Error("unknown OMF library size"))?;
+        let header = data
+            .read_bytes_at(0, 10)
+            .map_err(|_| Error("truncated OMF library header"))?;
+        if header[0] != OmfRecordType::Libhdr.to_u8() {
+            return Err(Error("missing OMF LIBHDR record"));
+        }
+        // The record length is the page size less the record type and length.
+        let page_size = u32::from(u16::from_le_bytes([header[1], header[2]])) + 3;
+        if !page_size.is_power_of_two() || page_size < 16 {
+            return Err(Error("invalid OMF library page size"));
+        }
+        let dictionary_offset = u32::from_le_bytes([header[3], header[4], header[5], header[6]]);
+        let dictionary_blocks = u16::from_le_bytes([header[7], header[8]]);
+        let flags = header[9];
+        Ok(OmfLibrary {
+            data,
+            len,
+            page_size,
+            dictionary_offset,
+            dictionary_blocks,
+            flags,
+            marker: PhantomData,
+        })
+    }
+
+    /// Return the page size. Each member starts at a multiple of the page size.
+    #[inline]
+    pub fn page_size(&self) -> u32 {
+        self.page_size
+    }
+
+    /// Return the file offset of the symbol dictionary.
+    #[inline]
+    pub fn dictionary_offset(&self) -> u32 {
+        self.dictionary_offset
+    }
+
+    /// Return the number of 512 byte blocks in the symbol dictionary.
+    #[inline]
+    pub fn dictionary_blocks(&self) -> u16 {
+        self.dictionary_blocks
+    }
+
+    /// Return true if the symbol dictionary is case sensitive.
+    #[inline]
+    pub fn is_case_sensitive(&self) -> bool {
+        self.flags & 0x01 != 0
+    }
+
+    /// Return an iterator over the member object modules.
+    ///
+    /// Members are located on demand as the iterator advances.
+    pub fn members(&self) -> OmfLibraryMemberIterator<'data, R> {
+        OmfLibraryMemberIterator {
+            data: self.data,
+            len: self.len,
+            page_size: self.page_size,
+            offset: u64::from(self.page_size),
+            marker: PhantomData,
+        }
+    }
+
+    /// Return the member object module at the given file offset.
+    pub fn member(&self, offset: u64) -> Result<OmfLibraryMember<'data>> {
+        if offset == 0 || offset % u64::from(self.page_size) != 0 {
+            return Err(Error("invalid OMF library member offset"));
+        }
+        parse_library_member(self.data, self.len, offset)?
+            .ok_or(Error("invalid OMF library member offset"))
+    }
+
+    /// Return the member object module starting at the given page number.
+    ///
+    /// Page numbers are used by the symbol dictionary to locate members.
+    pub fn member_at_page(&self, page: u16) -> Result<OmfLibraryMember<'data>> {
+        self.member(u64::from(page) * u64::from(self.page_size))
+    }
+}
+
+/// Return true if there is a plausible LIBHDR record at `offset`.
+pub(crate) fn is_library_header<'data, R: ReadRef<'data>>(data: R, offset: u64) -> bool {
+    let header = match data.read_bytes_at(offset, 3) {
+        Ok(header) => header,
+        Err(()) => return false,
+    };
+    if header[0] != OmfRecordType::Libhdr.to_u8() {
+        return false;
+    }
+    let page_size = u64::from(u16::from_le_bytes([header[1], header[2]])) + 3;
+    if !page_size.is_power_of_two() || page_size < 16 {
+        return false;
+    }
+    let first = offset + page_size;
+    match data.read_bytes_at(first, 1) {
+        Ok(&[rec]) if rec == OmfRecordType::Libend.to_u8() => true,
+        Ok(_) => is_module_header(data, first),
+        Err(()) => false,
+    }
+}
+
+/// Locate the member starting at `offset`.
+///
+/// This reads the THEADR record and the header of each following record until
+/// the MODEND record, but does not read the rest of the member data.
+///
+/// Returns `Ok(None)` at the LIBEND record or the end of the data.
+pub(crate) fn parse_library_member<'data, R: ReadRef<'data>>(
+    data: R,
+    len: u64,
+    offset: u64,
+) -> Result<Option<OmfLibraryMember<'data>>> {
+    let header = match data.read_bytes_at(offset, 3) {
+        Ok(header) => header,
+        Err(()) => return Ok(None),
+    };
+    match OmfRecordType::from_u8(header[0]) {
+        Some(OmfRecordType::Libend) => return Ok(None),
+        Some(OmfRecordType::Theadr | OmfRecordType::Lheadr) => {}
+        _ => return Err(Error("missing OMF library member header")),
+    }
+    let length = u16::from_le_bytes([header[1], header[2]]);
+    let theadr = data
+        .read_bytes_at(offset + 3, length.into())
+        .map_err(|_| Error("truncated OMF library member"))?;
+    let name = parse_name(theadr).map_err(|_| Error("invalid OMF library member name"))?;
+    let mut pos = offset + 3 + u64::from(length);
+    loop {
+        let header = data
+            .read_bytes_at(pos, 3)
+            .map_err(|_| Error("truncated OMF library member"))?;
+        let rec = OmfRecordType::from_u8(header[0]);
+        let length = u64::from(u16::from_le_bytes([header[1], header[2]]));
+        pos += 3 + length;
+        if pos > len {
+            return Err(Error("truncated OMF library member"));
+        }
+        if matches!(rec, Some(OmfRecordType::Modend | OmfRecordType::Modend32)) {
+            break;
+        }
+    }
+    Ok(Some(OmfLibraryMember {
+        offset,
+        size: pos - offset,
+        name,
+    }))
+}
+
+/// An iterator over the members of an [`OmfLibrary`].
+#[derive(Debug)]
+pub struct OmfLibraryMemberIterator<'data, R: ReadRef<'data> = &'data [u8]> {
+    data: R,
+    len: u64,
+    page_size: u32,
+    offset: u64,
+    marker: PhantomData<&'data ()>,
+}
+
+impl<'data, R: ReadRef<'data>> Iterator for OmfLibraryMemberIterator<'data, R> {
+    type Item = Result<OmfLibraryMember<'data>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.len {
+            return None;
+        }
+        match parse_library_member(self.data, self.len, self.offset) {
+            Ok(Some(member)) => {
+                // The next member starts at the next page boundary.
+                let page_size = u64::from(self.page_size);
+                let end = member.offset + member.size;
+                self.offset = (end + page_size - 1) / page_size * page_size;
+                Some(Ok(member))
+            }
+            Ok(None) => {
+                self.offset = self.len;
+                None
+            }
+            Err(error) => {
+                self.offset = self.len;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+/// An object module in an [`OmfLibrary`].
+///
+/// This only records the location of the member. Use [`Self::data`] or
+/// [`Self::parse`] to read the member itself.
+#[derive(Debug, Clone, Copy)]
+pub struct OmfLibraryMember<'data> {
+    offset: u64,
+    size: u64,
+    name: &'data [u8],
+}
+
+impl<'data> OmfLibraryMember<'data> {
+    /// Return the file offset of the member.
+    #[inline]
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Return the size of the member, from its THEADR record to its MODEND record.
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Return the offset and size of the member in the file.
+    #[inline]
+    pub fn file_range(&self) -> (u64, u64) {
+        (self.offset, self.size)
+    }
+
+    /// Return the module name from the THEADR record.
+    ///
+    /// The name is in the code page of the translator, so it may not be UTF-8.
+    #[inline]
+    pub fn name_bytes(&self) -> &'data [u8] {
+        self.name
+    }
+
+    /// Return the module name from the THEADR record.
+    ///
+    /// Returns an error if the name is not UTF-8.
+    pub fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name).map_err(|_| Error("non UTF-8 OMF library member name"))
+    }
+
+    /// Return the member data, from its THEADR record to its MODEND record.
+    ///
+    /// `data` must be the entire library file data.
+    pub fn data<R: ReadRef<'data>>(&self, data: R) -> Result<&'data [u8]> {
+        data.read_bytes_at(self.offset, self.size)
+            .map_err(|_| Error("truncated OMF library member"))
+    }
+
+    /// Parse the member as an object module.
+    ///
+    /// `data` must be the entire library file data.
+    pub fn parse<R: ReadRef<'data>>(&self, data: R) -> Result<OmfFile<'data, &'data [u8]>> {
+        OmfFile::parse(self.data(data)?)
+    }
+}
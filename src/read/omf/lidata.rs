@@ -0,0 +1,86 @@
+//! Expansion of OMF LIDATA records.
+//!
+//! A LIDATA record describes initialized data as a sequence of data blocks.
+//! Each block has a repeat count and either contains the data bytes directly,
+//! or contains nested blocks. This is used for arrays and for large blocks of
+//! repeated values, such as the stack segment.
+
+use alloc::vec::Vec;
+
+use crate::read::{Error, Result};
+
+/// The maximum nesting depth of LIDATA blocks.
+const MAX_DEPTH: usize = 32;
+
+/// Expand the data blocks of a LIDATA record.
+///
+/// Returns an error if the expanded data would be larger than `limit`.
+pub(super) fn expand_lidata(raw: &[u8], is_32bit: bool, limit: u64) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut p = 0;
+    while p < raw.len() {
+        expand_block(raw, &mut p, is_32bit, 0, limit, &mut out)?;
+    }
+    Ok(out)
+}
+
+/// Expand a single data block, appending the expanded bytes to `out`.
+fn expand_block(
+    raw: &[u8],
+    p: &mut usize,
+    is_32bit: bool,
+    depth: usize,
+    limit: u64,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    if depth >= MAX_DEPTH {
+        return Err(Error("OMF LIDATA blocks are nested too deeply"));
+    }
+    let header_size = if is_32bit { 6 } else { 4 };
+    let header = raw
+        .get(*p..*p + header_size)
+        .ok_or(Error("truncated OMF LIDATA block"))?;
+    *p += header_size;
+    let (repeat, blocks) = if is_32bit {
+        (
+            u32::from_le_bytes([header[0], header[1], header[2], header[3]]),
+            u16::from_le_bytes([header[4], header[5]]),
+        )
+    } else {
+        (
+            u32::from(u16::from_le_bytes([header[0], header[1]])),
+            u16::from_le_bytes([header[2], header[3]]),
+        )
+    };
+
+    let start = out.len();
+    if blocks == 0 {
+        // The block contains the data bytes, preceded by their count.
+        let len = usize::from(*raw.get(*p).ok_or(Error("truncated OMF LIDATA block"))?);
+        let data = raw
+            .get(*p + 1..*p + 1 + len)
+            .ok_or(Error("truncated OMF LIDATA block"))?;
+        *p += 1 + len;
+        out.extend_from_slice(data);
+    } else {
+        for _ in 0..blocks {
+            expand_block(raw, p, is_32bit, depth + 1, limit, out)?;
+        }
+    }
+
+    let len = out.len() - start;
+    let total = (len as u64)
+        .checked_mul(repeat.into())
+        .and_then(|total| total.checked_add(start as u64))
+        .filter(|&total| total <= limit)
+        .ok_or(Error("OMF segment exceeds the size limit"))?;
+    if repeat == 0 {
+        out.truncate(start);
+    } else {
+        out.reserve(total as usize - out.len());
+        for _ in 1..repeat {
+            out.extend_from_within(start..start + len);
+        }
+    }
+    Ok(())
+}
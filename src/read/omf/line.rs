@@ -0,0 +1,152 @@
+//! LINNUM and LINSYM record parsing for OMF object files.
+//!
+//! LINNUM records map offsets within a segment to line numbers in the source
+//! file named by the preceding THEADR record. Microsoft tools only emit them
+//! when compiling with line number information (`/Zd`).
+//!
+//! LINSYM records do the same for code in COMDATs, which are identified by
+//! name rather than by segment index.
+
+use alloc::vec::Vec;
+
+use crate::read::{Error, ReadRef, Result, SectionIndex};
+
+use super::{read_index, OmfFile};
+
+/// A line number entry from a LINNUM or LINSYM record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OmfLineNumber<'data> {
+    /// The source file name, from the THEADR record preceding the line number record.
+    pub file: Option<&'data str>,
+    /// The source line number.
+    pub line: u16,
+    /// The section containing the code for the line.
+    ///
+    /// This is 0 for LINSYM entries that name a COMDAT that is not defined
+    /// by the module.
+    pub section: SectionIndex,
+    /// The offset of the code for the line within the section.
+    pub offset: u32,
+}
+
+/// Parse the entries of a LINNUM record.
+pub(super) fn parse_linnum<'data>(
+    body: &[u8],
+    is_32bit: bool,
+    file: Option<&'data str>,
+    lines: &mut Vec<OmfLineNumber<'data>>,
+) -> Result<()> {
+    let mut p = 0;
+    // The base group is only used for overlays.
+    read_index(body, &mut p)?;
+    let segment = read_index(body, &mut p)?;
+    parse_entries(body, p, is_32bit, file, SectionIndex(segment.into()), lines)
+}
+
+/// Parse the entries of a LINSYM record.
+///
+/// Returns the LNAMES index of the name of the COMDAT that the entries belong
+/// to. The section of the entries is 0 until the COMDAT section is known.
+pub(super) fn parse_linsym<'data>(
+    body: &[u8],
+    is_32bit: bool,
+    file: Option<&'data str>,
+    lines: &mut Vec<OmfLineNumber<'data>>,
+) -> Result<u16> {
+    // The flags only indicate whether this continues a previous LINSYM record.
+    let mut p = 1;
+    let name = read_index(body, &mut p)?;
+    parse_entries(body, p, is_32bit, file, SectionIndex(0), lines)?;
+    Ok(name)
+}
+
+/// Parse the line number and offset pairs of a LINNUM or LINSYM record.
+fn parse_entries<'data>(
+    body: &[u8],
+    mut p: usize,
+    is_32bit: bool,
+    file: Option<&'data str>,
+    section: SectionIndex,
+    lines: &mut Vec<OmfLineNumber<'data>>,
+) -> Result<()> {
+    let entry_size = if is_32bit { 6 } else { 4 };
+    while p < body.len() {
+        let entry = body
+            .get(p..p + entry_size)
+            .ok_or(Error("truncated OMF line number entry"))?;
+        p += entry_size;
+        let offset = if is_32bit {
+            u32::from_le_bytes([entry[2], entry[3], entry[4], entry[5]])
+        } else {
+            u32::from(u16::from_le_bytes([entry[2], entry[3]]))
+        };
+        lines.push(OmfLineNumber {
+            file,
+            line: u16::from_le_bytes([entry[0], entry[1]]),
+            section,
+            offset,
+        });
+    }
+    Ok(())
+}
+
+/// The line numbers for a single section, sorted by offset.
+#[derive(Debug, Clone, Default)]
+pub struct OmfLineTable<'data> {
+    lines: Vec<OmfLineNumber<'data>>,
+}
+
+impl<'data> OmfLineTable<'data> {
+    /// Get all line numbers in the table, sorted by offset.
+    #[inline]
+    pub fn lines(&self) -> &[OmfLineNumber<'data>] {
+        &self.lines
+    }
+
+    /// Get the line number entry containing the given section offset.
+    ///
+    /// This is the entry with the highest offset that is not greater than
+    /// `offset`.
+    pub fn get(&self, offset: u64) -> Option<&OmfLineNumber<'data>> {
+        let index = match self
+            .lines
+            .binary_search_by_key(&offset, |line| line.offset.into())
+        {
+            Ok(index) => index,
+            Err(index) => index.checked_sub(1)?,
+        };
+        self.lines.get(index)
+    }
+
+    /// Get the source file name and line number for the given section offset.
+    pub fn find(&self, offset: u64) -> Option<(Option<&'data str>, u16)> {
+        self.get(offset).map(|line| (line.file, line.line))
+    }
+}
+
+impl<'data, R: ReadRef<'data>> OmfFile<'data, R> {
+    /// Return the line number table for a section.
+    ///
+    /// The table is empty if the section has no line numbers.
+    pub fn line_table(&self, section: SectionIndex) -> OmfLineTable<'data> {
+        let mut lines = self
+            .line_numbers
+            .iter()
+            .filter(|line| line.section == section)
+            .copied()
+            .collect::<Vec<_>>();
+        // Keep the first entry if there are several at the same offset.
+        lines.sort_by_key(|line| line.offset);
+        lines.dedup_by_key(|line| line.offset);
+        OmfLineTable { lines }
+    }
+
+    /// Get the source file name and line number for an offset within a section.
+    pub fn find_line(
+        &self,
+        section: SectionIndex,
+        offset: u64,
+    ) -> Option<(Option<&'data str>, u16)> {
+        self.line_table(section).find(offset)
+    }
+}
@@ -0,0 +1,306 @@
+//! Linker maps for OMF object modules.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::read::{ReadRef, SectionIndex};
+
+use super::{OmfFile, OmfFixupTarget, OmfSegmentLayout, OmfStartAddress};
+
+/// A linear address, and the frame that it is addressed relative to.
+///
+/// This is displayed as `frame:offset`, with the frame as a paragraph number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OmfMapAddress {
+    /// The linear address of the frame.
+    ///
+    /// This is a multiple of 16, so that it can be written as a paragraph number.
+    pub frame: u64,
+    /// The linear address.
+    pub address: u64,
+}
+
+impl OmfMapAddress {
+    /// Return the offset of the address from the frame.
+    #[inline]
+    pub fn offset(&self) -> u64 {
+        self.address.wrapping_sub(self.frame)
+    }
+
+    fn add(self, offset: u64) -> Self {
+        OmfMapAddress {
+            frame: self.frame,
+            address: self.address.wrapping_add(offset),
+        }
+    }
+}
+
+impl fmt::Display for OmfMapAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04X}:{:04X}", self.frame >> 4, self.offset())
+    }
+}
+
+/// A segment contribution in an [`OmfLinkMap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OmfMapSegment<'data> {
+    /// The segment name.
+    pub name: &'data [u8],
+    /// The segment class name.
+    pub class: &'data [u8],
+    /// The linear address of the start of the segment.
+    pub start: u64,
+    /// The length of the segment.
+    pub length: u64,
+    /// The name of the module that defined the segment.
+    pub module: Option<&'data str>,
+}
+
+/// A group in an [`OmfLinkMap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OmfMapGroup<'data> {
+    /// The group name.
+    pub name: &'data [u8],
+    /// The linear address of the group frame.
+    pub frame: u64,
+}
+
+/// A public symbol in an [`OmfLinkMap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OmfMapPublic<'data> {
+    /// The symbol name.
+    pub name: &'data [u8],
+    /// The address of the symbol.
+    ///
+    /// The frame is the frame of the symbol's base group if it has one, or
+    /// else the frame of its segment. Absolute symbols use a frame of 0.
+    pub address: OmfMapAddress,
+    /// Whether the symbol is an absolute symbol.
+    pub absolute: bool,
+    /// The name of the module that defined the symbol.
+    pub module: Option<&'data str>,
+}
+
+/// The segments, groups and public symbols of linked OMF modules.
+///
+/// This contains the information that classic DOS linkers write to a `.MAP`
+/// file, and the [`Display`](fmt::Display) implementation writes it in
+/// that format.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OmfLinkMap<'data> {
+    /// The segment contributions, sorted by start address.
+    ///
+    /// Segments that have no base address in their module's layout are omitted.
+    pub segments: Vec<OmfMapSegment<'data>>,
+    /// The groups, sorted by frame.
+    ///
+    /// Groups with the same name in several modules are combined, using the
+    /// lowest frame.
+    pub groups: Vec<OmfMapGroup<'data>>,
+    /// The global symbols that are defined in a segment with a base address,
+    /// or are absolute, sorted by address and then by name.
+    pub publics: Vec<OmfMapPublic<'data>>,
+    /// The program start address from the first main module that has one.
+    pub entry: Option<OmfMapAddress>,
+}
+
+impl<'data> OmfLinkMap<'data> {
+    /// Construct a map for modules that have been laid out.
+    ///
+    /// Each module is paired with the layout of its segments. See
+    /// [`OmfFile::sequential_layout`] for a simple way to create a layout.
+    pub fn new<'file, R, I>(modules: I) -> Self
+    where
+        'data: 'file,
+        R: ReadRef<'data> + 'file,
+        I: IntoIterator<Item = (&'file OmfFile<'data, R>, &'file OmfSegmentLayout)>,
+    {
+        let modules = modules.into_iter().collect::<Vec<_>>();
+        let mut map = OmfLinkMap::default();
+        for &(file, layout) in &modules {
+            for (i, segment) in file.segments.iter().enumerate() {
+                if let Some(start) = layout.base(SectionIndex(i + 1)) {
+                    map.segments.push(OmfMapSegment {
+                        name: file.name(segment.name),
+                        class: file.name(segment.class),
+                        start,
+                        length: segment.length,
+                        module: file.module_name,
+                    });
+                }
+            }
+            for (i, group) in file.groups.iter().enumerate() {
+                let name = file.name(group.name);
+                let frame = match file.group_frame(layout, i as u16 + 1) {
+                    Some(frame) => frame,
+                    None => continue,
+                };
+                match map.groups.iter_mut().find(|group| group.name == name) {
+                    Some(group) => group.frame = group.frame.min(frame),
+                    None => map.groups.push(OmfMapGroup { name, frame }),
+                }
+            }
+            for symbol in &file.symbols {
+                if !symbol.global || symbol.is_common {
+                    continue;
+                }
+                let (address, absolute) = match symbol.segment {
+                    Some(SectionIndex(0)) => (
+                        OmfMapAddress {
+                            frame: 0,
+                            address: symbol.offset,
+                        },
+                        true,
+                    ),
+                    Some(segment) => match map_address(file, layout, segment, symbol.group) {
+                        Some(address) => (address.add(symbol.offset), false),
+                        None => continue,
+                    },
+                    None => continue,
+                };
+                map.publics.push(OmfMapPublic {
+                    name: symbol.name,
+                    address,
+                    absolute,
+                    module: file.module_name,
+                });
+            }
+        }
+        map.segments.sort_by_key(|segment| segment.start);
+        map.groups.sort_by_key(|group| group.frame);
+        map.publics
+            .sort_by(|a, b| (a.address.address, a.name).cmp(&(b.address.address, b.name)));
+
+        map.entry = modules.iter().find_map(|&(file, layout)| {
+            if !file.main_module {
+                return None;
+            }
+            match file.start_address? {
+                OmfStartAddress::Logical {
+                    target,
+                    displacement,
+                    ..
+                } => {
+                    let address = match target {
+                        OmfFixupTarget::Segment(segment) => {
+                            map_address(file, layout, SectionIndex(segment.into()), 0)?
+                        }
+                        OmfFixupTarget::Group(group) => {
+                            let frame = file.group_frame(layout, group)?;
+                            OmfMapAddress {
+                                frame,
+                                address: frame,
+                            }
+                        }
+                        OmfFixupTarget::Symbol(external) => {
+                            let symbol = file.external_symbol(external)?;
+                            let name = file.symbols.get(symbol.0)?.name;
+                            map.publics
+                                .iter()
+                                .find(|public| public.name == name)?
+                                .address
+                        }
+                    };
+                    Some(address.add(displacement.into()))
+                }
+                OmfStartAddress::Physical { frame, offset } => {
+                    let frame = u64::from(frame) << 4;
+                    Some(OmfMapAddress {
+                        frame,
+                        address: frame + u64::from(offset),
+                    })
+                }
+            }
+        });
+        map
+    }
+}
+
+impl<'data> fmt::Display for OmfLinkMap<'data> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, " Start  Stop   Length Name                   Class")?;
+        // Contributions to the same segment are combined.
+        let mut segments = self.segments.iter().peekable();
+        while let Some(segment) = segments.next() {
+            let mut end = segment.start + segment.length;
+            while let Some(next) = segments.peek() {
+                if next.name != segment.name || next.class != segment.class {
+                    break;
+                }
+                end = end.max(next.start + next.length);
+                segments.next();
+            }
+            let length = end - segment.start;
+            writeln!(
+                f,
+                " {:05X}H {:05X}H {:05X}H {:<22} {}",
+                segment.start,
+                end.saturating_sub(1).max(segment.start),
+                length,
+                Name(segment.name),
+                Name(segment.class),
+            )?;
+        }
+
+        if !self.groups.is_empty() {
+            writeln!(f)?;
+            writeln!(f, " Origin   Group")?;
+            for group in &self.groups {
+                writeln!(f, " {:04X}:0   {}", group.frame >> 4, Name(group.name))?;
+            }
+        }
+
+        let write_public = |f: &mut fmt::Formatter<'_>, public: &OmfMapPublic<'_>| {
+            let abs = if public.absolute { "Abs" } else { "   " };
+            writeln!(f, " {}  {}  {}", public.address, abs, Name(public.name))
+        };
+        writeln!(f)?;
+        writeln!(f, "  Address         Publics by Name")?;
+        writeln!(f)?;
+        let mut by_name = self.publics.iter().collect::<Vec<_>>();
+        by_name.sort_by_key(|public| public.name);
+        for public in by_name {
+            write_public(f, public)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "  Address         Publics by Value")?;
+        writeln!(f)?;
+        for public in &self.publics {
+            write_public(f, public)?;
+        }
+
+        if let Some(entry) = self.entry {
+            writeln!(f)?;
+            writeln!(f, "Program entry point at {}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+/// Return the address of an offset of 0 in a segment, using the frame of `group`
+/// if it is not 0, or else the frame of the segment.
+fn map_address<'data, R: ReadRef<'data>>(
+    file: &OmfFile<'data, R>,
+    layout: &OmfSegmentLayout,
+    segment: SectionIndex,
+    group: u16,
+) -> Option<OmfMapAddress> {
+    let address = file.linear_address(layout, segment, 0)?;
+    let frame = match group {
+        0 => address & !0xf,
+        group => file.group_frame(layout, group)?,
+    };
+    Some(OmfMapAddress { frame, address })
+}
+
+/// A name that is displayed lossily as UTF-8.
+struct Name<'data>(&'data [u8]);
+
+impl<'data> fmt::Display for Name<'data> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match core::str::from_utf8(self.0) {
+            Ok(name) => f.pad(name),
+            Err(_) => f.pad(&alloc::string::String::from_utf8_lossy(self.0)),
+        }
+    }
+}
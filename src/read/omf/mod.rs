@@ -1,184 +1,978 @@
 //! Intel OMF reader (supports 16-bit and 32-bit records)
+//!
+//! [`OmfFile`] implements the [`Object`](crate::read::Object) trait for
+//! OMF object modules as produced by Microsoft, Borland and Watcom tools.
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::str;
 
 mod consts;
+pub use consts::OmfRecordType;
+
+mod comment;
+pub use comment::{
+    OmfComment, OmfCommentKind, OmfFloatKind, OmfIncrementalDefinition, OmfInstructionSet,
+    OmfLinkerDirectives, OmfMemoryModel, OmfModelKind,
+};
+
 mod section;
+pub use section::*;
+
 mod symbol;
+pub use symbol::*;
+
 mod object;
+pub use object::*;
+
+mod layout;
+pub use layout::*;
+
+mod map;
+pub use map::*;
+
+mod image;
+pub use image::*;
+
+mod dump;
+pub use dump::*;
+
+mod fixupp;
 
-use consts::*;
-use section::{OmfSection, OmfRelocation};
-use symbol::OmfSymbol;
+mod cache;
+use cache::OmfCache;
 
+mod lidata;
+
+mod typdef;
+pub use typdef::*;
+
+mod import;
+pub use import::*;
+
+mod export;
+pub use export::*;
+
+mod codeview;
+pub use codeview::*;
+
+mod line;
+pub use line::*;
+
+mod library;
+pub use library::*;
+
+mod module;
+pub use module::*;
+
+mod modend;
+pub use modend::*;
+
+mod dictionary;
+pub use dictionary::*;
+
+use crate::omf;
 use crate::read::{
-    self, Architecture, Error, ObjectSection, ObjectSectionIndex, ObjectSymbol,
-    ObjectSymbolTable, ReadRef, RelocationKind, RelocationEncoding, Result, SectionFlags,
-    SectionIndex, SymbolFlags, SymbolIndex, SymbolKind, SymbolScope, SymbolSection,
+    Error, Map, ReadRef, Result, SectionIndex, SectionKind, SymbolIndex, SymbolKind,
 };
 
-use self::comment::{OmfComment, OmfCommentKind};
-
-use crate::read::omf::section::OmfSectionData;
+/// An index into the interned names in [`OmfFile::names`].
+///
+/// LNAMES records often repeat the same segment and class names, so each
+/// distinct name is stored once. Equal names have equal ids, so names can be
+/// compared by id.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OmfNameId(pub u16);
 
 /// Logical segment group defined via GRPDEF (e.g., DGROUP).
 /// Stores a group name and 1-based indices of associated segments.
 /// Used by some linkers to load multiple segments into the same register.
 #[derive(Debug)]
-struct OmfGroup<'data> {
-    name: &'data str,
-    segment_indices: Vec<u16>,
+pub struct OmfGroup {
+    /// The group name.
+    pub name: OmfNameId,
+    /// The 1-based indices of the segments in the group.
+    pub segment_indices: Vec<u16>,
 }
 
-/// A COMDAT or COMDEF (minimal fields for now).
-#[derive(Debug)]
+/// Initialized communal data defined by a COMDAT record.
+#[derive(Debug, Clone)]
 // === COMDAT: Common Data records for duplicate-linkable functions/data ===
-pub struct OmfComdat<'data> {
-    pub name: &'data str,
+pub struct OmfComdatDef<'data> {
+    /// The COMDAT name.
+    pub name: &'data [u8],
+    /// The flags of the first COMDAT record.
+    ///
+    /// 0x01 marks a continuation record, 0x02 iterated data, and 0x04 a local name.
+    pub flags: u8,
+    /// The attributes of the COMDAT.
+    ///
+    /// The high nibble is the selection criteria, and the low nibble is the
+    /// allocation type. An allocation type of 0 means that the COMDAT is
+    /// allocated in the segment given by `segment_index`.
     pub selection: u8,
-    pub segment_index: u8,
+    /// The alignment of the COMDAT, using the SEGDEF alignment encoding.
+    ///
+    /// This is 0 if the alignment of the segment is used.
+    pub align: u8,
+    /// The 1-based segment index the COMDAT is allocated in.
+    ///
+    /// This is 0 unless the COMDAT uses explicit allocation.
+    pub segment_index: u16,
+    /// The offset of the data within the COMDAT.
     pub offset: u32,
-    pub segment_name: Option<&'data str>,
+    /// The name of the segment the COMDAT is allocated in, if known.
+    pub segment_name: Option<&'data [u8]>,
+    /// The data of the first COMDAT record.
+    ///
+    /// This is `None` if the data is iterated. See also `records`.
     pub data: Option<&'data [u8]>,
+    /// All of the data records for the COMDAT, including continuation records,
+    /// in file order.
+    pub records: Vec<OmfSectionData<'data>>,
+    /// The index of the section exposing this COMDAT.
+    pub section: SectionIndex,
+    /// The index of the symbol naming this COMDAT.
+    pub symbol: usize,
+    /// The fixups applying to the COMDAT data.
+    pub fixups: OmfFixups,
 }
 
 /// Common (uninitialized) symbol defined by a COMDEF record.
 #[derive(Debug)]
 // === COMDEF: Common (uninitialized) data symbols, like BSS ===
 pub struct OmfCommon<'data> {
-    pub name:        &'data str,
-    pub elem_size:   u32,   // size of one element
-    pub elem_count:  u32,   // number of elements
-    pub is_far:      bool,  // far vs. near
-    pub is_32bit:    bool,  // width of size/count fields
-}
-
-/// Helper: Determines if a comment class type supports subtyped comments.
-fn is_known_subtyped_class(class: u8) -> bool {
-    matches!(class, 0x00 | 0x01 | 0x9A) // Microsoft, Borland, Watcom
+    /// The symbol name.
+    pub name: &'data [u8],
+    /// The size of one element.
+    pub elem_size: u64,
+    /// The number of elements.
+    ///
+    /// This is always 1 for near communals.
+    pub elem_count: u64,
+    /// Far vs. near.
+    pub is_far: bool,
+    /// The 1-based SEGDEF index to allocate the variable in.
+    ///
+    /// Borland tools use data types 0x01 to 0x5F for this instead of the
+    /// near or far data types.
+    pub segment: Option<u16>,
 }
 
 /// Parsed Intel OMF object file.
 #[derive(Debug)]
 // === Main object container for parsed OMF file data ===
-pub struct OmfFile<'data, R: ReadRef<'data>> {
+pub struct OmfFile<'data, R: ReadRef<'data> = &'data [u8]> {
+    /// The raw file data.
     pub data: R,
+    /// The module name from the THEADR record.
+    ///
+    /// This is `None` if the name is not UTF-8.
     pub module_name: Option<&'data str>,
-    pub lnames: Vec<&'data str>,
-    pub segments: Vec<OmfSegment<'data>>,
+    /// The module name from the THEADR record, which may not be UTF-8.
+    pub module_name_bytes: Option<&'data [u8]>,
+    /// The names from all THEADR records, in file order.
+    ///
+    /// There is usually one THEADR record per module, but some tools emit
+    /// another THEADR for each included source file.
+    pub modules: Vec<&'data str>,
+    /// The original module name from a LIBMOD comment.
+    ///
+    /// Librarians add this comment to modules stored in a library, since the
+    /// THEADR name may be a full path or differ from the original object name.
+    pub library_module: Option<&'data str>,
+    /// The offset of the first record after the link pass separator comment.
+    ///
+    /// Records from this offset are only needed by the second pass of a linker.
+    /// See [`OmfFile::parse_pass_one`].
+    pub pass_separator: Option<usize>,
+    /// Whether the module uses the PharLap Easy OMF-386 conventions.
+    ///
+    /// This is set by an Easy OMF-386 comment. In this dialect, SEGDEF,
+    /// PUBDEF, LEDATA, LIDATA and FIXUPP records use 32-bit fields without
+    /// using the 32-bit record types, and fixup location type 5 is a 32-bit
+    /// offset.
+    pub pharlap: bool,
+    /// The distinct names from LNAMES records, indexed by [`OmfNameId`].
+    ///
+    /// The first name is always the empty name, which is also used for
+    /// missing names.
+    pub names: Vec<&'data [u8]>,
+    /// The LNAMES table, in file order.
+    pub lnames: Vec<OmfNameId>,
+    /// The segments defined by SEGDEF records.
+    pub segments: Vec<OmfSegmentDef<'data>>,
+    /// The symbols defined or declared by this module.
     pub symbols: Vec<OmfSymbol<'data>>,
-    pub groups: Vec<OmfGroup<'data>>,
-    pub comdats: Vec<OmfComdat<'data>>,
+    /// The groups defined by GRPDEF records.
+    pub groups: Vec<OmfGroup>,
+    /// The COMDAT records.
+    pub comdats: Vec<OmfComdatDef<'data>>,
+    /// The COMDEF records.
     pub commons: Vec<OmfCommon<'data>>,
+    /// The types defined by TYPDEF records.
+    pub types: Vec<OmfType<'data>>,
+    /// The COMENT records.
     pub comments: Vec<OmfComment<'data>>,
+    /// The line numbers from LINNUM and LINSYM records, in file order.
+    pub line_numbers: Vec<OmfLineNumber<'data>>,
+    /// Whether the MODEND record marks this as the main module of a program.
+    pub main_module: bool,
+    /// The program start address from the MODEND record.
+    ///
+    /// See also [`Object::entry`](crate::read::Object::entry).
+    pub start_address: Option<OmfStartAddress>,
+    /// The symbols in the external index space, in index order.
+    ///
+    /// EXTDEF, LEXTDEF, CEXTDEF, COMDEF and LCOMDEF records share a single 1-based
+    /// index space that is referenced by fixups. See [`OmfFile::external_symbol`].
+    pub externals: Vec<SymbolIndex>,
+    /// Index of symbols by name, preferring definitions.
+    symbol_names: Map<&'data [u8], SymbolIndex>,
+    /// Whether a DOSSEG comment requests the standard DOS segment ordering.
+    ///
+    /// See [`OmfFile::dosseg_order`].
+    pub dosseg: bool,
+    /// The linker directives from a LNKDIR comment.
+    ///
+    /// If there are multiple LNKDIR comments, the last one is used.
+    pub linker_directives: Option<OmfLinkerDirectives>,
+    /// The settings from a memory model comment.
+    ///
+    /// If there are multiple memory model comments, the last one is used.
+    pub memory_model: Option<OmfMemoryModel>,
+    /// The records with an unrecognized type, in file order.
+    ///
+    /// This is only used if [`OmfParseOptions::allow_unknown_records`] is set.
+    pub unknown_records: Vec<OmfUnknownRecord>,
+    /// The recoverable problems found while parsing, in file order.
+    pub diagnostics: Vec<OmfDiagnostic>,
+    /// The options that the module was parsed with.
+    options: OmfParseOptions,
+    /// The derived views that have been computed so far.
+    cache: OmfCache<'data>,
 }
 
-/// Internal segment helper.
-#[derive(Debug)]
-struct OmfSegment<'data> {
-    pub name: &'data str,
-    pub data: &'data [u8],
-    pub flags: SectionFlags,
-    pub fixups: Vec<OmfRelocation>,
+/// A recoverable problem found while parsing an OMF module.
+///
+/// The parser continues after these problems, so the affected fields may be
+/// empty or incomplete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OmfDiagnostic {
+    /// The file offset of the record containing the problem.
+    pub offset: usize,
+    /// The kind of problem.
+    pub kind: OmfDiagnosticKind,
 }
 
-/// Enumerates common OMF COMMENT classes.
+/// The kind of an [`OmfDiagnostic`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum OmfCommentClass {
-    /// Microsoft-specific comment (class = 0x00).
-    Microsoft,
+#[non_exhaustive]
+pub enum OmfDiagnosticKind {
+    /// An LNAMES index was out of range. The name is treated as empty,
+    /// or the record is ignored if it is a LINSYM record.
+    InvalidNameIndex(usize),
+    /// A segment index was out of range. The record is ignored.
+    InvalidSegmentIndex(usize),
+    /// A record with an unrecognized type was skipped.
+    UnknownRecord(u8),
+    /// The checksum of a record was wrong.
+    ChecksumMismatch,
+    /// A record was shorter than its fields.
+    ///
+    /// The missing fields of SEGDEF and LEDATA records are treated as 0.
+    /// The rest of other records is ignored.
+    TruncatedRecord,
+    /// The payload of a comment was invalid. The comment is ignored.
+    InvalidComment,
+    /// A record extended past the end of the data. Parsing stopped at this record.
+    TruncatedFile,
+    /// The module name in a THEADR or LHEADR record was not valid UTF-8.
+    /// The name is only available as bytes.
+    NonUtf8ModuleName,
+    /// The start address in a MODEND record was invalid. The start address is ignored.
+    InvalidStartAddress,
+}
 
-    /// Borland-specific comment (class = 0x01).
-    Borland,
+/// A record with an unrecognized type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OmfUnknownRecord {
+    /// The record type.
+    pub kind: u8,
+    /// The file offset of the record.
+    pub offset: usize,
+    /// The record length, including the checksum but not the type and length fields.
+    pub length: usize,
+}
 
-    /// Watcom-specific comment (class = 0x9A).
-    Watcom,
+/// A segment defined by a SEGDEF record.
+#[derive(Debug)]
+pub struct OmfSegmentDef<'data> {
+    /// The segment name.
+    pub name: OmfNameId,
+    /// The segment class name (e.g. `CODE`, `DATA`).
+    pub class: OmfNameId,
+    /// The segment data.
+    ///
+    /// This is the last data record for the segment. See also `records`.
+    pub data: OmfSectionData<'data>,
+    /// All of the data records for the segment, in file order.
+    pub records: Vec<OmfSectionData<'data>>,
+    /// The segment length.
+    pub length: u64,
+    /// The segment alignment.
+    pub align: u64,
+    /// The linear address of an absolute segment.
+    pub absolute: Option<u64>,
+    /// The section kind derived from the class name.
+    pub kind: SectionKind,
+    /// The format of the debug information in the segment, if it is a debug segment.
+    pub debug_format: Option<OmfDebugFormat>,
+    /// The name from the THEADR record of the translation unit defining the segment.
+    pub source_module: Option<&'data str>,
+    /// The fixups applying to the segment data.
+    pub fixups: OmfFixups,
+}
 
-    /// Embedded DWARF debug info (e.g., class = 0x88).
+/// The format of the debug information in an OMF debug segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OmfDebugFormat {
+    /// Microsoft CodeView, in the `$$SYMBOLS` and `$$TYPES` segments.
+    CodeView,
+    /// DWARF, in `.debug_*` segments emitted by Open Watcom.
     Dwarf,
+    /// Borland debug information, in the `$$BSYMS`, `$$BTYPES`, `$$BNAMES`,
+    /// `$$BROWSE` and `$$BROWFILE` segments.
+    Borland,
+}
 
-    /// Compiler version info.
-    Version,
+/// Options for [`OmfFile::parse_with_options`].
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct OmfParseOptions {
+    /// Only parse the records before the link pass separator.
+    ///
+    /// See [`OmfFile::parse_pass_one`].
+    pub pass_one: bool,
+    /// Only parse the records needed for the symbol table.
+    ///
+    /// See [`OmfFile::parse_symbols`].
+    pub symbols_only: bool,
+    /// Verify the checksum byte at the end of each record.
+    ///
+    /// A checksum of 0 is not verified, since many tools do not compute it.
+    pub verify_checksums: bool,
+    /// Skip records with an unrecognized type instead of returning an error.
+    ///
+    /// The skipped records are listed in [`OmfFile::unknown_records`].
+    pub allow_unknown_records: bool,
+    /// Stop parsing at a record that extends past the end of the data instead of
+    /// returning an error.
+    ///
+    /// The truncated record is reported in [`OmfFile::diagnostics`].
+    pub allow_truncated: bool,
+    /// Limits on the resources used for the module.
+    pub limits: OmfLimits,
+}
 
-    /// Unknown or unclassified.
-    Unknown(u8),
+/// Limits on the resources used when parsing an OMF module.
+///
+/// These prevent a small crafted file from causing large allocations.
+/// Exceeding a limit is an error.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct OmfLimits {
+    /// The maximum number of records in the module.
+    ///
+    /// The default is 4 Mi, which is far more than the records in the largest
+    /// known objects, but bounds the time spent on a crafted file.
+    pub max_records: usize,
+    /// The maximum number of symbols in the module.
+    ///
+    /// The default is 1 Mi. A symbol needs as little as 3 bytes of an EXTDEF
+    /// record, so without a limit the symbol table can be much larger than
+    /// the file.
+    pub max_symbols: usize,
+    /// The maximum size of the contents of a segment after combining and
+    /// expanding its data records.
+    ///
+    /// This is checked by [`OmfFile::segment_contents`], since a data record
+    /// can place a few bytes at an offset of up to 4 GiB. The default is 256 MiB.
+    pub max_segment_size: u64,
 }
 
-/// Represents a parsed COMMENT record from an OMF object.
-#[derive(Debug)]
-pub struct OmfComment<'data> {
-    /// The comment class type (e.g., DWARF, copyright, version, etc.)
-    pub class: OmfCommentClass,
+impl Default for OmfLimits {
+    fn default() -> Self {
+        OmfLimits {
+            max_records: 1 << 22,
+            max_symbols: 1 << 20,
+            max_segment_size: 256 << 20,
+        }
+    }
+}
+
+/// The kind of an OMF module, as returned by [`OmfFile::module_kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OmfModuleKind {
+    /// A module that defines code or data.
+    Object,
+    /// A module that only contains IMPDEF and EXPDEF comments.
+    ///
+    /// These are the members of import libraries.
+    Import,
+}
 
-    /// Subclass byte if applicable.
-    pub subtype: Option<u8>,
+/// The data record that a FIXUPP record applies to.
+#[derive(Debug, Clone, Copy)]
+enum FixupData {
+    /// A LEDATA or LIDATA record for a segment, by 0-based segment index.
+    Segment(usize),
+    /// A COMDAT record, by 0-based COMDAT index.
+    Comdat(usize),
+}
+
+/// Read an OMF index field.
+///
+/// Indices below 0x80 are encoded in one byte. Larger indices are encoded in
+/// two bytes, with the high bit of the first byte set.
+fn read_index(data: &[u8], p: &mut usize) -> Result<u16> {
+    let first = *data.get(*p).ok_or(Error("truncated OMF index"))?;
+    *p += 1;
+    if first & 0x80 == 0 {
+        return Ok(first.into());
+    }
+    let second = *data.get(*p).ok_or(Error("truncated OMF index"))?;
+    *p += 1;
+    Ok(u16::from(first & 0x7F) << 8 | u16::from(second))
+}
+
+/// Parse a GRPDEF component descriptor.
+///
+/// Returns the segment index of a segment descriptor. The obsolete
+/// descriptors that older tools emit are skipped.
+fn parse_group_component(data: &[u8], p: &mut usize) -> Result<Option<u16>> {
+    let truncated = Error("truncated OMF GRPDEF component");
+    let kind = *data.get(*p).ok_or(truncated)?;
+    *p += 1;
+    let size = match kind {
+        // Segment index.
+        0xFF => return read_index(data, p).map(Some),
+        // External index.
+        0xFE => {
+            read_index(data, p)?;
+            0
+        }
+        // Segment, class and overlay name indices.
+        0xFD => {
+            for _ in 0..3 {
+                read_index(data, p)?;
+            }
+            0
+        }
+        // LTL data field, maximum group length and group length.
+        0xFB => 5,
+        // Frame number and offset of an absolute group.
+        0xFA => 3,
+        _ => return Err(Error("invalid OMF GRPDEF component")),
+    };
+    data.get(*p..*p + size).ok_or(truncated)?;
+    *p += size;
+    Ok(None)
+}
+
+/// The fields of a COMDAT record.
+struct ComdatHeader<'data> {
+    flags: u8,
+    attributes: u8,
+    align: u8,
+    offset: u32,
+    segment_index: u16,
+    name_index: u16,
+    data: &'data [u8],
+}
+
+/// Parse the fields of a COMDAT record.
+fn parse_comdat(body: &[u8], is_32bit: bool) -> Result<ComdatHeader<'_>> {
+    let truncated = Error("truncated OMF COMDAT record");
+    let header = body.get(..3).ok_or(truncated)?;
+    let (flags, attributes, align) = (header[0], header[1], header[2]);
+    let mut p = 3;
+    let offset_size = if is_32bit { 4 } else { 2 };
+    let offset = body.get(p..p + offset_size).ok_or(truncated)?;
+    let offset = if is_32bit {
+        u32::from_le_bytes([offset[0], offset[1], offset[2], offset[3]])
+    } else {
+        u32::from(u16::from_le_bytes([offset[0], offset[1]]))
+    };
+    p += offset_size;
+    // The type index is only used for debug information.
+    read_index(body, &mut p)?;
+    let mut segment_index = 0;
+    if attributes & omf::COMDAT_ALLOCATION_MASK == omf::COMDAT_EXPLICIT {
+        // The public base is the same as for PUBDEF: a group index, a segment
+        // index, and a frame number if the segment index is 0.
+        read_index(body, &mut p)?;
+        segment_index = read_index(body, &mut p)?;
+        if segment_index == 0 {
+            // Frame number.
+            p += 2;
+        }
+    }
+    let name_index = read_index(body, &mut p)?;
+    let data = body.get(p..).ok_or(truncated)?;
+    Ok(ComdatHeader {
+        flags,
+        attributes,
+        align,
+        offset,
+        segment_index,
+        name_index,
+        data,
+    })
+}
+
+/// Return the alignment in bytes for a SEGDEF or COMDAT alignment type.
+fn segment_align(alignment: u8) -> u64 {
+    match alignment {
+        2 => 2,
+        3 => 16,
+        4 => 256,
+        5 => 4,
+        6 => 4096,
+        _ => 1,
+    }
+}
+
+/// Return the section kind of a COMDAT, using its allocation type.
+fn comdat_kind(comdat: &OmfComdatDef<'_>, segments: &[OmfSegmentDef<'_>]) -> SectionKind {
+    match comdat.selection & omf::COMDAT_ALLOCATION_MASK {
+        omf::COMDAT_EXPLICIT => usize::from(comdat.segment_index)
+            .checked_sub(1)
+            .and_then(|index| segments.get(index))
+            .map_or(SectionKind::Unknown, |segment| segment.kind),
+        omf::COMDAT_CODE16 | omf::COMDAT_CODE32 => SectionKind::Text,
+        omf::COMDAT_DATA16 | omf::COMDAT_DATA32 => SectionKind::Data,
+        _ => SectionKind::Unknown,
+    }
+}
+
+/// Read a variable length OMF value, as used by COMDEF and TYPDEF records.
+///
+/// Values up to 0x80 are encoded in one byte. Larger values are encoded with
+/// a prefix byte of 0x81, 0x84 or 0x88, followed by a 2, 3 or 4 byte value.
+fn read_length(data: &[u8], p: &mut usize) -> Result<u64> {
+    let first = *data.get(*p).ok_or(Error("truncated OMF length"))?;
+    *p += 1;
+    let size = match first {
+        0..=0x80 => return Ok(first.into()),
+        0x81 => 2,
+        0x84 => 3,
+        0x88 => 4,
+        _ => return Err(Error("invalid OMF length prefix")),
+    };
+    let bytes = data
+        .get(*p..*p + size)
+        .ok_or(Error("truncated OMF length"))?;
+    *p += size;
+    Ok(bytes
+        .iter()
+        .rev()
+        .fold(0, |value, &byte| value << 8 | u64::from(byte)))
+}
+
+/// Look up a 1-based LNAMES index, where 0 means no name.
+///
+/// An index that is out of range is reported and treated as no name.
+fn lookup_name(
+    lnames: &[OmfNameId],
+    index: usize,
+    offset: usize,
+    diagnostics: &mut Vec<OmfDiagnostic>,
+) -> OmfNameId {
+    if index == 0 {
+        return OmfNameId(0);
+    }
+    match lnames.get(index - 1) {
+        Some(&name) => name,
+        None => {
+            diagnostics.push(OmfDiagnostic {
+                offset,
+                kind: OmfDiagnosticKind::InvalidNameIndex(index),
+            });
+            OmfNameId(0)
+        }
+    }
+}
+
+/// Parse a length-prefixed OMF name.
+///
+/// Names are in the code page of the translator, so they may not be UTF-8.
+fn parse_name(data: &[u8]) -> Result<&[u8]> {
+    let len = *data.first().ok_or(Error("missing OMF string length"))? as usize;
+    data.get(1..1 + len).ok_or(Error("truncated OMF string"))
+}
+
+/// Parse a length-prefixed OMF string.
+fn parse_string(data: &[u8]) -> Result<&str> {
+    str::from_utf8(parse_name(data)?).map_err(|_| Error("non UTF-8 OMF string"))
+}
+
+/// Skip the zero or 0x1A bytes used to pad modules, starting at `offset`.
+///
+/// Returns the offset of the first byte that is not padding, or `len`.
+pub(crate) fn skip_padding<'data, R: ReadRef<'data>>(data: R, mut offset: u64, len: u64) -> u64 {
+    while offset < len {
+        match data.read_bytes_at(offset, 1) {
+            Ok([0x00 | 0x1A]) => offset += 1,
+            _ => break,
+        }
+    }
+    offset
+}
 
-    /// The raw payload (excluding class/subclass/type bytes).
-    pub data: &'data [u8],
+/// Return true if there is a plausible THEADR or LHEADR record at `offset`.
+pub(crate) fn is_module_header<'data, R: ReadRef<'data>>(data: R, offset: u64) -> bool {
+    let header = match data.read_bytes_at(offset, 3) {
+        Ok(header) => header,
+        Err(()) => return false,
+    };
+    if !matches!(
+        OmfRecordType::from_u8(header[0]),
+        Some(OmfRecordType::Theadr | OmfRecordType::Lheadr)
+    ) {
+        return false;
+    }
+    // The body is the name length, the name, and the checksum.
+    let len = u16::from_le_bytes([header[1], header[2]]);
+    if !(2..=257).contains(&len) {
+        return false;
+    }
+    let body = match data.read_bytes_at(offset + 3, len.into()) {
+        Ok(body) => body,
+        Err(()) => return false,
+    };
+    if usize::from(body[0]) + 2 != body.len() {
+        return false;
+    }
+    let checksum = body[body.len() - 1];
+    checksum == 0
+        || header
+            .iter()
+            .chain(body)
+            .fold(0u8, |sum, &b| sum.wrapping_add(b))
+            == 0
+}
+
+/// Return true if a segment or class name is the name of a DWARF section.
+fn is_dwarf_name(name: &[u8]) -> bool {
+    name.starts_with(b".debug_")
+}
+
+/// Determine the debug format of a segment from its name and class name.
+///
+/// Open Watcom emits DWARF debug sections as segments named `.debug_*` with
+/// a class of `DWARF`. Borland uses the `DEBSYM` and `DEBTYP` classes for
+/// its own segments as well as for CodeView, so check the names first.
+fn segment_debug_format(name: &[u8], class: &[u8]) -> Option<OmfDebugFormat> {
+    if is_dwarf_name(name) || is_dwarf_name(class) || class.eq_ignore_ascii_case(b"DWARF") {
+        Some(OmfDebugFormat::Dwarf)
+    } else if matches!(
+        name,
+        b"$$BSYMS" | b"$$BTYPES" | b"$$BNAMES" | b"$$BROWSE" | b"$$BROWFILE"
+    ) {
+        Some(OmfDebugFormat::Borland)
+    } else if name == CODEVIEW_SYMBOLS_SEGMENT.as_bytes()
+        || name == CODEVIEW_TYPES_SEGMENT.as_bytes()
+        || class == b"DEBSYM"
+        || class == b"DEBTYP"
+    {
+        Some(OmfDebugFormat::CodeView)
+    } else {
+        None
+    }
+}
 
-    /// Raw bytes including class/subtype for unknown/unparsed cases.
-    pub raw: &'data [u8],
+/// Map a segment class name to a section kind.
+fn segment_kind(class: &[u8]) -> SectionKind {
+    let ends_with = |suffix: &[u8]| {
+        class.len() >= suffix.len()
+            && class[class.len() - suffix.len()..].eq_ignore_ascii_case(suffix)
+    };
+    if ends_with(b"CODE") {
+        SectionKind::Text
+    } else if ends_with(b"BSS") || ends_with(b"STACK") {
+        SectionKind::UninitializedData
+    } else if ends_with(b"CONST") {
+        SectionKind::ReadOnlyData
+    } else {
+        SectionKind::Data
+    }
 }
 
-impl From<u8> for OmfCommentKind {
-    fn from(v: u8) -> Self {
-        match v {
-            0x00 => Self::Translator,
-            0x88 => Self::MicrosoftVer,
-            0x99 => Self::BorlandVer,
-            0x9C => Self::WatcomVer,
-            other => Self::Unknown(other),
+/// Map the kind of a segment to the kind of the symbols defined in it.
+fn segment_symbol_kind(kind: SectionKind) -> SymbolKind {
+    match kind {
+        SectionKind::Text => SymbolKind::Text,
+        SectionKind::Data | SectionKind::ReadOnlyData | SectionKind::UninitializedData => {
+            SymbolKind::Data
         }
+        _ => SymbolKind::Unknown,
     }
 }
 
 // === Implementation block for OmfFile: parsing, section access, etc. ===
 impl<'data, R: ReadRef<'data>> OmfFile<'data, R> {
-    /// Quick sniff (0x80-0x9F record id range).
+    /// Check whether the data looks like an OMF object module.
+    ///
+    /// The first record must be a THEADR or LHEADR record containing a single
+    /// name, and its checksum must be valid if it is nonzero.
     pub fn peek(data: R) -> core::result::Result<(), ()> {
-        if let Some(&b) = data.read_bytes_at(0, 1).ok().and_then(|s| s.first()) {
-            return if (0x80..=0x9F).contains(&b) { Ok(()) } else { Err(()) };
+        if is_module_header(data, 0) {
+            Ok(())
+        } else {
+            Err(())
         }
-        Err(())
     }
 
     /// Full parse.
+    ///
+    /// Parsing stops at the first MODEND record. Use [`Self::parse_modules`]
+    /// for a stream of several modules.
     pub fn parse(data: R) -> Result<Self> {
-        let bytes = data.read_bytes_at(0, data.len() as u64)?.as_ref();
+        Self::parse_with_options(data, OmfParseOptions::default())
+    }
+
+    /// Parse the records before the link pass separator.
+    ///
+    /// Compilers emit a link pass separator comment after the records that
+    /// the first pass of a linker needs, such as symbol and segment
+    /// definitions. The data and fixup records that follow it are not parsed,
+    /// which is much faster for symbol-only scans of large modules.
+    ///
+    /// If there is no link pass separator then the whole module is parsed.
+    pub fn parse_pass_one(data: R) -> Result<Self> {
+        Self::parse_with_options(
+            data,
+            OmfParseOptions {
+                pass_one: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Parse only the records needed for the symbol table.
+    ///
+    /// LEDATA, LIDATA, FIXUPP, LINNUM and LINSYM records are skipped, which is
+    /// much faster for consumers that only need symbol names, such as a
+    /// librarian building a dictionary. Segment, group and COMDAT definitions
+    /// are still parsed, since symbols refer to them.
+    ///
+    /// Section data, relocations and line numbers are not available until
+    /// [`OmfFile::load_data`] is called.
+    pub fn parse_symbols(data: R) -> Result<Self> {
+        Self::parse_with_options(
+            data,
+            OmfParseOptions {
+                symbols_only: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Return true if the data records of the module have been parsed.
+    ///
+    /// This is false after [`OmfFile::parse_symbols`], until
+    /// [`OmfFile::load_data`] is called.
+    #[inline]
+    pub fn is_data_loaded(&self) -> bool {
+        !self.options.symbols_only
+    }
+
+    /// Parse the data records that were skipped by [`OmfFile::parse_symbols`].
+    ///
+    /// This does nothing if the data records have already been parsed.
+    pub fn load_data(&mut self) -> Result<()> {
+        if self.options.symbols_only {
+            let options = OmfParseOptions {
+                symbols_only: false,
+                ..self.options
+            };
+            *self = Self::parse_with_options(self.data, options)?;
+        }
+        Ok(())
+    }
+
+    /// Parse with the given options.
+    pub fn parse_with_options(data: R, options: OmfParseOptions) -> Result<Self> {
+        use OmfRecordType::*;
+
+        let size = data.len().map_err(|_| Error("unknown OMF file size"))?;
 
         let mut pos = 0;
+        let mut names: Vec<&'data [u8]> = vec![&[]];
+        let mut name_ids: Map<&'data [u8], OmfNameId> = Map::new();
+        name_ids.insert(&[], OmfNameId(0));
         let mut lnames = Vec::new();
-        let mut segments = Vec::new();
-        let mut symbols  = Vec::new();
-        let mut groups   = Vec::new();
-        let mut comdats  = Vec::new();
-        let mut commons  = Vec::new();
+        let mut segments: Vec<OmfSegmentDef<'data>> = Vec::new();
+        let mut symbols = Vec::new();
+        let mut groups = Vec::new();
+        let mut comdats: Vec<OmfComdatDef<'data>> = Vec::new();
+        let mut commons = Vec::new();
+        let mut types = Vec::new();
         let mut comments = Vec::new();
+        let mut line_numbers = Vec::new();
+        let mut comdat_lines = Vec::new();
+        let mut main_module = false;
+        let mut start_address = None;
         let mut module_name = None;
-
-        while pos + 3 <= bytes.len() {
-            let rec = bytes[pos];
-            let len = u16::from_le_bytes([bytes[pos + 1], bytes[pos + 2]]) as usize;
-            let body = &bytes[pos + 3 .. pos + 3 + len];
+        let mut module_name_bytes = None;
+        let mut modules = Vec::new();
+        let mut library_module = None;
+        let mut pass_separator = None;
+        let mut pharlap = false;
+        let mut dosseg = false;
+        let mut externals = Vec::new();
+        let mut threads = fixupp::ThreadState::default();
+        let mut fixup_data = None;
+        let mut weak_externals = Vec::new();
+        let mut linker_directives = None;
+        let mut memory_model = None;
+        let mut unknown_records = Vec::new();
+        let mut diagnostics = Vec::new();
+        let limits = options.limits;
+        let mut records = 0;
+
+        // Records are read individually, so that sources such as `ReadCache`
+        // do not need to read the whole file into a single buffer.
+        'records: while pos as u64 + 3 <= size {
+            records += 1;
+            if records > limits.max_records {
+                return Err(Error("too many OMF records"));
+            }
+            // Checked before each record, since symbol records may `continue`.
+            if symbols.len() > limits.max_symbols {
+                return Err(Error("too many OMF symbols"));
+            }
+            let record_offset = pos;
+            let header = data
+                .read_bytes_at(pos as u64, 3)
+                .map_err(|_| Error("OMF read failed"))?;
+            let rec = header[0];
+            let len = u16::from_le_bytes([header[1], header[2]]) as usize;
+            if len == 0 {
+                return Err(Error("invalid OMF record length"));
+            }
+            if size - pos as u64 - 3 < len as u64 {
+                if !options.allow_truncated {
+                    return Err(Error("truncated OMF record"));
+                }
+                diagnostics.push(OmfDiagnostic {
+                    offset: record_offset,
+                    kind: OmfDiagnosticKind::TruncatedFile,
+                });
+                break;
+            }
+            let record = data
+                .read_bytes_at(pos as u64, 3 + len as u64)
+                .map_err(|_| Error("OMF read failed"))?;
+            // The last byte of the record is the checksum, and is not part of the body.
+            let body = &record[3..2 + len];
+            let checksum = record[2 + len];
+            if checksum != 0 {
+                let sum = record.iter().fold(0u8, |sum, &b| sum.wrapping_add(b));
+                if sum != 0 {
+                    if options.verify_checksums {
+                        return Err(Error("invalid OMF record checksum"));
+                    }
+                    diagnostics.push(OmfDiagnostic {
+                        offset: record_offset,
+                        kind: OmfDiagnosticKind::ChecksumMismatch,
+                    });
+                }
+            }
             pos += 3 + len;
 
-            // Parse OMF record types: identify based on type byte (rec)
-            match rec {
+            // Get a field of the record. If the record is too short for the field,
+            // report it and ignore the rest of the record.
+            macro_rules! field {
+                ($e:expr) => {
+                    match $e {
+                        Ok(value) => value,
+                        Err(_) => {
+                            diagnostics.push(OmfDiagnostic {
+                                offset: record_offset,
+                                kind: OmfDiagnosticKind::TruncatedRecord,
+                            });
+                            continue 'records;
+                        }
+                    }
+                };
+            }
+
+            if options.symbols_only
+                && matches!(
+                    OmfRecordType::from_u8(rec),
+                    Some(
+                        Ledata
+                            | Ledata32
+                            | Lidata
+                            | Lidata32
+                            | Fixupp
+                            | Fixupp32
+                            | Linnum
+                            | Linnum32
+                            | Linsym
+                            | Linsym32
+                    )
+                )
+            {
+                continue;
+            }
 
+            // Parse OMF record types: identify based on type byte (rec)
+            match OmfRecordType::from_u8(rec) {
                 // THEADR (Translator Header): Marks the start of a new module or source file.
                 // Typically contains the original source file name, used mostly for diagnostics.
-                // Only one THEADR is expected per object file.
-                THEADR => {
-                    module_name = Some(read::parse_string(body)?);
+                // Only one THEADR is expected per object file. LHEADR is used instead
+                // by some library members.
+                Some(Theadr | Lheadr) => {
+                    let name = field!(parse_name(body));
+                    module_name_bytes = Some(name);
+                    match str::from_utf8(name) {
+                        Ok(name) => {
+                            module_name = Some(name);
+                            modules.push(name);
+                        }
+                        Err(_) => {
+                            module_name = None;
+                            diagnostics.push(OmfDiagnostic {
+                                offset: record_offset,
+                                kind: OmfDiagnosticKind::NonUtf8ModuleName,
+                            });
+                        }
+                    }
                 }
 
                 // LNAMES (Logical Names): String table for segment/class/group identifiers.
                 // These are 1-based indexes used in SEGDEF, GRPDEF, COMDAT, etc.
                 // Contents may include segment names like 'CODE', 'DATA', 'CONST'.
-                LNAMES => {
+                Some(Lnames) => {
                     let mut p = 0;
                     while p < body.len() {
-                        let s = read::parse_string(&body[p..])?;
-                        lnames.push(s);
+                        let s = field!(parse_name(&body[p..]));
                         p += 1 + s.len();
+                        let id = match name_ids.get(s) {
+                            Some(&id) => id,
+                            None => {
+                                let id = u16::try_from(names.len())
+                                    .map_err(|_| Error("too many OMF names"))?;
+                                names.push(s);
+                                name_ids.insert(s, OmfNameId(id));
+                                OmfNameId(id)
+                            }
+                        };
+                        lnames.push(id);
                     }
                 }
 
@@ -187,396 +981,614 @@ impl<'data, R: ReadRef<'data>> OmfFile<'data, R> {
                 // Paired with LEDATA records that supply the raw bytes.
                 // Segment index (1-based) is used by PUBDEF, COMDAT, FIXUPP, etc.
                 // SEGDEF32 adds support for 32-bit offsets and lengths.
-                SEGDEF | SEGDEF32 => {
-                    let attr = body[0];
-                    let is_code  = attr & 0x01 == 0;
-                    let is_32bit = rec & 1 == 1;
-
-                    let (seg_len, name_idx) = if is_32bit {
-                        let len = u32::from_le_bytes([body[1], body[2], body[3], body[4]]) as usize;
-                        (len, body[5] as usize)
+                Some(kind @ (Segdef | Segdef32)) => {
+                    // ACBP byte: alignment (bits 7-5), combination (bits 4-2),
+                    // big (bit 1), and use32 (bit 0).
+                    // A truncated record still defines a segment, so that the indices
+                    // of the following segments are unchanged. The missing fields are
+                    // treated as 0. The longest form has an absolute frame, a 32-bit
+                    // length, and 2 byte name and class indices.
+                    let mut fields = [0; 12];
+                    let n = body.len().min(fields.len());
+                    fields[..n].copy_from_slice(&body[..n]);
+                    let attr = fields[0];
+                    let is_32bit = kind.is_32bit() || pharlap;
+                    let mut p = 1;
+
+                    let alignment = attr >> 5;
+                    let absolute = if alignment == 0 {
+                        // Absolute segment: frame number and offset precede the length.
+                        let frame = &fields[p..p + 3];
+                        p += 3;
+                        Some(
+                            u64::from(u16::from_le_bytes([frame[0], frame[1]])) * 16
+                                + u64::from(frame[2]),
+                        )
+                    } else {
+                        None
+                    };
+                    let align = segment_align(alignment);
+
+                    let seg_len = if is_32bit {
+                        let len = &fields[p..p + 4];
+                        p += 4;
+                        u64::from(u32::from_le_bytes([len[0], len[1], len[2], len[3]]))
                     } else {
-                        let len = u16::from_le_bytes([body[1], body[2]]) as usize;
-                        (len, body[3] as usize)
+                        let len = &fields[p..p + 2];
+                        p += 2;
+                        u64::from(u16::from_le_bytes([len[0], len[1]]))
+                    };
+                    // The "big" bit means the segment is exactly 64K (or 4G) long.
+                    let length = if attr & 0x02 != 0 {
+                        if is_32bit {
+                            1 << 32
+                        } else {
+                            1 << 16
+                        }
+                    } else {
+                        seg_len
                     };
 
-                    let name = lnames.get(name_idx.saturating_sub(1)).copied().unwrap_or("");
-                    let flags = if is_code { SectionFlags::EXECUTABLE } else { SectionFlags::NONE };
-                    segments.push(OmfSegment { name, data: &[], flags, fixups: Vec::new() });
+                    let name_idx = usize::from(read_index(&fields, &mut p).unwrap_or(0));
+                    let class_idx = usize::from(read_index(&fields, &mut p).unwrap_or(0));
+                    if p > body.len() {
+                        diagnostics.push(OmfDiagnostic {
+                            offset: record_offset,
+                            kind: OmfDiagnosticKind::TruncatedRecord,
+                        });
+                    }
+
+                    let name = lookup_name(&lnames, name_idx, record_offset, &mut diagnostics);
+                    let class = lookup_name(&lnames, class_idx, record_offset, &mut diagnostics);
+                    let debug_format = segment_debug_format(
+                        names[usize::from(name.0)],
+                        names[usize::from(class.0)],
+                    );
+                    segments.push(OmfSegmentDef {
+                        name,
+                        class,
+                        data: OmfSectionData::Ledata {
+                            offset: 0,
+                            data: &[],
+                        },
+                        records: Vec::new(),
+                        length,
+                        align,
+                        absolute,
+                        kind: if debug_format.is_some() {
+                            SectionKind::Debug
+                        } else {
+                            segment_kind(names[usize::from(class.0)])
+                        },
+                        debug_format,
+                        source_module: module_name,
+                        fixups: OmfFixups::default(),
+                    });
 
                     // LEDATA will fill `data` later.
                 }
 
-                // PUBDEF: Defines a symbol (function, variable, etc.) that is visible to the linker.
-                // Each entry specifies a name, segment index, and offset. These are considered
-                // "defined" (global) symbols and should be emitted via the standard symbol iterator.
-                PUBDEF => {
+                // PUBDEF / LPUBDEF: Define symbols (functions, variables, etc.) by name,
+                // segment index, and offset. PUBDEF symbols are visible to the linker,
+                // while LPUBDEF symbols are local to the module (e.g. C `static`).
+                // The 32-bit variants use 32-bit offsets.
+                Some(kind @ (Pubdef | Pubdef32 | Lpubdef | Lpubdef32)) => {
+                    let is_32bit = kind.is_32bit() || pharlap;
+                    let global = matches!(kind, Pubdef | Pubdef32);
                     let mut p = 0;
-                    while p + 2 < body.len() {
-                        let name_idx = body[p] as usize; p += 1;
-                        let name = lnames.get(name_idx.saturating_sub(1)).copied().unwrap_or("");
+                    let group = field!(read_index(body, &mut p));
+                    let seg_idx = field!(read_index(body, &mut p));
+                    // A segment index of 0 is followed by a frame number, and the
+                    // offsets are relative to that frame.
+                    let frame = if seg_idx == 0 {
+                        let frame = field!(body
+                            .get(p..p + 2)
+                            .ok_or(Error("truncated OMF PUBDEF frame")));
+                        p += 2;
+                        Some(u64::from(u16::from_le_bytes([frame[0], frame[1]])) * 16)
+                    } else {
+                        None
+                    };
 
-                        let seg_idx = body[p]; p += 1;
-                        let offset = u16::from_le_bytes([body[p], body[p+1]]) as u64; p += 2;
+                    while p < body.len() {
+                        let name = field!(parse_name(&body[p..]));
+                        p += 1 + name.len();
+                        let offset = if is_32bit {
+                            let offset = field!(body
+                                .get(p..p + 4)
+                                .ok_or(Error("truncated OMF PUBDEF offset")));
+                            p += 4;
+                            u64::from(u32::from_le_bytes([
+                                offset[0], offset[1], offset[2], offset[3],
+                            ]))
+                        } else {
+                            let offset = field!(body
+                                .get(p..p + 2)
+                                .ok_or(Error("truncated OMF PUBDEF offset")));
+                            p += 2;
+                            u64::from(u16::from_le_bytes([offset[0], offset[1]]))
+                        };
+                        let type_index = field!(read_index(body, &mut p));
 
                         symbols.push(OmfSymbol {
+                            index: symbols.len(),
                             name,
-                            segment: Some(seg_idx),
-                            offset,
-                            global: true,
+                            segment: Some(SectionIndex(seg_idx.into())),
+                            offset: frame.map_or(offset, |frame| frame + offset),
+                            global,
                             is_comdat: false,
+                            size: 0,
+                            is_common: false,
+                            type_index,
+                            weak: false,
+                            lazy: false,
+                            kind: SymbolKind::Unknown,
+                            default_resolution: None,
+                            ordinal: None,
+                            group,
+                            source_module: module_name,
                         });
                     }
                 }
-                
-                // LPUBDEF: 32-bit version of PUBDEF, with larger offsets and segment indices.
-                // Defines global/public symbols, same as PUBDEF.
-                LPUBDEF => {
-                    let mut p = 0;
-                    while p + 4 < body.len() {
-                        let name_idx = body[p] as usize; p += 1;
-                        let name = lnames.get(name_idx.saturating_sub(1)).copied().unwrap_or("");
 
-                        let seg_idx = u16::from_le_bytes([body[p], body[p + 1]]); p += 2;
-                        let offset = u32::from_le_bytes([body[p], body[p + 1], body[p + 2], body[p + 3]]) as u64;
-                        p += 4;
+                // LINNUM: Maps offsets in a segment to line numbers in the source file
+                // named by the THEADR record. The 32-bit variant uses 32-bit offsets.
+                Some(kind @ (Linnum | Linnum32)) => {
+                    let is_32bit = kind.is_32bit() || pharlap;
+                    field!(line::parse_linnum(
+                        body,
+                        is_32bit,
+                        module_name,
+                        &mut line_numbers
+                    ));
+                }
 
-                        symbols.push(OmfSymbol {
-                            name,
-                            segment: Some(seg_idx as u8), // NOTE: OMF segment indices are typically u8, but LPUBDEF uses u16 — if more than 255 segments ever appear, we should update OmfSymbol to match.
-                            offset,
-                            global: true,
-                            is_comdat: false,
-                        });
-                    }
+                // LINSYM: Line numbers for code in a COMDAT, which is identified by name.
+                // The offsets are relative to the start of the COMDAT.
+                Some(kind @ (Linsym | Linsym32)) => {
+                    let start = line_numbers.len();
+                    let is_32bit = kind.is_32bit();
+                    let diagnostic =
+                        match line::parse_linsym(body, is_32bit, module_name, &mut line_numbers) {
+                            Ok(name_idx) => match usize::from(name_idx)
+                                .checked_sub(1)
+                                .and_then(|index| lnames.get(index))
+                            {
+                                Some(name) => {
+                                    comdat_lines.push((
+                                        names[usize::from(name.0)],
+                                        start..line_numbers.len(),
+                                    ));
+                                    continue;
+                                }
+                                None => OmfDiagnosticKind::InvalidNameIndex(name_idx.into()),
+                            },
+                            Err(_) => OmfDiagnosticKind::TruncatedRecord,
+                        };
+                    // The offsets are relative to the COMDAT, so the lines are unusable.
+                    line_numbers.truncate(start);
+                    diagnostics.push(OmfDiagnostic {
+                        offset: record_offset,
+                        kind: diagnostic,
+                    });
+                }
+
+                // TYPDEF: Describes the size of variables, referenced by type index from
+                // EXTDEF, PUBDEF and COMDEF records.
+                Some(Typdef) => {
+                    types.push(field!(typdef::parse_typdef(body)));
                 }
 
-                // EXTDEF: Declares a symbol imported from another object or library.
+                // EXTDEF / LEXTDEF: Declare symbols imported from another object or library.
                 // These are marked undefined in the final object symbol table.
-                EXTDEF | LEXTDEF => {
+                // LEXTDEF symbols may only be resolved by a LPUBDEF or LCOMDEF in the
+                // same module.
+                Some(kind @ (Extdef | Lextdef | Lextdef32)) => {
+                    let global = kind == Extdef;
                     let mut p = 0;
                     while p < body.len() {
-                        let name_idx = body[p] as usize; p += 1;
-                        let name = lnames.get(name_idx.saturating_sub(1)).copied().unwrap_or("");
+                        let name = field!(parse_name(&body[p..]));
+                        p += 1 + name.len();
+                        let type_index = field!(read_index(body, &mut p));
 
+                        externals.push(SymbolIndex(symbols.len()));
                         symbols.push(OmfSymbol {
+                            index: symbols.len(),
                             name,
                             segment: None,
                             offset: 0,
-                            global: true,
+                            global,
                             is_comdat: false,
+                            size: 0,
+                            is_common: false,
+                            type_index,
+                            weak: false,
+                            lazy: false,
+                            kind: SymbolKind::Unknown,
+                            default_resolution: None,
+                            ordinal: None,
+                            group: 0,
+                            source_module: module_name,
                         });
                     }
                 }
-                
-                // LEXTDEF: Extended EXTDEF used in 32-bit OMF files.
-                // Declares undefined external symbols, just like EXTDEF.
-                // Some toolchains (Watcom/Borland) include optional ordinal fields here.
-                LEXTDEF => {
-                    let mut p = 0;
-                    while p + 1 < body.len() {
-                        let name_idx = body[p] as usize; p += 1;
 
-                        // If there's an ordinal field (Watcom/Borland may add it), skip 2 bytes.
-                        let _maybe_ordinal = if p + 1 < body.len() {
-                            let ord = u16::from_le_bytes([body[p], body[p + 1]]);
-                            p += 2;
-                            Some(ord)
-                        } else {
-                            None
-                        };
+                // CEXTDEF: Declares external references to COMDATs.
+                // Unlike EXTDEF, each entry names the symbol by its LNAMES index.
+                // The entries share the external index space used by EXTDEF.
+                Some(Cextdef) => {
+                    let mut p = 0;
+                    while p < body.len() {
+                        let name_idx = usize::from(field!(read_index(body, &mut p)));
+                        // The type index is unused.
+                        field!(read_index(body, &mut p));
 
-                        let name = lnames.get(name_idx.saturating_sub(1)).copied().unwrap_or("");
+                        let name = lookup_name(&lnames, name_idx, record_offset, &mut diagnostics);
+                        let name = names[usize::from(name.0)];
 
+                        externals.push(SymbolIndex(symbols.len()));
                         symbols.push(OmfSymbol {
+                            index: symbols.len(),
                             name,
                             segment: None,
                             offset: 0,
                             global: true,
                             is_comdat: false,
+                            size: 0,
+                            is_common: false,
+                            type_index: 0,
+                            weak: false,
+                            lazy: false,
+                            kind: SymbolKind::Unknown,
+                            default_resolution: None,
+                            ordinal: None,
+                            group: 0,
+                            source_module: module_name,
                         });
                     }
                 }
 
                 // FIXUPP: Contains relocation (fixup) records that patch addresses at link time.
-                // Each entry specifies a location in LEDATA or COMDAT that must be adjusted.
+                // Each entry specifies a location in the preceding LEDATA, LIDATA or COMDAT
+                // record that must be adjusted.
                 // Fixups may refer to segments, groups, or external symbols.
-                // This parser currently does not resolve or apply these fixups — placeholder only.
-                FIXUPP => {
-                    
-                    // This implementation currently skips "thread" subrecords (subtype 0b10),
-                    // which are used to compress FIXUPP data by setting reusable frame/target values.
-                    // Only explicit fixup records are parsed for now.
-                    //
-                    // TODO: Add support for thread definitions (THREAD subrecords) when needed.
-                    
-                    // Parse only explicit segment-relative FIXUP subrecords.
-                    let mut p = 0;
-                    while p < body.len() {
-                        let typ = body[p]; p += 1;
-
-                        // If high bit = 0b10, this is a THREAD sub-record → skip (see comment above).
-                        if typ & 0x80 == 0 { continue; }   // thread, ignored
-
-                        // --- decode location ---
-                        let loc_size = match (typ >> 5) & 0b11 {
-                            0b00 => 8,   // 8-bit offset    (rare)
-                            0b01 => 16,  // 16-bit offset   (near)
-                            0b10 => 32,  // 32-bit offset   (far/32)
-                            _    => 16,
-                        };
-                        let loc_off = u16::from_le_bytes([body[p], body[p + 1]]) as u32;
-                        p += 2;
-
-                        // --- decode target ---
-                        let tgt = body[p]; p += 1;
-                        let target_seg = tgt; // segment index (1-based)
-
-                        // Skip disp/extra bytes if present (not used yet).
-                        if (typ & 0x04) != 0 { p += 1; } // 1-byte displacement
-
-                        // Attach relocation to most recent segment.
-                        if let Some(seg) = segments.last_mut() {
-                            seg.fixups.push(OmfRelocation {
-                                offset:   loc_off,
-                                target:   OmfFixupTarget::Segment(target_seg as u16),
-                                frame:    Some(OmfFixupFrame::Location), // Default to location-relative frame (FIXME: decode actual frame)
-                                kind:     RelocationKind::Absolute,
-                                encoding: RelocationEncoding::Generic,
-                                size:     loc_size as u8,
-                                addend:   0,
-                            });
-                        }
+                Some(kind @ (Fixupp | Fixupp32)) => {
+                    let fixups = field!(fixupp::parse_fixupp(
+                        body,
+                        kind.is_32bit(),
+                        pharlap,
+                        &mut threads
+                    ));
+                    let (fixup_data, data_offset) = match fixup_data {
+                        Some(fixup_data) => fixup_data,
+                        // Records with only THREAD subrecords may precede the data.
+                        None if fixups.is_empty() => continue,
+                        None => return Err(Error("OMF FIXUPP record without preceding data")),
+                    };
+                    let target = match fixup_data {
+                        FixupData::Segment(index) => segments.get_mut(index).map(|s| &mut s.fixups),
+                        FixupData::Comdat(index) => comdats.get_mut(index).map(|c| &mut c.fixups),
                     }
+                    .ok_or(Error("invalid OMF data record index"))?;
+                    target.extend(fixups, data_offset);
                 }
 
                 // GRPDEF: Group Definition — logical group of multiple SEGDEFs (e.g., DGROUP).
                 // Common in 16-bit OMF: allows far pointers or grouped data access.
                 // Groups are referenced in FIXUPP and other relocatable records.
-                // Currently decoded into group name + list of segment indexes.
-                GRPDEF => {
+                // Decoded into group name + list of segment indexes.
+                Some(Grpdef) => {
                     if body.is_empty() {
                         continue;
                     }
 
-                    let group_name_index = body[0] as usize;
+                    let mut i = 0;
+                    let group_name_index = usize::from(field!(read_index(body, &mut i)));
                     let mut segment_indices = Vec::new();
 
-                    let mut i = 1;
                     while i < body.len() {
-                        // OMF encodes each group entry as a pair: kind + index
-                        let kind = body[i]; i += 1;
-                        let index = body.get(i).copied().unwrap_or(0); i += 1;
-
-                        if kind == 0x02 {
-                            // 0x02 = segment index (1-based)
-                            segment_indices.push(index as u16);
-                        } else {
-                            // TODO: Support other kinds (0x01 = group, 0x03 = external symbol)
+                        match parse_group_component(body, &mut i) {
+                            Ok(Some(index)) => segment_indices.push(index),
+                            Ok(None) => {}
+                            Err(_) => {
+                                diagnostics.push(OmfDiagnostic {
+                                    offset: record_offset,
+                                    kind: OmfDiagnosticKind::TruncatedRecord,
+                                });
+                                break;
+                            }
                         }
                     }
 
-                    if let Some(name) = lnames.get(group_name_index) {
-                        groups.push(OmfGroup {
-                            name,
-                            segment_indices,
-                        });
-                    }
-
-                    // TODO: These groups are recorded but not yet used for fixup resolution.
+                    groups.push(OmfGroup {
+                        name: lookup_name(
+                            &lnames,
+                            group_name_index,
+                            record_offset,
+                            &mut diagnostics,
+                        ),
+                        segment_indices,
+                    });
                 }
 
-                // COMDEF: Common (BSS-style) uninitialized symbols. Size only.
-                COMDEF => {
-                    // COMDEF record format (16- & 32-bit):
-                    // [name_index] [type] [elem_size] [elem_count]
-                    //  1 byte       1     2/4         2/4
-                    // Type 0x00 = near, 0x02 = far. We ignore arrays-of-commons for now
-                    // beyond elem_count > 1.
+                // COMDEF / LCOMDEF: Common (BSS-style) uninitialized symbols. Size only.
+                // LCOMDEF symbols are local to the module.
+                Some(kind @ (Comdef | Lcomdef)) => {
+                    // Each entry is a name, a type index, a data type, and then
+                    // the communal length using the variable length encoding:
+                    // - 0x61 (FAR): element count, then element size.
+                    // - 0x62 (NEAR): size in bytes.
+                    // - 0x01..=0x5F (Borland): segment index, then size in bytes.
                     let mut p = 0;
-                    let name_idx = body[p] as usize; p += 1;
-                    let typ = body[p]; p += 1;
-                    let is_far = typ & 0x02 != 0;
-                    let is_32bit = (rec & 1) == 1;
-
-                    let read_u = |bytes: &[u8], off: &mut usize, w32: bool| -> u32 {
-                        if w32 {
-                            let v = u32::from_le_bytes([bytes[*off], bytes[*off+1], bytes[*off+2], bytes[*off+3]]);
-                            *off += 4; v
-                        } else {
-                            let v = u16::from_le_bytes([bytes[*off], bytes[*off+1]]) as u32;
-                            *off += 2; v
-                        }
-                    };
-
-                    let elem_size  = read_u(body, &mut p, is_32bit);
-                    let elem_count = read_u(body, &mut p, is_32bit);
-
-                    let name = lnames.get(name_idx.saturating_sub(1)).copied().unwrap_or("");
-                    commons.push(OmfCommon {
-                        name,
-                        elem_size,
-                        elem_count,
-                        is_far,
-                        is_32bit,
-                    });
+                    while p < body.len() {
+                        let name = field!(parse_name(&body[p..]));
+                        p += 1 + name.len();
+                        let type_index = field!(read_index(body, &mut p));
+                        let data_type = *field!(body.get(p).ok_or(Error("truncated OMF COMDEF")));
+                        p += 1;
+
+                        let (elem_count, elem_size, segment) = match data_type {
+                            0x61 => {
+                                let count = field!(read_length(body, &mut p));
+                                let size = field!(read_length(body, &mut p));
+                                (count, size, None)
+                            }
+                            0x62 => (1, field!(read_length(body, &mut p)), None),
+                            0x01..=0x5F => (
+                                1,
+                                field!(read_length(body, &mut p)),
+                                Some(u16::from(data_type)),
+                            ),
+                            _ => return Err(Error("unsupported OMF COMDEF data type")),
+                        };
+                        let size = elem_size
+                            .checked_mul(elem_count)
+                            .ok_or(Error("invalid OMF COMDEF size"))?;
 
-                    // NOTE: Borland & Watcom emit extended COMDEF variants (8087, flex-array)
-                    // which include additional alignment or class bytes. These are not yet parsed.
+                        externals.push(SymbolIndex(symbols.len()));
+                        symbols.push(OmfSymbol {
+                            index: symbols.len(),
+                            name,
+                            segment: None,
+                            offset: 0,
+                            global: kind == Comdef,
+                            is_comdat: false,
+                            size,
+                            is_common: true,
+                            type_index,
+                            weak: false,
+                            lazy: false,
+                            kind: SymbolKind::Unknown,
+                            default_resolution: None,
+                            ordinal: None,
+                            group: 0,
+                            source_module: module_name,
+                        });
+                        commons.push(OmfCommon {
+                            name,
+                            elem_size,
+                            elem_count,
+                            is_far: data_type == 0x61,
+                            segment,
+                        });
+                    }
                 }
 
                 // COMDAT: Defines a link-once section, usually function- or data-level granularity.
-                // Format includes type (code/data), linkage attributes, and data body.
-                // Also includes local fixups, which are handled separately.
-                // This is common in Watcom, Borland, and Microsoft OMFs for inlined functions.
-                //
-                // Note: Borland/Watcom may omit explicit SEGDEF for COMDATs and treat COMDAT as implicit segment.
-                //       We assume SEGDEF precedes and segment list is valid.
-                //       COMDATs may be mergeable; we record them all for now.
+                // The record header gives the selection criteria and how the COMDAT is
+                // allocated: either in an explicit segment, or in an implicit segment of
+                // the given type. The data follows the header, and may be enumerated or
+                // iterated. It is followed by FIXUPP records like LEDATA.
                 //
-                COMDAT => {
-                    // NOTE: Retain all COMDATs (selection logic deferred).
-                    // Some Borland/Watcom variants define segment implicitly inside COMDAT;
-                    // if segment_index doesn't map to an existing SEGDEF, we fallback.
-                    let is_32bit = (rec & 1) == 1;
-                    let mut p = 0;
-
-                    if body.len() < 3 {
-                        continue; // too short to be valid
-                    }
-
-                    let selection = body[p]; p += 1;
-                    let mut attr_or_name = body[p]; p += 1;
-
-                    let (attributes, name_idx) = if attr_or_name >= 0xF0 {
-                        // Likely a known attribute; use it
-                        let attributes = attr_or_name;
-                        let name_idx = body[p]; p += 1;
-                        (attributes, name_idx as usize)
-                    } else {
-                        // No attribute byte; fallback
-                        (0, attr_or_name as usize)
+                // Large COMDATs are split across several records, with the continuation
+                // flag set on all but the first.
+                Some(kind @ (Comdat | Comdat32)) => {
+                    let is_32bit = kind.is_32bit();
+                    let header = match parse_comdat(body, is_32bit) {
+                        Ok(header) => header,
+                        Err(_) => {
+                            diagnostics.push(OmfDiagnostic {
+                                offset: record_offset,
+                                kind: OmfDiagnosticKind::TruncatedRecord,
+                            });
+                            continue;
+                        }
                     };
-
-                    let name = lnames.get(name_idx.saturating_sub(1)).copied().unwrap_or("");
-
-                    let segment_index = body.get(p).copied().unwrap_or(0); p += 1;
-
-                    let offset = if is_32bit {
-                        u32::from_le_bytes([
-                            *body.get(p).unwrap_or(&0),
-                            *body.get(p + 1).unwrap_or(&0),
-                            *body.get(p + 2).unwrap_or(&0),
-                            *body.get(p + 3).unwrap_or(&0),
-                        ])
+                    let name = lookup_name(
+                        &lnames,
+                        header.name_index.into(),
+                        record_offset,
+                        &mut diagnostics,
+                    );
+                    let name = names[usize::from(name.0)];
+                    let offset = header.offset;
+                    let record = if header.flags & omf::COMDAT_ITERATED != 0 {
+                        OmfSectionData::Lidata {
+                            offset,
+                            raw: header.data,
+                            is_32bit,
+                        }
                     } else {
-                        u16::from_le_bytes([
-                            *body.get(p).unwrap_or(&0),
-                            *body.get(p + 1).unwrap_or(&0),
-                        ]) as u32
+                        OmfSectionData::Comdat {
+                            offset,
+                            data: header.data,
+                        }
                     };
-                    p += if is_32bit { 4 } else { 2 };
 
-                    let seg_idx = segment_index.saturating_sub(1) as usize;
+                    if header.flags & omf::COMDAT_CONTINUATION != 0 {
+                        if let Some(index) = comdats.iter().rposition(|c| c.name == name) {
+                            comdats[index].records.push(record);
+                            fixup_data = Some((FixupData::Comdat(index), offset));
+                            continue;
+                        }
+                    }
 
-                    let (segment_name, data) = if let Some(seg) = segments.get(seg_idx) {
-                        (Some(seg.name), Some(seg.data))
-                    } else {
-                        // TODO: Borland/Watcom-specific variation may embed data or attributes not currently parsed.
-                        // TODO: Borland/Watcom-style implicit data:
-                        // Some OMF toolchains (e.g., Watcom/Borland) may define a COMDAT with no SEGDEF/LEDATA,
-                        // embedding the section data directly inside the COMDAT record. These are effectively
-                        // self-contained 'mini-sections' that should be parsed and stored here.
-                        // For now, we detect this by absence of a matching SEGDEF and placeholder detection:
-                        let looks_like_inline_data = body.len() > p + 2; // crude check (TODO: refine format-based)
-                        if looks_like_inline_data {
-                            let data_body = &body[p..];
-
-                            // Create a synthetic segment and attach it
-                            segments.push(OmfSection {
-                                index: segments.len(),
-                                name,
-                                data: OmfSectionData::Comdat {
-                                    offset,
-                                    data: data_body,
-                                },
-                                flags: SectionFlags::None,
-                                relocs: vec![],
-                            });
+                    let segment_name = usize::from(header.segment_index)
+                        .checked_sub(1)
+                        .and_then(|index| segments.get(index))
+                        .map(|segment| names[usize::from(segment.name.0)]);
 
-                            (Some(name), Some(OmfSectionData::Comdat {
-                                offset,
-                                data: data_body,
-                            }))
-                        } else {
-                            (None, None)
-                        }
-                    };
+                    // Each COMDAT also defines a symbol of the same name. The section
+                    // index is assigned once all SEGDEF records have been seen.
+                    let symbol = symbols.len();
+                    symbols.push(OmfSymbol {
+                        index: symbol,
+                        name,
+                        segment: None,
+                        offset: 0,
+                        global: header.flags & omf::COMDAT_LOCAL == 0,
+                        is_comdat: true,
+                        size: 0,
+                        is_common: false,
+                        type_index: 0,
+                        weak: false,
+                        lazy: false,
+                        kind: SymbolKind::Unknown,
+                        default_resolution: None,
+                        ordinal: None,
+                        group: 0,
+                        source_module: module_name,
+                    });
 
-                    comdats.push(OmfComdat {
+                    fixup_data = Some((FixupData::Comdat(comdats.len()), offset));
+                    comdats.push(OmfComdatDef {
                         name,
-                        selection,
-                        segment_index,
+                        flags: header.flags,
+                        selection: header.attributes,
+                        align: header.align,
+                        segment_index: header.segment_index,
                         offset,
                         segment_name,
-                        data,
+                        data: match record {
+                            OmfSectionData::Comdat { data, .. } => Some(data),
+                            _ => None,
+                        },
+                        records: vec![record],
+                        section: SectionIndex(0),
+                        symbol,
+                        fixups: OmfFixups::default(),
                     });
                 }
-                
-                //
-                // Ignored / not yet needed
-                //
-                
-                // MODEND: Indicates the logical end of the object module.
-                // Usually appears once, possibly with entry point info. Currently unused.
-                MODEND => {}
-                
-                // MODEND32: 32-bit version of MODEND, typically for 386+ objects.
-                // Entry point info and termination marker. Ignored for now.
-                MODEND32 => {}
-                
+
+                // MODEND / MODEND32: Indicates the logical end of the object module.
+                // Usually appears once, possibly with the program start address.
+                // Anything after it, such as library padding or the next module in a
+                // stream, is not part of this module. Use `parse_modules` for streams,
+                // since the name, segment and external indices restart in each module.
+                Some(kind @ (Modend | Modend32)) => {
+                    match modend::parse_modend(body, kind.is_32bit() || pharlap) {
+                        Ok((main, start)) => {
+                            main_module = main;
+                            start_address = start;
+                        }
+                        Err(_) => diagnostics.push(OmfDiagnostic {
+                            offset: record_offset,
+                            kind: OmfDiagnosticKind::InvalidStartAddress,
+                        }),
+                    }
+                    break;
+                }
+
                 // COMENT: Comment records embed optional metadata, such as compiler version,
-                // copyright strings, or linker directives. This parser currently skips them.
-                COMENT => {
-                    if let Some(cmt) = comment::parse_comment(body) {
-                        comments.push(cmt);
+                // copyright strings, or linker directives.
+                Some(Coment) => {
+                    let comment = match comment::parse_comment(body) {
+                        Ok(comment) => comment,
+                        Err(_) => {
+                            diagnostics.push(OmfDiagnostic {
+                                offset: record_offset,
+                                kind: OmfDiagnosticKind::InvalidComment,
+                            });
+                            continue;
+                        }
+                    };
+                    comments.push(comment);
+                    match comment.kind {
+                        OmfCommentKind::DosSeg => dosseg = true,
+                        OmfCommentKind::EasyOmf => pharlap = true,
+                        OmfCommentKind::MemoryModel(model) => memory_model = Some(model),
+                        OmfCommentKind::LinkerDirectives(directives) => {
+                            linker_directives = Some(directives);
+                        }
+                        OmfCommentKind::LibraryModule(name) => library_module = Some(name),
+                        OmfCommentKind::LinkPassSeparator => {
+                            pass_separator = Some(pos);
+                            if options.pass_one {
+                                break;
+                            }
+                        }
+                        OmfCommentKind::WeakExternals | OmfCommentKind::LazyExternals => {
+                            // Pairs of weak external index and default resolution index.
+                            let lazy = comment.kind == OmfCommentKind::LazyExternals;
+                            let payload = comment.data;
+                            let mut p = 0;
+                            while p < payload.len() {
+                                let weak = field!(read_index(payload, &mut p));
+                                let default = field!(read_index(payload, &mut p));
+                                weak_externals.push((weak, default, lazy));
+                            }
+                        }
+                        _ => {}
                     }
                 }
-                
+
                 // BAKPAT and NBKPAT: Used for back-patching fixups, often in very old tools.
                 // Rarely encountered today. Skipped unless needed for legacy format support.
-                BAKPAT => {}
-                NBKPAT => {}
-                
-                // LIBHDR and LIBDIR: Records for import libraries or static archives.
+                Some(Bakpat | Bakpat32 | Nbkpat | Nbkpat32) => {}
+
+                // LIBHDR and LIBEND: Records for import libraries or static archives.
                 // They contain indexing metadata but not object code. Ignored here.
-                LIBHDR => {}
-                LIBDIR => {}
-                
-                // RIDATA: Repeated initialization data. Alternative to LEDATA.
-                // Describes blocks of data filled with repeating values. Not parsed yet.
-                RIDATA => {}
-
-                // LIDATA and LLIDATA: Iterated data blocks.
-                // Support compressed initialization of repeating structures.
-                // Skipped here but required for full fidelity.
-                LIDATA | LLIDATA => {
-                    if body.len() < 3 {
-                        continue;
-                    }
-                    let offset = u16::from_le_bytes([body[0], body[1]]) as u32;
-                    let raw = &body[2..];
+                Some(Libhdr | Libend) => {}
 
-                    if let Some(seg) = segments.last_mut() {
-                        seg.data = OmfSectionData::Lidata { offset, raw };
+                // LIDATA and LIDATA32: Iterated data blocks.
+                // Support compressed initialization of repeating structures.
+                // Stored unexpanded here, and expanded when the segment contents are assembled.
+                Some(kind @ (Lidata | Lidata32)) => {
+                    let is_32bit = kind.is_32bit() || pharlap;
+                    let mut p = 0;
+                    let segment_index = usize::from(field!(read_index(body, &mut p)));
+                    let offset_size = if is_32bit { 4 } else { 2 };
+                    let offset = match body.get(p..p + offset_size) {
+                        Some(offset) => offset,
+                        None => {
+                            diagnostics.push(OmfDiagnostic {
+                                offset: record_offset,
+                                kind: OmfDiagnosticKind::TruncatedRecord,
+                            });
+                            continue;
+                        }
+                    };
+                    let offset = if is_32bit {
+                        u32::from_le_bytes([offset[0], offset[1], offset[2], offset[3]])
+                    } else {
+                        u32::from(u16::from_le_bytes([offset[0], offset[1]]))
+                    };
+                    let raw = &body[p + offset_size..];
+
+                    match segment_index
+                        .checked_sub(1)
+                        .filter(|index| *index < segments.len())
+                    {
+                        Some(seg_idx) => {
+                            let seg = &mut segments[seg_idx];
+                            seg.data = OmfSectionData::Lidata {
+                                offset,
+                                raw,
+                                is_32bit,
+                            };
+                            seg.records.push(seg.data);
+                            fixup_data = Some((FixupData::Segment(seg_idx), offset));
+                        }
+                        None => diagnostics.push(OmfDiagnostic {
+                            offset: record_offset,
+                            kind: OmfDiagnosticKind::InvalidSegmentIndex(segment_index),
+                        }),
                     }
-
-                    // TODO: Implement recursive expansion of LIDATA when needed.
                 }
-                
-                // LEDATA / LLEDATA:
+
+                // LEDATA / LEDATA32:
                 // Defines raw initialized data contents for a previously declared segment.
-                // LEDATA is the standard form for 16-bit objects; LLEDATA is its 32-bit variant.
+                // LEDATA is the standard form for 16-bit objects; LEDATA32 is its 32-bit variant.
                 // These records contain a segment index (referring to a SEGDEF), an offset
                 // within that segment, and the actual byte data to emit.
                 //
@@ -591,64 +1603,126 @@ impl<'data, R: ReadRef<'data>> OmfFile<'data, R> {
                 //
                 // Watcom and Borland also emit LEDATA for most code/data blocks that are not
                 // marked COMDAT.
-                LEDATA | LLEDATA => {
-                    let is_32bit = rec == LLIDATA;
+                Some(kind @ (Ledata | Ledata32)) => {
+                    let is_32bit = kind.is_32bit() || pharlap;
                     let mut p = 0;
 
-                    let seg_idx = body.get(p).copied().unwrap_or(0).saturating_sub(1) as usize;
-                    p += 1;
-
+                    let segment_index = usize::from(field!(read_index(body, &mut p)));
+                    let offset_size = if is_32bit { 4 } else { 2 };
+                    let offset = match body.get(p..p + offset_size) {
+                        Some(offset) => offset,
+                        None => {
+                            diagnostics.push(OmfDiagnostic {
+                                offset: record_offset,
+                                kind: OmfDiagnosticKind::TruncatedRecord,
+                            });
+                            continue;
+                        }
+                    };
                     let offset = if is_32bit {
-                        u32::from_le_bytes([
-                            *body.get(p).unwrap_or(&0),
-                            *body.get(p + 1).unwrap_or(&0),
-                            *body.get(p + 2).unwrap_or(&0),
-                            *body.get(p + 3).unwrap_or(&0),
-                        ])
+                        u32::from_le_bytes([offset[0], offset[1], offset[2], offset[3]])
                     } else {
-                        u16::from_le_bytes([
-                            *body.get(p).unwrap_or(&0),
-                            *body.get(p + 1).unwrap_or(&0),
-                        ]) as u32
+                        u32::from(u16::from_le_bytes([offset[0], offset[1]]))
                     };
-                    p += if is_32bit { 4 } else { 2 };
-
-                    let data_body = &body[p..];
-
-                    if let Some(seg) = segments.get_mut(seg_idx) {
-                        seg.data = OmfSectionData::Lidata {
-                            offset,
-                            encoded: data_body,
-                        };
+                    let data_body = &body[p + offset_size..];
+
+                    match segment_index
+                        .checked_sub(1)
+                        .filter(|index| *index < segments.len())
+                    {
+                        Some(seg_idx) => {
+                            let seg = &mut segments[seg_idx];
+                            seg.data = OmfSectionData::Ledata {
+                                offset,
+                                data: data_body,
+                            };
+                            seg.records.push(seg.data);
+                            fixup_data = Some((FixupData::Segment(seg_idx), offset));
+                        }
+                        None => diagnostics.push(OmfDiagnostic {
+                            offset: record_offset,
+                            kind: OmfDiagnosticKind::InvalidSegmentIndex(segment_index),
+                        }),
                     }
                 }
-                
-                // LCOMDEF: Extended COMDEF record used for common (BSS-style) uninitialized symbols.
-                // Supports 32-bit or segmented addressing for large-model objects. Not yet implemented.
-                LCOMDEF => {}
-                
-                // LSEGDEF: Extended SEGDEF record used to define segments with 32-bit sizes and attributes.
-                // Equivalent to SEGDEF, but required for full 32-bit OMF support. Not yet implemented.
-                LSEGDEF => {}
-                
-                // LGRPDEF: Extended GRPDEF for 32-bit group addressing.
-                // Not implemented yet, pending FIXUPP compatibility.
-                LGRPDEF => {}
-                
-                // LIDRNAME, LIDRTYP, LIDRVAL: Linker incremental debugging support.
-                // These records carry symbolic debugging data (CV, DWARF, etc).
-                // Not part of core object parsing and skipped here.
-                LIDRNAME => {}
-                LIDRTYP => {}
-                LIDRVAL => {}
 
+                _ if options.allow_unknown_records => {
+                    unknown_records.push(OmfUnknownRecord {
+                        kind: rec,
+                        offset: record_offset,
+                        length: len,
+                    });
+                    diagnostics.push(OmfDiagnostic {
+                        offset: record_offset,
+                        kind: OmfDiagnosticKind::UnknownRecord(rec),
+                    });
+                }
                 _ => return Err(Error("unknown OMF record")),
             }
         }
 
-        Ok(Self {
+        // Determine symbol kinds from the class of the segment they are defined in.
+        for symbol in &mut symbols {
+            symbol.kind = if symbol.is_common {
+                SymbolKind::Data
+            } else {
+                match symbol.segment {
+                    Some(index) => index
+                        .0
+                        .checked_sub(1)
+                        .and_then(|index| segments.get(index))
+                        .map_or(SymbolKind::Unknown, |segment| {
+                            segment_symbol_kind(segment.kind)
+                        }),
+                    None => SymbolKind::Unknown,
+                }
+            };
+        }
+
+        // COMDAT sections follow the SEGDEF sections.
+        for (i, comdat) in comdats.iter_mut().enumerate() {
+            comdat.section = SectionIndex(segments.len() + 1 + i);
+            let symbol = &mut symbols[comdat.symbol];
+            symbol.segment = Some(comdat.section);
+            symbol.kind = segment_symbol_kind(comdat_kind(comdat, &segments));
+        }
+
+        // Assign LINSYM line numbers to the section of the COMDAT they name.
+        for (name, range) in comdat_lines {
+            if let Some(comdat) = comdats.iter().find(|comdat| comdat.name == name) {
+                for line in &mut line_numbers[range] {
+                    line.section = comdat.section;
+                }
+            }
+        }
+
+        if symbols.len() > limits.max_symbols {
+            return Err(Error("too many OMF symbols"));
+        }
+
+        // Index symbols by name. Definitions take precedence over external declarations.
+        let mut symbol_names: Map<&'data [u8], SymbolIndex> = Map::new();
+        for symbol in &symbols {
+            let index = SymbolIndex(symbol.index);
+            symbol_names
+                .entry(symbol.name)
+                .and_modify(|existing| {
+                    if symbols[existing.0].segment.is_none() && symbol.segment.is_some() {
+                        *existing = index;
+                    }
+                })
+                .or_insert(index);
+        }
+
+        let mut file = Self {
             data,
             module_name,
+            module_name_bytes,
+            modules,
+            library_module,
+            pass_separator,
+            pharlap,
+            names,
             lnames,
             segments,
             symbols,
@@ -656,134 +1730,198 @@ impl<'data, R: ReadRef<'data>> OmfFile<'data, R> {
             comdats,
             commons,
             comments,
-        })
-    }
-
-    /// Turn parsed segments into `OmfSection` iterators.
-    pub fn sections(&'data self) -> impl Iterator<Item = OmfSection<'data>> + '_ {
-        self.segments.iter().enumerate().map(|(idx, seg)| OmfSection {
-            index: idx,
-            name: seg.name,
-            data: seg.data,
-            flags: seg.flags,
-            relocs: seg.fixups.clone(),
-        })
-        // COMDAT sections: we expose all COMDAT records, even if duplicates exist.
-        // The `selection` field in each COMDAT record determines how linkers resolve duplicates:
-        //   0x00 = PickAny, 0x01 = PickSame, 0x02 = PickSameSize, 0x03 = NoDuplicates, etc.
-        // We do not enforce these selection rules in this parser — all COMDATs are returned.
-        // If future deduplication is needed, filtering based on `selection` can be added here.
-        for comdat in &self.comdats {
-            let name = comdat.name;
-            let data = comdat.data.unwrap_or(&[]);
-            let section = OmfSection {
-                name,
-                data,
-                relocs: Vec::new(),
-                flags: SectionFlags::COMDAT,
-            };
-            sections.push(section);
+            line_numbers,
+            main_module,
+            start_address,
+            types,
+            symbol_names,
+            dosseg,
+            externals,
+            linker_directives,
+            memory_model,
+            unknown_records,
+            diagnostics,
+            options,
+            cache: OmfCache::default(),
+        };
+
+        // Resolve WKEXT and LZEXT entries once all external definitions have been seen.
+        for (weak, default, lazy) in weak_externals {
+            let default = file.external_symbol(default);
+            let symbol = file
+                .external_symbol(weak)
+                .and_then(|index| file.symbols.get_mut(index.0));
+            if let Some(symbol) = symbol {
+                if lazy {
+                    symbol.lazy = true;
+                } else {
+                    symbol.weak = true;
+                }
+                symbol.default_resolution = default;
+            }
         }
-        // NOTE: If `data` is empty, it likely comes from a COMDAT that defines no segment
-        // or was emitted by a Borland/Watcom-style object. This still gets exposed for introspection.
-    }
-}
 
-/* ---------- Trait implementations ---------- */
+        // Attach IMPDEF ordinals to the externals they import.
+        let ordinals = file
+            .import_definitions()
+            .filter_map(|import| match import.entry {
+                OmfImportEntry::Ordinal(ordinal) => Some((import.internal_name, ordinal)),
+                OmfImportEntry::Name(_) => None,
+            })
+            .collect::<Vec<_>>();
+        for (name, ordinal) in ordinals {
+            if let Some(index) = file.symbol_names.get(name.as_bytes()).copied() {
+                let symbol = &mut file.symbols[index.0];
+                if symbol.segment.is_none() {
+                    symbol.ordinal = Some(ordinal);
+                }
+            }
+        }
 
-impl<'data, R: ReadRef<'data>> ObjectSymbolTable<'data> for OmfFile<'data, R> {
-    type Symbol = OmfSymbol<'data>;
+        // Use TYPDEF records to determine sizes that weren't given explicitly.
+        for i in 0..file.symbols.len() {
+            let symbol = &file.symbols[i];
+            if symbol.size == 0 && symbol.type_index != 0 {
+                if let Some(size) = file.type_size(symbol.type_index) {
+                    file.symbols[i].size = size;
+                }
+            }
+        }
 
-    fn symbols(&self) -> Box<dyn Iterator<Item = Self::Symbol> + '_> {
-        Box::new(self.symbols.iter().cloned())
+        Ok(file)
     }
-
-    fn symbol_by_index(&self, index: SymbolIndex) -> Result<Self::Symbol> {
-        self.symbols.get(index.0).cloned().ok_or(Error("invalid OMF symbol index"))
+    /// Return the contents of a segment, combined from all of its LEDATA and
+    /// LIDATA records.
+    ///
+    /// This also accepts the section index of a COMDAT, and combines the data
+    /// of the COMDAT and its continuation records.
+    ///
+    /// Bytes that are not initialized by any record are zero.
+    ///
+    /// The contents are borrowed if they were assembled by [`Self::precompute`],
+    /// and are assembled on each call otherwise.
+    ///
+    /// Returns an error if the contents would be larger than
+    /// [`OmfLimits::max_segment_size`].
+    pub fn segment_data(&self, index: SectionIndex) -> Result<Cow<'_, [u8]>> {
+        if !self.is_data_loaded() {
+            return Err(Error("OMF section data is not loaded"));
+        }
+        if let Some(contents) = self.cache.section(index) {
+            return Ok(Cow::Borrowed(contents));
+        }
+        let records = self
+            .section_records(index)
+            .ok_or(Error("invalid OMF segment index"))?;
+        self.assemble_segment(records).map(Cow::Owned)
     }
-}
-
-// --- Unimplemented record handlers ---
-/// Parse LIDATA: Iterated data (patterned uninitialized storage)
-/// Not yet implemented. Common in BSS-like space savings.
-fn parse_lidata(_body: &[u8]) {
-    // TODO: Parse repeat descriptors and recursively nested LIDATA
-}
-
-/// Parse LLIDATA: Extended iterated data with 32-bit offsets
-/// Rare. Not yet implemented.
-fn parse_llidata(_body: &[u8]) {
-    // TODO: Support 32-bit range iterated initializations
-}
 
-/// Parse LCOMDEF: Extended COMDEF supporting 32-bit or segmented layout
-/// Used in large model or segmented data. Placeholder only.
-fn parse_lcomdef(_body: &[u8]) {
-    // TODO: Implement parsing of extended common symbols
-}
-
-/// Parse LSEGDEF: Extended SEGDEF variant with larger fields
-/// Required for full 32-bit OMF object parsing.
-fn parse_lsegdef(_body: &[u8]) {
-    // TODO: Handle extended segment definitions (larger offsets)
-}
-
-/// Parse LGRPDEF: Extended group definition record
-/// Used in segmented models. Not yet implemented.
-fn parse_lgrpdef(_body: &[u8]) {
-    // TODO: Decode group associations
-}
-
-/// Parse LLEDATA: Large LEDATA variant with 32-bit addressing
-/// Used in OMF32 for data sections exceeding 64KB.
-fn parse_lleddata(_body: &[u8]) {
-    // TODO: Implement handling of extended LEDATA
-}
-
-/// Parse BAKPAT: Backpatch record for old linkers
-/// Rare. Not used in modern OMF workflows.
-fn parse_bakpat(_body: &[u8]) {
-    // TODO: Handle segment-relative backpatching (obsolete)
-}
-
-/// Parse NBKPAT: Named backpatch variant
-/// Rare extension of BAKPAT using symbol names.
-fn parse_nbkpat(_body: &[u8]) {
-    // TODO: Consider handling named patching
-}
+    /// Return the data records of a segment or COMDAT section.
+    pub(super) fn section_records(&self, index: SectionIndex) -> Option<&[OmfSectionData<'data>]> {
+        let position = index.0.checked_sub(1)?;
+        match self.segments.get(position) {
+            Some(segment) => Some(&segment.records),
+            None => self
+                .comdats
+                .get(position - self.segments.len())
+                .map(|comdat| &*comdat.records),
+        }
+    }
 
-/// Parse RIDATA: Possibly reserved or tool-specific record
-/// Currently ignored.
-fn parse_ridata(_body: &[u8]) {
-    // TODO: Investigate usage in Watcom or Borland output
-}
+    /// Return an owned copy of the contents of a segment.
+    ///
+    /// See [`Self::segment_data`].
+    pub fn segment_contents(&self, index: SectionIndex) -> Result<Vec<u8>> {
+        self.segment_data(index).map(Cow::into_owned)
+    }
 
-/// Parse LIDRNAME: Index record - symbol name
-/// Used for linker index/debug information.
-fn parse_lidrname(_body: &[u8]) {
-    // TODO: Handle index-based symbol mapping
-}
+    /// Combine the data records of a segment or COMDAT.
+    fn assemble_segment(&self, records: &[OmfSectionData<'data>]) -> Result<Vec<u8>> {
+        let limit = self.options.limits.max_segment_size;
+        let mut contents = Vec::new();
+        for record in records {
+            let (offset, expanded);
+            let data = match *record {
+                OmfSectionData::Ledata { offset: o, data }
+                | OmfSectionData::Comdat { offset: o, data } => {
+                    offset = o;
+                    data
+                }
+                OmfSectionData::Lidata {
+                    offset: o,
+                    raw,
+                    is_32bit,
+                } => {
+                    offset = o;
+                    let limit = limit.saturating_sub(offset.into());
+                    expanded = lidata::expand_lidata(raw, is_32bit, limit)?;
+                    &expanded
+                }
+            };
+            let start = offset as usize;
+            let end = start + data.len();
+            if end as u64 > limit {
+                return Err(Error("OMF segment exceeds the size limit"));
+            }
+            if contents.len() < end {
+                contents.resize(end, 0);
+            }
+            contents[start..end].copy_from_slice(data);
+        }
+        Ok(contents)
+    }
 
-/// Parse LIDRTYP: Index record - type tag
-/// Used for extended symbol typing.
-fn parse_lidrtyp(_body: &[u8]) {
-    // TODO: Possibly useful in DWARF-style index objects
-}
+    /// Return the bytes of an interned name.
+    ///
+    /// Returns an empty name if the id is out of range.
+    #[inline]
+    pub fn name(&self, id: OmfNameId) -> &'data [u8] {
+        self.names.get(usize::from(id.0)).copied().unwrap_or(&[])
+    }
 
-/// Parse LIDRVAL: Index record - symbol value
-/// Usually part of debug record grouping.
-fn parse_lidrval(_body: &[u8]) {
-    // TODO: Add support if .OBJ files rely on this
-}
+    /// Return the name of the section for a segment.
+    ///
+    /// This is the segment name, unless the segment is a DWARF debug segment
+    /// whose `.debug_*` name is given by its class instead.
+    pub fn section_name(&self, segment: &OmfSegmentDef<'data>) -> &'data [u8] {
+        let name = self.name(segment.name);
+        let class = self.name(segment.class);
+        if !is_dwarf_name(name) && is_dwarf_name(class) {
+            class
+        } else {
+            name
+        }
+    }
 
-/// Parse LIBHDR: Marks start of a static library file (.LIB)
-/// Used before LIBDIR records. No-op for object files.
-fn parse_libhdr(_body: &[u8]) {
-    // TODO: Parse LIB archive metadata (name, version)
-}
+    /// Return the kind of the module.
+    ///
+    /// A module is an import module if it has at least one IMPDEF or EXPDEF
+    /// comment, and no segments, COMDATs, communal variables or symbol
+    /// definitions.
+    pub fn module_kind(&self) -> OmfModuleKind {
+        let has_imports = self.comments.iter().any(|comment| {
+            matches!(
+                comment.kind,
+                OmfCommentKind::Import(_) | OmfCommentKind::Export(_)
+            )
+        });
+        if has_imports
+            && self.segments.is_empty()
+            && self.comdats.is_empty()
+            && self.commons.is_empty()
+            && self.symbols.iter().all(|symbol| symbol.segment.is_none())
+        {
+            OmfModuleKind::Import
+        } else {
+            OmfModuleKind::Object
+        }
+    }
 
-/// Parse LIBDIR: Table of contents for .LIB archive
-/// Required to resolve modules within libraries.
-fn parse_libdir(_body: &[u8]) {
-    // TODO: Build module/offset map for archive members
+    /// Return the symbol for a 1-based index in the external index space.
+    ///
+    /// This is the index used by fixups that target external symbols.
+    pub fn external_symbol(&self, index: u16) -> Option<SymbolIndex> {
+        let index = usize::from(index).checked_sub(1)?;
+        self.externals.get(index).copied()
+    }
 }
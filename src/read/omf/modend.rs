@@ -0,0 +1,102 @@
+//! MODEND record parsing for OMF object files.
+//!
+//! The MODEND record ends a module. For the main module of a program, it also
+//! gives the start address, either as a target and frame that are resolved
+//! like a fixup, or as a physical frame number and offset.
+
+use crate::read::{Error, Result};
+
+use super::fixupp::parse_frame;
+use super::{read_index, OmfFixupFrame, OmfFixupTarget};
+
+/// The program start address from a MODEND record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OmfStartAddress {
+    /// A start address that is resolved by the linker, like a fixup.
+    Logical {
+        /// The target that the displacement is relative to.
+        target: OmfFixupTarget,
+        /// The frame of the start address, if known.
+        frame: Option<OmfFixupFrame>,
+        /// The displacement from the target.
+        displacement: u32,
+    },
+    /// A start address given by a frame number and offset.
+    Physical {
+        /// The frame number, which is the segment address divided by 16.
+        frame: u16,
+        /// The offset from the frame.
+        offset: u16,
+    },
+}
+
+/// Parse the body of a MODEND record.
+///
+/// Returns whether the module is a main module, and the start address if any.
+pub(super) fn parse_modend(body: &[u8], is_32bit: bool) -> Result<(bool, Option<OmfStartAddress>)> {
+    let module_type = match body.first() {
+        Some(&module_type) => module_type,
+        // Some tools omit the module type.
+        None => return Ok((false, None)),
+    };
+    let main = module_type & 0x80 != 0;
+    if module_type & 0x40 == 0 {
+        return Ok((main, None));
+    }
+    let mut p = 1;
+    if module_type & 0x01 == 0 {
+        let bytes = body
+            .get(p..p + 4)
+            .ok_or(Error("truncated OMF MODEND start address"))?;
+        let start = OmfStartAddress::Physical {
+            frame: u16::from_le_bytes([bytes[0], bytes[1]]),
+            offset: u16::from_le_bytes([bytes[2], bytes[3]]),
+        };
+        return Ok((main, Some(start)));
+    }
+
+    // The end data has the same format as the fix data of a FIXUP subrecord,
+    // but threads can't be used.
+    let end_data = *body
+        .get(p)
+        .ok_or(Error("truncated OMF MODEND start address"))?;
+    p += 1;
+    if end_data & 0x88 != 0 {
+        return Err(Error("unsupported OMF MODEND thread"));
+    }
+    let (frame_method, frame_index) = parse_frame(body, &mut p, (end_data >> 4) & 0x07)?;
+    let frame = match frame_method {
+        0 => Some(OmfFixupFrame::Segment(frame_index)),
+        1 => Some(OmfFixupFrame::Group(frame_index)),
+        2 => Some(OmfFixupFrame::Symbol(frame_index)),
+        4 => Some(OmfFixupFrame::Location),
+        _ => Some(OmfFixupFrame::Target),
+    };
+    let target_index = read_index(body, &mut p)?;
+    let target = match end_data & 0x03 {
+        0 => OmfFixupTarget::Segment(target_index),
+        1 => OmfFixupTarget::Group(target_index),
+        2 => OmfFixupTarget::Symbol(target_index),
+        _ => return Err(Error("unsupported OMF MODEND target method")),
+    };
+    // The displacement is omitted if the P bit is set.
+    let displacement = if end_data & 0x04 != 0 {
+        0
+    } else if is_32bit {
+        let bytes = body
+            .get(p..p + 4)
+            .ok_or(Error("truncated OMF MODEND start address"))?;
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    } else {
+        let bytes = body
+            .get(p..p + 2)
+            .ok_or(Error("truncated OMF MODEND start address"))?;
+        u32::from(u16::from_le_bytes([bytes[0], bytes[1]]))
+    };
+    let start = OmfStartAddress::Logical {
+        target,
+        frame,
+        displacement,
+    };
+    Ok((main, Some(start)))
+}
@@ -0,0 +1,103 @@
+//! Splitting a stream of OMF object modules.
+//!
+//! Some tools write several object modules to a single file, one after the
+//! other. Each module starts with a THEADR or LHEADR record and ends with a
+//! MODEND record, and may be followed by zero or 0x1A padding bytes.
+
+use core::marker::PhantomData;
+
+use crate::read::{Error, ReadRef, Result};
+
+use super::{is_module_header, skip_padding, OmfFile, OmfParseOptions, OmfRecordType};
+
+impl<'data, R: ReadRef<'data>> OmfFile<'data, R> {
+    /// Return an iterator over the object modules in a stream of modules.
+    ///
+    /// The stream is split at each MODEND record, and each module is parsed
+    /// separately. File offsets in the parsed modules, such as the offsets of
+    /// diagnostics, are relative to the start of the module.
+    pub fn parse_modules(data: R) -> OmfModuleIterator<'data, R> {
+        Self::parse_modules_with_options(data, OmfParseOptions::default())
+    }
+
+    /// Return an iterator over the object modules in a stream of modules,
+    /// parsing each module with the given options.
+    pub fn parse_modules_with_options(
+        data: R,
+        options: OmfParseOptions,
+    ) -> OmfModuleIterator<'data, R> {
+        OmfModuleIterator {
+            data,
+            len: data.len().unwrap_or(0),
+            offset: 0,
+            options,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// An iterator over the object modules in a stream of OMF modules.
+///
+/// Returned by [`OmfFile::parse_modules`].
+#[derive(Debug)]
+pub struct OmfModuleIterator<'data, R: ReadRef<'data> = &'data [u8]> {
+    data: R,
+    len: u64,
+    offset: u64,
+    options: OmfParseOptions,
+    marker: PhantomData<&'data ()>,
+}
+
+impl<'data, R: ReadRef<'data>> OmfModuleIterator<'data, R> {
+    /// Return the offset and size of the module starting at the current offset.
+    ///
+    /// A module without a MODEND record extends to the end of the data.
+    fn next_range(&self) -> (u64, u64) {
+        let mut pos = self.offset;
+        loop {
+            let header = match self.data.read_bytes_at(pos, 3) {
+                Ok(header) => header,
+                Err(()) => {
+                    pos = self.len;
+                    break;
+                }
+            };
+            let length = u64::from(u16::from_le_bytes([header[1], header[2]]));
+            pos = (pos + 3 + length).min(self.len);
+            if matches!(
+                OmfRecordType::from_u8(header[0]),
+                Some(OmfRecordType::Modend | OmfRecordType::Modend32)
+            ) {
+                break;
+            }
+        }
+        (self.offset, pos - self.offset)
+    }
+}
+
+impl<'data, R: ReadRef<'data>> Iterator for OmfModuleIterator<'data, R> {
+    type Item = Result<OmfFile<'data, &'data [u8]>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset != 0 {
+            // Stop at anything after a MODEND that isn't padding or another module.
+            self.offset = skip_padding(self.data, self.offset, self.len);
+            if !is_module_header(self.data, self.offset) {
+                return None;
+            }
+        } else if self.len == 0 {
+            return None;
+        }
+        let (offset, size) = self.next_range();
+        let result = match self.data.read_bytes_at(offset, size) {
+            Ok(data) => OmfFile::parse_with_options(data, self.options),
+            Err(()) => Err(Error("truncated OMF module")),
+        };
+        self.offset = if result.is_ok() {
+            offset + size
+        } else {
+            self.len
+        };
+        Some(result)
+    }
+}
@@ -1,116 +1,579 @@
-//! Trait glue: expose parsed OMF data through the `object::read::ObjectFile` API.
+//! Trait glue: expose parsed OMF data through the `object::read::Object` API.
 
-use super::{OmfFile, OmfSection, OmfSymbol};
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::{fmt, str};
+
+use super::{
+    OmfComdatDef, OmfCommentKind, OmfFile, OmfFixupTarget, OmfImportEntry, OmfSection,
+    OmfSectionData, OmfSectionIterator, OmfSegment, OmfSegmentIterator, OmfSegmentLayout,
+    OmfStartAddress, OmfSymbol, OmfSymbolIterator, OmfSymbolTable,
+};
 use crate::read::{
-    Architecture, Error, ObjectFile, ObjectSection, ObjectSectionIndex, ObjectSymbol,
-    ObjectSymbolTable, SectionIndex, SymbolIndex,
+    self, Architecture, ByteString, ComdatKind, Error, Export, FileFlags, Import,
+    NoDynamicRelocationIterator, Object, ObjectComdat, ObjectKind, ObjectMap, ObjectMapEntry,
+    ObjectMapFile, ObjectSection, ObjectSymbol, ReadRef, Result, SectionIndex, SymbolIndex,
+    SymbolMap, SymbolMapName,
 };
 
-impl<'data, R: crate::read::ReadRef<'data>> ObjectFile<'data> for OmfFile<'data, R> {
-    type Section = OmfSection<'data>;
-    type Symbol  = OmfSymbol<'data>;
+impl<'data, R: ReadRef<'data>> read::private::Sealed for OmfFile<'data, R> {}
+
+impl<'data, R: ReadRef<'data>> Object<'data> for OmfFile<'data, R> {
+    type Segment<'file>
+        = OmfSegment<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SegmentIterator<'file>
+        = OmfSegmentIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Section<'file>
+        = OmfSection<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SectionIterator<'file>
+        = OmfSectionIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Comdat<'file>
+        = OmfComdat<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type ComdatIterator<'file>
+        = OmfComdatIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Symbol<'file>
+        = OmfSymbol<'data>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolIterator<'file>
+        = OmfSymbolIterator<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolTable<'file>
+        = OmfSymbolTable<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type DynamicRelocationIterator<'file>
+        = NoDynamicRelocationIterator
+    where
+        Self: 'file,
+        'data: 'file;
 
     fn architecture(&self) -> Architecture {
         // Intel OMF is almost always 16/32-bit x86. Adjust if you add other CPUs.
         Architecture::I386
     }
 
-    fn sections(&'data self) -> Box<dyn Iterator<Item = Self::Section> + 'data> {
-        Box::new(self.sections())
+    #[inline]
+    fn is_little_endian(&self) -> bool {
+        true // OMF files are always little-endian
+    }
+
+    #[inline]
+    fn is_64(&self) -> bool {
+        false
+    }
+
+    fn kind(&self) -> ObjectKind {
+        ObjectKind::Relocatable
+    }
+
+    fn segments(&self) -> Self::SegmentIterator<'_> {
+        OmfSegmentIterator {
+            sections: self.sections(),
+        }
+    }
+
+    fn section_by_name_bytes<'file>(
+        &'file self,
+        section_name: &[u8],
+    ) -> Option<OmfSection<'data, 'file, R>> {
+        self.sections()
+            .find(|section| section.name_bytes() == Ok(section_name))
+    }
+
+    fn section_by_index(&self, index: SectionIndex) -> Result<OmfSection<'data, '_, R>> {
+        self.sections()
+            .find(|section| section.index() == index)
+            .ok_or(Error("invalid OMF section index"))
+    }
+
+    /// Return an iterator over sections in the OMF file.
+    ///
+    /// SEGDEF segments come first, using their 1-based segment index,
+    /// followed by one section for each COMDAT record.
+    fn sections(&self) -> Self::SectionIterator<'_> {
+        OmfSectionIterator {
+            file: self,
+            index: 0,
+        }
+    }
+
+    fn comdats(&self) -> Self::ComdatIterator<'_> {
+        OmfComdatIterator {
+            file: self,
+            iter: self.comdats.iter(),
+        }
+    }
+
+    fn symbol_table(&self) -> Option<OmfSymbolTable<'data, '_>> {
+        Some(OmfSymbolTable {
+            symbols: &self.symbols,
+        })
+    }
+
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<OmfSymbol<'data>> {
+        self.symbols
+            .get(index.0)
+            .cloned()
+            .ok_or(Error("invalid OMF symbol index"))
+    }
+
+    fn symbol_by_name_bytes<'file>(&'file self, symbol_name: &[u8]) -> Option<OmfSymbol<'data>> {
+        let index = self.symbol_names.get(symbol_name)?;
+        self.symbols.get(index.0).cloned()
+    }
+
+    /// Return a map of the symbols defined in segments and COMDATs.
+    ///
+    /// The addresses are relative to the start of the containing section.
+    /// Since every section starts at address 0, use
+    /// [`OmfFile::section_symbol_map`] to look up addresses within a single section.
+    ///
+    /// This copies the map built by [`OmfFile::precompute`] if there is one.
+    fn symbol_map(&self) -> SymbolMap<SymbolMapName<'data>> {
+        match self.cache.symbol_map() {
+            Some(map) => map.clone(),
+            None => self.build_symbol_map(|_| true),
+        }
+    }
+
+    fn symbols(&self) -> Self::SymbolIterator<'_> {
+        OmfSymbolIterator {
+            iter: self.symbols.iter(),
+        }
+    }
+
+    #[inline]
+    fn dynamic_symbol_table(&self) -> Option<OmfSymbolTable<'data, '_>> {
+        None
+    }
+
+    fn dynamic_symbols(&self) -> Self::SymbolIterator<'_> {
+        OmfSymbolIterator { iter: [].iter() }
+    }
+
+    #[inline]
+    fn dynamic_relocations(&self) -> Option<NoDynamicRelocationIterator> {
+        None
     }
 
-    fn section_by_index(&'data self, index: SectionIndex) -> Result<Self::Section, Error> {
-        self.sections().nth(index.0).ok_or(Error("invalid section index"))
+    /// Construct a map from addresses to symbol names and source file names.
+    ///
+    /// The segments are laid out with [`OmfFile::sequential_layout`] starting
+    /// at address 0, and COMDAT sections are placed after the last segment.
+    /// Use [`OmfFile::object_map_with_layout`] to choose the addresses.
+    fn object_map(&self) -> ObjectMap<'data> {
+        let mut layout = self.sequential_layout(0);
+        let mut next = self
+            .segments
+            .iter()
+            .enumerate()
+            .filter(|(_, segment)| segment.absolute.is_none())
+            .filter_map(|(i, segment)| {
+                layout
+                    .base(SectionIndex(i + 1))
+                    .map(|base| base.wrapping_add(segment.length))
+            })
+            .max()
+            .unwrap_or(0);
+        for (i, comdat) in self.comdats.iter().enumerate() {
+            layout.set_base(comdat.section, next);
+            let size = self
+                .section_at(self.segments.len() + i)
+                .map_or(0, |s| s.size);
+            next = next.wrapping_add(size);
+        }
+        self.object_map_with_layout(&layout)
     }
 
-    fn symbol_table(&'data self) -> Option<&dyn ObjectSymbolTable<'data, Symbol = Self::Symbol>> {
-        Some(self)
+    fn imports(&self) -> Result<Vec<Import<'data>>> {
+        // Like PE, imports by ordinal are omitted because they have no name.
+        let imports = self
+            .import_definitions()
+            .filter_map(|import| match import.entry {
+                OmfImportEntry::Name(name) => Some(Import {
+                    library: ByteString(import.module.as_bytes()),
+                    name: ByteString(name.as_bytes()),
+                }),
+                OmfImportEntry::Ordinal(_) => None,
+            })
+            .collect();
+        Ok(imports)
+    }
+
+    fn exports(&self) -> Result<Vec<Export<'data>>> {
+        // Exports are only returned if the module defines the internal symbol,
+        // since otherwise there is no address.
+        let exports = self
+            .export_definitions()
+            .filter_map(|export| {
+                let symbol = self.symbol_by_name(export.internal_name)?;
+                if !symbol.is_definition() {
+                    return None;
+                }
+                Some(Export {
+                    name: ByteString(export.exported_name.as_bytes()),
+                    address: symbol.address(),
+                })
+            })
+            .collect();
+        Ok(exports)
+    }
+
+    /// Return true if the module has DWARF, CodeView or Borland debug information.
+    fn has_debug_symbols(&self) -> bool {
+        let segments = self.segments.iter().any(|segment| {
+            let name = self.section_name(segment);
+            name == b".debug_info"
+                || name == super::CODEVIEW_SYMBOLS_SEGMENT.as_bytes()
+                || name == b"$$BSYMS"
+        });
+        segments
+            || self
+                .comments
+                .iter()
+                .any(|comment| comment.kind == OmfCommentKind::BorlandDebug)
+    }
+
+    fn relative_address_base(&self) -> u64 {
+        0
+    }
+
+    /// Return the start address from the MODEND record, or 0 if there is none.
+    ///
+    /// Sections have an address of 0, so a start address relative to a
+    /// segment or group is its offset within that segment or group. A start
+    /// address relative to an external symbol uses the offset of the symbol's
+    /// definition, if it is defined by this module.
+    fn entry(&self) -> u64 {
+        match self.start_address {
+            Some(OmfStartAddress::Logical {
+                target,
+                displacement,
+                ..
+            }) => {
+                let base = match target {
+                    OmfFixupTarget::Symbol(index) => self
+                        .external_symbol(index)
+                        .and_then(|index| self.symbols.get(index.0))
+                        .and_then(|symbol| self.symbol_names.get(symbol.name))
+                        .map_or(0, |index| self.symbols[index.0].offset),
+                    OmfFixupTarget::Segment(_) | OmfFixupTarget::Group(_) => 0,
+                };
+                base.wrapping_add(displacement.into())
+            }
+            Some(OmfStartAddress::Physical { frame, offset }) => {
+                u64::from(frame) * 16 + u64::from(offset)
+            }
+            None => 0,
+        }
+    }
+
+    #[inline]
+    fn flags(&self) -> FileFlags {
+        FileFlags::None
     }
 }
 
-// --- Section and Symbol Iterators ---
+impl<'data, R: ReadRef<'data>> OmfFile<'data, R> {
+    /// Return an iterator over the undefined symbols.
+    ///
+    /// These are the symbols declared by EXTDEF, LEXTDEF and CEXTDEF records.
+    /// Common symbols are not included.
+    pub fn undefined_symbols(&self) -> impl Iterator<Item = OmfSymbol<'data>> + '_ {
+        self.symbols
+            .iter()
+            .filter(|symbol| symbol.is_undefined())
+            .cloned()
+    }
+
+    /// Return the section at a 0-based position in the order used by
+    /// [`Object::sections`].
+    ///
+    /// SEGDEF segments come first, using their 1-based segment index,
+    /// followed by one section for each COMDAT record.
+    pub(super) fn section_at(&self, position: usize) -> Option<OmfSection<'data, '_, R>> {
+        if let Some(seg) = self.segments.get(position) {
+            return Some(OmfSection {
+                file: self,
+                index: SectionIndex(position + 1),
+                name: self.section_name(seg),
+                data: seg.data,
+                kind: seg.kind,
+                size: seg.length,
+                align: seg.align,
+                relocs: &seg.fixups,
+            });
+        }
+        // COMDAT sections: we expose all COMDATs, even if duplicates exist.
+        // The `selection` field in each COMDAT record determines how linkers resolve duplicates.
+        // We do not enforce these selection rules in this parser — all COMDATs are returned.
+        // NOTE: `data` is empty for iterated COMDATs, which must be expanded using
+        // `segment_data`.
+        let comdat = self.comdats.get(position - self.segments.len())?;
+        let data = comdat.data.unwrap_or(&[]);
+        let size = match *comdat.records {
+            [OmfSectionData::Comdat { offset, data }] => u64::from(offset) + data.len() as u64,
+            _ => self
+                .segment_data(comdat.section)
+                .map_or(data.len(), |contents| contents.len()) as u64,
+        };
+        let segment = usize::from(comdat.segment_index)
+            .checked_sub(1)
+            .and_then(|index| self.segments.get(index));
+        let align = match comdat.align {
+            0 => segment.map_or(1, |segment| segment.align),
+            align => super::segment_align(align),
+        };
+        Some(OmfSection {
+            file: self,
+            index: comdat.section,
+            name: comdat.name,
+            data: OmfSectionData::Comdat {
+                offset: comdat.offset,
+                data,
+            },
+            kind: super::comdat_kind(comdat, &self.segments),
+            size,
+            align,
+            relocs: &comdat.fixups,
+        })
+    }
+
+    /// Construct a map from addresses to symbol names and source file names,
+    /// using the section addresses in `layout`.
+    ///
+    /// Symbols in sections that have no base address in `layout` are omitted.
+    /// The object file of each symbol is the source file of the line number
+    /// covering the symbol if there is one, or else the THEADR module that
+    /// defined the symbol. The member name is the LIBMOD module name, if any.
+    pub fn object_map_with_layout(&self, layout: &OmfSegmentLayout) -> ObjectMap<'data> {
+        let sections = self.segments.len() + self.comdats.len();
+        let line_tables = (1..=sections)
+            .map(|index| self.line_table(SectionIndex(index)))
+            .collect::<Vec<_>>();
+        let member = self.library_module.map(str::as_bytes);
+
+        let mut objects: Vec<ObjectMapFile<'data>> = Vec::new();
+        let mut entries = Vec::new();
+        for symbol in &self.symbols {
+            let section = match symbol.segment {
+                Some(SectionIndex(0)) | None => continue,
+                Some(section) => section,
+            };
+            if symbol.name.is_empty() {
+                continue;
+            }
+            let address = match layout.base(section) {
+                Some(base) => base.wrapping_add(symbol.offset),
+                None => continue,
+            };
+            let path = line_tables
+                .get(section.0 - 1)
+                .and_then(|table| table.find(symbol.offset))
+                .and_then(|(file, _)| file)
+                .or(symbol.source_module)
+                .unwrap_or("")
+                .as_bytes();
+            let object = match objects.iter().position(|object| object.path() == path) {
+                Some(object) => object,
+                None => {
+                    objects.push(ObjectMapFile::new(path, member));
+                    objects.len() - 1
+                }
+            };
+            entries.push(ObjectMapEntry {
+                address,
+                size: symbol.size,
+                name: symbol.name,
+                object,
+            });
+        }
+        ObjectMap {
+            symbols: SymbolMap::new(entries),
+            objects,
+        }
+    }
+
+    /// Return a map of the symbols defined in the given section.
+    ///
+    /// The addresses are offsets within the section. The map is borrowed if it
+    /// was built by [`OmfFile::precompute`], and is built on each call otherwise.
+    pub fn section_symbol_map(
+        &self,
+        section: SectionIndex,
+    ) -> Cow<'_, SymbolMap<SymbolMapName<'data>>> {
+        match self.cache.section_symbol_map(section) {
+            Some(map) => Cow::Borrowed(map),
+            None => Cow::Owned(self.build_symbol_map(|index| index == section)),
+        }
+    }
 
-/// Return an iterator over sections in the OMF file.
-/// Currently returns raw SEGDEF/COMDAT segments only (no dedup/group expansion).
-pub fn sections(&'data self) -> OmfSectionIterator<'data> {
-    OmfSectionIterator {
-        iter: self.sections.iter(),
+    pub(super) fn build_symbol_map<F>(&self, filter: F) -> SymbolMap<SymbolMapName<'data>>
+    where
+        F: Fn(SectionIndex) -> bool,
+    {
+        let mut symbols = self
+            .symbols
+            .iter()
+            .filter(|symbol| !symbol.name.is_empty())
+            .filter(|symbol| match symbol.segment {
+                // Absolute symbols don't have a segment-relative address.
+                Some(SectionIndex(0)) | None => false,
+                Some(index) => filter(index),
+            })
+            .collect::<Vec<_>>();
+        // Prefer global symbols, then earlier symbols, when addresses are shared.
+        symbols.sort_by_key(|symbol| (symbol.offset, !symbol.global, symbol.index));
+        symbols.dedup_by_key(|symbol| symbol.offset);
+        SymbolMap::new(
+            symbols
+                .into_iter()
+                .filter_map(|symbol| {
+                    let name = str::from_utf8(symbol.name).ok()?;
+                    Some(SymbolMapName::new(symbol.offset, name))
+                })
+                .collect(),
+        )
     }
 }
 
-/// Iterator over OMF sections.
-pub struct OmfSectionIterator<'data> {
-    iter: std::slice::Iter<'data, OmfSection>,
+/// An iterator for the COMDAT records in an [`OmfFile`].
+pub struct OmfComdatIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    file: &'file OmfFile<'data, R>,
+    iter: core::slice::Iter<'file, OmfComdatDef<'data>>,
 }
 
-impl<'data> Iterator for OmfSectionIterator<'data> {
-    type Item = &'data OmfSection;
+impl<'data, 'file, R: ReadRef<'data>> fmt::Debug for OmfComdatIterator<'data, 'file, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OmfComdatIterator").finish_non_exhaustive()
+    }
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for OmfComdatIterator<'data, 'file, R> {
+    type Item = OmfComdat<'data, 'file, R>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next()
+        Some(OmfComdat {
+            file: self.file,
+            comdat: self.iter.next()?,
+        })
     }
 }
 
-/// Return an iterator over all symbols in the OMF file.
-/// Includes EXTDEF, PUBDEF, and COMDAT names as appropriate.
-pub fn symbols(&'data self) -> OmfSymbolIterator<'data> {
-    OmfSymbolIterator {
-        iter: self.symbols.iter(),
+impl<'data> OmfComdatDef<'data> {
+    /// Get the COMDAT kind from the selection criteria.
+    pub fn kind(&self) -> ComdatKind {
+        match self.selection & 0xF0 {
+            0x00 => ComdatKind::NoDuplicates,
+            0x10 => ComdatKind::Any,
+            0x20 => ComdatKind::SameSize,
+            0x30 => ComdatKind::ExactMatch,
+            _ => ComdatKind::Unknown,
+        }
     }
 }
 
-/// Iterator over OMF symbols.
-pub struct OmfSymbolIterator<'data> {
-    iter: std::slice::Iter<'data, OmfSymbol>,
+/// A COMDAT in an [`OmfFile`].
+///
+/// Each COMDAT is a single section.
+pub struct OmfComdat<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    file: &'file OmfFile<'data, R>,
+    comdat: &'file OmfComdatDef<'data>,
 }
 
-impl<'data> Iterator for OmfSymbolIterator<'data> {
-    type Item = &'data OmfSymbol;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next()
+impl<'data, 'file, R: ReadRef<'data>> OmfComdat<'data, 'file, R> {
+    /// Get the COMDAT definition.
+    #[inline]
+    pub fn omf_comdat(&self) -> &'file OmfComdatDef<'data> {
+        self.comdat
     }
 }
 
+impl<'data, 'file, R: ReadRef<'data>> fmt::Debug for OmfComdat<'data, 'file, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OmfComdat")
+            .field("comdat", self.comdat)
+            .finish_non_exhaustive()
+    }
+}
 
-// --- ObjectFile Trait Implementation ---
-
-use crate::read::{ObjectFile, ObjectSymbol, ObjectSection, Symbol, SymbolKind, SymbolScope, SymbolSection};
-use crate::{Architecture, BinaryFormat, Endianness};
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for OmfComdat<'data, 'file, R> {}
 
-impl<'data> ObjectFile<'data> for OmfFile<'data> {
-    type Section = OmfSection<'data>;
-    type SectionIterator = OmfSectionIterator<'data>;
-    type Symbol = OmfSymbol<'data>;
-    type SymbolIterator = OmfSymbolIterator<'data>;
+impl<'data, 'file, R: ReadRef<'data>> ObjectComdat<'data> for OmfComdat<'data, 'file, R> {
+    type SectionIterator = OmfComdatSectionIterator<'data, 'file, R>;
 
-    pub fn architecture(&self) -> Architecture {
-        Architecture::I386 // OMF is used only for 16/32-bit Intel platforms
+    #[inline]
+    fn kind(&self) -> ComdatKind {
+        self.comdat.kind()
     }
 
-    pub fn file_format(&self) -> BinaryFormat {
-        BinaryFormat::Omf
+    #[inline]
+    fn symbol(&self) -> SymbolIndex {
+        SymbolIndex(self.comdat.symbol)
     }
 
-    pub fn is_little_endian(&self) -> bool {
-        true // OMF files are always little-endian
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        Ok(self.comdat.name)
     }
 
-    pub fn entry(&self) -> Option<u64> {
-        None // OMF object files do not define entry points
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.comdat.name).map_err(|_| Error("Non UTF-8 OMF COMDAT name"))
     }
 
-    pub fn sections(&'data self) -> Self::SectionIterator {
-        self.sections()
+    #[inline]
+    fn sections(&self) -> Self::SectionIterator {
+        OmfComdatSectionIterator {
+            file: self.file,
+            section: Some(self.comdat.section),
+        }
     }
+}
+
+/// An iterator for the sections in an [`OmfComdat`].
+pub struct OmfComdatSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file OmfFile<'data, R>,
+    section: Option<SectionIndex>,
+}
 
-    pub fn symbols(&'data self) -> Self::SymbolIterator {
-        self.symbols()
+impl<'data, 'file, R: ReadRef<'data>> fmt::Debug for OmfComdatSectionIterator<'data, 'file, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OmfComdatSectionIterator")
+            .field("section", &self.section)
+            .finish_non_exhaustive()
     }
-    
-    /// Returns all OMF `COMENT` records — compiler/linker banners, version strings, and metadata.
-    /// These are not used for linking but may provide useful diagnostics.
-    pub fn comments(&'data self) -> impl Iterator<Item = &OmfComment<'data>> {
-        self.comments.iter()
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for OmfComdatSectionIterator<'data, 'file, R> {
+    type Item = SectionIndex;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.section.take()
     }
 }
@@ -1,85 +1,276 @@
 //! OMF section representation.
 //!
 //! A “section” in OMF corresponds to a single SEGDEF/LEDATA pair (or a COMDAT),
-//! and may also come from compressed LIDATA.  We expose the same struct as both
-//! an `ObjectSection` and an `ObjectSegment` to satisfy the `object` crate
-//! APIs without duplication.
+//! and may also come from compressed LIDATA.  Each section is also exposed as
+//! an [`OmfSegment`], since OMF makes no distinction between the two.
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::str;
 
 use crate::read::{
-    Error, ObjectSection, ObjectSegment, Relocation, RelocationEncoding, RelocationKind,
-    RelocationTarget, SectionFlags, SectionIndex, SegmentFlags,
+    self, CompressedData, CompressedFileRange, Error, ObjectSection, ObjectSegment, ReadRef,
+    Relocation, RelocationEncoding, RelocationFlags, RelocationKind, RelocationMap,
+    RelocationTarget, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags,
 };
 
-use crate::read::SectionFlags;
-use crate::read::{ObjectSegment, SegmentFlags};
-
-use crate::read::omf::section::OmfSectionData;
-
+use super::fixupp::PackedFixup;
 use super::OmfFile;
 
 /// Encapsulates the origin and contents for a section’s data.
 ///
-/// * `Ledata`  – raw bytes loaded directly from a LEDATA record  
-/// * `Comdat`  – bytes attached to a COMDAT record (link-once)  
-/// * `Lidata`  – compressed iterated data, stored **unexpanded** for now
-#[derive(Debug)]
+/// * `Ledata`  – raw bytes loaded directly from a LEDATA record
+/// * `Comdat`  – bytes attached to a COMDAT record (link-once)
+/// * `Lidata`  – compressed iterated data, stored unexpanded
+#[derive(Debug, Clone, Copy)]
 pub enum OmfSectionData<'data> {
     /// Raw data from a LEDATA record.
     Ledata {
+        /// Offset of the data within the segment.
         offset: u32,
+        /// The data bytes.
         data: &'data [u8],
     },
     /// Data attached to a COMDAT record.
     Comdat {
+        /// Offset of the data within the COMDAT.
         offset: u32,
+        /// The data bytes.
         data: &'data [u8],
     },
     /// Logical Iterated Data (LIDATA), stored as undecoded bytes.
     /// Used for repeating/uninitialized block definitions.
     Lidata {
+        /// Offset of the data within the segment.
         offset: u32,
+        /// The undecoded iterated data blocks.
         raw: &'data [u8],
+        /// Whether the repeat counts of the blocks are 32-bit.
+        is_32bit: bool,
     },
 }
 
-/// Concrete OMF section (also used as segment).
-#[derive(Debug)]
-pub struct OmfSection<'data> {
-    pub index: usize,
-    pub name: &'data str,
-    pub data: OmfSectionData<'data>,
-    pub flags: SectionFlags,
-    pub relocs: Vec<OmfRelocation>,
+impl<'data> OmfSectionData<'data> {
+    /// Return the offset of the data within its segment or COMDAT.
+    pub fn offset(&self) -> u32 {
+        match *self {
+            OmfSectionData::Ledata { offset, .. }
+            | OmfSectionData::Comdat { offset, .. }
+            | OmfSectionData::Lidata { offset, .. } => offset,
+        }
+    }
+
+    /// Return the bytes that are available without expansion.
+    ///
+    /// For `LIDATA` this returns an empty slice, because the data is
+    /// compressed. Use [`OmfFile::segment_data`] for the expanded data.
+    pub fn bytes(&self) -> &'data [u8] {
+        match *self {
+            OmfSectionData::Ledata { data, .. } => data,
+            OmfSectionData::Comdat { data, .. } => data,
+            OmfSectionData::Lidata { .. } => &[],
+        }
+    }
 }
 
+/// Target of a fixup: what the relocated value refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OmfFixupTarget {
+    /// A segment, by 1-based SEGDEF index.
     Segment(u16),
+    /// A group, by 1-based GRPDEF index.
     Group(u16),
+    /// An external symbol, by 1-based external index.
     Symbol(u16),
 }
 
+/// Frame of a fixup: the segment base the relocated value is relative to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OmfFixupFrame {
+    /// A segment, by 1-based SEGDEF index.
     Segment(u16),
+    /// A group, by 1-based GRPDEF index.
     Group(u16),
+    /// The frame of an external symbol, by 1-based external index.
     Symbol(u16),
+    /// The frame of the location being fixed up.
     Location,
+    /// The frame of the fixup target.
+    Target,
 }
 
 /// Relocation emitted by a FIXUPP sub-record.
-///
-/// *Only segment-relative fixups are decoded for now; threaded or
-/// external/symbol fixups are TODO.*
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct OmfRelocation {
+    /// Offset of the fixup location within the section.
     pub offset: u32,
+    /// The target of the fixup.
     pub target: OmfFixupTarget,
+    /// The frame of the fixup, if known.
     pub frame: Option<OmfFixupFrame>,
-    pub kind:  RelocationKind,
+    /// The generic relocation kind.
+    pub kind: RelocationKind,
+    /// The generic relocation encoding.
     pub encoding: RelocationEncoding,
-    pub size:  u8,
+    /// The size in bits of the fixup location.
+    pub size: u8,
+    /// The addend to apply to the target.
     pub addend: i64,
 }
 
+impl OmfRelocation {
+    /// Convert to the generic relocation representation.
+    ///
+    /// External symbol targets are resolved using the external index space of `file`.
+    pub fn relocation<'data, R: ReadRef<'data>>(&self, file: &OmfFile<'data, R>) -> Relocation {
+        let target = match self.target {
+            OmfFixupTarget::Segment(index) => RelocationTarget::Section(SectionIndex(index.into())),
+            OmfFixupTarget::Symbol(index) => match file.external_symbol(index) {
+                Some(symbol) => RelocationTarget::Symbol(symbol),
+                None => RelocationTarget::Absolute,
+            },
+            OmfFixupTarget::Group(_) => RelocationTarget::Absolute,
+        };
+        Relocation {
+            kind: self.kind,
+            encoding: self.encoding,
+            size: self.size,
+            target,
+            addend: self.addend,
+            // OMF fixups add the target address to the value stored in the data.
+            implicit_addend: true,
+            flags: RelocationFlags::Generic {
+                kind: self.kind,
+                encoding: self.encoding,
+                size: self.size,
+            },
+        }
+    }
+}
+
+/// The fixups for a segment or COMDAT.
+///
+/// Large modules can contain tens of thousands of fixups, so these are stored
+/// in a compact form and decoded to an [`OmfRelocation`] when they are read.
+#[derive(Debug, Clone, Default)]
+pub struct OmfFixups {
+    fixups: Vec<PackedFixup>,
+}
+
+impl OmfFixups {
+    /// Return the number of fixups.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.fixups.len()
+    }
+
+    /// Return true if there are no fixups.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.fixups.is_empty()
+    }
+
+    /// Return the fixup at the given index.
+    pub fn get(&self, index: usize) -> Option<OmfRelocation> {
+        self.fixups.get(index).map(PackedFixup::decode)
+    }
+
+    /// Return an iterator over the fixups, in the order they were defined.
+    pub fn iter(&self) -> OmfFixupIterator<'_> {
+        OmfFixupIterator {
+            fixups: self.fixups.iter(),
+        }
+    }
+
+    /// Append the fixups from a FIXUPP record, adding `data_offset` to the
+    /// offset of each fixup.
+    pub(super) fn extend(&mut self, fixups: Vec<PackedFixup>, data_offset: u32) {
+        self.fixups.extend(fixups.into_iter().map(|mut fixup| {
+            fixup.offset = fixup.offset.wrapping_add(data_offset);
+            fixup
+        }));
+    }
+}
+
+impl<'a> IntoIterator for &'a OmfFixups {
+    type Item = OmfRelocation;
+    type IntoIter = OmfFixupIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the fixups in an [`OmfFixups`].
+#[derive(Debug, Clone)]
+pub struct OmfFixupIterator<'a> {
+    fixups: core::slice::Iter<'a, PackedFixup>,
+}
+
+impl<'a> Iterator for OmfFixupIterator<'a> {
+    type Item = OmfRelocation;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.fixups.next().map(PackedFixup::decode)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.fixups.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for OmfFixupIterator<'a> {}
+
+/// An iterator for the sections in an [`OmfFile`].
+#[derive(Debug)]
+pub struct OmfSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file OmfFile<'data, R>,
+    /// The position of the next section, counting segments then COMDATs.
+    pub(super) index: usize,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for OmfSectionIterator<'data, 'file, R> {
+    type Item = OmfSection<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let section = self.file.section_at(self.index)?;
+        self.index += 1;
+        Some(section)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.file.segments.len() + self.file.comdats.len()).saturating_sub(self.index);
+        (len, Some(len))
+    }
+}
+
+/// A section in an [`OmfFile`].
+#[derive(Debug)]
+pub struct OmfSection<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file OmfFile<'data, R>,
+    pub(super) index: SectionIndex,
+    pub(super) name: &'data [u8],
+    pub(super) data: OmfSectionData<'data>,
+    pub(super) kind: SectionKind,
+    pub(super) size: u64,
+    pub(super) align: u64,
+    pub(super) relocs: &'file OmfFixups,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> OmfSection<'data, 'file, R> {
+    /// Return the origin and undecoded contents of the section data.
+    pub fn section_data(&self) -> OmfSectionData<'data> {
+        self.data
+    }
+
+    /// Return the fixups for this section.
+    pub fn fixups(&self) -> &'file OmfFixups {
+        self.relocs
+    }
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for OmfSection<'data, 'file, R> {}
+
 // -----------------------------------------------------------------------------
 // ObjectSection Trait Implementation
 //
@@ -90,113 +281,223 @@ pub struct OmfRelocation {
 // We implement this on `OmfSection` to enable consumers to access section
 // data, relocations, names, and flags in a format-agnostic way.
 // -----------------------------------------------------------------------------
-impl<'data> ObjectSection<'data> for OmfSection<'data> {
-
-    /// Type used to represent relocations in this format.
-    type Relocation = OmfRelocation;
+impl<'data, 'file, R: ReadRef<'data>> ObjectSection<'data> for OmfSection<'data, 'file, R> {
+    type RelocationIterator = OmfRelocationIterator<'data, 'file, R>;
 
-    /// Return this section's numeric index within the object file.
+    /// Return this section's 1-based index (the SEGDEF index for segments).
+    #[inline]
     fn index(&self) -> SectionIndex {
-        SectionIndex(self.index)
-    }
-
-    /// Return the name of this section, parsed from SEGDEF or COMDAT headers.
-    fn name(&self) -> Result<&'data str, Error> {
-        Ok(self.name)
-    }
-
-    /// Return raw bytes. For `LIDATA` we currently return an empty slice,
-    /// because the data is compressed and not yet expanded.
-    fn data(&self) -> Result<&'data [u8], Error> {
-        match self.data {
-            OmfSectionData::Ledata { data, .. } => Ok(data),
-            OmfSectionData::Comdat { data, .. } => Ok(data),
-            OmfSectionData::Lidata { .. } => {
-                // LIDATA: Compressed iterated data — not yet expanded
-                Ok(&[])
-            }
-        }
+        self.index
     }
 
     /// Return the virtual address this section should be loaded at.
     /// OMF object files do not assign fixed runtime addresses; return 0.
+    #[inline]
     fn address(&self) -> u64 {
         0
     }
 
-    /// Return the number of bytes in the section's contents.
+    /// Return the segment length declared by the SEGDEF record.
+    #[inline]
     fn size(&self) -> u64 {
-        match self.data {
-            OmfSectionData::Ledata { data, .. } => data.len() as u64,
-            OmfSectionData::Comdat { data, .. } => data.len() as u64,
-            OmfSectionData::Lidata { .. } => 0, // unknown until expanded
-        }
+        self.size
     }
 
-    /// Return the required memory alignment of this section.
-    /// OMF does not encode alignment, so we assume byte-aligned (1).
+    /// Return the alignment declared by the SEGDEF record.
+    #[inline]
     fn align(&self) -> u64 {
-        1
+        self.align
     }
 
-    /// Return section flags.
-    /// These are set heuristically by the parser; OMF does not provide real flags.
-    fn flags(&self) -> SectionFlags {
-        self.flags
+    #[inline]
+    fn file_range(&self) -> Option<(u64, u64)> {
+        None
+    }
+
+    /// Return the section data if it is stored in a single LEDATA or COMDAT
+    /// record at offset 0.
+    ///
+    /// Use [`ObjectSection::uncompressed_data`] for the data of other sections.
+    fn data(&self) -> Result<&'data [u8]> {
+        if !self.file.is_data_loaded() {
+            return Err(Error("OMF section data is not loaded"));
+        }
+        match self.file.section_records(self.index) {
+            Some([])
+            | Some([OmfSectionData::Ledata { offset: 0, .. }])
+            | Some([OmfSectionData::Comdat { offset: 0, .. }])
+            | None => Ok(self.data.bytes()),
+            Some(_) => Err(Error("OMF section data is split across data records")),
+        }
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        Ok(read::util::data_range(
+            self.data()?,
+            self.address(),
+            address,
+            size,
+        ))
+    }
+
+    #[inline]
+    fn compressed_file_range(&self) -> Result<CompressedFileRange> {
+        Ok(CompressedFileRange::none(self.file_range()))
+    }
+
+    #[inline]
+    fn compressed_data(&self) -> Result<CompressedData<'data>> {
+        self.data().map(CompressedData::none)
+    }
+
+    /// Return the section data, combined from all of its LEDATA and LIDATA records,
+    /// or from all of its COMDAT records.
+    ///
+    /// The data is only copied if the section is not a single data record at
+    /// offset 0. Large segments, such as DWARF debug segments, are usually
+    /// split across many LEDATA records.
+    fn uncompressed_data(&self) -> Result<Cow<'data, [u8]>> {
+        match self.file.section_records(self.index) {
+            Some([])
+            | Some([OmfSectionData::Ledata { offset: 0, .. }])
+            | Some([OmfSectionData::Comdat { offset: 0, .. }])
+            | None => self.data().map(Cow::Borrowed),
+            Some(_) => self.file.segment_contents(self.index).map(Cow::Owned),
+        }
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        Ok(self.name)
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name).map_err(|_| Error("Non UTF-8 OMF section name"))
+    }
+
+    #[inline]
+    fn segment_name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn segment_name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn kind(&self) -> SectionKind {
+        self.kind
     }
 
     /// Return an iterator over this section’s relocation entries.
     /// Each relocation adjusts a target offset in the section.
-    fn relocations(&self) -> Box<dyn Iterator<Item = (u64, &Self::Relocation)> + '_> {
-        Box::new(self.relocs.iter().map(|r| (r.offset, r)))
+    ///
+    /// This is empty if the data records have not been loaded.
+    fn relocations(&self) -> OmfRelocationIterator<'data, 'file, R> {
+        OmfRelocationIterator {
+            file: self.file,
+            relocs: self.relocs.iter(),
+        }
     }
 
-    /// Return an owning reference to the segment this section belongs to.
-    /// Not used in OMF yet; all segments are treated independently.
-    fn segment(&self) -> Option<ObjectSegment<'data>> {
-        None
+    fn relocation_map(&self) -> Result<RelocationMap> {
+        RelocationMap::new(self.file, self)
+    }
+
+    /// OMF does not provide section flags beyond those mapped to the section kind.
+    #[inline]
+    fn flags(&self) -> SectionFlags {
+        SectionFlags::None
     }
 }
 
-// -----------------------------------------------------------------------------
-// ObjectSegment Trait Implementation
-//
-// In the `object` crate, a “segment” is a top-level binary region
-// (e.g., ELF PT_LOAD, COFF section).  For OMF, each SEGDEF/LEDATA pair
-// (and each COMDAT-as-section) maps 1-to-1 with `OmfSection`,
-// so we can expose the same struct as both a Section *and* a Segment.
-// -----------------------------------------------------------------------------
-impl<'data> ObjectSegment<'data> for OmfSection<'data> {
-    /// Segment name (same string as the section’s).
-    fn name(&self) -> Result<&'data str, ()> {
-        Ok(self.name)
+/// An iterator for the segments in an [`OmfFile`].
+#[derive(Debug)]
+pub struct OmfSegmentIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) sections: OmfSectionIterator<'data, 'file, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for OmfSegmentIterator<'data, 'file, R> {
+    type Item = OmfSegment<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.sections.next().map(|section| OmfSegment { section })
     }
+}
+
+/// A segment in an [`OmfFile`].
+///
+/// Each SEGDEF record and each COMDAT maps to both a section and a segment.
+#[derive(Debug)]
+pub struct OmfSegment<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) section: OmfSection<'data, 'file, R>,
+}
 
-    /// Segment virtual address.
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for OmfSegment<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSegment<'data> for OmfSegment<'data, 'file, R> {
     /// OMF object files don’t have fixed runtime addresses, so return 0.
+    #[inline]
     fn address(&self) -> u64 {
         0
     }
 
-    /// Size of the segment in bytes.
+    #[inline]
     fn size(&self) -> u64 {
-        self.data.len() as u64
+        self.section.size
     }
 
-    /// Alignment requirement.  OMF doesn’t encode explicit alignment,
-    /// so we default to 1-byte alignment.
+    #[inline]
     fn align(&self) -> u64 {
-        1
+        self.section.align
+    }
+
+    #[inline]
+    fn file_range(&self) -> (u64, u64) {
+        (0, 0)
+    }
+
+    fn data(&self) -> Result<&'data [u8]> {
+        self.section.data()
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        self.section.data_range(address, size)
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(Some(self.section.name))
     }
 
-    /// Raw segment data.
-    fn data(&self) -> Result<&'data [u8], ()> {
-        Ok(self.data)
+    #[inline]
+    fn name(&self) -> Result<Option<&str>> {
+        str::from_utf8(self.section.name)
+            .map(Some)
+            .map_err(|_| Error("Non UTF-8 OMF segment name"))
     }
 
-    /// Segment permission / attribute flags.
     /// OMF has no per-segment permission bits, so expose `SegmentFlags::None`.
+    #[inline]
     fn flags(&self) -> SegmentFlags {
         SegmentFlags::None
     }
 }
+
+/// An iterator for the relocations in an [`OmfSection`].
+#[derive(Debug)]
+pub struct OmfRelocationIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    file: &'file OmfFile<'data, R>,
+    relocs: OmfFixupIterator<'file>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for OmfRelocationIterator<'data, 'file, R> {
+    type Item = (u64, Relocation);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let reloc = self.relocs.next()?;
+        Some((reloc.offset.into(), reloc.relocation(self.file)))
+    }
+}
@@ -1,59 +1,195 @@
+//! OMF symbol representation.
+
+use core::str;
+
 use crate::read::{
-    Error, ObjectSymbol, ReadRef, SymbolFlags, SymbolIndex, SymbolKind, SymbolScope,
-    SymbolSection,
+    self, Error, ObjectSymbol, ObjectSymbolTable, Result, SectionIndex, SymbolFlags, SymbolIndex,
+    SymbolKind, SymbolScope, SymbolSection,
 };
 
-use super::OmfFile;
-
-#[derive(Debug)]
+/// A symbol defined by a PUBDEF or COMDAT record, or declared by an EXTDEF record.
+#[derive(Debug, Clone)]
 pub struct OmfSymbol<'data> {
+    /// Index of this symbol in the file's symbol table.
     pub index: usize,
-    pub name: &'data str,
-    pub address: u64,
-    pub section: SymbolSection,
+    /// The symbol name.
+    pub name: &'data [u8],
+    /// The section containing the symbol, or `None` for external symbols.
+    ///
+    /// For symbols defined by PUBDEF records this is the 1-based SEGDEF index,
+    /// and `SectionIndex(0)` is used for absolute symbols.
+    pub segment: Option<SectionIndex>,
+    /// The offset of the symbol within its segment.
+    ///
+    /// For absolute symbols this is the linear address given by the PUBDEF
+    /// base frame and offset.
+    pub offset: u64,
+    /// Whether the symbol is visible to other modules.
+    pub global: bool,
+    /// Whether the symbol names a COMDAT.
+    pub is_comdat: bool,
+    /// The size of the symbol, if known.
+    ///
+    /// For common symbols this is the allocation size.
+    pub size: u64,
+    /// Whether the symbol is a common symbol defined by a COMDEF or LCOMDEF record.
+    pub is_common: bool,
+    /// The 1-based index of the TYPDEF record describing the symbol, or 0 if none.
+    pub type_index: u16,
+    /// Whether the symbol is a weak external declared by a WKEXT comment.
+    pub weak: bool,
+    /// Whether the symbol is a lazy external declared by a LZEXT comment.
+    ///
+    /// A lazy external only uses its default resolution if the symbol is
+    /// not found during library search.
+    pub lazy: bool,
+    /// The symbol to use if a weak or lazy external is not otherwise resolved.
+    pub default_resolution: Option<SymbolIndex>,
+    /// The import ordinal for an external symbol that is imported by ordinal.
+    ///
+    /// This is set from an IMPDEF comment whose internal name matches the symbol.
+    pub ordinal: Option<u16>,
+    /// The 1-based GRPDEF index of the base group of a PUBDEF symbol, or 0 if none.
+    ///
+    /// Group-based symbols are addressed relative to the frame of the group
+    /// rather than the frame of their segment.
+    pub group: u16,
+    /// The name from the THEADR record of the translation unit that defined
+    /// or declared the symbol.
+    pub source_module: Option<&'data str>,
+    /// The symbol kind, derived from the class of the segment containing the symbol.
     pub kind: SymbolKind,
-    pub scope: SymbolScope,
-    pub flags: SymbolFlags<()>,
 }
 
+impl<'data> read::private::Sealed for OmfSymbol<'data> {}
+
 impl<'data> ObjectSymbol<'data> for OmfSymbol<'data> {
+    #[inline]
     fn index(&self) -> SymbolIndex {
         SymbolIndex(self.index)
     }
 
-    fn name(&self) -> Result<&'data str, Error> {
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
         Ok(self.name)
     }
 
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name).map_err(|_| Error("Non UTF-8 OMF symbol name"))
+    }
+
+    #[inline]
+    fn address(&self) -> u64 {
+        self.offset
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.size
+    }
+
+    #[inline]
     fn kind(&self) -> SymbolKind {
-        // Determine the symbol kind based on source:
-        // - COMDAT with data? → Data
-        // - COMDAT with code? → Text (future work: infer from flags)
-        // - EXTDEF/PUBDEF with unknown role? → default to Data
-                self.kind
+        self.kind
+    }
+
+    fn section(&self) -> SymbolSection {
+        if self.is_common {
+            return SymbolSection::Common;
+        }
+        match self.segment {
+            None => SymbolSection::Undefined,
+            Some(SectionIndex(0)) => SymbolSection::Absolute,
+            Some(index) => SymbolSection::Section(index),
+        }
+    }
+
+    #[inline]
+    fn is_undefined(&self) -> bool {
+        self.segment.is_none() && !self.is_common
+    }
+
+    #[inline]
+    fn is_definition(&self) -> bool {
+        self.segment.is_some()
+    }
+
+    #[inline]
+    fn is_common(&self) -> bool {
+        self.is_common
     }
 
+    #[inline]
+    fn is_weak(&self) -> bool {
+        self.weak || self.lazy
+    }
+
+    #[inline]
     fn scope(&self) -> SymbolScope {
-        self.scope
+        if self.global {
+            SymbolScope::Linkage
+        } else {
+            SymbolScope::Compilation
+        }
     }
 
-    fn section(&self) -> SymbolSection {
-        // Map section index to actual section.
-        // If 0 (undefined), mark as such. Otherwise direct mapping.
-                self.section
+    #[inline]
+    fn is_global(&self) -> bool {
+        self.global
     }
 
-    fn address(&self) -> u64 {
-        self.address
+    #[inline]
+    fn is_local(&self) -> bool {
+        !self.global
     }
 
-    fn size(&self) -> u64 {
-        // If the size is known (e.g. from COMDAT or COMDEF), return it.
-        // Otherwise fallback to segment end - offset, or 0 if unknown.
-                0
+    #[inline]
+    fn flags(&self) -> SymbolFlags<SectionIndex, SymbolIndex> {
+        SymbolFlags::None
+    }
+}
+
+/// A symbol table in an [`OmfFile`](super::OmfFile).
+#[derive(Debug, Clone, Copy)]
+pub struct OmfSymbolTable<'data, 'file> {
+    pub(super) symbols: &'file [OmfSymbol<'data>],
+}
+
+impl<'data, 'file> read::private::Sealed for OmfSymbolTable<'data, 'file> {}
+
+impl<'data, 'file> ObjectSymbolTable<'data> for OmfSymbolTable<'data, 'file> {
+    type Symbol = OmfSymbol<'data>;
+    type SymbolIterator = OmfSymbolIterator<'data, 'file>;
+
+    fn symbols(&self) -> Self::SymbolIterator {
+        OmfSymbolIterator {
+            iter: self.symbols.iter(),
+        }
     }
 
-    fn flags(&self) -> SymbolFlags<()> {
-        self.flags
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<Self::Symbol> {
+        self.symbols
+            .get(index.0)
+            .cloned()
+            .ok_or(read::Error("invalid OMF symbol index"))
     }
-}
\ No newline at end of file
+}
+
+/// An iterator for the symbols in an [`OmfFile`](super::OmfFile).
+#[derive(Debug)]
+pub struct OmfSymbolIterator<'data, 'file> {
+    pub(super) iter: core::slice::Iter<'file, OmfSymbol<'data>>,
+}
+
+impl<'data, 'file> Iterator for OmfSymbolIterator<'data, 'file> {
+    type Item = OmfSymbol<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().cloned()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
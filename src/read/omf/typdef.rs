@@ -0,0 +1,95 @@
+//! TYPDEF record parsing for OMF object files.
+//!
+//! TYPDEF records are emitted by old Microsoft tools to describe the size of
+//! variables declared by EXTDEF, PUBDEF and COMDEF records. Each record is
+//! referenced by a 1-based type index, in the order the records appear.
+
+use crate::read::{Error, ReadRef, Result};
+
+use super::{parse_string, read_index, read_length, OmfFile, OmfSymbol};
+
+/// A type defined by a TYPDEF record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OmfType<'data> {
+    /// The type name. This is usually empty.
+    pub name: &'data str,
+    /// The type description.
+    pub leaf: OmfTypeLeaf,
+}
+
+/// The description of a type defined by a TYPDEF record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OmfTypeLeaf {
+    /// A variable that is addressed with a near pointer.
+    Near {
+        /// The variable type (0x7B scalar, 0x79 structure, 0x77 array).
+        var_type: u8,
+        /// The size of the variable in bits.
+        bits: u64,
+    },
+    /// An array that is addressed with a far pointer.
+    Far {
+        /// The number of elements.
+        count: u64,
+        /// The 1-based type index of the element type.
+        element_type: u16,
+    },
+    /// A type description that is not supported.
+    Unknown(u8),
+}
+
+/// Parse the body of a TYPDEF record.
+pub(super) fn parse_typdef(body: &[u8]) -> Result<OmfType<'_>> {
+    let name = parse_string(body)?;
+    // Skip the name and the EN byte, which is always 0.
+    let mut p = 1 + name.len() + 1;
+    let leaf = *body.get(p).ok_or(Error("truncated OMF TYPDEF"))?;
+    p += 1;
+    let leaf = match leaf {
+        0x62 => {
+            let var_type = *body.get(p).ok_or(Error("truncated OMF TYPDEF"))?;
+            p += 1;
+            let bits = read_length(body, &mut p)?;
+            OmfTypeLeaf::Near { var_type, bits }
+        }
+        0x61 => {
+            let count = read_length(body, &mut p)?;
+            let element_type = read_index(body, &mut p)?;
+            OmfTypeLeaf::Far {
+                count,
+                element_type,
+            }
+        }
+        leaf => OmfTypeLeaf::Unknown(leaf),
+    };
+    Ok(OmfType { name, leaf })
+}
+
+impl<'data, R: ReadRef<'data>> OmfFile<'data, R> {
+    /// Return the type for a 1-based type index.
+    pub fn type_by_index(&self, index: u16) -> Option<&OmfType<'data>> {
+        self.types.get(usize::from(index).checked_sub(1)?)
+    }
+
+    /// Return the type referenced by a symbol, if any.
+    pub fn symbol_type(&self, symbol: &OmfSymbol<'data>) -> Option<&OmfType<'data>> {
+        self.type_by_index(symbol.type_index)
+    }
+
+    /// Return the size in bytes of the type with the given 1-based type index.
+    ///
+    /// Returns `None` if the type index is invalid or the size cannot be determined.
+    pub fn type_size(&self, index: u16) -> Option<u64> {
+        match self.type_by_index(index)?.leaf {
+            OmfTypeLeaf::Near { bits, .. } => Some(bits / 8),
+            OmfTypeLeaf::Far {
+                count,
+                element_type,
+            } => match self.type_by_index(element_type)?.leaf {
+                OmfTypeLeaf::Near { bits, .. } => count.checked_mul(bits / 8),
+                _ => None,
+            },
+            OmfTypeLeaf::Unknown(_) => None,
+        }
+    }
+}
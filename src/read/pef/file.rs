@@ -0,0 +1,551 @@
+use alloc::vec::Vec;
+
+use crate::endian::BigEndian as BE;
+use crate::pef;
+use crate::read::{
+    self, Architecture, ByteString, ComdatKind, Error, Export, FileFlags, Import,
+    NoDynamicRelocationIterator, Object, ObjectComdat, ObjectKind, ReadError, ReadRef, Result,
+    SectionIndex, StringTable, SymbolIndex, SymbolKind, SymbolSection,
+};
+
+use super::{
+    PefSection, PefSectionIterator, PefSegment, PefSegmentIterator, PefSymbol, PefSymbolIterator,
+    PefSymbolTable,
+};
+
+/// A PEF container.
+///
+/// Most functions in the [`Object`] trait are implemented for this type.
+#[derive(Debug)]
+pub struct PefFile<'data, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) data: R,
+    header: &'data pef::ContainerHeader,
+    pub(super) sections: &'data [pef::SectionHeader],
+    section_names: StringTable<'data, R>,
+    loader: Option<&'data pef::LoaderInfoHeader>,
+    libraries: Vec<PefImportedLibrary<'data>>,
+    pub(super) symbols: Vec<PefSymbol<'data>>,
+}
+
+impl<'data, R: ReadRef<'data>> PefFile<'data, R> {
+    /// Parse the raw PEF container data.
+    pub fn parse(data: R) -> Result<Self> {
+        let header = data
+            .read_at::<pef::ContainerHeader>(0)
+            .read_error("Invalid PEF header size or alignment")?;
+        if header.tag1.get(BE) != pef::PEF_TAG1 || header.tag2.get(BE) != pef::PEF_TAG2 {
+            return Err(Error("Invalid PEF magic"));
+        }
+        if header.format_version.get(BE) != pef::PEF_VERSION {
+            return Err(Error("Unsupported PEF format version"));
+        }
+        if header.inst_section_count.get(BE) > header.section_count.get(BE) {
+            return Err(Error("Invalid PEF instantiated section count"));
+        }
+
+        let mut offset = core::mem::size_of::<pef::ContainerHeader>() as u64;
+        let sections = data
+            .read_slice_at::<pef::SectionHeader>(offset, header.section_count.get(BE).into())
+            .read_error("Invalid PEF section header count")?;
+        offset += core::mem::size_of_val(sections) as u64;
+        let section_names = StringTable::new(data, offset, data.len().unwrap_or(offset));
+
+        let mut file = PefFile {
+            data,
+            header,
+            sections,
+            section_names,
+            loader: None,
+            libraries: Vec::new(),
+            symbols: Vec::new(),
+        };
+        if let Some(loader) = sections
+            .iter()
+            .find(|section| section.section_kind == pef::PEF_LOADER_SECTION)
+        {
+            file.parse_loader(loader)?;
+        }
+        Ok(file)
+    }
+
+    /// Parse the imports and exports in the loader section.
+    fn parse_loader(&mut self, section: &'data pef::SectionHeader) -> Result<()> {
+        let data = self.data;
+        let base = u64::from(section.container_offset.get(BE));
+        let end = base + u64::from(section.container_length.get(BE));
+        let loader = data
+            .read_at::<pef::LoaderInfoHeader>(base)
+            .read_error("Invalid PEF loader header size or alignment")?;
+        self.loader = Some(loader);
+        let strings_offset = base + u64::from(loader.loader_strings_offset.get(BE));
+        let strings = StringTable::new(data, strings_offset, end);
+
+        let mut offset = base + core::mem::size_of::<pef::LoaderInfoHeader>() as u64;
+        let libraries = data
+            .read_slice_at::<pef::ImportedLibrary>(
+                offset,
+                loader.imported_library_count.get(BE) as usize,
+            )
+            .read_error("Invalid PEF imported library count")?;
+        offset += core::mem::size_of_val(libraries) as u64;
+        let imports = data
+            .read_slice_at::<pef::ImportedSymbol>(
+                offset,
+                loader.total_imported_symbol_count.get(BE) as usize,
+            )
+            .read_error("Invalid PEF imported symbol count")?;
+
+        for library in libraries {
+            let name = strings
+                .get(library.name_offset.get(BE))
+                .read_error("Invalid PEF imported library name offset")?;
+            let first = library.first_imported_symbol.get(BE) as usize;
+            let count = library.imported_symbol_count.get(BE) as usize;
+            if imports.get(first..first.wrapping_add(count)).is_none() {
+                return Err(Error("Invalid PEF imported library symbol range"));
+            }
+            self.libraries.push(PefImportedLibrary {
+                library,
+                name,
+                symbols: first..first + count,
+            });
+        }
+
+        for import in imports {
+            let name = strings
+                .get(import.name_offset())
+                .read_error("Invalid PEF imported symbol name offset")?;
+            let symbol_class = import.symbol_class();
+            self.symbols.push(PefSymbol {
+                index: SymbolIndex(self.symbols.len()),
+                name,
+                symbol_class,
+                section: SymbolSection::Undefined,
+                kind: symbol_kind(symbol_class),
+                address: 0,
+            });
+        }
+
+        let mut offset = base + u64::from(loader.export_hash_offset.get(BE));
+        let hash_table_power = loader.export_hash_table_power.get(BE);
+        if hash_table_power >= 32 {
+            return Err(Error("Invalid PEF export hash table size"));
+        }
+        offset += 4 << hash_table_power;
+        let count = loader.exported_symbol_count.get(BE) as usize;
+        let keys = data
+            .read_slice_at::<pef::ExportedSymbolKey>(offset, count)
+            .read_error("Invalid PEF exported symbol count")?;
+        offset += core::mem::size_of_val(keys) as u64;
+        let exports = data
+            .read_slice_at::<pef::ExportedSymbol>(offset, count)
+            .read_error("Invalid PEF exported symbol count")?;
+        for (key, export) in keys.iter().zip(exports) {
+            let name = data
+                .read_bytes_at(
+                    strings_offset + u64::from(export.name_offset()),
+                    key.name_length().into(),
+                )
+                .read_error("Invalid PEF exported symbol name")?;
+            let value = export.symbol_value.get(BE);
+            let (section, address) = match export.section_index.get(BE) {
+                pef::PEF_ABSOLUTE_EXPORT => (SymbolSection::Absolute, value.into()),
+                index if index >= 0 => {
+                    let header = self
+                        .sections
+                        .get(index as usize)
+                        .read_error("Invalid PEF exported symbol section index")?;
+                    (
+                        SymbolSection::Section(SectionIndex(index as usize)),
+                        u64::from(header.default_address.get(BE)) + u64::from(value),
+                    )
+                }
+                // Re-exported imports, which have the index of the imported symbol
+                // as the value.
+                _ => (SymbolSection::Unknown, 0),
+            };
+            let symbol_class = export.symbol_class();
+            self.symbols.push(PefSymbol {
+                index: SymbolIndex(self.symbols.len()),
+                name,
+                symbol_class,
+                section,
+                kind: symbol_kind(symbol_class),
+                address,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the container header.
+    #[inline]
+    pub fn header(&self) -> &'data pef::ContainerHeader {
+        self.header
+    }
+
+    /// Returns the section headers.
+    #[inline]
+    pub fn section_headers(&self) -> &'data [pef::SectionHeader] {
+        self.sections
+    }
+
+    /// Returns the loader section header, if there is a loader section.
+    #[inline]
+    pub fn loader_header(&self) -> Option<&'data pef::LoaderInfoHeader> {
+        self.loader
+    }
+
+    /// Returns the imported libraries.
+    #[inline]
+    pub fn imported_libraries(&self) -> &[PefImportedLibrary<'data>] {
+        &self.libraries
+    }
+
+    /// Return the name of a section.
+    pub(super) fn section_name(&self, section: &pef::SectionHeader) -> Result<&'data [u8]> {
+        let offset = section.name_offset.get(BE);
+        if offset < 0 {
+            return Ok(&[]);
+        }
+        self.section_names
+            .get(offset as u32)
+            .read_error("Invalid PEF section name offset")
+    }
+
+    /// Return the number of instantiated sections.
+    #[inline]
+    pub(super) fn inst_section_count(&self) -> usize {
+        self.header.inst_section_count.get(BE).into()
+    }
+
+    /// Return the address of a section and offset from the loader header.
+    fn loader_address(&self, section: i32, offset: u32) -> Option<u64> {
+        if section < 0 {
+            return None;
+        }
+        let header = self.sections.get(section as usize)?;
+        Some(u64::from(header.default_address.get(BE)) + u64::from(offset))
+    }
+}
+
+impl<'data, R: ReadRef<'data>> read::private::Sealed for PefFile<'data, R> {}
+
+impl<'data, R: ReadRef<'data>> Object<'data> for PefFile<'data, R> {
+    type Segment<'file>
+        = PefSegment<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SegmentIterator<'file>
+        = PefSegmentIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Section<'file>
+        = PefSection<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SectionIterator<'file>
+        = PefSectionIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Comdat<'file>
+        = PefComdat<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type ComdatIterator<'file>
+        = PefComdatIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Symbol<'file>
+        = PefSymbol<'data>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolIterator<'file>
+        = PefSymbolIterator<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolTable<'file>
+        = PefSymbolTable<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type DynamicRelocationIterator<'file>
+        = NoDynamicRelocationIterator
+    where
+        Self: 'file,
+        'data: 'file;
+
+    fn architecture(&self) -> Architecture {
+        match self.header.architecture.get(BE) {
+            pef::PEF_ARCH_POWERPC => Architecture::PowerPc,
+            pef::PEF_ARCH_M68K => Architecture::M68k,
+            _ => Architecture::Unknown,
+        }
+    }
+
+    #[inline]
+    fn is_little_endian(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn is_64(&self) -> bool {
+        false
+    }
+
+    /// Return `Executable` if there is a main symbol, otherwise `Dynamic`.
+    fn kind(&self) -> ObjectKind {
+        match self.loader {
+            Some(loader) if loader.main_section.get(BE) >= 0 => ObjectKind::Executable,
+            _ => ObjectKind::Dynamic,
+        }
+    }
+
+    fn segments(&self) -> PefSegmentIterator<'data, '_, R> {
+        PefSegmentIterator {
+            sections: PefSectionIterator {
+                file: self,
+                iter: self.sections[..self.inst_section_count()]
+                    .iter()
+                    .enumerate(),
+            },
+        }
+    }
+
+    fn section_by_name_bytes<'file>(
+        &'file self,
+        section_name: &[u8],
+    ) -> Option<PefSection<'data, 'file, R>> {
+        use crate::read::ObjectSection;
+        self.sections()
+            .find(|section| section.name_bytes() == Ok(section_name))
+    }
+
+    fn section_by_index(&self, index: SectionIndex) -> Result<PefSection<'data, '_, R>> {
+        let section = self
+            .sections
+            .get(index.0)
+            .read_error("Invalid PEF section index")?;
+        Ok(PefSection {
+            file: self,
+            index,
+            section,
+        })
+    }
+
+    fn sections(&self) -> PefSectionIterator<'data, '_, R> {
+        PefSectionIterator {
+            file: self,
+            iter: self.sections.iter().enumerate(),
+        }
+    }
+
+    #[inline]
+    fn comdats(&self) -> PefComdatIterator<'data, '_, R> {
+        PefComdatIterator { file: self }
+    }
+
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<PefSymbol<'data>> {
+        self.symbols
+            .get(index.0)
+            .copied()
+            .read_error("Invalid PEF symbol index")
+    }
+
+    fn symbols(&self) -> PefSymbolIterator<'data, '_> {
+        PefSymbolIterator {
+            iter: self.symbols.iter(),
+        }
+    }
+
+    fn symbol_table(&self) -> Option<PefSymbolTable<'data, '_>> {
+        Some(PefSymbolTable {
+            symbols: &self.symbols,
+        })
+    }
+
+    fn dynamic_symbols(&self) -> PefSymbolIterator<'data, '_> {
+        PefSymbolIterator { iter: [].iter() }
+    }
+
+    #[inline]
+    fn dynamic_symbol_table(&self) -> Option<PefSymbolTable<'data, '_>> {
+        None
+    }
+
+    #[inline]
+    fn dynamic_relocations(&self) -> Option<NoDynamicRelocationIterator> {
+        None
+    }
+
+    fn imports(&self) -> Result<Vec<Import<'data>>> {
+        let mut imports = Vec::new();
+        for library in &self.libraries {
+            for symbol in &self.symbols[library.symbols.clone()] {
+                imports.push(Import {
+                    library: ByteString(library.name),
+                    name: ByteString(symbol.name),
+                });
+            }
+        }
+        Ok(imports)
+    }
+
+    fn exports(&self) -> Result<Vec<Export<'data>>> {
+        use crate::read::ObjectSymbol;
+        Ok(self
+            .symbols
+            .iter()
+            .filter(|symbol| symbol.is_definition())
+            .map(|symbol| Export {
+                name: ByteString(symbol.name),
+                address: symbol.address,
+            })
+            .collect())
+    }
+
+    fn has_debug_symbols(&self) -> bool {
+        self.sections
+            .iter()
+            .any(|section| section.section_kind == pef::PEF_DEBUG_SECTION)
+    }
+
+    #[inline]
+    fn relative_address_base(&self) -> u64 {
+        0
+    }
+
+    /// Return the address of the main symbol, or 0 if there is none.
+    ///
+    /// For PowerPC, this is usually the address of a transition vector.
+    fn entry(&self) -> u64 {
+        self.loader
+            .and_then(|loader| {
+                self.loader_address(loader.main_section.get(BE), loader.main_offset.get(BE))
+            })
+            .unwrap_or(0)
+    }
+
+    #[inline]
+    fn flags(&self) -> FileFlags {
+        FileFlags::None
+    }
+}
+
+/// A library in the imported library table of a [`PefFile`].
+#[derive(Debug, Clone)]
+pub struct PefImportedLibrary<'data> {
+    library: &'data pef::ImportedLibrary,
+    name: &'data [u8],
+    symbols: core::ops::Range<usize>,
+}
+
+impl<'data> PefImportedLibrary<'data> {
+    /// Returns the imported library table entry.
+    #[inline]
+    pub fn library(&self) -> &'data pef::ImportedLibrary {
+        self.library
+    }
+
+    /// Returns the name of the library.
+    #[inline]
+    pub fn name(&self) -> &'data [u8] {
+        self.name
+    }
+
+    /// Returns the indices of the symbols imported from this library.
+    #[inline]
+    pub fn symbols(&self) -> core::ops::Range<usize> {
+        self.symbols.clone()
+    }
+}
+
+fn symbol_kind(symbol_class: u8) -> SymbolKind {
+    match symbol_class & pef::PEF_SYMBOL_CLASS_MASK {
+        pef::PEF_CODE_SYMBOL | pef::PEF_GLUE_SYMBOL => SymbolKind::Text,
+        pef::PEF_DATA_SYMBOL | pef::PEF_TVECT_SYMBOL | pef::PEF_TOC_SYMBOL => SymbolKind::Data,
+        _ => SymbolKind::Unknown,
+    }
+}
+
+/// An iterator for the COMDAT section groups in a [`PefFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct PefComdatIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file PefFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for PefComdatIterator<'data, 'file, R> {
+    type Item = PefComdat<'data, 'file, R>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+/// A COMDAT section group in a [`PefFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct PefComdat<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file PefFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for PefComdat<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectComdat<'data> for PefComdat<'data, 'file, R> {
+    type SectionIterator = PefComdatSectionIterator<'data, 'file, R>;
+
+    #[inline]
+    fn kind(&self) -> ComdatKind {
+        unreachable!();
+    }
+
+    #[inline]
+    fn symbol(&self) -> SymbolIndex {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn sections(&self) -> Self::SectionIterator {
+        unreachable!();
+    }
+}
+
+/// An iterator for the sections in a COMDAT section group in a [`PefFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct PefComdatSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file PefFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for PefComdatSectionIterator<'data, 'file, R> {
+    type Item = SectionIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
@@ -0,0 +1,37 @@
+//! Support for reading PEF containers.
+//!
+//! The Preferred Executable Format (PEF) is used by the Code Fragment Manager
+//! of the classic Mac OS for PowerPC and CFM-68K applications and shared
+//! libraries.
+//!
+//! ## High level API
+//!
+//! [`PefFile`] implements the [`Object`](crate::read::Object) trait for PEF
+//! containers. Each section in the container is a section, using the 0-based
+//! section number as the section index, and each instantiated section is also
+//! a segment. Pattern-initialized data sections are expanded by
+//! [`ObjectSection::uncompressed_data`](crate::read::ObjectSection::uncompressed_data).
+//!
+//! The imported and exported symbols in the loader section are symbols.
+//! Relocations are not supported.
+//!
+//! ## Low level API
+//!
+//! [`PefFile`] also provides access to the section headers
+//! ([`PefFile::section_headers`]), the loader section header
+//! ([`PefFile::loader_header`]), and the imported libraries
+//! ([`PefFile::imported_libraries`]).
+mod file;
+pub use file::*;
+
+mod section;
+pub use section::*;
+
+mod symbol;
+pub use symbol::*;
+
+mod relocation;
+pub use relocation::*;
+
+mod pattern;
+use pattern::expand_pattern_data;
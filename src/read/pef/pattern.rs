@@ -0,0 +1,112 @@
+//! Expansion of PEF pattern-initialized data.
+//!
+//! A pattern-initialized data section is stored as a sequence of instructions
+//! that zero fill, copy, or repeat blocks of bytes. This is used to compress
+//! data sections that contain mostly zeros or repeated values, such as tables
+//! of transition vectors.
+
+use alloc::vec::Vec;
+
+use crate::pef;
+use crate::read::{Bytes, Error, ReadError, Result};
+
+/// Expand pattern-initialized data.
+///
+/// Returns an error if the expanded data would be larger than `limit`, or if
+/// `limit` bytes can't be allocated.
+pub(super) fn expand_pattern_data(data: &[u8], limit: usize) -> Result<Vec<u8>> {
+    let mut data = Bytes(data);
+    let mut out = Vec::new();
+    out.try_reserve_exact(limit)
+        .ok()
+        .read_error("PEF pattern data allocation failed")?;
+    while !data.is_empty() {
+        let instruction = *data
+            .read::<u8>()
+            .read_error("Invalid PEF pattern instruction")?;
+        let opcode = instruction >> pef::PEF_PK_OPCODE_SHIFT;
+        let mut count = u32::from(instruction & pef::PEF_PK_COUNT_MASK);
+        if count == 0 {
+            count = read_argument(&mut data)?;
+        }
+        match opcode {
+            pef::PEF_PK_DATA_ZERO => {
+                zero(&mut out, count, limit)?;
+            }
+            pef::PEF_PK_DATA_BLOCK => {
+                let block = read_block(&mut data, count)?;
+                copy(&mut out, block, limit)?;
+            }
+            pef::PEF_PK_DATA_REPEAT => {
+                let repeat_count = read_argument(&mut data)?;
+                let block = read_block(&mut data, count)?;
+                for _ in 0..=repeat_count {
+                    copy(&mut out, block, limit)?;
+                }
+            }
+            pef::PEF_PK_DATA_REPEAT_BLOCK => {
+                let custom_size = read_argument(&mut data)?;
+                let repeat_count = read_argument(&mut data)?;
+                let common = read_block(&mut data, count)?;
+                for _ in 0..repeat_count {
+                    copy(&mut out, common, limit)?;
+                    let custom = read_block(&mut data, custom_size)?;
+                    copy(&mut out, custom, limit)?;
+                }
+                copy(&mut out, common, limit)?;
+            }
+            pef::PEF_PK_DATA_REPEAT_ZERO => {
+                let custom_size = read_argument(&mut data)?;
+                let repeat_count = read_argument(&mut data)?;
+                for _ in 0..repeat_count {
+                    zero(&mut out, count, limit)?;
+                    let custom = read_block(&mut data, custom_size)?;
+                    copy(&mut out, custom, limit)?;
+                }
+                zero(&mut out, count, limit)?;
+            }
+            _ => return Err(Error("Unknown PEF pattern opcode")),
+        }
+    }
+    Ok(out)
+}
+
+/// Read a variable length argument.
+fn read_argument(data: &mut Bytes<'_>) -> Result<u32> {
+    let mut value = 0u32;
+    loop {
+        let byte = *data
+            .read::<u8>()
+            .read_error("Invalid PEF pattern argument")?;
+        value = value
+            .checked_mul(1 << 7)
+            .read_error("Invalid PEF pattern argument")?
+            | u32::from(byte & !pef::PEF_PK_ARG_MORE);
+        if byte & pef::PEF_PK_ARG_MORE == 0 {
+            return Ok(value);
+        }
+    }
+}
+
+fn read_block<'data>(data: &mut Bytes<'data>, size: u32) -> Result<&'data [u8]> {
+    data.read_bytes(size as usize)
+        .read_error("Invalid PEF pattern data size")
+        .map(|block| block.0)
+}
+
+fn copy(out: &mut Vec<u8>, block: &[u8], limit: usize) -> Result<()> {
+    if block.len() > limit - out.len() {
+        return Err(Error("PEF pattern data exceeds the section size"));
+    }
+    out.extend_from_slice(block);
+    Ok(())
+}
+
+fn zero(out: &mut Vec<u8>, size: u32, limit: usize) -> Result<()> {
+    let size = size as usize;
+    if size > limit - out.len() {
+        return Err(Error("PEF pattern data exceeds the section size"));
+    }
+    out.resize(out.len() + size, 0);
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use crate::read::{ReadRef, Relocation};
+
+use super::PefFile;
+
+/// An iterator for the relocations in a [`PefSection`](super::PefSection).
+///
+/// This is a stub that doesn't implement any functionality. The relocation
+/// instructions in the loader section are not supported.
+#[derive(Debug)]
+pub struct PefRelocationIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    pub(super) file: &'file PefFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for PefRelocationIterator<'data, 'file, R> {
+    type Item = (u64, Relocation);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
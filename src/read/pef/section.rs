@@ -0,0 +1,281 @@
+use alloc::borrow::Cow;
+use core::str;
+
+use crate::endian::BigEndian as BE;
+use crate::pef;
+use crate::read::{
+    self, CompressedData, CompressedFileRange, CompressionFormat, ObjectSection, ObjectSegment,
+    ReadError, ReadRef, RelocationMap, Result, SectionFlags, SectionIndex, SectionKind,
+    SegmentFlags,
+};
+
+use super::{expand_pattern_data, PefFile, PefRelocationIterator};
+
+/// An iterator for the sections in a [`PefFile`].
+#[derive(Debug)]
+pub struct PefSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file PefFile<'data, R>,
+    pub(super) iter: core::iter::Enumerate<core::slice::Iter<'data, pef::SectionHeader>>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for PefSectionIterator<'data, 'file, R> {
+    type Item = PefSection<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(index, section)| PefSection {
+            file: self.file,
+            index: SectionIndex(index),
+            section,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// A section in a [`PefFile`].
+///
+/// The section index is the 0-based index used by the loader section.
+#[derive(Debug)]
+pub struct PefSection<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file PefFile<'data, R>,
+    pub(super) index: SectionIndex,
+    pub(super) section: &'data pef::SectionHeader,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> PefSection<'data, 'file, R> {
+    /// Return the section header.
+    #[inline]
+    pub fn pef_section(&self) -> &'data pef::SectionHeader {
+        self.section
+    }
+
+    /// Return true if the section is loaded into memory.
+    #[inline]
+    pub fn is_instantiated(&self) -> bool {
+        self.index.0 < self.file.inst_section_count()
+    }
+
+    fn is_pattern_data(&self) -> bool {
+        self.section.section_kind == pef::PEF_PATTERN_DATA_SECTION
+    }
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for PefSection<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSection<'data> for PefSection<'data, 'file, R> {
+    type RelocationIterator = PefRelocationIterator<'data, 'file, R>;
+
+    #[inline]
+    fn index(&self) -> SectionIndex {
+        self.index
+    }
+
+    #[inline]
+    fn address(&self) -> u64 {
+        self.section.default_address.get(BE).into()
+    }
+
+    /// Return the size in memory of an instantiated section, or the size in
+    /// the container of other sections.
+    fn size(&self) -> u64 {
+        if self.is_instantiated() {
+            self.section.total_length.get(BE).into()
+        } else {
+            self.section.container_length.get(BE).into()
+        }
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        1 << self.section.alignment.min(63)
+    }
+
+    fn file_range(&self) -> Option<(u64, u64)> {
+        let size = self.section.container_length.get(BE);
+        if size == 0 {
+            return None;
+        }
+        Some((self.section.container_offset.get(BE).into(), size.into()))
+    }
+
+    /// Return the section data in the container.
+    ///
+    /// For pattern-initialized data sections, this is the pattern
+    /// instructions. Use [`ObjectSection::uncompressed_data`] for the expanded
+    /// data.
+    fn data(&self) -> Result<&'data [u8]> {
+        match self.file_range() {
+            Some((offset, size)) => self
+                .file
+                .data
+                .read_bytes_at(offset, size)
+                .read_error("Invalid PEF section offset or size"),
+            None => Ok(&[]),
+        }
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        Ok(read::util::data_range(
+            self.data()?,
+            self.address(),
+            address,
+            size,
+        ))
+    }
+
+    fn compressed_file_range(&self) -> Result<CompressedFileRange> {
+        let mut range = CompressedFileRange::none(self.file_range());
+        if self.is_pattern_data() {
+            range.format = CompressionFormat::Unknown;
+            range.uncompressed_size = self.section.unpacked_length.get(BE).into();
+        }
+        Ok(range)
+    }
+
+    fn compressed_data(&self) -> Result<CompressedData<'data>> {
+        let mut data = CompressedData::none(self.data()?);
+        if self.is_pattern_data() {
+            data.format = CompressionFormat::Unknown;
+            data.uncompressed_size = self.section.unpacked_length.get(BE).into();
+        }
+        Ok(data)
+    }
+
+    /// Return the section data, with pattern-initialized data expanded.
+    ///
+    /// This does not include the zero-initialized data at the end of
+    /// the section.
+    fn uncompressed_data(&self) -> Result<Cow<'data, [u8]>> {
+        let data = self.data()?;
+        if !self.is_pattern_data() {
+            return Ok(Cow::Borrowed(data));
+        }
+        let size = self.section.unpacked_length.get(BE) as usize;
+        let mut out = expand_pattern_data(data, size)?;
+        out.resize(size, 0);
+        Ok(Cow::Owned(out))
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        self.file.section_name(self.section)
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name_bytes()?)
+            .ok()
+            .read_error("Non UTF-8 PEF section name")
+    }
+
+    #[inline]
+    fn segment_name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn segment_name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    fn kind(&self) -> SectionKind {
+        match self.section.section_kind {
+            pef::PEF_CODE_SECTION => SectionKind::Text,
+            pef::PEF_UNPACKED_DATA_SECTION
+            | pef::PEF_PATTERN_DATA_SECTION
+            | pef::PEF_EXEC_DATA_SECTION => SectionKind::Data,
+            pef::PEF_CONSTANT_SECTION => SectionKind::ReadOnlyData,
+            pef::PEF_LOADER_SECTION => SectionKind::Metadata,
+            pef::PEF_DEBUG_SECTION => SectionKind::Debug,
+            pef::PEF_EXCEPTION_SECTION | pef::PEF_TRACEBACK_SECTION => SectionKind::Other,
+            _ => SectionKind::Unknown,
+        }
+    }
+
+    fn relocations(&self) -> PefRelocationIterator<'data, 'file, R> {
+        PefRelocationIterator { file: self.file }
+    }
+
+    fn relocation_map(&self) -> Result<RelocationMap> {
+        RelocationMap::new(self.file, self)
+    }
+
+    #[inline]
+    fn flags(&self) -> SectionFlags {
+        SectionFlags::None
+    }
+}
+
+/// An iterator for the segments in a [`PefFile`].
+#[derive(Debug)]
+pub struct PefSegmentIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) sections: PefSectionIterator<'data, 'file, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for PefSegmentIterator<'data, 'file, R> {
+    type Item = PefSegment<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.sections.next().map(|section| PefSegment { section })
+    }
+}
+
+/// A segment in a [`PefFile`].
+///
+/// Each instantiated section is both a section and a segment.
+#[derive(Debug)]
+pub struct PefSegment<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    section: PefSection<'data, 'file, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for PefSegment<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSegment<'data> for PefSegment<'data, 'file, R> {
+    #[inline]
+    fn address(&self) -> u64 {
+        self.section.address()
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.section.size()
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        self.section.align()
+    }
+
+    #[inline]
+    fn file_range(&self) -> (u64, u64) {
+        self.section.file_range().unwrap_or((0, 0))
+    }
+
+    /// Return the segment data in the container.
+    ///
+    /// For pattern-initialized data sections, this is the pattern instructions.
+    fn data(&self) -> Result<&'data [u8]> {
+        self.section.data()
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        self.section.data_range(address, size)
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<Option<&[u8]>> {
+        self.section.name_bytes().map(Some)
+    }
+
+    #[inline]
+    fn name(&self) -> Result<Option<&str>> {
+        self.section.name().map(Some)
+    }
+
+    #[inline]
+    fn flags(&self) -> SegmentFlags {
+        SegmentFlags::None
+    }
+}
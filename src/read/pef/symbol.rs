@@ -0,0 +1,164 @@
+use core::str;
+
+use crate::pef;
+use crate::read::{
+    self, ObjectSymbol, ObjectSymbolTable, ReadError, Result, SectionIndex, SymbolFlags,
+    SymbolIndex, SymbolKind, SymbolScope, SymbolSection,
+};
+
+/// A symbol in a [`PefFile`](super::PefFile).
+///
+/// This is either an imported symbol or an exported symbol from the loader
+/// section. The imported symbols come first, so the symbol index of an
+/// imported symbol is its index in the imported symbol table.
+#[derive(Debug, Clone, Copy)]
+pub struct PefSymbol<'data> {
+    pub(super) index: SymbolIndex,
+    pub(super) name: &'data [u8],
+    pub(super) symbol_class: u8,
+    pub(super) section: SymbolSection,
+    pub(super) kind: SymbolKind,
+    pub(super) address: u64,
+}
+
+impl<'data> PefSymbol<'data> {
+    /// Return the symbol class, including the flags.
+    ///
+    /// This is a combination of the `pef::PEF_*_SYMBOL` constants.
+    #[inline]
+    pub fn symbol_class(&self) -> u8 {
+        self.symbol_class
+    }
+}
+
+impl<'data> read::private::Sealed for PefSymbol<'data> {}
+
+impl<'data> ObjectSymbol<'data> for PefSymbol<'data> {
+    #[inline]
+    fn index(&self) -> SymbolIndex {
+        self.index
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        Ok(self.name)
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name)
+            .ok()
+            .read_error("Non UTF-8 PEF symbol name")
+    }
+
+    #[inline]
+    fn address(&self) -> u64 {
+        self.address
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        0
+    }
+
+    #[inline]
+    fn kind(&self) -> SymbolKind {
+        self.kind
+    }
+
+    #[inline]
+    fn section(&self) -> SymbolSection {
+        self.section
+    }
+
+    #[inline]
+    fn is_undefined(&self) -> bool {
+        self.section == SymbolSection::Undefined
+    }
+
+    #[inline]
+    fn is_definition(&self) -> bool {
+        matches!(
+            self.section,
+            SymbolSection::Section(_) | SymbolSection::Absolute
+        )
+    }
+
+    #[inline]
+    fn is_common(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn is_weak(&self) -> bool {
+        self.is_undefined() && self.symbol_class & pef::PEF_WEAK_IMPORT_SYMBOL != 0
+    }
+
+    #[inline]
+    fn scope(&self) -> SymbolScope {
+        if self.is_undefined() {
+            SymbolScope::Unknown
+        } else {
+            SymbolScope::Dynamic
+        }
+    }
+
+    #[inline]
+    fn is_global(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn is_local(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn flags(&self) -> SymbolFlags<SectionIndex, SymbolIndex> {
+        SymbolFlags::None
+    }
+}
+
+/// A symbol table in a [`PefFile`](super::PefFile).
+#[derive(Debug, Clone, Copy)]
+pub struct PefSymbolTable<'data, 'file> {
+    pub(super) symbols: &'file [PefSymbol<'data>],
+}
+
+impl<'data, 'file> read::private::Sealed for PefSymbolTable<'data, 'file> {}
+
+impl<'data, 'file> ObjectSymbolTable<'data> for PefSymbolTable<'data, 'file> {
+    type Symbol = PefSymbol<'data>;
+    type SymbolIterator = PefSymbolIterator<'data, 'file>;
+
+    fn symbols(&self) -> Self::SymbolIterator {
+        PefSymbolIterator {
+            iter: self.symbols.iter(),
+        }
+    }
+
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<Self::Symbol> {
+        self.symbols
+            .get(index.0)
+            .copied()
+            .read_error("Invalid PEF symbol index")
+    }
+}
+
+/// An iterator for the symbols in a [`PefFile`](super::PefFile).
+#[derive(Debug)]
+pub struct PefSymbolIterator<'data, 'file> {
+    pub(super) iter: core::slice::Iter<'file, PefSymbol<'data>>,
+}
+
+impl<'data, 'file> Iterator for PefSymbolIterator<'data, 'file> {
+    type Item = PefSymbol<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().copied()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
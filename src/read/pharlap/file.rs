@@ -0,0 +1,491 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use crate::endian::{LittleEndian as LE, U16Bytes, U32Bytes};
+use crate::pe;
+use crate::pharlap;
+use crate::read::{
+    self, Architecture, Bytes, ComdatKind, Error, Export, FileFlags, Import,
+    NoDynamicRelocationIterator, Object, ObjectComdat, ObjectKind, ReadError, ReadRef, Result,
+    SectionIndex, SymbolIndex,
+};
+
+use super::{
+    PharLapSection, PharLapSectionIterator, PharLapSegment, PharLapSegmentIterator, PharLapSymbol,
+    PharLapSymbolIterator, PharLapSymbolTable,
+};
+
+/// The header of a [`PharLapFile`].
+#[derive(Debug, Clone, Copy)]
+pub enum PharLapHeader<'data> {
+    /// An old-style header.
+    Mp(&'data pharlap::MpHeader),
+    /// A new-style flat model header.
+    P3(&'data pharlap::P3Header),
+}
+
+/// A PharLap 386|DOS-Extender executable.
+///
+/// Most functions in the [`Object`] trait are implemented for this type.
+#[derive(Debug)]
+pub struct PharLapFile<'data, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) data: R,
+    header: PharLapHeader<'data>,
+    header_offset: u64,
+    image_offset: u64,
+    image_size: u64,
+    memory_size: u64,
+    extra_size: u64,
+    pub(super) address: u64,
+    entry: u64,
+    packed: bool,
+    pub(super) relocations: &'data [U32Bytes<LE>],
+}
+
+impl<'data, R: ReadRef<'data>> PharLapFile<'data, R> {
+    /// Parse the raw PharLap file data.
+    pub fn parse(data: R) -> Result<Self> {
+        let (header_offset, header) = parse_headers(data)?;
+        let mut file = PharLapFile {
+            data,
+            header,
+            header_offset,
+            image_offset: 0,
+            image_size: 0,
+            memory_size: 0,
+            extra_size: 0,
+            address: 0,
+            entry: 0,
+            packed: false,
+            relocations: &[],
+        };
+        match header {
+            PharLapHeader::Mp(header) => {
+                let mut file_size = u64::from(header.page_count.get(LE)) * 512;
+                let last_page_size = header.last_page_size.get(LE);
+                if last_page_size != 0 {
+                    let unused = 512u64
+                        .checked_sub(last_page_size.into())
+                        .read_error("Invalid PharLap last page size")?;
+                    file_size = file_size.saturating_sub(unused);
+                }
+                let header_size = u64::from(header.header_size.get(LE)) * 16;
+                file.image_offset = header_offset + header_size;
+                file.image_size = file_size
+                    .checked_sub(header_size)
+                    .read_error("Invalid PharLap header size")?;
+                file.memory_size = file.image_size;
+                file.extra_size = u64::from(header.min_extra.get(LE)) * 0x1000;
+                file.entry = header.initial_eip.get(LE).into();
+                file.relocations = data
+                    .read_slice_at(
+                        header_offset + u64::from(header.relocation_offset.get(LE)),
+                        header.relocation_count.get(LE).into(),
+                    )
+                    .read_error("Invalid PharLap relocation table offset or size")?;
+            }
+            PharLapHeader::P3(header) => {
+                file.image_offset = header_offset + u64::from(header.image_offset.get(LE));
+                file.image_size = header.image_size.get(LE).into();
+                file.memory_size = match header.memory_size.get(LE) {
+                    0 => file.image_size,
+                    size => size.into(),
+                };
+                file.extra_size = header.min_extra.get(LE).into();
+                file.address = header.base_load_offset.get(LE).into();
+                file.entry = header.initial_eip.get(LE).into();
+                file.packed = header.flags.get(LE) & pharlap::P3_FLAG_PACKED != 0;
+                file.relocations = data
+                    .read_slice_at(
+                        header_offset + u64::from(header.relocation_offset.get(LE)),
+                        header.relocation_size.get(LE) as usize / 4,
+                    )
+                    .read_error("Invalid PharLap relocation table offset or size")?;
+            }
+        }
+        Ok(file)
+    }
+
+    /// Return the PharLap header.
+    pub fn header(&self) -> PharLapHeader<'data> {
+        self.header
+    }
+
+    /// Return the file offset of the PharLap header.
+    ///
+    /// This is non-zero if the executable is bound to an MS-DOS stub program.
+    /// All offsets in the header are relative to this.
+    pub fn header_offset(&self) -> u64 {
+        self.header_offset
+    }
+
+    /// Return true if the load image is packed.
+    pub fn is_packed(&self) -> bool {
+        self.packed
+    }
+
+    /// Return the range of the load image in the file.
+    pub fn image_file_range(&self) -> (u64, u64) {
+        (self.image_offset, self.image_size)
+    }
+
+    /// Return the size of the load image in memory, excluding the minimum
+    /// extra memory that is allocated after it.
+    pub fn image_memory_size(&self) -> u64 {
+        self.memory_size
+    }
+
+    /// Return the minimum size of the extra memory that is allocated after
+    /// the load image.
+    pub fn extra_size(&self) -> u64 {
+        self.extra_size
+    }
+
+    /// Return the relocation table.
+    ///
+    /// Each entry is the offset in the load image of a 16-bit segment selector.
+    pub fn relocation_table(&self) -> &'data [U32Bytes<LE>] {
+        self.relocations
+    }
+
+    /// Return the data of the load image in the file, which may be packed.
+    pub(super) fn raw_image_data(&self) -> Result<&'data [u8]> {
+        self.data
+            .read_bytes_at(self.image_offset, self.image_size)
+            .read_error("Invalid PharLap load image offset or size")
+    }
+
+    /// Return the load image, unpacking it if needed.
+    ///
+    /// A packed image is unpacked to its size in memory.
+    pub fn image_data(&self) -> Result<Cow<'data, [u8]>> {
+        let data = self.raw_image_data()?;
+        if self.packed {
+            unpack_image(data, self.memory_size as usize).map(Cow::Owned)
+        } else {
+            Ok(Cow::Borrowed(data))
+        }
+    }
+}
+
+/// Return true if the data is a PharLap executable.
+pub fn is_pharlap_file<'data, R: ReadRef<'data>>(data: R) -> bool {
+    parse_headers(data).is_ok()
+}
+
+fn parse_headers<'data, R: ReadRef<'data>>(data: R) -> Result<(u64, PharLapHeader<'data>)> {
+    let mut header_offset = 0;
+    let dos_header = data.read_at::<pe::ImageDosHeader>(0);
+    if let Ok(dos_header) = dos_header {
+        if dos_header.e_magic.get(LE) == pe::IMAGE_DOS_SIGNATURE {
+            // A bound executable follows the stub program.
+            header_offset = u64::from(dos_header.e_cp.get(LE)) * 512;
+            let last_page_size = dos_header.e_cblp.get(LE);
+            if last_page_size != 0 {
+                let unused = 512u64
+                    .checked_sub(last_page_size.into())
+                    .read_error("Invalid PharLap stub last page size")?;
+                header_offset = header_offset.saturating_sub(unused);
+            }
+        }
+    }
+    let signature = data
+        .read_at::<U16Bytes<LE>>(header_offset)
+        .read_error("Invalid PharLap header offset")?
+        .get(LE);
+    match signature {
+        pharlap::EXP_MP_SIGNATURE => {
+            let header = data
+                .read_at::<pharlap::MpHeader>(header_offset)
+                .read_error("Invalid PharLap header size")?;
+            if header.header_size.get(LE) == 0 {
+                return Err(Error("Invalid PharLap header size"));
+            }
+            Ok((header_offset, PharLapHeader::Mp(header)))
+        }
+        pharlap::EXP_P3_SIGNATURE => {
+            let header = data
+                .read_at::<pharlap::P3Header>(header_offset)
+                .read_error("Invalid PharLap header size")?;
+            if header.level.get(LE) != pharlap::P3_LEVEL_FLAT {
+                return Err(Error("Unsupported PharLap header level"));
+            }
+            Ok((header_offset, PharLapHeader::P3(header)))
+        }
+        pharlap::EXP_P2_SIGNATURE => Err(Error("Unsupported PharLap multisegment file")),
+        _ => Err(Error("Invalid PharLap signature")),
+    }
+}
+
+/// Unpack a packed load image.
+///
+/// See [`pharlap::P3_FLAG_PACKED`] for the format.
+fn unpack_image(data: &[u8], size: usize) -> Result<Vec<u8>> {
+    let invalid = "Invalid PharLap packed load image";
+    let mut data = Bytes(data);
+    let mut image = Vec::new();
+    image
+        .try_reserve_exact(size)
+        .ok()
+        .read_error("PharLap load image allocation failed")?;
+    while !data.is_empty() {
+        let count = data.read::<U16Bytes<LE>>().read_error(invalid)?.get(LE);
+        if count & pharlap::P3_PACK_REPEAT == 0 {
+            let bytes = data.read_bytes(count.into()).read_error(invalid)?;
+            image.extend_from_slice(bytes.0);
+        } else {
+            let length = *data.read::<u8>().read_error(invalid)?;
+            let string = data.read_bytes(length.into()).read_error(invalid)?;
+            let count = usize::from(count & !pharlap::P3_PACK_REPEAT);
+            if image.len() + count * string.len() > size {
+                return Err(Error(invalid));
+            }
+            for _ in 0..count {
+                image.extend_from_slice(string.0);
+            }
+        }
+        if image.len() > size {
+            return Err(Error(invalid));
+        }
+    }
+    image.resize(size, 0);
+    Ok(image)
+}
+
+impl<'data, R: ReadRef<'data>> read::private::Sealed for PharLapFile<'data, R> {}
+
+impl<'data, R: ReadRef<'data>> Object<'data> for PharLapFile<'data, R> {
+    type Segment<'file>
+        = PharLapSegment<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SegmentIterator<'file>
+        = PharLapSegmentIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Section<'file>
+        = PharLapSection<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SectionIterator<'file>
+        = PharLapSectionIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Comdat<'file>
+        = PharLapComdat<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type ComdatIterator<'file>
+        = PharLapComdatIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Symbol<'file>
+        = PharLapSymbol<'data>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolIterator<'file>
+        = PharLapSymbolIterator<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolTable<'file>
+        = PharLapSymbolTable<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type DynamicRelocationIterator<'file>
+        = NoDynamicRelocationIterator
+    where
+        Self: 'file,
+        'data: 'file;
+
+    fn architecture(&self) -> Architecture {
+        Architecture::I386
+    }
+
+    #[inline]
+    fn is_little_endian(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn is_64(&self) -> bool {
+        false
+    }
+
+    fn kind(&self) -> ObjectKind {
+        ObjectKind::Executable
+    }
+
+    fn segments(&self) -> PharLapSegmentIterator<'data, '_, R> {
+        PharLapSegmentIterator {
+            sections: self.sections(),
+        }
+    }
+
+    fn section_by_name_bytes<'file>(
+        &'file self,
+        section_name: &[u8],
+    ) -> Option<PharLapSection<'data, 'file, R>> {
+        use crate::read::ObjectSection;
+        self.sections()
+            .find(|section| section.name_bytes() == Ok(section_name))
+    }
+
+    fn section_by_index(&self, index: SectionIndex) -> Result<PharLapSection<'data, '_, R>> {
+        if index != SectionIndex(1) {
+            return Err(Error("Invalid PharLap section index"));
+        }
+        Ok(PharLapSection { file: self })
+    }
+
+    fn sections(&self) -> PharLapSectionIterator<'data, '_, R> {
+        PharLapSectionIterator {
+            file: self,
+            done: false,
+        }
+    }
+
+    #[inline]
+    fn comdats(&self) -> PharLapComdatIterator<'data, '_, R> {
+        PharLapComdatIterator { file: self }
+    }
+
+    fn symbol_by_index(&self, _index: SymbolIndex) -> Result<PharLapSymbol<'data>> {
+        Err(Error("PharLap files have no symbols"))
+    }
+
+    fn symbols(&self) -> PharLapSymbolIterator<'data, '_> {
+        PharLapSymbolIterator::default()
+    }
+
+    #[inline]
+    fn symbol_table(&self) -> Option<PharLapSymbolTable<'data, '_>> {
+        None
+    }
+
+    fn dynamic_symbols(&self) -> PharLapSymbolIterator<'data, '_> {
+        PharLapSymbolIterator::default()
+    }
+
+    #[inline]
+    fn dynamic_symbol_table(&self) -> Option<PharLapSymbolTable<'data, '_>> {
+        None
+    }
+
+    #[inline]
+    fn dynamic_relocations(&self) -> Option<NoDynamicRelocationIterator> {
+        None
+    }
+
+    fn imports(&self) -> Result<Vec<Import<'data>>> {
+        Ok(Vec::new())
+    }
+
+    fn exports(&self) -> Result<Vec<Export<'data>>> {
+        Ok(Vec::new())
+    }
+
+    #[inline]
+    fn has_debug_symbols(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn relative_address_base(&self) -> u64 {
+        0
+    }
+
+    /// Return the initial EIP.
+    #[inline]
+    fn entry(&self) -> u64 {
+        self.entry
+    }
+
+    #[inline]
+    fn flags(&self) -> FileFlags {
+        FileFlags::None
+    }
+}
+
+/// An iterator for the COMDAT section groups in a [`PharLapFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct PharLapComdatIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file PharLapFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for PharLapComdatIterator<'data, 'file, R> {
+    type Item = PharLapComdat<'data, 'file, R>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+/// A COMDAT section group in a [`PharLapFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct PharLapComdat<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file PharLapFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for PharLapComdat<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectComdat<'data> for PharLapComdat<'data, 'file, R> {
+    type SectionIterator = PharLapComdatSectionIterator<'data, 'file, R>;
+
+    #[inline]
+    fn kind(&self) -> ComdatKind {
+        unreachable!();
+    }
+
+    #[inline]
+    fn symbol(&self) -> SymbolIndex {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn sections(&self) -> Self::SectionIterator {
+        unreachable!();
+    }
+}
+
+/// An iterator for the sections in a COMDAT section group in a [`PharLapFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct PharLapComdatSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file PharLapFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for PharLapComdatSectionIterator<'data, 'file, R> {
+    type Item = SectionIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
@@ -0,0 +1,30 @@
+//! Support for reading PharLap 386|DOS-Extender executables.
+//!
+//! Both old-style (`MP`) and new-style flat model (`P3`) `.EXP` files are
+//! supported, either on their own or bound to an MS-DOS stub program.
+//!
+//! ## High level API
+//!
+//! [`PharLapFile`] implements the [`Object`](crate::read::Object) trait for
+//! PharLap executables. The load image is a single section and segment, with
+//! an index of 1. Its size includes the minimum extra memory that is
+//! allocated after the load image. The relocation table entries are
+//! relocations in this section. PharLap executables have no symbols.
+//!
+//! ## Low level API
+//!
+//! [`PharLapFile`] also provides access to the header
+//! ([`PharLapFile::header`]), the raw relocation table
+//! ([`PharLapFile::relocation_table`]), and the unpacked load image
+//! ([`PharLapFile::image_data`]).
+mod file;
+pub use file::*;
+
+mod section;
+pub use section::*;
+
+mod symbol;
+pub use symbol::*;
+
+mod relocation;
+pub use relocation::*;
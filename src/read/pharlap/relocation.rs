@@ -0,0 +1,46 @@
+use crate::endian::LittleEndian as LE;
+use crate::read::{
+    ReadRef, Relocation, RelocationEncoding, RelocationFlags, RelocationKind, RelocationTarget,
+    SectionIndex,
+};
+
+use super::PharLapFile;
+
+/// An iterator for the relocations in a [`PharLapSection`](super::PharLapSection).
+///
+/// Each relocation is a 16-bit selector of the load image.
+#[derive(Debug)]
+pub struct PharLapRelocationIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file PharLapFile<'data, R>,
+    pub(super) index: usize,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for PharLapRelocationIterator<'data, 'file, R> {
+    type Item = (u64, Relocation);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.file.relocations.get(self.index)?.get(LE);
+        self.index += 1;
+        let (kind, encoding, size) = (
+            RelocationKind::SectionIndex,
+            RelocationEncoding::Generic,
+            16,
+        );
+        Some((
+            offset.into(),
+            Relocation {
+                kind,
+                encoding,
+                size,
+                target: RelocationTarget::Section(SectionIndex(1)),
+                addend: 0,
+                implicit_addend: false,
+                flags: RelocationFlags::Generic {
+                    kind,
+                    encoding,
+                    size,
+                },
+            },
+        ))
+    }
+}
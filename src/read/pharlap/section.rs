@@ -0,0 +1,231 @@
+use alloc::borrow::Cow;
+use core::str;
+
+use crate::read::{
+    self, CompressedData, CompressedFileRange, Error, ObjectSection, ObjectSegment, ReadError,
+    ReadRef, RelocationMap, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags,
+};
+
+use super::{PharLapFile, PharLapRelocationIterator};
+
+/// An iterator for the sections in a [`PharLapFile`].
+#[derive(Debug)]
+pub struct PharLapSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file PharLapFile<'data, R>,
+    pub(super) done: bool,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for PharLapSectionIterator<'data, 'file, R> {
+    type Item = PharLapSection<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        self.done = true;
+        Some(PharLapSection { file: self.file })
+    }
+}
+
+/// A section in a [`PharLapFile`].
+///
+/// The load image is the only section, and is also a segment.
+#[derive(Debug)]
+pub struct PharLapSection<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file PharLapFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> PharLapSection<'data, 'file, R> {
+    /// Return the data of this section, unpacking it if needed.
+    ///
+    /// See [`PharLapFile::image_data`].
+    pub fn uncompressed_data(&self) -> Result<Cow<'data, [u8]>> {
+        self.file.image_data()
+    }
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for PharLapSection<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSection<'data> for PharLapSection<'data, 'file, R> {
+    type RelocationIterator = PharLapRelocationIterator<'data, 'file, R>;
+
+    #[inline]
+    fn index(&self) -> SectionIndex {
+        SectionIndex(1)
+    }
+
+    /// Return the offset in the program segment that the load image is loaded at.
+    #[inline]
+    fn address(&self) -> u64 {
+        self.file.address
+    }
+
+    /// Return the size of the load image in memory, including the minimum
+    /// extra memory that is allocated after it.
+    #[inline]
+    fn size(&self) -> u64 {
+        self.file.image_memory_size() + self.file.extra_size()
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        16
+    }
+
+    /// Return the range of the load image in the file.
+    ///
+    /// Returns `None` if the load image is packed.
+    fn file_range(&self) -> Option<(u64, u64)> {
+        if self.file.is_packed() {
+            None
+        } else {
+            Some(self.file.image_file_range())
+        }
+    }
+
+    /// Return the load image.
+    ///
+    /// Returns an error if the load image is packed; use
+    /// [`PharLapSection::uncompressed_data`] instead.
+    fn data(&self) -> Result<&'data [u8]> {
+        if self.file.is_packed() {
+            return Err(Error("Unsupported packed PharLap load image"));
+        }
+        self.file.raw_image_data()
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        Ok(read::util::data_range(
+            self.data()?,
+            self.address(),
+            address,
+            size,
+        ))
+    }
+
+    #[inline]
+    fn compressed_file_range(&self) -> Result<CompressedFileRange> {
+        Ok(CompressedFileRange::none(self.file_range()))
+    }
+
+    #[inline]
+    fn compressed_data(&self) -> Result<CompressedData<'data>> {
+        self.data().map(CompressedData::none)
+    }
+
+    /// The load image has no name, so return `IMAGE`.
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        Ok(b"IMAGE")
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name_bytes()?)
+            .ok()
+            .read_error("Non UTF-8 PharLap section name")
+    }
+
+    #[inline]
+    fn segment_name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn segment_name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    /// The load image contains both code and data.
+    #[inline]
+    fn kind(&self) -> SectionKind {
+        SectionKind::Text
+    }
+
+    fn relocations(&self) -> PharLapRelocationIterator<'data, 'file, R> {
+        PharLapRelocationIterator {
+            file: self.file,
+            index: 0,
+        }
+    }
+
+    fn relocation_map(&self) -> Result<RelocationMap> {
+        RelocationMap::new(self.file, self)
+    }
+
+    #[inline]
+    fn flags(&self) -> SectionFlags {
+        SectionFlags::None
+    }
+}
+
+/// An iterator for the segments in a [`PharLapFile`].
+#[derive(Debug)]
+pub struct PharLapSegmentIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) sections: PharLapSectionIterator<'data, 'file, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for PharLapSegmentIterator<'data, 'file, R> {
+    type Item = PharLapSegment<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.sections
+            .next()
+            .map(|section| PharLapSegment { section })
+    }
+}
+
+/// A segment in a [`PharLapFile`].
+///
+/// The load image is the only segment, and is also a section.
+#[derive(Debug)]
+pub struct PharLapSegment<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) section: PharLapSection<'data, 'file, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for PharLapSegment<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSegment<'data> for PharLapSegment<'data, 'file, R> {
+    #[inline]
+    fn address(&self) -> u64 {
+        self.section.address()
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.section.size()
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        self.section.align()
+    }
+
+    #[inline]
+    fn file_range(&self) -> (u64, u64) {
+        self.section.file_range().unwrap_or((0, 0))
+    }
+
+    fn data(&self) -> Result<&'data [u8]> {
+        self.section.data()
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        self.section.data_range(address, size)
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn flags(&self) -> SegmentFlags {
+        SegmentFlags::None
+    }
+}
@@ -0,0 +1,423 @@
+use alloc::vec::Vec;
+
+use crate::read::{
+    self, Architecture, ComdatKind, Error, Export, FileFlags, Import, NoDynamicRelocationIterator,
+    Object, ObjectComdat, ObjectKind, ReadError, ReadRef, Result, SectionIndex, SymbolIndex,
+};
+use crate::srec;
+
+use super::{
+    SrecSection, SrecSectionIterator, SrecSegment, SrecSegmentIterator, SrecSymbol,
+    SrecSymbolIterator, SrecSymbolTable,
+};
+
+/// A contiguous range of data in an [`SrecFile`].
+#[derive(Debug)]
+pub(super) struct SrecSectionData {
+    pub(super) address: u64,
+    pub(super) data: Vec<u8>,
+}
+
+/// A Motorola S-record file.
+///
+/// Most functions in the [`Object`] trait are implemented for this type.
+#[derive(Debug)]
+pub struct SrecFile<'data, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    data: R,
+    pub(super) sections: Vec<SrecSectionData>,
+    header: Option<Vec<u8>>,
+    record_count: Option<u32>,
+    entry: Option<u32>,
+    marker: core::marker::PhantomData<&'data ()>,
+}
+
+impl<'data, R: ReadRef<'data>> SrecFile<'data, R> {
+    /// Parse the raw S-record file data.
+    pub fn parse(data: R) -> Result<Self> {
+        let len = data.len().read_error("Unknown S-record file length")?;
+        let text = data
+            .read_bytes_at(0, len)
+            .read_error("Invalid S-record file data")?;
+        let mut file = SrecFile {
+            data,
+            sections: Vec::new(),
+            header: None,
+            record_count: None,
+            entry: None,
+            marker: core::marker::PhantomData,
+        };
+        for line in text.split(|c| *c == b'\n') {
+            let line = trim(line);
+            if line.is_empty() {
+                continue;
+            }
+            let (record_type, address, data) = parse_record(line)?;
+            match record_type {
+                srec::SREC_HEADER => file.header = Some(data),
+                srec::SREC_DATA_16 | srec::SREC_DATA_24 | srec::SREC_DATA_32 => {
+                    file.add_data(address.into(), &data)
+                }
+                srec::SREC_COUNT_16 | srec::SREC_COUNT_24 => file.record_count = Some(address),
+                srec::SREC_START_16 | srec::SREC_START_24 | srec::SREC_START_32 => {
+                    file.entry = Some(address);
+                    break;
+                }
+                _ => return Err(Error("Unknown S-record type")),
+            }
+        }
+        Ok(file)
+    }
+
+    /// Add the data of a data record, starting a new section if it doesn't
+    /// follow the previous data.
+    fn add_data(&mut self, address: u64, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+        if let Some(section) = self.sections.last_mut() {
+            if section.address + section.data.len() as u64 == address {
+                section.data.extend_from_slice(data);
+                return;
+            }
+        }
+        self.sections.push(SrecSectionData {
+            address,
+            data: data.to_vec(),
+        });
+    }
+
+    /// Return the data of the header record, if any.
+    ///
+    /// This is usually a description of the file in ASCII.
+    #[inline]
+    pub fn header(&self) -> Option<&[u8]> {
+        self.header.as_deref()
+    }
+
+    /// Return the number of data records given by the count record, if any.
+    #[inline]
+    pub fn record_count(&self) -> Option<u32> {
+        self.record_count
+    }
+
+    /// Return the entry point from the termination record, if any.
+    #[inline]
+    pub fn start_address(&self) -> Option<u32> {
+        self.entry
+    }
+}
+
+/// Remove the line ending from a line.
+fn trim(mut line: &[u8]) -> &[u8] {
+    while let [rest @ .., b'\r' | b' ' | b'\t'] = line {
+        line = rest;
+    }
+    line
+}
+
+/// Decode a record, and check its length and checksum.
+///
+/// Returns the record type, the address, and the data.
+fn parse_record(line: &[u8]) -> Result<(u8, u32, Vec<u8>)> {
+    let (record_type, digits) = match *line {
+        [srec::SREC_RECORD_MARK, record_type @ b'0'..=b'9', ref digits @ ..]
+            if digits.len() % 2 == 0 =>
+        {
+            (record_type - b'0', digits)
+        }
+        _ => return Err(Error("Invalid S-record")),
+    };
+    let address_size = srec::address_size(record_type).read_error("Unknown S-record type")?;
+    let mut record = Vec::with_capacity(digits.len() / 2);
+    for pair in digits.chunks_exact(2) {
+        let byte = hex_digit(pair[0])
+            .zip(hex_digit(pair[1]))
+            .map(|(high, low)| (high << 4) | low)
+            .read_error("Invalid S-record digit")?;
+        record.push(byte);
+    }
+    // The count includes the address and checksum.
+    if record.len() < address_size + 2 || record.len() != usize::from(record[0]) + 1 {
+        return Err(Error("Invalid S-record length"));
+    }
+    if record.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0xff {
+        return Err(Error("Invalid S-record checksum"));
+    }
+    record.pop();
+    let address = record[1..][..address_size]
+        .iter()
+        .fold(0, |address, byte| (address << 8) | u32::from(*byte));
+    record.drain(..address_size + 1);
+    Ok((record_type, address, record))
+}
+
+fn hex_digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Check if the data starts with an S-record.
+///
+/// This checks the record mark, that the record type is known, and that the
+/// count and address fields are hexadecimal digits.
+pub(crate) fn is_srec_file<'data, R: ReadRef<'data>>(data: R) -> bool {
+    let header = match data.read_bytes_at(0, 8) {
+        Ok(header) => header,
+        Err(_) => return false,
+    };
+    header[0] == srec::SREC_RECORD_MARK
+        && header[1].is_ascii_digit()
+        && srec::address_size(header[1] - b'0').is_some()
+        && header[2..].iter().all(|c| hex_digit(*c).is_some())
+}
+
+impl<'data, R: ReadRef<'data>> read::private::Sealed for SrecFile<'data, R> {}
+
+impl<'data, R: ReadRef<'data>> Object<'data> for SrecFile<'data, R> {
+    type Segment<'file>
+        = SrecSegment<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SegmentIterator<'file>
+        = SrecSegmentIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Section<'file>
+        = SrecSection<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SectionIterator<'file>
+        = SrecSectionIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Comdat<'file>
+        = SrecComdat<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type ComdatIterator<'file>
+        = SrecComdatIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Symbol<'file>
+        = SrecSymbol<'data>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolIterator<'file>
+        = SrecSymbolIterator<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolTable<'file>
+        = SrecSymbolTable<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type DynamicRelocationIterator<'file>
+        = NoDynamicRelocationIterator
+    where
+        Self: 'file,
+        'data: 'file;
+
+    /// S-record files don't record the architecture.
+    #[inline]
+    fn architecture(&self) -> Architecture {
+        Architecture::Unknown
+    }
+
+    /// S-record files don't record the byte order, so this returns false.
+    #[inline]
+    fn is_little_endian(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn is_64(&self) -> bool {
+        false
+    }
+
+    fn kind(&self) -> ObjectKind {
+        ObjectKind::Executable
+    }
+
+    fn segments(&self) -> SrecSegmentIterator<'data, '_, R> {
+        SrecSegmentIterator {
+            sections: self.sections(),
+        }
+    }
+
+    fn section_by_name_bytes<'file>(
+        &'file self,
+        _section_name: &[u8],
+    ) -> Option<SrecSection<'data, 'file, R>> {
+        // Sections in S-record files don't have names.
+        None
+    }
+
+    fn section_by_index(&self, index: SectionIndex) -> Result<SrecSection<'data, '_, R>> {
+        let section = index
+            .0
+            .checked_sub(1)
+            .and_then(|index| self.sections.get(index))
+            .read_error("Invalid S-record section index")?;
+        Ok(SrecSection {
+            file: self,
+            index,
+            section,
+        })
+    }
+
+    fn sections(&self) -> SrecSectionIterator<'data, '_, R> {
+        SrecSectionIterator {
+            file: self,
+            iter: self.sections.iter().enumerate(),
+        }
+    }
+
+    #[inline]
+    fn comdats(&self) -> SrecComdatIterator<'data, '_, R> {
+        SrecComdatIterator { file: self }
+    }
+
+    fn symbol_by_index(&self, _index: SymbolIndex) -> Result<SrecSymbol<'data>> {
+        Err(Error("S-record files have no symbols"))
+    }
+
+    fn symbols(&self) -> SrecSymbolIterator<'data, '_> {
+        SrecSymbolIterator::default()
+    }
+
+    #[inline]
+    fn symbol_table(&self) -> Option<SrecSymbolTable<'data, '_>> {
+        None
+    }
+
+    fn dynamic_symbols(&self) -> SrecSymbolIterator<'data, '_> {
+        SrecSymbolIterator::default()
+    }
+
+    #[inline]
+    fn dynamic_symbol_table(&self) -> Option<SrecSymbolTable<'data, '_>> {
+        None
+    }
+
+    #[inline]
+    fn dynamic_relocations(&self) -> Option<NoDynamicRelocationIterator> {
+        None
+    }
+
+    fn imports(&self) -> Result<Vec<Import<'data>>> {
+        Ok(Vec::new())
+    }
+
+    fn exports(&self) -> Result<Vec<Export<'data>>> {
+        Ok(Vec::new())
+    }
+
+    #[inline]
+    fn has_debug_symbols(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn relative_address_base(&self) -> u64 {
+        0
+    }
+
+    /// Return the address from the termination record.
+    #[inline]
+    fn entry(&self) -> u64 {
+        self.entry.unwrap_or(0).into()
+    }
+
+    #[inline]
+    fn flags(&self) -> FileFlags {
+        FileFlags::None
+    }
+}
+
+/// An iterator for the COMDAT section groups in an [`SrecFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct SrecComdatIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file SrecFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for SrecComdatIterator<'data, 'file, R> {
+    type Item = SrecComdat<'data, 'file, R>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+/// A COMDAT section group in an [`SrecFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct SrecComdat<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file SrecFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for SrecComdat<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectComdat<'data> for SrecComdat<'data, 'file, R> {
+    type SectionIterator = SrecComdatSectionIterator<'data, 'file, R>;
+
+    #[inline]
+    fn kind(&self) -> ComdatKind {
+        unreachable!();
+    }
+
+    #[inline]
+    fn symbol(&self) -> SymbolIndex {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn sections(&self) -> Self::SectionIterator {
+        unreachable!();
+    }
+}
+
+/// An iterator for the sections in a COMDAT section group in an [`SrecFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct SrecComdatSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file SrecFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for SrecComdatSectionIterator<'data, 'file, R> {
+    type Item = SectionIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
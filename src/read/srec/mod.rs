@@ -0,0 +1,31 @@
+//! Support for reading Motorola S-record files.
+//!
+//! S19, S28 and S37 files are supported, and the checksum of each record is
+//! validated.
+//!
+//! ## High level API
+//!
+//! [`SrecFile`] implements the [`Object`](crate::read::Object) trait for
+//! S-record files. Each contiguous range of data is a section and a segment,
+//! and the section data is decoded by
+//! [`ObjectSection::uncompressed_data`](crate::read::ObjectSection::uncompressed_data).
+//! The entry point is given by the termination record. S-record files have no
+//! symbols or relocations.
+//!
+//! ## Low level API
+//!
+//! [`SrecFile`] also provides access to the header record ([`SrecFile::header`]),
+//! the count record ([`SrecFile::record_count`]), and the termination record
+//! ([`SrecFile::start_address`]), and [`SrecSection::contents`] borrows the
+//! decoded data of a section.
+mod file;
+pub use file::*;
+
+mod section;
+pub use section::*;
+
+mod symbol;
+pub use symbol::*;
+
+mod relocation;
+pub use relocation::*;
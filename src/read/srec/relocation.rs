@@ -0,0 +1,22 @@
+use crate::read::{ReadRef, Relocation};
+
+use super::SrecFile;
+
+/// An iterator for the relocations in an [`SrecSection`](super::SrecSection).
+///
+/// This is a stub that doesn't implement any functionality. S-record files
+/// have no relocations.
+#[derive(Debug)]
+pub struct SrecRelocationIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    pub(super) file: &'file SrecFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for SrecRelocationIterator<'data, 'file, R> {
+    type Item = (u64, Relocation);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
@@ -0,0 +1,133 @@
+use core::marker::PhantomData;
+
+use crate::read::{
+    self, Error, ObjectSymbol, ObjectSymbolTable, Result, SectionIndex, SymbolFlags, SymbolIndex,
+    SymbolKind, SymbolScope, SymbolSection,
+};
+
+/// A symbol in a [`SrecFile`](super::SrecFile).
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug, Clone)]
+pub struct SrecSymbol<'data> {
+    marker: PhantomData<&'data ()>,
+}
+
+impl<'data> read::private::Sealed for SrecSymbol<'data> {}
+
+impl<'data> ObjectSymbol<'data> for SrecSymbol<'data> {
+    #[inline]
+    fn index(&self) -> SymbolIndex {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn address(&self) -> u64 {
+        unreachable!();
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        unreachable!();
+    }
+
+    #[inline]
+    fn kind(&self) -> SymbolKind {
+        unreachable!();
+    }
+
+    #[inline]
+    fn section(&self) -> SymbolSection {
+        unreachable!();
+    }
+
+    #[inline]
+    fn is_undefined(&self) -> bool {
+        unreachable!();
+    }
+
+    #[inline]
+    fn is_definition(&self) -> bool {
+        unreachable!();
+    }
+
+    #[inline]
+    fn is_common(&self) -> bool {
+        unreachable!();
+    }
+
+    #[inline]
+    fn is_weak(&self) -> bool {
+        unreachable!();
+    }
+
+    #[inline]
+    fn scope(&self) -> SymbolScope {
+        unreachable!();
+    }
+
+    #[inline]
+    fn is_global(&self) -> bool {
+        unreachable!();
+    }
+
+    #[inline]
+    fn is_local(&self) -> bool {
+        unreachable!();
+    }
+
+    #[inline]
+    fn flags(&self) -> SymbolFlags<SectionIndex, SymbolIndex> {
+        unreachable!();
+    }
+}
+
+/// A symbol table in a [`SrecFile`](super::SrecFile).
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug, Clone, Copy)]
+pub struct SrecSymbolTable<'data, 'file> {
+    marker: PhantomData<&'file &'data ()>,
+}
+
+impl<'data, 'file> read::private::Sealed for SrecSymbolTable<'data, 'file> {}
+
+impl<'data, 'file> ObjectSymbolTable<'data> for SrecSymbolTable<'data, 'file> {
+    type Symbol = SrecSymbol<'data>;
+    type SymbolIterator = SrecSymbolIterator<'data, 'file>;
+
+    fn symbols(&self) -> Self::SymbolIterator {
+        SrecSymbolIterator::default()
+    }
+
+    fn symbol_by_index(&self, _index: SymbolIndex) -> Result<Self::Symbol> {
+        Err(Error("S-record files have no symbols"))
+    }
+}
+
+/// An iterator for the symbols in a [`SrecFile`](super::SrecFile).
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug, Default)]
+pub struct SrecSymbolIterator<'data, 'file> {
+    marker: PhantomData<&'file &'data ()>,
+}
+
+impl<'data, 'file> Iterator for SrecSymbolIterator<'data, 'file> {
+    type Item = SrecSymbol<'data>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
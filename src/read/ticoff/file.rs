@@ -0,0 +1,576 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::endian::{Endian, Endianness, U16Bytes, U32Bytes};
+use crate::read::{
+    self, Architecture, ComdatKind, Error, Export, FileFlags, Import, NoDynamicRelocationIterator,
+    Object, ObjectComdat, ObjectKind, ObjectSection, ReadError, ReadRef, Result, SectionIndex,
+    StringTable, SymbolIndex,
+};
+use crate::ticoff;
+
+use super::{
+    TiCoffSection, TiCoffSectionHeader, TiCoffSectionIterator, TiCoffSegment,
+    TiCoffSegmentIterator, TiCoffSymbol, TiCoffSymbolIterator, TiCoffSymbolTable,
+};
+
+/// The file header of a [`TiCoffFile`].
+#[derive(Debug, Clone, Copy)]
+pub enum TiCoffFileHeader<'data> {
+    /// The file header of a COFF0 file.
+    V0(&'data ticoff::FileHeaderV0<Endianness>),
+    /// The file header of a COFF1 or COFF2 file.
+    V1(&'data ticoff::FileHeader<Endianness>),
+}
+
+impl<'data> TiCoffFileHeader<'data> {
+    /// Return the TI COFF version: 0, 1 or 2.
+    pub fn version(&self, endian: Endianness) -> u16 {
+        match self {
+            TiCoffFileHeader::V0(_) => 0,
+            TiCoffFileHeader::V1(header) => header.f_version.get(endian) - ticoff::TI_COFF1 + 1,
+        }
+    }
+
+    /// Return the target ID. One of the `ticoff::TI_TARGET_*` constants.
+    pub fn target_id(&self, endian: Endianness) -> u16 {
+        match self {
+            TiCoffFileHeader::V0(header) => header.f_target_id.get(endian),
+            TiCoffFileHeader::V1(header) => header.f_target_id.get(endian),
+        }
+    }
+
+    /// Return the number of section headers.
+    pub fn number_of_sections(&self, endian: Endianness) -> u16 {
+        match self {
+            TiCoffFileHeader::V0(header) => header.f_nscns.get(endian),
+            TiCoffFileHeader::V1(header) => header.f_nscns.get(endian),
+        }
+    }
+
+    /// Return the file offset of the symbol table.
+    pub fn symbol_table_offset(&self, endian: Endianness) -> u32 {
+        match self {
+            TiCoffFileHeader::V0(header) => header.f_symptr.get(endian),
+            TiCoffFileHeader::V1(header) => header.f_symptr.get(endian),
+        }
+    }
+
+    /// Return the number of entries in the symbol table.
+    pub fn number_of_symbols(&self, endian: Endianness) -> u32 {
+        match self {
+            TiCoffFileHeader::V0(header) => header.f_nsyms.get(endian),
+            TiCoffFileHeader::V1(header) => header.f_nsyms.get(endian),
+        }
+    }
+
+    /// Return the size of the optional header.
+    pub fn optional_header_size(&self, endian: Endianness) -> u16 {
+        match self {
+            TiCoffFileHeader::V0(header) => header.f_opthdr.get(endian),
+            TiCoffFileHeader::V1(header) => header.f_opthdr.get(endian),
+        }
+    }
+
+    /// Return the flags. A combination of the `ticoff::F_*` constants.
+    pub fn flags(&self, endian: Endianness) -> u16 {
+        match self {
+            TiCoffFileHeader::V0(header) => header.f_flags.get(endian),
+            TiCoffFileHeader::V1(header) => header.f_flags.get(endian),
+        }
+    }
+
+    /// Return the size of the file header.
+    fn size(&self) -> u64 {
+        match self {
+            TiCoffFileHeader::V0(_) => {
+                core::mem::size_of::<ticoff::FileHeaderV0<Endianness>>() as u64
+            }
+            TiCoffFileHeader::V1(_) => {
+                core::mem::size_of::<ticoff::FileHeader<Endianness>>() as u64
+            }
+        }
+    }
+}
+
+/// A TI COFF object file or executable.
+///
+/// Most functions in the [`Object`] trait are implemented for this type.
+#[derive(Debug)]
+pub struct TiCoffFile<'data, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) data: R,
+    pub(super) endian: Endianness,
+    header: TiCoffFileHeader<'data>,
+    optional_header: Option<&'data ticoff::OptionalHeader<Endianness>>,
+    pub(super) sections: Vec<TiCoffSectionHeader<'data>>,
+    symbols: &'data [ticoff::Symbol<Endianness>],
+    pub(super) strings: StringTable<'data, R>,
+}
+
+impl<'data, R: ReadRef<'data>> TiCoffFile<'data, R> {
+    /// Parse the raw TI COFF file data.
+    pub fn parse(data: R) -> Result<Self> {
+        let (header, endian) = parse_header(data)?;
+        let mut offset = header.size();
+
+        let optional_header_size = header.optional_header_size(endian);
+        let mut optional_header = None;
+        if optional_header_size != 0 {
+            let opthdr = data
+                .read_at::<ticoff::OptionalHeader<Endianness>>(offset)
+                .read_error("Invalid TI COFF optional header size")?;
+            if opthdr.o_magic.get(endian) == ticoff::OPTIONAL_HEADER_MAGIC {
+                optional_header = Some(opthdr);
+            }
+            offset += u64::from(optional_header_size);
+        }
+
+        let nscns = header.number_of_sections(endian) as usize;
+        let sections = if header.version(endian) == 2 {
+            data.read_slice_at::<ticoff::SectionHeaderV2<Endianness>>(offset, nscns)
+                .read_error("Invalid TI COFF section headers")?
+                .iter()
+                .map(TiCoffSectionHeader::V2)
+                .collect()
+        } else {
+            data.read_slice_at::<ticoff::SectionHeader<Endianness>>(offset, nscns)
+                .read_error("Invalid TI COFF section headers")?
+                .iter()
+                .map(TiCoffSectionHeader::V1)
+                .collect()
+        };
+
+        // The symbol table may not be present.
+        let mut symbols = &[][..];
+        let mut strings = StringTable::default();
+        let symptr = header.symbol_table_offset(endian);
+        if symptr != 0 {
+            let mut offset = u64::from(symptr);
+            symbols = data
+                .read_slice(&mut offset, header.number_of_symbols(endian) as usize)
+                .read_error("Invalid TI COFF symbol table offset or size")?;
+            // The string table is optional if no names are stored in it.
+            if let Ok(size) = data.read_at::<U32Bytes<Endianness>>(offset) {
+                let end = offset
+                    .checked_add(size.get(endian).into())
+                    .read_error("Invalid TI COFF string table length")?;
+                strings = StringTable::new(data, offset, end);
+            }
+        }
+
+        Ok(TiCoffFile {
+            data,
+            endian,
+            header,
+            optional_header,
+            sections,
+            symbols,
+            strings,
+        })
+    }
+
+    /// Returns the endianness.
+    #[inline]
+    pub fn endian(&self) -> Endianness {
+        self.endian
+    }
+
+    /// Returns the raw TI COFF file data.
+    #[inline]
+    pub fn data(&self) -> R {
+        self.data
+    }
+
+    /// Returns the file header.
+    #[inline]
+    pub fn file_header(&self) -> TiCoffFileHeader<'data> {
+        self.header
+    }
+
+    /// Returns the TI COFF version: 0, 1 or 2.
+    #[inline]
+    pub fn version(&self) -> u16 {
+        self.header.version(self.endian)
+    }
+
+    /// Returns the target ID. One of the `ticoff::TI_TARGET_*` constants.
+    #[inline]
+    pub fn target_id(&self) -> u16 {
+        self.header.target_id(self.endian)
+    }
+
+    /// Returns the optional header, if present.
+    #[inline]
+    pub fn optional_header(&self) -> Option<&'data ticoff::OptionalHeader<Endianness>> {
+        self.optional_header
+    }
+
+    /// Returns the section headers.
+    ///
+    /// Warning: section indices start at 1.
+    #[inline]
+    pub fn section_headers(&self) -> &[TiCoffSectionHeader<'data>] {
+        &self.sections
+    }
+
+    /// Returns the raw symbol table entries, including auxiliary entries.
+    #[inline]
+    pub fn raw_symbols(&self) -> &'data [ticoff::Symbol<Endianness>] {
+        self.symbols
+    }
+
+    /// Returns the string table.
+    #[inline]
+    pub fn strings(&self) -> StringTable<'data, R> {
+        self.strings
+    }
+
+    /// Return the number of bytes in each addressable unit of the target.
+    ///
+    /// Addresses and section sizes are in addressable units, which are 16 bits
+    /// for the C2800 and C5400 targets.
+    pub fn octets_per_byte(&self) -> u64 {
+        match self.target_id() {
+            ticoff::TI_TARGET_C2800 | ticoff::TI_TARGET_C5400 => 2,
+            _ => 1,
+        }
+    }
+
+    pub(super) fn section_header(&self, index: SectionIndex) -> Result<TiCoffSectionHeader<'data>> {
+        index
+            .0
+            .checked_sub(1)
+            .and_then(|index| self.sections.get(index))
+            .copied()
+            .read_error("Invalid TI COFF section index")
+    }
+
+    fn symbol_table_internal(&self) -> TiCoffSymbolTable<'data, '_, R> {
+        TiCoffSymbolTable {
+            symbols: self.symbols,
+            strings: self.strings,
+            sections: &self.sections,
+            endian: self.endian,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'data, R: ReadRef<'data>> read::private::Sealed for TiCoffFile<'data, R> {}
+
+impl<'data, R: ReadRef<'data>> Object<'data> for TiCoffFile<'data, R> {
+    type Segment<'file>
+        = TiCoffSegment<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SegmentIterator<'file>
+        = TiCoffSegmentIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Section<'file>
+        = TiCoffSection<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SectionIterator<'file>
+        = TiCoffSectionIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Comdat<'file>
+        = TiCoffComdat<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type ComdatIterator<'file>
+        = TiCoffComdatIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Symbol<'file>
+        = TiCoffSymbol<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolIterator<'file>
+        = TiCoffSymbolIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolTable<'file>
+        = TiCoffSymbolTable<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type DynamicRelocationIterator<'file>
+        = NoDynamicRelocationIterator
+    where
+        Self: 'file,
+        'data: 'file;
+
+    fn architecture(&self) -> Architecture {
+        match self.target_id() {
+            ticoff::TI_TARGET_TMS470 => Architecture::Arm,
+            ticoff::TI_TARGET_C6000 => Architecture::TiC6000,
+            ticoff::TI_TARGET_C2800 => Architecture::TiC2000,
+            ticoff::TI_TARGET_MSP430 => Architecture::Msp430,
+            _ => Architecture::Unknown,
+        }
+    }
+
+    #[inline]
+    fn is_little_endian(&self) -> bool {
+        self.endian.is_little_endian()
+    }
+
+    #[inline]
+    fn is_64(&self) -> bool {
+        false
+    }
+
+    fn kind(&self) -> ObjectKind {
+        if self.header.flags(self.endian) & ticoff::F_EXEC != 0 {
+            ObjectKind::Executable
+        } else {
+            ObjectKind::Relocatable
+        }
+    }
+
+    fn segments(&self) -> TiCoffSegmentIterator<'data, '_, R> {
+        TiCoffSegmentIterator {
+            file: self,
+            index: 1,
+        }
+    }
+
+    fn section_by_name_bytes<'file>(
+        &'file self,
+        section_name: &[u8],
+    ) -> Option<TiCoffSection<'data, 'file, R>> {
+        self.sections()
+            .find(|section| section.name_bytes() == Ok(section_name))
+    }
+
+    fn section_by_index(&self, index: SectionIndex) -> Result<TiCoffSection<'data, '_, R>> {
+        let header = self.section_header(index)?;
+        Ok(TiCoffSection {
+            file: self,
+            index,
+            header,
+        })
+    }
+
+    fn sections(&self) -> TiCoffSectionIterator<'data, '_, R> {
+        TiCoffSectionIterator {
+            file: self,
+            index: 1,
+        }
+    }
+
+    #[inline]
+    fn comdats(&self) -> TiCoffComdatIterator<'data, '_, R> {
+        TiCoffComdatIterator { file: self }
+    }
+
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<TiCoffSymbol<'data, '_, R>> {
+        use crate::read::ObjectSymbolTable;
+        self.symbol_table_internal().symbol_by_index(index)
+    }
+
+    fn symbols(&self) -> TiCoffSymbolIterator<'data, '_, R> {
+        TiCoffSymbolIterator {
+            table: self.symbol_table_internal(),
+            index: 0,
+        }
+    }
+
+    #[inline]
+    fn symbol_table(&self) -> Option<TiCoffSymbolTable<'data, '_, R>> {
+        Some(self.symbol_table_internal())
+    }
+
+    fn dynamic_symbols(&self) -> TiCoffSymbolIterator<'data, '_, R> {
+        TiCoffSymbolIterator {
+            table: TiCoffSymbolTable {
+                symbols: &[],
+                strings: StringTable::default(),
+                sections: &[],
+                endian: self.endian,
+                marker: PhantomData,
+            },
+            index: 0,
+        }
+    }
+
+    #[inline]
+    fn dynamic_symbol_table(&self) -> Option<TiCoffSymbolTable<'data, '_, R>> {
+        None
+    }
+
+    #[inline]
+    fn dynamic_relocations(&self) -> Option<NoDynamicRelocationIterator> {
+        None
+    }
+
+    fn imports(&self) -> Result<Vec<Import<'data>>> {
+        // TI COFF files don't support dynamic linking.
+        Ok(Vec::new())
+    }
+
+    fn exports(&self) -> Result<Vec<Export<'data>>> {
+        // TI COFF files don't support dynamic linking.
+        Ok(Vec::new())
+    }
+
+    fn has_debug_symbols(&self) -> bool {
+        self.section_by_name(".debug_info").is_some()
+    }
+
+    #[inline]
+    fn relative_address_base(&self) -> u64 {
+        0
+    }
+
+    fn entry(&self) -> u64 {
+        match self.optional_header {
+            Some(header) => header.o_entry.get(self.endian).into(),
+            None => 0,
+        }
+    }
+
+    #[inline]
+    fn flags(&self) -> FileFlags {
+        FileFlags::None
+    }
+}
+
+/// Read the TI COFF file header, and determine its endianness.
+fn parse_header<'data, R: ReadRef<'data>>(
+    data: R,
+) -> Result<(TiCoffFileHeader<'data>, Endianness)> {
+    let magic = data
+        .read_at::<U16Bytes<Endianness>>(0)
+        .read_error("Invalid TI COFF file header size")?;
+    for endian in [Endianness::Little, Endianness::Big] {
+        match magic.get(endian) {
+            ticoff::TI_COFF1 | ticoff::TI_COFF2 => {
+                let header = data
+                    .read_at::<ticoff::FileHeader<Endianness>>(0)
+                    .read_error("Invalid TI COFF file header size")?;
+                if is_target_id(header.f_target_id.get(endian)) {
+                    return Ok((TiCoffFileHeader::V1(header), endian));
+                }
+            }
+            target_id if is_target_id(target_id) => {
+                let header = data
+                    .read_at::<ticoff::FileHeaderV0<Endianness>>(0)
+                    .read_error("Invalid TI COFF file header size")?;
+                return Ok((TiCoffFileHeader::V0(header), endian));
+            }
+            _ => {}
+        }
+    }
+    Err(Error("Unsupported TI COFF version or target"))
+}
+
+/// Return true if this is a known TI COFF target ID.
+fn is_target_id(target_id: u16) -> bool {
+    matches!(
+        target_id,
+        ticoff::TI_TARGET_TMS470
+            | ticoff::TI_TARGET_C5400
+            | ticoff::TI_TARGET_C6000
+            | ticoff::TI_TARGET_C5500
+            | ticoff::TI_TARGET_C2800
+            | ticoff::TI_TARGET_MSP430
+            | ticoff::TI_TARGET_C5500_PLUS
+    )
+}
+
+/// Check whether the data looks like a TI COFF file.
+///
+/// This requires a known version ID and target ID, and that the optional
+/// header is either absent or has the expected size.
+pub(crate) fn is_ticoff_file<'data, R: ReadRef<'data>>(data: R) -> bool {
+    match parse_header(data) {
+        Ok((header, endian)) => matches!(header.optional_header_size(endian), 0 | 28),
+        Err(_) => false,
+    }
+}
+
+/// An iterator for the COMDAT section groups in a [`TiCoffFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct TiCoffComdatIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file TiCoffFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for TiCoffComdatIterator<'data, 'file, R> {
+    type Item = TiCoffComdat<'data, 'file, R>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+/// A COMDAT section group in a [`TiCoffFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct TiCoffComdat<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file TiCoffFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for TiCoffComdat<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectComdat<'data> for TiCoffComdat<'data, 'file, R> {
+    type SectionIterator = TiCoffComdatSectionIterator<'data, 'file, R>;
+
+    #[inline]
+    fn kind(&self) -> ComdatKind {
+        unreachable!();
+    }
+
+    #[inline]
+    fn symbol(&self) -> SymbolIndex {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn sections(&self) -> Self::SectionIterator {
+        unreachable!();
+    }
+}
+
+/// An iterator for the sections in a COMDAT section group in a [`TiCoffFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct TiCoffComdatSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file TiCoffFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for TiCoffComdatSectionIterator<'data, 'file, R> {
+    type Item = SectionIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
@@ -0,0 +1,38 @@
+//! Support for reading TI COFF files.
+//!
+//! TI COFF is the variant of COFF used by the Texas Instruments toolchains for
+//! their DSPs and microcontrollers, such as the C2000 and C6000 families. The
+//! COFF0, COFF1 and COFF2 versions are supported, in either byte order.
+//!
+//! ## High level API
+//!
+//! [`TiCoffFile`] implements the [`Object`](crate::read::Object) trait for TI
+//! COFF files. Each section that is allocated in memory is also a segment.
+//! Addresses and sizes are in the addressable units of the target, which are
+//! 16 bits for some targets ([`TiCoffFile::octets_per_byte`]).
+//!
+//! The symbol table and relocations use the raw symbol table indices, so symbol
+//! indices in relocations can be passed to
+//! [`Object::symbol_by_index`](crate::read::Object::symbol_by_index).
+//! Auxiliary symbol table entries are skipped when iterating the symbol table.
+//!
+//! ## Low level API
+//!
+//! [`TiCoffFile`] also provides access to the file header
+//! ([`TiCoffFile::file_header`]), the optional header
+//! ([`TiCoffFile::optional_header`]), the section headers
+//! ([`TiCoffFile::section_headers`]), and the raw symbol table entries
+//! ([`TiCoffFile::raw_symbols`]). The headers differ between versions, so
+//! [`TiCoffFileHeader`] and [`TiCoffSectionHeader`] provide accessors for
+//! their fields.
+mod file;
+pub use file::*;
+
+mod section;
+pub use section::*;
+
+mod symbol;
+pub use symbol::*;
+
+mod relocation;
+pub use relocation::*;
@@ -0,0 +1,79 @@
+use core::slice;
+
+use crate::endian::Endianness;
+use crate::read::{
+    ReadRef, Relocation, RelocationEncoding, RelocationFlags, RelocationKind, RelocationTarget,
+    SymbolIndex,
+};
+use crate::ticoff;
+
+use super::TiCoffFile;
+
+/// The relocation entries of a section in a [`TiCoffFile`].
+#[derive(Debug)]
+pub(super) enum TiCoffRelocations<'data> {
+    V0(slice::Iter<'data, ticoff::RelocationV0<Endianness>>),
+    V1(slice::Iter<'data, ticoff::Relocation<Endianness>>),
+}
+
+/// An iterator for the relocations in a [`TiCoffSection`](super::TiCoffSection).
+///
+/// The addend is stored in the relocated location. Only the generic relocation
+/// types are mapped to a [`RelocationKind`]; the target specific types are
+/// available in [`RelocationFlags::Coff`].
+#[derive(Debug)]
+pub struct TiCoffRelocationIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file TiCoffFile<'data, R>,
+    pub(super) address: u64,
+    pub(super) relocations: TiCoffRelocations<'data>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for TiCoffRelocationIterator<'data, 'file, R> {
+    type Item = (u64, Relocation);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let endian = self.file.endian;
+        let (vaddr, symndx, typ) = match &mut self.relocations {
+            TiCoffRelocations::V0(iter) => {
+                let relocation = iter.next()?;
+                (
+                    relocation.r_vaddr.get(endian),
+                    u32::from(relocation.r_symndx.get(endian)),
+                    relocation.r_type.get(endian),
+                )
+            }
+            TiCoffRelocations::V1(iter) => {
+                let relocation = iter.next()?;
+                (
+                    relocation.r_vaddr.get(endian),
+                    relocation.r_symndx.get(endian),
+                    relocation.r_type.get(endian),
+                )
+            }
+        };
+        let (kind, size) = match typ {
+            ticoff::R_RELBYTE => (RelocationKind::Absolute, 8),
+            ticoff::R_RELWORD => (RelocationKind::Absolute, 16),
+            ticoff::R_RELLONG => (RelocationKind::Absolute, 32),
+            _ => (RelocationKind::Unknown, 0),
+        };
+        let target = if typ == ticoff::R_ABS {
+            RelocationTarget::Absolute
+        } else {
+            RelocationTarget::Symbol(SymbolIndex(symndx as usize))
+        };
+        let offset = u64::from(vaddr).wrapping_sub(self.address) * self.file.octets_per_byte();
+        Some((
+            offset,
+            Relocation {
+                kind,
+                encoding: RelocationEncoding::Generic,
+                size,
+                target,
+                addend: 0,
+                implicit_addend: true,
+                flags: RelocationFlags::Coff { typ },
+            },
+        ))
+    }
+}
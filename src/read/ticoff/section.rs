@@ -0,0 +1,388 @@
+use core::str;
+
+use crate::endian::{Endian, Endianness};
+use crate::read::{
+    self, CompressedData, CompressedFileRange, ObjectSection, ObjectSegment, ReadError, ReadRef,
+    RelocationMap, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags,
+};
+use crate::ticoff;
+
+use super::{TiCoffFile, TiCoffRelocationIterator, TiCoffRelocations};
+
+/// A section header in a [`TiCoffFile`].
+#[derive(Debug, Clone, Copy)]
+pub enum TiCoffSectionHeader<'data> {
+    /// A section header in a COFF0 or COFF1 file.
+    V1(&'data ticoff::SectionHeader<Endianness>),
+    /// A section header in a COFF2 file.
+    V2(&'data ticoff::SectionHeaderV2<Endianness>),
+}
+
+impl<'data> TiCoffSectionHeader<'data> {
+    /// Return the raw section name.
+    pub fn raw_name(&self) -> &'data [u8; 8] {
+        match self {
+            TiCoffSectionHeader::V1(header) => &header.s_name,
+            TiCoffSectionHeader::V2(header) => &header.s_name,
+        }
+    }
+
+    /// Return the virtual address.
+    pub fn virtual_address(&self, endian: Endianness) -> u32 {
+        match self {
+            TiCoffSectionHeader::V1(header) => header.s_vaddr.get(endian),
+            TiCoffSectionHeader::V2(header) => header.s_vaddr.get(endian),
+        }
+    }
+
+    /// Return the section size, in addressable units of the target.
+    pub fn size(&self, endian: Endianness) -> u32 {
+        match self {
+            TiCoffSectionHeader::V1(header) => header.s_size.get(endian),
+            TiCoffSectionHeader::V2(header) => header.s_size.get(endian),
+        }
+    }
+
+    /// Return the file offset of the section data.
+    pub fn data_offset(&self, endian: Endianness) -> u32 {
+        match self {
+            TiCoffSectionHeader::V1(header) => header.s_scnptr.get(endian),
+            TiCoffSectionHeader::V2(header) => header.s_scnptr.get(endian),
+        }
+    }
+
+    /// Return the file offset of the relocation entries.
+    pub fn relocation_offset(&self, endian: Endianness) -> u32 {
+        match self {
+            TiCoffSectionHeader::V1(header) => header.s_relptr.get(endian),
+            TiCoffSectionHeader::V2(header) => header.s_relptr.get(endian),
+        }
+    }
+
+    /// Return the number of relocation entries.
+    pub fn number_of_relocations(&self, endian: Endianness) -> u32 {
+        match self {
+            TiCoffSectionHeader::V1(header) => header.s_nreloc.get(endian).into(),
+            TiCoffSectionHeader::V2(header) => header.s_nreloc.get(endian),
+        }
+    }
+
+    /// Return the flags. A combination of the `ticoff::STYP_*` constants.
+    pub fn flags(&self, endian: Endianness) -> u32 {
+        match self {
+            TiCoffSectionHeader::V1(header) => header.s_flags.get(endian).into(),
+            TiCoffSectionHeader::V2(header) => header.s_flags.get(endian),
+        }
+    }
+
+    /// Return the memory page number.
+    pub fn page(&self, endian: Endianness) -> u16 {
+        match self {
+            TiCoffSectionHeader::V1(header) => header.s_page.into(),
+            TiCoffSectionHeader::V2(header) => header.s_page.get(endian),
+        }
+    }
+
+    /// Return true if the section has data in the file.
+    pub fn has_data(&self, endian: Endianness) -> bool {
+        let flags = self.flags(endian);
+        flags & (ticoff::STYP_BSS | ticoff::STYP_DSECT | ticoff::STYP_NOLOAD) == 0
+            && self.data_offset(endian) != 0
+    }
+
+    /// Return true if the section is allocated in memory.
+    pub fn is_allocated(&self, endian: Endianness) -> bool {
+        let flags = self.flags(endian);
+        flags & (ticoff::STYP_COPY | ticoff::STYP_DSECT | ticoff::STYP_NOLOAD) == 0
+    }
+}
+
+/// An iterator for the sections in a [`TiCoffFile`].
+#[derive(Debug)]
+pub struct TiCoffSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file TiCoffFile<'data, R>,
+    pub(super) index: usize,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for TiCoffSectionIterator<'data, 'file, R> {
+    type Item = TiCoffSection<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = SectionIndex(self.index);
+        let header = self.file.section_header(index).ok()?;
+        self.index += 1;
+        Some(TiCoffSection {
+            file: self.file,
+            index,
+            header,
+        })
+    }
+}
+
+/// A section in a [`TiCoffFile`].
+///
+/// The address and size are in addressable units of the target, which may be
+/// larger than a byte. See [`TiCoffFile::octets_per_byte`].
+#[derive(Debug)]
+pub struct TiCoffSection<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file TiCoffFile<'data, R>,
+    pub(super) index: SectionIndex,
+    pub(super) header: TiCoffSectionHeader<'data>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> TiCoffSection<'data, 'file, R> {
+    /// Get the section header.
+    #[inline]
+    pub fn ticoff_header(&self) -> TiCoffSectionHeader<'data> {
+        self.header
+    }
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for TiCoffSection<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSection<'data> for TiCoffSection<'data, 'file, R> {
+    type RelocationIterator = TiCoffRelocationIterator<'data, 'file, R>;
+
+    #[inline]
+    fn index(&self) -> SectionIndex {
+        self.index
+    }
+
+    #[inline]
+    fn address(&self) -> u64 {
+        self.header.virtual_address(self.file.endian).into()
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.header.size(self.file.endian).into()
+    }
+
+    fn align(&self) -> u64 {
+        let flags = self.header.flags(self.file.endian);
+        1 << ((flags & ticoff::STYP_ALIGN) >> ticoff::STYP_ALIGN_SHIFT)
+    }
+
+    fn file_range(&self) -> Option<(u64, u64)> {
+        let endian = self.file.endian;
+        if !self.header.has_data(endian) {
+            return None;
+        }
+        Some((
+            self.header.data_offset(endian).into(),
+            self.size() * self.file.octets_per_byte(),
+        ))
+    }
+
+    fn data(&self) -> Result<&'data [u8]> {
+        match self.file_range() {
+            Some((offset, size)) => self
+                .file
+                .data
+                .read_bytes_at(offset, size)
+                .read_error("Invalid TI COFF section offset or size"),
+            None => Ok(&[]),
+        }
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        Ok(read::util::data_range(
+            self.data()?,
+            self.address(),
+            address,
+            size,
+        ))
+    }
+
+    #[inline]
+    fn compressed_file_range(&self) -> Result<CompressedFileRange> {
+        Ok(CompressedFileRange::none(self.file_range()))
+    }
+
+    #[inline]
+    fn compressed_data(&self) -> Result<CompressedData<'data>> {
+        self.data().map(CompressedData::none)
+    }
+
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        let name = self.header.raw_name();
+        if name[..4] == [0; 4] {
+            let offset = self
+                .file
+                .endian
+                .read_u32_bytes([name[4], name[5], name[6], name[7]]);
+            self.file
+                .strings
+                .get(offset)
+                .read_error("Invalid TI COFF section name offset")
+        } else {
+            Ok(match memchr::memchr(b'\0', name) {
+                Some(end) => &name[..end],
+                None => &name[..],
+            })
+        }
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name_bytes()?)
+            .ok()
+            .read_error("Non UTF-8 TI COFF section name")
+    }
+
+    #[inline]
+    fn segment_name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn segment_name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    fn kind(&self) -> SectionKind {
+        let flags = self.header.flags(self.file.endian);
+        if flags & ticoff::STYP_TEXT != 0 {
+            SectionKind::Text
+        } else if flags & ticoff::STYP_DATA != 0 {
+            SectionKind::Data
+        } else if flags & ticoff::STYP_BSS != 0 {
+            SectionKind::UninitializedData
+        } else if self
+            .name_bytes()
+            .map_or(false, |name| name.starts_with(b".debug"))
+        {
+            SectionKind::Debug
+        } else if flags & ticoff::STYP_COPY != 0 {
+            SectionKind::Other
+        } else {
+            SectionKind::Unknown
+        }
+    }
+
+    fn relocations(&self) -> TiCoffRelocationIterator<'data, 'file, R> {
+        let endian = self.file.endian;
+        let offset = u64::from(self.header.relocation_offset(endian));
+        let count = self.header.number_of_relocations(endian) as usize;
+        let relocations = if self.file.version() == 0 {
+            TiCoffRelocations::V0(
+                self.file
+                    .data
+                    .read_slice_at(offset, count)
+                    .unwrap_or(&[])
+                    .iter(),
+            )
+        } else {
+            TiCoffRelocations::V1(
+                self.file
+                    .data
+                    .read_slice_at(offset, count)
+                    .unwrap_or(&[])
+                    .iter(),
+            )
+        };
+        TiCoffRelocationIterator {
+            file: self.file,
+            address: self.address(),
+            relocations,
+        }
+    }
+
+    fn relocation_map(&self) -> Result<RelocationMap> {
+        RelocationMap::new(self.file, self)
+    }
+
+    #[inline]
+    fn flags(&self) -> SectionFlags {
+        SectionFlags::Coff {
+            characteristics: self.header.flags(self.file.endian),
+        }
+    }
+}
+
+/// An iterator for the segments in a [`TiCoffFile`].
+///
+/// Sections that are not allocated in memory are skipped.
+#[derive(Debug)]
+pub struct TiCoffSegmentIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file TiCoffFile<'data, R>,
+    pub(super) index: usize,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for TiCoffSegmentIterator<'data, 'file, R> {
+    type Item = TiCoffSegment<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let index = SectionIndex(self.index);
+            let header = self.file.section_header(index).ok()?;
+            self.index += 1;
+            if header.is_allocated(self.file.endian) {
+                return Some(TiCoffSegment {
+                    section: TiCoffSection {
+                        file: self.file,
+                        index,
+                        header,
+                    },
+                });
+            }
+        }
+    }
+}
+
+/// A segment in a [`TiCoffFile`].
+///
+/// This is a section that is allocated in memory.
+#[derive(Debug)]
+pub struct TiCoffSegment<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    section: TiCoffSection<'data, 'file, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for TiCoffSegment<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSegment<'data> for TiCoffSegment<'data, 'file, R> {
+    #[inline]
+    fn address(&self) -> u64 {
+        self.section.address()
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.section.size()
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        self.section.align()
+    }
+
+    #[inline]
+    fn file_range(&self) -> (u64, u64) {
+        self.section.file_range().unwrap_or((0, 0))
+    }
+
+    fn data(&self) -> Result<&'data [u8]> {
+        self.section.data()
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        self.section.data_range(address, size)
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<Option<&[u8]>> {
+        self.section.name_bytes().map(Some)
+    }
+
+    #[inline]
+    fn name(&self) -> Result<Option<&str>> {
+        self.section.name().map(Some)
+    }
+
+    #[inline]
+    fn flags(&self) -> SegmentFlags {
+        SegmentFlags::Coff {
+            characteristics: self.section.header.flags(self.section.file.endian),
+        }
+    }
+}
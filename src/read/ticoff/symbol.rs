@@ -0,0 +1,257 @@
+use core::marker::PhantomData;
+use core::str;
+
+use crate::endian::{Endian, Endianness};
+use crate::read::{
+    self, ObjectSymbol, ObjectSymbolTable, ReadError, ReadRef, Result, SectionIndex, StringTable,
+    SymbolFlags, SymbolIndex, SymbolKind, SymbolScope, SymbolSection,
+};
+use crate::ticoff;
+
+use super::TiCoffSectionHeader;
+
+/// A symbol table in a [`TiCoffFile`](super::TiCoffFile).
+#[derive(Debug, Clone, Copy)]
+pub struct TiCoffSymbolTable<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) symbols: &'data [ticoff::Symbol<Endianness>],
+    pub(super) strings: StringTable<'data, R>,
+    pub(super) sections: &'file [TiCoffSectionHeader<'data>],
+    pub(super) endian: Endianness,
+    pub(super) marker: PhantomData<&'file ()>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for TiCoffSymbolTable<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSymbolTable<'data>
+    for TiCoffSymbolTable<'data, 'file, R>
+{
+    type Symbol = TiCoffSymbol<'data, 'file, R>;
+    type SymbolIterator = TiCoffSymbolIterator<'data, 'file, R>;
+
+    fn symbols(&self) -> Self::SymbolIterator {
+        TiCoffSymbolIterator {
+            table: *self,
+            index: 0,
+        }
+    }
+
+    /// Get the symbol at the given index.
+    ///
+    /// The index must not be an auxiliary entry.
+    fn symbol_by_index(&self, index: SymbolIndex) -> Result<Self::Symbol> {
+        let symbol = self
+            .symbols
+            .get(index.0)
+            .read_error("Invalid TI COFF symbol index")?;
+        Ok(TiCoffSymbol {
+            table: *self,
+            index,
+            symbol,
+        })
+    }
+}
+
+/// An iterator for the symbols in a [`TiCoffFile`](super::TiCoffFile).
+///
+/// Auxiliary entries are skipped.
+#[derive(Debug)]
+pub struct TiCoffSymbolIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) table: TiCoffSymbolTable<'data, 'file, R>,
+    pub(super) index: usize,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for TiCoffSymbolIterator<'data, 'file, R> {
+    type Item = TiCoffSymbol<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.index;
+        let symbol = self.table.symbols.get(index)?;
+        self.index += 1 + usize::from(symbol.n_numaux);
+        Some(TiCoffSymbol {
+            table: self.table,
+            index: SymbolIndex(index),
+            symbol,
+        })
+    }
+}
+
+/// A symbol in a [`TiCoffFile`](super::TiCoffFile).
+#[derive(Debug, Clone, Copy)]
+pub struct TiCoffSymbol<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    table: TiCoffSymbolTable<'data, 'file, R>,
+    index: SymbolIndex,
+    symbol: &'data ticoff::Symbol<Endianness>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> TiCoffSymbol<'data, 'file, R> {
+    /// Return the raw symbol table entry.
+    #[inline]
+    pub fn raw_symbol(&self) -> &'data ticoff::Symbol<Endianness> {
+        self.symbol
+    }
+
+    fn value(&self) -> u64 {
+        self.symbol.n_value.get(self.table.endian).into()
+    }
+
+    fn section_number(&self) -> i16 {
+        self.symbol.n_scnum.get(self.table.endian)
+    }
+
+    fn is_external(&self) -> bool {
+        matches!(
+            self.symbol.n_sclass,
+            ticoff::C_EXT | ticoff::C_EXTREF | ticoff::C_EXTLAB
+        )
+    }
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for TiCoffSymbol<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSymbol<'data> for TiCoffSymbol<'data, 'file, R> {
+    #[inline]
+    fn index(&self) -> SymbolIndex {
+        self.index
+    }
+
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        let name = &self.symbol.n_name;
+        if name[..4] == [0; 4] {
+            let offset = self
+                .table
+                .endian
+                .read_u32_bytes([name[4], name[5], name[6], name[7]]);
+            self.table
+                .strings
+                .get(offset)
+                .read_error("Invalid TI COFF symbol name offset")
+        } else {
+            Ok(match memchr::memchr(b'\0', name) {
+                Some(end) => &name[..end],
+                None => &name[..],
+            })
+        }
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        let name = self.name_bytes()?;
+        str::from_utf8(name)
+            .ok()
+            .read_error("Non UTF-8 TI COFF symbol name")
+    }
+
+    fn address(&self) -> u64 {
+        match self.section() {
+            SymbolSection::Section(_) | SymbolSection::Absolute => self.value(),
+            _ => 0,
+        }
+    }
+
+    /// Return the size of a common symbol.
+    ///
+    /// TI COFF does not record the size of other symbols.
+    fn size(&self) -> u64 {
+        if self.is_common() {
+            self.value()
+        } else {
+            0
+        }
+    }
+
+    fn kind(&self) -> SymbolKind {
+        match self.symbol.n_sclass {
+            ticoff::C_FILE => SymbolKind::File,
+            ticoff::C_EXT
+            | ticoff::C_EXTREF
+            | ticoff::C_EXTLAB
+            | ticoff::C_STAT
+            | ticoff::C_STATLAB
+            | ticoff::C_LABEL => match self.section() {
+                SymbolSection::Section(index) => {
+                    let section = match self.table.sections.get(index.0 - 1) {
+                        Some(section) => section,
+                        None => return SymbolKind::Unknown,
+                    };
+                    let flags = section.flags(self.table.endian);
+                    if self.symbol.n_sclass == ticoff::C_STAT
+                        && self.symbol.n_numaux != 0
+                        && self.value() == u64::from(section.virtual_address(self.table.endian))
+                    {
+                        SymbolKind::Section
+                    } else if self.symbol.n_sclass == ticoff::C_LABEL {
+                        SymbolKind::Label
+                    } else if flags & ticoff::STYP_TEXT != 0 {
+                        SymbolKind::Text
+                    } else {
+                        SymbolKind::Data
+                    }
+                }
+                SymbolSection::Common => SymbolKind::Data,
+                _ => SymbolKind::Unknown,
+            },
+            _ => SymbolKind::Unknown,
+        }
+    }
+
+    fn section(&self) -> SymbolSection {
+        if self.symbol.n_sclass == ticoff::C_FILE {
+            return SymbolSection::None;
+        }
+        match self.section_number() {
+            ticoff::N_UNDEF if self.is_external() && self.value() != 0 => SymbolSection::Common,
+            ticoff::N_UNDEF => SymbolSection::Undefined,
+            ticoff::N_ABS => SymbolSection::Absolute,
+            ticoff::N_DEBUG => SymbolSection::None,
+            index if index > 0 => SymbolSection::Section(SectionIndex(index as usize)),
+            _ => SymbolSection::Unknown,
+        }
+    }
+
+    #[inline]
+    fn is_undefined(&self) -> bool {
+        self.section() == SymbolSection::Undefined
+    }
+
+    fn is_definition(&self) -> bool {
+        matches!(
+            self.section(),
+            SymbolSection::Section(_) | SymbolSection::Absolute
+        ) && self.kind() != SymbolKind::Section
+    }
+
+    #[inline]
+    fn is_common(&self) -> bool {
+        self.section() == SymbolSection::Common
+    }
+
+    #[inline]
+    fn is_weak(&self) -> bool {
+        false
+    }
+
+    fn scope(&self) -> SymbolScope {
+        if self.is_undefined() {
+            SymbolScope::Unknown
+        } else if self.is_external() {
+            SymbolScope::Linkage
+        } else {
+            SymbolScope::Compilation
+        }
+    }
+
+    #[inline]
+    fn is_global(&self) -> bool {
+        self.is_external()
+    }
+
+    #[inline]
+    fn is_local(&self) -> bool {
+        !self.is_global()
+    }
+
+    #[inline]
+    fn flags(&self) -> SymbolFlags<SectionIndex, SymbolIndex> {
+        SymbolFlags::None
+    }
+}
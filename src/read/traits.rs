@@ -275,7 +275,8 @@ pub trait Object<'data>: read::private::Sealed {
 
     /// Construct a map from addresses to symbol names and object file names.
     ///
-    /// This is derived from Mach-O STAB entries.
+    /// This is derived from Mach-O STAB entries, and from OMF public symbols
+    /// and line numbers.
     fn object_map(&self) -> ObjectMap<'data> {
         ObjectMap::default()
     }
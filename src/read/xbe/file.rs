@@ -0,0 +1,487 @@
+use alloc::vec::Vec;
+
+use crate::endian::{LittleEndian as LE, U32Bytes};
+use crate::read::{
+    self, Architecture, ComdatKind, Error, Export, FileFlags, Import, NoDynamicRelocationIterator,
+    Object, ObjectComdat, ObjectKind, ObjectSection, ReadError, ReadRef, Result, SectionIndex,
+    SymbolIndex,
+};
+use crate::xbe;
+
+use super::{
+    XbeSection, XbeSectionIterator, XbeSegment, XbeSegmentIterator, XbeSymbol, XbeSymbolIterator,
+    XbeSymbolTable,
+};
+
+/// The kind of console that an [`XbeFile`] is for.
+///
+/// This determines the keys used to encode the entry point and the kernel
+/// thunk table address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XbeConsole {
+    /// A retail console.
+    Retail,
+    /// A debug kit.
+    Debug,
+    /// A Chihiro arcade system.
+    Chihiro,
+}
+
+impl XbeConsole {
+    fn entry_key(self) -> u32 {
+        match self {
+            XbeConsole::Retail => xbe::XBE_ENTRY_RETAIL_KEY,
+            XbeConsole::Debug => xbe::XBE_ENTRY_DEBUG_KEY,
+            XbeConsole::Chihiro => xbe::XBE_ENTRY_CHIHIRO_KEY,
+        }
+    }
+
+    fn kernel_thunk_key(self) -> u32 {
+        match self {
+            XbeConsole::Retail => xbe::XBE_KERNEL_THUNK_RETAIL_KEY,
+            XbeConsole::Debug => xbe::XBE_KERNEL_THUNK_DEBUG_KEY,
+            XbeConsole::Chihiro => xbe::XBE_KERNEL_THUNK_CHIHIRO_KEY,
+        }
+    }
+}
+
+/// A kernel function imported by an [`XbeFile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XbeKernelImport {
+    /// The ordinal of the function in the kernel export table.
+    pub ordinal: u32,
+    /// The address of the kernel thunk table entry.
+    ///
+    /// The loader replaces the entry with the address of the function.
+    pub address: u64,
+}
+
+/// An original Xbox executable.
+///
+/// Most functions in the [`Object`] trait are implemented for this type.
+#[derive(Debug)]
+pub struct XbeFile<'data, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) data: R,
+    header: &'data xbe::ImageHeader,
+    pub(super) sections: &'data [xbe::SectionHeader],
+    console: XbeConsole,
+}
+
+impl<'data, R: ReadRef<'data>> XbeFile<'data, R> {
+    /// Parse the raw XBE file data.
+    pub fn parse(data: R) -> Result<Self> {
+        let header = data
+            .read_at::<xbe::ImageHeader>(0)
+            .read_error("Invalid XBE header size or alignment")?;
+        if header.magic.get(LE) != xbe::XBE_MAGIC {
+            return Err(Error("Invalid XBE magic"));
+        }
+        let base_address = header.base_address.get(LE);
+
+        let sections = header
+            .section_headers_address
+            .get(LE)
+            .checked_sub(base_address)
+            .read_error("Invalid XBE section headers address")?;
+        let sections = data
+            .read_slice_at(sections.into(), header.number_of_sections.get(LE) as usize)
+            .read_error("Invalid XBE section headers size or alignment")?;
+
+        // The console is determined by which key decodes the entry point
+        // to an address within the image.
+        let entry_point = header.entry_point.get(LE);
+        let image_end = base_address.saturating_add(header.size_of_image.get(LE));
+        let console = [XbeConsole::Retail, XbeConsole::Debug, XbeConsole::Chihiro]
+            .iter()
+            .copied()
+            .find(|console| {
+                let entry_point = entry_point ^ console.entry_key();
+                entry_point >= base_address && entry_point < image_end
+            })
+            .unwrap_or(XbeConsole::Retail);
+
+        Ok(XbeFile {
+            data,
+            header,
+            sections,
+            console,
+        })
+    }
+
+    /// Returns the raw XBE file data.
+    #[inline]
+    pub fn data(&self) -> R {
+        self.data
+    }
+
+    /// Returns the image header.
+    #[inline]
+    pub fn image_header(&self) -> &'data xbe::ImageHeader {
+        self.header
+    }
+
+    /// Returns the section headers.
+    #[inline]
+    pub fn section_headers(&self) -> &'data [xbe::SectionHeader] {
+        self.sections
+    }
+
+    /// Returns the address that the image is loaded at.
+    #[inline]
+    pub fn base_address(&self) -> u32 {
+        self.header.base_address.get(LE)
+    }
+
+    /// Returns the kind of console that the executable is for.
+    #[inline]
+    pub fn console(&self) -> XbeConsole {
+        self.console
+    }
+
+    /// Returns the decoded entry point address.
+    #[inline]
+    pub fn entry_point(&self) -> u32 {
+        self.header.entry_point.get(LE) ^ self.console.entry_key()
+    }
+
+    /// Returns the decoded address of the kernel thunk table.
+    #[inline]
+    pub fn kernel_thunk_address(&self) -> u32 {
+        self.header.kernel_image_thunk_address.get(LE) ^ self.console.kernel_thunk_key()
+    }
+
+    /// Returns the certificate.
+    pub fn certificate(&self) -> Result<&'data xbe::Certificate> {
+        let offset = self
+            .header_offset(self.header.certificate_address.get(LE))
+            .read_error("Invalid XBE certificate address")?;
+        self.data
+            .read_at(offset)
+            .read_error("Invalid XBE certificate size or alignment")
+    }
+
+    /// Returns the versions of the libraries that the executable was linked with.
+    pub fn library_versions(&self) -> Result<&'data [xbe::LibraryVersion]> {
+        let count = self.header.number_of_library_versions.get(LE) as usize;
+        if count == 0 {
+            return Ok(&[]);
+        }
+        let offset = self
+            .header_offset(self.header.library_versions_address.get(LE))
+            .read_error("Invalid XBE library versions address")?;
+        self.data
+            .read_slice_at(offset, count)
+            .read_error("Invalid XBE library versions size or alignment")
+    }
+
+    /// Returns the kernel functions imported by the executable.
+    ///
+    /// These are read from the kernel thunk table, which is terminated by
+    /// a zero entry.
+    pub fn kernel_imports(&self) -> Result<Vec<XbeKernelImport>> {
+        let mut imports = Vec::new();
+        let mut address = self.kernel_thunk_address();
+        let mut offset = self
+            .address_to_offset(address)
+            .read_error("Invalid XBE kernel thunk table address")?;
+        loop {
+            let thunk = self
+                .data
+                .read::<U32Bytes<LE>>(&mut offset)
+                .read_error("Invalid XBE kernel thunk table size")?
+                .get(LE);
+            if thunk == 0 {
+                break;
+            }
+            if thunk & xbe::XBE_KERNEL_THUNK_ORDINAL == 0 {
+                return Err(Error("Unsupported XBE kernel import by name"));
+            }
+            imports.push(XbeKernelImport {
+                ordinal: thunk & !xbe::XBE_KERNEL_THUNK_ORDINAL,
+                address: address.into(),
+            });
+            address = address.wrapping_add(4);
+        }
+        Ok(imports)
+    }
+
+    /// Convert an address in the image to a file offset.
+    ///
+    /// Returns `None` if the address is not in the headers or in the
+    /// file data of a section.
+    pub fn address_to_offset(&self, address: u32) -> Option<u64> {
+        if let Some(offset) = self.header_offset(address) {
+            return Some(offset);
+        }
+        self.sections.iter().find_map(|section| {
+            let offset = address.checked_sub(section.virtual_address.get(LE))?;
+            if offset < section.raw_size.get(LE) {
+                Some(u64::from(section.raw_address.get(LE)) + u64::from(offset))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Convert an address in the headers to a file offset.
+    pub(super) fn header_offset(&self, address: u32) -> Option<u64> {
+        let offset = address.checked_sub(self.base_address())?;
+        if offset < self.header.size_of_headers.get(LE) {
+            Some(offset.into())
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn section_header(&self, index: SectionIndex) -> Result<&'data xbe::SectionHeader> {
+        index
+            .0
+            .checked_sub(1)
+            .and_then(|index| self.sections.get(index))
+            .read_error("Invalid XBE section index")
+    }
+}
+
+impl<'data, R: ReadRef<'data>> read::private::Sealed for XbeFile<'data, R> {}
+
+impl<'data, R: ReadRef<'data>> Object<'data> for XbeFile<'data, R> {
+    type Segment<'file>
+        = XbeSegment<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SegmentIterator<'file>
+        = XbeSegmentIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Section<'file>
+        = XbeSection<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SectionIterator<'file>
+        = XbeSectionIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Comdat<'file>
+        = XbeComdat<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type ComdatIterator<'file>
+        = XbeComdatIterator<'data, 'file, R>
+    where
+        Self: 'file,
+        'data: 'file;
+    type Symbol<'file>
+        = XbeSymbol<'data>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolIterator<'file>
+        = XbeSymbolIterator<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type SymbolTable<'file>
+        = XbeSymbolTable<'data, 'file>
+    where
+        Self: 'file,
+        'data: 'file;
+    type DynamicRelocationIterator<'file>
+        = NoDynamicRelocationIterator
+    where
+        Self: 'file,
+        'data: 'file;
+
+    #[inline]
+    fn architecture(&self) -> Architecture {
+        Architecture::I386
+    }
+
+    #[inline]
+    fn is_little_endian(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn is_64(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn kind(&self) -> ObjectKind {
+        ObjectKind::Executable
+    }
+
+    fn segments(&self) -> XbeSegmentIterator<'data, '_, R> {
+        XbeSegmentIterator {
+            file: self,
+            index: 1,
+        }
+    }
+
+    fn section_by_name_bytes<'file>(
+        &'file self,
+        section_name: &[u8],
+    ) -> Option<XbeSection<'data, 'file, R>> {
+        self.sections()
+            .find(|section| section.name_bytes() == Ok(section_name))
+    }
+
+    fn section_by_index(&self, index: SectionIndex) -> Result<XbeSection<'data, '_, R>> {
+        let header = self.section_header(index)?;
+        Ok(XbeSection {
+            file: self,
+            index,
+            header,
+        })
+    }
+
+    fn sections(&self) -> XbeSectionIterator<'data, '_, R> {
+        XbeSectionIterator {
+            file: self,
+            index: 1,
+        }
+    }
+
+    #[inline]
+    fn comdats(&self) -> XbeComdatIterator<'data, '_, R> {
+        XbeComdatIterator { file: self }
+    }
+
+    fn symbol_by_index(&self, _index: SymbolIndex) -> Result<XbeSymbol<'data>> {
+        Err(Error("XBE files have no symbols"))
+    }
+
+    fn symbols(&self) -> XbeSymbolIterator<'data, '_> {
+        XbeSymbolIterator::default()
+    }
+
+    #[inline]
+    fn symbol_table(&self) -> Option<XbeSymbolTable<'data, '_>> {
+        None
+    }
+
+    fn dynamic_symbols(&self) -> XbeSymbolIterator<'data, '_> {
+        XbeSymbolIterator::default()
+    }
+
+    #[inline]
+    fn dynamic_symbol_table(&self) -> Option<XbeSymbolTable<'data, '_>> {
+        None
+    }
+
+    #[inline]
+    fn dynamic_relocations(&self) -> Option<NoDynamicRelocationIterator> {
+        None
+    }
+
+    /// Kernel functions are imported by ordinal, so like PE they are omitted.
+    ///
+    /// Use [`XbeFile::kernel_imports`] to get these.
+    fn imports(&self) -> Result<Vec<Import<'data>>> {
+        Ok(Vec::new())
+    }
+
+    fn exports(&self) -> Result<Vec<Export<'data>>> {
+        Ok(Vec::new())
+    }
+
+    #[inline]
+    fn has_debug_symbols(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn relative_address_base(&self) -> u64 {
+        self.base_address().into()
+    }
+
+    #[inline]
+    fn entry(&self) -> u64 {
+        self.entry_point().into()
+    }
+
+    #[inline]
+    fn flags(&self) -> FileFlags {
+        FileFlags::None
+    }
+}
+
+/// An iterator for the COMDAT section groups in a [`XbeFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct XbeComdatIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file XbeFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for XbeComdatIterator<'data, 'file, R> {
+    type Item = XbeComdat<'data, 'file, R>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+/// A COMDAT section group in a [`XbeFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct XbeComdat<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file XbeFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for XbeComdat<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectComdat<'data> for XbeComdat<'data, 'file, R> {
+    type SectionIterator = XbeComdatSectionIterator<'data, 'file, R>;
+
+    #[inline]
+    fn kind(&self) -> ComdatKind {
+        unreachable!();
+    }
+
+    #[inline]
+    fn symbol(&self) -> SymbolIndex {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn sections(&self) -> Self::SectionIterator {
+        unreachable!();
+    }
+}
+
+/// An iterator for the sections in a COMDAT section group in a [`XbeFile`].
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug)]
+pub struct XbeComdatSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    file: &'file XbeFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for XbeComdatSectionIterator<'data, 'file, R> {
+    type Item = SectionIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
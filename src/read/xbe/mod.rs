@@ -0,0 +1,34 @@
+//! Support for reading original Xbox executables (XBE).
+//!
+//! XBE files are converted from PE images by the Xbox toolchain. The headers
+//! are loaded at the base address, and the sections are loaded at fixed
+//! addresses after them. There are no symbols or relocations.
+//!
+//! ## High level API
+//!
+//! [`XbeFile`] implements the [`Object`](crate::read::Object) trait for XBE
+//! files. Each section is also a segment. The entry point is decoded using the
+//! key for the console that the executable is for ([`XbeFile::console`]).
+//!
+//! Kernel functions are only imported by ordinal, so
+//! [`Object::imports`](crate::read::Object::imports) is always empty.
+//!
+//! ## Low level API
+//!
+//! [`XbeFile`] also provides access to the image header
+//! ([`XbeFile::image_header`]), the certificate ([`XbeFile::certificate`]),
+//! the section headers ([`XbeFile::section_headers`]), the library versions
+//! ([`XbeFile::library_versions`]), and the kernel thunk table
+//! ([`XbeFile::kernel_imports`]). [`XbeSection::digest`] returns the SHA-1
+//! digest of a section.
+mod file;
+pub use file::*;
+
+mod section;
+pub use section::*;
+
+mod symbol;
+pub use symbol::*;
+
+mod relocation;
+pub use relocation::*;
@@ -0,0 +1,22 @@
+use crate::read::{ReadRef, Relocation};
+
+use super::XbeFile;
+
+/// An iterator for the relocations in an [`XbeSection`](super::XbeSection).
+///
+/// This is a stub that doesn't implement any functionality. XBE files
+/// have no relocations.
+#[derive(Debug)]
+pub struct XbeRelocationIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    #[allow(unused)]
+    pub(super) file: &'file XbeFile<'data, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for XbeRelocationIterator<'data, 'file, R> {
+    type Item = (u64, Relocation);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
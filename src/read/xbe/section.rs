@@ -0,0 +1,257 @@
+use core::str;
+
+use crate::endian::LittleEndian as LE;
+use crate::read::{
+    self, CompressedData, CompressedFileRange, ObjectSection, ObjectSegment, ReadError, ReadRef,
+    RelocationMap, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags,
+};
+use crate::xbe;
+
+use super::{XbeFile, XbeRelocationIterator};
+
+/// An iterator for the sections in an [`XbeFile`].
+#[derive(Debug)]
+pub struct XbeSectionIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file XbeFile<'data, R>,
+    pub(super) index: usize,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for XbeSectionIterator<'data, 'file, R> {
+    type Item = XbeSection<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = SectionIndex(self.index);
+        let header = self.file.section_header(index).ok()?;
+        self.index += 1;
+        Some(XbeSection {
+            file: self.file,
+            index,
+            header,
+        })
+    }
+}
+
+/// A section in an [`XbeFile`].
+///
+/// Each section is also a segment.
+#[derive(Debug)]
+pub struct XbeSection<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file XbeFile<'data, R>,
+    pub(super) index: SectionIndex,
+    pub(super) header: &'data xbe::SectionHeader,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> XbeSection<'data, 'file, R> {
+    /// Get the raw section header.
+    #[inline]
+    pub fn xbe_header(&self) -> &'data xbe::SectionHeader {
+        self.header
+    }
+
+    /// Get the SHA-1 digest of the section data.
+    #[inline]
+    pub fn digest(&self) -> &'data [u8; 20] {
+        &self.header.section_digest
+    }
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for XbeSection<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSection<'data> for XbeSection<'data, 'file, R> {
+    type RelocationIterator = XbeRelocationIterator<'data, 'file, R>;
+
+    #[inline]
+    fn index(&self) -> SectionIndex {
+        self.index
+    }
+
+    #[inline]
+    fn address(&self) -> u64 {
+        self.header.virtual_address.get(LE).into()
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.header.virtual_size.get(LE).into()
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        4
+    }
+
+    #[inline]
+    fn file_range(&self) -> Option<(u64, u64)> {
+        Some((
+            self.header.raw_address.get(LE).into(),
+            self.header.raw_size.get(LE).into(),
+        ))
+    }
+
+    fn data(&self) -> Result<&'data [u8]> {
+        self.file
+            .data
+            .read_bytes_at(
+                self.header.raw_address.get(LE).into(),
+                self.header.raw_size.get(LE).into(),
+            )
+            .read_error("Invalid XBE section offset or size")
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        Ok(read::util::data_range(
+            self.data()?,
+            self.address(),
+            address,
+            size,
+        ))
+    }
+
+    #[inline]
+    fn compressed_file_range(&self) -> Result<CompressedFileRange> {
+        Ok(CompressedFileRange::none(self.file_range()))
+    }
+
+    #[inline]
+    fn compressed_data(&self) -> Result<CompressedData<'data>> {
+        self.data().map(CompressedData::none)
+    }
+
+    /// Return the section name, which is stored in the headers.
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        let offset = self
+            .file
+            .header_offset(self.header.section_name_address.get(LE))
+            .read_error("Invalid XBE section name address")?;
+        let end = self.file.image_header().size_of_headers.get(LE);
+        self.file
+            .data
+            .read_bytes_at_until(offset..end.into(), 0)
+            .read_error("Invalid XBE section name")
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        str::from_utf8(self.name_bytes()?)
+            .ok()
+            .read_error("Non UTF-8 XBE section name")
+    }
+
+    #[inline]
+    fn segment_name_bytes(&self) -> Result<Option<&[u8]>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn segment_name(&self) -> Result<Option<&str>> {
+        Ok(None)
+    }
+
+    fn kind(&self) -> SectionKind {
+        let flags = self.header.flags.get(LE);
+        if flags & xbe::XBE_SECTION_EXECUTABLE != 0 {
+            SectionKind::Text
+        } else if flags & xbe::XBE_SECTION_WRITABLE != 0 {
+            if self.header.raw_size.get(LE) == 0 {
+                SectionKind::UninitializedData
+            } else {
+                SectionKind::Data
+            }
+        } else {
+            SectionKind::ReadOnlyData
+        }
+    }
+
+    #[inline]
+    fn relocations(&self) -> XbeRelocationIterator<'data, 'file, R> {
+        XbeRelocationIterator { file: self.file }
+    }
+
+    fn relocation_map(&self) -> Result<RelocationMap> {
+        RelocationMap::new(self.file, self)
+    }
+
+    #[inline]
+    fn flags(&self) -> SectionFlags {
+        SectionFlags::None
+    }
+}
+
+/// An iterator for the segments in an [`XbeFile`].
+#[derive(Debug)]
+pub struct XbeSegmentIterator<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    pub(super) file: &'file XbeFile<'data, R>,
+    pub(super) index: usize,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> Iterator for XbeSegmentIterator<'data, 'file, R> {
+    type Item = XbeSegment<'data, 'file, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = SectionIndex(self.index);
+        let header = self.file.section_header(index).ok()?;
+        self.index += 1;
+        Some(XbeSegment {
+            section: XbeSection {
+                file: self.file,
+                index,
+                header,
+            },
+        })
+    }
+}
+
+/// A segment in an [`XbeFile`].
+///
+/// This is the same as the section with the same index.
+#[derive(Debug)]
+pub struct XbeSegment<'data, 'file, R: ReadRef<'data> = &'data [u8]> {
+    section: XbeSection<'data, 'file, R>,
+}
+
+impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for XbeSegment<'data, 'file, R> {}
+
+impl<'data, 'file, R: ReadRef<'data>> ObjectSegment<'data> for XbeSegment<'data, 'file, R> {
+    #[inline]
+    fn address(&self) -> u64 {
+        self.section.address()
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        self.section.size()
+    }
+
+    #[inline]
+    fn align(&self) -> u64 {
+        self.section.align()
+    }
+
+    #[inline]
+    fn file_range(&self) -> (u64, u64) {
+        self.section.file_range().unwrap_or((0, 0))
+    }
+
+    fn data(&self) -> Result<&'data [u8]> {
+        self.section.data()
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        self.section.data_range(address, size)
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<Option<&[u8]>> {
+        self.section.name_bytes().map(Some)
+    }
+
+    #[inline]
+    fn name(&self) -> Result<Option<&str>> {
+        self.section.name().map(Some)
+    }
+
+    #[inline]
+    fn flags(&self) -> SegmentFlags {
+        SegmentFlags::None
+    }
+}
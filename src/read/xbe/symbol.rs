@@ -0,0 +1,133 @@
+use core::marker::PhantomData;
+
+use crate::read::{
+    self, Error, ObjectSymbol, ObjectSymbolTable, Result, SectionIndex, SymbolFlags, SymbolIndex,
+    SymbolKind, SymbolScope, SymbolSection,
+};
+
+/// A symbol in an [`XbeFile`](super::XbeFile).
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug, Clone)]
+pub struct XbeSymbol<'data> {
+    marker: PhantomData<&'data ()>,
+}
+
+impl<'data> read::private::Sealed for XbeSymbol<'data> {}
+
+impl<'data> ObjectSymbol<'data> for XbeSymbol<'data> {
+    #[inline]
+    fn index(&self) -> SymbolIndex {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn name(&self) -> Result<&'data str> {
+        unreachable!();
+    }
+
+    #[inline]
+    fn address(&self) -> u64 {
+        unreachable!();
+    }
+
+    #[inline]
+    fn size(&self) -> u64 {
+        unreachable!();
+    }
+
+    #[inline]
+    fn kind(&self) -> SymbolKind {
+        unreachable!();
+    }
+
+    #[inline]
+    fn section(&self) -> SymbolSection {
+        unreachable!();
+    }
+
+    #[inline]
+    fn is_undefined(&self) -> bool {
+        unreachable!();
+    }
+
+    #[inline]
+    fn is_definition(&self) -> bool {
+        unreachable!();
+    }
+
+    #[inline]
+    fn is_common(&self) -> bool {
+        unreachable!();
+    }
+
+    #[inline]
+    fn is_weak(&self) -> bool {
+        unreachable!();
+    }
+
+    #[inline]
+    fn scope(&self) -> SymbolScope {
+        unreachable!();
+    }
+
+    #[inline]
+    fn is_global(&self) -> bool {
+        unreachable!();
+    }
+
+    #[inline]
+    fn is_local(&self) -> bool {
+        unreachable!();
+    }
+
+    #[inline]
+    fn flags(&self) -> SymbolFlags<SectionIndex, SymbolIndex> {
+        unreachable!();
+    }
+}
+
+/// A symbol table in an [`XbeFile`](super::XbeFile).
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug, Clone, Copy)]
+pub struct XbeSymbolTable<'data, 'file> {
+    marker: PhantomData<&'file &'data ()>,
+}
+
+impl<'data, 'file> read::private::Sealed for XbeSymbolTable<'data, 'file> {}
+
+impl<'data, 'file> ObjectSymbolTable<'data> for XbeSymbolTable<'data, 'file> {
+    type Symbol = XbeSymbol<'data>;
+    type SymbolIterator = XbeSymbolIterator<'data, 'file>;
+
+    fn symbols(&self) -> Self::SymbolIterator {
+        XbeSymbolIterator::default()
+    }
+
+    fn symbol_by_index(&self, _index: SymbolIndex) -> Result<Self::Symbol> {
+        Err(Error("XBE files have no symbols"))
+    }
+}
+
+/// An iterator for the symbols in an [`XbeFile`](super::XbeFile).
+///
+/// This is a stub that doesn't implement any functionality.
+#[derive(Debug, Default)]
+pub struct XbeSymbolIterator<'data, 'file> {
+    marker: PhantomData<&'file &'data ()>,
+}
+
+impl<'data, 'file> Iterator for XbeSymbolIterator<'data, 'file> {
+    type Item = XbeSymbol<'data>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
@@ -0,0 +1,52 @@
+//! Motorola S-record definitions.
+//!
+//! These definitions are independent of read/write support, although we do implement
+//! some traits useful for those.
+//!
+//! An S-record file is a text file containing a sequence of records, one per
+//! line. Each record starts with [`SREC_RECORD_MARK`] and a decimal digit
+//! giving the record type, which is followed by pairs of hexadecimal digits
+//! giving the bytes of the record:
+//!
+//! - the number of bytes in the rest of the record,
+//! - the address, big endian, with a size that depends on the record type,
+//! - the data bytes,
+//! - a checksum, which is the one's complement of the sum of the other bytes.
+//!
+//! Files using 16-bit, 24-bit and 32-bit addresses are known as S19, S28
+//! and S37 files respectively.
+
+/// The character at the start of each record.
+pub const SREC_RECORD_MARK: u8 = b'S';
+
+// Values for the record type.
+/// Header record. The data is a vendor specific description of the file.
+pub const SREC_HEADER: u8 = 0;
+/// Data record with a 16-bit address.
+pub const SREC_DATA_16: u8 = 1;
+/// Data record with a 24-bit address.
+pub const SREC_DATA_24: u8 = 2;
+/// Data record with a 32-bit address.
+pub const SREC_DATA_32: u8 = 3;
+/// Count record. The address is the 16-bit number of preceding data records.
+pub const SREC_COUNT_16: u8 = 5;
+/// Count record. The address is the 24-bit number of preceding data records.
+pub const SREC_COUNT_24: u8 = 6;
+/// Termination record. The address is the 32-bit entry point.
+pub const SREC_START_32: u8 = 7;
+/// Termination record. The address is the 24-bit entry point.
+pub const SREC_START_24: u8 = 8;
+/// Termination record. The address is the 16-bit entry point.
+pub const SREC_START_16: u8 = 9;
+
+/// Return the size in bytes of the address field for a record type.
+///
+/// Returns `None` for unknown record types.
+pub fn address_size(record_type: u8) -> Option<usize> {
+    match record_type {
+        SREC_HEADER | SREC_DATA_16 | SREC_COUNT_16 | SREC_START_16 => Some(2),
+        SREC_DATA_24 | SREC_COUNT_24 | SREC_START_24 => Some(3),
+        SREC_DATA_32 | SREC_START_32 => Some(4),
+        _ => None,
+    }
+}
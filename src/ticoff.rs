@@ -0,0 +1,372 @@
+//! TI COFF definitions.
+//!
+//! These definitions are independent of read/write support, although we do implement
+//! some traits useful for those.
+//!
+//! This module is based on the "Common Object File Format" application report
+//! (SPRAAO8) from Texas Instruments, and on the `coff/ti.h` header of binutils.
+//!
+//! TI COFF is the variant of System V COFF that is used by the Texas Instruments
+//! toolchains for their DSPs and microcontrollers. There are three versions:
+//!
+//! - COFF0 files start with a [`FileHeaderV0`], which is the System V COFF file
+//!   header with the target ID in place of the magic number.
+//! - COFF1 files start with a [`FileHeader`], which adds a version ID at the
+//!   start. The section headers are the same as for COFF0.
+//! - COFF2 files are the same as COFF1 files, except that [`SectionHeaderV2`]
+//!   is used for the section headers.
+//!
+//! All of the fields are in the byte order of the target, which is given by
+//! [`F_LITTLE`] or [`F_BIG`] in the file header flags. The headers are not
+//! aligned in the file, so byte array types are used for all fields.
+
+#![allow(missing_docs)]
+
+use crate::endian::{Endian, I16Bytes, U16Bytes, U32Bytes};
+use crate::pod::Pod;
+
+/// The file header of a COFF0 file.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct FileHeaderV0<E: Endian> {
+    /// The target ID. One of the `TI_TARGET_*` constants.
+    pub f_target_id: U16Bytes<E>,
+    /// The number of section headers.
+    pub f_nscns: U16Bytes<E>,
+    /// The time and date stamp.
+    pub f_timdat: U32Bytes<E>,
+    /// The file offset of the symbol table.
+    pub f_symptr: U32Bytes<E>,
+    /// The number of entries in the symbol table.
+    pub f_nsyms: U32Bytes<E>,
+    /// The size of the optional header. Either 0 or 28.
+    pub f_opthdr: U16Bytes<E>,
+    /// Flags. A combination of the `F_*` constants.
+    pub f_flags: U16Bytes<E>,
+}
+
+/// The file header of a COFF1 or COFF2 file.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct FileHeader<E: Endian> {
+    /// The version ID. One of [`TI_COFF1`] or [`TI_COFF2`].
+    pub f_version: U16Bytes<E>,
+    /// The number of section headers.
+    pub f_nscns: U16Bytes<E>,
+    /// The time and date stamp.
+    pub f_timdat: U32Bytes<E>,
+    /// The file offset of the symbol table.
+    pub f_symptr: U32Bytes<E>,
+    /// The number of entries in the symbol table.
+    pub f_nsyms: U32Bytes<E>,
+    /// The size of the optional header. Either 0 or 28.
+    pub f_opthdr: U16Bytes<E>,
+    /// Flags. A combination of the `F_*` constants.
+    pub f_flags: U16Bytes<E>,
+    /// The target ID. One of the `TI_TARGET_*` constants.
+    pub f_target_id: U16Bytes<E>,
+}
+
+// Values for `FileHeader::f_version`.
+/// COFF1 version ID.
+pub const TI_COFF1: u16 = 0x00c1;
+/// COFF2 version ID.
+pub const TI_COFF2: u16 = 0x00c2;
+
+// Values for `f_target_id`.
+/// TMS470 (ARM).
+pub const TI_TARGET_TMS470: u16 = 0x0097;
+/// TMS320C5400.
+pub const TI_TARGET_C5400: u16 = 0x0098;
+/// TMS320C6000.
+pub const TI_TARGET_C6000: u16 = 0x0099;
+/// TMS320C5500.
+pub const TI_TARGET_C5500: u16 = 0x009c;
+/// TMS320C2800, which is used by the C2000 family.
+pub const TI_TARGET_C2800: u16 = 0x009d;
+/// MSP430.
+pub const TI_TARGET_MSP430: u16 = 0x00a0;
+/// TMS320C5500+.
+pub const TI_TARGET_C5500_PLUS: u16 = 0x00a1;
+
+// Values for `f_flags`.
+/// Relocation information has been stripped.
+pub const F_RELFLG: u16 = 0x0001;
+/// The file is executable.
+pub const F_EXEC: u16 = 0x0002;
+/// Line numbers have been stripped.
+pub const F_LNNO: u16 = 0x0004;
+/// Local symbols have been stripped.
+pub const F_LSYMS: u16 = 0x0008;
+/// The target is little endian.
+pub const F_LITTLE: u16 = 0x0100;
+/// The target is big endian.
+pub const F_BIG: u16 = 0x0200;
+/// Duplicate symbols have been removed.
+pub const F_SYMMERGE: u16 = 0x1000;
+
+/// The optional file header.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct OptionalHeader<E: Endian> {
+    /// The magic number. Must be [`OPTIONAL_HEADER_MAGIC`].
+    pub o_magic: U16Bytes<E>,
+    /// The version stamp.
+    pub o_vstamp: U16Bytes<E>,
+    /// The size of the executable code.
+    pub o_tsize: U32Bytes<E>,
+    /// The size of the initialized data.
+    pub o_dsize: U32Bytes<E>,
+    /// The size of the uninitialized data.
+    pub o_bsize: U32Bytes<E>,
+    /// The entry point address.
+    pub o_entry: U32Bytes<E>,
+    /// The address of the executable code.
+    pub o_text_start: U32Bytes<E>,
+    /// The address of the initialized data.
+    pub o_data_start: U32Bytes<E>,
+}
+
+/// The magic number of the optional file header.
+pub const OPTIONAL_HEADER_MAGIC: u16 = 0x0108;
+
+/// A section header in a COFF0 or COFF1 file.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct SectionHeader<E: Endian> {
+    /// The section name.
+    ///
+    /// If the first 4 bytes are zero, then the last 4 bytes are an offset
+    /// in the string table.
+    pub s_name: [u8; 8],
+    /// The physical address.
+    pub s_paddr: U32Bytes<E>,
+    /// The virtual address.
+    pub s_vaddr: U32Bytes<E>,
+    /// The section size, in addressable units of the target.
+    pub s_size: U32Bytes<E>,
+    /// The file offset of the section data.
+    pub s_scnptr: U32Bytes<E>,
+    /// The file offset of the relocation entries.
+    pub s_relptr: U32Bytes<E>,
+    /// The file offset of the line number entries.
+    pub s_lnnoptr: U32Bytes<E>,
+    /// The number of relocation entries.
+    pub s_nreloc: U16Bytes<E>,
+    /// The number of line number entries.
+    pub s_nlnno: U16Bytes<E>,
+    /// Flags. A combination of the `STYP_*` constants.
+    pub s_flags: U16Bytes<E>,
+    /// Reserved.
+    pub s_reserved: u8,
+    /// The memory page number.
+    pub s_page: u8,
+}
+
+/// A section header in a COFF2 file.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct SectionHeaderV2<E: Endian> {
+    /// The section name.
+    ///
+    /// If the first 4 bytes are zero, then the last 4 bytes are an offset
+    /// in the string table.
+    pub s_name: [u8; 8],
+    /// The physical address.
+    pub s_paddr: U32Bytes<E>,
+    /// The virtual address.
+    pub s_vaddr: U32Bytes<E>,
+    /// The section size, in addressable units of the target.
+    pub s_size: U32Bytes<E>,
+    /// The file offset of the section data.
+    pub s_scnptr: U32Bytes<E>,
+    /// The file offset of the relocation entries.
+    pub s_relptr: U32Bytes<E>,
+    /// The file offset of the line number entries.
+    pub s_lnnoptr: U32Bytes<E>,
+    /// The number of relocation entries.
+    pub s_nreloc: U32Bytes<E>,
+    /// The number of line number entries.
+    pub s_nlnno: U32Bytes<E>,
+    /// Flags. A combination of the `STYP_*` constants.
+    pub s_flags: U32Bytes<E>,
+    /// Reserved.
+    pub s_reserved: U16Bytes<E>,
+    /// The memory page number.
+    pub s_page: U16Bytes<E>,
+}
+
+// Values for `s_flags`.
+/// Regular section.
+pub const STYP_REG: u32 = 0x0000;
+/// Dummy section.
+pub const STYP_DSECT: u32 = 0x0001;
+/// Section that is not loaded.
+pub const STYP_NOLOAD: u32 = 0x0002;
+/// Grouped section.
+pub const STYP_GROUP: u32 = 0x0004;
+/// Padding section.
+pub const STYP_PAD: u32 = 0x0008;
+/// Copy section, which is relocated and loaded but not allocated.
+pub const STYP_COPY: u32 = 0x0010;
+/// Executable code.
+pub const STYP_TEXT: u32 = 0x0020;
+/// Initialized data.
+pub const STYP_DATA: u32 = 0x0040;
+/// Uninitialized data.
+pub const STYP_BSS: u32 = 0x0080;
+/// Mask for the alignment, which is a power of two.
+pub const STYP_ALIGN: u32 = 0x0f00;
+/// Shift for the alignment.
+pub const STYP_ALIGN_SHIFT: u32 = 8;
+/// The section must not cross a page boundary.
+pub const STYP_BLOCK: u32 = 0x1000;
+/// The section is passed through unchanged.
+pub const STYP_PASS: u32 = 0x2000;
+/// Conditionally linked section.
+pub const STYP_CLINK: u32 = 0x4000;
+/// Vector table section.
+pub const STYP_VECTOR: u32 = 0x8000;
+/// The section has been padded.
+pub const STYP_PADDED: u32 = 0x0001_0000;
+
+/// A symbol table entry.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Symbol<E: Endian> {
+    /// The symbol name.
+    ///
+    /// If the first 4 bytes are zero, then the last 4 bytes are an offset
+    /// in the string table.
+    pub n_name: [u8; 8],
+    /// The symbol value.
+    ///
+    /// This is an address for symbols defined in a section.
+    pub n_value: U32Bytes<E>,
+    /// The section number, or one of the `N_*` constants.
+    pub n_scnum: I16Bytes<E>,
+    /// The symbol type. Reserved.
+    pub n_type: U16Bytes<E>,
+    /// The storage class. One of the `C_*` constants.
+    pub n_sclass: u8,
+    /// The number of auxiliary entries that follow this entry.
+    pub n_numaux: u8,
+}
+
+/// The size of a symbol table entry, including auxiliary entries.
+pub const SYMBOL_SIZE: usize = 18;
+
+// Values for `Symbol::n_scnum`.
+/// Undefined symbol.
+pub const N_UNDEF: i16 = 0;
+/// Absolute symbol.
+pub const N_ABS: i16 = -1;
+/// Debugging symbol.
+pub const N_DEBUG: i16 = -2;
+
+// Values for `Symbol::n_sclass`.
+/// No storage class.
+pub const C_NULL: u8 = 0;
+/// Automatic variable.
+pub const C_AUTO: u8 = 1;
+/// External symbol.
+pub const C_EXT: u8 = 2;
+/// Static symbol.
+pub const C_STAT: u8 = 3;
+/// Register variable.
+pub const C_REG: u8 = 4;
+/// External reference.
+pub const C_EXTREF: u8 = 5;
+/// Label.
+pub const C_LABEL: u8 = 6;
+/// Undefined label.
+pub const C_ULABEL: u8 = 7;
+/// Member of a structure.
+pub const C_MOS: u8 = 8;
+/// Function argument.
+pub const C_ARG: u8 = 9;
+/// Structure tag.
+pub const C_STRTAG: u8 = 10;
+/// Member of a union.
+pub const C_MOU: u8 = 11;
+/// Union tag.
+pub const C_UNTAG: u8 = 12;
+/// Type definition.
+pub const C_TPDEF: u8 = 13;
+/// Undefined static symbol.
+pub const C_USTATIC: u8 = 14;
+/// Enumeration tag.
+pub const C_ENTAG: u8 = 15;
+/// Member of an enumeration.
+pub const C_MOE: u8 = 16;
+/// Register parameter.
+pub const C_REGPARM: u8 = 17;
+/// Bit field.
+pub const C_FIELD: u8 = 18;
+/// Tentative external definition.
+pub const C_UEXT: u8 = 19;
+/// Static load time label.
+pub const C_STATLAB: u8 = 20;
+/// External load time label.
+pub const C_EXTLAB: u8 = 21;
+/// Last declared parameter of a function with a variable number of arguments.
+pub const C_VARARG: u8 = 27;
+/// Beginning or end of a block.
+pub const C_BLOCK: u8 = 100;
+/// Beginning or end of a function.
+pub const C_FCN: u8 = 101;
+/// End of a structure.
+pub const C_EOS: u8 = 102;
+/// File name.
+pub const C_FILE: u8 = 103;
+/// Line number reformatted as a symbol.
+pub const C_LINE: u8 = 104;
+
+/// A relocation entry in a COFF0 file.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct RelocationV0<E: Endian> {
+    /// The address of the location to relocate.
+    pub r_vaddr: U32Bytes<E>,
+    /// The symbol table index.
+    pub r_symndx: U16Bytes<E>,
+    /// Reserved.
+    pub r_disp: U16Bytes<E>,
+    /// The relocation type. One of the `R_*` constants, or a target specific type.
+    pub r_type: U16Bytes<E>,
+}
+
+/// A relocation entry in a COFF1 or COFF2 file.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Relocation<E: Endian> {
+    /// The address of the location to relocate.
+    pub r_vaddr: U32Bytes<E>,
+    /// The symbol table index.
+    pub r_symndx: U32Bytes<E>,
+    /// Additional bits of the address for some relocation types.
+    pub r_disp: U16Bytes<E>,
+    /// The relocation type. One of the `R_*` constants, or a target specific type.
+    pub r_type: U16Bytes<E>,
+}
+
+// Values for `r_type`.
+/// No relocation.
+pub const R_ABS: u16 = 0x0000;
+/// 8-bit direct reference.
+pub const R_RELBYTE: u16 = 0x000f;
+/// 16-bit direct reference.
+pub const R_RELWORD: u16 = 0x0010;
+/// 32-bit direct reference.
+pub const R_RELLONG: u16 = 0x0011;
+
+unsafe_impl_endian_pod!(
+    FileHeaderV0,
+    FileHeader,
+    OptionalHeader,
+    SectionHeader,
+    SectionHeaderV2,
+    Symbol,
+    RelocationV0,
+    Relocation,
+);
@@ -2,12 +2,12 @@
 //!
 //! This module provides a unified write API for relocatable object files
 //! using [`Object`]. This does not support writing executable files.
-//! This supports the following file formats: COFF, ELF, Mach-O, and XCOFF.
+//! This supports the following file formats: COFF, ELF, Mach-O, OMF, and XCOFF.
 //!
 //! The submodules define helpers for writing the raw structs. These support
 //! writing both relocatable and executable files. There are writers for
 //! the following file formats: [COFF](coff::Writer), [ELF](elf::Writer),
-//! and [PE](pe::Writer).
+//! and [PE](pe::Writer). There is also a writer for OMF libraries.
 
 use alloc::borrow::Cow;
 use alloc::string::String;
@@ -35,6 +35,9 @@ mod macho;
 #[cfg(feature = "macho")]
 pub use macho::MachOBuildVersion;
 
+#[cfg(feature = "omf")]
+pub mod omf;
+
 #[cfg(feature = "pe")]
 pub mod pe;
 
@@ -95,6 +98,20 @@ pub struct Object<'a> {
     /// Mach-O MH_SUBSECTIONS_VIA_SYMBOLS flag. Only ever set if format is Mach-O.
     #[cfg(feature = "macho")]
     macho_subsections_via_symbols: bool,
+    /// OMF line numbers, as section, offset and line.
+    #[cfg(feature = "omf")]
+    omf_line_numbers: Vec<(SectionId, u32, u16)>,
+    #[cfg(feature = "omf")]
+    omf_target_profile: omf::TargetProfile,
+    #[cfg(feature = "omf")]
+    omf_zero_checksums: bool,
+    #[cfg(feature = "omf")]
+    omf_entry: Option<SymbolId>,
+    /// OMF groups, as name and sections.
+    #[cfg(feature = "omf")]
+    omf_groups: Vec<(Vec<u8>, Vec<SectionId>)>,
+    #[cfg(feature = "omf")]
+    omf_dgroup: bool,
 }
 
 impl<'a> Object<'a> {
@@ -122,6 +139,18 @@ impl<'a> Object<'a> {
             macho_build_version: None,
             #[cfg(feature = "macho")]
             macho_subsections_via_symbols: false,
+            #[cfg(feature = "omf")]
+            omf_line_numbers: Vec::new(),
+            #[cfg(feature = "omf")]
+            omf_target_profile: omf::TargetProfile::default(),
+            #[cfg(feature = "omf")]
+            omf_zero_checksums: false,
+            #[cfg(feature = "omf")]
+            omf_entry: None,
+            #[cfg(feature = "omf")]
+            omf_groups: Vec::new(),
+            #[cfg(feature = "omf")]
+            omf_dgroup: false,
         }
     }
 
@@ -172,6 +201,8 @@ impl<'a> Object<'a> {
             BinaryFormat::Elf => &[],
             #[cfg(feature = "macho")]
             BinaryFormat::MachO => self.macho_segment_name(segment),
+            #[cfg(feature = "omf")]
+            BinaryFormat::Omf => self.omf_segment_name(segment),
             _ => unimplemented!(),
         }
     }
@@ -274,6 +305,8 @@ impl<'a> Object<'a> {
             BinaryFormat::MachO => self.macho_section_info(section),
             #[cfg(feature = "xcoff")]
             BinaryFormat::Xcoff => self.xcoff_section_info(section),
+            #[cfg(feature = "omf")]
+            BinaryFormat::Omf => self.omf_section_info(section),
             _ => unimplemented!(),
         }
     }
@@ -584,6 +617,8 @@ impl<'a> Object<'a> {
             BinaryFormat::MachO => self.macho_translate_relocation(&mut relocation)?,
             #[cfg(feature = "xcoff")]
             BinaryFormat::Xcoff => self.xcoff_translate_relocation(&mut relocation)?,
+            #[cfg(feature = "omf")]
+            BinaryFormat::Omf => self.omf_translate_relocation(&mut relocation)?,
             _ => unimplemented!(),
         }
         let implicit = match self.format {
@@ -595,6 +630,8 @@ impl<'a> Object<'a> {
             BinaryFormat::MachO => self.macho_adjust_addend(&mut relocation)?,
             #[cfg(feature = "xcoff")]
             BinaryFormat::Xcoff => self.xcoff_adjust_addend(&mut relocation)?,
+            #[cfg(feature = "omf")]
+            BinaryFormat::Omf => self.omf_adjust_addend(&mut relocation)?,
             _ => unimplemented!(),
         };
         if implicit && relocation.addend != 0 {
@@ -619,6 +656,8 @@ impl<'a> Object<'a> {
             BinaryFormat::MachO => self.macho_relocation_size(relocation)?,
             #[cfg(feature = "xcoff")]
             BinaryFormat::Xcoff => self.xcoff_relocation_size(relocation)?,
+            #[cfg(feature = "omf")]
+            BinaryFormat::Omf => self.omf_relocation_size(relocation)?,
             _ => unimplemented!(),
         };
         let data = self.sections[section.0].data_mut();
@@ -676,6 +715,8 @@ impl<'a> Object<'a> {
             BinaryFormat::MachO => self.macho_write(buffer),
             #[cfg(feature = "xcoff")]
             BinaryFormat::Xcoff => self.xcoff_write(buffer),
+            #[cfg(feature = "omf")]
+            BinaryFormat::Omf => self.omf_write(buffer),
             _ => unimplemented!(),
         }
     }
@@ -0,0 +1,295 @@
+//! Helper for writing OMF libraries.
+//!
+//! An OMF library (`.LIB`) contains a LIBHDR record, followed by the member
+//! object modules, each starting on a page boundary, followed by a LIBEND
+//! record and a dictionary of the public symbols defined by the members.
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+#[cfg(not(feature = "std"))]
+use hashbrown::HashSet;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+use crate::omf::{
+    dictionary_hash, DICTIONARY_BLOCK_FULL, DICTIONARY_BLOCK_SIZE, DICTIONARY_BUCKETS, LHEADR,
+    LIBEND, LIBHDR, MODEND, MODEND32, PUBDEF, PUBDEF32, THEADR,
+};
+use crate::write::util::{align, write_align};
+use crate::write::{Error, Result, WritableBuffer};
+
+/// The flag in the LIBHDR record for a case sensitive dictionary.
+const LIBHDR_CASE_SENSITIVE: u8 = 0x01;
+
+/// A helper for writing OMF libraries.
+///
+/// The members must be complete OMF object modules, from the THEADR record to
+/// the MODEND record. The dictionary is generated from the names in the PUBDEF
+/// records of the members.
+#[derive(Debug)]
+pub struct LibraryWriter<'a> {
+    page_size: u32,
+    case_sensitive: bool,
+    members: Vec<LibraryMember<'a>>,
+}
+
+#[derive(Debug)]
+struct LibraryMember<'a> {
+    data: &'a [u8],
+    symbols: Vec<&'a [u8]>,
+}
+
+impl<'a> Default for LibraryWriter<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> LibraryWriter<'a> {
+    /// Create a new `LibraryWriter`.
+    ///
+    /// The page size defaults to 16, and the dictionary is case insensitive.
+    pub fn new() -> Self {
+        LibraryWriter {
+            page_size: 16,
+            case_sensitive: false,
+            members: Vec::new(),
+        }
+    }
+
+    /// Set the page size.
+    ///
+    /// Members start on a page boundary, and are located by a 16-bit page number,
+    /// so larger libraries need a larger page size. The page size must be a power
+    /// of two from 16 to 32768.
+    pub fn set_page_size(&mut self, page_size: u32) -> Result<()> {
+        if !page_size.is_power_of_two() || !(16..=32768).contains(&page_size) {
+            return Err(Error(format!(
+                "invalid OMF library page size {}",
+                page_size
+            )));
+        }
+        self.page_size = page_size;
+        Ok(())
+    }
+
+    /// Set whether the dictionary is case sensitive.
+    pub fn set_case_sensitive(&mut self, case_sensitive: bool) {
+        self.case_sensitive = case_sensitive;
+    }
+
+    /// Add an object module to the library.
+    ///
+    /// Returns an error if the data is not a single OMF object module.
+    pub fn add_member(&mut self, data: &'a [u8]) -> Result<()> {
+        let symbols =
+            parse_member(data).ok_or_else(|| Error(String::from("invalid OMF library member")))?;
+        self.members.push(LibraryMember { data, symbols });
+        Ok(())
+    }
+
+    /// Write the library to the buffer.
+    pub fn write(&self, buffer: &mut dyn WritableBuffer) -> Result<()> {
+        let page_size = self.page_size as usize;
+
+        // Assign the member pages, and collect the dictionary entries.
+        let mut offset = page_size;
+        let mut entries: Vec<(&[u8], u16)> = Vec::new();
+        let mut names = HashSet::new();
+        for member in &self.members {
+            let page = u16::try_from(offset / page_size).map_err(|_| {
+                Error(format!(
+                    "OMF library is too large for page size {}",
+                    page_size
+                ))
+            })?;
+            for &name in &member.symbols {
+                // The first definition of a symbol is used.
+                let key = if self.case_sensitive {
+                    name.to_vec()
+                } else {
+                    name.to_ascii_lowercase()
+                };
+                if names.insert(key) {
+                    entries.push((name, page));
+                }
+            }
+            offset = align(offset + member.data.len(), page_size);
+        }
+
+        // The LIBEND record pads to the start of the dictionary.
+        let libend_offset = offset;
+        let dictionary_offset = align(libend_offset + 3, DICTIONARY_BLOCK_SIZE);
+        let dictionary = build_dictionary(&entries)?;
+        let dictionary_blocks = dictionary.len() / DICTIONARY_BLOCK_SIZE;
+        let len = dictionary_offset + dictionary.len();
+        let dictionary_offset = u32::try_from(dictionary_offset)
+            .map_err(|_| Error(String::from("OMF library is too large")))?;
+
+        buffer
+            .reserve(len)
+            .map_err(|_| Error(String::from("Cannot allocate buffer")))?;
+
+        let mut header = [0; 10];
+        header[0] = LIBHDR;
+        header[1..3].copy_from_slice(&((self.page_size - 3) as u16).to_le_bytes());
+        header[3..7].copy_from_slice(&dictionary_offset.to_le_bytes());
+        header[7..9].copy_from_slice(&(dictionary_blocks as u16).to_le_bytes());
+        if self.case_sensitive {
+            header[9] |= LIBHDR_CASE_SENSITIVE;
+        }
+        buffer.write_bytes(&header);
+        buffer.resize(page_size);
+
+        for member in &self.members {
+            buffer.write_bytes(member.data);
+            write_align(buffer, page_size);
+        }
+
+        debug_assert_eq!(buffer.len(), libend_offset);
+        let libend_len = dictionary_offset as usize - libend_offset - 3;
+        buffer.write_bytes(&[LIBEND]);
+        buffer.write_bytes(&(libend_len as u16).to_le_bytes());
+        buffer.resize(dictionary_offset as usize);
+        buffer.write_bytes(&dictionary);
+        debug_assert_eq!(buffer.len(), len);
+        Ok(())
+    }
+}
+
+/// Check that the data is a single object module, and return the names in its
+/// PUBDEF records.
+fn parse_member(data: &[u8]) -> Option<Vec<&[u8]>> {
+    if !matches!(data.first(), Some(&THEADR) | Some(&LHEADR)) {
+        return None;
+    }
+    let mut symbols = Vec::new();
+    let mut pos = 0;
+    loop {
+        let header = data.get(pos..pos + 3)?;
+        let length = usize::from(u16::from_le_bytes([header[1], header[2]]));
+        // The body excludes the checksum.
+        let body = data
+            .get(pos + 3..pos + 3 + length)?
+            .get(..length.checked_sub(1)?)?;
+        pos += 3 + length;
+        match header[0] {
+            PUBDEF | PUBDEF32 => parse_pubdef(body, header[0] == PUBDEF32, &mut symbols)?,
+            MODEND | MODEND32 => break,
+            _ => {}
+        }
+    }
+    if pos != data.len() {
+        return None;
+    }
+    Some(symbols)
+}
+
+/// Parse the names in a PUBDEF record.
+fn parse_pubdef<'a>(body: &'a [u8], is_32bit: bool, symbols: &mut Vec<&'a [u8]>) -> Option<()> {
+    let mut p = 0;
+    // Base group index.
+    read_index(body, &mut p)?;
+    let segment = read_index(body, &mut p)?;
+    if segment == 0 {
+        // Base frame.
+        p += 2;
+    }
+    let offset_size = if is_32bit { 4 } else { 2 };
+    while p < body.len() {
+        let len = usize::from(*body.get(p)?);
+        symbols.push(body.get(p + 1..p + 1 + len)?);
+        p += 1 + len + offset_size;
+        // Type index.
+        read_index(body, &mut p)?;
+    }
+    Some(())
+}
+
+/// Read a 1 or 2 byte OMF index.
+fn read_index(data: &[u8], p: &mut usize) -> Option<u16> {
+    let first = *data.get(*p)?;
+    *p += 1;
+    if first & 0x80 == 0 {
+        return Some(first.into());
+    }
+    let second = *data.get(*p)?;
+    *p += 1;
+    Some(u16::from(first & 0x7F) << 8 | u16::from(second))
+}
+
+/// Build the dictionary for the given names and page numbers.
+///
+/// The number of blocks must be prime. If the names do not fit in a given
+/// number of blocks, then the next prime is tried.
+fn build_dictionary(entries: &[(&[u8], u16)]) -> Result<Vec<u8>> {
+    let size: usize = entries.iter().map(|(name, _)| (name.len() + 4) & !1).sum();
+    let min_blocks = core::cmp::max(
+        entries.len() / usize::from(DICTIONARY_BUCKETS) + 1,
+        size / (DICTIONARY_BLOCK_SIZE - usize::from(DICTIONARY_BUCKETS) - 1) + 1,
+    );
+    let mut blocks = min_blocks as u16;
+    loop {
+        blocks = next_prime(blocks)
+            .ok_or_else(|| Error(String::from("OMF library dictionary is too large")))?;
+        if let Some(dictionary) = try_build_dictionary(entries, blocks) {
+            return Ok(dictionary);
+        }
+        blocks += 1;
+    }
+}
+
+fn try_build_dictionary(entries: &[(&[u8], u16)], blocks: u16) -> Option<Vec<u8>> {
+    let mut data = alloc::vec![0; usize::from(blocks) * DICTIONARY_BLOCK_SIZE];
+    for block in data.chunks_exact_mut(DICTIONARY_BLOCK_SIZE) {
+        // The free space offset is in words.
+        block[usize::from(DICTIONARY_BUCKETS)] = (usize::from(DICTIONARY_BUCKETS) + 1) as u8 / 2;
+    }
+    'entries: for &(name, page) in entries {
+        if name.is_empty() || name.len() > 255 {
+            continue;
+        }
+        let hash = dictionary_hash(name, blocks);
+        let entry_size = (name.len() + 4) & !1;
+        let mut block = hash.block;
+        for _ in 0..blocks {
+            let data =
+                &mut data[usize::from(block) * DICTIONARY_BLOCK_SIZE..][..DICTIONARY_BLOCK_SIZE];
+            let free = data[usize::from(DICTIONARY_BUCKETS)];
+            if free != DICTIONARY_BLOCK_FULL {
+                let mut bucket = hash.bucket;
+                for _ in 0..DICTIONARY_BUCKETS {
+                    if data[usize::from(bucket)] == 0 {
+                        let offset = usize::from(free) * 2;
+                        if offset + entry_size > DICTIONARY_BLOCK_SIZE {
+                            break;
+                        }
+                        data[usize::from(bucket)] = free;
+                        data[offset] = name.len() as u8;
+                        data[offset + 1..][..name.len()].copy_from_slice(name);
+                        data[offset + 1 + name.len()..][..2].copy_from_slice(&page.to_le_bytes());
+                        let free = offset + entry_size;
+                        data[usize::from(DICTIONARY_BUCKETS)] = if free >= DICTIONARY_BLOCK_SIZE {
+                            DICTIONARY_BLOCK_FULL
+                        } else {
+                            (free / 2) as u8
+                        };
+                        continue 'entries;
+                    }
+                    bucket = (bucket + hash.bucket_delta) % DICTIONARY_BUCKETS;
+                }
+                // Lookups continue to the next block only if this block is full.
+                data[usize::from(DICTIONARY_BUCKETS)] = DICTIONARY_BLOCK_FULL;
+            }
+            block = (block + hash.block_delta) % blocks;
+        }
+        return None;
+    }
+    Some(data)
+}
+
+/// Return the smallest prime that is at least `n`.
+fn next_prime(n: u16) -> Option<u16> {
+    (n.max(2)..=u16::MAX).find(|&n| (2..n).take_while(|i| i * i <= n).all(|i| n % i != 0))
+}
@@ -0,0 +1,14 @@
+//! Support for writing OMF files.
+//!
+//! Provides [`LibraryWriter`] for writing OMF libraries, and [`Writer`] for
+//! writing the individual records of an OMF object module.
+//! This also provides OMF support for [`write::Object`](crate::write::Object).
+
+mod library;
+pub use library::*;
+
+mod object;
+pub use object::*;
+
+mod writer;
+pub use writer::*;
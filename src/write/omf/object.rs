@@ -0,0 +1,838 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use crate::omf::{
+    CEXTDEF, COMDAT, COMDAT32, COMDEF, EXTDEF, LPUBDEF, LPUBDEF32, PUBDEF, PUBDEF32, SEGDEF,
+    SEGDEF32, THEADR,
+};
+use crate::write::*;
+
+use super::{write_comdef_length, write_index, write_name, Fixup, StartAddress, Writer};
+
+/// The NEAR data type of a COMDEF entry.
+const COMDEF_NEAR: u8 = 0x62;
+
+/// The classes of the segments that are placed in DGROUP.
+const DGROUP_CLASSES: [&[u8]; 4] = [b"DATA", b"CONST", b"BSS", b"STACK"];
+
+/// COMDAT selection criteria.
+const COMDAT_NO_MATCH: u8 = 0x00;
+const COMDAT_PICK_ANY: u8 = 0x10;
+const COMDAT_SAME_SIZE: u8 = 0x20;
+const COMDAT_EXACT_MATCH: u8 = 0x30;
+
+/// COMDAT allocation type for explicit allocation in a segment.
+const COMDAT_EXPLICIT: u8 = 0x00;
+
+/// COMDAT flags.
+const COMDAT_CONTINUATION: u8 = 0x01;
+const COMDAT_LOCAL: u8 = 0x04;
+
+/// The processor and addressing model that an OMF object file targets.
+///
+/// This determines whether 16-bit or 32-bit records and segments are used.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TargetProfile {
+    /// 8086 real mode, using 16-bit records and segments.
+    Real8086,
+    /// 80286 protected mode, using 16-bit records and segments.
+    ///
+    /// The records are the same as for real mode.
+    Protected286,
+    /// 80386 flat model, using 32-bit records and segments.
+    ///
+    /// Fixups use the `FLAT` group as their frame.
+    #[default]
+    Flat386,
+}
+
+impl TargetProfile {
+    /// Return true if the profile uses 32-bit records and segments.
+    #[inline]
+    pub fn is_32bit(self) -> bool {
+        self == TargetProfile::Flat386
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+struct SymbolOffsets {
+    /// The 1-based index in the external index space, if the symbol is external.
+    ///
+    /// For COMDAT symbols, this is the index of their CEXTDEF entry.
+    external: u16,
+}
+
+#[derive(Default, Clone, Copy)]
+struct SectionOffsets {
+    /// The 1-based index of the SEGDEF containing the section.
+    segment: u16,
+    comdat: Option<SectionComdat>,
+}
+
+#[derive(Clone, Copy)]
+struct SectionComdat {
+    /// The selection criteria and allocation type.
+    attributes: u8,
+    flags: u8,
+    /// The LNAMES index of the COMDAT name.
+    name: u16,
+    /// The index of the COMDAT symbol.
+    symbol: usize,
+}
+
+/// The indices needed to write fixups.
+struct Offsets<'a> {
+    symbols: &'a [SymbolOffsets],
+    sections: &'a [SectionOffsets],
+    /// The 1-based GRPDEF index of each segment's group, or 0 if it has none.
+    segment_groups: &'a [u16],
+    /// The group index of the FLAT group, if fixups use it as their frame.
+    flat_group: Option<u16>,
+    is_32bit: bool,
+}
+
+// Public methods.
+impl<'a> Object<'a> {
+    /// Add a source line number for an offset within a section.
+    ///
+    /// The line numbers are written as LINNUM records, or as LINSYM records
+    /// for COMDAT sections. The source file is the name of the first file
+    /// symbol.
+    ///
+    /// Requires `feature = "omf"`.
+    pub fn add_omf_line_number(&mut self, section: SectionId, offset: u32, line: u16) {
+        self.omf_line_numbers.push((section, offset, line));
+    }
+
+    /// Specify the processor and addressing model for an OMF object file.
+    ///
+    /// The default is [`TargetProfile::Flat386`]. For 16-bit profiles, writing
+    /// fails if a section is larger than 64K, or an offset or relocation does
+    /// not fit in 16 bits.
+    ///
+    /// Requires `feature = "omf"`.
+    #[inline]
+    pub fn set_omf_target_profile(&mut self, profile: TargetProfile) {
+        self.omf_target_profile = profile;
+    }
+
+    /// Mark the OMF module as the main module of a program, and set the start
+    /// address to the given symbol.
+    ///
+    /// Requires `feature = "omf"`.
+    #[inline]
+    pub fn set_omf_entry(&mut self, symbol: SymbolId) {
+        self.omf_entry = Some(symbol);
+    }
+
+    /// Add an OMF group containing the segments of the given sections.
+    ///
+    /// Groups are written in the order they are added, after the `FLAT` group
+    /// for the 386 flat profile. A segment can only be in one group. In
+    /// 16-bit profiles, fixups that target a segment in a group use the group
+    /// as their frame.
+    ///
+    /// Requires `feature = "omf"`.
+    pub fn add_omf_group(&mut self, name: Vec<u8>, sections: Vec<SectionId>) {
+        self.omf_groups.push((name, sections));
+    }
+
+    /// Specify whether to gather the segments with a `DATA`, `CONST`, `BSS`
+    /// or `STACK` class into `DGROUP`, as DOS compilers do.
+    ///
+    /// `DGROUP` is added after the groups from [`Self::add_omf_group`], and
+    /// is omitted if there are no such segments.
+    ///
+    /// Requires `feature = "omf"`.
+    #[inline]
+    pub fn set_omf_dgroup(&mut self, dgroup: bool) {
+        self.omf_dgroup = dgroup;
+    }
+
+    /// Specify whether to write 0 instead of the checksum of each OMF record.
+    ///
+    /// See [`Writer::set_zero_checksums`].
+    ///
+    /// Requires `feature = "omf"`.
+    #[inline]
+    pub fn set_omf_zero_checksums(&mut self, zero_checksums: bool) {
+        self.omf_zero_checksums = zero_checksums;
+    }
+}
+
+// Private methods.
+impl<'a> Object<'a> {
+    pub(crate) fn omf_segment_name(&self, segment: StandardSegment) -> &'static [u8] {
+        // The segment of a section is used as the class name of its SEGDEF.
+        match segment {
+            StandardSegment::Text => &b"CODE"[..],
+            StandardSegment::Data => &b"DATA"[..],
+            StandardSegment::Debug => &b"DEBUG"[..],
+        }
+    }
+
+    pub(crate) fn omf_section_info(
+        &self,
+        section: StandardSection,
+    ) -> (&'static [u8], &'static [u8], SectionKind, SectionFlags) {
+        match section {
+            StandardSection::Text => (
+                &b"CODE"[..],
+                &b"_TEXT"[..],
+                SectionKind::Text,
+                SectionFlags::None,
+            ),
+            StandardSection::Data => (
+                &b"DATA"[..],
+                &b"_DATA"[..],
+                SectionKind::Data,
+                SectionFlags::None,
+            ),
+            StandardSection::ReadOnlyData
+            | StandardSection::ReadOnlyDataWithRel
+            | StandardSection::ReadOnlyString => (
+                &b"CONST"[..],
+                &b"CONST"[..],
+                SectionKind::ReadOnlyData,
+                SectionFlags::None,
+            ),
+            StandardSection::UninitializedData => (
+                &b"BSS"[..],
+                &b"_BSS"[..],
+                SectionKind::UninitializedData,
+                SectionFlags::None,
+            ),
+            StandardSection::Tls => {
+                // Unsupported section.
+                (&[], &[], SectionKind::Tls, SectionFlags::None)
+            }
+            StandardSection::UninitializedTls => {
+                // Unsupported section.
+                (&[], &[], SectionKind::UninitializedTls, SectionFlags::None)
+            }
+            StandardSection::TlsVariables => {
+                // Unsupported section.
+                (&[], &[], SectionKind::TlsVariables, SectionFlags::None)
+            }
+            StandardSection::Common => {
+                // Unsupported section.
+                (&[], &[], SectionKind::Common, SectionFlags::None)
+            }
+            StandardSection::GnuProperty => {
+                // Unsupported section.
+                (&[], &[], SectionKind::Note, SectionFlags::None)
+            }
+        }
+    }
+
+    pub(crate) fn omf_translate_relocation(&mut self, reloc: &mut Relocation) -> Result<()> {
+        // Generic relocations are converted to fixups when writing.
+        if fixup_location(reloc.flags).is_none() {
+            return Err(Error(format!("unimplemented relocation {:?}", reloc)));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn omf_adjust_addend(&mut self, relocation: &mut Relocation) -> Result<bool> {
+        // Self-relative fixups are relative to the end of the location.
+        if let RelocationFlags::Generic {
+            kind: RelocationKind::Relative,
+            size,
+            ..
+        } = relocation.flags
+        {
+            relocation.addend += i64::from(size / 8);
+        }
+        // The addend is stored in the target displacement of the fixup.
+        Ok(false)
+    }
+
+    pub(crate) fn omf_relocation_size(&self, reloc: &Relocation) -> Result<u8> {
+        if let RelocationFlags::Generic { size, .. } = reloc.flags {
+            Ok(size)
+        } else {
+            Err(Error(format!("unexpected relocation {:?}", reloc)))
+        }
+    }
+
+    pub(crate) fn omf_write(&self, buffer: &mut dyn WritableBuffer) -> Result<()> {
+        if self.architecture != Architecture::I386 {
+            return Err(Error(format!(
+                "unimplemented architecture {:?}",
+                self.architecture
+            )));
+        }
+        let is_32bit = self.omf_target_profile.is_32bit();
+        // The largest segment size. Segments of exactly this size set the big bit.
+        let max_size = if is_32bit { 1 << 32 } else { 1 << 16 };
+        let mut writer = Writer::new();
+        writer.set_zero_checksums(self.omf_zero_checksums);
+
+        // The module name is taken from the first file symbol.
+        let module_name = self
+            .symbols
+            .iter()
+            .find(|symbol| symbol.kind == SymbolKind::File)
+            .map(|symbol| &*symbol.name)
+            .unwrap_or(&[]);
+        let mut theadr = Vec::new();
+        write_name(&mut theadr, module_name)?;
+        writer.write_record(THEADR, &theadr)?;
+
+        // Find the COMDAT sections.
+        let mut section_offsets = vec![SectionOffsets::default(); self.sections.len()];
+        for comdat in &self.comdats {
+            let symbol = &self.symbols[comdat.symbol.0];
+            let comdat_section = match symbol.section {
+                SymbolSection::Section(id) if symbol.value == 0 => id.0,
+                _ => {
+                    return Err(Error(format!(
+                        "unsupported COMDAT symbol `{}` section {:?}",
+                        symbol.name().unwrap_or(""),
+                        symbol.section
+                    )));
+                }
+            };
+            let selection = match comdat.kind {
+                ComdatKind::NoDuplicates => COMDAT_NO_MATCH,
+                ComdatKind::Any => COMDAT_PICK_ANY,
+                ComdatKind::SameSize => COMDAT_SAME_SIZE,
+                ComdatKind::ExactMatch => COMDAT_EXACT_MATCH,
+                _ => {
+                    return Err(Error(format!(
+                        "unsupported COMDAT symbol `{}` kind {:?}",
+                        symbol.name().unwrap_or(""),
+                        comdat.kind
+                    )));
+                }
+            };
+            if let Some(id) = comdat.sections.iter().find(|id| id.0 != comdat_section) {
+                return Err(Error(format!(
+                    "unsupported OMF associative COMDAT section `{}`",
+                    self.sections[id.0].name().unwrap_or("")
+                )));
+            }
+            let flags = if symbol.scope == SymbolScope::Compilation {
+                COMDAT_LOCAL
+            } else {
+                0
+            };
+            section_offsets[comdat_section].comdat = Some(SectionComdat {
+                // Explicit allocation, in the segment of the section.
+                attributes: selection | COMDAT_EXPLICIT,
+                flags,
+                name: 0,
+                symbol: comdat.symbol.0,
+            });
+        }
+
+        // Assign LNAMES indices. The empty name is used for overlay names.
+        let empty_name = writer.add_name(&[])?;
+        // Flat model code uses the FLAT group as the frame of its fixups.
+        let flat_name = if is_32bit {
+            Some(writer.add_name(b"FLAT")?)
+        } else {
+            None
+        };
+        // COMDATs are allocated in a segment with the same name and class as
+        // their section, which is only defined once. Segments for other
+        // sections come first, so that their data can be written in order.
+        let mut segments = Vec::new();
+        let mut segment_names = Vec::new();
+        let mut segment_classes = Vec::new();
+        let mut order = (0..self.sections.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&index| section_offsets[index].comdat.is_some());
+        for index in order {
+            let section = &self.sections[index];
+            if section.size > max_size {
+                return Err(Error(format!(
+                    "OMF segment `{}` is too large",
+                    section.name().unwrap_or("")
+                )));
+            }
+            let class = if section.segment.is_empty() {
+                match section.kind {
+                    SectionKind::Text => &b"CODE"[..],
+                    SectionKind::ReadOnlyData | SectionKind::ReadOnlyString => &b"CONST"[..],
+                    SectionKind::UninitializedData => &b"BSS"[..],
+                    _ => &b"DATA"[..],
+                }
+            } else {
+                &*section.segment
+            };
+            let names = (writer.add_name(&section.name)?, writer.add_name(class)?);
+            let offsets = &mut section_offsets[index];
+            if let Some(comdat) = &mut offsets.comdat {
+                comdat.name = writer.add_name(&self.symbols[comdat.symbol].name)?;
+                if let Some(segment) = segment_names.iter().position(|&n| n == names) {
+                    offsets.segment = segment as u16 + 1;
+                    continue;
+                }
+            }
+            segment_names.push(names);
+            segment_classes.push(class);
+            segments.push(index);
+            offsets.segment = u16::try_from(segments.len())
+                .ok()
+                .filter(|&segment| segment < 0x8000)
+                .ok_or_else(|| Error(String::from("too many OMF segments")))?;
+        }
+
+        // Assign the segments to groups. A segment can only be in one group.
+        let mut groups = Vec::new();
+        if let Some(flat_name) = flat_name {
+            // The FLAT group has no segments.
+            groups.push((flat_name, Vec::new()));
+        }
+        let mut segment_groups = vec![0; segments.len()];
+        let mut group_sections = self
+            .omf_groups
+            .iter()
+            .map(|(name, sections)| (&**name, sections.iter().map(|id| id.0).collect()))
+            .collect::<Vec<(&[u8], Vec<_>)>>();
+        if self.omf_dgroup {
+            let sections = segments
+                .iter()
+                .zip(&segment_classes)
+                .filter(|&(_, class)| DGROUP_CLASSES.contains(class))
+                .map(|(&index, _)| index)
+                .collect::<Vec<_>>();
+            if !sections.is_empty() {
+                group_sections.push((&b"DGROUP"[..], sections));
+            }
+        }
+        for (name, sections) in group_sections {
+            let group = u16::try_from(groups.len() + 1)
+                .ok()
+                .filter(|&group| group < 0x8000)
+                .ok_or_else(|| Error(String::from("too many OMF groups")))?;
+            let mut group_segments = Vec::new();
+            for index in sections {
+                let segment = section_offsets[index].segment;
+                let segment_group = &mut segment_groups[usize::from(segment) - 1];
+                if *segment_group == group {
+                    continue;
+                }
+                if *segment_group != 0 {
+                    return Err(Error(format!(
+                        "OMF segment `{}` is in more than one group",
+                        self.sections[index].name().unwrap_or("")
+                    )));
+                }
+                *segment_group = group;
+                group_segments.push(segment);
+            }
+            groups.push((writer.add_name(name)?, group_segments));
+        }
+        writer.write_lnames()?;
+
+        // Write the SEGDEF records.
+        for (&index, &(name, class)) in segments.iter().zip(&segment_names) {
+            let section = &self.sections[index];
+            // The contents of COMDATs are added by the linker.
+            let length = if section_offsets[index].comdat.is_some() {
+                0
+            } else {
+                section.size
+            };
+            let alignment = segment_alignment(section.align)?;
+            // Public combination.
+            let mut acbp = (alignment << 5) | (2 << 2);
+            if length == max_size {
+                // The big bit, for a segment of the largest size.
+                acbp |= 0x02;
+            }
+            if is_32bit {
+                acbp |= 0x01;
+            }
+            let mut body = vec![acbp];
+            write_offset(&mut body, is_32bit, length & (max_size - 1));
+            write_index(&mut body, name)?;
+            write_index(&mut body, class)?;
+            write_index(&mut body, empty_name)?;
+            let kind = if is_32bit { SEGDEF32 } else { SEGDEF };
+            writer.write_record(kind, &body)?;
+        }
+
+        // Write the GRPDEF records.
+        for (name, segments) in &groups {
+            writer.write_group(*name, segments)?;
+        }
+        let flat_group = flat_name.map(|_| 1);
+
+        // Assign external indices, and collect the public symbols for each segment.
+        let mut symbol_offsets = vec![SymbolOffsets::default(); self.symbols.len()];
+        let mut externals = Vec::new();
+        let mut commons = Vec::new();
+        let mut publics = vec![(Vec::new(), Vec::new()); segments.len()];
+        let mut absolutes = Vec::new();
+        for (index, symbol) in self.symbols.iter().enumerate() {
+            if matches!(symbol.kind, SymbolKind::File | SymbolKind::Section) {
+                continue;
+            }
+            if let SymbolSection::Section(id) = symbol.section {
+                if let Some(comdat) = section_offsets[id.0].comdat {
+                    // The COMDAT record defines the COMDAT symbol, and other
+                    // symbols can only be referenced relative to it.
+                    if comdat.symbol != index && symbol.scope != SymbolScope::Compilation {
+                        return Err(Error(format!(
+                            "unsupported OMF symbol `{}` in COMDAT section",
+                            symbol.name().unwrap_or("")
+                        )));
+                    }
+                    continue;
+                }
+            }
+            let mut entry = Vec::new();
+            write_name(&mut entry, &symbol.name)?;
+            match symbol.section {
+                SymbolSection::None => continue,
+                SymbolSection::Undefined => {
+                    // Type index.
+                    entry.push(0);
+                    externals.push((index, entry));
+                }
+                SymbolSection::Common => {
+                    entry.push(0);
+                    entry.push(COMDEF_NEAR);
+                    write_comdef_length(&mut entry, symbol.size)?;
+                    commons.push((index, entry));
+                }
+                SymbolSection::Absolute | SymbolSection::Section(_) => {
+                    if symbol.value >= max_size {
+                        return Err(Error(format!(
+                            "OMF symbol `{}` value is too large",
+                            symbol.name().unwrap_or("")
+                        )));
+                    }
+                    write_offset(&mut entry, is_32bit, symbol.value);
+                    entry.push(0);
+                    let is_global = symbol.scope != SymbolScope::Compilation;
+                    match symbol.section {
+                        SymbolSection::Section(id) => {
+                            let segment = usize::from(section_offsets[id.0].segment) - 1;
+                            let (global, local) = &mut publics[segment];
+                            let publics = if is_global { global } else { local };
+                            publics.push(entry);
+                        }
+                        _ => absolutes.push((is_global, entry)),
+                    }
+                }
+            }
+        }
+        // COMDATs are referenced using CEXTDEF records.
+        let mut comdat_externals = Vec::new();
+        for (index, offsets) in section_offsets.iter().enumerate() {
+            if let Some(comdat) = offsets.comdat {
+                let target = SymbolSection::Section(SectionId(index));
+                let referenced = self.sections.iter().any(|section| {
+                    section
+                        .relocations
+                        .iter()
+                        .any(|relocation| self.symbols[relocation.symbol.0].section == target)
+                }) || self
+                    .omf_entry
+                    .map_or(false, |entry| self.symbols[entry.0].section == target);
+                if referenced {
+                    let mut entry = Vec::new();
+                    write_index(&mut entry, comdat.name)?;
+                    // Type index.
+                    entry.push(0);
+                    comdat_externals.push((comdat.symbol, entry));
+                }
+            }
+        }
+        // EXTDEF, COMDEF and CEXTDEF records share the external index space.
+        let all_externals = externals.iter().chain(&commons).chain(&comdat_externals);
+        for (external, &(index, _)) in all_externals.enumerate() {
+            symbol_offsets[index].external = u16::try_from(external + 1)
+                .ok()
+                .filter(|&external| external < 0x8000)
+                .ok_or_else(|| Error(String::from("too many OMF external symbols")))?;
+        }
+
+        // Write the symbol definitions.
+        let (pubdef, lpubdef) = if is_32bit {
+            (PUBDEF32, LPUBDEF32)
+        } else {
+            (PUBDEF, LPUBDEF)
+        };
+        for (index, (global, local)) in publics.iter().enumerate() {
+            let mut header = Vec::new();
+            write_index(&mut header, segment_groups[index])?;
+            write_index(&mut header, index as u16 + 1)?;
+            writer.write_entries(pubdef, &header, global)?;
+            writer.write_entries(lpubdef, &header, local)?;
+        }
+        for &(is_global, ref entry) in &absolutes {
+            // No group or segment, and a frame number of 0.
+            let kind = if is_global { pubdef } else { lpubdef };
+            writer.write_entries(kind, &[0, 0, 0, 0], core::slice::from_ref(entry))?;
+        }
+        let externals = externals.into_iter().map(|(_, entry)| entry);
+        writer.write_entries(EXTDEF, &[], &externals.collect::<Vec<_>>())?;
+        let commons = commons.into_iter().map(|(_, entry)| entry);
+        writer.write_entries(COMDEF, &[], &commons.collect::<Vec<_>>())?;
+        let comdat_externals = comdat_externals.into_iter().map(|(_, entry)| entry);
+        writer.write_entries(CEXTDEF, &[], &comdat_externals.collect::<Vec<_>>())?;
+
+        // Group the line numbers by section.
+        let mut section_lines = vec![Vec::new(); self.sections.len()];
+        for &(section, offset, line) in &self.omf_line_numbers {
+            section_lines[section.0].push((offset, line));
+        }
+        for lines in &mut section_lines {
+            lines.sort_by_key(|&(offset, _)| offset);
+        }
+
+        // Write the section data, fixups and line numbers.
+        let offsets = Offsets {
+            symbols: &symbol_offsets,
+            sections: &section_offsets,
+            segment_groups: &segment_groups,
+            flat_group,
+            is_32bit,
+        };
+        for (index, section) in self.sections.iter().enumerate() {
+            let segment = section_offsets[index].segment;
+            if let Some(comdat) = section_offsets[index].comdat {
+                let align = match section.align {
+                    // Use the alignment of the segment.
+                    0..=1 => 0,
+                    align => segment_alignment(align)?,
+                };
+                let header = |start: usize| {
+                    let mut flags = comdat.flags;
+                    if start != 0 {
+                        flags |= COMDAT_CONTINUATION;
+                    }
+                    let mut body = vec![flags, comdat.attributes, align];
+                    write_offset(&mut body, is_32bit, start as u64);
+                    // Type index, and the public base.
+                    body.push(0);
+                    write_index(&mut body, segment_groups[usize::from(segment) - 1])?;
+                    write_index(&mut body, segment)?;
+                    write_index(&mut body, comdat.name)?;
+                    Ok((if is_32bit { COMDAT32 } else { COMDAT }, body))
+                };
+                let fixups = self.omf_section_fixups(index, &offsets)?;
+                if section.size == 0 {
+                    // COMDATs always have at least one record.
+                    let (kind, body) = header(0)?;
+                    writer.write_record(kind, &body)?;
+                } else if section.is_bss() {
+                    // COMDATs must be initialized.
+                    let data = vec![0; section.size as usize];
+                    writer.write_data(is_32bit, &data, &fixups, header)?;
+                } else {
+                    writer.write_data(is_32bit, &section.data, &fixups, header)?;
+                }
+                writer.write_comdat_line_numbers(is_32bit, comdat.name, &section_lines[index])?;
+                continue;
+            }
+            if section.is_bss() {
+                if !section.relocations.is_empty() {
+                    return Err(Error(format!(
+                        "OMF segment `{}` has relocations but no data",
+                        section.name().unwrap_or("")
+                    )));
+                }
+                continue;
+            }
+            let fixups = self.omf_section_fixups(index, &offsets)?;
+            writer.write_ledata(is_32bit, segment, 0, &section.data, &fixups)?;
+            writer.write_line_numbers(is_32bit, segment, &section_lines[index])?;
+        }
+
+        // The start address is relative to the entry symbol.
+        let start = match self.omf_entry {
+            Some(entry) => {
+                let (target_method, target_index, value) =
+                    self.omf_fixup_target(entry, &offsets)?;
+                let (frame_method, frame_index) =
+                    self.omf_fixup_frame(target_method, target_index, &offsets);
+                Some(StartAddress::Logical {
+                    frame_method,
+                    frame_index,
+                    target_method,
+                    target_index,
+                    displacement: value as u32,
+                })
+            }
+            None => None,
+        };
+        writer.write_modend(is_32bit, self.omf_entry.is_some(), start)?;
+        writer.write(buffer)
+    }
+
+    /// Return the fixups for the relocations of a section.
+    fn omf_section_fixups(&self, index: usize, offsets: &Offsets<'_>) -> Result<Vec<Fixup>> {
+        let section = &self.sections[index];
+        let mut fixups = Vec::with_capacity(section.relocations.len());
+        for relocation in &section.relocations {
+            let (location, segment_relative, size) = fixup_location(relocation.flags).unwrap();
+            if size == 4 && !offsets.is_32bit {
+                return Err(Error(format!(
+                    "unsupported 32-bit relocation in 16-bit OMF segment `{}`",
+                    section.name().unwrap_or("")
+                )));
+            }
+            let offset = u32::try_from(relocation.offset)
+                .ok()
+                .filter(|&offset| offset as usize + size <= section.data.len())
+                .ok_or_else(|| {
+                    Error(format!(
+                        "invalid relocation offset {} in OMF segment `{}`",
+                        relocation.offset,
+                        section.name().unwrap_or("")
+                    ))
+                })?;
+            let (target_method, target_index, value) =
+                self.omf_fixup_target(relocation.symbol, offsets)?;
+            // Negative displacements wrap around.
+            let displacement = value
+                .checked_add(relocation.addend)
+                .and_then(|displacement| {
+                    if offsets.is_32bit {
+                        u32::try_from(displacement)
+                            .ok()
+                            .or_else(|| i32::try_from(displacement).ok().map(|d| d as u32))
+                    } else {
+                        u16::try_from(displacement)
+                            .ok()
+                            .or_else(|| i16::try_from(displacement).ok().map(|d| d as u16))
+                            .map(u32::from)
+                    }
+                })
+                .ok_or_else(|| Error(format!("invalid OMF fixup addend {:?}", relocation)))?;
+            let (frame_method, frame_index) =
+                self.omf_fixup_frame(target_method, target_index, offsets);
+            fixups.push(Fixup {
+                offset,
+                location,
+                segment_relative,
+                frame_method,
+                frame_index,
+                target_method,
+                target_index,
+                displacement,
+            });
+        }
+        Ok(fixups)
+    }
+
+    /// Return the frame method and frame index for a fixup target.
+    fn omf_fixup_frame(
+        &self,
+        target_method: u8,
+        target_index: u16,
+        offsets: &Offsets<'_>,
+    ) -> (u8, u16) {
+        if let Some(group) = offsets.flat_group {
+            // Frame method F1, using the FLAT group.
+            return (1, group);
+        }
+        if target_method == 0 {
+            let group = offsets.segment_groups[usize::from(target_index) - 1];
+            if group != 0 {
+                // Frame method F1, so that the offset is relative to the group.
+                return (1, group);
+            }
+        }
+        // Frame method F5, using the frame of the target.
+        (5, 0)
+    }
+
+    /// Return the target method, target index, and base displacement for a symbol.
+    fn omf_fixup_target(
+        &self,
+        symbol_id: SymbolId,
+        offsets: &Offsets<'_>,
+    ) -> Result<(u8, u16, i64)> {
+        let symbol = &self.symbols[symbol_id.0];
+        match symbol.section {
+            SymbolSection::Section(id) => {
+                let value = if symbol.kind == SymbolKind::Section {
+                    0
+                } else {
+                    symbol.value as i64
+                };
+                match offsets.sections[id.0].comdat {
+                    // Method T2: the external index of the COMDAT.
+                    Some(comdat) => Ok((2, offsets.symbols[comdat.symbol].external, value)),
+                    // Method T0: segment index.
+                    None => Ok((0, offsets.sections[id.0].segment, value)),
+                }
+            }
+            // Method T2: external index.
+            SymbolSection::Undefined | SymbolSection::Common => {
+                Ok((2, offsets.symbols[symbol_id.0].external, 0))
+            }
+            _ => Err(Error(format!(
+                "unimplemented relocation target for symbol `{}`",
+                symbol.name().unwrap_or("")
+            ))),
+        }
+    }
+}
+
+/// Return the SEGDEF alignment type for an alignment in bytes.
+fn segment_alignment(align: u64) -> Result<u8> {
+    Ok(match align {
+        0..=1 => 1,
+        2 => 2,
+        3..=4 => 5,
+        5..=16 => 3,
+        17..=256 => 4,
+        257..=4096 => 6,
+        _ => {
+            return Err(Error(format!(
+                "unimplemented OMF segment alignment {}",
+                align
+            )))
+        }
+    })
+}
+
+/// Append a 16-bit or 32-bit offset.
+///
+/// The offset must already have been checked to fit.
+fn write_offset(out: &mut Vec<u8>, is_32bit: bool, offset: u64) {
+    if is_32bit {
+        out.extend_from_slice(&(offset as u32).to_le_bytes());
+    } else {
+        out.extend_from_slice(&(offset as u16).to_le_bytes());
+    }
+}
+
+/// Return the location type, whether the fixup is segment-relative, and the
+/// size in bytes of the location, for a relocation.
+fn fixup_location(flags: RelocationFlags) -> Option<(u8, bool, usize)> {
+    let (kind, encoding, size) = match flags {
+        RelocationFlags::Generic {
+            kind,
+            encoding,
+            size,
+        } => (kind, encoding, size),
+        _ => return None,
+    };
+    if encoding != RelocationEncoding::Generic {
+        return None;
+    }
+    Some(match (kind, size) {
+        // Low-order byte.
+        (RelocationKind::Absolute | RelocationKind::SegmentRelative, 8) => (0, true, 1),
+        (RelocationKind::Relative, 8) => (0, false, 1),
+        // 16-bit offset.
+        (RelocationKind::Absolute | RelocationKind::SegmentRelative, 16) => (1, true, 2),
+        (RelocationKind::Relative, 16) => (1, false, 2),
+        // 16-bit segment base.
+        (RelocationKind::SectionIndex, 16) => (2, true, 2),
+        // 32-bit offset.
+        (RelocationKind::Absolute | RelocationKind::SegmentRelative, 32) => (9, true, 4),
+        (RelocationKind::Relative, 32) => (9, false, 4),
+        _ => return None,
+    })
+}
@@ -0,0 +1,806 @@
+//! Helper for writing OMF object modules.
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+#[cfg(not(feature = "std"))]
+use hashbrown::HashMap;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::omf::{
+    COMENT, COMENT_COPYRIGHT, COMENT_DEFAULT_LIBRARY, COMENT_DOSSEG, COMENT_MEMORY_MODEL,
+    COMENT_NO_PURGE, COMENT_OMFEXT, COMENT_TRANSLATOR, FIXUPP, FIXUPP32, GRPDEF, LEDATA, LEDATA32,
+    LINNUM, LINNUM32, LINSYM, LINSYM32, LNAMES, MODEND, MODEND32, OMFEXT_EXPDEF, OMFEXT_IMPDEF,
+};
+use crate::write::{Error, Result, WritableBuffer};
+
+/// The size at which [`Writer`] splits records that contain a list of entries.
+const MAX_RECORD_SIZE: usize = 1024;
+
+/// The maximum size of the data in a data record.
+///
+/// The location of a fixup is a 10-bit offset within the data of the
+/// preceding data record.
+const MAX_DATA_SIZE: usize = 1024;
+
+/// A helper for writing OMF object modules.
+///
+/// OMF modules are a sequence of records, so this collects each record as it
+/// is given, and [`Self::write`] writes them all to the output buffer. The
+/// record length and checksum are added automatically. The output only
+/// depends on the records that are written, so identical inputs produce
+/// identical modules.
+///
+/// Records refer to names by their index in the LNAMES records. Use
+/// [`Self::add_name`] to allocate these indices, and [`Self::write_lnames`]
+/// to write the names before the records that use them.
+#[derive(Debug, Default)]
+pub struct Writer {
+    records: Vec<u8>,
+    zero_checksums: bool,
+
+    names: HashMap<Vec<u8>, u16>,
+    name_count: u16,
+    pending_names: Vec<u8>,
+
+    frame_threads: [Option<(u8, u16)>; 4],
+    target_threads: [Option<(u8, u16)>; 4],
+}
+
+impl Writer {
+    /// Create a new `Writer`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether to write 0 instead of computing the checksum of each record.
+    ///
+    /// MASM writes 0, which linkers accept as a record without a checksum.
+    /// This only affects records written after the call.
+    pub fn set_zero_checksums(&mut self, zero_checksums: bool) {
+        self.zero_checksums = zero_checksums;
+    }
+
+    /// Return the number of bytes in the records written so far.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Return true if no records have been written yet.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Write the records to the output buffer.
+    pub fn write(&self, buffer: &mut dyn WritableBuffer) -> Result<()> {
+        buffer
+            .reserve(self.records.len())
+            .map_err(|_| Error(String::from("Cannot allocate buffer")))?;
+        buffer.write_bytes(&self.records);
+        Ok(())
+    }
+
+    /// Write a record with the given type and body.
+    ///
+    /// The record length and checksum are added to the body.
+    pub fn write_record(&mut self, kind: u8, body: &[u8]) -> Result<()> {
+        let length = u16::try_from(body.len() + 1)
+            .map_err(|_| Error(format!("OMF record 0x{:02x} is too long", kind)))?;
+        let mut header = [kind, 0, 0];
+        header[1..].copy_from_slice(&length.to_le_bytes());
+        let checksum = if self.zero_checksums {
+            0
+        } else {
+            let sum = header
+                .iter()
+                .chain(body)
+                .fold(0u8, |sum, &byte| sum.wrapping_add(byte));
+            0u8.wrapping_sub(sum)
+        };
+        self.records.extend_from_slice(&header);
+        self.records.extend_from_slice(body);
+        self.records.push(checksum);
+        Ok(())
+    }
+
+    /// Write records containing a list of entries.
+    ///
+    /// Each record body starts with `header`, followed by as many entries as
+    /// fit. This is used for records such as PUBDEF and EXTDEF that define a
+    /// variable number of names. Nothing is written if there are no entries.
+    pub fn write_entries(&mut self, kind: u8, header: &[u8], entries: &[Vec<u8>]) -> Result<()> {
+        let mut body = header.to_vec();
+        for entry in entries {
+            if body.len() > header.len() && body.len() + entry.len() > MAX_RECORD_SIZE {
+                self.write_record(kind, &body)?;
+                body.truncate(header.len());
+            }
+            body.extend_from_slice(entry);
+        }
+        if body.len() > header.len() {
+            self.write_record(kind, &body)?;
+        }
+        Ok(())
+    }
+
+    /// Write LEDATA records for data at an offset within a segment, each
+    /// followed by FIXUPP records for its fixups.
+    ///
+    /// `segment` is the 1-based SEGDEF index. The offset of each fixup is its
+    /// offset within `data`. See [`Self::write_data`] for how the data is split.
+    pub fn write_ledata(
+        &mut self,
+        is_32bit: bool,
+        segment: u16,
+        offset: u32,
+        data: &[u8],
+        fixups: &[Fixup],
+    ) -> Result<()> {
+        let max_size = if is_32bit { 1 << 32 } else { 1 << 16 };
+        if u64::from(offset) + data.len() as u64 > max_size {
+            return Err(Error(format!(
+                "OMF data at offset 0x{:x} is too large for segment {}",
+                offset, segment
+            )));
+        }
+        self.write_data(is_32bit, data, fixups, |start| {
+            let mut body = Vec::new();
+            write_index(&mut body, segment)?;
+            let offset = offset + start as u32;
+            if is_32bit {
+                body.extend_from_slice(&offset.to_le_bytes());
+                Ok((LEDATA32, body))
+            } else {
+                body.extend_from_slice(&(offset as u16).to_le_bytes());
+                Ok((LEDATA, body))
+            }
+        })
+    }
+
+    /// Write data records, each followed by FIXUPP records for its fixups.
+    ///
+    /// The data is split into records of at most 1024 bytes, without splitting
+    /// the location of a fixup across records. `header` returns the record
+    /// type and the fields preceding the data for a record starting at the
+    /// given offset within `data`. This allows the data to be written as
+    /// LEDATA or COMDAT records. Nothing is written if `data` is empty.
+    ///
+    /// The offset of each fixup is its offset within `data`, and the fixups
+    /// must not overlap.
+    pub fn write_data<F>(
+        &mut self,
+        is_32bit: bool,
+        data: &[u8],
+        fixups: &[Fixup],
+        mut header: F,
+    ) -> Result<()>
+    where
+        F: FnMut(usize) -> Result<(u8, Vec<u8>)>,
+    {
+        let mut fixups = fixups.to_vec();
+        fixups.sort_by_key(|fixup| fixup.offset);
+        let mut previous_end = 0;
+        for fixup in &fixups {
+            let offset = fixup.offset as usize;
+            let end = location_size(fixup.location).map(|size| offset + size);
+            match end {
+                Some(end) if offset >= previous_end && end <= data.len() => previous_end = end,
+                _ => return Err(Error(format!("invalid OMF fixup {:?}", fixup))),
+            }
+        }
+        let mut fixups = fixups.into_iter().peekable();
+
+        let mut start = 0;
+        while start < data.len() {
+            // Don't split the location of a fixup across records.
+            let mut end = core::cmp::min(start + MAX_DATA_SIZE, data.len());
+            for fixup in fixups.clone() {
+                let offset = fixup.offset as usize;
+                if offset >= end {
+                    break;
+                }
+                if offset + location_size(fixup.location).unwrap() > end {
+                    end = offset;
+                    break;
+                }
+            }
+
+            let (kind, mut body) = header(start)?;
+            body.extend_from_slice(&data[start..end]);
+            self.write_record(kind, &body)?;
+
+            let mut record_fixups = Vec::new();
+            while let Some(mut fixup) = fixups.next_if(|fixup| (fixup.offset as usize) < end) {
+                fixup.offset -= start as u32;
+                record_fixups.push(fixup);
+            }
+            self.write_fixups(is_32bit, &record_fixups)?;
+            start = end;
+        }
+        Ok(())
+    }
+
+    /// Write FIXUPP records for the preceding data record.
+    ///
+    /// Frames and targets that are used by several fixups are written as
+    /// THREAD subrecords, and then referred to by thread number. Threads remain
+    /// defined for later calls. The fixups are split across as many records as
+    /// needed.
+    pub fn write_fixups(&mut self, is_32bit: bool, fixups: &[Fixup]) -> Result<()> {
+        let kind = if is_32bit { FIXUPP32 } else { FIXUPP };
+        let mut frame_uses = HashMap::new();
+        let mut target_uses = HashMap::new();
+        for fixup in fixups {
+            if fixup.offset >= 0x400
+                || fixup.location > 0x0F
+                || matches!(fixup.frame_method, 3 | 6..=0xFF)
+                || fixup.target_method > 2
+                || (!is_32bit && fixup.displacement > 0xFFFF)
+            {
+                return Err(Error(format!("invalid OMF fixup {:?}", fixup)));
+            }
+            if fixup.frame_method < 3 {
+                *frame_uses
+                    .entry((fixup.frame_method, fixup.frame_index))
+                    .or_insert(0) += 1;
+            }
+            *target_uses
+                .entry((fixup.target_method, fixup.target_index))
+                .or_insert(0) += 1;
+        }
+
+        let mut body = Vec::new();
+        for fixup in fixups {
+            let mut subrecords = Vec::new();
+            let frame = (fixup.frame_method, fixup.frame_index);
+            let frame_thread = match frame_uses.get_mut(&frame) {
+                Some(uses) => {
+                    let remaining = *uses;
+                    *uses -= 1;
+                    select_thread(
+                        &mut self.frame_threads,
+                        frame,
+                        remaining,
+                        &frame_uses,
+                        0x40,
+                        &mut subrecords,
+                    )?
+                }
+                // F4 and F5 have no frame datum.
+                None => None,
+            };
+            let target = (fixup.target_method, fixup.target_index);
+            let uses = target_uses.get_mut(&target).unwrap();
+            let remaining = *uses;
+            *uses -= 1;
+            let target_thread = select_thread(
+                &mut self.target_threads,
+                target,
+                remaining,
+                &target_uses,
+                0x00,
+                &mut subrecords,
+            )?;
+
+            let mut locat = 0x80 | (fixup.location << 2) | (fixup.offset >> 8) as u8;
+            if fixup.segment_relative {
+                locat |= 0x40;
+            }
+            subrecords.push(locat);
+            subrecords.push(fixup.offset as u8);
+            let mut fix_data = match frame_thread {
+                Some(thread) => 0x80 | (thread << 4),
+                None => fixup.frame_method << 4,
+            };
+            fix_data |= match target_thread {
+                Some(thread) => 0x08 | thread,
+                None => fixup.target_method,
+            };
+            if fixup.displacement == 0 {
+                // No displacement.
+                fix_data |= 0x04;
+            }
+            subrecords.push(fix_data);
+            if frame_thread.is_none() && fixup.frame_method < 3 {
+                write_index(&mut subrecords, fixup.frame_index)?;
+            }
+            if target_thread.is_none() {
+                write_index(&mut subrecords, fixup.target_index)?;
+            }
+            if fixup.displacement != 0 {
+                if is_32bit {
+                    subrecords.extend_from_slice(&fixup.displacement.to_le_bytes());
+                } else {
+                    subrecords.extend_from_slice(&(fixup.displacement as u16).to_le_bytes());
+                }
+            }
+
+            if !body.is_empty() && body.len() + subrecords.len() > MAX_RECORD_SIZE {
+                self.write_record(kind, &body)?;
+                body.clear();
+            }
+            body.extend_from_slice(&subrecords);
+        }
+        if !body.is_empty() {
+            self.write_record(kind, &body)?;
+        }
+        Ok(())
+    }
+
+    /// Write LINNUM records for a segment.
+    ///
+    /// `segment` is the 1-based SEGDEF index, and each line number entry is an
+    /// offset within the segment and a source line number. The source file is
+    /// named by the preceding THEADR record.
+    pub fn write_line_numbers(
+        &mut self,
+        is_32bit: bool,
+        segment: u16,
+        lines: &[(u32, u16)],
+    ) -> Result<()> {
+        let kind = if is_32bit { LINNUM32 } else { LINNUM };
+        // No base group.
+        let mut header = vec![0];
+        write_index(&mut header, segment)?;
+        for entries in line_entries(is_32bit, header.len(), lines)? {
+            let mut body = header.clone();
+            body.extend_from_slice(&entries);
+            self.write_record(kind, &body)?;
+        }
+        Ok(())
+    }
+
+    /// Write LINSYM records for a COMDAT.
+    ///
+    /// `name` is the LNAMES index of the COMDAT name, and each line number
+    /// entry is an offset within the COMDAT and a source line number. The
+    /// records must follow the COMDAT records that they refer to.
+    pub fn write_comdat_line_numbers(
+        &mut self,
+        is_32bit: bool,
+        name: u16,
+        lines: &[(u32, u16)],
+    ) -> Result<()> {
+        let kind = if is_32bit { LINSYM32 } else { LINSYM };
+        let mut header = vec![0];
+        write_index(&mut header, name)?;
+        for entries in line_entries(is_32bit, header.len(), lines)? {
+            let mut body = header.clone();
+            body.extend_from_slice(&entries);
+            self.write_record(kind, &body)?;
+            // Later records continue the first.
+            header[0] = 0x01;
+        }
+        Ok(())
+    }
+
+    /// Write a GRPDEF record.
+    ///
+    /// `name` is the LNAMES index of the group name, and `segments` are the
+    /// 1-based SEGDEF indices of the segments in the group.
+    pub fn write_group(&mut self, name: u16, segments: &[u16]) -> Result<()> {
+        let mut body = Vec::new();
+        write_index(&mut body, name)?;
+        for &segment in segments {
+            // Segment index component.
+            body.push(0xFF);
+            write_index(&mut body, segment)?;
+        }
+        self.write_record(GRPDEF, &body)
+    }
+
+    /// Write the MODEND record that ends the module.
+    ///
+    /// `main` marks the module as the main module of a program, which usually
+    /// also gives the start address. A MODEND32 record is only used for a
+    /// logical start address in a 32-bit module.
+    pub fn write_modend(
+        &mut self,
+        is_32bit: bool,
+        main: bool,
+        start: Option<StartAddress>,
+    ) -> Result<()> {
+        let mut module_type = if main { 0x80 } else { 0 };
+        let mut body = vec![0];
+        let mut kind = MODEND;
+        match start {
+            None => {}
+            Some(StartAddress::Physical { frame, offset }) => {
+                module_type |= 0x40;
+                body.extend_from_slice(&frame.to_le_bytes());
+                body.extend_from_slice(&offset.to_le_bytes());
+            }
+            Some(
+                start @ StartAddress::Logical {
+                    frame_method,
+                    frame_index,
+                    target_method,
+                    target_index,
+                    displacement,
+                },
+            ) => {
+                // F4 uses the frame of the location, which a start address doesn't have.
+                if matches!(frame_method, 3 | 4 | 6..=0xFF)
+                    || target_method > 2
+                    || (!is_32bit && displacement > 0xFFFF)
+                {
+                    return Err(Error(format!("invalid OMF start address {:?}", start)));
+                }
+                // The logical address bit.
+                module_type |= 0x41;
+                // The displacement is always present.
+                body.push((frame_method << 4) | target_method);
+                if frame_method < 3 {
+                    write_index(&mut body, frame_index)?;
+                }
+                write_index(&mut body, target_index)?;
+                if is_32bit {
+                    body.extend_from_slice(&displacement.to_le_bytes());
+                    kind = MODEND32;
+                } else {
+                    body.extend_from_slice(&(displacement as u16).to_le_bytes());
+                }
+            }
+        }
+        body[0] = module_type;
+        self.write_record(kind, &body)
+    }
+
+    /// Write a COMENT record.
+    ///
+    /// `attributes` is a combination of [`COMENT_NO_PURGE`] and
+    /// [`COMENT_NO_LIST`](super::COMENT_NO_LIST).
+    /// The data follows the comment class, and its format depends on the class.
+    pub fn write_comment(&mut self, attributes: u8, class: u8, data: &[u8]) -> Result<()> {
+        let mut body = Vec::with_capacity(2 + data.len());
+        body.push(attributes);
+        body.push(class);
+        body.extend_from_slice(data);
+        self.write_record(COMENT, &body)
+    }
+
+    /// Write a comment naming the translator that produced the module.
+    pub fn write_translator(&mut self, name: &[u8]) -> Result<()> {
+        self.write_comment(0, COMENT_TRANSLATOR, name)
+    }
+
+    /// Write a copyright comment.
+    ///
+    /// The comment is marked so that utilities that strip comments keep it.
+    pub fn write_copyright(&mut self, text: &[u8]) -> Result<()> {
+        self.write_comment(COMENT_NO_PURGE, COMENT_COPYRIGHT, text)
+    }
+
+    /// Write a memory model comment.
+    ///
+    /// `codes` is a string of ASCII codes such as `b"3fO"`. The memory model is
+    /// given by `s`, `m`, `c`, `l`, `h` or `f`, the instruction set by `0`, `1`,
+    /// `2` or `3`, the floating point kind by `e`, `p` or `A`, and `O` indicates
+    /// optimized code.
+    pub fn write_memory_model(&mut self, codes: &[u8]) -> Result<()> {
+        self.write_comment(0, COMENT_MEMORY_MODEL, codes)
+    }
+
+    /// Write a comment requesting the standard DOS segment ordering.
+    pub fn write_dosseg(&mut self) -> Result<()> {
+        self.write_comment(COMENT_NO_PURGE, COMENT_DOSSEG, &[])
+    }
+
+    /// Write a comment asking the linker to search a library.
+    ///
+    /// The name does not have a length prefix, and usually has no extension.
+    pub fn write_default_library(&mut self, name: &[u8]) -> Result<()> {
+        self.write_comment(COMENT_NO_PURGE, COMENT_DEFAULT_LIBRARY, name)
+    }
+
+    /// Write an IMPDEF comment declaring a symbol that is imported from a DLL.
+    ///
+    /// `internal_name` is the name that the module uses for the symbol, and
+    /// `module` is the name of the DLL.
+    pub fn write_import(
+        &mut self,
+        internal_name: &[u8],
+        module: &[u8],
+        entry: ImportEntry<'_>,
+    ) -> Result<()> {
+        let mut data = Vec::new();
+        data.push(OMFEXT_IMPDEF);
+        data.push(matches!(entry, ImportEntry::Ordinal(_)) as u8);
+        write_name(&mut data, internal_name)?;
+        write_name(&mut data, module)?;
+        match entry {
+            ImportEntry::Ordinal(ordinal) => data.extend_from_slice(&ordinal.to_le_bytes()),
+            // An empty entry name means the entry name is the same as the internal name.
+            ImportEntry::Name(name) if name == internal_name => data.push(0),
+            ImportEntry::Name(name) => write_name(&mut data, name)?,
+        }
+        self.write_comment(0, COMENT_OMFEXT, &data)
+    }
+
+    /// Write an EXPDEF comment declaring an entry point that is exported from a DLL.
+    pub fn write_export(&mut self, export: &Export<'_>) -> Result<()> {
+        if export.parameter_count > 0x1F {
+            return Err(Error(format!(
+                "OMF export `{}` has too many parameter words",
+                String::from_utf8_lossy(export.exported_name)
+            )));
+        }
+        let mut flags = export.parameter_count;
+        if export.ordinal.is_some() {
+            flags |= 0x80;
+        }
+        if export.resident_name {
+            flags |= 0x40;
+        }
+        if export.no_data {
+            flags |= 0x20;
+        }
+        let mut data = Vec::new();
+        data.push(OMFEXT_EXPDEF);
+        data.push(flags);
+        write_name(&mut data, export.exported_name)?;
+        // An empty internal name means the internal name is the same as the exported name.
+        match export.internal_name {
+            Some(name) if name != export.exported_name => write_name(&mut data, name)?,
+            _ => data.push(0),
+        }
+        if let Some(ordinal) = export.ordinal {
+            data.extend_from_slice(&ordinal.to_le_bytes());
+        }
+        self.write_comment(0, COMENT_OMFEXT, &data)
+    }
+
+    /// Return the 1-based LNAMES index of a name, allocating one if needed.
+    ///
+    /// Newly allocated names are written by the next call to [`Self::write_lnames`].
+    pub fn add_name(&mut self, name: &[u8]) -> Result<u16> {
+        if let Some(&index) = self.names.get(name) {
+            return Ok(index);
+        }
+        if self.name_count >= 0x7FFF {
+            return Err(Error(String::from("too many OMF names")));
+        }
+        write_name(&mut self.pending_names, name)?;
+        self.name_count += 1;
+        self.names.insert(name.to_vec(), self.name_count);
+        Ok(self.name_count)
+    }
+
+    /// Write LNAMES records for the names allocated since the last call.
+    pub fn write_lnames(&mut self) -> Result<()> {
+        let names = core::mem::take(&mut self.pending_names);
+        let mut start = 0;
+        while start < names.len() {
+            // Split before the name that would exceed the record size.
+            let mut end = start;
+            while end < names.len() {
+                let next = end + 1 + usize::from(names[end]);
+                if end > start && next - start > MAX_RECORD_SIZE {
+                    break;
+                }
+                end = next;
+            }
+            self.write_record(LNAMES, &names[start..end])?;
+            start = end;
+        }
+        Ok(())
+    }
+}
+
+/// A fixup to write with [`Writer::write_fixups`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fixup {
+    /// The offset of the location within the data of the preceding data record.
+    ///
+    /// For [`Writer::write_fixups`], this must be less than 1024. For
+    /// [`Writer::write_data`], this is the offset within the data.
+    pub offset: u32,
+    /// The location type.
+    pub location: u8,
+    /// True if the fixup is segment-relative, false if it is self-relative.
+    pub segment_relative: bool,
+    /// The frame method, from F0 to F5.
+    ///
+    /// F3 is not supported.
+    pub frame_method: u8,
+    /// The segment, group or external index of the frame.
+    ///
+    /// This is ignored for F4 and F5.
+    pub frame_index: u16,
+    /// The target method, from T0 to T2.
+    ///
+    /// Methods T4 to T6 are used automatically if the displacement is 0.
+    pub target_method: u8,
+    /// The segment, group or external index of the target.
+    pub target_index: u16,
+    /// The displacement from the target.
+    pub displacement: u32,
+}
+
+/// A program start address to write with [`Writer::write_modend`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartAddress {
+    /// A start address that is resolved by the linker, like a fixup.
+    Logical {
+        /// The frame method: F0 to F2, or F5.
+        frame_method: u8,
+        /// The segment, group or external index of the frame.
+        ///
+        /// This is ignored for F5.
+        frame_index: u16,
+        /// The target method, from T0 to T2.
+        target_method: u8,
+        /// The segment, group or external index of the target.
+        target_index: u16,
+        /// The displacement from the target.
+        displacement: u32,
+    },
+    /// A start address given by a frame number and offset.
+    Physical {
+        /// The frame number, which is the segment address divided by 16.
+        frame: u16,
+        /// The offset from the frame.
+        offset: u16,
+    },
+}
+
+/// The DLL entry imported by [`Writer::write_import`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportEntry<'a> {
+    /// Import by name.
+    Name(&'a [u8]),
+    /// Import by ordinal.
+    Ordinal(u16),
+}
+
+/// An export to write with [`Writer::write_export`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Export<'a> {
+    /// The name that the entry point is exported as.
+    pub exported_name: &'a [u8],
+    /// The name of the public symbol that is exported.
+    ///
+    /// If this is `None`, the exported name is used.
+    pub internal_name: Option<&'a [u8]>,
+    /// The export ordinal.
+    pub ordinal: Option<u16>,
+    /// Whether the exported name is kept in the resident names table.
+    pub resident_name: bool,
+    /// Whether the entry point does not use the data segment.
+    pub no_data: bool,
+    /// The number of parameter words for call gates.
+    ///
+    /// This must be less than 32.
+    pub parameter_count: u8,
+}
+
+/// Return the size in bytes of a fixup location type.
+fn location_size(location: u8) -> Option<usize> {
+    match location {
+        // Low-order byte, or high-order byte.
+        0 | 4 => Some(1),
+        // 16-bit offset, segment base, or loader-resolved 16-bit offset.
+        1 | 2 | 5 => Some(2),
+        // 16:16 pointer, 32-bit offset, or loader-resolved 32-bit offset.
+        3 | 9 | 13 => Some(4),
+        // 16:32 pointer.
+        11 => Some(6),
+        _ => None,
+    }
+}
+
+/// Return the encoded line number entries, split into record bodies.
+///
+/// `header_size` is the size of the fields preceding the entries in each record.
+fn line_entries(is_32bit: bool, header_size: usize, lines: &[(u32, u16)]) -> Result<Vec<Vec<u8>>> {
+    let entry_size = if is_32bit { 6 } else { 4 };
+    let mut bodies = Vec::new();
+    for chunk in lines.chunks((MAX_RECORD_SIZE - header_size) / entry_size) {
+        let mut body = Vec::with_capacity(chunk.len() * entry_size);
+        for &(offset, line) in chunk {
+            body.extend_from_slice(&line.to_le_bytes());
+            if is_32bit {
+                body.extend_from_slice(&offset.to_le_bytes());
+            } else {
+                let offset = u16::try_from(offset).map_err(|_| {
+                    Error(format!(
+                        "OMF line number offset 0x{:x} is too large",
+                        offset
+                    ))
+                })?;
+                body.extend_from_slice(&offset.to_le_bytes());
+            }
+        }
+        bodies.push(body);
+    }
+    Ok(bodies)
+}
+
+/// Return the thread to use for a frame or target, defining one if needed.
+///
+/// `remaining` is the number of uses of the datum by this fixup and later
+/// fixups in the same call, and `uses` holds the remaining uses of every datum
+/// after this fixup. A thread is only defined if it reduces the size of the
+/// fixups, and only replaces a thread that has no remaining uses.
+fn select_thread(
+    threads: &mut [Option<(u8, u16)>; 4],
+    datum: (u8, u16),
+    remaining: usize,
+    uses: &HashMap<(u8, u16), usize>,
+    thread_kind: u8,
+    out: &mut Vec<u8>,
+) -> Result<Option<u8>> {
+    if let Some(thread) = threads.iter().position(|&thread| thread == Some(datum)) {
+        return Ok(Some(thread as u8));
+    }
+    // A thread definition is a byte longer than an explicit datum,
+    // but later uses of the thread need no datum.
+    let index_size = if datum.1 < 0x80 { 1 } else { 2 };
+    if remaining * index_size <= 1 + index_size {
+        return Ok(None);
+    }
+    let thread = match threads.iter().position(|thread| {
+        thread.map_or(true, |thread| uses.get(&thread).map_or(true, |&n| n == 0))
+    }) {
+        Some(thread) => thread,
+        None => return Ok(None),
+    };
+    threads[thread] = Some(datum);
+    out.push(thread_kind | (datum.0 << 2) | thread as u8);
+    write_index(out, datum.1)?;
+    Ok(Some(thread as u8))
+}
+
+/// Append a name, preceded by its length.
+///
+/// Returns an error if the name is longer than 255 bytes.
+pub fn write_name(out: &mut Vec<u8>, name: &[u8]) -> Result<()> {
+    let len = u8::try_from(name.len()).map_err(|_| {
+        Error(format!(
+            "OMF name `{}` is too long",
+            String::from_utf8_lossy(name)
+        ))
+    })?;
+    out.push(len);
+    out.extend_from_slice(name);
+    Ok(())
+}
+
+/// Append an index, using 1 byte for indices below 0x80, and 2 bytes otherwise.
+///
+/// Returns an error if the index is 0x8000 or more.
+pub fn write_index(out: &mut Vec<u8>, index: u16) -> Result<()> {
+    if index < 0x80 {
+        out.push(index as u8);
+    } else if index < 0x8000 {
+        out.push(0x80 | (index >> 8) as u8);
+        out.push(index as u8);
+    } else {
+        return Err(Error(format!("OMF index {} is too large", index)));
+    }
+    Ok(())
+}
+
+/// Append a COMDEF length, using the variable length encoding.
+pub fn write_comdef_length(out: &mut Vec<u8>, length: u64) -> Result<()> {
+    if length < 0x80 {
+        out.push(length as u8);
+    } else if length <= 0xFFFF {
+        out.push(0x81);
+        out.extend_from_slice(&(length as u16).to_le_bytes());
+    } else if length <= 0xFF_FFFF {
+        out.push(0x84);
+        out.extend_from_slice(&(length as u32).to_le_bytes()[..3]);
+    } else if let Ok(length) = u32::try_from(length) {
+        out.push(0x88);
+        out.extend_from_slice(&length.to_le_bytes());
+    } else {
+        return Err(Error(format!(
+            "OMF common symbol size {} is too large",
+            length
+        )));
+    }
+    Ok(())
+}
@@ -9,12 +9,30 @@ type IndexSet<K> = indexmap::IndexSet<K, hashbrown::DefaultHashBuilder>;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct StringId(usize);
 
+#[cfg_attr(
+    not(any(
+        feature = "coff",
+        feature = "elf",
+        feature = "macho",
+        feature = "xcoff"
+    )),
+    allow(dead_code)
+)]
 #[derive(Debug, Default)]
 pub(crate) struct StringTable<'a> {
     strings: IndexSet<&'a [u8]>,
     offsets: Vec<usize>,
 }
 
+#[cfg_attr(
+    not(any(
+        feature = "coff",
+        feature = "elf",
+        feature = "macho",
+        feature = "xcoff"
+    )),
+    allow(dead_code)
+)]
 impl<'a> StringTable<'a> {
     /// Add a string to the string table.
     ///
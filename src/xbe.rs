@@ -0,0 +1,210 @@
+//! Xbox executable (XBE) definitions.
+//!
+//! These definitions are independent of read/write support, although we do implement
+//! some traits useful for those.
+//!
+//! This module is based on the XBE documentation of the Xbox homebrew community.
+//!
+//! XBE files are the executables of the original Xbox. The file starts with an
+//! [`ImageHeader`], which is followed by the [`Certificate`], the
+//! [`SectionHeader`]s, the section names, the [`LibraryVersion`]s, and then
+//! the section data. All values are little endian.
+//!
+//! The headers are loaded at the base address, so addresses within the
+//! headers are converted to file offsets by subtracting the base address. The
+//! entry point and the kernel thunk table address are encoded with an XOR key
+//! that depends on whether the executable is for retail, debug or Chihiro
+//! consoles.
+
+#![allow(missing_docs)]
+
+use crate::endian::{LittleEndian as LE, U16Bytes, U32Bytes};
+use crate::pod::Pod;
+
+/// The value of [`ImageHeader::magic`]: `XBEH`.
+pub const XBE_MAGIC: u32 = 0x4845_4258;
+
+/// The XOR key for the entry point of retail executables.
+pub const XBE_ENTRY_RETAIL_KEY: u32 = 0xa8fc_57ab;
+/// The XOR key for the entry point of debug executables.
+pub const XBE_ENTRY_DEBUG_KEY: u32 = 0x9485_9d4b;
+/// The XOR key for the entry point of Chihiro executables.
+pub const XBE_ENTRY_CHIHIRO_KEY: u32 = 0x40b5_c16e;
+/// The XOR key for the kernel thunk table address of retail executables.
+pub const XBE_KERNEL_THUNK_RETAIL_KEY: u32 = 0x5b6d_40b6;
+/// The XOR key for the kernel thunk table address of debug executables.
+pub const XBE_KERNEL_THUNK_DEBUG_KEY: u32 = 0xefb1_f152;
+/// The XOR key for the kernel thunk table address of Chihiro executables.
+pub const XBE_KERNEL_THUNK_CHIHIRO_KEY: u32 = 0x2290_059d;
+
+/// The header at the start of an XBE file.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ImageHeader {
+    /// The magic number, [`XBE_MAGIC`].
+    pub magic: U32Bytes<LE>,
+    /// The RSA signature of the headers.
+    pub signature: [u8; 256],
+    /// The address that the image is loaded at.
+    pub base_address: U32Bytes<LE>,
+    /// The size of all of the headers.
+    pub size_of_headers: U32Bytes<LE>,
+    /// The size of the image in memory.
+    pub size_of_image: U32Bytes<LE>,
+    /// The size of this header.
+    pub size_of_image_header: U32Bytes<LE>,
+    /// The time and date stamp.
+    pub time_date: U32Bytes<LE>,
+    /// The address of the [`Certificate`].
+    pub certificate_address: U32Bytes<LE>,
+    /// The number of sections.
+    pub number_of_sections: U32Bytes<LE>,
+    /// The address of the section headers.
+    pub section_headers_address: U32Bytes<LE>,
+    /// Initialization flags. A combination of the `XBE_INIT_*` constants.
+    pub initialization_flags: U32Bytes<LE>,
+    /// The entry point address, encoded with one of the `XBE_ENTRY_*_KEY` constants.
+    pub entry_point: U32Bytes<LE>,
+    /// The address of the TLS directory.
+    pub tls_address: U32Bytes<LE>,
+    /// The stack size of the original PE image.
+    pub pe_stack_commit: U32Bytes<LE>,
+    /// The heap reserve size of the original PE image.
+    pub pe_heap_reserve: U32Bytes<LE>,
+    /// The heap commit size of the original PE image.
+    pub pe_heap_commit: U32Bytes<LE>,
+    /// The base address of the original PE image.
+    pub pe_base_address: U32Bytes<LE>,
+    /// The size of the original PE image.
+    pub pe_size_of_image: U32Bytes<LE>,
+    /// The checksum of the original PE image.
+    pub pe_checksum: U32Bytes<LE>,
+    /// The time and date stamp of the original PE image.
+    pub pe_time_date: U32Bytes<LE>,
+    /// The address of the debug path name.
+    pub debug_pathname_address: U32Bytes<LE>,
+    /// The address of the debug file name.
+    pub debug_filename_address: U32Bytes<LE>,
+    /// The address of the UTF-16 debug file name.
+    pub debug_unicode_filename_address: U32Bytes<LE>,
+    /// The address of the kernel thunk table, encoded with one of the
+    /// `XBE_KERNEL_THUNK_*_KEY` constants.
+    pub kernel_image_thunk_address: U32Bytes<LE>,
+    /// The address of the non-kernel import directory.
+    pub non_kernel_import_directory_address: U32Bytes<LE>,
+    /// The number of library versions.
+    pub number_of_library_versions: U32Bytes<LE>,
+    /// The address of the library versions.
+    pub library_versions_address: U32Bytes<LE>,
+    /// The address of the kernel library version.
+    pub kernel_library_version_address: U32Bytes<LE>,
+    /// The address of the XAPI library version.
+    pub xapi_library_version_address: U32Bytes<LE>,
+    /// The address of the logo bitmap.
+    pub logo_bitmap_address: U32Bytes<LE>,
+    /// The size of the logo bitmap.
+    pub logo_bitmap_size: U32Bytes<LE>,
+}
+
+// Values for `ImageHeader::initialization_flags`.
+/// Mount the utility drive.
+pub const XBE_INIT_MOUNT_UTILITY_DRIVE: u32 = 0x0000_0001;
+/// Format the utility drive.
+pub const XBE_INIT_FORMAT_UTILITY_DRIVE: u32 = 0x0000_0002;
+/// Limit the memory to 64 MiB.
+pub const XBE_INIT_LIMIT_64MB: u32 = 0x0000_0004;
+/// Don't set up the hard disk.
+pub const XBE_INIT_DONT_SETUP_HARDDISK: u32 = 0x0000_0008;
+
+/// The certificate of an XBE file.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Certificate {
+    /// The size of the certificate.
+    pub size: U32Bytes<LE>,
+    /// The time and date stamp.
+    pub time_date: U32Bytes<LE>,
+    /// The title ID.
+    pub title_id: U32Bytes<LE>,
+    /// The title name, in UTF-16 and padded with zeros.
+    pub title_name: [U16Bytes<LE>; 40],
+    /// Alternate title IDs.
+    pub alternate_title_ids: [U32Bytes<LE>; 16],
+    /// The allowed media types.
+    pub allowed_media: U32Bytes<LE>,
+    /// The game regions.
+    pub game_region: U32Bytes<LE>,
+    /// The game ratings.
+    pub game_ratings: U32Bytes<LE>,
+    /// The disk number.
+    pub disk_number: U32Bytes<LE>,
+    /// The certificate version.
+    pub version: U32Bytes<LE>,
+    /// The LAN key.
+    pub lan_key: [u8; 16],
+    /// The signature key.
+    pub signature_key: [u8; 16],
+    /// Alternate signature keys.
+    pub alternate_signature_keys: [[u8; 16]; 16],
+}
+
+/// A section header.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct SectionHeader {
+    /// Flags. A combination of the `XBE_SECTION_*` constants.
+    pub flags: U32Bytes<LE>,
+    /// The address of the section in memory.
+    pub virtual_address: U32Bytes<LE>,
+    /// The size of the section in memory.
+    pub virtual_size: U32Bytes<LE>,
+    /// The file offset of the section data.
+    pub raw_address: U32Bytes<LE>,
+    /// The size of the section data in the file.
+    pub raw_size: U32Bytes<LE>,
+    /// The address of the null terminated section name.
+    pub section_name_address: U32Bytes<LE>,
+    /// The reference count of the section name.
+    pub section_name_reference_count: U32Bytes<LE>,
+    /// The address of the reference count of the first page.
+    pub head_shared_page_reference_count_address: U32Bytes<LE>,
+    /// The address of the reference count of the last page.
+    pub tail_shared_page_reference_count_address: U32Bytes<LE>,
+    /// The SHA-1 digest of the section data.
+    pub section_digest: [u8; 20],
+}
+
+// Values for `SectionHeader::flags`.
+/// The section is writable.
+pub const XBE_SECTION_WRITABLE: u32 = 0x0000_0001;
+/// The section is loaded when the executable starts.
+pub const XBE_SECTION_PRELOAD: u32 = 0x0000_0002;
+/// The section is executable.
+pub const XBE_SECTION_EXECUTABLE: u32 = 0x0000_0004;
+/// The section was inserted after linking.
+pub const XBE_SECTION_INSERTED_FILE: u32 = 0x0000_0008;
+/// The first page of the section is read-only.
+pub const XBE_SECTION_HEAD_PAGE_READ_ONLY: u32 = 0x0000_0010;
+/// The last page of the section is read-only.
+pub const XBE_SECTION_TAIL_PAGE_READ_ONLY: u32 = 0x0000_0020;
+
+/// The version of a library that an XBE file was linked with.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct LibraryVersion {
+    /// The library name, padded with zeros.
+    pub library_name: [u8; 8],
+    /// The major version.
+    pub major_version: U16Bytes<LE>,
+    /// The minor version.
+    pub minor_version: U16Bytes<LE>,
+    /// The build version.
+    pub build_version: U16Bytes<LE>,
+    /// Flags.
+    pub flags: U16Bytes<LE>,
+}
+
+/// The bit that is set in a kernel thunk table entry that imports by ordinal.
+pub const XBE_KERNEL_THUNK_ORDINAL: u32 = 0x8000_0000;
+
+unsafe_impl_pod!(ImageHeader, Certificate, SectionHeader, LibraryVersion);
@@ -0,0 +1,122 @@
+use object::build::SectionEdits;
+use object::read::{Object, ObjectSection, ObjectSymbol};
+use object::{
+    write, Architecture, BinaryFormat, Endianness, RelocationEncoding, RelocationFlags,
+    RelocationKind, RelocationTarget, SectionKind, SymbolFlags, SymbolKind, SymbolScope,
+};
+
+/// Write an object with code that refers to a data symbol, and a section
+/// that nothing refers to.
+fn object(format: BinaryFormat, names: [&[u8]; 3]) -> Vec<u8> {
+    let mut object = write::Object::new(format, Architecture::I386, Endianness::Little);
+    object.set_mangling(write::Mangling::None);
+    let [text_name, data_name, extra_name] = names;
+    let text = object.add_section(Vec::new(), text_name.to_vec(), SectionKind::Text);
+    // mov eax, [_value]; ret
+    object.append_section_data(text, &[0xA1, 0, 0, 0, 0, 0xC3], 4);
+    let data = object.add_section(Vec::new(), data_name.to_vec(), SectionKind::Data);
+    object.append_section_data(data, &[1, 2, 3, 4], 4);
+    let extra = object.add_section(Vec::new(), extra_name.to_vec(), SectionKind::Data);
+    object.append_section_data(extra, &[5, 6], 1);
+    let value = object.add_symbol(write::Symbol {
+        name: b"_value".to_vec(),
+        value: 0,
+        size: 4,
+        kind: SymbolKind::Data,
+        scope: SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Section(data),
+        flags: SymbolFlags::None,
+    });
+    object
+        .add_relocation(
+            text,
+            write::Relocation {
+                offset: 1,
+                symbol: value,
+                addend: 0,
+                flags: RelocationFlags::Generic {
+                    kind: RelocationKind::Absolute,
+                    encoding: RelocationEncoding::Generic,
+                    size: 32,
+                },
+            },
+        )
+        .unwrap();
+    object.write().unwrap()
+}
+
+fn check_edits(format: BinaryFormat, names: [&[u8]; 3]) {
+    let [text_name, data_name, extra_name] = names;
+    let bytes = object(format, names);
+
+    let conversion = SectionEdits::new()
+        .remove_section(extra_name.to_vec())
+        .rename_section(data_name.to_vec(), b"MYDATA".to_vec())
+        .pad_section(text_name.to_vec(), 16)
+        .add_section(b"ADDED".to_vec(), SectionKind::Data, vec![7, 8, 9])
+        .apply(&bytes)
+        .unwrap();
+    assert_eq!(conversion.untranslated, []);
+    let edited_bytes = conversion.object.write().unwrap();
+    let edited = object::File::parse(&*edited_bytes).unwrap();
+    assert_eq!(edited.format(), format);
+
+    let names = edited
+        .sections()
+        .map(|section| section.name().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            String::from_utf8_lossy(text_name).into_owned(),
+            "MYDATA".into(),
+            "ADDED".into()
+        ]
+    );
+    let text = edited.section_by_name_bytes(text_name).unwrap();
+    assert_eq!(
+        text.uncompressed_data().unwrap(),
+        &[0xA1, 0, 0, 0, 0, 0xC3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0][..]
+    );
+    let (offset, relocation) = text.relocations().next().unwrap();
+    assert_eq!(offset, 1);
+    // OMF fixups refer to the segment of symbols that are defined in the module.
+    match relocation.target() {
+        RelocationTarget::Symbol(symbol) => {
+            let symbol = edited.symbol_by_index(symbol).unwrap();
+            assert_eq!(symbol.name().unwrap(), "_value");
+        }
+        RelocationTarget::Section(section) => {
+            let section = edited.section_by_index(section).unwrap();
+            assert_eq!(section.name().unwrap(), "MYDATA");
+        }
+        target => panic!("unexpected relocation target {:?}", target),
+    }
+    let data = edited.section_by_name("MYDATA").unwrap();
+    assert_eq!(data.uncompressed_data().unwrap(), &[1, 2, 3, 4][..]);
+    let added = edited.section_by_name("ADDED").unwrap();
+    assert_eq!(added.uncompressed_data().unwrap(), &[7, 8, 9][..]);
+
+    // The data section is referenced by a relocation in the code.
+    assert!(SectionEdits::new()
+        .remove_section(data_name.to_vec())
+        .apply(&bytes)
+        .is_err());
+    assert!(SectionEdits::new()
+        .rename_section(b"MISSING".to_vec(), b"NEW".to_vec())
+        .apply(&bytes)
+        .is_err());
+}
+
+#[cfg(feature = "coff")]
+#[test]
+fn coff_section_edits() {
+    check_edits(BinaryFormat::Coff, [b".text", b".data", b".extra"]);
+}
+
+#[cfg(feature = "omf")]
+#[test]
+fn omf_section_edits() {
+    check_edits(BinaryFormat::Omf, [b"_TEXT", b"_DATA", b"_EXTRA"]);
+}
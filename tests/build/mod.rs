@@ -1,3 +1,7 @@
 #![cfg(feature = "build")]
 
+#[cfg(any(feature = "coff", feature = "omf"))]
+mod edit;
 mod elf;
+#[cfg(feature = "omf")]
+mod omf;
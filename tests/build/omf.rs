@@ -0,0 +1,483 @@
+#[cfg(feature = "coff")]
+use object::build::omf::{coff_to_omf, omf_to_coff};
+use object::build::omf::{rebuild_omf, Untranslated};
+use object::read::{Object, ObjectComdat, ObjectSection, ObjectSymbol};
+use object::{
+    read, write, Architecture, BinaryFormat, ComdatKind, Endianness, RelocationKind,
+    RelocationTarget, SectionIndex, SectionKind, SymbolFlags, SymbolKind, SymbolScope,
+};
+
+#[cfg(feature = "coff")]
+#[test]
+fn omf_to_coff_object() {
+    let mut object = write::Object::new(BinaryFormat::Omf, Architecture::I386, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    // call _ext; mov eax, [_value + 4]; a segment base; ret
+    object.append_section_data(text, &[0xE8, 0, 0, 0, 0, 0xA1, 0, 0, 0, 0, 0, 0, 0xC3], 16);
+    let data = object.section_id(write::StandardSection::Data);
+    object.append_section_data(data, &[1, 2, 3, 4, 5, 6, 7, 8], 4);
+    let bss = object.section_id(write::StandardSection::UninitializedData);
+    object.append_section_bss(bss, 16, 4);
+
+    let section = object.add_section(b"CODE".to_vec(), b"_TEXT".to_vec(), SectionKind::Text);
+    object.append_section_data(section, &[0xC3], 16);
+
+    let mut symbol = |name: &[u8], value, kind, scope, section| {
+        object.add_symbol(write::Symbol {
+            name: name.to_vec(),
+            value,
+            size: 0,
+            kind,
+            scope,
+            weak: false,
+            section,
+            flags: SymbolFlags::None,
+        })
+    };
+    let start = symbol(
+        b"_start",
+        0,
+        SymbolKind::Text,
+        SymbolScope::Linkage,
+        write::SymbolSection::Section(text),
+    );
+    let value = symbol(
+        b"_value",
+        4,
+        SymbolKind::Data,
+        SymbolScope::Linkage,
+        write::SymbolSection::Section(data),
+    );
+    symbol(
+        b"_local",
+        8,
+        SymbolKind::Data,
+        SymbolScope::Compilation,
+        write::SymbolSection::Section(bss),
+    );
+    let ext = symbol(
+        b"_ext",
+        0,
+        SymbolKind::Text,
+        SymbolScope::Unknown,
+        write::SymbolSection::Undefined,
+    );
+    let common = symbol(
+        b"_common",
+        0,
+        SymbolKind::Data,
+        SymbolScope::Linkage,
+        write::SymbolSection::Common,
+    );
+
+    let inline = symbol(
+        b"_inline",
+        0,
+        SymbolKind::Text,
+        SymbolScope::Linkage,
+        write::SymbolSection::Section(section),
+    );
+    object.symbol_mut(common).size = 8;
+    object.add_comdat(write::Comdat {
+        kind: ComdatKind::Any,
+        symbol: inline,
+        sections: vec![section],
+    });
+
+    for (offset, symbol, kind, size, addend) in [
+        (1, ext, RelocationKind::Relative, 32, -4),
+        (6, value, RelocationKind::Absolute, 32, 4),
+        (10, value, RelocationKind::SectionIndex, 16, 0),
+    ] {
+        object
+            .add_relocation(
+                text,
+                write::Relocation {
+                    offset,
+                    symbol,
+                    addend,
+                    flags: object::RelocationFlags::Generic {
+                        kind,
+                        encoding: object::RelocationEncoding::Generic,
+                        size,
+                    },
+                },
+            )
+            .unwrap();
+    }
+    object.set_omf_entry(start);
+    let bytes = object.write().unwrap();
+
+    let omf = read::omf::OmfFile::parse(&*bytes).unwrap();
+    let conversion = omf_to_coff(&omf).unwrap();
+    assert_eq!(
+        conversion.untranslated,
+        [
+            Untranslated::Relocation {
+                section: SectionIndex(1),
+                offset: 10,
+                reason: "unsupported fixup location",
+            },
+            Untranslated::Entry,
+        ]
+    );
+    let bytes = conversion.object.write().unwrap();
+
+    let coff = read::File::parse(&*bytes).unwrap();
+    assert_eq!(coff.format(), BinaryFormat::Coff);
+    assert_eq!(coff.architecture(), Architecture::I386);
+
+    let names = |file: &read::File<'_>| {
+        file.sections()
+            .map(|section| (section.name().unwrap().to_string(), section.kind()))
+            .collect::<Vec<_>>()
+    };
+    let names_omf = omf
+        .sections()
+        .map(|section| (section.name().unwrap().to_string(), section.kind()))
+        .collect::<Vec<_>>();
+    assert_eq!(names(&coff), names_omf);
+
+    let text = coff.section_by_index(SectionIndex(1)).unwrap();
+    let text_data = text.data().unwrap();
+    assert_eq!(&text_data[1..5], &[0, 0, 0, 0]);
+    // Fixups to defined symbols target the segment.
+    assert_eq!(&text_data[6..10], &[8, 0, 0, 0]);
+    let relocations = text.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 2);
+    let symbol = |target| match target {
+        RelocationTarget::Symbol(index) => coff.symbol_by_index(index).unwrap(),
+        _ => panic!("unexpected target {:?}", target),
+    };
+    let (offset, relocation) = &relocations[0];
+    assert_eq!(*offset, 1);
+    assert_eq!(relocation.kind(), RelocationKind::Relative);
+    assert_eq!(symbol(relocation.target()).name().unwrap(), "_ext");
+    let (offset, relocation) = &relocations[1];
+    assert_eq!(*offset, 6);
+    assert_eq!(relocation.kind(), RelocationKind::Absolute);
+    let target = symbol(relocation.target());
+    assert_eq!(target.kind(), SymbolKind::Section);
+    assert_eq!(target.section_index(), Some(SectionIndex(2)));
+
+    let symbol = coff.symbol_by_name("_value").unwrap();
+    assert_eq!(symbol.address(), 4);
+    assert_eq!(symbol.section_index(), Some(SectionIndex(2)));
+    assert!(symbol.is_global());
+    let symbol = coff.symbol_by_name("_local").unwrap();
+    assert_eq!(symbol.address(), 8);
+    assert!(symbol.is_local());
+    assert!(coff.symbol_by_name("_ext").unwrap().is_undefined());
+    let symbol = coff.symbol_by_name("_common").unwrap();
+    assert!(symbol.is_common());
+    assert_eq!(symbol.size(), 8);
+
+    let comdat = coff.comdats().next().unwrap();
+    assert_eq!(comdat.kind(), ComdatKind::Any);
+    assert_eq!(comdat.name().unwrap(), "_inline");
+}
+
+#[cfg(feature = "coff")]
+#[test]
+fn coff_to_omf_object() {
+    let mut object = write::Object::new(BinaryFormat::Coff, Architecture::I386, Endianness::Little);
+    object.set_mangling(write::Mangling::None);
+    object.add_file_symbol(b"test.c".to_vec());
+    let text = object.section_id(write::StandardSection::Text);
+    // call _ext; mov eax, [_value + 4]; an image offset; ret
+    object.append_section_data(
+        text,
+        &[0xE8, 0, 0, 0, 0, 0xA1, 4, 0, 0, 0, 0, 0, 0, 0, 0xC3],
+        16,
+    );
+    let data = object.section_id(write::StandardSection::Data);
+    object.append_section_data(data, &[1, 2, 3, 4, 5, 6, 7, 8], 4);
+    let bss = object.section_id(write::StandardSection::UninitializedData);
+    object.append_section_bss(bss, 16, 4);
+    let drectve = object.add_section(Vec::new(), b".drectve".to_vec(), SectionKind::Linker);
+    object.append_section_data(drectve, b" /DEFAULTLIB:LIBC ", 1);
+    let section = object.add_section(Vec::new(), b".text$inline".to_vec(), SectionKind::Text);
+    object.append_section_data(section, &[0xC3], 16);
+    object.section_symbol(section);
+
+    let mut symbol = |name: &[u8], value, kind, scope, section| {
+        object.add_symbol(write::Symbol {
+            name: name.to_vec(),
+            value,
+            size: 0,
+            kind,
+            scope,
+            weak: false,
+            section,
+            flags: SymbolFlags::None,
+        })
+    };
+    symbol(
+        b"_start",
+        0,
+        SymbolKind::Text,
+        SymbolScope::Linkage,
+        write::SymbolSection::Section(text),
+    );
+    let value = symbol(
+        b"_value",
+        4,
+        SymbolKind::Data,
+        SymbolScope::Linkage,
+        write::SymbolSection::Section(data),
+    );
+    symbol(
+        b"_local",
+        8,
+        SymbolKind::Data,
+        SymbolScope::Compilation,
+        write::SymbolSection::Section(bss),
+    );
+    let ext = symbol(
+        b"_ext",
+        0,
+        SymbolKind::Text,
+        SymbolScope::Unknown,
+        write::SymbolSection::Undefined,
+    );
+    let common = symbol(
+        b"_common",
+        0,
+        SymbolKind::Data,
+        SymbolScope::Linkage,
+        write::SymbolSection::Common,
+    );
+    let inline = symbol(
+        b"_inline",
+        0,
+        SymbolKind::Text,
+        SymbolScope::Linkage,
+        write::SymbolSection::Section(section),
+    );
+    object.symbol_mut(common).size = 8;
+    object.add_comdat(write::Comdat {
+        kind: ComdatKind::Any,
+        symbol: inline,
+        sections: vec![section],
+    });
+
+    for (offset, symbol, kind) in [
+        (1, ext, RelocationKind::Relative),
+        (6, value, RelocationKind::Absolute),
+        (10, value, RelocationKind::ImageOffset),
+    ] {
+        object
+            .add_relocation(
+                text,
+                write::Relocation {
+                    offset,
+                    symbol,
+                    addend: if kind == RelocationKind::Relative {
+                        -4
+                    } else {
+                        0
+                    },
+                    flags: object::RelocationFlags::Generic {
+                        kind,
+                        encoding: object::RelocationEncoding::Generic,
+                        size: 32,
+                    },
+                },
+            )
+            .unwrap();
+    }
+    let bytes = object.write().unwrap();
+
+    let coff = read::coff::CoffFile::<&[u8]>::parse(&*bytes).unwrap();
+    let conversion = coff_to_omf(&coff).unwrap();
+    assert_eq!(
+        conversion.untranslated,
+        [
+            Untranslated::Section {
+                index: SectionIndex(4),
+                reason: "unsupported section kind",
+            },
+            Untranslated::Relocation {
+                section: SectionIndex(1),
+                offset: 10,
+                reason: "unsupported relocation kind",
+            },
+        ]
+    );
+    let bytes = conversion.object.write().unwrap();
+
+    let omf = read::omf::OmfFile::parse(&*bytes).unwrap();
+    assert_eq!(omf.module_name, Some("test.c"));
+    let sections = omf
+        .sections()
+        .map(|section| (section.name().unwrap(), section.kind()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sections,
+        [
+            (".text", SectionKind::Text),
+            (".data", SectionKind::Data),
+            (".bss", SectionKind::UninitializedData),
+            // The segment that the COMDAT is allocated in.
+            (".text$inline", SectionKind::Text),
+            ("_inline", SectionKind::Text),
+        ]
+    );
+
+    // The implicit addends are kept in the data.
+    let text = omf.section_by_index(SectionIndex(1)).unwrap();
+    let text_data = text.uncompressed_data().unwrap();
+    assert_eq!(&text_data[6..10], &[4, 0, 0, 0]);
+    let relocations = text.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 2);
+    let (offset, relocation) = &relocations[0];
+    assert_eq!(*offset, 1);
+    assert_eq!(relocation.kind(), RelocationKind::Relative);
+    assert_eq!(relocation.addend(), 0);
+    match relocation.target() {
+        RelocationTarget::Symbol(index) => {
+            assert_eq!(omf.symbol_by_index(index).unwrap().name().unwrap(), "_ext")
+        }
+        target => panic!("unexpected target {:?}", target),
+    }
+    let (offset, relocation) = &relocations[1];
+    assert_eq!(*offset, 6);
+    assert_eq!(relocation.kind(), RelocationKind::SegmentRelative);
+    assert_eq!(relocation.addend(), 4);
+    assert_eq!(
+        relocation.target(),
+        RelocationTarget::Section(SectionIndex(2))
+    );
+
+    let symbol = omf.symbol_by_name("_value").unwrap();
+    assert_eq!(symbol.address(), 4);
+    assert_eq!(symbol.section_index(), Some(SectionIndex(2)));
+    assert!(symbol.is_global());
+    let symbol = omf.symbol_by_name("_local").unwrap();
+    assert_eq!(symbol.address(), 8);
+    assert!(symbol.is_local());
+    assert!(omf.symbol_by_name("_ext").unwrap().is_undefined());
+    let symbol = omf.symbol_by_name("_common").unwrap();
+    assert!(symbol.is_common());
+    assert_eq!(symbol.size(), 8);
+
+    let comdat = omf.comdats().next().unwrap();
+    assert_eq!(comdat.kind(), ComdatKind::Any);
+    assert_eq!(comdat.name().unwrap(), "_inline");
+}
+
+#[test]
+fn omf_rebuild_object() {
+    use object::write::omf::TargetProfile;
+
+    let mut object = write::Object::new(BinaryFormat::Omf, Architecture::I386, Endianness::Little);
+    object.set_omf_target_profile(TargetProfile::Real8086);
+    object.add_file_symbol(b"test.c".to_vec());
+    let text = object.section_id(write::StandardSection::Text);
+    // call _ext; mov ax, [_value + 2]; mov ax, seg _value; ret
+    object.append_section_data(text, &[0xE8, 0, 0, 0xA1, 0, 0, 0xB8, 0, 0, 0xC3], 1);
+    let data = object.section_id(write::StandardSection::Data);
+    object.append_section_data(data, &[1, 2, 3, 4], 2);
+    let bss = object.section_id(write::StandardSection::UninitializedData);
+    object.append_section_bss(bss, 16, 2);
+    let section = object.add_section(b"CODE".to_vec(), b"_TEXT".to_vec(), SectionKind::Text);
+    object.append_section_data(section, &[0xC3], 1);
+    object.add_omf_group(b"DGROUP".to_vec(), vec![data, bss]);
+    object.add_omf_line_number(text, 0, 10);
+    object.add_omf_line_number(text, 6, 11);
+
+    let mut symbol = |name: &[u8], value, kind, scope, section| {
+        object.add_symbol(write::Symbol {
+            name: name.to_vec(),
+            value,
+            size: 0,
+            kind,
+            scope,
+            weak: false,
+            section,
+            flags: SymbolFlags::None,
+        })
+    };
+    let start = symbol(
+        b"_start",
+        3,
+        SymbolKind::Text,
+        SymbolScope::Linkage,
+        write::SymbolSection::Section(text),
+    );
+    let value = symbol(
+        b"_value",
+        2,
+        SymbolKind::Data,
+        SymbolScope::Linkage,
+        write::SymbolSection::Section(data),
+    );
+    symbol(
+        b"_local",
+        8,
+        SymbolKind::Data,
+        SymbolScope::Compilation,
+        write::SymbolSection::Section(bss),
+    );
+    let ext = symbol(
+        b"_ext",
+        0,
+        SymbolKind::Text,
+        SymbolScope::Unknown,
+        write::SymbolSection::Undefined,
+    );
+    let inline = symbol(
+        b"_inline",
+        0,
+        SymbolKind::Text,
+        SymbolScope::Linkage,
+        write::SymbolSection::Section(section),
+    );
+    object.add_comdat(write::Comdat {
+        kind: ComdatKind::Any,
+        symbol: inline,
+        sections: vec![section],
+    });
+    for (offset, symbol, kind, addend) in [
+        (1, ext, RelocationKind::Relative, -2),
+        (4, value, RelocationKind::Absolute, 2),
+        (7, value, RelocationKind::SectionIndex, 0),
+    ] {
+        object
+            .add_relocation(
+                text,
+                write::Relocation {
+                    offset,
+                    symbol,
+                    addend,
+                    flags: object::RelocationFlags::Generic {
+                        kind,
+                        encoding: object::RelocationEncoding::Generic,
+                        size: 16,
+                    },
+                },
+            )
+            .unwrap();
+    }
+    object.set_omf_entry(start);
+    let bytes = object.write().unwrap();
+    let omf = read::omf::OmfFile::parse(&*bytes).unwrap();
+
+    let conversion = rebuild_omf(&omf).unwrap();
+    assert_eq!(conversion.untranslated, []);
+    let rebuilt_bytes = conversion.object.write().unwrap();
+    assert_eq!(rebuilt_bytes, bytes);
+
+    // Edit the rebuilt object.
+    let mut conversion = rebuild_omf(&omf).unwrap();
+    let object = &mut conversion.object;
+    let extra = object.add_section(b"DATA".to_vec(), b"_EXTRA".to_vec(), SectionKind::Data);
+    object.append_section_data(extra, &[5, 6], 1);
+    let rebuilt_bytes = conversion.object.write().unwrap();
+    let rebuilt = read::omf::OmfFile::parse(&*rebuilt_bytes).unwrap();
+    assert_eq!(rebuilt.sections().count(), omf.sections().count() + 1);
+    let section = rebuilt.section_by_name("_EXTRA").unwrap();
+    assert_eq!(section.uncompressed_data().unwrap(), &[5, 6][..]);
+    assert_eq!(rebuilt.symbols().count(), omf.symbols().count());
+    assert_eq!(rebuilt.start_address, omf.start_address);
+}
@@ -0,0 +1,140 @@
+use object::read::aout::AoutFile;
+use object::{
+    aout, BinaryFormat, FileKind, Object, ObjectKind, ObjectSection, ObjectSegment, ObjectSymbol,
+    RelocationKind, RelocationTarget, SectionIndex, SectionKind, SymbolIndex, SymbolSection,
+};
+
+use super::util::{put, put32, put32be};
+
+/// A little endian i386 object file with a call to an external function,
+/// a pointer to its own data, and a debugging symbol.
+fn i386_object() -> Vec<u8> {
+    let mut data = vec![0; 0x81];
+    put32(&mut data, 0x00, (100 << 16) | 0o407);
+    put32(&mut data, 0x04, 8); // text
+    put32(&mut data, 0x08, 4); // data
+    put32(&mut data, 0x0c, 8); // bss
+    put32(&mut data, 0x10, 48); // symbols
+    put32(&mut data, 0x18, 8); // text relocations
+    put32(&mut data, 0x1c, 8); // data relocations
+
+    put(
+        &mut data,
+        0x20,
+        &[0xe8, 0xfc, 0xff, 0xff, 0xff, 0x90, 0x90, 0x90],
+    );
+    put32(&mut data, 0x28, 8);
+
+    // call foo
+    put32(&mut data, 0x2c, 1);
+    put32(&mut data, 0x30, 2 | (1 << 24) | (2 << 25) | (1 << 27));
+    // .long .data
+    put32(&mut data, 0x34, 0);
+    put32(&mut data, 0x38, u32::from(aout::N_DATA) | (2 << 25));
+
+    let symbols = [
+        (4, aout::N_TEXT | aout::N_EXT, 0),
+        (9, aout::N_BSS, 12),
+        (13, aout::N_UNDF | aout::N_EXT, 0),
+        (17, 0x64, 0),
+    ];
+    for (i, (strx, n_type, value)) in symbols.iter().enumerate() {
+        let offset = 0x3c + i * 12;
+        put32(&mut data, offset, *strx);
+        data[offset + 4] = *n_type;
+        put32(&mut data, offset + 8, *value);
+    }
+    put32(&mut data, 0x6c, 21);
+    put(&mut data, 0x70, b"main\0buf\0foo\0x.c\0");
+    data
+}
+
+#[test]
+fn aout_omagic_parse() {
+    let data = i386_object();
+    assert_eq!(FileKind::parse(&*data).unwrap(), FileKind::Aout);
+    let file = object::File::parse(&*data).unwrap();
+    assert_eq!(file.format(), BinaryFormat::Aout);
+    assert_eq!(file.kind(), ObjectKind::Relocatable);
+    assert_eq!(file.architecture(), object::Architecture::I386);
+    assert!(file.is_little_endian());
+    assert!(file.has_debug_symbols());
+
+    let sections = file.sections().collect::<Vec<_>>();
+    assert_eq!(sections.len(), 3);
+    assert_eq!(sections[1].name(), Ok(".data"));
+    assert_eq!(sections[1].address(), 8);
+    assert_eq!(sections[1].data(), Ok(&[8, 0, 0, 0][..]));
+    assert_eq!(sections[2].kind(), SectionKind::UninitializedData);
+    assert_eq!(sections[2].address(), 12);
+    assert_eq!(sections[2].size(), 8);
+
+    let symbols = file.symbols().collect::<Vec<_>>();
+    assert_eq!(symbols.len(), 3);
+    assert_eq!(symbols[0].name(), Ok("main"));
+    assert!(symbols[0].is_global());
+    assert_eq!(
+        symbols[0].section(),
+        SymbolSection::Section(SectionIndex(1))
+    );
+    assert_eq!(symbols[1].name(), Ok("buf"));
+    assert!(symbols[1].is_local());
+    assert_eq!(symbols[1].section_index(), Some(SectionIndex(3)));
+    assert_eq!(symbols[1].address(), 12);
+    assert!(symbols[2].is_undefined());
+    assert!(file.symbol_by_index(SymbolIndex(3)).is_err());
+
+    let relocations = sections[0].relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 1);
+    assert_eq!(relocations[0].0, 1);
+    assert_eq!(relocations[0].1.kind(), RelocationKind::Relative);
+    assert_eq!(relocations[0].1.size(), 32);
+    assert_eq!(
+        relocations[0].1.target(),
+        RelocationTarget::Symbol(SymbolIndex(2))
+    );
+    assert!(relocations[0].1.has_implicit_addend());
+    let foo = file.symbol_by_index(SymbolIndex(2)).unwrap();
+    assert_eq!(foo.name(), Ok("foo"));
+
+    let relocations = sections[1].relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 1);
+    assert_eq!(relocations[0].1.kind(), RelocationKind::Absolute);
+    assert_eq!(
+        relocations[0].1.target(),
+        RelocationTarget::Section(SectionIndex(2))
+    );
+    assert_eq!(relocations[0].1.addend(), -8);
+}
+
+#[test]
+fn aout_zmagic_parse() {
+    // A big endian 68020 demand paged executable.
+    let mut data = vec![0; 0x420];
+    put32be(&mut data, 0x00, (2 << 16) | 0o413);
+    put32be(&mut data, 0x04, 0x10); // text
+    put32be(&mut data, 0x08, 0x10); // data
+    put32be(&mut data, 0x0c, 0x20); // bss
+    put32be(&mut data, 0x14, 4); // entry
+    put(&mut data, 0x400, &[0x4e; 0x10]);
+
+    assert_eq!(FileKind::parse(&*data).unwrap(), FileKind::Aout);
+    let file = object::File::parse(&*data).unwrap();
+    assert_eq!(file.kind(), ObjectKind::Executable);
+    assert_eq!(file.architecture(), object::Architecture::M68k);
+    assert!(!file.is_little_endian());
+    assert_eq!(file.entry(), 4);
+    assert_eq!(file.symbols().count(), 0);
+
+    let segments = file.segments().collect::<Vec<_>>();
+    assert_eq!(segments.len(), 2);
+    assert_eq!(segments[0].file_range(), (0x400, 0x10));
+    assert_eq!(segments[0].data(), Ok(&[0x4e; 0x10][..]));
+    assert_eq!(segments[1].address(), 0x400);
+    assert_eq!(segments[1].size(), 0x30);
+    assert_eq!(segments[1].file_range(), (0x410, 0x10));
+
+    let file = AoutFile::<&[u8]>::parse(&*data).unwrap();
+    assert_eq!(file.magic(), aout::ZMAGIC);
+    assert_eq!(file.header().n_machtype(file.endian()), aout::M_68020);
+}
@@ -21,3 +21,66 @@ fn coff_extended_relocations() {
     let relocations = code_section.relocations().collect::<Vec<_>>();
     assert_eq!(relocations.len(), 65536);
 }
+
+#[cfg(feature = "coff")]
+#[test]
+fn coff_djgpp() {
+    use object::{FileKind, ObjectKind, ObjectSymbol};
+
+    // An MS-DOS stub of 0x100 bytes: 1 page, with 0x100 bytes in the last page.
+    let mut data = vec![0; 0x100];
+    data[0..2].copy_from_slice(b"MZ");
+    data[2..4].copy_from_slice(&0x100u16.to_le_bytes());
+    data[4..6].copy_from_slice(&1u16.to_le_bytes());
+
+    let text = [0x90, 0x90, 0xc3];
+    let text_offset = 20 + 28 + 40;
+    let symbols_offset = text_offset + text.len();
+
+    // File header.
+    data.extend_from_slice(&pe::IMAGE_FILE_MACHINE_I386.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&(symbols_offset as u32).to_le_bytes());
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&28u16.to_le_bytes());
+    data.extend_from_slice(&pe::IMAGE_FILE_EXECUTABLE_IMAGE.to_le_bytes());
+    // Optional header.
+    data.extend_from_slice(&0x010bu16.to_le_bytes());
+    data.extend_from_slice(&[0; 26]);
+    // Section header.
+    data.extend_from_slice(b".text\0\0\0");
+    data.extend_from_slice(&0x1000u32.to_le_bytes());
+    data.extend_from_slice(&0x1000u32.to_le_bytes());
+    data.extend_from_slice(&(text.len() as u32).to_le_bytes());
+    data.extend_from_slice(&(text_offset as u32).to_le_bytes());
+    data.extend_from_slice(&[0; 12]);
+    data.extend_from_slice(&pe::IMAGE_SCN_CNT_CODE.to_le_bytes());
+    data.extend_from_slice(&text);
+    // Symbol table.
+    data.extend_from_slice(b"_start\0\0");
+    data.extend_from_slice(&0x1000u32.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&0u16.to_le_bytes());
+    data.extend_from_slice(&[pe::IMAGE_SYM_CLASS_EXTERNAL, 0]);
+    // String table.
+    data.extend_from_slice(&4u32.to_le_bytes());
+
+    assert_eq!(FileKind::parse(&*data).unwrap(), FileKind::Coff);
+    let object = object::File::parse(&*data).unwrap();
+    assert_eq!(object.kind(), ObjectKind::Executable);
+
+    let file = read::coff::CoffFile::<_>::parse(&*data).unwrap();
+    assert_eq!(file.image_offset(), 0x100);
+    assert_eq!(file.dos_stub().unwrap().len(), 0x100);
+    let section = file.section_by_name(".text").unwrap();
+    assert_eq!(section.data().unwrap(), &text);
+    assert_eq!(section.file_range(), Some((0x100 + text_offset as u64, 3)));
+    let symbol = file.symbol_by_name("_start").unwrap();
+    assert_eq!(symbol.section_index(), Some(section.index()));
+
+    // More than 512 bytes in the last page of the stub.
+    data[2..4].copy_from_slice(&0xffffu16.to_le_bytes());
+    assert!(!read::coff::is_djgpp_file(&*data));
+    assert!(FileKind::parse(&*data).is_err());
+}
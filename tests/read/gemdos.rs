@@ -0,0 +1,100 @@
+use object::read::gemdos::GemdosFile;
+use object::{
+    gemdos, BinaryFormat, FileKind, Object, ObjectKind, ObjectSection, ObjectSegment, ObjectSymbol,
+    RelocationKind, RelocationTarget, SectionIndex, SectionKind, SymbolSection,
+};
+
+fn symbol(data: &mut Vec<u8>, name: &[u8], sym_type: u16, value: u32) {
+    let mut padded = [0; 8];
+    padded[..name.len()].copy_from_slice(name);
+    data.extend_from_slice(&padded);
+    data.extend_from_slice(&sym_type.to_be_bytes());
+    data.extend_from_slice(&value.to_be_bytes());
+}
+
+#[test]
+fn gemdos_parse() {
+    let text_size = 0x108u32;
+    let mut data = Vec::new();
+    data.extend_from_slice(&gemdos::PRG_MAGIC.to_be_bytes());
+    for value in [text_size, 8, 16, 14 * 3, 0, gemdos::PF_FASTLOAD] {
+        data.extend_from_slice(&value.to_be_bytes());
+    }
+    data.extend_from_slice(&0u16.to_be_bytes());
+    data.resize(data.len() + text_size as usize, 0);
+    data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0x01, 0x10]);
+
+    let global = gemdos::DRI_DEFINED | gemdos::DRI_GLOBAL;
+    symbol(&mut data, b"_main", global | gemdos::DRI_TEXT, 0);
+    symbol(
+        &mut data,
+        b"_long_na",
+        global | gemdos::DRI_BSS | gemdos::DRI_LONG_NAME,
+        0x110,
+    );
+    data.extend_from_slice(b"me_buffer\0\0\0\0\0");
+
+    // Fixups at 2, 2 + 254 + 4 and 260 + 8.
+    data.extend_from_slice(&2u32.to_be_bytes());
+    data.extend_from_slice(&[1, 4, 8, 0]);
+
+    assert_eq!(FileKind::parse(&*data).unwrap(), FileKind::Gemdos);
+    let file = object::File::parse(&*data).unwrap();
+    assert_eq!(file.format(), BinaryFormat::Gemdos);
+    assert_eq!(file.kind(), ObjectKind::Executable);
+    assert_eq!(file.architecture(), object::Architecture::M68k);
+    assert_eq!(file.entry(), 0);
+
+    let segments = file.segments().collect::<Vec<_>>();
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].address(), 0);
+    assert_eq!(segments[0].size(), 0x108 + 8 + 16);
+    assert_eq!(segments[0].file_range(), (28, 0x108 + 8));
+
+    let sections = file.sections().collect::<Vec<_>>();
+    assert_eq!(sections.len(), 3);
+    assert_eq!(sections[0].name(), Ok(".text"));
+    assert_eq!(sections[0].kind(), SectionKind::Text);
+    assert_eq!(sections[0].file_range(), Some((28, 0x108)));
+    assert_eq!(sections[1].name(), Ok(".data"));
+    assert_eq!(sections[1].address(), 0x108);
+    assert_eq!(sections[1].data(), Ok(&[0, 0, 0, 0, 0, 0, 0x01, 0x10][..]));
+    assert_eq!(sections[2].kind(), SectionKind::UninitializedData);
+    assert_eq!(sections[2].address(), 0x110);
+    assert_eq!(sections[2].file_range(), None);
+
+    let relocations = sections[0].relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 2);
+    assert_eq!(relocations[0].0, 2);
+    assert_eq!(relocations[1].0, 260);
+    assert_eq!(relocations[1].1.kind(), RelocationKind::Absolute);
+    assert_eq!(relocations[1].1.size(), 32);
+    assert_eq!(
+        relocations[1].1.target(),
+        RelocationTarget::Section(SectionIndex(1))
+    );
+    let relocations = sections[1].relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 1);
+    assert_eq!(relocations[0].0, 4);
+
+    let symbols = file.symbols().collect::<Vec<_>>();
+    assert_eq!(symbols.len(), 2);
+    assert_eq!(symbols[0].name(), Ok("_main"));
+    assert!(symbols[0].is_global());
+    assert_eq!(
+        symbols[0].section(),
+        SymbolSection::Section(SectionIndex(1))
+    );
+    assert_eq!(symbols[1].name(), Ok("_long_na"));
+    assert_eq!(
+        symbols[1].section(),
+        SymbolSection::Section(SectionIndex(3))
+    );
+    assert_eq!(symbols[1].address(), 0x110);
+
+    let file = GemdosFile::<&[u8]>::parse(&*data).unwrap();
+    assert_eq!(file.program_flags(), gemdos::PF_FASTLOAD);
+    assert_eq!(file.fixups(), &[2, 260, 268]);
+    let symbol = file.symbol_by_index(object::SymbolIndex(1)).unwrap();
+    assert_eq!(symbol.extended_name(), b"me_buffer");
+}
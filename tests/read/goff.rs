@@ -0,0 +1,230 @@
+use object::read::goff::GoffFile;
+use object::{
+    goff, BinaryFormat, FileKind, Object, ObjectKind, ObjectSection, ObjectSymbol,
+    RelocationEncoding, RelocationKind, RelocationTarget, SectionIndex, SectionKind, SymbolIndex,
+    SymbolScope, SymbolSection,
+};
+
+/// Append a logical record, split into 80-byte physical records.
+fn record(data: &mut Vec<u8>, record_type: u8, fields: &[u8]) {
+    let mut rest = fields;
+    let mut flags = 0;
+    loop {
+        let size = rest
+            .len()
+            .min(goff::GOFF_RECORD_LENGTH - goff::GOFF_PREFIX_LENGTH);
+        let (chunk, next) = rest.split_at(size);
+        if !next.is_empty() {
+            flags |= goff::GOFF_CONTINUED;
+        } else {
+            flags &= !goff::GOFF_CONTINUED;
+        }
+        data.extend_from_slice(&[goff::GOFF_PTV_PREFIX, (record_type << 4) | flags, 0]);
+        data.extend_from_slice(chunk);
+        data.resize(data.len() + goff::GOFF_RECORD_LENGTH - 3 - size, 0);
+        if next.is_empty() {
+            return;
+        }
+        rest = next;
+        flags = goff::GOFF_CONTINUATION;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn esd(
+    data: &mut Vec<u8>,
+    symbol_type: u8,
+    esdid: u32,
+    parent_esdid: u32,
+    offset: u32,
+    length: u32,
+    tasking: u8,
+    binding: u8,
+    loading: u8,
+    name: &[u8],
+) {
+    let mut fields = vec![symbol_type];
+    fields.extend_from_slice(&esdid.to_be_bytes());
+    fields.extend_from_slice(&parent_esdid.to_be_bytes());
+    fields.extend_from_slice(&[0; 4]);
+    fields.extend_from_slice(&offset.to_be_bytes());
+    fields.extend_from_slice(&[0; 4]);
+    fields.extend_from_slice(&length.to_be_bytes());
+    fields.resize(60 - 3, 0);
+    fields.extend_from_slice(&[goff::ESD_AMODE_64, goff::ESD_RMODE_64, 0, tasking]);
+    fields.extend_from_slice(&[binding, loading, 3, 0, 0, 0]);
+    fields.extend_from_slice(&(name.len() as u16).to_be_bytes());
+    fields.extend_from_slice(name);
+    record(data, goff::GOFF_RT_ESD, &fields);
+}
+
+fn txt(data: &mut Vec<u8>, esdid: u32, offset: u32, text: &[u8]) {
+    let mut fields = vec![0];
+    fields.extend_from_slice(&esdid.to_be_bytes());
+    fields.extend_from_slice(&[0; 4]);
+    fields.extend_from_slice(&offset.to_be_bytes());
+    fields.extend_from_slice(&[0; 6]);
+    fields.extend_from_slice(&(text.len() as u16).to_be_bytes());
+    fields.extend_from_slice(text);
+    record(data, goff::GOFF_RT_TXT, &fields);
+}
+
+#[test]
+fn goff_parse() {
+    let mut data = Vec::new();
+    record(&mut data, goff::GOFF_RT_HDR, &[0; 57]);
+    esd(&mut data, goff::ESD_ST_SD, 1, 0, 0, 0, 0, 0, 0, b"MAIN");
+    esd(
+        &mut data,
+        goff::ESD_ST_ED,
+        2,
+        1,
+        0,
+        68,
+        goff::ESD_EXE_CODE,
+        0,
+        0,
+        b"C_CODE",
+    );
+    esd(
+        &mut data,
+        goff::ESD_ST_LD,
+        3,
+        2,
+        4,
+        0,
+        goff::ESD_EXE_CODE,
+        goff::ESD_BST_STRONG,
+        goff::ESD_BSC_LIBRARY,
+        b"func",
+    );
+    esd(
+        &mut data,
+        goff::ESD_ST_ER,
+        4,
+        1,
+        0,
+        0,
+        goff::ESD_EXE_CODE,
+        goff::ESD_BST_WEAK,
+        goff::ESD_BSC_IMPORT_EXPORT,
+        b"ext",
+    );
+    esd(
+        &mut data,
+        goff::ESD_ST_ER,
+        5,
+        1,
+        0,
+        0,
+        0,
+        0,
+        goff::ESD_BSC_LIBRARY,
+        b"a_name_that_does_not_fit_in_the_first_record",
+    );
+    txt(&mut data, 2, 0, &[0x07, 0x07, 0x07, 0x07]);
+    txt(&mut data, 2, 8, &[0xa7; 60]);
+
+    let mut rld = vec![0];
+    rld.extend_from_slice(&32u16.to_be_bytes());
+    rld.extend_from_slice(&[0, 0, 0, 0, 4, 0, 0, 0]);
+    rld.extend_from_slice(&[0, 0, 0, 4, 0, 0, 0, 2, 0, 0, 0, 8]);
+    rld.extend_from_slice(&[
+        goff::RLD_FLAG_SAME_RID | goff::RLD_FLAG_SAME_PID,
+        goff::RLD_RT_RELATIVE_IMMEDIATE << 4,
+        0,
+        0,
+        4,
+        0,
+        0,
+        0,
+    ]);
+    rld.extend_from_slice(&[0, 0, 0, 20]);
+    record(&mut data, goff::GOFF_RT_RLD, &rld);
+    record(&mut data, goff::GOFF_RT_END, &[0; 23]);
+
+    assert_eq!(FileKind::parse(&*data).unwrap(), FileKind::Goff);
+    let object = object::File::parse(&*data).unwrap();
+    assert_eq!(object.format(), BinaryFormat::Goff);
+    assert_eq!(object.kind(), ObjectKind::Relocatable);
+    assert_eq!(object.architecture(), object::Architecture::S390x);
+
+    let file = GoffFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.esds().len(), 5);
+    assert_eq!(file.records().count(), 10);
+
+    let section = file.section_by_index(SectionIndex(1)).unwrap();
+    assert_eq!(section.name(), Ok("C_CODE"));
+    assert_eq!(section.segment_name(), Ok(Some("MAIN")));
+    assert_eq!(section.kind(), SectionKind::Text);
+    assert_eq!(section.size(), 68);
+    assert_eq!(section.align(), 8);
+    assert!(section.data().is_err());
+    let contents = section.uncompressed_data().unwrap();
+    assert_eq!(contents.len(), 68);
+    assert_eq!(&contents[..8], &[0x07, 0x07, 0x07, 0x07, 0, 0, 0, 0]);
+    assert_eq!(&contents[8..], &[0xa7; 60][..]);
+
+    let relocations = section.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 2);
+    let (offset, relocation) = &relocations[0];
+    assert_eq!(*offset, 8);
+    assert_eq!(relocation.kind(), RelocationKind::Absolute);
+    assert_eq!(relocation.size(), 32);
+    assert_eq!(
+        relocation.target(),
+        RelocationTarget::Symbol(SymbolIndex(1))
+    );
+    let (offset, relocation) = &relocations[1];
+    assert_eq!(*offset, 20);
+    assert_eq!(relocation.kind(), RelocationKind::Relative);
+    assert_eq!(relocation.encoding(), RelocationEncoding::S390xDbl);
+    assert_eq!(
+        relocation.target(),
+        RelocationTarget::Symbol(SymbolIndex(1))
+    );
+
+    let symbols = file.symbols().collect::<Vec<_>>();
+    assert_eq!(symbols.len(), 3);
+    assert_eq!(symbols[0].name(), Ok("func"));
+    assert_eq!(symbols[0].address(), 4);
+    assert_eq!(
+        symbols[0].section(),
+        SymbolSection::Section(SectionIndex(1))
+    );
+    assert_eq!(symbols[0].scope(), SymbolScope::Linkage);
+    assert!(symbols[0].is_global());
+    assert_eq!(symbols[1].name(), Ok("ext"));
+    assert!(symbols[1].is_undefined());
+    assert!(symbols[1].is_weak());
+    assert_eq!(symbols[1].scope(), SymbolScope::Dynamic);
+    assert!(symbols[2].name_bytes().is_err());
+    assert_eq!(
+        file.esds()[4].name(),
+        b"a_name_that_does_not_fit_in_the_first_record"
+    );
+}
+
+#[test]
+fn goff_txt_past_length() {
+    let mut data = Vec::new();
+    record(&mut data, goff::GOFF_RT_HDR, &[0; 57]);
+    esd(&mut data, goff::ESD_ST_SD, 1, 0, 0, 0, 0, 0, 0, b"MAIN");
+    esd(
+        &mut data,
+        goff::ESD_ST_ED,
+        2,
+        1,
+        0,
+        4,
+        goff::ESD_EXE_CODE,
+        0,
+        0,
+        b"C_CODE",
+    );
+    txt(&mut data, 2, 0xffff_fff0, &[0x07; 4]);
+    record(&mut data, goff::GOFF_RT_END, &[0; 23]);
+
+    let file = GoffFile::<&[u8]>::parse(&data).unwrap();
+    assert!(file.section_contents(SectionIndex(1)).is_err());
+}
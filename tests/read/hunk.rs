@@ -0,0 +1,141 @@
+use object::read::hunk::HunkFile;
+use object::{
+    hunk, BinaryFormat, FileKind, Object, ObjectKind, ObjectSection, ObjectSegment, ObjectSymbol,
+    RelocationKind, RelocationTarget, SectionIndex, SectionKind, SymbolIndex, SymbolSection,
+};
+
+fn words(data: &mut Vec<u8>, words: &[u32]) {
+    for word in words {
+        data.extend_from_slice(&word.to_be_bytes());
+    }
+}
+
+/// An object file unit with a code hunk that references an external function,
+/// and a chip memory data hunk that points into the code hunk.
+fn object_unit() -> Vec<u8> {
+    let mut data = Vec::new();
+    words(&mut data, &[hunk::HUNK_UNIT, 1]);
+    data.extend_from_slice(b"main");
+    words(&mut data, &[hunk::HUNK_NAME, 1]);
+    data.extend_from_slice(b"text");
+    words(&mut data, &[hunk::HUNK_CODE, 2]);
+    data.extend_from_slice(&[0x4e, 0xb9, 0, 0, 0, 0, 0x4e, 0x75]);
+    words(&mut data, &[hunk::HUNK_EXT]);
+    words(&mut data, &[(u32::from(hunk::EXT_DEF) << 24) | 2]);
+    data.extend_from_slice(b"_main\0\0\0");
+    words(&mut data, &[0]);
+    words(&mut data, &[(u32::from(hunk::EXT_REF32) << 24) | 1]);
+    data.extend_from_slice(b"_foo");
+    words(&mut data, &[1, 2]);
+    words(&mut data, &[(u32::from(hunk::EXT_COMMON) << 24) | 1]);
+    data.extend_from_slice(b"_buf");
+    words(&mut data, &[64, 0, 0]);
+    words(&mut data, &[hunk::HUNK_SYMBOL, 2]);
+    data.extend_from_slice(b"_local\0\0");
+    words(&mut data, &[6, 0, hunk::HUNK_END]);
+    words(&mut data, &[hunk::HUNK_DATA | hunk::HUNKF_CHIP, 1, 4]);
+    words(&mut data, &[hunk::HUNK_RELOC32, 1, 0, 0, 0, hunk::HUNK_END]);
+    data
+}
+
+#[test]
+fn hunk_object_parse() {
+    let data = object_unit();
+    assert_eq!(FileKind::parse(&*data).unwrap(), FileKind::Hunk);
+    let file = object::File::parse(&*data).unwrap();
+    assert_eq!(file.format(), BinaryFormat::Hunk);
+    assert_eq!(file.kind(), ObjectKind::Relocatable);
+    assert_eq!(file.architecture(), object::Architecture::M68k);
+    assert_eq!(file.segments().count(), 0);
+
+    let sections = file.sections().collect::<Vec<_>>();
+    assert_eq!(sections.len(), 2);
+    assert_eq!(sections[0].name(), Ok("text"));
+    assert_eq!(sections[0].kind(), SectionKind::Text);
+    assert_eq!(sections[0].size(), 8);
+    assert_eq!(sections[1].name(), Ok("DATA"));
+    assert_eq!(sections[1].data(), Ok(&[0, 0, 0, 4][..]));
+
+    let symbols = file.symbols().collect::<Vec<_>>();
+    assert_eq!(symbols.len(), 4);
+    assert_eq!(symbols[0].name(), Ok("_main"));
+    assert!(symbols[0].is_global());
+    assert_eq!(
+        symbols[0].section(),
+        SymbolSection::Section(SectionIndex(1))
+    );
+    assert_eq!(symbols[1].name(), Ok("_foo"));
+    assert!(symbols[1].is_undefined());
+    assert_eq!(symbols[2].name(), Ok("_buf"));
+    assert!(symbols[2].is_common());
+    assert_eq!(symbols[2].size(), 64);
+    assert_eq!(symbols[3].name(), Ok("_local"));
+    assert!(symbols[3].is_local());
+    assert_eq!(symbols[3].address(), 6);
+
+    let relocations = sections[0].relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 1);
+    assert_eq!(relocations[0].0, 2);
+    assert_eq!(relocations[0].1.kind(), RelocationKind::Absolute);
+    assert_eq!(relocations[0].1.size(), 32);
+    assert_eq!(
+        relocations[0].1.target(),
+        RelocationTarget::Symbol(SymbolIndex(1))
+    );
+
+    let relocations = sections[1].relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 1);
+    assert_eq!(
+        relocations[0].1.target(),
+        RelocationTarget::Section(SectionIndex(1))
+    );
+
+    let file = HunkFile::<&[u8]>::parse(&*data).unwrap();
+    assert_eq!(file.units().len(), 1);
+    assert_eq!(file.units()[0].name(), b"main");
+    assert_eq!(file.hunks()[1].memory_flags(), hunk::HUNKF_CHIP);
+}
+
+#[test]
+fn hunk_executable_parse() {
+    let mut data = Vec::new();
+    words(&mut data, &[hunk::HUNK_HEADER, 0, 2, 0, 1, 2]);
+    words(&mut data, &[hunk::HUNKF_MEMORY | 4, 0x0001_0002]);
+    words(&mut data, &[hunk::HUNK_CODE, 2]);
+    data.extend_from_slice(&[0x41, 0xf9, 0, 0, 0, 0, 0x4e, 0x75]);
+    // Short relocations, padded to a multiple of 4 bytes.
+    words(&mut data, &[hunk::HUNK_RELOC32SHORT]);
+    for word in [2u16, 1, 2, 4, 0, 0] {
+        data.extend_from_slice(&word.to_be_bytes());
+    }
+    words(&mut data, &[hunk::HUNK_DEBUG, 1, 0, hunk::HUNK_END]);
+    words(&mut data, &[hunk::HUNK_BSS, 4, hunk::HUNK_END]);
+
+    assert_eq!(FileKind::parse(&*data).unwrap(), FileKind::Hunk);
+    let file = object::File::parse(&*data).unwrap();
+    assert_eq!(file.kind(), ObjectKind::Executable);
+    assert!(file.has_debug_symbols());
+
+    let segments = file.segments().collect::<Vec<_>>();
+    assert_eq!(segments.len(), 2);
+    assert_eq!(segments[0].file_range(), (0x28, 8));
+    assert_eq!(segments[1].size(), 16);
+
+    let section = file.section_by_index(SectionIndex(2)).unwrap();
+    assert_eq!(section.kind(), SectionKind::UninitializedData);
+    assert_eq!(section.file_range(), None);
+
+    let section = file.section_by_index(SectionIndex(1)).unwrap();
+    let relocations = section.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 2);
+    assert_eq!(relocations[0].0, 2);
+    assert_eq!(relocations[1].0, 4);
+    assert_eq!(
+        relocations[0].1.target(),
+        RelocationTarget::Section(SectionIndex(2))
+    );
+
+    let file = HunkFile::<&[u8]>::parse(&*data).unwrap();
+    assert_eq!(file.hunks()[1].memory_flags(), hunk::HUNKF_MEMORY);
+    assert_eq!(file.hunks()[1].memory_attributes(), 0x0001_0002);
+}
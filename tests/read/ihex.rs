@@ -0,0 +1,81 @@
+use std::fmt::Write;
+
+use object::read::ihex::IhexFile;
+use object::{ihex, BinaryFormat, FileKind, Object, ObjectSection, ObjectSegment, SectionIndex};
+
+fn record(text: &mut String, record_type: u8, address: u16, data: &[u8]) {
+    let mut bytes = vec![data.len() as u8];
+    bytes.extend_from_slice(&address.to_be_bytes());
+    bytes.push(record_type);
+    bytes.extend_from_slice(data);
+    let sum = bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    bytes.push(sum.wrapping_neg());
+    text.push(':');
+    for byte in bytes {
+        write!(text, "{:02X}", byte).unwrap();
+    }
+    text.push_str("\r\n");
+}
+
+#[test]
+fn ihex_parse() {
+    let mut text = String::new();
+    record(&mut text, ihex::IHEX_DATA, 0x0100, &[1, 2, 3, 4]);
+    record(&mut text, ihex::IHEX_DATA, 0x0104, &[5, 6]);
+    record(&mut text, ihex::IHEX_DATA, 0x0200, &[7]);
+    record(
+        &mut text,
+        ihex::IHEX_EXTENDED_LINEAR_ADDRESS,
+        0,
+        &[0x08, 0x00],
+    );
+    record(&mut text, ihex::IHEX_DATA, 0xfffe, &[8, 9, 10]);
+    record(
+        &mut text,
+        ihex::IHEX_EXTENDED_SEGMENT_ADDRESS,
+        0,
+        &[0x10, 0x00],
+    );
+    record(&mut text, ihex::IHEX_DATA, 0x0010, &[11]);
+    record(
+        &mut text,
+        ihex::IHEX_START_LINEAR_ADDRESS,
+        0,
+        &[0x08, 0x00, 0x01, 0x00],
+    );
+    record(&mut text, ihex::IHEX_END_OF_FILE, 0, &[]);
+    let data = text.as_bytes();
+
+    assert_eq!(FileKind::parse(data).unwrap(), FileKind::Ihex);
+    let object = object::File::parse(data).unwrap();
+    assert_eq!(object.format(), BinaryFormat::Ihex);
+    assert_eq!(object.entry(), 0x0800_0100);
+
+    let file = IhexFile::<&[u8]>::parse(data).unwrap();
+    let sections = file
+        .sections()
+        .map(|section| (section.address(), section.contents().to_vec()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sections,
+        [
+            (0x100, vec![1, 2, 3, 4, 5, 6]),
+            (0x200, vec![7]),
+            (0x0800_fffe, vec![8, 9]),
+            (0x0800_0000, vec![10]),
+            (0x10010, vec![11]),
+        ]
+    );
+    let section = file.section_by_index(SectionIndex(1)).unwrap();
+    assert!(section.data().is_err());
+    assert_eq!(&*section.uncompressed_data().unwrap(), &[1, 2, 3, 4, 5, 6]);
+    assert_eq!(
+        file.segments().map(|segment| segment.size()).sum::<u64>(),
+        11
+    );
+    assert_eq!(file.symbols().count(), 0);
+
+    let mut bad = text.into_bytes();
+    bad[9] ^= 1;
+    assert!(IhexFile::<&[u8]>::parse(&*bad).is_err());
+}
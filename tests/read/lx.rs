@@ -0,0 +1,281 @@
+use object::read::lx::{LxFile, LxImport, LxImportName};
+use object::{
+    lx, BinaryFormat, FileKind, Object, ObjectKind, ObjectSection, ObjectSymbol, RelocationKind,
+    RelocationTarget, SectionIndex, SectionKind, SymbolIndex, SymbolKind, SymbolSection,
+};
+
+use super::util::{put, put16, put32};
+
+/// A library with a code object, a data object with an iterated page, two
+/// named entry points, a forwarder, and imports from `DOSCALLS`.
+fn lx_library() -> Vec<u8> {
+    let mut data = vec![0; 0x220];
+    put(&mut data, 0, b"MZ");
+    put32(&mut data, 0x3c, 0x40);
+
+    // LX header, with most table offsets relative to it.
+    let h = 0x40;
+    put(&mut data, h, b"LX");
+    put32(&mut data, h + 0x10, lx::E32_MODDLL);
+    put32(&mut data, h + 0x14, 3); // pages
+    put32(&mut data, h + 0x18, 1); // start object
+    put32(&mut data, h + 0x1c, 4); // EIP
+    put32(&mut data, h + 0x28, 0x1000); // page size
+    put32(&mut data, h + 0x2c, 4); // page offset shift
+    put32(&mut data, h + 0x40, 0xc4); // object table
+    put32(&mut data, h + 0x44, 2);
+    put32(&mut data, h + 0x48, 0xf4); // object page table
+    put32(&mut data, h + 0x58, 0x10c); // resident names
+    put32(&mut data, h + 0x5c, 0x11c); // entry table
+    put32(&mut data, h + 0x68, 0x138); // fixup page table
+    put32(&mut data, h + 0x6c, 0x148); // fixup records
+    put32(&mut data, h + 0x70, 0x170); // import modules
+    put32(&mut data, h + 0x74, 1);
+    put32(&mut data, h + 0x78, 0x17a); // import procedures
+    put32(&mut data, h + 0x80, 0x200); // data pages (file offset)
+    put32(&mut data, h + 0x88, 0x1e0); // non-resident names (file offset)
+    put32(&mut data, h + 0x8c, 15);
+
+    // Object table: code in page 1, and data in pages 2 and 3.
+    let objects = [
+        [0x20, 0x10000, 0x2005, 1, 1, 0],
+        [0x2000, 0x20000, 0x2003, 2, 2, 0],
+    ];
+    for (i, object) in objects.iter().enumerate() {
+        for (j, value) in object.iter().enumerate() {
+            put32(&mut data, h + 0xc4 + i * 24 + j * 4, *value);
+        }
+    }
+
+    // Object page table: valid, iterated, and zero-filled pages.
+    put(&mut data, h + 0xf4, &[0, 0, 0, 0, 0x10, 0, 0, 0]);
+    put(&mut data, h + 0xfc, &[1, 0, 0, 0, 6, 0, 1, 0]);
+    put(&mut data, h + 0x104, &[0, 0, 0, 0, 0, 0, 3, 0]);
+
+    // Resident names.
+    put(&mut data, h + 0x10c, b"\x04DEMO\0\0\x05Hello\x01\0\0");
+
+    // Entry table: ordinals 1 and 2 in object 1, and ordinal 3 forwarded.
+    put(
+        &mut data,
+        h + 0x11c,
+        &[2, 3, 1, 0, 1, 4, 0, 0, 0, 1, 8, 0, 0, 0],
+    );
+    put(&mut data, h + 0x12a, &[1, 4, 0, 0, 0, 1, 0, 7, 0, 0, 0, 0]);
+
+    // Fixup page table: all records are in page 1.
+    for (i, offset) in [0, 0x27, 0x27, 0x27].iter().enumerate() {
+        put32(&mut data, h + 0x138 + i * 4, *offset);
+    }
+    // 32-bit offset of object 2.
+    put(&mut data, h + 0x148, &[7, 0x10, 4, 0, 2, 0x10, 0, 0, 0]);
+    // Self-relative import by name, with a source list.
+    put(&mut data, h + 0x151, &[0x28, 2, 2, 1, 0, 0, 8, 0, 0x0c, 0]);
+    // Import by ordinal, additive.
+    put(&mut data, h + 0x15b, &[7, 0x85, 0x14, 0, 1, 5, 2, 0]);
+    // Entry ordinal 2.
+    put(&mut data, h + 0x163, &[7, 3, 0x18, 0, 2]);
+    // Continuation of a fixup from the previous page.
+    put(&mut data, h + 0x168, &[7, 0, 0xfe, 0xff, 2, 0, 0]);
+
+    // Import module names and procedure names.
+    put(&mut data, h + 0x170, b"\x08DOSCALLS");
+    put(&mut data, h + 0x17a, b"\x07DosExit");
+
+    // Non-resident names.
+    put(&mut data, 0x1e0, b"\x04desc\0\0\x05Other\x02\0");
+
+    // Page data.
+    put(&mut data, 0x200, &[0x90; 0x10]);
+    put(&mut data, 0x210, &[4, 0, 2, 0, b'a', b'b']);
+    data
+}
+
+/// A program with a single code page, bound behind a DOS extender stub and a
+/// DOS/4G header.
+fn le_bound_program() -> Vec<u8> {
+    let mut data = vec![0; 0x1b0];
+    // Extender stub, with no `e_lfanew`.
+    put(&mut data, 0, b"MZ");
+    put16(&mut data, 0x02, 0x40);
+    put16(&mut data, 0x04, 1);
+    put(&mut data, 0x40, b"BW");
+    put16(&mut data, 0x42, 0x20);
+    put16(&mut data, 0x44, 1);
+
+    // Stub of the bound image, which gives the LE header offset.
+    let base = 0x60;
+    put(&mut data, base, b"MZ");
+    put16(&mut data, base + 0x02, 0x40);
+    put16(&mut data, base + 0x04, 1);
+    put32(&mut data, base + 0x3c, 0x40);
+
+    let h = 0xa0;
+    put(&mut data, h, b"LE");
+    put32(&mut data, h + 0x14, 1); // pages
+    put32(&mut data, h + 0x18, 1); // start object
+    put32(&mut data, h + 0x28, 0x1000); // page size
+    put32(&mut data, h + 0x2c, 0x10); // last page size
+    put32(&mut data, h + 0x40, 0xc4); // object table
+    put32(&mut data, h + 0x44, 1);
+    put32(&mut data, h + 0x48, 0xdc); // object page table
+    put32(&mut data, h + 0x58, 0xe0); // resident names
+    put32(&mut data, h + 0x5c, 0xe8); // entry table
+    put32(&mut data, h + 0x68, 0xec); // fixup page table
+    put32(&mut data, h + 0x6c, 0xf4); // fixup records
+    put32(&mut data, h + 0x70, 0xf4); // import modules
+    put32(&mut data, h + 0x78, 0xf4); // import procedures
+    put32(&mut data, h + 0x80, 0x140); // data pages, relative to the bound image
+
+    for (i, value) in [0x10, 0x10000, 0x0005, 1, 1, 0].iter().enumerate() {
+        put32(&mut data, h + 0xc4 + i * 4, *value);
+    }
+    put(&mut data, h + 0xdc, &[0, 0, 1, 0]);
+    put(&mut data, h + 0xe0, b"\x04DEMO\0\0");
+    put(&mut data, 0x1a0, &[0xc3; 0x10]);
+    data
+}
+
+#[test]
+fn lx_library_parse() {
+    let data = lx_library();
+    assert_eq!(FileKind::parse(&*data).unwrap(), FileKind::Lx);
+    let file = object::File::parse(&*data).unwrap();
+    assert_eq!(file.format(), BinaryFormat::Lx);
+    assert_eq!(file.kind(), ObjectKind::Dynamic);
+    assert_eq!(file.entry(), 0x10004);
+
+    let sections = file.sections().collect::<Vec<_>>();
+    assert_eq!(sections.len(), 2);
+    assert_eq!(sections[0].name(), Ok("CODE"));
+    assert_eq!(sections[0].kind(), SectionKind::Text);
+    assert_eq!(sections[0].address(), 0x10000);
+    assert_eq!(sections[0].file_range(), Some((0x200, 0x10)));
+    assert_eq!(sections[0].data(), Ok(&[0x90; 0x10][..]));
+    assert_eq!(sections[1].name(), Ok("DATA"));
+    assert_eq!(sections[1].kind(), SectionKind::Data);
+    assert_eq!(sections[1].size(), 0x2000);
+    assert_eq!(sections[1].file_range(), None);
+    assert!(sections[1].data().is_err());
+
+    let symbols = file.symbols().collect::<Vec<_>>();
+    assert_eq!(symbols.len(), 4);
+    assert_eq!(symbols[0].name(), Ok("Hello"));
+    assert_eq!(
+        symbols[0].section(),
+        SymbolSection::Section(SectionIndex(1))
+    );
+    assert_eq!(symbols[0].address(), 0x10004);
+    assert_eq!(symbols[0].kind(), SymbolKind::Text);
+    assert_eq!(symbols[1].name(), Ok("Other"));
+    assert_eq!(symbols[1].address(), 0x10008);
+    assert_eq!(symbols[2].name(), Ok("DosExit"));
+    assert!(symbols[2].is_undefined());
+    assert_eq!(symbols[3].name(), Ok(""));
+    assert!(symbols[3].is_undefined());
+
+    assert_eq!(file.exports().unwrap().len(), 2);
+    let imports = file.imports().unwrap();
+    assert_eq!(imports.len(), 1);
+    assert_eq!(imports[0].library(), b"DOSCALLS");
+    assert_eq!(imports[0].name(), b"DosExit");
+
+    let summary = sections[0]
+        .relocations()
+        .map(|(offset, r)| (offset, r.kind(), r.size(), r.target(), r.addend()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        [
+            (
+                4,
+                RelocationKind::Absolute,
+                32,
+                RelocationTarget::Section(SectionIndex(2)),
+                0x10
+            ),
+            (
+                8,
+                RelocationKind::Relative,
+                32,
+                RelocationTarget::Symbol(SymbolIndex(2)),
+                -4
+            ),
+            (
+                12,
+                RelocationKind::Relative,
+                32,
+                RelocationTarget::Symbol(SymbolIndex(2)),
+                -4
+            ),
+            (
+                0x14,
+                RelocationKind::Absolute,
+                32,
+                RelocationTarget::Symbol(SymbolIndex(3)),
+                2
+            ),
+            (
+                0x18,
+                RelocationKind::Absolute,
+                32,
+                RelocationTarget::Section(SectionIndex(1)),
+                8
+            ),
+        ]
+    );
+
+    let file = LxFile::<&[u8]>::parse(&*data).unwrap();
+    assert!(!file.is_le());
+    assert_eq!(file.module_name(), Some(&b"DEMO"[..]));
+    assert_eq!(file.description(), Some(&b"desc"[..]));
+    assert_eq!(file.import_modules(), [&b"DOSCALLS"[..]]);
+    assert_eq!(file.entries().len(), 3);
+    assert_eq!(file.entries()[2].kind, lx::ENTRY_FORWARDER);
+    assert_eq!(file.pages()[1].offset, 0x210);
+    assert_eq!(file.page_fixups(0).unwrap().len(), 5);
+    assert_eq!(
+        file.import_list()[1],
+        LxImport {
+            module: b"DOSCALLS",
+            name: LxImportName::Ordinal(5),
+        }
+    );
+    let object = file.object_data(SectionIndex(2)).unwrap();
+    assert_eq!(object.len(), 0x2000);
+    assert_eq!(&object[..10], b"abababab\0\0");
+}
+
+#[test]
+fn le_bound_parse() {
+    let data = le_bound_program();
+    assert_eq!(FileKind::parse(&*data).unwrap(), FileKind::Lx);
+    let file = object::File::parse(&*data).unwrap();
+    assert_eq!(file.kind(), ObjectKind::Executable);
+    assert_eq!(file.entry(), 0x10000);
+    let section = file.section_by_index(SectionIndex(1)).unwrap();
+    assert_eq!(section.kind(), SectionKind::Text);
+    assert_eq!(section.file_range(), Some((0x1a0, 0x10)));
+    assert_eq!(section.data(), Ok(&[0xc3; 0x10][..]));
+
+    let file = LxFile::<&[u8]>::parse(&*data).unwrap();
+    assert!(file.is_le());
+    assert_eq!(file.base_offset(), 0x60);
+    assert_eq!(file.header_offset(), 0xa0);
+    assert_eq!(file.module_name(), Some(&b"DEMO"[..]));
+}
+
+#[test]
+fn le_bound_invalid_last_page_size() {
+    let mut data = le_bound_program();
+    put16(&mut data, 0x02, 0xffff);
+    assert!(LxFile::<&[u8]>::parse(&*data).is_err());
+}
+
+#[test]
+fn lx_invalid_page_size() {
+    let mut data = lx_library();
+    put32(&mut data, 0x40 + 0x28, 0xffff_f000);
+    let file = LxFile::<&[u8]>::parse(&*data).unwrap();
+    assert!(file.object_data(SectionIndex(2)).is_err());
+}
@@ -1,5 +1,34 @@
 #![cfg(feature = "read")]
 
+#[cfg(feature = "aout")]
+mod aout;
 mod coff;
 mod elf;
+#[cfg(feature = "gemdos")]
+mod gemdos;
+#[cfg(feature = "goff")]
+mod goff;
+#[cfg(feature = "hunk")]
+mod hunk;
+#[cfg(feature = "ihex")]
+mod ihex;
+#[cfg(feature = "lx")]
+mod lx;
 mod macho;
+#[cfg(feature = "ne")]
+mod ne;
+#[cfg(feature = "omf")]
+mod omf;
+#[cfg(feature = "pef")]
+mod pef;
+#[cfg(feature = "pharlap")]
+mod pharlap;
+#[cfg(feature = "srec")]
+mod srec;
+#[cfg(feature = "ticoff")]
+mod ticoff;
+#[cfg(feature = "xbe")]
+mod xbe;
+
+#[cfg(any(feature = "aout", feature = "lx", feature = "ne", feature = "pharlap"))]
+mod util;
@@ -0,0 +1,213 @@
+use object::read::ne::{NeFile, NeImport, NeImportName, NeResource, NeResourceName};
+use object::{
+    BinaryFormat, FileKind, Object, ObjectKind, ObjectSection, ObjectSymbol, RelocationKind,
+    RelocationTarget, SectionIndex, SectionKind, SymbolIndex, SymbolKind, SymbolScope,
+    SymbolSection,
+};
+
+use super::util::{put, put16};
+
+/// A library with a code segment, a moveable data segment, two named
+/// entry points, two imports from `KERNEL`, and a resource.
+fn ne_library() -> Vec<u8> {
+    let mut data = vec![0; 0x170];
+    put(&mut data, 0, b"MZ");
+    put16(&mut data, 0x3c, 0x40);
+
+    // NE header, with table offsets relative to it.
+    let h = 0x40;
+    put(&mut data, h, b"NE");
+    put16(&mut data, h + 0x04, 0x8a); // entry table
+    put16(&mut data, h + 0x06, 14);
+    put16(&mut data, h + 0x0c, 0x8001); // library, single data
+    put16(&mut data, h + 0x14, 2); // IP
+    put16(&mut data, h + 0x16, 1); // CS
+    put16(&mut data, h + 0x1c, 2); // segments
+    put16(&mut data, h + 0x1e, 1); // module references
+    put16(&mut data, h + 0x20, 20); // non-resident names size
+    put16(&mut data, h + 0x22, 0x40); // segment table
+    put16(&mut data, h + 0x24, 0x50); // resource table
+    put16(&mut data, h + 0x26, 0x68); // resident names
+    put16(&mut data, h + 0x28, 0x78); // module references
+    put16(&mut data, h + 0x2a, 0x7a); // imported names
+    put16(&mut data, h + 0x2c, 0xd8); // non-resident names (file offset)
+    put16(&mut data, h + 0x32, 4); // alignment shift
+    data[h + 0x36] = 2; // Windows
+
+    // Segment table: code with relocations, and moveable data.
+    put(
+        &mut data,
+        h + 0x40,
+        &[0x10, 0, 0x20, 0, 0x00, 0x01, 0x20, 0],
+    );
+    put(
+        &mut data,
+        h + 0x48,
+        &[0x15, 0, 0x04, 0, 0x11, 0x00, 0x00, 0x01],
+    );
+
+    // Resource table: RT_RCDATA with ID 1.
+    put16(&mut data, h + 0x50, 4);
+    put(&mut data, h + 0x52, &[0x0a, 0x80, 1, 0, 0, 0, 0, 0]);
+    put(
+        &mut data,
+        h + 0x5a,
+        &[0x16, 0, 1, 0, 0x30, 0, 0x01, 0x80, 0, 0, 0, 0],
+    );
+
+    // Resident names, module references and imported names.
+    put(&mut data, h + 0x68, b"\x04DEMO\0\0\x05Hello\x01\0\0");
+    put16(&mut data, h + 0x78, 1);
+    put(&mut data, h + 0x7a, b"\0\x06KERNEL\x07GetTick");
+
+    // Entry table: ordinal 1 in fixed segment 1, ordinal 2 in moveable segment 2.
+    put(&mut data, h + 0x8a, &[1, 1, 1, 0x02, 0x00]);
+    put(
+        &mut data,
+        h + 0x8f,
+        &[1, 0xff, 1, 0xcd, 0x3f, 2, 0x04, 0x00, 0],
+    );
+
+    // Non-resident names.
+    put(&mut data, 0xd8, b"\x09Demo desc\0\0\x04Data\x02\0\0");
+
+    // Code segment, with a chain of two locations for `GetTick`.
+    put16(&mut data, 0x106, 0x000a);
+    put16(&mut data, 0x10a, 0xffff);
+    put16(&mut data, 0x120, 4);
+    // Import by name, non-additive.
+    put(&mut data, 0x122, &[5, 2, 0x06, 0, 1, 0, 8, 0]);
+    // Selector of segment 2, additive.
+    put(&mut data, 0x12a, &[2, 4, 0x0c, 0, 2, 0, 0, 0]);
+    // Far pointer to ordinal 5, additive.
+    put(&mut data, 0x132, &[3, 5, 0x10, 0, 1, 0, 5, 0]);
+    // Offset of entry ordinal 2 in a moveable segment, additive.
+    put(&mut data, 0x13a, &[5, 4, 0x14, 0, 0xff, 0, 2, 0]);
+
+    // Data segment and resource data.
+    put(&mut data, 0x150, &[1, 2, 3, 4]);
+    put(&mut data, 0x160, b"resource data!!\0");
+    data
+}
+
+#[test]
+fn ne_library_parse() {
+    let data = ne_library();
+    assert_eq!(FileKind::parse(&*data).unwrap(), FileKind::Ne);
+    let file = object::File::parse(&*data).unwrap();
+    assert_eq!(file.format(), BinaryFormat::Ne);
+    assert_eq!(file.kind(), ObjectKind::Dynamic);
+    assert_eq!(file.entry(), 2);
+
+    let sections = file.sections().collect::<Vec<_>>();
+    assert_eq!(sections.len(), 2);
+    assert_eq!(sections[0].name(), Ok("CODE"));
+    assert_eq!(sections[0].kind(), SectionKind::Text);
+    assert_eq!(sections[0].file_range(), Some((0x100, 0x20)));
+    assert_eq!(sections[1].name(), Ok("DATA"));
+    assert_eq!(sections[1].kind(), SectionKind::Data);
+    assert_eq!(sections[1].size(), 0x100);
+    assert_eq!(sections[1].data(), Ok(&[1, 2, 3, 4][..]));
+
+    let symbols = file.symbols().collect::<Vec<_>>();
+    assert_eq!(symbols.len(), 4);
+    assert_eq!(symbols[0].name(), Ok("Hello"));
+    assert_eq!(
+        symbols[0].section(),
+        SymbolSection::Section(SectionIndex(1))
+    );
+    assert_eq!(symbols[0].address(), 2);
+    assert_eq!(symbols[0].kind(), SymbolKind::Text);
+    assert_eq!(symbols[0].scope(), SymbolScope::Dynamic);
+    assert_eq!(symbols[1].name(), Ok("Data"));
+    assert_eq!(
+        symbols[1].section(),
+        SymbolSection::Section(SectionIndex(2))
+    );
+    assert_eq!(symbols[1].address(), 4);
+    assert_eq!(symbols[1].kind(), SymbolKind::Data);
+    assert_eq!(symbols[2].name(), Ok("GetTick"));
+    assert!(symbols[2].is_undefined());
+    assert_eq!(symbols[3].name(), Ok(""));
+    assert!(symbols[3].is_undefined());
+
+    let exports = file.exports().unwrap();
+    assert_eq!(exports.len(), 2);
+    assert_eq!(exports[0].name(), b"Hello");
+    assert_eq!(exports[1].address(), 4);
+    let imports = file.imports().unwrap();
+    assert_eq!(imports.len(), 1);
+    assert_eq!(imports[0].library(), b"KERNEL");
+    assert_eq!(imports[0].name(), b"GetTick");
+
+    let relocations = sections[0].relocations().collect::<Vec<_>>();
+    let summary = relocations
+        .iter()
+        .map(|(offset, r)| (*offset, r.kind(), r.size(), r.target(), r.addend()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        [
+            (
+                6,
+                RelocationKind::SegmentRelative,
+                16,
+                RelocationTarget::Symbol(SymbolIndex(2)),
+                0
+            ),
+            (
+                10,
+                RelocationKind::SegmentRelative,
+                16,
+                RelocationTarget::Symbol(SymbolIndex(2)),
+                0
+            ),
+            (
+                12,
+                RelocationKind::SectionIndex,
+                16,
+                RelocationTarget::Section(SectionIndex(2)),
+                0
+            ),
+            (
+                16,
+                RelocationKind::Unknown,
+                32,
+                RelocationTarget::Symbol(SymbolIndex(3)),
+                0
+            ),
+            (
+                20,
+                RelocationKind::SegmentRelative,
+                16,
+                RelocationTarget::Section(SectionIndex(2)),
+                4
+            ),
+        ]
+    );
+    assert!(!relocations[0].1.has_implicit_addend());
+    assert!(relocations[2].1.has_implicit_addend());
+
+    let file = NeFile::<&[u8]>::parse(&*data).unwrap();
+    assert_eq!(file.module_name(), Some(&b"DEMO"[..]));
+    assert_eq!(file.description(), Some(&b"Demo desc"[..]));
+    assert_eq!(file.module_references(), [&b"KERNEL"[..]]);
+    assert_eq!(file.entries().len(), 2);
+    assert!(file.entries()[1].moveable);
+    assert_eq!(
+        file.import_list()[1],
+        NeImport {
+            module: b"KERNEL",
+            name: NeImportName::Ordinal(5),
+        }
+    );
+    assert_eq!(
+        file.resources().unwrap(),
+        [NeResource {
+            type_id: NeResourceName::Id(10),
+            name: NeResourceName::Id(1),
+            flags: 0x30,
+            data: b"resource data!!\0",
+        }]
+    );
+}
@@ -1,20 +1,2393 @@
-#[cfg(test)]
-mod tests {
-    use object::{Object, ObjectSection};
+use std::borrow::Cow;
 
-    #[test]
-    fn test_parse_simple_omf() {
-        let raw = include_bytes!("../../testfiles/omf/simple.obj");
-        let obj = object::read::File::parse(raw.as_ref()).expect("parse");
+use object::read::omf::{
+    OmfCodeViewSymbolKind, OmfCodeViewTypeKind, OmfCommentKind, OmfDebugFormat, OmfDiagnosticKind,
+    OmfFile, OmfFixupFrame, OmfFixupTarget, OmfFloatKind, OmfImportEntry, OmfInstructionSet,
+    OmfLibrary, OmfModelKind, OmfModuleKind, OmfParseOptions, OmfRecordType,
+};
+use object::{
+    Object, ObjectSection, ObjectSymbol, SectionIndex, SectionKind, SymbolKind, SymbolScope,
+};
 
-        let symbols = obj.symbols().collect::<Vec<_>>();
-        assert!(!symbols.is_empty(), "Should parse at least one symbol");
+/// Append an OMF record with a zero checksum.
+fn record(out: &mut Vec<u8>, kind: u8, body: &[u8]) {
+    out.push(kind);
+    out.extend_from_slice(&(body.len() as u16 + 1).to_le_bytes());
+    out.extend_from_slice(body);
+    out.push(0);
+}
+
+fn lnames(out: &mut Vec<u8>, names: &[&str]) {
+    let mut body = Vec::new();
+    for name in names {
+        body.push(name.len() as u8);
+        body.extend_from_slice(name.as_bytes());
+    }
+    record(out, 0x96, &body);
+}
+
+/// A module with `_TEXT` (word aligned, 0x13 bytes), `_DATA` (para aligned,
+/// 0x20 bytes), an absolute segment at 0xB800:0000, and `DGROUP` containing `_DATA`.
+fn layout_module() -> Vec<u8> {
+    let mut out = Vec::new();
+    record(&mut out, 0x80, b"\x06layout");
+    lnames(
+        &mut out,
+        &["", "_TEXT", "CODE", "_DATA", "DATA", "DGROUP", "VIDEO"],
+    );
+    // SEGDEF: ACBP, length, name, class, overlay.
+    record(&mut out, 0x98, &[0x48, 0x13, 0x00, 2, 3, 1]);
+    record(&mut out, 0x98, &[0x68, 0x20, 0x00, 4, 5, 1]);
+    record(
+        &mut out,
+        0x98,
+        &[0x00, 0x00, 0xB8, 0x00, 0x00, 0x10, 7, 5, 1],
+    );
+    // GRPDEF: DGROUP = { _DATA }
+    record(&mut out, 0x9A, &[6, 0xFF, 2]);
+    record(&mut out, 0x8A, &[0x00]);
+    out
+}
+
+#[test]
+fn omf_parse_segments() {
+    let data = layout_module();
+    let file = object::File::parse(&*data).unwrap();
+    assert_eq!(file.format(), object::BinaryFormat::Omf);
+
+    let sections = file.sections().collect::<Vec<_>>();
+    assert_eq!(sections.len(), 3);
+    assert_eq!(sections[0].name(), Ok("_TEXT"));
+    assert_eq!(sections[0].kind(), SectionKind::Text);
+    assert_eq!(sections[0].size(), 0x13);
+    assert_eq!(sections[0].align(), 2);
+    assert_eq!(sections[1].name(), Ok("_DATA"));
+    assert_eq!(sections[1].kind(), SectionKind::Data);
+    assert_eq!(sections[1].align(), 16);
+}
+
+#[test]
+fn omf_sequential_layout() {
+    let data = layout_module();
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    let layout = file.sequential_layout(0x1001);
+
+    assert_eq!(layout.base(SectionIndex(1)), Some(0x1002));
+    assert_eq!(layout.base(SectionIndex(2)), Some(0x1020));
+    assert_eq!(layout.base(SectionIndex(3)), Some(0xB8000));
+    assert_eq!(layout.base(SectionIndex(4)), None);
+
+    assert_eq!(
+        file.linear_address(&layout, SectionIndex(2), 4),
+        Some(0x1024)
+    );
+    assert_eq!(file.linear_address(&layout, SectionIndex(0), 0), None);
+    assert_eq!(file.linear_address(&layout, SectionIndex(4), 0), None);
+
+    assert_eq!(
+        file.segment_offset(&layout, 0x1014),
+        Some((SectionIndex(1), 0x12))
+    );
+    // Alignment padding between segments belongs to no segment.
+    assert_eq!(file.segment_offset(&layout, 0x1015), None);
+    assert_eq!(
+        file.segment_offset(&layout, 0xB8004),
+        Some((SectionIndex(3), 4))
+    );
+}
+
+#[test]
+fn test_parse_simple_omf() {
+    let raw = std::fs::read("testfiles/omf/simple.obj").expect("Could not read simple.obj");
+    let obj = object::read::File::parse(&*raw).expect("parse");
+
+    let symbols = obj.symbols().collect::<Vec<_>>();
+    assert!(!symbols.is_empty(), "Should parse at least one symbol");
+
+    let sections = obj.sections().collect::<Vec<_>>();
+    assert!(!sections.is_empty(), "Should parse at least one section");
+
+    for sec in &sections {
+        println!("Section: {} size={}", sec.name().unwrap_or("?"), sec.size());
+    }
+}
+
+#[test]
+fn omf_group_frame() {
+    let data = layout_module();
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+
+    let mut layout = object::read::omf::OmfSegmentLayout::new();
+    layout.set_base(SectionIndex(1), 0x10000);
+    layout.set_base(SectionIndex(2), 0x12345);
+
+    assert_eq!(file.group_frame(&layout, 1), Some(0x12340));
+    assert_eq!(
+        file.group_offset(&layout, 1, SectionIndex(2), 0x10),
+        Some(0x15)
+    );
+    assert_eq!(file.group_frame(&layout, 0), None);
+    assert_eq!(file.group_frame(&layout, 2), None);
+}
+
+#[test]
+fn omf_group_components() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x05group");
+    lnames(&mut data, &["", "_TEXT", "CODE", "_DATA", "DGROUP"]);
+    record(&mut data, 0x98, &[0x48, 0x10, 0x00, 2, 3, 1]);
+    record(&mut data, 0x98, &[0x48, 0x10, 0x00, 4, 3, 1]);
+    // Obsolete external, name and absolute descriptors around the segments.
+    record(
+        &mut data,
+        0x9A,
+        &[
+            5, 0xFE, 1, 0xFF, 1, 0xFD, 2, 3, 1, 0xFA, 0x00, 0xB8, 0x00, 0xFF, 2,
+        ],
+    );
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.groups.len(), 1);
+    assert_eq!(file.name(file.groups[0].name), b"DGROUP");
+    assert_eq!(file.groups[0].segment_indices, [1, 2]);
+    assert!(file.diagnostics.is_empty());
+}
+
+#[test]
+fn omf_pubdef_base() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x06pubdef");
+    lnames(&mut data, &["", "_DATA", "DATA", "DGROUP"]);
+    record(&mut data, 0x98, &[0x68, 0x20, 0x00, 2, 3, 1]);
+    record(&mut data, 0x9A, &[4, 0xFF, 1]);
+    // Group-based public in _DATA.
+    record(&mut data, 0x90, b"\x01\x01\x04_var\x10\x00\x00");
+    // Absolute public at 0040:0017.
+    record(&mut data, 0x90, b"\x00\x00\x40\x00\x07_kbflag\x17\x00\x00");
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    let mut layout = object::read::omf::OmfSegmentLayout::new();
+    layout.set_base(SectionIndex(1), 0x12345);
+
+    let var = file.symbol_by_name("_var").unwrap();
+    assert_eq!(var.group, 1);
+    assert_eq!(var.section_index(), Some(SectionIndex(1)));
+    assert_eq!(file.symbol_address(&layout, &var), Some(0x12355));
+    assert_eq!(
+        file.group_offset(&layout, var.group, SectionIndex(1), var.offset),
+        Some(0x15)
+    );
+
+    let kbflag = file.symbol_by_name("_kbflag").unwrap();
+    assert_eq!(kbflag.section(), object::SymbolSection::Absolute);
+    assert_eq!(kbflag.address(), 0x417);
+    assert_eq!(file.symbol_address(&layout, &kbflag), Some(0x417));
+}
+
+#[test]
+fn omf_comdat_absolute_base() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x06comdat");
+    lnames(&mut data, &["", "_DATA", "DATA", "DGROUP", "_abs"]);
+    record(&mut data, 0x98, &[0x68, 0x20, 0x00, 2, 3, 1]);
+    record(&mut data, 0x9A, &[4, 0xFF, 1]);
+    // Explicitly allocated COMDAT with a group, no segment, and frame 0x0040.
+    record(
+        &mut data,
+        0xC2,
+        &[0, 0, 0, 0x10, 0x00, 0, 1, 0, 0x40, 0x00, 5, 0xC3],
+    );
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert!(file.diagnostics.is_empty());
+    assert_eq!(file.comdats.len(), 1);
+    let comdat = &file.comdats[0];
+    assert_eq!(comdat.name, b"_abs");
+    assert_eq!(comdat.segment_index, 0);
+    assert_eq!(comdat.offset, 0x10);
+    assert_eq!(comdat.data, Some(&[0xC3][..]));
+}
+
+#[test]
+fn omf_dosseg() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x06dosseg");
+    // COMENT: attributes, class 0x9E (DOSSEG).
+    record(&mut data, 0x88, &[0x80, 0x9E]);
+    lnames(
+        &mut data,
+        &[
+            "", "DGROUP", "_TEXT", "CODE", "_BSS", "BSS", "STACK", "_DATA", "DATA", "FAR_DATA",
+            "BEGDATA",
+        ],
+    );
+    record(&mut data, 0x98, &[0x68, 0x10, 0x00, 5, 6, 1]);
+    record(&mut data, 0x98, &[0x74, 0x10, 0x00, 7, 7, 1]);
+    record(&mut data, 0x98, &[0x68, 0x10, 0x00, 8, 9, 1]);
+    record(&mut data, 0x98, &[0x48, 0x10, 0x00, 3, 4, 1]);
+    record(&mut data, 0x98, &[0x68, 0x10, 0x00, 10, 10, 1]);
+    record(&mut data, 0x98, &[0x68, 0x10, 0x00, 11, 11, 1]);
+    record(&mut data, 0x9A, &[2, 0xFF, 1, 0xFF, 2, 0xFF, 3, 0xFF, 6]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert!(file.dosseg);
+    assert_eq!(
+        file.dosseg_order(),
+        [4, 5, 6, 3, 1, 2].map(SectionIndex).to_vec()
+    );
+
+    let data = layout_module();
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert!(!file.dosseg);
+}
+
+#[test]
+fn omf_link_map() {
+    use object::read::omf::{OmfLinkMap, OmfMapAddress, OmfSegmentLayout};
+
+    let mut main = Vec::new();
+    record(&mut main, 0x80, b"\x04main");
+    lnames(&mut main, &["", "_TEXT", "CODE", "_DATA", "DATA", "DGROUP"]);
+    record(&mut main, 0x98, &[0x48, 0x10, 0x00, 2, 3, 1]);
+    record(&mut main, 0x98, &[0x68, 0x20, 0x00, 4, 5, 1]);
+    record(&mut main, 0x9A, &[6, 0xFF, 2]);
+    record(&mut main, 0x90, b"\x00\x01\x05_main\x04\x00\x00");
+    // Group-based public in _DATA.
+    record(&mut main, 0x90, b"\x01\x02\x04_var\x10\x00\x00");
+    // Absolute public at 0040:0017.
+    record(&mut main, 0x90, b"\x00\x00\x40\x00\x07_kbflag\x17\x00\x00");
+    // MODEND: main module, start address at _TEXT:0004.
+    record(&mut main, 0x8A, &[0xC1, 0x50, 0x01, 0x04, 0x00]);
+    let main = OmfFile::<&[u8]>::parse(&main).unwrap();
+
+    let mut helper = Vec::new();
+    record(&mut helper, 0x80, b"\x06helper");
+    lnames(&mut helper, &["", "_TEXT", "CODE"]);
+    record(&mut helper, 0x98, &[0x48, 0x08, 0x00, 2, 3, 1]);
+    record(&mut helper, 0x90, b"\x00\x01\x07_helper\x00\x00\x00");
+    record(&mut helper, 0x8A, &[0x00]);
+    let helper = OmfFile::<&[u8]>::parse(&helper).unwrap();
+
+    let mut main_layout = OmfSegmentLayout::new();
+    main_layout.set_base(SectionIndex(1), 0x1000);
+    main_layout.set_base(SectionIndex(2), 0x1020);
+    let mut helper_layout = OmfSegmentLayout::new();
+    helper_layout.set_base(SectionIndex(1), 0x1010);
+
+    let map = OmfLinkMap::new([(&main, &main_layout), (&helper, &helper_layout)]);
+    assert_eq!(map.segments.len(), 3);
+    assert_eq!(map.segments[1].module, Some("helper"));
+    assert_eq!(map.groups.len(), 1);
+    assert_eq!(map.groups[0].frame, 0x1020);
+    let var = map.publics.iter().find(|p| p.name == b"_var").unwrap();
+    assert_eq!(
+        var.address,
+        OmfMapAddress {
+            frame: 0x1020,
+            address: 0x1030
+        }
+    );
+    assert_eq!(var.address.offset(), 0x10);
+    assert_eq!(
+        map.entry,
+        Some(OmfMapAddress {
+            frame: 0x1000,
+            address: 0x1004
+        })
+    );
+
+    assert_eq!(
+        map.to_string(),
+        " Start  Stop   Length Name                   Class
+ 01000H 01017H 00018H _TEXT                  CODE
+ 01020H 0103FH 00020H _DATA                  DATA
+
+ Origin   Group
+ 0102:0   DGROUP
+
+  Address         Publics by Name
+
+ 0101:0000       _helper
+ 0000:0417  Abs  _kbflag
+ 0100:0004       _main
+ 0102:0010       _var
+
+  Address         Publics by Value
+
+ 0000:0417  Abs  _kbflag
+ 0100:0004       _main
+ 0101:0000       _helper
+ 0102:0010       _var
+
+Program entry point at 0100:0004
+"
+    );
+}
+
+#[test]
+fn omf_image() {
+    use object::read::omf::{OmfImage, OmfSegmentLayout};
+
+    // A tiny model program, with `_TEXT` at ORG 100h.
+    let mut main = Vec::new();
+    record(&mut main, 0x80, b"\x04main");
+    lnames(&mut main, &["", "_TEXT", "CODE", "_DATA", "DATA", "DGROUP"]);
+    record(&mut main, 0x98, &[0x48, 0x0C, 0x01, 2, 3, 1]);
+    record(&mut main, 0x98, &[0x48, 0x04, 0x00, 4, 5, 1]);
+    record(&mut main, 0x9A, &[6, 0xFF, 1, 0xFF, 2]);
+    record(&mut main, 0x8C, b"\x07_helper\x00");
+    // call _helper; mov ax, offset DGROUP:_DATA; jmp far _TEXT:0100h; ret
+    record(
+        &mut main,
+        0xA0,
+        &[
+            1, 0x00, 0x01, 0xE8, 0x00, 0x00, 0xB8, 0x00, 0x00, 0xEA, 0x00, 0x00, 0x00, 0x00, 0xC3,
+        ],
+    );
+    record(
+        &mut main,
+        0x9C,
+        &[
+            0x84, 0x01, 0x56, 0x01, // Self-relative offset to `_helper`.
+            0xC4, 0x04, 0x14, 0x01, 0x02, // Offset of `_DATA` in DGROUP.
+            0xCC, 0x07, 0x50, 0x01, 0x00, 0x01, // Far pointer to `_TEXT:0100`.
+        ],
+    );
+    record(&mut main, 0xA0, &[2, 0x00, 0x00, b'H', b'i', b'$', 0]);
+    record(&mut main, 0x8A, &[0x00]);
+    let main = OmfFile::<&[u8]>::parse(&main).unwrap();
+
+    let mut helper = Vec::new();
+    record(&mut helper, 0x80, b"\x06helper");
+    lnames(&mut helper, &["", "_TEXT", "CODE"]);
+    record(&mut helper, 0x98, &[0x48, 0x02, 0x00, 2, 3, 1]);
+    record(&mut helper, 0x90, b"\x00\x01\x07_helper\x00\x00\x00");
+    record(&mut helper, 0xA0, &[1, 0x00, 0x00, 0xC3, 0x90]);
+    record(&mut helper, 0x8A, &[0x00]);
+    let helper = OmfFile::<&[u8]>::parse(&helper).unwrap();
+
+    let mut main_layout = OmfSegmentLayout::new();
+    main_layout.set_base(SectionIndex(1), 0);
+    main_layout.set_base(SectionIndex(2), 0x110);
+    let mut helper_layout = OmfSegmentLayout::new();
+    helper_layout.set_base(SectionIndex(1), 0x10C);
+
+    let image = OmfImage::new([(&main, &main_layout), (&helper, &helper_layout)], 0x100).unwrap();
+    assert_eq!(image.origin, 0x100);
+    assert_eq!(
+        image.data,
+        [
+            0xE8, 0x09, 0x00, 0xB8, 0x10, 0x01, 0xEA, 0x00, 0x01, 0x00, 0x00, 0xC3, 0xC3, 0x90,
+            0x00, 0x00, b'H', b'i', b'$', 0,
+        ]
+    );
+
+    assert!(OmfImage::new([(&main, &main_layout)], 0x100).is_err());
+    assert!(OmfImage::new([(&main, &main_layout), (&helper, &helper_layout)], 0x101).is_err());
+}
+
+#[test]
+fn omf_dump() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x04dump");
+    record(&mut data, 0x88, b"\x00\x00TC86");
+    lnames(&mut data, &["", "_TEXT", "CODE", "DGROUP"]);
+    record(&mut data, 0x98, &[0x48, 0x08, 0x00, 2, 3, 1]);
+    record(&mut data, 0x9A, &[4, 0xFF, 1]);
+    record(&mut data, 0x8C, b"\x05_puts\x00");
+    record(&mut data, 0x90, b"\x00\x01\x05_main\x00\x00\x00");
+    record(
+        &mut data,
+        0xA0,
+        &[1, 0x00, 0x00, 0xE8, 0x00, 0x00, 0xB8, 0x00, 0x00, 0xC3],
+    );
+    // FIXUPP: a target thread for `_puts`, a self-relative call through the
+    // thread, and a group-relative offset of `_TEXT`.
+    record(
+        &mut data,
+        0x9C,
+        &[
+            0x08, 0x01, 0x84, 0x01, 0x58, 0x00, 0x00, 0xC4, 0x04, 0x14, 0x01, 0x01,
+        ],
+    );
+    record(&mut data, 0x8A, &[0x00]);
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(
+        file.dump().to_string(),
+        r#"0x0000 THEADR (0x80), length 6
+    Name: "dump"
+0x0009 COMENT (0x88), length 7
+    Class: 0x00
+    Translator: "TC86"
+0x0013 LNAMES (0x96), length 20
+    Name 1: ""
+    Name 2: "_TEXT"
+    Name 3: "CODE"
+    Name 4: "DGROUP"
+0x002A SEGDEF (0x98), length 7
+    Segment: 1
+    Name: "_TEXT" (2)
+    Class: "CODE" (3)
+    Overlay: "" (1)
+    Alignment: word
+    Combine: public
+    Length: 0x8
+    Use32: false
+0x0034 GRPDEF (0x9A), length 4
+    Group: 1
+    Name: "DGROUP" (4)
+    Member: "_TEXT" (1)
+0x003B EXTDEF (0x8C), length 8
+    External 1: "_puts"
+0x0046 PUBDEF (0x90), length 12
+    Segment: "_TEXT" (1)
+    Public: "_main" at 0x0
+0x0055 LEDATA (0xA0), length 11
+    Segment: "_TEXT" (1)
+    Offset: 0x0
+    Size: 0x7
+0x0063 FIXUPP (0x9C), length 13
+    Segment: "_TEXT" (1)
+    Fixup 0x1: self-relative offset16, target external "_puts" (1) + 0x0, frame target
+    Fixup 0x4: offset16, target segment "_TEXT" (1) + 0x0, frame group "DGROUP" (1)
+0x0073 MODEND (0x8A), length 2
+    Main: false
+"#
+    );
+}
+
+#[test]
+fn omf_local_symbols() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x06locals");
+    lnames(&mut data, &["", "_TEXT", "CODE", "pub", "lpub", "lcom"]);
+    record(&mut data, 0x98, &[0x48, 0x10, 0x00, 2, 3, 1]);
+    record(&mut data, 0x90, b"\x00\x01\x03pub\x02\x00\x00");
+    record(&mut data, 0xB6, b"\x00\x01\x04lpub\x04\x00\x00");
+    record(&mut data, 0xB4, b"\x04lext\x00");
+    record(&mut data, 0xB8, b"\x04lcom\x00\x62\x04");
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = object::File::parse(&*data).unwrap();
+    let symbols = file
+        .symbols()
+        .map(|symbol| (symbol.name().unwrap(), symbol.scope()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        symbols,
+        [
+            ("pub", SymbolScope::Linkage),
+            ("lpub", SymbolScope::Compilation),
+            ("lext", SymbolScope::Compilation),
+            ("lcom", SymbolScope::Compilation),
+        ]
+    );
+    let lpub = file.symbol_by_name("lpub").unwrap();
+    assert!(lpub.is_local());
+    assert_eq!(lpub.address(), 4);
+    assert_eq!(lpub.section_index(), Some(SectionIndex(1)));
+}
+
+#[test]
+fn omf_cextdef() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x07cextdef");
+    lnames(&mut data, &["", "_foo", "_bar"]);
+    // CEXTDEF: (name index, type index) pairs.
+    record(&mut data, 0xBC, &[2, 0, 3, 0]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = object::File::parse(&*data).unwrap();
+    let symbols = file.symbols().collect::<Vec<_>>();
+    assert_eq!(symbols.len(), 2);
+    assert_eq!(symbols[0].name(), Ok("_foo"));
+    assert!(symbols[0].is_undefined());
+    assert_eq!(symbols[1].name(), Ok("_bar"));
+    assert!(symbols[1].is_global());
+}
+
+#[test]
+fn omf_fixups() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x06fixups");
+    lnames(&mut data, &["", "_TEXT", "CODE", "DGROUP", "_comm"]);
+    record(&mut data, 0x98, &[0x48, 0x10, 0x00, 2, 3, 1]);
+    record(&mut data, 0x9A, &[4, 0xFF, 1]);
+    // External indices: 1 = _ext1, 2 = _comm, 3 = _ext2
+    record(&mut data, 0x8C, b"\x05_ext1\x00");
+    record(&mut data, 0xB0, b"\x05_comm\x00\x62\x04");
+    record(&mut data, 0x8C, b"\x05_ext2\x00");
+    record(&mut data, 0xA0, &[1, 0x04, 0x00, 0, 0, 0, 0, 0, 0, 0, 0]);
+    record(
+        &mut data,
+        0x9C,
+        &[
+            // THREAD: target thread 1 = T2 external 3.
+            0x09, 3,
+            // FIXUP: segment-relative offset16 at 0x2, F1 group 1, T2 external 2.
+            0xC4, 0x02, 0x16, 1, 2,
+            // FIXUP: self-relative offset16 at 0x4, F5, target thread 1, displacement 0x10.
+            0x84, 0x04, 0x59, 0x10, 0x00,
+            // FIXUP: segment base at 0x6, F0 segment 1, T0 segment 1, no displacement.
+            0xC8, 0x06, 0x04, 1, 1,
+        ],
+    );
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = object::File::parse(&*data).unwrap();
+    let symbol = |target| match target {
+        object::RelocationTarget::Symbol(index) => {
+            file.symbol_by_index(index).unwrap().name().unwrap()
+        }
+        _ => panic!("unexpected target {:?}", target),
+    };
+    let section = file.section_by_index(SectionIndex(1)).unwrap();
+    let relocations = section.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 3);
+
+    let (offset, relocation) = &relocations[0];
+    assert_eq!(*offset, 6);
+    assert_eq!(relocation.kind(), object::RelocationKind::SegmentRelative);
+    assert_eq!(relocation.size(), 16);
+    assert_eq!(symbol(relocation.target()), "_comm");
+    assert_eq!(relocation.addend(), 0);
+
+    let (offset, relocation) = &relocations[1];
+    assert_eq!(*offset, 8);
+    assert_eq!(relocation.kind(), object::RelocationKind::Relative);
+    assert_eq!(symbol(relocation.target()), "_ext2");
+    assert_eq!(relocation.addend(), 0x10);
+
+    let (offset, relocation) = &relocations[2];
+    assert_eq!(*offset, 10);
+    assert_eq!(relocation.kind(), object::RelocationKind::SectionIndex);
+    assert_eq!(
+        relocation.target(),
+        object::RelocationTarget::Section(SectionIndex(1))
+    );
+
+    let file = OmfFile::parse(&*data).unwrap();
+    let fixups = &file.segments[0].fixups;
+    assert_eq!(fixups.len(), 3);
+    let fixup = fixups.get(0).unwrap();
+    assert_eq!(fixup.offset, 6);
+    assert_eq!(fixup.target, OmfFixupTarget::Symbol(2));
+    assert_eq!(fixup.frame, Some(OmfFixupFrame::Group(1)));
+    let fixup = fixups.iter().nth(1).unwrap();
+    assert_eq!(fixup.target, OmfFixupTarget::Symbol(3));
+    assert_eq!(fixup.frame, Some(OmfFixupFrame::Target));
+    assert_eq!(fixup.addend, 0x10);
+}
+
+#[test]
+fn omf_fixup_threads_before_data() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x06thread");
+    lnames(&mut data, &["", "_TEXT", "CODE"]);
+    record(&mut data, 0x98, &[0x48, 0x10, 0x00, 2, 3, 1]);
+    // THREAD: target thread 0 = T0 segment 1.
+    record(&mut data, 0x9C, &[0x00, 0x01]);
+    record(&mut data, 0xA0, &[1, 0x00, 0x00, 0, 0]);
+    // FIXUP: segment-relative offset16 at 0, F5, target thread 0, no displacement.
+    record(&mut data, 0x9C, &[0xC4, 0x00, 0x5C]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = object::File::parse(&*data).unwrap();
+    let section = file.section_by_index(SectionIndex(1)).unwrap();
+    let relocations = section.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 1);
+    assert_eq!(relocations[0].0, 0);
+    assert_eq!(
+        relocations[0].1.target(),
+        object::RelocationTarget::Section(SectionIndex(1))
+    );
+}
+
+#[test]
+fn omf_pharlap() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x07pharlap");
+    record(&mut data, 0x88, b"\x80\xAA80386");
+    lnames(&mut data, &["", "_TEXT", "CODE"]);
+    // SEGDEF with a 32-bit length and a trailing access byte.
+    record(&mut data, 0x98, &[0x49, 0x10, 0, 0, 0, 2, 3, 1, 0x04]);
+    record(&mut data, 0x90, b"\x00\x01\x05_main\x08\x00\x00\x00\x00");
+    // LEDATA with a 32-bit offset.
+    record(&mut data, 0xA0, &[1, 0x08, 0, 0, 0, 0xB8, 0, 0, 0, 0, 0xC3]);
+    // FIXUPP: 32-bit segment-relative offset at 1, target segment 1, displacement 0x10.
+    record(&mut data, 0x9C, &[0xD4, 0x01, 0x50, 1, 0x10, 0, 0, 0]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert!(file.pharlap);
+    let text = file.section_by_index(SectionIndex(1)).unwrap();
+    assert_eq!(text.size(), 0x10);
+    assert_eq!(file.symbol_by_name("_main").unwrap().address(), 8);
+
+    let relocations = text.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 1);
+    let (offset, relocation) = &relocations[0];
+    assert_eq!(*offset, 9);
+    assert_eq!(relocation.kind(), object::RelocationKind::SegmentRelative);
+    assert_eq!(relocation.size(), 32);
+    assert_eq!(relocation.addend(), 0x10);
+}
+
+/// Append a CodeView record with the given type and data.
+fn codeview_record(out: &mut Vec<u8>, kind: u16, data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u16 + 2).to_le_bytes());
+    out.extend_from_slice(&kind.to_le_bytes());
+    out.extend_from_slice(data);
+}
+
+/// Append LEDATA records for `data` to segment 1, split into two records.
+fn split_ledata(out: &mut Vec<u8>, data: &[u8]) {
+    let (first, second) = data.split_at(data.len() / 2);
+    for (offset, chunk) in [(0, first), (first.len(), second)] {
+        let mut body = vec![1];
+        body.extend_from_slice(&(offset as u16).to_le_bytes());
+        body.extend_from_slice(chunk);
+        record(out, 0xA0, &body);
+    }
+}
+
+#[test]
+fn omf_split_section_data() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x05split");
+    lnames(&mut data, &["", "_TEXT", "CODE"]);
+    record(&mut data, 0x98, &[0x48, 0x04, 0x00, 2, 3, 1]);
+    split_ledata(&mut data, &[1, 2, 3, 4]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = object::File::parse(&*data).unwrap();
+    let section = file.section_by_index(SectionIndex(1)).unwrap();
+    assert!(section.data().is_err());
+    assert!(section.data_range(0, 2).is_err());
+    assert!(section.compressed_data().is_err());
+    assert_eq!(&*section.uncompressed_data().unwrap(), [1, 2, 3, 4]);
+}
+
+#[test]
+fn omf_codeview_symbols() {
+    let mut symbols = 1u32.to_le_bytes().to_vec();
+    let mut proc = Vec::new();
+    proc.extend_from_slice(&[0; 4]);
+    proc.extend_from_slice(&49u32.to_le_bytes());
+    proc.extend_from_slice(&[0; 4]);
+    // Length, debug start, debug end, offset, segment, type, flags.
+    for value in [0x20u16, 3, 0x1F, 0x10, 0, 0x74] {
+        proc.extend_from_slice(&value.to_le_bytes());
+    }
+    proc.push(0);
+    proc.extend_from_slice(b"\x05_main");
+    codeview_record(&mut symbols, 0x0105, &proc);
+    codeview_record(&mut symbols, 0x0100, b"\xFE\xFF\x74\x00\x01x");
+    codeview_record(&mut symbols, 0x0006, &[]);
+
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x02cv");
+    lnames(&mut data, &["", "$$SYMBOLS", "DEBSYM"]);
+    record(&mut data, 0x98, &[0x28, symbols.len() as u8, 0, 2, 3, 1]);
+    split_ledata(&mut data, &symbols);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    let symbols = file.codeview_symbols().unwrap().unwrap();
+    let symbols = symbols.iter().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(symbols.len(), 3);
+    assert_eq!(symbols[0].name(), Some("_main"));
+    assert_eq!(
+        symbols[0].kind,
+        OmfCodeViewSymbolKind::Procedure {
+            name: b"_main",
+            global: true,
+            offset: 0x10,
+            segment: 0,
+            length: 0x20,
+            type_index: 0x74,
+            end: 49,
+        }
+    );
+    assert_eq!(
+        symbols[1].kind,
+        OmfCodeViewSymbolKind::FrameRelative {
+            name: b"x",
+            offset: -2,
+            type_index: 0x74,
+        }
+    );
+    assert_eq!(symbols[2].offset, 49);
+    assert_eq!(symbols[2].kind, OmfCodeViewSymbolKind::End);
+
+    let data = layout_module();
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert!(file.codeview_symbols().unwrap().is_none());
+}
+
+#[test]
+fn omf_comdef_size() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x06comdef");
+    // COMDEF: a far array of 0x10 elements of 4 bytes, a near variable with a
+    // 3-byte length, and a Borland communal in segment 1.
+    record(
+        &mut data,
+        0xB0,
+        b"\x04_buf\x00\x61\x10\x04\x04_big\x00\x62\x84\x00\x00\x01\x04_seg\x00\x01\x08",
+    );
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    let symbol = file.symbol_by_name("_buf").unwrap();
+    assert!(symbol.is_common());
+    assert!(!symbol.is_undefined());
+    assert_eq!(symbol.section(), object::SymbolSection::Common);
+    assert_eq!(symbol.size(), 0x40);
+    assert!(file.commons[0].is_far);
+    assert_eq!(file.commons[0].elem_count, 0x10);
+
+    assert_eq!(file.symbol_by_name("_big").unwrap().size(), 0x10000);
+    assert!(!file.commons[1].is_far);
+
+    assert_eq!(file.symbol_by_name("_seg").unwrap().size(), 8);
+    assert_eq!(file.commons[2].segment, Some(1));
+}
+
+#[test]
+fn omf_typdef() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x06typdef");
+    // TYPDEF 1: NEAR scalar, 16 bits.
+    record(&mut data, 0x8E, &[0, 0, 0x62, 0x7B, 0x10]);
+    // TYPDEF 2: FAR array of 0x1000 elements of type 1.
+    record(&mut data, 0x8E, &[0, 0, 0x61, 0x81, 0x00, 0x10, 1]);
+    record(&mut data, 0x8C, b"\x04_var\x01\x04_arr\x02\x04_unk\x00");
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.types.len(), 2);
+    assert_eq!(file.type_size(1), Some(2));
+    assert_eq!(file.type_size(2), Some(0x2000));
+    assert_eq!(file.type_size(3), None);
+
+    let sizes = file
+        .symbols()
+        .map(|symbol| symbol.size())
+        .collect::<Vec<_>>();
+    assert_eq!(sizes, [2, 0x2000, 0]);
+    assert_eq!(
+        file.symbol_type(&file.symbols[1]).map(|ty| ty.leaf),
+        Some(object::read::omf::OmfTypeLeaf::Far {
+            count: 0x1000,
+            element_type: 1
+        })
+    );
+}
+
+#[test]
+fn omf_weak_externals() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x05wkext");
+    record(
+        &mut data,
+        0x8C,
+        b"\x05_weak\x00\x08_default\x00\x06_other\x00",
+    );
+    // COMENT WKEXT: external 1 defaults to external 2.
+    record(&mut data, 0x88, &[0x80, 0xA8, 1, 2]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    let weak = file.symbol_by_name("_weak").unwrap();
+    assert!(weak.is_weak());
+    assert!(weak.is_undefined());
+    assert_eq!(weak.default_resolution, Some(object::SymbolIndex(1)));
+    let other = file.symbol_by_name("_other").unwrap();
+    assert!(!other.is_weak());
+    assert_eq!(other.default_resolution, None);
+}
+
+#[test]
+fn omf_lazy_externals() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x05lzext");
+    record(&mut data, 0x8C, b"\x05_lazy\x00\x08_default\x00");
+    // COMENT LZEXT: external 1 defaults to external 2.
+    record(&mut data, 0x88, &[0x80, 0xA9, 1, 2]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    let lazy = file.symbol_by_name("_lazy").unwrap();
+    assert!(lazy.lazy);
+    assert!(!lazy.weak);
+    assert!(lazy.is_weak());
+    assert_eq!(lazy.default_resolution, Some(object::SymbolIndex(1)));
+    assert!(!file.symbol_by_name("_default").unwrap().lazy);
+}
+
+#[test]
+fn omf_default_libraries() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x05hello");
+    record(&mut data, 0x88, b"\x00\x9FSLIBCE");
+    record(&mut data, 0x88, b"\x00\x9FOLDNAMES");
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    let libraries = file.default_libraries().collect::<Vec<_>>();
+    assert_eq!(libraries, ["SLIBCE", "OLDNAMES"]);
+}
+
+#[test]
+fn omf_import_ordinals() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x06impdef");
+    record(&mut data, 0x8C, b"\x07_bystub\x00\x06_named\x00");
+    // COMENT IMPDEF: `_bystub` imported from KERNEL by ordinal 3.
+    record(
+        &mut data,
+        0x88,
+        b"\x00\xA0\x01\x01\x07_bystub\x06KERNEL\x03\x00",
+    );
+    // COMENT IMPDEF: `_named` imported from KERNEL by name.
+    record(&mut data, 0x88, b"\x00\xA0\x01\x00\x06_named\x06KERNEL\x00");
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.symbol_by_name("_bystub").unwrap().ordinal, Some(3));
+    assert_eq!(file.symbol_by_name("_named").unwrap().ordinal, None);
+}
+
+#[test]
+fn omf_imports() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x06impdef");
+    // By name, with an entry name that differs from the internal name.
+    record(
+        &mut data,
+        0x88,
+        b"\x00\xA0\x01\x00\x05_Beep\x06KERNEL\x0bMESSAGEBEEP",
+    );
+    // By name, with an empty entry name.
+    record(&mut data, 0x88, b"\x00\xA0\x01\x00\x05Yield\x06KERNEL\x00");
+    // By ordinal.
+    record(
+        &mut data,
+        0x88,
+        b"\x00\xA0\x01\x01\x05Catch\x06KERNEL\x37\x00",
+    );
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.module_kind(), OmfModuleKind::Import);
+    let definitions = file.import_definitions().collect::<Vec<_>>();
+    assert_eq!(definitions.len(), 3);
+    assert_eq!(definitions[2].internal_name, "Catch");
+    assert_eq!(definitions[2].entry, OmfImportEntry::Ordinal(0x37));
+
+    let imports = file.imports().unwrap();
+    let imports = imports
+        .iter()
+        .map(|import| (import.library(), import.name()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        imports,
+        [
+            (&b"KERNEL"[..], &b"MESSAGEBEEP"[..]),
+            (&b"KERNEL"[..], &b"Yield"[..])
+        ]
+    );
+}
+
+#[test]
+fn omf_exports() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x06expdef");
+    lnames(&mut data, &["", "_TEXT", "CODE"]);
+    record(&mut data, 0x98, &[0x48, 0x20, 0x00, 2, 3, 1]);
+    record(
+        &mut data,
+        0x90,
+        b"\x00\x01\x07WEP_IMP\x04\x00\x00\x07LibMain\x10\x00\x00",
+    );
+    // EXPDEF: WEP with internal name WEP_IMP, by ordinal 1, resident name.
+    record(
+        &mut data,
+        0x88,
+        b"\x00\xA0\x02\xC0\x03WEP\x07WEP_IMP\x01\x00",
+    );
+    // EXPDEF: LibMain with the same internal name and no ordinal.
+    record(&mut data, 0x88, b"\x00\xA0\x02\x02\x07LibMain\x00");
+    // EXPDEF: an export with no definition in this module.
+    record(&mut data, 0x88, b"\x00\xA0\x02\x00\x07Missing\x00");
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.module_kind(), OmfModuleKind::Object);
+    let definitions = file.export_definitions().collect::<Vec<_>>();
+    assert_eq!(definitions.len(), 3);
+    assert_eq!(definitions[0].internal_name, "WEP_IMP");
+    assert_eq!(definitions[0].ordinal, Some(1));
+    assert!(definitions[0].resident_name);
+    assert_eq!(definitions[1].internal_name, "LibMain");
+    assert_eq!(definitions[1].ordinal, None);
+    assert_eq!(definitions[1].parameter_count, 2);
+
+    let exports = file.exports().unwrap();
+    let exports = exports
+        .iter()
+        .map(|export| (export.name(), export.address()))
+        .collect::<Vec<_>>();
+    assert_eq!(exports, [(&b"WEP"[..], 4), (&b"LibMain"[..], 0x10)]);
+}
+
+#[test]
+fn omf_incremental_definitions() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x06incdef");
+    // INCDEF: 2 EXTDEFs added, 1 LINNUM removed, then padding.
+    record(&mut data, 0x88, &[0x00, 0xA0, 0x03, 2, 0, 0xFF, 0xFF, 0, 0]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    let incdefs = file.incremental_definitions().collect::<Vec<_>>();
+    assert_eq!(incdefs.len(), 1);
+    assert_eq!(incdefs[0].extdef_delta, 2);
+    assert_eq!(incdefs[0].linnum_delta, -1);
+}
+
+#[test]
+fn omf_linker_directives() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x06lnkdir");
+    // LNKDIR: new executable, omit $PUBLICS, p-code 1, CodeView 4.
+    record(&mut data, 0x88, &[0x00, 0xA0, 0x05, 0x03, 1, 4]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    let directives = file.linker_directives.unwrap();
+    assert!(directives.new_executable);
+    assert!(directives.omit_publics);
+    assert!(!directives.run_mpcex);
+    assert_eq!(directives.pcode_version, 1);
+    assert_eq!(directives.codeview_version, 4);
+}
+
+#[test]
+fn omf_comments() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x08comments");
+    record(&mut data, 0x88, b"\x00\x00MS C 6.00");
+    record(&mut data, 0x88, b"\x00\x9E");
+    record(&mut data, 0x88, &[0x00, 0xA0, 0x03, 1, 0, 0, 0]);
+    record(&mut data, 0x88, b"\xC0\xC0vendor");
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    let comments = &file.comments;
+    assert_eq!(comments.len(), 4);
+    assert_eq!(comments[0].class, 0x00);
+    assert_eq!(comments[0].kind, OmfCommentKind::Translator);
+    assert_eq!(comments[0].data, b"MS C 6.00");
+    assert!(!comments[0].no_purge);
+    assert!(!comments[0].no_list);
+    assert_eq!(comments[1].kind, OmfCommentKind::DosSeg);
+    assert_eq!(comments[2].class, 0xA0);
+    assert_eq!(comments[2].subtype, Some(0x03));
+    assert_eq!(comments[2].data, [1, 0, 0, 0]);
+    assert!(matches!(
+        comments[2].kind,
+        OmfCommentKind::IncrementalDefinition(_)
+    ));
+    assert_eq!(comments[3].class, 0xC0);
+    assert_eq!(comments[3].subtype, None);
+    assert_eq!(comments[3].kind, OmfCommentKind::Other);
+    assert_eq!(comments[3].data, b"vendor");
+    assert!(comments[3].no_purge);
+    assert!(comments[3].no_list);
+}
+
+#[test]
+fn omf_producer() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x05hello");
+    record(&mut data, 0x88, b"\x00\x00MS C 6.00");
+    record(&mut data, 0x88, b"\x00\x01Copyright Example Corp");
+    record(&mut data, 0x88, b"\x00\x00ignored");
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.producer(), Some("MS C 6.00"));
+    assert_eq!(file.copyright(), Some("Copyright Example Corp"));
+
+    let data = layout_module();
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.producer(), None);
+    assert_eq!(file.copyright(), None);
+}
+
+#[test]
+fn omf_exe_strings() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x06exestr");
+    record(&mut data, 0x88, b"\x00\xA4serial 0042\xFF");
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    let strings = file.exe_strings().collect::<Vec<_>>();
+    assert_eq!(strings, [&b"serial 0042\xFF"[..]]);
+}
+
+#[test]
+fn omf_library_module() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x0eC:\\SRC\\HELLO.C");
+    record(&mut data, 0x88, b"\x80\xA3\x05hello");
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.module_name, Some("C:\\SRC\\HELLO.C"));
+    assert_eq!(file.library_module, Some("hello"));
+}
+
+#[test]
+fn omf_pass_separator() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x04pass");
+    lnames(&mut data, &["", "_TEXT", "CODE"]);
+    record(&mut data, 0x98, &[0x48, 0x04, 0x00, 2, 3, 1]);
+    record(&mut data, 0x90, b"\x00\x01\x05_main\x00\x00\x00");
+    record(&mut data, 0x88, &[0x40, 0xA2, 0x01]);
+    let separator = data.len();
+    record(&mut data, 0xA0, &[1, 0x00, 0x00, 0x90, 0x90, 0x90, 0xC3]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.pass_separator, Some(separator));
+    let text = file.section_by_index(SectionIndex(1)).unwrap();
+    assert_eq!(text.data().unwrap(), [0x90, 0x90, 0x90, 0xC3]);
+
+    let file = OmfFile::<&[u8]>::parse_pass_one(&data).unwrap();
+    assert_eq!(file.pass_separator, Some(separator));
+    assert!(file.symbol_by_name("_main").is_some());
+    let text = file.section_by_index(SectionIndex(1)).unwrap();
+    assert_eq!(text.size(), 4);
+    assert!(text.data().unwrap().is_empty());
+}
+
+#[test]
+fn omf_memory_model() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x05model");
+    record(&mut data, 0x88, b"\x00\x9D2lOe");
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    let model = file.memory_model.unwrap();
+    assert_eq!(model.model, Some(OmfModelKind::Large));
+    assert_eq!(model.instruction_set, Some(OmfInstructionSet::I80286));
+    assert_eq!(model.float, Some(OmfFloatKind::Emulated));
+    assert!(model.optimized);
+}
+
+#[test]
+fn omf_symbol_kind() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x04kind");
+    lnames(
+        &mut data,
+        &[
+            "", "_TEXT", "CODE", "_DATA", "DATA", "_func", "_var", "_comm",
+        ],
+    );
+    record(&mut data, 0x98, &[0x48, 0x10, 0x00, 2, 3, 1]);
+    record(&mut data, 0x98, &[0x48, 0x10, 0x00, 4, 5, 1]);
+    record(&mut data, 0x90, b"\x00\x01\x05_func\x00\x00\x00");
+    record(&mut data, 0x90, b"\x00\x02\x04_var\x02\x00\x00");
+    record(&mut data, 0xB0, b"\x05_comm\x00\x62\x04");
+    record(&mut data, 0x8C, b"\x04_ext\x00");
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = object::File::parse(&*data).unwrap();
+    let kinds = file
+        .symbols()
+        .map(|symbol| (symbol.name().unwrap(), symbol.kind()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            ("_func", SymbolKind::Text),
+            ("_var", SymbolKind::Data),
+            ("_comm", SymbolKind::Data),
+            ("_ext", SymbolKind::Unknown),
+        ]
+    );
+}
+
+#[test]
+fn omf_symbol_by_name() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x04name");
+    lnames(&mut data, &["", "_TEXT", "CODE", "_func"]);
+    record(&mut data, 0x8C, b"\x05_func\x00\x04_ext\x00");
+    record(&mut data, 0x98, &[0x48, 0x10, 0x00, 2, 3, 1]);
+    record(&mut data, 0x90, b"\x00\x01\x05_func\x08\x00\x00");
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    // The definition is preferred over the external declaration.
+    let func = file.symbol_by_name("_func").unwrap();
+    assert_eq!(func.index(), object::SymbolIndex(2));
+    assert!(func.is_definition());
+    let ext = file.symbol_by_name_bytes(b"_ext").unwrap();
+    assert_eq!(ext.index(), object::SymbolIndex(1));
+    assert!(file.symbol_by_name("_none").is_none());
+}
 
-        let sections = obj.sections().collect::<Vec<_>>();
-        assert!(!sections.is_empty(), "Should parse at least one section");
+#[test]
+fn omf_symbol_map() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x03map");
+    lnames(
+        &mut data,
+        &[
+            "", "_TEXT", "CODE", "_DATA", "DATA", "_a", "_b", "_c", "_abs",
+        ],
+    );
+    record(&mut data, 0x98, &[0x48, 0x20, 0x00, 2, 3, 1]);
+    record(&mut data, 0x98, &[0x48, 0x20, 0x00, 4, 5, 1]);
+    record(
+        &mut data,
+        0x90,
+        b"\x00\x01\x02_a\x00\x00\x00\x02_b\x10\x00\x00",
+    );
+    record(&mut data, 0x90, b"\x00\x02\x02_c\x08\x00\x00");
+    // Absolute symbol: segment 0 is followed by a frame number.
+    record(&mut data, 0x90, b"\x00\x00\x00\x00\x04_abs\x04\x00\x00");
+    record(&mut data, 0xB6, b"\x00\x01\x02_b\x00\x00\x00");
+    record(&mut data, 0x8A, &[0x00]);
 
-        for sec in &sections {
-            println!("Section: {} size={}", sec.name().unwrap_or("?"), sec.size());
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    let map = file.section_symbol_map(SectionIndex(1));
+    let names = map
+        .symbols()
+        .iter()
+        .map(|symbol| (symbol.address(), symbol.name()))
+        .collect::<Vec<_>>();
+    assert_eq!(names, [(0, "_a"), (0x10, "_b")]);
+    assert_eq!(map.get(0x14).unwrap().name(), "_b");
+
+    let map = file.section_symbol_map(SectionIndex(2));
+    assert_eq!(map.get(0x8).unwrap().name(), "_c");
+    assert!(map.get(0x4).is_none());
+
+    let map = file.symbol_map();
+    assert_eq!(map.symbols().len(), 3);
+}
+
+#[test]
+fn omf_undefined_symbols() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x05undef");
+    lnames(&mut data, &["", "_TEXT", "CODE", "_pub", "_cext", "_comm"]);
+    record(&mut data, 0x98, &[0x48, 0x20, 0x00, 2, 3, 1]);
+    record(&mut data, 0x90, b"\x00\x01\x04_pub\x00\x00\x00");
+    record(&mut data, 0x8C, b"\x04_ext\x00");
+    record(&mut data, 0xB4, b"\x05_lext\x00");
+    record(&mut data, 0xBC, &[5, 0]);
+    record(&mut data, 0xB0, b"\x05_comm\x00\x62\x04");
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    let names = file
+        .undefined_symbols()
+        .map(|symbol| symbol.name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, [&b"_ext"[..], b"_lext", b"_cext"]);
+    for symbol in file.undefined_symbols() {
+        assert_eq!(symbol.section(), object::SymbolSection::Undefined);
+    }
+}
+
+#[test]
+fn omf_codeview_types() {
+    let mut types = 1u32.to_le_bytes().to_vec();
+    // 0x1000: pointer to 0x1001.
+    codeview_record(&mut types, 0x0002, b"\x0a\x00\x01\x10");
+    // 0x1001: struct point { count 2, fields 0x1002, property 0, dlist 0, vshape 0, size 4 }.
+    codeview_record(
+        &mut types,
+        0x0005,
+        b"\x02\x00\x02\x10\x00\x00\x00\x00\x00\x00\x04\x00\x05point",
+    );
+    // 0x1002: field list.
+    codeview_record(&mut types, 0x0204, &[]);
+    // 0x1003: int (*)(int), with the argument list at 0x1004.
+    codeview_record(&mut types, 0x0008, b"\x74\x00\x00\x00\x01\x00\x04\x10");
+    codeview_record(&mut types, 0x0201, b"\x01\x00\x74\x00");
+
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x02cv");
+    lnames(&mut data, &["", "$$TYPES", "DEBTYP"]);
+    record(&mut data, 0x98, &[0x28, types.len() as u8, 0, 2, 3, 1]);
+    split_ledata(&mut data, &types);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert!(file.codeview_symbols().unwrap().is_none());
+    let types = file.codeview_types().unwrap().unwrap();
+    assert_eq!(types.len(), 5);
+    assert_eq!(types.get(0x0074).unwrap(), None);
+    assert_eq!(types.get(0x1005).unwrap(), None);
+    assert_eq!(
+        types.get(0x1000).unwrap().unwrap().kind,
+        OmfCodeViewTypeKind::Pointer {
+            attributes: 0x0a,
+            type_index: 0x1001,
+        }
+    );
+    assert_eq!(
+        types.get(0x1001).unwrap().unwrap().kind,
+        OmfCodeViewTypeKind::Structure {
+            is_class: false,
+            count: 2,
+            fields: 0x1002,
+            property: 0,
+            size: 4,
+            name: b"point",
+        }
+    );
+    assert_eq!(
+        types.get(0x1002).unwrap().unwrap().kind,
+        OmfCodeViewTypeKind::Other
+    );
+    assert_eq!(
+        types.get(0x1003).unwrap().unwrap().kind,
+        OmfCodeViewTypeKind::Procedure {
+            return_type: 0x74,
+            calling_convention: 0,
+            parameter_count: 1,
+            arguments: 0x1004,
+        }
+    );
+    let all = types.iter().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(all[4].index, 0x1004);
+    assert_eq!(
+        all[4].kind,
+        OmfCodeViewTypeKind::ArgumentList { types: b"\x74\x00" }
+    );
+}
+
+#[test]
+fn omf_line_numbers() {
+    let mut data = layout_module();
+    data.truncate(data.len() - 5);
+    // LINNUM: group 0, segment 1, then (line, offset) pairs out of order.
+    record(
+        &mut data,
+        0x94,
+        &[0, 1, 3, 0, 0x00, 0x00, 5, 0, 0x08, 0x00, 4, 0, 0x03, 0x00],
+    );
+    // LINNUM32: group 0, segment 2.
+    record(&mut data, 0x95, &[0, 2, 10, 0, 0x04, 0x00, 0x00, 0x00]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.line_numbers.len(), 4);
+
+    let table = file.line_table(SectionIndex(1));
+    let lines = table
+        .lines()
+        .iter()
+        .map(|line| (line.line, line.offset))
+        .collect::<Vec<_>>();
+    assert_eq!(lines, [(3, 0), (4, 3), (5, 8)]);
+    assert_eq!(table.find(0), Some((Some("layout"), 3)));
+    assert_eq!(table.find(7), Some((Some("layout"), 4)));
+    assert_eq!(table.find(0x12), Some((Some("layout"), 5)));
+
+    assert_eq!(file.find_line(SectionIndex(2), 3), None);
+    assert_eq!(
+        file.find_line(SectionIndex(2), 4),
+        Some((Some("layout"), 10))
+    );
+    assert!(file.line_table(SectionIndex(3)).lines().is_empty());
+}
+
+#[test]
+fn omf_comdat_line_numbers() {
+    let mut data = layout_module();
+    data.truncate(data.len() - 5);
+    lnames(&mut data, &["_f"]);
+    // COMDAT: flags, attributes, align, offset, type, group, segment, name index, data.
+    record(
+        &mut data,
+        0xC2,
+        &[0, 0, 0, 0, 0, 0, 0, 1, 8, 0x90, 0x90, 0xC3],
+    );
+    // LINSYM: flags, name index, then (line, offset) pairs.
+    record(&mut data, 0xC4, &[0, 8, 7, 0, 0, 0, 8, 0, 2, 0]);
+    // LINNUM: group 0, segment 1.
+    record(&mut data, 0x94, &[0, 1, 20, 0, 0, 0]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    let section = file.comdats[0].section;
+    assert_eq!(section, SectionIndex(4));
+    assert_eq!(file.line_numbers.len(), 3);
+    assert_eq!(file.line_numbers[0].section, section);
+    assert_eq!(file.find_line(section, 0), Some((Some("layout"), 7)));
+    assert_eq!(file.find_line(section, 2), Some((Some("layout"), 8)));
+    assert_eq!(
+        file.find_line(SectionIndex(1), 0),
+        Some((Some("layout"), 20))
+    );
+}
+
+#[test]
+fn omf_dwarf_segments() {
+    let info = b"\x07\x00\x00\x00\x04\x00\x00\x00\x00\x00\x04";
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x05dwarf");
+    lnames(&mut data, &["", ".debug_info", "DWARF", ".debug_abbrev"]);
+    // .debug_info with class DWARF, then a segment named by its class.
+    record(&mut data, 0x98, &[0x28, info.len() as u8, 0, 2, 3, 1]);
+    record(&mut data, 0x98, &[0x28, 1, 0, 1, 4, 1]);
+    split_ledata(&mut data, info);
+    record(&mut data, 0xA0, &[2, 0, 0, 0]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = object::File::parse(&*data).unwrap();
+    assert!(file.has_debug_symbols());
+    let section = file.section_by_name(".debug_info").unwrap();
+    assert_eq!(section.kind(), SectionKind::Debug);
+    assert_eq!(&*section.uncompressed_data().unwrap(), &info[..]);
+    let section = file.section_by_name(".debug_abbrev").unwrap();
+    assert_eq!(section.kind(), SectionKind::Debug);
+    assert_eq!(&*section.uncompressed_data().unwrap(), &[0]);
+
+    let data = layout_module();
+    let file = object::File::parse(&*data).unwrap();
+    assert!(!file.has_debug_symbols());
+}
+
+#[test]
+fn omf_borland_debug() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x07borland");
+    // Borland source file comment.
+    record(&mut data, 0x88, b"\x00\xE8\x00\x05a.cpp\x00\x00\x00\x00");
+    lnames(
+        &mut data,
+        &["", "$$BSYMS", "DEBSYM", "$$SYMBOLS", "_TEXT", "CODE"],
+    );
+    record(&mut data, 0x98, &[0x28, 0, 0, 2, 3, 1]);
+    record(&mut data, 0x98, &[0x28, 0, 0, 4, 3, 1]);
+    record(&mut data, 0x98, &[0x28, 0, 0, 5, 6, 1]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.comments[0].kind, OmfCommentKind::BorlandDebug);
+    let formats = file
+        .segments
+        .iter()
+        .map(|segment| segment.debug_format)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        formats,
+        [
+            Some(OmfDebugFormat::Borland),
+            Some(OmfDebugFormat::CodeView),
+            None
+        ]
+    );
+    assert_eq!(file.segments[0].kind, SectionKind::Debug);
+    assert_eq!(file.segments[1].kind, SectionKind::Debug);
+    assert_eq!(file.segments[2].kind, SectionKind::Text);
+    assert!(file.has_debug_symbols());
+}
+
+#[test]
+fn omf_source_modules() {
+    let mut data = layout_module();
+    data.truncate(data.len() - 5);
+    // PUBDEF: group 0, segment 1, "_a" at 0.
+    record(&mut data, 0x90, b"\x00\x01\x02_a\x00\x00\x00");
+    record(&mut data, 0x80, b"\x05inc.h");
+    // EXTDEF: "_b", type 0.
+    record(&mut data, 0x8C, b"\x02_b\x00");
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.modules, ["layout", "inc.h"]);
+    assert_eq!(file.segments[0].source_module, Some("layout"));
+    let a = file.symbol_by_name("_a").unwrap();
+    assert_eq!(a.source_module, Some("layout"));
+    let b = file.symbol_by_name("_b").unwrap();
+    assert_eq!(b.source_module, Some("inc.h"));
+}
+
+#[test]
+fn omf_object_map() {
+    let mut data = layout_module();
+    data.truncate(data.len() - 5);
+    lnames(&mut data, &["_f"]);
+    // PUBDEF: _main at _TEXT:0004 and _x at _DATA:0002.
+    record(&mut data, 0x90, b"\x00\x01\x05_main\x04\x00\x00");
+    record(&mut data, 0x90, b"\x00\x02\x02_x\x02\x00\x00");
+    // COMDAT _f.
+    record(&mut data, 0xC2, &[0, 0, 0, 0, 0, 0, 0, 1, 8, 0x90, 0xC3]);
+    record(&mut data, 0x80, b"\x06main.c");
+    // LINNUM: line 12 at _TEXT:0000.
+    record(&mut data, 0x94, &[0, 1, 12, 0, 0, 0]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = object::File::parse(&*data).unwrap();
+    let map = file.object_map();
+    let names = map
+        .symbols()
+        .iter()
+        .map(|entry| (entry.name(), entry.address()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            (&b"_main"[..], 0x04),
+            (&b"_x"[..], 0x22),
+            (&b"_f"[..], 0x40)
+        ]
+    );
+    let entry = map.get(0x05).unwrap();
+    assert_eq!(entry.name(), b"_main");
+    assert_eq!(entry.object(&map).path(), b"main.c");
+    let entry = map.get(0x22).unwrap();
+    assert_eq!(entry.object(&map).path(), b"layout");
+    assert_eq!(map.objects().len(), 2);
+}
+
+/// Pad `out` with zeros to a multiple of `page_size`.
+fn pad(out: &mut Vec<u8>, page_size: usize) {
+    out.resize((out.len() + page_size - 1) / page_size * page_size, 0);
+}
+
+/// A library with a page size of 32, containing the modules `one` and `two`.
+fn library() -> Vec<u8> {
+    // LIBHDR: record length (page size - 3), dictionary offset, dictionary blocks, flags.
+    let mut data = vec![0xF0, 0x1D, 0x00, 0x80, 0, 0, 0, 1, 0, 0x01];
+    pad(&mut data, 32);
+    record(&mut data, 0x80, b"\x03one");
+    record(&mut data, 0x90, b"\x00\x00\x00\x00\x04_one\x00\x01\x00");
+    record(&mut data, 0x8A, &[0x00]);
+    pad(&mut data, 32);
+    record(&mut data, 0x80, b"\x03two");
+    record(&mut data, 0x8C, b"\x04_one\x00");
+    record(&mut data, 0x8A, &[0x00]);
+    pad(&mut data, 32);
+    // LIBEND.
+    record(&mut data, 0xF1, &[]);
+    pad(&mut data, 32);
+    data
+}
+
+#[test]
+fn omf_library_members() {
+    let data = library();
+    assert_eq!(
+        object::FileKind::parse(&*data).unwrap(),
+        object::FileKind::OmfLibrary
+    );
+    assert!(object::File::parse(&*data).is_err());
+
+    let library = OmfLibrary::parse(&*data).unwrap();
+    assert_eq!(library.page_size(), 32);
+    assert_eq!(library.dictionary_offset(), 0x80);
+    assert_eq!(library.dictionary_blocks(), 1);
+    assert!(library.is_case_sensitive());
+
+    let members = library.members().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(members.len(), 2);
+    assert_eq!(members[0].offset(), 0x20);
+    assert_eq!(members[0].name(), Ok("one"));
+    assert_eq!(members[0].name_bytes(), b"one");
+    assert_eq!(members[1].offset(), 0x40);
+    assert_eq!(members[1].name(), Ok("two"));
+
+    let one = members[0].parse(&*data).unwrap();
+    assert!(one.symbol_by_name("_one").unwrap().is_definition());
+    let two = library.member_at_page(2).unwrap();
+    assert_eq!(two.file_range(), (0x40, 0x17));
+    let two = two.parse(&*data).unwrap();
+    assert!(two.symbol_by_name("_one").unwrap().is_undefined());
+    assert!(library.member(0x30).is_err());
+}
+
+/// Return the dictionary bucket for a name in a dictionary with one block.
+fn dictionary_bucket(name: &[u8]) -> usize {
+    let mut bucket = 0u16;
+    for &c in name.iter().rev() {
+        bucket = bucket.rotate_right(2) ^ u16::from(c | 0x20);
+    }
+    usize::from(bucket % 37)
+}
+
+/// Append a dictionary block containing `_one` (page 1) and `_two` (page 2).
+fn dictionary(data: &mut Vec<u8>) {
+    let mut block = vec![0u8; 512];
+    let mut free = 38;
+    for (name, page) in [(&b"_one"[..], 1u16), (&b"_two"[..], 2)] {
+        assert_eq!(block[dictionary_bucket(name)], 0);
+        block[dictionary_bucket(name)] = (free / 2) as u8;
+        block[free] = name.len() as u8;
+        block[free + 1..free + 1 + name.len()].copy_from_slice(name);
+        block[free + 1 + name.len()..free + 3 + name.len()].copy_from_slice(&page.to_le_bytes());
+        free += (3 + name.len() + 1) & !1;
+    }
+    block[37] = (free / 2) as u8;
+    data.extend_from_slice(&block);
+}
+
+#[test]
+fn omf_library_symbol_index() {
+    // The dictionary is missing, so the index must be built from the members.
+    let data = library();
+    let library = OmfLibrary::parse(&*data).unwrap();
+    assert!(library.symbols().is_err());
+
+    let symbols = library.symbol_index().unwrap();
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].name(), b"_one");
+    assert_eq!(symbols[0].offset(), 0x20);
+    assert_eq!(
+        library.member(symbols[0].offset()).unwrap().name(),
+        Ok("one")
+    );
+}
+
+#[test]
+fn omf_library_dictionary() {
+    let mut data = library();
+    assert_eq!(data.len(), 0x80);
+    dictionary(&mut data);
+
+    // Extended dictionary: 2 modules, the second depending on the first.
+    let mut extended = Vec::new();
+    extended.extend_from_slice(&2u16.to_le_bytes());
+    for (page, offset) in [(1u16, 10u16), (2, 12)] {
+        extended.extend_from_slice(&page.to_le_bytes());
+        extended.extend_from_slice(&offset.to_le_bytes());
+    }
+    extended.extend_from_slice(&[0, 0, 1, 0, 0, 0]);
+    data.push(0xF2);
+    data.extend_from_slice(&(extended.len() as u16).to_le_bytes());
+    data.extend_from_slice(&extended);
+
+    let library = OmfLibrary::parse(&*data).unwrap();
+    let dictionary = library.dictionary().unwrap();
+    assert_eq!(dictionary.blocks(), 1);
+    assert_eq!(dictionary.find(b"_one"), Some(1));
+    assert_eq!(dictionary.find(b"_two"), Some(2));
+    assert_eq!(dictionary.find(b"_ONE"), None);
+    assert_eq!(dictionary.find(b"_three"), None);
+    let mut symbols = dictionary.symbols().collect::<Result<Vec<_>, _>>().unwrap();
+    symbols.sort();
+    assert_eq!(symbols, [(&b"_one"[..], 1), (&b"_two"[..], 2)]);
+
+    let member = library.member_for_symbol(b"_two").unwrap().unwrap();
+    assert_eq!(member.name(), Ok("two"));
+    assert!(library.member_for_symbol(b"_three").unwrap().is_none());
+    let mut symbols = library
+        .symbols()
+        .unwrap()
+        .map(|symbol| {
+            let symbol = symbol.unwrap();
+            (symbol.name(), symbol.offset())
+        })
+        .collect::<Vec<_>>();
+    symbols.sort();
+    assert_eq!(symbols, [(&b"_one"[..], 0x20), (&b"_two"[..], 0x40)]);
+
+    let extended = library.extended_dictionary().unwrap().unwrap();
+    assert_eq!(extended.len(), 2);
+    let modules = extended
+        .modules()
+        .map(|module| {
+            let module = module.unwrap();
+            (module.page(), module.dependencies().collect::<Vec<_>>())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(modules, [(1, vec![]), (2, vec![1])]);
+}
+
+#[test]
+fn omf_library_archive() {
+    use object::read::archive::{ArchiveFile, ArchiveKind};
+
+    let mut data = library();
+    dictionary(&mut data);
+
+    let archive = ArchiveFile::parse(&*data).unwrap();
+    assert_eq!(archive.kind(), ArchiveKind::OmfLib);
+    assert!(!archive.is_thin());
+
+    let members = archive.members().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(members.len(), 2);
+    assert_eq!(members[0].name(), b"one");
+    assert_eq!(members[0].file_range(), (0x20, 0x1D));
+    assert_eq!(members[0].date(), None);
+    assert_eq!(members[1].name(), b"two");
+    let two = members[1].data(&*data).unwrap();
+    assert!(OmfFile::parse(two).is_ok());
+
+    let mut symbols = archive
+        .symbols()
+        .unwrap()
+        .unwrap()
+        .map(|symbol| {
+            let symbol = symbol.unwrap();
+            (symbol.name(), symbol.offset())
+        })
+        .collect::<Vec<_>>();
+    symbols.sort_by_key(|symbol| symbol.0);
+    assert_eq!(symbols.len(), 2);
+    assert_eq!(symbols[0].0, b"_one");
+    let member = archive.member(symbols[1].1).unwrap();
+    assert_eq!(member.name(), b"two");
+}
+
+#[test]
+fn omf_library_non_utf8_member_name() {
+    let mut data = library();
+    // Rename the first member to "\xF6ne".
+    assert_eq!(&data[0x24..0x27], b"one");
+    data[0x24] = 0xF6;
+
+    let library = OmfLibrary::parse(&*data).unwrap();
+    let members = library.members().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(members[0].name_bytes(), b"\xF6ne");
+    assert!(members[0].name().is_err());
+    assert_eq!(members[1].name(), Ok("two"));
+
+    let archive = object::read::archive::ArchiveFile::parse(&*data).unwrap();
+    let members = archive.members().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(members[0].name(), b"\xF6ne");
+}
+
+#[test]
+fn omf_import_library_to_coff() {
+    use object::pe;
+    use object::read::coff::{ImportFile, ImportName};
+
+    let mut data = vec![0xF0, 0x0D, 0x00, 0, 0, 0, 0, 0, 0, 0];
+    pad(&mut data, 16);
+    for (name, impdef) in [
+        (
+            &b"\x05_Beep"[..],
+            &b"\x00\x05_Beep\x06KERNEL\x0bMESSAGEBEEP"[..],
+        ),
+        (b"\x05Yield", b"\x00\x05Yield\x06KERNEL\x00"),
+        (b"\x05Catch", b"\x01\x05Catch\x06KERNEL\x37\x00"),
+    ] {
+        record(&mut data, 0x80, name);
+        let mut coment = vec![0x00, 0xA0, 0x01];
+        coment.extend_from_slice(impdef);
+        record(&mut data, 0x88, &coment);
+        record(&mut data, 0x8A, &[0x00]);
+        pad(&mut data, 16);
+    }
+    record(&mut data, 0xF1, &[]);
+
+    let library = OmfLibrary::parse(&*data).unwrap();
+    let imports = library.import_definitions().unwrap();
+    assert_eq!(imports.len(), 3);
+
+    let coff = imports
+        .iter()
+        .map(|import| import.to_coff_import(pe::IMAGE_FILE_MACHINE_I386))
+        .collect::<Vec<_>>();
+    let files = coff
+        .iter()
+        .map(|data| ImportFile::parse(&**data).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(files[0].symbol(), b"_Beep");
+    assert_eq!(files[0].dll(), b"KERNEL");
+    assert_eq!(files[0].import(), ImportName::Name(b"MESSAGEBEEP"));
+    assert_eq!(files[1].import(), ImportName::Name(b"Yield"));
+    assert_eq!(files[2].symbol(), b"Catch");
+    assert_eq!(files[2].import(), ImportName::Ordinal(0x37));
+    assert_eq!(files[2].architecture(), object::Architecture::I386);
+}
+
+#[test]
+fn omf_checksums() {
+    let mut data = Vec::new();
+    // THEADR with a valid checksum.
+    data.extend_from_slice(b"\x80\x06\x00\x04test");
+    let sum = data.iter().fold(0u8, |sum, &b| sum.wrapping_add(b));
+    data.push(sum.wrapping_neg());
+    // MODEND with a checksum of 0, which is not verified.
+    record(&mut data, 0x8A, &[0x00]);
+
+    let mut options = OmfParseOptions::default();
+    options.verify_checksums = true;
+    let file = OmfFile::<&[u8]>::parse_with_options(&data, options).unwrap();
+    assert_eq!(file.module_name, Some("test"));
+
+    // The checksum is not part of the record body.
+    data[8] ^= 1;
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.module_name, Some("test"));
+    assert!(OmfFile::<&[u8]>::parse_with_options(&data, options).is_err());
+}
+
+#[test]
+fn omf_unknown_records() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x07unknown");
+    // A vendor record type that is not recognized.
+    record(&mut data, 0xDE, &[1, 2, 3]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    assert!(OmfFile::<&[u8]>::parse(&data).is_err());
+
+    let mut options = OmfParseOptions::default();
+    options.allow_unknown_records = true;
+    let file = OmfFile::<&[u8]>::parse_with_options(&data, options).unwrap();
+    assert_eq!(file.module_name, Some("unknown"));
+    assert_eq!(file.unknown_records.len(), 1);
+    let unknown = file.unknown_records[0];
+    assert_eq!(
+        (unknown.kind, unknown.offset, unknown.length),
+        (0xDE, 12, 4)
+    );
+}
+
+#[test]
+fn omf_diagnostics() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x05diags");
+    lnames(&mut data, &["", "_TEXT", "CODE"]);
+    // SEGDEF with an out of range class name index.
+    let segdef = data.len();
+    record(&mut data, 0x98, &[0x48, 0x04, 0x00, 2, 9, 1]);
+    // COMENT IMPDEF with a truncated payload.
+    let coment = data.len();
+    record(&mut data, 0x88, b"\x00\xA0\x01\x00\x05_Be");
+    // LEDATA for a segment that is not defined.
+    let ledata = data.len();
+    record(&mut data, 0xA0, &[5, 0, 0, 0x90]);
+    // MODEND with a bad checksum.
+    let modend = data.len();
+    data.extend_from_slice(&[0x8A, 0x02, 0x00, 0x00, 0x01]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.name(file.segments[0].name), b"_TEXT");
+    assert_eq!(file.name(file.segments[0].class), b"");
+    assert!(file.comments.is_empty());
+    let diagnostics = file
+        .diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.offset, diagnostic.kind))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        diagnostics,
+        [
+            (segdef, OmfDiagnosticKind::InvalidNameIndex(9)),
+            (coment, OmfDiagnosticKind::InvalidComment),
+            (ledata, OmfDiagnosticKind::InvalidSegmentIndex(5)),
+            (modend, OmfDiagnosticKind::ChecksumMismatch),
+        ]
+    );
+}
+
+#[test]
+fn omf_truncated_record() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x05trunc");
+    lnames(&mut data, &["", "_TEXT", "CODE"]);
+    // LEDATA whose length extends past the end of the file.
+    let ledata = data.len();
+    data.extend_from_slice(&[0xA0, 0x10, 0x00, 1, 0, 0, 0x90]);
+
+    assert!(OmfFile::<&[u8]>::parse(&data).is_err());
+
+    let mut options = OmfParseOptions::default();
+    options.allow_truncated = true;
+    let file = OmfFile::<&[u8]>::parse_with_options(&data, options).unwrap();
+    assert_eq!(file.module_name, Some("trunc"));
+    let lnames = file
+        .lnames
+        .iter()
+        .map(|&id| file.name(id))
+        .collect::<Vec<_>>();
+    assert_eq!(lnames, [&b""[..], b"_TEXT", b"CODE"]);
+    assert_eq!(file.diagnostics.len(), 1);
+    assert_eq!(file.diagnostics[0].offset, ledata);
+    assert_eq!(file.diagnostics[0].kind, OmfDiagnosticKind::TruncatedFile);
+}
+
+#[test]
+fn omf_truncated_segdef() {
+    // A SEGDEF with only the ACBP byte, and a SEGDEF32 that ends within the length.
+    for (kind, body, length) in [(0x98, &[0x48][..], 0), (0x99, &[0x48, 0x10, 0x00], 0x10)] {
+        let mut data = Vec::new();
+        record(&mut data, 0x80, b"\x05trunc");
+        lnames(&mut data, &["", "_TEXT", "CODE", "_DATA", "DATA"]);
+        let segdef = data.len();
+        record(&mut data, kind, body);
+        record(&mut data, 0x98, &[0x48, 0x04, 0x00, 4, 5, 1]);
+        record(&mut data, 0x8A, &[0x00]);
+
+        let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+        assert_eq!(file.segments.len(), 2);
+        assert_eq!(file.segments[0].length, length);
+        assert_eq!(file.name(file.segments[0].name), b"");
+        assert_eq!(file.name(file.segments[1].name), b"_DATA");
+        assert_eq!(file.diagnostics.len(), 1);
+        assert_eq!(file.diagnostics[0].offset, segdef);
+        assert_eq!(file.diagnostics[0].kind, OmfDiagnosticKind::TruncatedRecord);
+    }
+}
+
+#[test]
+fn omf_truncated_fields() {
+    use OmfDiagnosticKind::*;
+    // Each record is valid, and is invalid if its last byte is removed.
+    let records: &[(u8, &[u8], OmfDiagnosticKind)] = &[
+        (0x80, b"\x04name", TruncatedRecord),
+        (0x82, b"\x04name", TruncatedRecord),
+        (0x96, b"\x04DATA", TruncatedRecord),
+        (0x98, &[0x48, 0x10, 0x00, 2, 3], TruncatedRecord),
+        (0x99, &[0x48, 0x10, 0x00, 0x00, 0x00, 2, 3], TruncatedRecord),
+        (
+            0x98,
+            &[0x08, 0x00, 0xB8, 0x00, 0x10, 0x00, 2, 3],
+            TruncatedRecord,
+        ),
+        (0x9A, &[2, 0xFF, 1], TruncatedRecord),
+        (0x90, b"\x00\x01\x03pub\x02\x00\x00", TruncatedRecord),
+        (
+            0x91,
+            b"\x00\x01\x03pub\x02\x00\x00\x00\x00",
+            TruncatedRecord,
+        ),
+        (
+            0x90,
+            b"\x00\x00\x40\x00\x03abs\x17\x00\x00",
+            TruncatedRecord,
+        ),
+        (0xB6, b"\x00\x01\x04lpub\x04\x00\x00", TruncatedRecord),
+        (0x8C, b"\x03ext\x00", TruncatedRecord),
+        (0xB4, b"\x04lext\x00", TruncatedRecord),
+        (0xBC, &[2, 0], TruncatedRecord),
+        (0xB0, b"\x04near\x00\x62\x04", TruncatedRecord),
+        (0xB0, b"\x03far\x00\x61\x02\x04", TruncatedRecord),
+        (0xB8, b"\x04lcom\x00\x62\x04", TruncatedRecord),
+        (0x8E, &[0, 0, 0x62, 0x7B, 0x10], TruncatedRecord),
+        (0x94, &[0, 1, 0x0A, 0x00, 0x00, 0x00], TruncatedRecord),
+        (
+            0x95,
+            &[0, 1, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00],
+            TruncatedRecord,
+        ),
+        (0xC4, &[0, 2, 0x0A, 0x00, 0x00, 0x00], TruncatedRecord),
+        (0x9C, &[0xC4, 0x00, 0x50, 1, 0x00, 0x00], TruncatedRecord),
+        (0xA0, &[1, 0x00, 0x00], TruncatedRecord),
+        (0xA1, &[1, 0x00, 0x00, 0x00, 0x00], TruncatedRecord),
+        (0xA2, &[1, 0x00, 0x00], TruncatedRecord),
+        (
+            0xC2,
+            &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 1, 2],
+            TruncatedRecord,
+        ),
+        (0x88, &[0x80, 0xA8, 1, 2], TruncatedRecord),
+        (0x88, &[0x80, 0xA9, 1, 2], TruncatedRecord),
+        (0x8A, &[0xC1, 0x50, 0x01, 0x04, 0x00], InvalidStartAddress),
+    ];
+    for &(kind, body, truncated) in records {
+        for len in 0..=body.len() {
+            let mut data = Vec::new();
+            record(&mut data, 0x80, b"\x05trunc");
+            lnames(&mut data, &["", "_TEXT", "CODE"]);
+            record(&mut data, 0x98, &[0x48, 0x10, 0x00, 2, 3, 1]);
+            record(&mut data, 0x8C, b"\x02_a\x00\x02_b\x00");
+            record(&mut data, 0xA0, &[1, 0x00, 0x00, 0, 0, 0, 0]);
+            let offset = data.len();
+            record(&mut data, kind, &body[..len]);
+            record(&mut data, 0x8A, &[0x00]);
+
+            let file = OmfFile::<&[u8]>::parse(&data)
+                .unwrap_or_else(|e| panic!("record {:#x} length {}: {}", kind, len, e));
+            let diagnostics = file
+                .diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.offset == offset)
+                .map(|diagnostic| diagnostic.kind)
+                .collect::<Vec<_>>();
+            if len == body.len() {
+                assert_eq!(diagnostics, [], "record {:#x}", kind);
+            } else if len + 1 == body.len() {
+                assert_eq!(diagnostics, [truncated], "record {:#x}", kind);
+            }
+        }
+    }
+}
+
+#[test]
+fn omf_invalid_data_records() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x07invalid");
+    lnames(&mut data, &["", "_TEXT", "CODE"]);
+    record(&mut data, 0x98, &[0x48, 0x10, 0x00, 2, 3, 1]);
+    // LEDATA that ends within the offset.
+    let truncated = data.len();
+    record(&mut data, 0xA0, &[1, 0x00]);
+    // LEDATA and LIDATA for segment index 0.
+    let ledata = data.len();
+    record(&mut data, 0xA0, &[0, 0x00, 0x00, 0x90]);
+    let lidata = data.len();
+    record(
+        &mut data,
+        0xA2,
+        &[0, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x90],
+    );
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert!(file.segments[0].records.is_empty());
+    let diagnostics = file
+        .diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.offset, diagnostic.kind))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        diagnostics,
+        [
+            (truncated, OmfDiagnosticKind::TruncatedRecord),
+            (ledata, OmfDiagnosticKind::InvalidSegmentIndex(0)),
+            (lidata, OmfDiagnosticKind::InvalidSegmentIndex(0)),
+        ]
+    );
+}
+
+/// Append a 1 or 2 byte OMF index.
+fn index(out: &mut Vec<u8>, index: u16) {
+    if index < 0x80 {
+        out.push(index as u8);
+    } else {
+        out.push(0x80 | (index >> 8) as u8);
+        out.push(index as u8);
+    }
+}
+
+#[test]
+fn omf_large_indices() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x05large");
+    // LNAMES 1 is the class, and 2..=201 are segment names.
+    let names = (0..200).map(|i| format!("SEG{}", i)).collect::<Vec<_>>();
+    lnames(&mut data, &["CODE"]);
+    for chunk in names.chunks(50) {
+        lnames(
+            &mut data,
+            &chunk.iter().map(String::as_str).collect::<Vec<_>>(),
+        );
+    }
+    for i in 0..200 {
+        let mut segdef = vec![0x28, 0x01, 0x00];
+        index(&mut segdef, i + 2);
+        index(&mut segdef, 1);
+        index(&mut segdef, 0);
+        record(&mut data, 0x98, &segdef);
+    }
+    // GRPDEF named SEG199, containing segment 150.
+    let mut grpdef = Vec::new();
+    index(&mut grpdef, 201);
+    grpdef.push(0xFF);
+    index(&mut grpdef, 150);
+    record(&mut data, 0x9A, &grpdef);
+    // CEXTDEF naming SEG180.
+    let mut cextdef = Vec::new();
+    index(&mut cextdef, 182);
+    index(&mut cextdef, 0);
+    record(&mut data, 0xBC, &cextdef);
+    // LEDATA for segment 150.
+    let mut ledata = Vec::new();
+    index(&mut ledata, 150);
+    ledata.extend_from_slice(&[0, 0, 0xC3]);
+    record(&mut data, 0xA0, &ledata);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert!(file.diagnostics.is_empty());
+    assert_eq!(file.segments.len(), 200);
+    assert_eq!(file.name(file.segments[149].name), b"SEG149");
+    assert_eq!(file.name(file.segments[149].class), b"CODE");
+    assert_eq!(file.name(file.segments[199].name), b"SEG199");
+    assert_eq!(file.name(file.groups[0].name), b"SEG199");
+    assert_eq!(file.groups[0].segment_indices, [150]);
+    assert_eq!(file.symbols[0].name, b"SEG180");
+    assert_eq!(file.segment_contents(SectionIndex(150)).unwrap(), [0xC3]);
+}
+
+#[test]
+fn omf_symbol_segments_above_255() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x04many");
+    lnames(&mut data, &["CODE", "_TEXT", "_func"]);
+    for _ in 0..300 {
+        record(&mut data, 0x98, &[0x28, 0x01, 0x00, 2, 1, 0]);
+    }
+    // LPUBDEF in segment 290.
+    let mut lpubdef = Vec::new();
+    index(&mut lpubdef, 0);
+    index(&mut lpubdef, 290);
+    lpubdef.extend_from_slice(b"\x06_local\x04\x00\x00");
+    record(&mut data, 0xB6, &lpubdef);
+    // COMDAT allocated in segment 260.
+    let mut comdat = vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+    index(&mut comdat, 0);
+    index(&mut comdat, 260);
+    index(&mut comdat, 3);
+    comdat.push(0xC3);
+    record(&mut data, 0xC2, &comdat);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert!(file.diagnostics.is_empty());
+    assert_eq!(file.segments.len(), 300);
+    let local = file.symbol_by_name("_local").unwrap();
+    assert_eq!(local.section_index(), Some(SectionIndex(290)));
+    assert_eq!(local.address(), 4);
+    assert_eq!(file.comdats.len(), 1);
+    assert_eq!(file.comdats[0].segment_index, 260);
+    assert_eq!(file.comdats[0].segment_name, Some(&b"_TEXT"[..]));
+}
+
+#[test]
+fn omf_non_utf8_names() {
+    let mut data = Vec::new();
+    // THEADR named "m\xFCn.c".
+    record(&mut data, 0x80, b"\x05m\xFCn.c");
+    // LNAMES: "", "_T\x8EXT", "CODE".
+    record(&mut data, 0x96, b"\x00\x05_T\x8EXT\x04CODE");
+    record(&mut data, 0x98, &[0x28, 0x01, 0x00, 0x02, 0x03, 0x01]);
+    // PUBDEF "_gr\xFC\xDF" at offset 0 in segment 1.
+    record(&mut data, 0x90, b"\x00\x01\x05_gr\xFC\xDF\x00\x00\x00");
+    record(&mut data, 0xA0, &[0x01, 0x00, 0x00, 0xC3]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.module_name, None);
+    assert_eq!(file.module_name_bytes, Some(&b"m\xFCn.c"[..]));
+    assert_eq!(
+        file.diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.kind)
+            .collect::<Vec<_>>(),
+        [OmfDiagnosticKind::NonUtf8ModuleName]
+    );
+
+    let section = file.section_by_index(SectionIndex(1)).unwrap();
+    assert_eq!(section.name_bytes().unwrap(), b"_T\x8EXT");
+    assert!(section.name().is_err());
+    assert_eq!(section.data().unwrap(), [0xC3]);
+
+    let symbol = file.symbols().next().unwrap();
+    assert_eq!(symbol.name_bytes().unwrap(), b"_gr\xFC\xDF");
+    assert!(symbol.name().is_err());
+    assert_eq!(symbol.section_index(), Some(SectionIndex(1)));
+}
+
+#[test]
+fn omf_peek() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x06peek.c");
+    record(&mut data, 0x8A, &[0x00]);
+    pad(&mut data, 16);
+    assert!(OmfFile::peek(&*data).is_ok());
+    assert_eq!(
+        object::FileKind::parse(&*data).unwrap(),
+        object::FileKind::Omf
+    );
+
+    // A valid checksum is accepted, and an invalid one is rejected.
+    let sum = data[..10].iter().fold(0u8, |sum, &b| sum.wrapping_add(b));
+    data[10] = sum.wrapping_neg();
+    assert!(OmfFile::peek(&*data).is_ok());
+    data[10] ^= 1;
+    assert!(OmfFile::peek(&*data).is_err());
+    data[10] = 0;
+
+    // The name must fill the record.
+    data[3] = 5;
+    assert!(OmfFile::peek(&*data).is_err());
+    data[3] = 6;
+
+    // Other records in the 0x80..=0x9F range are not module headers.
+    data[0] = 0x88;
+    assert!(OmfFile::peek(&*data).is_err());
+    assert!(object::FileKind::parse(&*data).is_err());
+
+    let mut data = library();
+    assert!(OmfLibrary::peek(&*data).is_ok());
+    // The first page must start with a member.
+    data[32] = 0x88;
+    assert!(OmfLibrary::peek(&*data).is_err());
+    assert!(object::FileKind::parse(&*data).is_err());
+}
+
+#[test]
+fn omf_module_stream() {
+    let mut data = Vec::new();
+    for (name, symbol, code) in [("one.c", "_one", 0xC3), ("two.c", "_two", 0x90)] {
+        let mut theadr = vec![name.len() as u8];
+        theadr.extend_from_slice(name.as_bytes());
+        record(&mut data, 0x80, &theadr);
+        lnames(&mut data, &["_TEXT", "CODE"]);
+        record(&mut data, 0x98, &[0x28, 0x01, 0x00, 0x01, 0x02, 0x01]);
+        let mut pubdef = vec![0x00, 0x01, symbol.len() as u8];
+        pubdef.extend_from_slice(symbol.as_bytes());
+        pubdef.extend_from_slice(&[0x00, 0x00, 0x00]);
+        record(&mut data, 0x90, &pubdef);
+        record(&mut data, 0xA0, &[0x01, 0x00, 0x00, code]);
+        record(&mut data, 0x8A, &[0x00]);
+    }
+
+    // A single parse stops at the end of the first module.
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.modules, ["one.c"]);
+    assert_eq!(file.sections().count(), 1);
+    assert_eq!(file.symbols().count(), 1);
+
+    let modules = OmfFile::parse_modules(&*data)
+        .collect::<object::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(modules.len(), 2);
+    for (module, (name, symbol, code)) in modules
+        .iter()
+        .zip([("one.c", "_one", 0xC3), ("two.c", "_two", 0x90)])
+    {
+        assert_eq!(module.module_name, Some(name));
+        assert_eq!(module.modules, [name]);
+        let sections = module.sections().collect::<Vec<_>>();
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].name(), Ok("_TEXT"));
+        assert_eq!(sections[0].data().unwrap(), [code]);
+        let symbols = module.symbols().collect::<Vec<_>>();
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].name(), Ok(symbol));
+        assert_eq!(symbols[0].section_index(), Some(SectionIndex(1)));
+    }
+}
+
+#[test]
+fn omf_lidata() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x06lidata");
+    lnames(&mut data, &["", "_TEXT", "CODE"]);
+    record(&mut data, 0x98, &[0x48, 0x10, 0x00, 2, 3, 1]);
+    record(&mut data, 0x90, b"\x00\x01\x04_foo\x02\x00\x00");
+    record(&mut data, 0xA0, b"\x01\x00\x001234");
+    let mut lidata = vec![1, 0x04, 0x00];
+    // Repeat 2 times: 2 nested blocks.
+    lidata.extend_from_slice(&[2, 0, 2, 0]);
+    // Repeat 1 time: "ab".
+    lidata.extend_from_slice(&[1, 0, 0, 0, 2, b'a', b'b']);
+    // Repeat 3 times: "x".
+    lidata.extend_from_slice(&[3, 0, 0, 0, 1, b'x']);
+    // Repeat 2 times: "z".
+    lidata.extend_from_slice(&[2, 0, 0, 0, 1, b'z']);
+    record(&mut data, 0xA2, &lidata);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let mut file = OmfFile::parse(&*data).unwrap();
+    let contents = file.segment_contents(SectionIndex(1)).unwrap();
+    assert_eq!(contents, b"1234abxxxabxxxzz");
+    let section = file.section_by_index(SectionIndex(1)).unwrap();
+    assert_eq!(&*section.uncompressed_data().unwrap(), contents);
+    assert!(matches!(
+        file.segment_data(SectionIndex(1)).unwrap(),
+        Cow::Owned(_)
+    ));
+
+    // The precomputed contents and symbol maps are borrowed.
+    file.precompute();
+    let precomputed = file.segment_data(SectionIndex(1)).unwrap();
+    assert!(matches!(precomputed, Cow::Borrowed(_)));
+    assert_eq!(*precomputed, *contents);
+    let map = file.section_symbol_map(SectionIndex(1));
+    assert!(matches!(map, Cow::Borrowed(_)));
+    assert_eq!(map.get(3).unwrap().name(), "_foo");
+    assert_eq!(file.symbol_map().symbols(), map.symbols());
+}
+
+#[test]
+fn omf_file_is_sync() {
+    fn assert_sync<T: Sync>() {}
+    assert_sync::<OmfFile<'static, &'static [u8]>>();
+    assert_sync::<object::File<'static, &'static [u8]>>();
+}
+
+#[test]
+fn omf_limits() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x06limits");
+    lnames(&mut data, &["_TEXT", "CODE"]);
+    // A 32-bit segment with a LEDATA record at offset 0x7FFFFFF0.
+    record(
+        &mut data,
+        0x99,
+        &[0xA9, 0x00, 0x00, 0x00, 0x80, 0x01, 0x02, 0x01],
+    );
+    record(&mut data, 0x90, b"\x00\x01\x04_one\x00\x00\x00");
+    record(&mut data, 0x90, b"\x00\x01\x04_two\x00\x00\x00");
+    record(&mut data, 0xA1, &[0x01, 0xF0, 0xFF, 0xFF, 0x7F, 0xC3]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert!(file.segment_contents(SectionIndex(1)).is_err());
+
+    let limits = OmfParseOptions::default().limits;
+    assert_eq!(limits.max_records, 1 << 22);
+    assert_eq!(limits.max_symbols, 1 << 20);
+
+    let mut options = OmfParseOptions::default();
+    options.limits.max_symbols = 1;
+    assert!(OmfFile::<&[u8]>::parse_with_options(&data, options).is_err());
+    options.limits.max_symbols = 2;
+    assert!(OmfFile::<&[u8]>::parse_with_options(&data, options).is_ok());
+
+    let mut options = OmfParseOptions::default();
+    options.limits.max_records = 6;
+    assert!(OmfFile::<&[u8]>::parse_with_options(&data, options).is_err());
+    options.limits.max_records = 7;
+    assert!(OmfFile::<&[u8]>::parse_with_options(&data, options).is_ok());
+}
+
+#[test]
+fn omf_record_types() {
+    for value in 0..=0xFF {
+        if let Some(kind) = OmfRecordType::from_u8(value) {
+            assert_eq!(kind.to_u8(), value);
         }
     }
+    assert_eq!(OmfRecordType::from_u8(0xA0), Some(OmfRecordType::Ledata));
+    assert_eq!(OmfRecordType::from_u8(0xB4), Some(OmfRecordType::Lextdef));
+    assert_eq!(OmfRecordType::from_u8(0xF1), Some(OmfRecordType::Libend));
+    assert_eq!(OmfRecordType::from_u8(0x84), None);
+    assert!(OmfRecordType::Lidata32.is_32bit());
+    assert!(!OmfRecordType::Libend.is_32bit());
+}
+
+#[test]
+fn omf_padding_after_modend() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x03one");
+    record(&mut data, 0x8A, &[0x00]);
+    // A page of padding, as in a library member.
+    data.extend_from_slice(&[0x1A; 5]);
+    pad(&mut data, 32);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.modules, ["one"]);
+    assert!(file.diagnostics.is_empty());
+
+    // Padding between modules is skipped.
+    record(&mut data, 0x80, b"\x03two");
+    record(&mut data, 0x8B, &[0x00]);
+    data.extend_from_slice(&[0x00; 3]);
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.modules, ["one"]);
+    let modules = OmfFile::parse_modules(&*data)
+        .map(|module| module.unwrap().module_name)
+        .collect::<Vec<_>>();
+    assert_eq!(modules, [Some("one"), Some("two")]);
+
+    // Splitting stops at anything after MODEND that isn't a module header.
+    data.extend_from_slice(&[0xFF; 8]);
+    let modules = OmfFile::parse_modules(&*data)
+        .map(|module| module.unwrap().module_name)
+        .collect::<Vec<_>>();
+    assert_eq!(modules, [Some("one"), Some("two")]);
+}
+
+#[test]
+fn omf_parse_symbols() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x07symbols");
+    lnames(&mut data, &["_TEXT", "CODE"]);
+    record(&mut data, 0x98, &[0x28, 0x04, 0x00, 0x01, 0x02, 0x01]);
+    record(&mut data, 0x90, b"\x00\x01\x05_main\x00\x00\x00");
+    record(&mut data, 0x8C, b"\x04_ext\x00");
+    record(&mut data, 0xA0, &[0x01, 0x00, 0x00, 0xE8, 0x00, 0x00, 0xC3]);
+    // FIXUPP: self-relative offset at 1, target external 1.
+    record(&mut data, 0x9C, &[0x84, 0x01, 0x56, 0x01]);
+    record(&mut data, 0x94, &[0x00, 0x01, 0x0A, 0x00, 0x00, 0x00]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let mut file = OmfFile::<&[u8]>::parse_symbols(&data).unwrap();
+    assert!(!file.is_data_loaded());
+    let names = file
+        .symbols()
+        .map(|symbol| symbol.name().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["_main", "_ext"]);
+    let section = file.section_by_index(SectionIndex(1)).unwrap();
+    assert_eq!(section.name().unwrap(), "_TEXT");
+    assert!(section.data().is_err());
+    assert_eq!(section.relocations().count(), 0);
+    assert!(file.line_numbers.is_empty());
+
+    file.load_data().unwrap();
+    assert!(file.is_data_loaded());
+    let section = file.section_by_index(SectionIndex(1)).unwrap();
+    assert_eq!(section.data().unwrap(), [0xE8, 0x00, 0x00, 0xC3]);
+    assert_eq!(section.relocations().count(), 1);
+    assert_eq!(file.line_numbers.len(), 1);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn omf_read_cache() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x05cache");
+    lnames(&mut data, &["_TEXT", "CODE"]);
+    record(&mut data, 0x98, &[0x28, 0x01, 0x00, 0x01, 0x02, 0x01]);
+    record(&mut data, 0x90, b"\x00\x01\x05_main\x00\x00\x00");
+    record(&mut data, 0xA0, &[0x01, 0x00, 0x00, 0xC3]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let cache = object::read::ReadCache::new(std::io::Cursor::new(data));
+    let file = OmfFile::parse(&cache).unwrap();
+    assert_eq!(file.module_name, Some("cache"));
+    let section = file.section_by_index(SectionIndex(1)).unwrap();
+    assert_eq!(section.data().unwrap(), [0xC3]);
+    let symbol = file.symbols().next().unwrap();
+    assert_eq!(symbol.name().unwrap(), "_main");
+}
+
+#[test]
+fn omf_interned_names() {
+    let mut data = Vec::new();
+    record(&mut data, 0x80, b"\x05names");
+    lnames(&mut data, &["", "_TEXT", "CODE"]);
+    // A second LNAMES record repeating the names.
+    lnames(&mut data, &["CODE", "_TEXT2", ""]);
+    record(&mut data, 0x98, &[0x28, 0x01, 0x00, 0x02, 0x03, 0x01]);
+    record(&mut data, 0x98, &[0x28, 0x01, 0x00, 0x05, 0x04, 0x01]);
+    record(&mut data, 0x8A, &[0x00]);
+
+    let file = OmfFile::<&[u8]>::parse(&data).unwrap();
+    assert_eq!(file.lnames.len(), 6);
+    assert_eq!(file.names, [&b""[..], b"_TEXT", b"CODE", b"_TEXT2"]);
+    assert_eq!(file.lnames[0], file.lnames[5]);
+    assert_eq!(file.segments[0].class, file.segments[1].class);
+    assert_ne!(file.segments[0].name, file.segments[1].name);
+    assert_eq!(file.name(file.segments[1].name), b"_TEXT2");
+    assert_eq!(file.name(file.segments[1].class), b"CODE");
 }
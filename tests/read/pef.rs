@@ -0,0 +1,144 @@
+use object::read::pef::PefFile;
+use object::{
+    pef, BinaryFormat, CompressionFormat, FileKind, Object, ObjectKind, ObjectSection,
+    ObjectSymbol, SectionIndex, SectionKind, SymbolSection,
+};
+
+fn words(data: &mut Vec<u8>, words: &[u32]) {
+    for word in words {
+        data.extend_from_slice(&word.to_be_bytes());
+    }
+}
+
+fn section(
+    data: &mut Vec<u8>,
+    name_offset: i32,
+    total_length: u32,
+    unpacked_length: u32,
+    container: (u32, u32),
+    section_kind: u8,
+) {
+    data.extend_from_slice(&name_offset.to_be_bytes());
+    words(
+        data,
+        &[0, total_length, unpacked_length, container.1, container.0],
+    );
+    data.extend_from_slice(&[section_kind, pef::PEF_PROCESS_SHARE, 4, 0]);
+}
+
+#[test]
+fn pef_parse() {
+    let mut data = Vec::new();
+    words(
+        &mut data,
+        &[
+            pef::PEF_TAG1,
+            pef::PEF_TAG2,
+            pef::PEF_ARCH_POWERPC,
+            1,
+            0,
+            0,
+            0,
+            0,
+        ],
+    );
+    words(&mut data, &[(3 << 16) | 2, 0]);
+    section(&mut data, 0, 8, 8, (136, 8), pef::PEF_CODE_SECTION);
+    section(
+        &mut data,
+        5,
+        32,
+        28,
+        (144, 23),
+        pef::PEF_PATTERN_DATA_SECTION,
+    );
+    section(&mut data, -1, 0, 142, (168, 142), pef::PEF_LOADER_SECTION);
+    data.extend_from_slice(b"code\0data\0\0\0");
+
+    // Code section.
+    data.extend_from_slice(&[0x38, 0x60, 0, 0, 0x4e, 0x80, 0, 0x20]);
+
+    // Pattern-initialized data section.
+    data.extend_from_slice(&[0x24, 1, 2, 3, 4]);
+    data.extend_from_slice(&[0x04]);
+    data.extend_from_slice(&[0x42, 2, 0xaa, 0xbb]);
+    data.extend_from_slice(&[0x82, 1, 2, 0x11, 0x22]);
+    data.extend_from_slice(&[0x61, 2, 1, 0xcc, 5, 6]);
+    data.extend_from_slice(&[0x00, 2]);
+    data.push(0);
+
+    // Loader section.
+    let tvect = u32::from(pef::PEF_TVECT_SYMBOL) << 24;
+    let weak = u32::from(pef::PEF_WEAK_IMPORT_SYMBOL) << 24;
+    words(&mut data, &[1, 16, u32::MAX, 0, u32::MAX, 0]);
+    words(&mut data, &[1, 2, 0, 88, 88, 124, 0, 1]);
+    words(&mut data, &[0, 0, 0, 2, 0, 0]);
+    words(&mut data, &[tvect | 13, weak | tvect | 22]);
+    data.extend_from_slice(b"InterfaceLib\0DebugStr\0SysBeep\0main\0\0");
+    words(&mut data, &[1 << 18, 4 << 16, tvect | 30, 16]);
+    data.extend_from_slice(&1u16.to_be_bytes());
+
+    assert_eq!(FileKind::parse(&*data).unwrap(), FileKind::Pef);
+    let file = object::File::parse(&*data).unwrap();
+    assert_eq!(file.format(), BinaryFormat::Pef);
+    assert_eq!(file.kind(), ObjectKind::Executable);
+    assert_eq!(file.architecture(), object::Architecture::PowerPc);
+    assert_eq!(file.entry(), 16);
+    assert_eq!(file.segments().count(), 2);
+
+    let sections = file.sections().collect::<Vec<_>>();
+    assert_eq!(sections.len(), 3);
+    assert_eq!(sections[0].name(), Ok("code"));
+    assert_eq!(sections[0].kind(), SectionKind::Text);
+    assert_eq!(sections[0].align(), 16);
+    assert_eq!(sections[1].name(), Ok("data"));
+    assert_eq!(sections[1].kind(), SectionKind::Data);
+    assert_eq!(sections[1].size(), 32);
+    assert_eq!(sections[1].data().unwrap().len(), 23);
+    assert_eq!(
+        sections[1].compressed_file_range().unwrap().format,
+        CompressionFormat::Unknown
+    );
+    assert_eq!(
+        &*sections[1].uncompressed_data().unwrap(),
+        &[
+            1, 2, 3, 4, 0, 0, 0, 0, 0xaa, 0xbb, 0xaa, 0xbb, 0xaa, 0xbb, 0, 0, 0x11, 0, 0, 0x22, 0,
+            0, 0xcc, 5, 6, 0xcc, 0, 0
+        ][..]
+    );
+    assert_eq!(sections[2].name(), Ok(""));
+    assert_eq!(sections[2].kind(), SectionKind::Metadata);
+    assert_eq!(sections[2].size(), 142);
+
+    let symbols = file.symbols().collect::<Vec<_>>();
+    assert_eq!(symbols.len(), 3);
+    assert_eq!(symbols[0].name(), Ok("DebugStr"));
+    assert!(symbols[0].is_undefined());
+    assert!(!symbols[0].is_weak());
+    assert_eq!(symbols[1].name(), Ok("SysBeep"));
+    assert!(symbols[1].is_weak());
+    assert_eq!(symbols[2].name(), Ok("main"));
+    assert_eq!(
+        symbols[2].section(),
+        SymbolSection::Section(SectionIndex(1))
+    );
+    assert_eq!(symbols[2].address(), 16);
+
+    let imports = file.imports().unwrap();
+    assert_eq!(imports.len(), 2);
+    assert_eq!(imports[0].library(), b"InterfaceLib");
+    assert_eq!(imports[1].name(), b"SysBeep");
+    let exports = file.exports().unwrap();
+    assert_eq!(exports.len(), 1);
+    assert_eq!(exports[0].name(), b"main");
+
+    let file = PefFile::<&[u8]>::parse(&*data).unwrap();
+    assert_eq!(file.imported_libraries()[0].name(), b"InterfaceLib");
+    assert_eq!(file.imported_libraries()[0].symbols(), 0..2);
+    assert_eq!(
+        file.section_headers()[1]
+            .unpacked_length
+            .get(object::BigEndian),
+        28
+    );
+}
@@ -0,0 +1,130 @@
+use object::read::pharlap::{PharLapFile, PharLapHeader};
+use object::{
+    BinaryFormat, FileKind, Object, ObjectKind, ObjectSection, ObjectSegment, RelocationKind,
+    RelocationTarget, SectionIndex, SectionKind,
+};
+
+use super::util::{put, put16, put32};
+
+/// A new-style flat model program with a packed load image and two relocations.
+fn p3_program() -> Vec<u8> {
+    let mut data = vec![0; 0x193];
+    put(&mut data, 0, b"P3");
+    put16(&mut data, 0x02, 1); // flat
+    put16(&mut data, 0x04, 0x180);
+    put32(&mut data, 0x06, 0x193);
+    put32(&mut data, 0x14, 0x180); // relocation table
+    put32(&mut data, 0x18, 8);
+    put32(&mut data, 0x26, 0x188); // load image
+    put32(&mut data, 0x2a, 11);
+    put32(&mut data, 0x56, 0x100); // minimum extra
+    put32(&mut data, 0x5e, 0x1000); // base load offset
+    put32(&mut data, 0x68, 0x1004); // EIP
+    put16(&mut data, 0x72, 1); // packed
+    put32(&mut data, 0x74, 0x40); // memory size
+
+    put32(&mut data, 0x180, 0x10);
+    put32(&mut data, 0x184, 0x20);
+    // A literal run, and a repeated string.
+    put(
+        &mut data,
+        0x188,
+        &[4, 0, b'a', b'b', b'c', b'd', 3, 0x80, 2, b'x', b'y'],
+    );
+    data
+}
+
+/// An old-style program bound to an MS-DOS stub.
+fn mp_program() -> Vec<u8> {
+    let mut data = vec![0; 0x80];
+    put(&mut data, 0, b"MZ");
+    put16(&mut data, 0x02, 0x40);
+    put16(&mut data, 0x04, 1);
+
+    let h = 0x40;
+    put(&mut data, h, b"MP");
+    put16(&mut data, h + 0x02, 0x40);
+    put16(&mut data, h + 0x04, 1);
+    put16(&mut data, h + 0x06, 1); // relocations
+    put16(&mut data, h + 0x08, 3); // header paragraphs
+    put16(&mut data, h + 0x0a, 1); // minimum extra pages
+    put32(&mut data, h + 0x14, 0x10); // EIP
+    put16(&mut data, h + 0x18, 0x1e); // relocation table
+    put16(&mut data, h + 0x1c, 1);
+    put32(&mut data, h + 0x1e, 8);
+    put(&mut data, h + 0x30, &[0xcc; 0x10]);
+    data
+}
+
+#[test]
+fn pharlap_p3_parse() {
+    let data = p3_program();
+    assert_eq!(FileKind::parse(&*data).unwrap(), FileKind::PharLap);
+    let file = object::File::parse(&*data).unwrap();
+    assert_eq!(file.format(), BinaryFormat::PharLap);
+    assert_eq!(file.kind(), ObjectKind::Executable);
+    assert_eq!(file.entry(), 0x1004);
+    assert_eq!(file.symbols().count(), 0);
+
+    let sections = file.sections().collect::<Vec<_>>();
+    assert_eq!(sections.len(), 1);
+    assert_eq!(sections[0].index(), SectionIndex(1));
+    assert_eq!(sections[0].kind(), SectionKind::Text);
+    assert_eq!(sections[0].address(), 0x1000);
+    assert_eq!(sections[0].size(), 0x140);
+    assert_eq!(sections[0].file_range(), None);
+    assert!(sections[0].data().is_err());
+
+    let relocations = sections[0].relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 2);
+    assert_eq!(relocations[1].0, 0x20);
+    assert_eq!(relocations[1].1.kind(), RelocationKind::SectionIndex);
+    assert_eq!(relocations[1].1.size(), 16);
+    assert_eq!(
+        relocations[1].1.target(),
+        RelocationTarget::Section(SectionIndex(1))
+    );
+
+    let file = PharLapFile::<&[u8]>::parse(&*data).unwrap();
+    assert!(matches!(file.header(), PharLapHeader::P3(_)));
+    assert!(file.is_packed());
+    let image = file.image_data().unwrap();
+    assert_eq!(image.len(), 0x40);
+    assert_eq!(&image[..12], b"abcdxyxyxy\0\0");
+}
+
+#[test]
+fn pharlap_mp_parse() {
+    let data = mp_program();
+    assert_eq!(FileKind::parse(&*data).unwrap(), FileKind::PharLap);
+    let file = object::File::parse(&*data).unwrap();
+    assert_eq!(file.entry(), 0x10);
+
+    let segment = file.segments().next().unwrap();
+    assert_eq!(segment.address(), 0);
+    assert_eq!(segment.size(), 0x1010);
+    assert_eq!(segment.file_range(), (0x70, 0x10));
+    assert_eq!(segment.data(), Ok(&[0xcc; 0x10][..]));
+
+    let section = file.section_by_index(SectionIndex(1)).unwrap();
+    let relocations = section.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 1);
+    assert_eq!(relocations[0].0, 8);
+
+    let file = PharLapFile::<&[u8]>::parse(&*data).unwrap();
+    assert_eq!(file.header_offset(), 0x40);
+    assert!(matches!(file.header(), PharLapHeader::Mp(_)));
+}
+
+#[test]
+fn pharlap_invalid_last_page_size() {
+    // In the MS-DOS stub.
+    let mut data = mp_program();
+    put16(&mut data, 0x02, 0xffff);
+    assert!(PharLapFile::<&[u8]>::parse(&*data).is_err());
+
+    // In the MP header.
+    let mut data = mp_program();
+    put16(&mut data, 0x42, 0xffff);
+    assert!(PharLapFile::<&[u8]>::parse(&*data).is_err());
+}
@@ -0,0 +1,58 @@
+use std::fmt::Write;
+
+use object::read::srec::SrecFile;
+use object::{srec, BinaryFormat, FileKind, Object, ObjectSection, SectionIndex};
+
+fn record(text: &mut String, record_type: u8, address: u32, data: &[u8]) {
+    let address_size = srec::address_size(record_type).unwrap();
+    let mut bytes = vec![(address_size + data.len() + 1) as u8];
+    bytes.extend_from_slice(&address.to_be_bytes()[4 - address_size..]);
+    bytes.extend_from_slice(data);
+    let sum = bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    bytes.push(!sum);
+    write!(text, "S{}", record_type).unwrap();
+    for byte in bytes {
+        write!(text, "{:02X}", byte).unwrap();
+    }
+    text.push('\n');
+}
+
+#[test]
+fn srec_parse() {
+    let mut text = String::new();
+    record(&mut text, srec::SREC_HEADER, 0, b"test");
+    record(&mut text, srec::SREC_DATA_24, 0x01_0000, &[1, 2, 3, 4]);
+    record(&mut text, srec::SREC_DATA_24, 0x01_0004, &[5, 6]);
+    record(&mut text, srec::SREC_DATA_32, 0x2000_0000, &[7, 8]);
+    record(&mut text, srec::SREC_COUNT_16, 3, &[]);
+    record(&mut text, srec::SREC_START_24, 0x01_0002, &[]);
+    let data = text.as_bytes();
+
+    assert_eq!(FileKind::parse(data).unwrap(), FileKind::Srec);
+    let object = object::File::parse(data).unwrap();
+    assert_eq!(object.format(), BinaryFormat::Srec);
+    assert_eq!(object.entry(), 0x01_0002);
+
+    let file = SrecFile::<&[u8]>::parse(data).unwrap();
+    assert_eq!(file.header(), Some(&b"test"[..]));
+    assert_eq!(file.record_count(), Some(3));
+    let sections = file
+        .sections()
+        .map(|section| (section.address(), section.contents().to_vec()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sections,
+        [
+            (0x01_0000, vec![1, 2, 3, 4, 5, 6]),
+            (0x2000_0000, vec![7, 8]),
+        ]
+    );
+    let section = file.section_by_index(SectionIndex(2)).unwrap();
+    assert!(section.data().is_err());
+    assert_eq!(&*section.uncompressed_data().unwrap(), &[7, 8]);
+
+    let mut bad = text.into_bytes();
+    let last = bad.len() - 2;
+    bad[last] ^= 1;
+    assert!(SrecFile::<&[u8]>::parse(&*bad).is_err());
+}
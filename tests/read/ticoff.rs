@@ -0,0 +1,215 @@
+use object::endian::{Endian, Endianness};
+use object::read::ticoff::TiCoffFile;
+use object::{
+    ticoff, Architecture, BinaryFormat, FileKind, Object, ObjectKind, ObjectSection, ObjectSymbol,
+    RelocationTarget, SectionIndex, SectionKind, SymbolIndex, SymbolKind, SymbolSection,
+};
+
+struct Builder {
+    endian: Endianness,
+    data: Vec<u8>,
+}
+
+impl Builder {
+    fn u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        let bytes = self.endian.write_u16_bytes(value);
+        self.data.extend_from_slice(&bytes);
+    }
+
+    fn u32(&mut self, value: u32) {
+        let bytes = self.endian.write_u32_bytes(value);
+        self.data.extend_from_slice(&bytes);
+    }
+
+    fn name(&mut self, name: &[u8]) {
+        let mut bytes = [0; 8];
+        bytes[..name.len()].copy_from_slice(name);
+        self.data.extend_from_slice(&bytes);
+    }
+
+    fn symbol(&mut self, name: &[u8], value: u32, scnum: i16, sclass: u8, numaux: u8) {
+        self.name(name);
+        self.u32(value);
+        self.u16(scnum as u16);
+        self.u16(0);
+        self.u8(sclass);
+        self.u8(numaux);
+        for _ in 0..numaux {
+            self.data.extend_from_slice(&[0; ticoff::SYMBOL_SIZE]);
+        }
+    }
+}
+
+#[test]
+fn ticoff2_c6000() {
+    let long_name = b"external_function\0";
+    let mut b = Builder {
+        endian: Endianness::Little,
+        data: Vec::new(),
+    };
+    let text_offset = 22 + 28 + 2 * 48;
+    let reloc_offset = text_offset + 4;
+    let symbols_offset = reloc_offset + 12;
+
+    // File header.
+    b.u16(ticoff::TI_COFF2);
+    b.u16(2);
+    b.u32(0);
+    b.u32(symbols_offset);
+    b.u32(4);
+    b.u16(28);
+    b.u16(ticoff::F_EXEC | ticoff::F_LITTLE);
+    b.u16(ticoff::TI_TARGET_C6000);
+    // Optional header.
+    b.u16(ticoff::OPTIONAL_HEADER_MAGIC);
+    b.u16(0);
+    b.u32(4);
+    b.u32(0);
+    b.u32(16);
+    b.u32(0x8000);
+    b.u32(0x8000);
+    b.u32(0);
+    // Section headers.
+    b.name(b".text");
+    b.u32(0x8000);
+    b.u32(0x8000);
+    b.u32(4);
+    b.u32(text_offset);
+    b.u32(reloc_offset);
+    b.u32(0);
+    b.u32(1);
+    b.u32(0);
+    b.u32(ticoff::STYP_TEXT | (2 << ticoff::STYP_ALIGN_SHIFT));
+    b.u16(0);
+    b.u16(0);
+    b.name(b".bss");
+    b.u32(0x9000);
+    b.u32(0x9000);
+    b.u32(16);
+    b.u32(0);
+    b.u32(0);
+    b.u32(0);
+    b.u32(0);
+    b.u32(0);
+    b.u32(ticoff::STYP_BSS);
+    b.u16(0);
+    b.u16(0);
+    // Section data.
+    b.data.extend_from_slice(&[1, 2, 3, 4]);
+    // Relocations.
+    b.u32(0x8000);
+    b.u32(3);
+    b.u16(0);
+    b.u16(ticoff::R_RELLONG);
+    // Symbol table.
+    b.symbol(b".text", 0x8000, 1, ticoff::C_STAT, 1);
+    b.symbol(b"_main", 0x8000, 1, ticoff::C_EXT, 0);
+    b.u32(0);
+    b.u32(4);
+    b.u32(0);
+    b.u16(0);
+    b.u16(0);
+    b.u8(ticoff::C_EXT);
+    b.u8(0);
+    // String table.
+    b.u32(4 + long_name.len() as u32);
+    b.data.extend_from_slice(long_name);
+    let data = &*b.data;
+
+    assert_eq!(FileKind::parse(data).unwrap(), FileKind::TiCoff);
+    let object = object::File::parse(data).unwrap();
+    assert_eq!(object.format(), BinaryFormat::TiCoff);
+    assert_eq!(object.architecture(), Architecture::TiC6000);
+    assert_eq!(object.kind(), ObjectKind::Executable);
+    assert!(object.is_little_endian());
+    assert_eq!(object.entry(), 0x8000);
+
+    let file = TiCoffFile::<&[u8]>::parse(data).unwrap();
+    assert_eq!(file.version(), 2);
+    let sections = file
+        .sections()
+        .map(|section| (section.name().unwrap(), section.kind(), section.address()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sections,
+        [
+            (".text", SectionKind::Text, 0x8000),
+            (".bss", SectionKind::UninitializedData, 0x9000),
+        ]
+    );
+    let text = file.section_by_index(SectionIndex(1)).unwrap();
+    assert_eq!(text.data().unwrap(), &[1, 2, 3, 4]);
+    assert_eq!(text.align(), 4);
+    let relocations = text.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 1);
+    assert_eq!(relocations[0].0, 0);
+    assert_eq!(relocations[0].1.size(), 32);
+    assert_eq!(
+        relocations[0].1.target(),
+        RelocationTarget::Symbol(SymbolIndex(3))
+    );
+
+    let symbols = file
+        .symbols()
+        .map(|symbol| (symbol.index().0, symbol.name().unwrap(), symbol.kind()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        symbols,
+        [
+            (0, ".text", SymbolKind::Section),
+            (2, "_main", SymbolKind::Text),
+            (3, "external_function", SymbolKind::Unknown),
+        ]
+    );
+    let symbol = file.symbol_by_index(SymbolIndex(3)).unwrap();
+    assert_eq!(symbol.section(), SymbolSection::Undefined);
+}
+
+#[test]
+fn ticoff0_c2800() {
+    let mut b = Builder {
+        endian: Endianness::Big,
+        data: Vec::new(),
+    };
+    let text_offset = 20 + 40;
+
+    // File header.
+    b.u16(ticoff::TI_TARGET_C2800);
+    b.u16(1);
+    b.u32(0);
+    b.u32(0);
+    b.u32(0);
+    b.u16(0);
+    b.u16(ticoff::F_BIG);
+    // Section header.
+    b.name(b".text");
+    b.u32(0x100);
+    b.u32(0x100);
+    b.u32(2);
+    b.u32(text_offset);
+    b.u32(0);
+    b.u32(0);
+    b.u16(0);
+    b.u16(0);
+    b.u16(ticoff::STYP_TEXT as u16);
+    b.u8(0);
+    b.u8(0);
+    // Section data, in 16-bit words.
+    b.data.extend_from_slice(&[1, 2, 3, 4]);
+    let data = &*b.data;
+
+    assert_eq!(FileKind::parse(data).unwrap(), FileKind::TiCoff);
+    let file = TiCoffFile::<&[u8]>::parse(data).unwrap();
+    assert_eq!(file.version(), 0);
+    assert_eq!(file.architecture(), Architecture::TiC2000);
+    assert_eq!(file.kind(), ObjectKind::Relocatable);
+    assert!(!file.is_little_endian());
+    let text = file.section_by_name(".text").unwrap();
+    assert_eq!(text.size(), 2);
+    assert_eq!(text.data().unwrap(), &[1, 2, 3, 4]);
+    assert_eq!(file.symbols().count(), 0);
+}
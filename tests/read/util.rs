@@ -0,0 +1,24 @@
+//! Helpers for building test files in place.
+
+// Not every format's tests use every helper.
+#![allow(dead_code)]
+
+/// Write `bytes` at `offset`.
+pub fn put(data: &mut [u8], offset: usize, bytes: &[u8]) {
+    data[offset..offset + bytes.len()].copy_from_slice(bytes);
+}
+
+/// Write a little endian `u16` at `offset`.
+pub fn put16(data: &mut [u8], offset: usize, value: u16) {
+    put(data, offset, &value.to_le_bytes());
+}
+
+/// Write a little endian `u32` at `offset`.
+pub fn put32(data: &mut [u8], offset: usize, value: u32) {
+    put(data, offset, &value.to_le_bytes());
+}
+
+/// Write a big endian `u32` at `offset`.
+pub fn put32be(data: &mut [u8], offset: usize, value: u32) {
+    put(data, offset, &value.to_be_bytes());
+}
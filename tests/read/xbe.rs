@@ -0,0 +1,126 @@
+use object::read::xbe::{XbeConsole, XbeFile, XbeKernelImport};
+use object::{
+    xbe, Architecture, BinaryFormat, FileKind, Object, ObjectKind, ObjectSection, ObjectSegment,
+    SectionKind,
+};
+
+struct Builder {
+    data: Vec<u8>,
+}
+
+impl Builder {
+    fn u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn pad(&mut self, offset: usize) {
+        assert!(self.data.len() <= offset);
+        self.data.resize(offset, 0);
+    }
+}
+
+#[test]
+fn xbe_retail() {
+    let base = 0x10000;
+    let certificate_offset = 0x178;
+    let section_offset = certificate_offset + 0x1d0;
+    let name_offset = section_offset + 56;
+    let headers_size = name_offset + 8;
+    let text_offset = 0x400;
+    let text_address = 0x11000;
+    let mut b = Builder { data: Vec::new() };
+
+    // Image header.
+    b.u32(xbe::XBE_MAGIC);
+    b.data.extend_from_slice(&[0; 256]);
+    b.u32(base);
+    b.u32(headers_size);
+    b.u32(0x2000);
+    b.u32(0x178);
+    b.u32(0);
+    b.u32(base + certificate_offset);
+    b.u32(1);
+    b.u32(base + section_offset);
+    b.u32(xbe::XBE_INIT_LIMIT_64MB);
+    b.u32((text_address + 12) ^ xbe::XBE_ENTRY_RETAIL_KEY);
+    for _ in 0..11 {
+        b.u32(0);
+    }
+    b.u32(text_address ^ xbe::XBE_KERNEL_THUNK_RETAIL_KEY);
+    for _ in 0..7 {
+        b.u32(0);
+    }
+    // Certificate.
+    b.pad(certificate_offset as usize);
+    b.u32(0x1d0);
+    b.u32(0);
+    b.u32(0x4d53_0001);
+    b.pad(section_offset as usize);
+    // Section header.
+    b.u32(xbe::XBE_SECTION_EXECUTABLE | xbe::XBE_SECTION_PRELOAD);
+    b.u32(text_address);
+    b.u32(0x20);
+    b.u32(text_offset);
+    b.u32(16);
+    b.u32(base + name_offset);
+    b.u32(0);
+    b.u32(0);
+    b.u32(0);
+    b.data.extend_from_slice(&[0xaa; 20]);
+    // Section name.
+    b.data.extend_from_slice(b".text\0");
+    // Section data, starting with the kernel thunk table.
+    b.pad(text_offset as usize);
+    b.u32(xbe::XBE_KERNEL_THUNK_ORDINAL | 1);
+    b.u32(xbe::XBE_KERNEL_THUNK_ORDINAL | 49);
+    b.u32(0);
+    b.data.extend_from_slice(&[0xc3, 0, 0, 0]);
+    let data = &*b.data;
+
+    assert_eq!(FileKind::parse(data).unwrap(), FileKind::Xbe);
+    let object = object::File::parse(data).unwrap();
+    assert_eq!(object.format(), BinaryFormat::Xbe);
+    assert_eq!(object.architecture(), Architecture::I386);
+    assert_eq!(object.kind(), ObjectKind::Executable);
+    assert_eq!(object.relative_address_base(), 0x10000);
+    assert_eq!(object.entry(), 0x1100c);
+    assert_eq!(object.symbols().count(), 0);
+    assert!(object.imports().unwrap().is_empty());
+    assert_eq!(object.segments().count(), 1);
+
+    let file = XbeFile::<&[u8]>::parse(data).unwrap();
+    assert_eq!(file.console(), XbeConsole::Retail);
+    assert_eq!(file.kernel_thunk_address(), 0x11000);
+    assert_eq!(
+        file.certificate()
+            .unwrap()
+            .title_id
+            .get(object::LittleEndian),
+        0x4d53_0001
+    );
+    assert!(file.library_versions().unwrap().is_empty());
+
+    let text = file.section_by_name(".text").unwrap();
+    assert_eq!(text.kind(), SectionKind::Text);
+    assert_eq!(text.address(), 0x11000);
+    assert_eq!(text.size(), 0x20);
+    assert_eq!(text.file_range(), Some((0x400, 16)));
+    assert_eq!(text.data().unwrap().len(), 16);
+    assert_eq!(text.digest(), &[0xaa; 20]);
+    let segment = file.segments().next().unwrap();
+    assert_eq!(segment.name().unwrap(), Some(".text"));
+
+    assert_eq!(
+        file.kernel_imports().unwrap(),
+        [
+            XbeKernelImport {
+                ordinal: 1,
+                address: 0x11000,
+            },
+            XbeKernelImport {
+                ordinal: 49,
+                address: 0x11004,
+            },
+        ]
+    );
+}
@@ -13,6 +13,8 @@ mod comdat;
 mod common;
 mod elf;
 mod macho;
+#[cfg(feature = "omf")]
+mod omf;
 mod section_flags;
 mod tls;
 
@@ -0,0 +1,1045 @@
+use object::read::archive::{ArchiveFile, ArchiveKind};
+use object::read::omf::OmfLibrary;
+use object::read::{Object, ObjectComdat, ObjectSection, ObjectSymbol};
+use object::write::omf::LibraryWriter;
+use object::{
+    read, write, Architecture, BinaryFormat, ComdatKind, Endianness, RelocationEncoding,
+    RelocationFlags, RelocationKind, RelocationTarget, SectionKind, SymbolFlags, SymbolKind,
+    SymbolScope,
+};
+
+/// Write an OMF record with a zero checksum.
+fn record(out: &mut Vec<u8>, kind: u8, body: &[u8]) {
+    out.push(kind);
+    out.extend_from_slice(&(body.len() as u16 + 1).to_le_bytes());
+    out.extend_from_slice(body);
+    out.push(0);
+}
+
+/// Return an object module that defines the given public symbols.
+fn module(name: &str, symbols: &[&str]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut theadr = vec![name.len() as u8];
+    theadr.extend_from_slice(name.as_bytes());
+    record(&mut out, 0x80, &theadr);
+    let mut pubdef = vec![0, 0, 0, 0];
+    for symbol in symbols {
+        pubdef.push(symbol.len() as u8);
+        pubdef.extend_from_slice(symbol.as_bytes());
+        pubdef.extend_from_slice(&[0, 0, 0]);
+    }
+    record(&mut out, 0x90, &pubdef);
+    record(&mut out, 0x8A, &[0]);
+    out
+}
+
+#[test]
+fn omf_library() {
+    let one = module("one", &["_one", "_shared"]);
+    let two = module("two", &["_two", "_SHARED"]);
+    // Enough symbols to need several dictionary blocks.
+    let names = (0..200)
+        .map(|i| format!("_symbol{}", i))
+        .collect::<Vec<_>>();
+    let many = module(
+        "many",
+        &names.iter().map(String::as_str).collect::<Vec<_>>(),
+    );
+
+    let mut writer = LibraryWriter::new();
+    assert!(writer.set_page_size(24).is_err());
+    writer.set_page_size(32).unwrap();
+    assert!(writer.add_member(&one[..one.len() - 1]).is_err());
+    writer.add_member(&one).unwrap();
+    writer.add_member(&two).unwrap();
+    writer.add_member(&many).unwrap();
+    let mut data = Vec::new();
+    writer.write(&mut data).unwrap();
+
+    let library = OmfLibrary::parse(&*data).unwrap();
+    assert_eq!(library.page_size(), 32);
+    assert!(!library.is_case_sensitive());
+    assert_eq!(library.dictionary_offset() % 512, 0);
+    assert!(library.dictionary_blocks() > 1);
+
+    let members = library.members().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(members.len(), 3);
+    assert_eq!(members[0].offset(), 32);
+    assert_eq!(members[0].data(&*data).unwrap(), &one[..]);
+    assert_eq!(members[1].name(), Ok("two"));
+    assert_eq!(members[2].offset() % 32, 0);
+
+    let member = library.member_for_symbol(b"_two").unwrap().unwrap();
+    assert_eq!(member.name(), Ok("two"));
+    // The first definition is used for case insensitive duplicates.
+    let member = library.member_for_symbol(b"_Shared").unwrap().unwrap();
+    assert_eq!(member.name(), Ok("one"));
+    for name in &names {
+        let member = library.member_for_symbol(name.as_bytes()).unwrap().unwrap();
+        assert_eq!(member.name(), Ok("many"));
+    }
+    assert!(library.member_for_symbol(b"_three").unwrap().is_none());
+    assert_eq!(library.symbols().unwrap().count(), 203);
+
+    let file = members[1].parse(&*data).unwrap();
+    assert!(file.symbol_by_name("_two").unwrap().is_definition());
+
+    let archive = ArchiveFile::parse(&*data).unwrap();
+    assert_eq!(archive.kind(), ArchiveKind::OmfLib);
+    assert_eq!(archive.members().count(), 3);
+}
+
+#[test]
+fn omf_library_case_sensitive() {
+    let one = module("one", &["_shared"]);
+    let two = module("two", &["_SHARED"]);
+
+    let mut writer = LibraryWriter::new();
+    writer.set_case_sensitive(true);
+    writer.add_member(&one).unwrap();
+    writer.add_member(&two).unwrap();
+    let mut data = Vec::new();
+    writer.write(&mut data).unwrap();
+
+    let library = OmfLibrary::parse(&*data).unwrap();
+    assert!(library.is_case_sensitive());
+    assert_eq!(library.page_size(), 16);
+    let member = library.member_for_symbol(b"_SHARED").unwrap().unwrap();
+    assert_eq!(member.name(), Ok("two"));
+    let member = library.member_for_symbol(b"_shared").unwrap().unwrap();
+    assert_eq!(member.name(), Ok("one"));
+}
+
+#[test]
+fn omf_object() {
+    let mut object = write::Object::new(BinaryFormat::Omf, Architecture::I386, Endianness::Little);
+    object.add_file_symbol(b"test.c".to_vec());
+
+    let text = object.section_id(write::StandardSection::Text);
+    let code = (0..2000).map(|i| i as u8).collect::<Vec<_>>();
+    object.append_section_data(text, &code, 16);
+    let data = object.section_id(write::StandardSection::Data);
+    object.append_section_data(data, &[0; 8], 4);
+    let bss = object.section_id(write::StandardSection::UninitializedData);
+    object.append_section_bss(bss, 16, 4);
+
+    let mut symbol = |name: &[u8], value, section, scope| {
+        object.add_symbol(write::Symbol {
+            name: name.to_vec(),
+            value,
+            size: 0,
+            kind: SymbolKind::Unknown,
+            scope,
+            weak: false,
+            section,
+            flags: SymbolFlags::None,
+        })
+    };
+    symbol(
+        b"_main",
+        0x10,
+        write::SymbolSection::Section(text),
+        SymbolScope::Linkage,
+    );
+    let local = symbol(
+        b"_local",
+        4,
+        write::SymbolSection::Section(data),
+        SymbolScope::Compilation,
+    );
+    let external = symbol(
+        b"_external",
+        0,
+        write::SymbolSection::Undefined,
+        SymbolScope::Unknown,
+    );
+    let common = object.add_common_symbol(
+        write::Symbol {
+            name: b"_common".to_vec(),
+            value: 0,
+            size: 0,
+            kind: SymbolKind::Data,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Undefined,
+            flags: SymbolFlags::None,
+        },
+        300,
+        4,
+    );
+
+    let mut relocation = |section, offset, symbol, addend, kind| {
+        object
+            .add_relocation(
+                section,
+                write::Relocation {
+                    offset,
+                    symbol,
+                    addend,
+                    flags: RelocationFlags::Generic {
+                        kind,
+                        encoding: RelocationEncoding::Generic,
+                        size: 32,
+                    },
+                },
+            )
+            .unwrap();
+    };
+    relocation(text, 1, external, -4, RelocationKind::Relative);
+    // This location is split across the first LEDATA record boundary.
+    relocation(text, 1022, local, 2, RelocationKind::Absolute);
+    relocation(data, 0, common, 0, RelocationKind::Absolute);
+    let bytes = object.write().unwrap();
+
+    let file = read::File::parse(&*bytes).unwrap();
+    assert_eq!(file.format(), BinaryFormat::Omf);
+    let omf = read::omf::OmfFile::parse(&*bytes).unwrap();
+    assert_eq!(omf.module_name, Some("test.c"));
+    assert!(omf.diagnostics.is_empty());
+
+    let sections = file.sections().collect::<Vec<_>>();
+    assert_eq!(sections.len(), 3);
+    assert_eq!(sections[0].name(), Ok("_TEXT"));
+    assert_eq!(sections[0].kind(), SectionKind::Text);
+    assert_eq!(sections[0].size(), 2000);
+    assert_eq!(sections[0].align(), 16);
+    assert_eq!(&*sections[0].uncompressed_data().unwrap(), &code[..]);
+    assert_eq!(sections[1].name(), Ok("_DATA"));
+    assert_eq!(sections[1].kind(), SectionKind::Data);
+    assert_eq!(sections[2].name(), Ok("_BSS"));
+    assert_eq!(sections[2].kind(), SectionKind::UninitializedData);
+    assert_eq!(sections[2].size(), 16);
+
+    let main = file.symbol_by_name("_main").unwrap();
+    assert!(main.is_global());
+    assert_eq!(main.section_index(), Some(sections[0].index()));
+    assert_eq!(main.address(), 0x10);
+    let local = file.symbol_by_name("_local").unwrap();
+    assert!(local.is_local());
+    assert_eq!(local.address(), 4);
+    assert!(file.symbol_by_name("_external").unwrap().is_undefined());
+    let common = file.symbol_by_name("_common").unwrap();
+    assert!(common.is_common());
+    assert_eq!(common.size(), 300);
+
+    let target = |target| match target {
+        RelocationTarget::Symbol(index) => file.symbol_by_index(index).unwrap().name().unwrap(),
+        _ => panic!("unexpected target {:?}", target),
+    };
+    let relocations = sections[0].relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 2);
+    assert_eq!(relocations[0].0, 1);
+    assert_eq!(relocations[0].1.kind(), RelocationKind::Relative);
+    assert_eq!(target(relocations[0].1.target()), "_external");
+    assert_eq!(relocations[0].1.addend(), 0);
+    assert_eq!(relocations[1].0, 1022);
+    assert_eq!(relocations[1].1.kind(), RelocationKind::SegmentRelative);
+    assert_eq!(
+        relocations[1].1.target(),
+        RelocationTarget::Section(sections[1].index())
+    );
+    assert_eq!(relocations[1].1.addend(), 6);
+    let relocations = sections[1].relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 1);
+    assert_eq!(target(relocations[0].1.target()), "_common");
+}
+
+#[test]
+fn omf_writer() {
+    use object::omf::{LEDATA32, MODEND, SEGDEF32, THEADR};
+    use write::omf::{self, Writer};
+
+    let mut writer = Writer::new();
+    let mut theadr = Vec::new();
+    omf::write_name(&mut theadr, b"test.asm").unwrap();
+    writer.write_record(THEADR, &theadr).unwrap();
+
+    let empty = writer.add_name(b"").unwrap();
+    let text = writer.add_name(b"_TEXT").unwrap();
+    let code = writer.add_name(b"CODE").unwrap();
+    assert_eq!((empty, text, code), (1, 2, 3));
+    assert_eq!(writer.add_name(b"_TEXT").unwrap(), text);
+    // Enough names to need several LNAMES records.
+    for i in 0..200 {
+        writer.add_name(format!("name{:03}", i).as_bytes()).unwrap();
+    }
+    writer.write_lnames().unwrap();
+    assert!(writer.add_name(&[b'x'; 256]).is_err());
+
+    let mut segdef = vec![0x69];
+    segdef.extend_from_slice(&4u32.to_le_bytes());
+    omf::write_index(&mut segdef, text).unwrap();
+    omf::write_index(&mut segdef, code).unwrap();
+    omf::write_index(&mut segdef, empty).unwrap();
+    assert!(omf::write_index(&mut Vec::new(), 0x8000).is_err());
+    writer.write_record(SEGDEF32, &segdef).unwrap();
+    let mut ledata = vec![1, 0, 0, 0, 0];
+    ledata.extend_from_slice(&[0x90, 0x90, 0x90, 0xc3]);
+    writer.write_record(LEDATA32, &ledata).unwrap();
+    writer.write_record(MODEND, &[0]).unwrap();
+    let mut bytes = Vec::new();
+    writer.write(&mut bytes).unwrap();
+    assert_eq!(writer.len(), bytes.len());
+
+    let mut options = read::omf::OmfParseOptions::default();
+    options.verify_checksums = true;
+    let file = read::omf::OmfFile::parse_with_options(&*bytes, options).unwrap();
+    assert_eq!(file.module_name, Some("test.asm"));
+    assert_eq!(file.names.len(), 203);
+    assert_eq!(file.names[202], b"name199");
+    let section = file.section_by_name("_TEXT").unwrap();
+    assert_eq!(section.data().unwrap(), &[0x90, 0x90, 0x90, 0xc3]);
+}
+
+#[test]
+fn omf_writer_ledata() {
+    use object::omf::{EXTDEF, FIXUPP, LEDATA, MODEND, SEGDEF, THEADR};
+    use write::omf::{self, Fixup, Writer};
+
+    let mut writer = Writer::new();
+    writer.write_record(THEADR, b"\x04test").unwrap();
+    let empty = writer.add_name(b"").unwrap();
+    let text = writer.add_name(b"_TEXT").unwrap();
+    let code = writer.add_name(b"CODE").unwrap();
+    writer.write_lnames().unwrap();
+    let mut segdef = vec![0x28];
+    segdef.extend_from_slice(&3000u16.to_le_bytes());
+    omf::write_index(&mut segdef, text).unwrap();
+    omf::write_index(&mut segdef, code).unwrap();
+    omf::write_index(&mut segdef, empty).unwrap();
+    writer.write_record(SEGDEF, &segdef).unwrap();
+    writer.write_record(EXTDEF, b"\x09_external\x00").unwrap();
+
+    // 16-bit offsets that would be split across the record boundaries.
+    let fixup = |offset| Fixup {
+        offset,
+        location: 1,
+        segment_relative: true,
+        frame_method: 5,
+        frame_index: 0,
+        target_method: 2,
+        target_index: 1,
+        displacement: 0,
+    };
+    let data = (0..3000).map(|i| i as u8).collect::<Vec<_>>();
+    let fixups = [fixup(2047), fixup(1023)];
+    writer.write_ledata(false, 1, 0, &data, &fixups).unwrap();
+    let overlapping = [fixup(10), fixup(11)];
+    assert!(writer
+        .write_ledata(false, 1, 0, &data, &overlapping)
+        .is_err());
+    assert!(writer
+        .write_ledata(false, 1, 0, &data, &[fixup(2999)])
+        .is_err());
+    assert!(writer.write_ledata(false, 1, 0xFF00, &data, &[]).is_err());
+    writer.write_record(MODEND, &[0]).unwrap();
+    let mut bytes = Vec::new();
+    writer.write(&mut bytes).unwrap();
+
+    // Each LEDATA record is followed by the fixups for its data.
+    let mut records = Vec::new();
+    let mut p = 0;
+    while p < bytes.len() {
+        let len = usize::from(u16::from_le_bytes([bytes[p + 1], bytes[p + 2]]));
+        records.push((bytes[p], len));
+        p += 3 + len;
+    }
+    let records = records
+        .into_iter()
+        .filter(|&(kind, _)| kind == LEDATA || kind == FIXUPP)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        records,
+        [
+            (LEDATA, 3 + 1023 + 1),
+            (LEDATA, 3 + 1024 + 1),
+            (FIXUPP, 5),
+            (LEDATA, 3 + 953 + 1),
+            (FIXUPP, 5),
+        ]
+    );
+
+    let file = read::File::parse(&*bytes).unwrap();
+    let section = file.section_by_name("_TEXT").unwrap();
+    assert_eq!(section.uncompressed_data().unwrap(), data);
+    let offsets = section
+        .relocations()
+        .map(|(offset, _)| offset)
+        .collect::<Vec<_>>();
+    assert_eq!(offsets, [1023, 2047]);
+}
+
+#[test]
+fn omf_object_fixup_threads() {
+    let mut object = write::Object::new(BinaryFormat::Omf, Architecture::I386, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0; 1024], 16);
+    let external = object.add_symbol(write::Symbol {
+        name: b"_external".to_vec(),
+        value: 0,
+        size: 0,
+        kind: SymbolKind::Unknown,
+        scope: SymbolScope::Unknown,
+        weak: false,
+        section: write::SymbolSection::Undefined,
+        flags: SymbolFlags::None,
+    });
+    let flags = RelocationFlags::Generic {
+        kind: RelocationKind::Absolute,
+        encoding: RelocationEncoding::Generic,
+        size: 32,
+    };
+    // More fixups than fit in one record if written explicitly.
+    for i in 0..256 {
+        let addend = if i % 2 == 0 { 0 } else { i };
+        let relocation = write::Relocation {
+            offset: i as u64 * 4,
+            symbol: external,
+            addend,
+            flags,
+        };
+        object.add_relocation(text, relocation).unwrap();
+    }
+    let bytes = object.write().unwrap();
+
+    // The FIXUPP records should be small enough for any linker.
+    let mut p = 0;
+    let mut fixupp_len = 0;
+    while p < bytes.len() {
+        let len = usize::from(u16::from_le_bytes([bytes[p + 1], bytes[p + 2]]));
+        if bytes[p] == 0x9D {
+            assert!(len <= 1025);
+            fixupp_len += len;
+        }
+        p += 3 + len;
+    }
+    // Explicit fixups would need 5 bytes each, plus the displacements.
+    assert!(fixupp_len < 256 * 5 + 128 * 4);
+
+    let file = read::File::parse(&*bytes).unwrap();
+    let section = file.section_by_name("_TEXT").unwrap();
+    let relocations = section.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 256);
+    for (i, (offset, relocation)) in relocations.iter().enumerate() {
+        assert_eq!(*offset, i as u64 * 4);
+        assert!(matches!(relocation.target(), RelocationTarget::Symbol(_)));
+        let addend = if i % 2 == 0 { 0 } else { i as i64 };
+        assert_eq!(relocation.addend(), addend);
+    }
+}
+
+#[test]
+fn omf_object_comdat() {
+    let mut object = write::Object::new(BinaryFormat::Omf, Architecture::I386, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xE8, 0, 0, 0, 0, 0xC3], 16);
+
+    // A COMDAT that is too large for a single record.
+    let mut comdat = |name: &[u8], size: usize, kind| {
+        let section = object.add_section(b"CODE".to_vec(), b"_TEXT".to_vec(), SectionKind::Text);
+        let data = (0..size).map(|i| i as u8).collect::<Vec<_>>();
+        object.append_section_data(section, &data, 16);
+        let symbol = object.add_symbol(write::Symbol {
+            name: name.to_vec(),
+            value: 0,
+            size: size as u64,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Section(section),
+            flags: SymbolFlags::None,
+        });
+        object.add_comdat(write::Comdat {
+            kind,
+            symbol,
+            sections: vec![section],
+        });
+        (section, symbol)
+    };
+    let (large, large_symbol) = comdat(b"_large", 3000, ComdatKind::Any);
+    let (small, _) = comdat(b"_small", 4, ComdatKind::ExactMatch);
+    let external = object.add_symbol(write::Symbol {
+        name: b"_external".to_vec(),
+        value: 0,
+        size: 0,
+        kind: SymbolKind::Unknown,
+        scope: SymbolScope::Unknown,
+        weak: false,
+        section: write::SymbolSection::Undefined,
+        flags: SymbolFlags::None,
+    });
+    let mut relocation = |section, offset, symbol, addend, kind| {
+        let relocation = write::Relocation {
+            offset,
+            symbol,
+            addend,
+            flags: RelocationFlags::Generic {
+                kind,
+                encoding: RelocationEncoding::Generic,
+                size: 32,
+            },
+        };
+        object.add_relocation(section, relocation).unwrap();
+    };
+    relocation(text, 1, large_symbol, -4, RelocationKind::Relative);
+    relocation(large, 2500, external, 0, RelocationKind::Absolute);
+    relocation(small, 0, large_symbol, 8, RelocationKind::Absolute);
+    let bytes = object.write().unwrap();
+
+    let file = read::File::parse(&*bytes).unwrap();
+    let comdats = file.comdats().collect::<Vec<_>>();
+    assert_eq!(comdats.len(), 2);
+    assert_eq!(comdats[0].name(), Ok("_large"));
+    assert_eq!(comdats[0].kind(), ComdatKind::Any);
+    assert_eq!(comdats[1].name(), Ok("_small"));
+    assert_eq!(comdats[1].kind(), ComdatKind::ExactMatch);
+
+    // The COMDATs are allocated in the _TEXT segment, which is only defined once.
+    let sections = file.sections().collect::<Vec<_>>();
+    assert_eq!(sections.len(), 3);
+    assert_eq!(sections[0].name(), Ok("_TEXT"));
+    assert_eq!(sections[1].name(), Ok("_large"));
+    assert_eq!(sections[1].kind(), SectionKind::Text);
+    assert_eq!(sections[1].size(), 3000);
+    let data = sections[1].uncompressed_data().unwrap();
+    assert_eq!(data.len(), 3000);
+    assert!(data.iter().enumerate().all(|(i, &b)| b == i as u8));
+
+    let symbol = file
+        .symbols()
+        .find(|symbol| symbol.name() == Ok("_large") && symbol.is_definition())
+        .unwrap();
+    assert_eq!(symbol.section_index(), Some(sections[1].index()));
+
+    let target_name = |target| match target {
+        RelocationTarget::Symbol(index) => file.symbol_by_index(index).unwrap().name().unwrap(),
+        _ => panic!("unexpected relocation target {:?}", target),
+    };
+    let (offset, reloc) = sections[0].relocations().next().unwrap();
+    assert_eq!(offset, 1);
+    assert_eq!(target_name(reloc.target()), "_large");
+    let (offset, reloc) = sections[1].relocations().next().unwrap();
+    assert_eq!(offset, 2500);
+    assert_eq!(target_name(reloc.target()), "_external");
+    let (offset, reloc) = sections[2].relocations().next().unwrap();
+    assert_eq!(offset, 0);
+    assert_eq!(target_name(reloc.target()), "_large");
+    assert_eq!(reloc.addend(), 8);
+}
+
+#[test]
+fn omf_writer_comments() {
+    use object::omf::{COMENT_NO_LIST, MODEND, THEADR};
+    use read::omf::{OmfCommentKind, OmfModelKind};
+    use write::omf::Writer;
+
+    let mut writer = Writer::new();
+    writer.write_record(THEADR, b"\x04test").unwrap();
+    writer.write_translator(b"Test Assembler").unwrap();
+    writer.write_copyright(b"(C) Test").unwrap();
+    writer.write_memory_model(b"3sO").unwrap();
+    writer.write_dosseg().unwrap();
+    writer.write_default_library(b"SLIBCE").unwrap();
+    writer
+        .write_comment(COMENT_NO_LIST, 0xE9, &[1, 2, 3])
+        .unwrap();
+    writer.write_record(MODEND, &[0]).unwrap();
+    let mut bytes = Vec::new();
+    writer.write(&mut bytes).unwrap();
+
+    let file = read::omf::OmfFile::parse(&*bytes).unwrap();
+    assert_eq!(file.producer(), Some("Test Assembler"));
+    assert_eq!(file.copyright(), Some("(C) Test"));
+    assert_eq!(file.default_libraries().collect::<Vec<_>>(), ["SLIBCE"]);
+    let comments = &file.comments;
+    assert_eq!(comments.len(), 6);
+    assert!(!comments[0].no_purge);
+    assert!(comments[1].no_purge);
+    match comments[2].kind {
+        OmfCommentKind::MemoryModel(model) => {
+            assert_eq!(model.model, Some(OmfModelKind::Small));
+            assert!(model.optimized);
+        }
+        kind => panic!("unexpected comment {:?}", kind),
+    }
+    assert_eq!(comments[3].kind, OmfCommentKind::DosSeg);
+    assert!(comments[3].no_purge);
+    assert!(comments[4].no_purge);
+    assert_eq!(comments[5].class, 0xE9);
+    assert!(comments[5].no_list && !comments[5].no_purge);
+    assert_eq!(comments[5].data, [1, 2, 3]);
+}
+
+#[test]
+fn omf_writer_imports_exports() {
+    use object::omf::{MODEND, THEADR};
+    use read::omf::{OmfExport, OmfImport, OmfImportEntry};
+    use write::omf::{Export, ImportEntry, Writer};
+
+    let mut writer = Writer::new();
+    writer.write_record(THEADR, b"\x04test").unwrap();
+    writer
+        .write_import(b"_Foo", b"KERNEL", ImportEntry::Name(b"_Foo"))
+        .unwrap();
+    writer
+        .write_import(b"_Bar", b"KERNEL", ImportEntry::Name(b"BAR"))
+        .unwrap();
+    writer
+        .write_import(b"_Baz", b"USER", ImportEntry::Ordinal(42))
+        .unwrap();
+    writer
+        .write_export(&Export {
+            exported_name: b"EXPORTED",
+            ..Default::default()
+        })
+        .unwrap();
+    writer
+        .write_export(&Export {
+            exported_name: b"ENTRY",
+            internal_name: Some(b"_Entry"),
+            ordinal: Some(7),
+            resident_name: true,
+            no_data: true,
+            parameter_count: 3,
+        })
+        .unwrap();
+    assert!(writer
+        .write_export(&Export {
+            exported_name: b"BAD",
+            parameter_count: 0x20,
+            ..Default::default()
+        })
+        .is_err());
+    writer.write_record(MODEND, &[0]).unwrap();
+    let mut bytes = Vec::new();
+    writer.write(&mut bytes).unwrap();
+
+    let file = read::omf::OmfFile::parse(&*bytes).unwrap();
+    assert_eq!(
+        file.import_definitions().collect::<Vec<_>>(),
+        [
+            OmfImport {
+                internal_name: "_Foo",
+                module: "KERNEL",
+                entry: OmfImportEntry::Name("_Foo"),
+            },
+            OmfImport {
+                internal_name: "_Bar",
+                module: "KERNEL",
+                entry: OmfImportEntry::Name("BAR"),
+            },
+            OmfImport {
+                internal_name: "_Baz",
+                module: "USER",
+                entry: OmfImportEntry::Ordinal(42),
+            },
+        ]
+    );
+    assert_eq!(
+        file.export_definitions().collect::<Vec<_>>(),
+        [
+            OmfExport {
+                exported_name: "EXPORTED",
+                internal_name: "EXPORTED",
+                ordinal: None,
+                resident_name: false,
+                no_data: false,
+                parameter_count: 0,
+            },
+            OmfExport {
+                exported_name: "ENTRY",
+                internal_name: "_Entry",
+                ordinal: Some(7),
+                resident_name: true,
+                no_data: true,
+                parameter_count: 3,
+            },
+        ]
+    );
+}
+
+#[test]
+fn omf_object_line_numbers() {
+    let mut object = write::Object::new(BinaryFormat::Omf, Architecture::I386, Endianness::Little);
+    object.add_file_symbol(b"test.c".to_vec());
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0x90; 16], 16);
+    object.add_omf_line_number(text, 8, 20);
+    object.add_omf_line_number(text, 0, 10);
+
+    // Enough lines to need several LINSYM records.
+    let section = object.add_section(b"CODE".to_vec(), b"_TEXT".to_vec(), SectionKind::Text);
+    object.append_section_data(section, &[0x90; 400], 16);
+    let symbol = object.add_symbol(write::Symbol {
+        name: b"_inline".to_vec(),
+        value: 0,
+        size: 400,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Section(section),
+        flags: SymbolFlags::None,
+    });
+    object.add_comdat(write::Comdat {
+        kind: ComdatKind::Any,
+        symbol,
+        sections: vec![section],
+    });
+    for i in 0..200 {
+        object.add_omf_line_number(section, i * 2, 100 + i as u16);
+    }
+    let bytes = object.write().unwrap();
+
+    let file = read::omf::OmfFile::parse(&*bytes).unwrap();
+    let text = file.section_by_name("_TEXT").unwrap().index();
+    assert_eq!(file.find_line(text, 0), Some((Some("test.c"), 10)));
+    assert_eq!(file.find_line(text, 12), Some((Some("test.c"), 20)));
+    let inline = file
+        .symbols()
+        .find(|symbol| symbol.name() == Ok("_inline") && symbol.is_definition())
+        .unwrap()
+        .section_index()
+        .unwrap();
+    let table = file.line_table(inline);
+    assert_eq!(table.lines().len(), 200);
+    assert_eq!(table.find(0), Some((Some("test.c"), 100)));
+    assert_eq!(table.find(399), Some((Some("test.c"), 299)));
+}
+
+#[test]
+fn omf_object_16bit() {
+    use write::omf::TargetProfile;
+
+    let object = |size: usize, relocation_size: u8| {
+        let mut object =
+            write::Object::new(BinaryFormat::Omf, Architecture::I386, Endianness::Little);
+        object.set_omf_target_profile(TargetProfile::Real8086);
+        let text = object.section_id(write::StandardSection::Text);
+        object.append_section_data(text, &vec![0x90; size], 16);
+        object.add_symbol(write::Symbol {
+            name: b"_main".to_vec(),
+            value: 0x100,
+            size: 0,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Section(text),
+            flags: SymbolFlags::None,
+        });
+        let external = object.add_symbol(write::Symbol {
+            name: b"_external".to_vec(),
+            value: 0,
+            size: 0,
+            kind: SymbolKind::Unknown,
+            scope: SymbolScope::Unknown,
+            weak: false,
+            section: write::SymbolSection::Undefined,
+            flags: SymbolFlags::None,
+        });
+        let relocation = write::Relocation {
+            offset: 0x200,
+            symbol: external,
+            addend: -2,
+            flags: RelocationFlags::Generic {
+                kind: RelocationKind::Absolute,
+                encoding: RelocationEncoding::Generic,
+                size: relocation_size,
+            },
+        };
+        object.add_relocation(text, relocation).unwrap();
+        object.write()
+    };
+
+    // A segment of exactly 64K uses the big bit.
+    let bytes = object(0x10000, 16).unwrap();
+    let mut kinds = Vec::new();
+    let mut p = 0;
+    while p < bytes.len() {
+        kinds.push(bytes[p]);
+        p += 3 + usize::from(u16::from_le_bytes([bytes[p + 1], bytes[p + 2]]));
+    }
+    assert!(kinds.contains(&object::omf::SEGDEF));
+    assert!(kinds.contains(&object::omf::LEDATA));
+    assert!(kinds.contains(&object::omf::FIXUPP));
+    assert!(kinds.contains(&object::omf::PUBDEF));
+    assert!(!kinds.contains(&object::omf::GRPDEF));
+    assert!(kinds.iter().all(|&kind| kind & 1 == 0));
+
+    let file = read::File::parse(&*bytes).unwrap();
+    let section = file.section_by_name("_TEXT").unwrap();
+    assert_eq!(section.size(), 0x10000);
+    assert_eq!(section.uncompressed_data().unwrap().len(), 0x10000);
+    let symbol = file.symbol_by_name("_main").unwrap();
+    assert_eq!(symbol.address(), 0x100);
+    let relocations = section.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 1);
+    assert_eq!(relocations[0].0, 0x200);
+    assert_eq!(relocations[0].1.size(), 16);
+    assert_eq!(relocations[0].1.addend() as u16, 0xFFFE);
+
+    // Values that don't fit in 16 bits are errors.
+    assert!(object(0x10001, 16).is_err());
+    assert!(object(0x1000, 32).is_err());
+}
+
+#[test]
+fn omf_object_checksums() {
+    let object = |zero_checksums| {
+        let mut object =
+            write::Object::new(BinaryFormat::Omf, Architecture::I386, Endianness::Little);
+        object.set_omf_zero_checksums(zero_checksums);
+        let text = object.section_id(write::StandardSection::Text);
+        object.append_section_data(text, &[0; 400], 16);
+        // Enough names and fixups for hash ordering to matter.
+        for i in 0..100 {
+            let symbol = object.add_symbol(write::Symbol {
+                name: format!("_external{}", i).into_bytes(),
+                value: 0,
+                size: 0,
+                kind: SymbolKind::Unknown,
+                scope: SymbolScope::Unknown,
+                weak: false,
+                section: write::SymbolSection::Undefined,
+                flags: SymbolFlags::None,
+            });
+            let relocation = write::Relocation {
+                offset: i * 4,
+                symbol,
+                addend: 0,
+                flags: RelocationFlags::Generic {
+                    kind: RelocationKind::Absolute,
+                    encoding: RelocationEncoding::Generic,
+                    size: 32,
+                },
+            };
+            object.add_relocation(text, relocation).unwrap();
+        }
+        object.write().unwrap()
+    };
+
+    // The output is the same for identical inputs.
+    let bytes = object(false);
+    assert_eq!(bytes, object(false));
+    let mut options = read::omf::OmfParseOptions::default();
+    options.verify_checksums = true;
+    read::omf::OmfFile::parse_with_options(&*bytes, options).unwrap();
+
+    // Zero checksums only change the last byte of each record.
+    let zero = object(true);
+    assert_eq!(zero.len(), bytes.len());
+    let mut p = 0;
+    while p < zero.len() {
+        let end = p + 3 + usize::from(u16::from_le_bytes([zero[p + 1], zero[p + 2]]));
+        assert_eq!(zero[p..end - 1], bytes[p..end - 1]);
+        assert_eq!(zero[end - 1], 0);
+        p = end;
+    }
+    let file = read::omf::OmfFile::parse_with_options(&*zero, options).unwrap();
+    assert!(file.diagnostics.is_empty());
+}
+
+#[test]
+fn omf_object_entry() {
+    use object::omf::THEADR;
+    use read::omf::{OmfFixupFrame, OmfFixupTarget, OmfStartAddress};
+    use write::omf::{StartAddress, Writer};
+
+    let mut object = write::Object::new(BinaryFormat::Omf, Architecture::I386, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0x90; 32], 16);
+    let start = object.add_symbol(write::Symbol {
+        name: b"_start".to_vec(),
+        value: 0x10,
+        size: 0,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Section(text),
+        flags: SymbolFlags::None,
+    });
+    object.set_omf_entry(start);
+    let bytes = object.write().unwrap();
+
+    let file = read::omf::OmfFile::parse(&*bytes).unwrap();
+    assert!(file.main_module);
+    assert_eq!(
+        file.start_address,
+        Some(OmfStartAddress::Logical {
+            target: OmfFixupTarget::Segment(1),
+            frame: Some(OmfFixupFrame::Group(1)),
+            displacement: 0x10,
+        })
+    );
+    assert_eq!(file.entry(), 0x10);
+
+    // A physical start address, and a module that is not a main module.
+    for (main, start, entry) in [
+        (
+            true,
+            Some(StartAddress::Physical {
+                frame: 0x1234,
+                offset: 0x10,
+            }),
+            0x12350,
+        ),
+        (false, None, 0),
+    ] {
+        let mut writer = Writer::new();
+        writer.write_record(THEADR, b"\x04test").unwrap();
+        writer.write_modend(false, main, start).unwrap();
+        let mut bytes = Vec::new();
+        writer.write(&mut bytes).unwrap();
+        let file = read::omf::OmfFile::parse(&*bytes).unwrap();
+        assert_eq!(file.main_module, main);
+        assert_eq!(file.entry(), entry);
+    }
+}
+
+#[test]
+fn omf_object_groups() {
+    use read::omf::{OmfFixupFrame, OmfFixupTarget};
+    use write::omf::TargetProfile;
+
+    let mut object = write::Object::new(BinaryFormat::Omf, Architecture::I386, Endianness::Little);
+    object.set_omf_target_profile(TargetProfile::Real8086);
+    object.set_omf_dgroup(true);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xB8, 0, 0, 0xC3], 1);
+    let data = object.section_id(write::StandardSection::Data);
+    object.append_section_data(data, &[1, 2, 3, 4], 2);
+    let bss = object.section_id(write::StandardSection::UninitializedData);
+    object.append_section_bss(bss, 16, 2);
+    let code = object.add_section(Vec::new(), b"_CODE2".to_vec(), SectionKind::Text);
+    object.append_section_data(code, &[0xC3], 1);
+    object.add_omf_group(b"CGROUP".to_vec(), vec![text, code]);
+    let symbol = object.add_symbol(write::Symbol {
+        name: b"_value".to_vec(),
+        value: 2,
+        size: 2,
+        kind: SymbolKind::Data,
+        scope: SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Section(data),
+        flags: SymbolFlags::None,
+    });
+    let relocation = write::Relocation {
+        offset: 1,
+        symbol,
+        addend: 0,
+        flags: RelocationFlags::Generic {
+            kind: RelocationKind::Absolute,
+            encoding: RelocationEncoding::Generic,
+            size: 16,
+        },
+    };
+    object.add_relocation(text, relocation).unwrap();
+    let bytes = object.write().unwrap();
+
+    let file = read::omf::OmfFile::parse(&*bytes).unwrap();
+    let groups = file
+        .groups
+        .iter()
+        .map(|group| (file.name(group.name), &*group.segment_indices))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        groups,
+        [(&b"CGROUP"[..], &[1, 4][..]), (&b"DGROUP"[..], &[2, 3][..])]
+    );
+    // Offsets in DGROUP are relative to the group.
+    let relocation = file.segments[0].fixups.get(0).unwrap();
+    assert_eq!(relocation.target, OmfFixupTarget::Segment(2));
+    assert_eq!(relocation.frame, Some(OmfFixupFrame::Group(2)));
+    assert_eq!(relocation.addend, 2);
+
+    // A segment can only be in one group.
+    object.add_omf_group(b"OTHER".to_vec(), vec![data]);
+    assert!(object.write().is_err());
+}
+
+#[test]
+fn omf_object_diff() {
+    use read::ObjectDiff;
+
+    fn object(new: bool) -> Vec<u8> {
+        let mut object =
+            write::Object::new(BinaryFormat::Omf, Architecture::I386, Endianness::Little);
+        let text = object.section_id(write::StandardSection::Text);
+        let data = if new {
+            [0xE8, 0, 0, 0, 0, 0xC3]
+        } else {
+            [0xE8, 0, 0, 0, 0, 0x90]
+        };
+        object.append_section_data(text, &data, 16);
+        if new {
+            let data = object.section_id(write::StandardSection::Data);
+            object.append_section_data(data, &[1, 2, 3, 4], 4);
+        }
+        object.add_symbol(write::Symbol {
+            name: b"_main".to_vec(),
+            value: if new { 1 } else { 0 },
+            size: 0,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Section(text),
+            flags: SymbolFlags::None,
+        });
+        let callee = object.add_symbol(write::Symbol {
+            name: if new {
+                b"_new".to_vec()
+            } else {
+                b"_old".to_vec()
+            },
+            value: 0,
+            size: 0,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Dynamic,
+            weak: false,
+            section: write::SymbolSection::Undefined,
+            flags: SymbolFlags::None,
+        });
+        object
+            .add_relocation(
+                text,
+                write::Relocation {
+                    offset: 1,
+                    symbol: callee,
+                    addend: -4,
+                    flags: RelocationFlags::Generic {
+                        kind: RelocationKind::Relative,
+                        encoding: RelocationEncoding::Generic,
+                        size: 32,
+                    },
+                },
+            )
+            .unwrap();
+        object.write().unwrap()
+    }
+
+    let old = object(false);
+    let new = object(true);
+    let old = read::omf::OmfFile::parse(&*old).unwrap();
+    let new = read::omf::OmfFile::parse(&*new).unwrap();
+    assert!(ObjectDiff::new(&old, &old).is_empty());
+
+    let diff = ObjectDiff::new(&old, &new);
+    assert_eq!(
+        diff.differences[1],
+        read::ObjectDifference::RelocationChanged {
+            section: b"_TEXT".to_vec(),
+            offset: 1,
+            field: read::RelocationDiffField::Target,
+        }
+    );
+    assert_eq!(
+        diff.to_string(),
+        "\
+section `_TEXT`: Data changed
+relocation `_TEXT`+0x1: Target changed
+section `_DATA` added
+symbol `_main`: Address changed
+symbol `_old` removed
+symbol `_new` added
+"
+    );
+}
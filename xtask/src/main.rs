@@ -133,6 +133,20 @@ fn cmd_features() -> Result<(), DynError> {
         "read_core,write_core,pe",
         "read_core,write_core,xcoff",
         "read_core,wasm",
+        "read_core,write_core,omf",
+        "build_core,write_core,omf",
+        "read_core,ne",
+        "read_core,lx",
+        "read_core,pharlap",
+        "read_core,aout",
+        "read_core,hunk",
+        "read_core,gemdos",
+        "read_core,pef",
+        "read_core,goff",
+        "read_core,ihex",
+        "read_core,srec",
+        "read_core,ticoff",
+        "read_core,xbe",
         // Test miscellaneous features individually.
         "std",
         "compression",